@@ -0,0 +1,90 @@
+//! Play a mock game scenario and print what the mod would send
+//!
+//! ```bash
+//! cargo run -p speedfog-race-core --features mock_game --example mock_race \
+//!     -- core/examples/mock_race.toml
+//! ```
+//!
+//! Prints the client messages (JSON, one per line) a racer would send for the
+//! scenario: status updates every second, fog gate flags as they get set and
+//! zone queries when a loading screen ends.
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use speedfog_race_core::mock_game::{MockGame, Scenario};
+use speedfog_race_core::{ClientMessage, EventFlagSource, GameStateReader, WarpDetector};
+
+const FRAME: Duration = Duration::from_millis(100);
+const STATUS_INTERVAL_MS: u64 = 1000;
+
+fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("usage: mock_race <scenario.toml>");
+        std::process::exit(2);
+    };
+    let content = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path, e);
+        std::process::exit(1);
+    });
+    let scenario = Scenario::from_toml(&content).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path, e);
+        std::process::exit(1);
+    });
+
+    let watched = scenario.flags();
+    let mut game = MockGame::new(scenario);
+    game.wait_for_game_loaded();
+    let mut triggered = BTreeSet::new();
+    let mut was_loading = game.is_loading();
+    let mut last_status = None;
+
+    loop {
+        let igt_ms = game.read_igt().unwrap_or(0);
+        for &flag_id in &watched {
+            if game.is_flag_set(flag_id) == Some(true) && triggered.insert(flag_id) {
+                send(&ClientMessage::EventFlag {
+                    flag_id,
+                    igt_ms,
+                    seq: None,
+                    idempotency_key: None,
+                });
+            }
+        }
+
+        if was_loading && !game.is_loading() {
+            if let Some(position) = game.read_position() {
+                send(&ClientMessage::ZoneQuery {
+                    grace_entity_id: game.captured_grace_entity_id(),
+                    map_id: Some(position.map_id_str.clone()),
+                    position: Some([position.x, position.y, position.z]),
+                    play_region_id: position.play_region_id,
+                });
+                game.clear_captured_warps();
+            }
+        }
+        was_loading = game.is_loading();
+
+        let second = game.now_ms() / STATUS_INTERVAL_MS;
+        if last_status != Some(second) {
+            last_status = Some(second);
+            send(&ClientMessage::StatusUpdate {
+                igt_ms,
+                death_count: game.read_deaths().unwrap_or(0),
+                disconnecting: false,
+            });
+        }
+
+        if game.is_finished() {
+            break;
+        }
+        game.advance(FRAME);
+    }
+}
+
+fn send(message: &ClientMessage) {
+    match serde_json::to_string(message) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("serialize: {}", e),
+    }
+}
@@ -0,0 +1,217 @@
+//! Activity feed of the other racers
+//!
+//! The server announces each racer's milestones to every mod (`race_activity`,
+//! protocol 24+): a new zone entered, a boss defeated, the finish. The overlay
+//! lists the last few as a ticker below the leaderboard; entries scroll out
+//! after a while, or when newer ones push them out. Each category can be
+//! turned off in `[activity_feed]`.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// `[activity_feed]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityFeedSettings {
+    /// Show the other racers' milestones below the leaderboard
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Entries listed at most
+    #[serde(default = "default_max_entries")]
+    pub max_entries: usize,
+    /// Seconds an entry stays listed
+    #[serde(default = "default_visible_secs")]
+    pub visible_secs: f32,
+    #[serde(default = "default_true")]
+    pub zones: bool,
+    #[serde(default = "default_true")]
+    pub bosses: bool,
+    #[serde(default = "default_true")]
+    pub finishes: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+fn default_max_entries() -> usize {
+    5
+}
+fn default_visible_secs() -> f32 {
+    30.0
+}
+
+impl Default for ActivityFeedSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: default_max_entries(),
+            visible_secs: default_visible_secs(),
+            zones: true,
+            bosses: true,
+            finishes: true,
+        }
+    }
+}
+
+impl ActivityFeedSettings {
+    /// Whether entries of this kind are listed
+    pub fn shows(&self, kind: ActivityKind) -> bool {
+        self.enabled
+            && match kind {
+                ActivityKind::Zone => self.zones,
+                ActivityKind::Boss => self.bosses,
+                ActivityKind::Finish => self.finishes,
+                ActivityKind::Unknown => false,
+            }
+    }
+
+    fn visible_for(&self) -> Duration {
+        Duration::from_secs_f32(self.visible_secs.max(0.0))
+    }
+}
+
+/// Milestone of a `race_activity` message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    /// First visit of a zone
+    Zone,
+    /// Left a boss arena, so its boss is down
+    Boss,
+    Finish,
+    /// Kind added by a newer server, never listed
+    #[serde(other)]
+    Unknown,
+}
+
+/// One entry of the feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Activity {
+    pub player_name: String,
+    pub kind: ActivityKind,
+    /// Zone entered, or the boss arena left. None when the race hides zones.
+    pub zone_name: Option<String>,
+    pub igt_ms: u32,
+    /// When the mod received it, for the ticker
+    pub received_at: Instant,
+}
+
+/// Last milestones of the other racers, oldest first
+#[derive(Debug, Default)]
+pub struct ActivityFeed {
+    entries: VecDeque<Activity>,
+}
+
+impl ActivityFeed {
+    /// Add a milestone, unless its kind is filtered out
+    pub fn push(&mut self, activity: Activity, settings: &ActivityFeedSettings) -> bool {
+        if !settings.shows(activity.kind) {
+            return false;
+        }
+        self.entries.push_back(activity);
+        while self.entries.len() > settings.max_entries {
+            self.entries.pop_front();
+        }
+        true
+    }
+
+    /// Entries still listed at `now`, oldest first
+    pub fn visible<'a>(
+        &'a self,
+        now: Instant,
+        settings: &ActivityFeedSettings,
+    ) -> impl Iterator<Item = &'a Activity> + 'a {
+        let visible_for = settings.visible_for();
+        let enabled = settings.enabled;
+        self.entries
+            .iter()
+            .filter(move |a| enabled && now.saturating_duration_since(a.received_at) < visible_for)
+    }
+
+    /// Forget every entry, e.g. when the seed changes
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(name: &str, kind: ActivityKind, now: Instant) -> Activity {
+        Activity {
+            player_name: name.to_string(),
+            kind,
+            zone_name: Some("Stormveil Castle".to_string()),
+            igt_ms: 60_000,
+            received_at: now,
+        }
+    }
+
+    fn names(feed: &ActivityFeed, now: Instant) -> Vec<&str> {
+        feed.visible(now, &ActivityFeedSettings::default())
+            .map(|a| a.player_name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_keeps_last_entries() {
+        let now = Instant::now();
+        let settings = ActivityFeedSettings::default();
+        let mut feed = ActivityFeed::default();
+        for i in 0..7 {
+            feed.push(
+                activity(&format!("racer{}", i), ActivityKind::Zone, now),
+                &settings,
+            );
+        }
+        assert_eq!(
+            names(&feed, now),
+            ["racer2", "racer3", "racer4", "racer5", "racer6"]
+        );
+    }
+
+    #[test]
+    fn test_entries_expire() {
+        let start = Instant::now();
+        let settings = ActivityFeedSettings::default();
+        let mut feed = ActivityFeed::default();
+        feed.push(activity("early", ActivityKind::Boss, start), &settings);
+        let later = start + Duration::from_secs(20);
+        feed.push(activity("late", ActivityKind::Finish, later), &settings);
+        assert_eq!(names(&feed, start + Duration::from_secs(35)), ["late"]);
+        assert!(names(&feed, start + Duration::from_secs(60)).is_empty());
+    }
+
+    #[test]
+    fn test_category_filters() {
+        let now = Instant::now();
+        let settings = ActivityFeedSettings {
+            zones: false,
+            ..Default::default()
+        };
+        let mut feed = ActivityFeed::default();
+        assert!(!feed.push(activity("racer", ActivityKind::Zone, now), &settings));
+        assert!(feed.push(activity("racer", ActivityKind::Boss, now), &settings));
+        assert!(!feed.push(activity("racer", ActivityKind::Unknown, now), &settings));
+
+        let disabled = ActivityFeedSettings {
+            enabled: false,
+            ..Default::default()
+        };
+        assert_eq!(feed.visible(now, &disabled).count(), 0);
+    }
+
+    #[test]
+    fn test_unknown_kind() {
+        let kind: ActivityKind = serde_json::from_str(r#""boss""#).unwrap();
+        assert_eq!(kind, ActivityKind::Boss);
+        let kind: ActivityKind = serde_json::from_str(r#""great_rune""#).unwrap();
+        assert_eq!(kind, ActivityKind::Unknown);
+    }
+}
@@ -0,0 +1,322 @@
+//! Idle (AFK) detection
+//!
+//! A racer who walks away mid-race still shows as playing on the leaderboard.
+//! The detector tracks the character's movement: the game clock keeps running
+//! while the character stands still and stops on the title screen, so
+//! movement is the activity signal, and both standing still and sitting on
+//! the title screen count as idle. After `timeout_minutes` without moving, a
+//! local banner warns for `grace_secs` (the player can cancel it) before the
+//! player is marked AFK and, optionally, reported to the server.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Movement (in meters) that counts as activity; smaller drifts are ignored
+const MIN_MOVE: f32 = 1.0;
+
+/// `[afk]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AfkSettings {
+    /// Detect idle players during a race
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Minutes without moving before the AFK warning
+    #[serde(default = "default_timeout_minutes")]
+    pub timeout_minutes: f32,
+    /// Seconds the warning stays up (cancelable) before the player is AFK
+    #[serde(default = "default_grace_secs")]
+    pub grace_secs: f32,
+    /// Tell the server, so the leaderboard greys out idle players
+    #[serde(default = "default_enabled")]
+    pub notify_server: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+fn default_timeout_minutes() -> f32 {
+    5.0
+}
+fn default_grace_secs() -> f32 {
+    30.0
+}
+
+impl Default for AfkSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            timeout_minutes: default_timeout_minutes(),
+            grace_secs: default_grace_secs(),
+            notify_server: default_enabled(),
+        }
+    }
+}
+
+impl AfkSettings {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs_f32(self.timeout_minutes.max(0.0) * 60.0)
+    }
+
+    pub fn grace(&self) -> Duration {
+        Duration::from_secs_f32(self.grace_secs.max(0.0))
+    }
+}
+
+/// Where the player stands in the idle cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AfkState {
+    Active,
+    /// Idle for the timeout, banner shown since this instant
+    Warning {
+        since: Instant,
+    },
+    Afk,
+}
+
+/// State change worth telling the player (and the server) about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AfkEvent {
+    /// Idle for the timeout: the cancelable warning is shown
+    Warned,
+    /// The warning ran out: the player is AFK
+    WentAfk,
+    /// The player moved again after a warning or while AFK
+    Returned { was_afk: bool },
+}
+
+/// Character position where movement is tracked: (map id, x/y/z)
+pub type Location = (u32, [f32; 3]);
+
+/// Motion tracking of the local character
+#[derive(Debug)]
+pub struct AfkDetector {
+    state: AfkState,
+    last_location: Option<Location>,
+    last_activity: Option<Instant>,
+}
+
+impl Default for AfkDetector {
+    fn default() -> Self {
+        Self {
+            state: AfkState::Active,
+            last_location: None,
+            last_activity: None,
+        }
+    }
+}
+
+impl AfkDetector {
+    pub fn state(&self) -> AfkState {
+        self.state
+    }
+
+    pub fn is_afk(&self) -> bool {
+        self.state == AfkState::Afk
+    }
+
+    /// Feed the character's location at `now` (None while unreadable:
+    /// loading screen, title screen).
+    pub fn observe(
+        &mut self,
+        now: Instant,
+        location: Option<Location>,
+        settings: &AfkSettings,
+    ) -> Option<AfkEvent> {
+        let moved = match (self.last_location, location) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some((last_map, last)), Some((map, pos))) => {
+                map != last_map || distance(last, pos) >= MIN_MOVE
+            }
+        };
+        if moved {
+            self.last_location = location;
+        }
+        let last_activity = match self.last_activity {
+            Some(at) if !moved => at,
+            _ => {
+                self.last_activity = Some(now);
+                now
+            }
+        };
+
+        match self.state {
+            AfkState::Active => {
+                if now.saturating_duration_since(last_activity) >= settings.timeout() {
+                    self.state = AfkState::Warning { since: now };
+                    return Some(AfkEvent::Warned);
+                }
+                None
+            }
+            _ if moved => {
+                let was_afk = self.is_afk();
+                self.state = AfkState::Active;
+                Some(AfkEvent::Returned { was_afk })
+            }
+            AfkState::Warning { since } => {
+                if now.saturating_duration_since(since) >= settings.grace() {
+                    self.state = AfkState::Afk;
+                    return Some(AfkEvent::WentAfk);
+                }
+                None
+            }
+            AfkState::Afk => None,
+        }
+    }
+
+    /// The player dismissed the warning (or the AFK banner): active again,
+    /// with a fresh timeout. Returns whether they were AFK.
+    pub fn cancel(&mut self, now: Instant) -> bool {
+        let was_afk = self.is_afk();
+        self.state = AfkState::Active;
+        self.last_activity = Some(now);
+        was_afk
+    }
+
+    /// Time left on the warning before the player is marked AFK
+    pub fn grace_remaining(&self, now: Instant, settings: &AfkSettings) -> Option<Duration> {
+        match self.state {
+            AfkState::Warning { since } => Some(
+                settings
+                    .grace()
+                    .saturating_sub(now.saturating_duration_since(since)),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Forget the tracking (race not running, new seed)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let dx = a[0] - b[0];
+    let dy = a[1] - b[1];
+    let dz = a[2] - b[2];
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: u32 = 0x0A000000;
+
+    fn settings() -> AfkSettings {
+        AfkSettings {
+            timeout_minutes: 1.0,
+            grace_secs: 10.0,
+            ..AfkSettings::default()
+        }
+    }
+
+    fn secs(s: u64) -> Duration {
+        Duration::from_secs(s)
+    }
+
+    fn at(x: f32) -> Option<Location> {
+        Some((MAP, [x, 0.0, 0.0]))
+    }
+
+    #[test]
+    fn test_idle_warns_then_goes_afk() {
+        let t0 = Instant::now();
+        let settings = settings();
+        let mut afk = AfkDetector::default();
+        assert_eq!(afk.observe(t0, at(0.0), &settings), None);
+        assert_eq!(afk.observe(t0 + secs(59), at(0.0), &settings), None);
+        assert_eq!(
+            afk.observe(t0 + secs(60), at(0.5), &settings),
+            Some(AfkEvent::Warned)
+        );
+        assert_eq!(afk.grace_remaining(t0 + secs(64), &settings), Some(secs(6)));
+        assert_eq!(afk.observe(t0 + secs(69), at(0.0), &settings), None);
+        assert_eq!(
+            afk.observe(t0 + secs(70), at(0.0), &settings),
+            Some(AfkEvent::WentAfk)
+        );
+        assert!(afk.is_afk());
+        assert_eq!(afk.observe(t0 + secs(500), at(0.0), &settings), None);
+    }
+
+    #[test]
+    fn test_movement_resets_timeout() {
+        let t0 = Instant::now();
+        let settings = settings();
+        let mut afk = AfkDetector::default();
+        afk.observe(t0, at(0.0), &settings);
+        afk.observe(t0 + secs(50), at(5.0), &settings);
+        assert_eq!(afk.observe(t0 + secs(100), at(5.0), &settings), None);
+        assert_eq!(
+            afk.observe(t0 + secs(110), at(5.0), &settings),
+            Some(AfkEvent::Warned)
+        );
+    }
+
+    #[test]
+    fn test_unreadable_position_is_idle() {
+        let t0 = Instant::now();
+        let settings = settings();
+        let mut afk = AfkDetector::default();
+        afk.observe(t0, at(0.0), &settings);
+        // Quit to the title screen
+        assert_eq!(
+            afk.observe(t0 + secs(60), None, &settings),
+            Some(AfkEvent::Warned)
+        );
+    }
+
+    #[test]
+    fn test_moving_returns_from_afk() {
+        let t0 = Instant::now();
+        let settings = settings();
+        let mut afk = AfkDetector::default();
+        afk.observe(t0, at(0.0), &settings);
+        afk.observe(t0 + secs(60), at(0.0), &settings);
+        assert_eq!(
+            afk.observe(t0 + secs(65), at(3.0), &settings),
+            Some(AfkEvent::Returned { was_afk: false })
+        );
+        afk.observe(t0 + secs(125), at(3.0), &settings);
+        afk.observe(t0 + secs(135), at(3.0), &settings);
+        // Another map counts as movement (warp, fog gate)
+        assert_eq!(
+            afk.observe(t0 + secs(140), Some((MAP + 1, [3.0, 0.0, 0.0])), &settings),
+            Some(AfkEvent::Returned { was_afk: true })
+        );
+        assert_eq!(afk.state(), AfkState::Active);
+    }
+
+    #[test]
+    fn test_cancel_restarts_timeout() {
+        let t0 = Instant::now();
+        let settings = settings();
+        let mut afk = AfkDetector::default();
+        afk.observe(t0, at(0.0), &settings);
+        afk.observe(t0 + secs(60), at(0.0), &settings);
+        assert!(!afk.cancel(t0 + secs(62)));
+        assert_eq!(afk.observe(t0 + secs(100), at(0.0), &settings), None);
+        assert_eq!(
+            afk.observe(t0 + secs(122), at(0.0), &settings),
+            Some(AfkEvent::Warned)
+        );
+        afk.observe(t0 + secs(132), at(0.0), &settings);
+        assert!(afk.cancel(t0 + secs(140)));
+        assert_eq!(afk.grace_remaining(t0 + secs(140), &settings), None);
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings: AfkSettings = toml::from_str("").unwrap();
+        assert_eq!(settings, AfkSettings::default());
+        assert_eq!(settings.timeout(), secs(300));
+        let settings: AfkSettings =
+            toml::from_str("timeout_minutes = 2.5\nnotify_server = false").unwrap();
+        assert_eq!(settings.timeout(), secs(150));
+        assert!(!settings.notify_server);
+        assert!(settings.enabled);
+    }
+}
@@ -0,0 +1,277 @@
+//! Overlay window anchoring
+//!
+//! Computes window positions from an anchor preset and edge offsets, so overlay
+//! windows stay in place across resolution changes and ultrawide setups.
+
+use std::fmt;
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Screen point a window is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Anchor {
+    TopLeft,
+    #[default]
+    TopRight,
+    TopCenter,
+    BottomLeft,
+    BottomRight,
+    Center,
+}
+
+impl Anchor {
+    /// Window pivot: the fraction of the window size that sits on the anchor point.
+    /// E.g. bottom-right anchoring pins the window's bottom-right corner.
+    pub fn pivot(self) -> [f32; 2] {
+        match self {
+            Anchor::TopLeft => [0.0, 0.0],
+            Anchor::TopRight => [1.0, 0.0],
+            Anchor::TopCenter => [0.5, 0.0],
+            Anchor::BottomLeft => [0.0, 1.0],
+            Anchor::BottomRight => [1.0, 1.0],
+            Anchor::Center => [0.5, 0.5],
+        }
+    }
+}
+
+/// Distance from the anchored screen edge.
+///
+/// Configured either as a number of pixels (`20.0`) or a percentage of the
+/// display size (`"2.5%"`), which keeps the same relative spot on any resolution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Offset {
+    Pixels(f32),
+    Percent(f32),
+}
+
+impl Offset {
+    /// Resolve to pixels along an axis of the given display length
+    pub fn to_pixels(self, display_len: f32) -> f32 {
+        match self {
+            Offset::Pixels(px) => px,
+            Offset::Percent(pct) => display_len * pct / 100.0,
+        }
+    }
+
+    /// Scale a pixel offset with the overlay; percentages already follow the display
+    pub fn scaled(self, factor: f32) -> Self {
+        match self {
+            Offset::Pixels(px) => Offset::Pixels(px * factor),
+            Offset::Percent(pct) => Offset::Percent(pct),
+        }
+    }
+
+    /// Parse `"12"`, `"12.5px"` or `"3%"`
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Some(pct) = s.strip_suffix('%') {
+            return pct.trim().parse::<f32>().ok().map(Offset::Percent);
+        }
+        let px = s.strip_suffix("px").unwrap_or(s);
+        px.trim().parse::<f32>().ok().map(Offset::Pixels)
+    }
+}
+
+impl Default for Offset {
+    fn default() -> Self {
+        Offset::Pixels(0.0)
+    }
+}
+
+impl Serialize for Offset {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Offset::Pixels(px) => serializer.serialize_f32(*px),
+            Offset::Percent(pct) => serializer.serialize_str(&format!("{}%", pct)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Offset {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct OffsetVisitor;
+
+        impl Visitor<'_> for OffsetVisitor {
+            type Value = Offset;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a pixel count or a percentage string like \"5%\"")
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Offset, E> {
+                Ok(Offset::Pixels(v as f32))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Offset, E> {
+                Ok(Offset::Pixels(v as f32))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Offset, E> {
+                Ok(Offset::Pixels(v as f32))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Offset, E> {
+                Offset::parse(v).ok_or_else(|| E::custom(format!("Invalid offset: '{}'", v)))
+            }
+        }
+
+        deserializer.deserialize_any(OffsetVisitor)
+    }
+}
+
+/// Compute the window position and pivot for an anchored window.
+///
+/// Offsets push the window inward from the anchored edges; for `Center` they
+/// shift the window right/down from the middle of the screen.
+/// Pass the returned pivot to ImGui's `position_pivot` so the window size
+/// doesn't need to be known in advance.
+pub fn anchored_position(
+    display_size: [f32; 2],
+    anchor: Anchor,
+    offset_x: Offset,
+    offset_y: Offset,
+) -> ([f32; 2], [f32; 2]) {
+    let [dw, dh] = display_size;
+    let ox = offset_x.to_pixels(dw);
+    let oy = offset_y.to_pixels(dh);
+
+    let x = match anchor {
+        Anchor::TopLeft | Anchor::BottomLeft => ox,
+        Anchor::TopRight | Anchor::BottomRight => dw - ox,
+        Anchor::TopCenter | Anchor::Center => dw * 0.5 + ox,
+    };
+    let y = match anchor {
+        Anchor::TopLeft | Anchor::TopRight | Anchor::TopCenter => oy,
+        Anchor::BottomLeft | Anchor::BottomRight => dh - oy,
+        Anchor::Center => dh * 0.5 + oy,
+    };
+    ([x, y], anchor.pivot())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FHD: [f32; 2] = [1920.0, 1080.0];
+
+    #[test]
+    fn test_offset_parse() {
+        assert_eq!(Offset::parse("20"), Some(Offset::Pixels(20.0)));
+        assert_eq!(Offset::parse("12.5px"), Some(Offset::Pixels(12.5)));
+        assert_eq!(Offset::parse(" 5% "), Some(Offset::Percent(5.0)));
+        assert_eq!(Offset::parse("abc"), None);
+        assert_eq!(Offset::parse("%"), None);
+    }
+
+    #[test]
+    fn test_offset_to_pixels() {
+        assert_eq!(Offset::Pixels(20.0).to_pixels(1920.0), 20.0);
+        assert_eq!(Offset::Percent(10.0).to_pixels(1920.0), 192.0);
+    }
+
+    #[test]
+    fn test_offset_scaled() {
+        assert_eq!(Offset::Pixels(20.0).scaled(2.0), Offset::Pixels(40.0));
+        assert_eq!(Offset::Percent(2.5).scaled(2.0), Offset::Percent(2.5));
+    }
+
+    #[test]
+    fn test_offset_deserialize() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            a: Offset,
+            b: Offset,
+            c: Offset,
+        }
+        let w: Wrapper = toml::from_str("a = 20.0\nb = 15\nc = \"2.5%\"").unwrap();
+        assert_eq!(w.a, Offset::Pixels(20.0));
+        assert_eq!(w.b, Offset::Pixels(15.0));
+        assert_eq!(w.c, Offset::Percent(2.5));
+        assert!(toml::from_str::<Wrapper>("a = \"x\"\nb = 1\nc = 1").is_err());
+    }
+
+    #[test]
+    fn test_anchor_deserialize() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            anchor: Anchor,
+        }
+        let w: Wrapper = toml::from_str("anchor = \"bottom_left\"").unwrap();
+        assert_eq!(w.anchor, Anchor::BottomLeft);
+    }
+
+    #[test]
+    fn test_top_right_matches_legacy_layout() {
+        let (pos, pivot) = anchored_position(
+            FHD,
+            Anchor::TopRight,
+            Offset::Pixels(20.0),
+            Offset::Pixels(20.0),
+        );
+        assert_eq!(pos, [1900.0, 20.0]);
+        assert_eq!(pivot, [1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_top_center() {
+        let (pos, pivot) = anchored_position(
+            FHD,
+            Anchor::TopCenter,
+            Offset::Pixels(0.0),
+            Offset::Percent(2.0),
+        );
+        assert_eq!(pos, [960.0, 21.6]);
+        assert_eq!(pivot, [0.5, 0.0]);
+    }
+
+    #[test]
+    fn test_bottom_left() {
+        let (pos, pivot) = anchored_position(
+            FHD,
+            Anchor::BottomLeft,
+            Offset::Pixels(10.0),
+            Offset::Pixels(30.0),
+        );
+        assert_eq!(pos, [10.0, 1050.0]);
+        assert_eq!(pivot, [0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_center_shifts_by_offset() {
+        let (pos, pivot) = anchored_position(
+            FHD,
+            Anchor::Center,
+            Offset::Pixels(0.0),
+            Offset::Pixels(-40.0),
+        );
+        assert_eq!(pos, [960.0, 500.0]);
+        assert_eq!(pivot, [0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_percent_offsets_scale_with_resolution() {
+        let uhd = [3840.0, 2160.0];
+        let (fhd_pos, _) = anchored_position(
+            FHD,
+            Anchor::BottomRight,
+            Offset::Percent(5.0),
+            Offset::Percent(10.0),
+        );
+        let (uhd_pos, _) = anchored_position(
+            uhd,
+            Anchor::BottomRight,
+            Offset::Percent(5.0),
+            Offset::Percent(10.0),
+        );
+        assert_eq!(fhd_pos, [1824.0, 972.0]);
+        assert_eq!(uhd_pos, [fhd_pos[0] * 2.0, fhd_pos[1] * 2.0]);
+    }
+}
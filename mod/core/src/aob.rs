@@ -0,0 +1,124 @@
+//! Array-of-bytes signature scanning
+//!
+//! Patterns use the usual IDA-style notation: hex bytes separated by spaces,
+//! with `?` or `??` as single-byte wildcards (e.g. `"48 8b 05 ?? ?? ?? ?? 48 85 c0"`).
+//! Used to locate game globals when the running version is not in the offset table.
+
+/// A parsed pattern: `None` entries match any byte
+pub type Pattern = Vec<Option<u8>>;
+
+/// Parse an IDA-style pattern string.
+///
+/// Returns None if a token is not a 2-digit hex byte or wildcard, or if the
+/// pattern has no concrete byte at all (it would match everywhere).
+pub fn parse_pattern(s: &str) -> Option<Pattern> {
+    let pattern = s
+        .split_whitespace()
+        .map(|tok| match tok {
+            "?" | "??" => Some(None),
+            _ if tok.len() == 2 => u8::from_str_radix(tok, 16).ok().map(Some),
+            _ => None,
+        })
+        .collect::<Option<Pattern>>()?;
+
+    if pattern.iter().any(|b| b.is_some()) {
+        Some(pattern)
+    } else {
+        None
+    }
+}
+
+/// Find the first offset in `haystack` where `pattern` matches.
+pub fn find_pattern(haystack: &[u8], pattern: &[Option<u8>]) -> Option<usize> {
+    if pattern.is_empty() || haystack.len() < pattern.len() {
+        return None;
+    }
+    haystack.windows(pattern.len()).position(|window| {
+        window.iter().zip(pattern).all(|(byte, p)| match p {
+            Some(expected) => byte == expected,
+            None => true,
+        })
+    })
+}
+
+/// Resolve the target of a RIP-relative instruction (e.g. `mov rax, [rip+disp32]`).
+///
+/// `instr` holds the instruction bytes starting at `instr_addr`; the little-endian
+/// 32-bit displacement is read at `disp_offset` and is relative to the end of the
+/// instruction (`instr_addr + instr_len`).
+pub fn rip_relative_target(
+    instr_addr: usize,
+    instr: &[u8],
+    disp_offset: usize,
+    instr_len: usize,
+) -> Option<usize> {
+    let disp_bytes: [u8; 4] = instr.get(disp_offset..disp_offset + 4)?.try_into().ok()?;
+    let disp = i32::from_le_bytes(disp_bytes) as isize;
+    instr_addr.checked_add(instr_len)?.checked_add_signed(disp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pattern() {
+        assert_eq!(
+            parse_pattern("48 8b ?? ? C3"),
+            Some(vec![Some(0x48), Some(0x8B), None, None, Some(0xC3)])
+        );
+    }
+
+    #[test]
+    fn test_parse_pattern_invalid() {
+        assert_eq!(parse_pattern(""), None);
+        assert_eq!(parse_pattern("?? ??"), None); // wildcards only
+        assert_eq!(parse_pattern("48 8"), None); // short token
+        assert_eq!(parse_pattern("48 zz"), None); // not hex
+        assert_eq!(parse_pattern("488b"), None); // missing separator
+    }
+
+    #[test]
+    fn test_find_pattern() {
+        let haystack = [0x00, 0x48, 0x8B, 0x05, 0x11, 0x22, 0x33, 0x44, 0xC3];
+        let pattern = parse_pattern("48 8b 05 ?? ?? ?? ?? c3").unwrap();
+        assert_eq!(find_pattern(&haystack, &pattern), Some(1));
+    }
+
+    #[test]
+    fn test_find_pattern_first_match() {
+        let haystack = [0xAA, 0xBB, 0xAA, 0xBB];
+        let pattern = parse_pattern("aa bb").unwrap();
+        assert_eq!(find_pattern(&haystack, &pattern), Some(0));
+    }
+
+    #[test]
+    fn test_find_pattern_no_match() {
+        let haystack = [0x48, 0x8B, 0x06];
+        let pattern = parse_pattern("48 8b 05").unwrap();
+        assert_eq!(find_pattern(&haystack, &pattern), None);
+        // Pattern longer than haystack
+        let long = parse_pattern("48 8b 06 00").unwrap();
+        assert_eq!(find_pattern(&haystack, &long), None);
+    }
+
+    #[test]
+    fn test_rip_relative_forward() {
+        // mov rax, [rip+0x100] at 0x1000, 7 bytes long → 0x1000 + 7 + 0x100
+        let instr = [0x48, 0x8B, 0x05, 0x00, 0x01, 0x00, 0x00];
+        assert_eq!(rip_relative_target(0x1000, &instr, 3, 7), Some(0x1107));
+    }
+
+    #[test]
+    fn test_rip_relative_backward() {
+        // Negative displacement: -0x10
+        let instr = [0x48, 0x8B, 0x05, 0xF0, 0xFF, 0xFF, 0xFF];
+        assert_eq!(rip_relative_target(0x1000, &instr, 3, 7), Some(0x0FF7));
+    }
+
+    #[test]
+    fn test_rip_relative_truncated() {
+        let instr = [0x48, 0x8B, 0x05, 0x00];
+        assert_eq!(rip_relative_target(0x1000, &instr, 3, 7), None);
+    }
+}
@@ -0,0 +1,220 @@
+//! Audio cues
+//!
+//! Short sounds on race events, so the player doesn't have to watch the
+//! overlay: a zone revealed after a loading screen, a new discovery, being
+//! overtaken and the finish. Each cue plays a WAV file the player drops next
+//! to the DLL. Playback is platform code (`dll::audio`); the volume is applied
+//! here by scaling the PCM samples once, when the file is loaded.
+
+use serde::{Deserialize, Serialize};
+
+/// `[audio]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioSettings {
+    /// Play the cues whose WAV file is present
+    #[serde(default)]
+    pub enabled: bool,
+    /// 0.0 (silent) to 1.0 (the file as is)
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    #[serde(default = "default_true")]
+    pub zone_reveal: bool,
+    #[serde(default = "default_true")]
+    pub discovery: bool,
+    #[serde(default = "default_true")]
+    pub overtaken: bool,
+    #[serde(default = "default_true")]
+    pub finish: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+fn default_volume() -> f32 {
+    0.8
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            volume: default_volume(),
+            zone_reveal: true,
+            discovery: true,
+            overtaken: true,
+            finish: true,
+        }
+    }
+}
+
+impl AudioSettings {
+    /// Whether this cue is played
+    pub fn plays(&self, cue: AudioCue) -> bool {
+        self.enabled
+            && match cue {
+                AudioCue::ZoneReveal => self.zone_reveal,
+                AudioCue::Discovery => self.discovery,
+                AudioCue::Overtaken => self.overtaken,
+                AudioCue::Finish => self.finish,
+            }
+    }
+}
+
+/// Race event with a sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioCue {
+    /// Zone name shown after a loading screen (already visited)
+    ZoneReveal,
+    /// First visit of a zone acknowledged by the server
+    Discovery,
+    /// Another player took the player's position
+    Overtaken,
+    /// Finish detected
+    Finish,
+}
+
+impl AudioCue {
+    pub const ALL: [AudioCue; 4] = [
+        AudioCue::ZoneReveal,
+        AudioCue::Discovery,
+        AudioCue::Overtaken,
+        AudioCue::Finish,
+    ];
+
+    /// WAV file played for this cue, next to the DLL
+    pub fn file_name(self) -> &'static str {
+        match self {
+            AudioCue::ZoneReveal => "sound_zone.wav",
+            AudioCue::Discovery => "sound_discovery.wav",
+            AudioCue::Overtaken => "sound_overtaken.wav",
+            AudioCue::Finish => "sound_finish.wav",
+        }
+    }
+}
+
+const WAVE_FORMAT_PCM: u16 = 1;
+
+/// Copy of a PCM WAV file (8 or 16 bits) with its samples scaled to `volume`
+pub fn scale_wav(data: &[u8], volume: f32) -> Result<Vec<u8>, String> {
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err("not a WAV file".to_string());
+    }
+    let mut out = data.to_vec();
+    let mut bits = None;
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let id = &data[pos..pos + 4];
+        let size = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]])
+            as usize;
+        let start = pos + 8;
+        let end = start.saturating_add(size).min(data.len());
+        match id {
+            b"fmt " => {
+                if end - start < 16 {
+                    return Err("truncated fmt chunk".to_string());
+                }
+                let format = u16::from_le_bytes([data[start], data[start + 1]]);
+                let sample_bits = u16::from_le_bytes([data[start + 14], data[start + 15]]);
+                if format != WAVE_FORMAT_PCM || !matches!(sample_bits, 8 | 16) {
+                    return Err(format!(
+                        "unsupported format {} ({} bits), use 8 or 16-bit PCM",
+                        format, sample_bits
+                    ));
+                }
+                bits = Some(sample_bits);
+            }
+            b"data" => {
+                let bits = bits.ok_or("data chunk before fmt chunk")?;
+                scale_samples(&mut out[start..end], bits, volume.clamp(0.0, 1.0));
+                return Ok(out);
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even size
+        pos = start.saturating_add(size + size % 2);
+    }
+    Err("no data chunk".to_string())
+}
+
+fn scale_samples(samples: &mut [u8], bits: u16, volume: f32) {
+    if volume >= 1.0 {
+        return;
+    }
+    if bits == 8 {
+        // Unsigned, silence at 128
+        for s in samples.iter_mut() {
+            *s = ((*s as f32 - 128.0) * volume + 128.0).round() as u8;
+        }
+    } else {
+        for s in samples.chunks_exact_mut(2) {
+            let value = i16::from_le_bytes([s[0], s[1]]);
+            let scaled = (value as f32 * volume).round() as i16;
+            s.copy_from_slice(&scaled.to_le_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal PCM WAV with an extra chunk before the samples
+    fn wav(bits: u16, samples: &[u8]) -> Vec<u8> {
+        let mut data = b"RIFF\0\0\0\0WAVE".to_vec();
+        data.extend_from_slice(b"fmt \x10\0\0\0");
+        data.extend_from_slice(&WAVE_FORMAT_PCM.to_le_bytes());
+        data.extend_from_slice(&1u16.to_le_bytes()); // mono
+        data.extend_from_slice(&44100u32.to_le_bytes());
+        data.extend_from_slice(&(44100 * bits as u32 / 8).to_le_bytes());
+        data.extend_from_slice(&(bits / 8).to_le_bytes());
+        data.extend_from_slice(&bits.to_le_bytes());
+        data.extend_from_slice(b"LIST\x03\0\0\0abc\0");
+        data.extend_from_slice(b"data");
+        data.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+        data.extend_from_slice(samples);
+        data
+    }
+
+    #[test]
+    fn test_scale_16_bit() {
+        let samples: Vec<u8> = [1000i16, -2000, 0]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let scaled = scale_wav(&wav(16, &samples), 0.5).unwrap();
+        let data = &scaled[scaled.len() - 6..];
+        assert_eq!(i16::from_le_bytes([data[0], data[1]]), 500);
+        assert_eq!(i16::from_le_bytes([data[2], data[3]]), -1000);
+        assert_eq!(i16::from_le_bytes([data[4], data[5]]), 0);
+        // The header is untouched
+        assert_eq!(scaled.len(), wav(16, &samples).len());
+    }
+
+    #[test]
+    fn test_scale_8_bit() {
+        let scaled = scale_wav(&wav(8, &[228, 128, 28]), 0.5).unwrap();
+        assert_eq!(&scaled[scaled.len() - 3..], &[178, 128, 78]);
+        // Full volume keeps the file as is
+        assert_eq!(scale_wav(&wav(8, &[228]), 1.0).unwrap(), wav(8, &[228]));
+    }
+
+    #[test]
+    fn test_rejects_other_files() {
+        assert!(scale_wav(b"OggS", 0.5).is_err());
+        let mut float = wav(16, &[0, 0]);
+        float[20] = 3; // IEEE float
+        assert!(scale_wav(&float, 0.5).is_err());
+        let header_only = &wav(16, &[])[..36];
+        assert!(scale_wav(header_only, 0.5).is_err());
+    }
+
+    #[test]
+    fn test_cues_toggle() {
+        let mut settings = AudioSettings::default();
+        assert!(!settings.plays(AudioCue::Finish));
+        settings.enabled = true;
+        settings.overtaken = false;
+        assert!(settings.plays(AudioCue::Finish));
+        assert!(!settings.plays(AudioCue::Overtaken));
+    }
+}
@@ -0,0 +1,176 @@
+//! Overlay auto-hide after inactivity
+//!
+//! Long exploration stretches leave the overlay unchanged on screen for
+//! minutes. With `[auto_hide]`, it fades to a low opacity once nothing has
+//! changed for a while: no zone change, no leaderboard movement, no race
+//! event, toast or hotkey. Any of them brings it back at full opacity at once.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// `[auto_hide]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutoHideSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds without any change before the overlay fades
+    #[serde(default = "default_after_secs")]
+    pub after_secs: u64,
+    /// Opacity once faded (0.0 = invisible, 1.0 = no fade)
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+    /// Length of the fade out, in milliseconds (coming back is instant)
+    #[serde(default = "default_fade_ms")]
+    pub fade_ms: u64,
+}
+
+fn default_after_secs() -> u64 {
+    60
+}
+fn default_opacity() -> f32 {
+    0.25
+}
+fn default_fade_ms() -> u64 {
+    1000
+}
+
+impl Default for AutoHideSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            after_secs: default_after_secs(),
+            opacity: default_opacity(),
+            fade_ms: default_fade_ms(),
+        }
+    }
+}
+
+/// Time since the last activity, turned into the overlay's opacity
+#[derive(Debug)]
+pub struct AutoHide {
+    last_activity: Instant,
+    /// State seen on the previous frame (zone, leaderboard order)
+    fingerprint: u64,
+}
+
+impl AutoHide {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            last_activity: now,
+            fingerprint: 0,
+        }
+    }
+
+    /// Something happened: back to full opacity
+    pub fn wake(&mut self, now: Instant) {
+        self.last_activity = now;
+    }
+
+    /// The overlay's state this frame, as a hash; a change counts as activity
+    pub fn observe(&mut self, fingerprint: u64, now: Instant) {
+        if fingerprint != self.fingerprint {
+            self.fingerprint = fingerprint;
+            self.wake(now);
+        }
+    }
+
+    /// Opacity of the overlay this frame, 1.0 while not faded
+    pub fn alpha(&self, now: Instant, settings: &AutoHideSettings) -> f32 {
+        if !settings.enabled {
+            return 1.0;
+        }
+        let target = settings.opacity.clamp(0.0, 1.0);
+        let idle = now.saturating_duration_since(self.last_activity);
+        let Some(fading) = idle.checked_sub(Duration::from_secs(settings.after_secs)) else {
+            return 1.0;
+        };
+        let fade = Duration::from_millis(settings.fade_ms);
+        if fading >= fade {
+            return target;
+        }
+        1.0 - (1.0 - target) * (fading.as_secs_f32() / fade.as_secs_f32())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> AutoHideSettings {
+        AutoHideSettings {
+            enabled: true,
+            after_secs: 10,
+            opacity: 0.2,
+            fade_ms: 1000,
+        }
+    }
+
+    #[test]
+    fn test_disabled_never_fades() {
+        let start = Instant::now();
+        let hide = AutoHide::new(start);
+        let later = start + Duration::from_secs(3600);
+        assert_eq!(hide.alpha(later, &AutoHideSettings::default()), 1.0);
+    }
+
+    #[test]
+    fn test_fades_after_inactivity() {
+        let start = Instant::now();
+        let hide = AutoHide::new(start);
+        let settings = settings();
+        assert_eq!(hide.alpha(start + Duration::from_secs(9), &settings), 1.0);
+        let half = hide.alpha(start + Duration::from_millis(10_500), &settings);
+        assert!((half - 0.6).abs() < 1e-4, "{}", half);
+        assert_eq!(hide.alpha(start + Duration::from_secs(30), &settings), 0.2);
+    }
+
+    #[test]
+    fn test_activity_restores_instantly() {
+        let start = Instant::now();
+        let mut hide = AutoHide::new(start);
+        let settings = settings();
+        let faded = start + Duration::from_secs(20);
+        assert_eq!(hide.alpha(faded, &settings), 0.2);
+        hide.wake(faded);
+        assert_eq!(hide.alpha(faded, &settings), 1.0);
+    }
+
+    #[test]
+    fn test_state_change_is_activity() {
+        let start = Instant::now();
+        let mut hide = AutoHide::new(start);
+        let settings = settings();
+        hide.observe(42, start);
+        let faded = start + Duration::from_secs(20);
+        // Same zone and leaderboard
+        hide.observe(42, faded);
+        assert_eq!(hide.alpha(faded, &settings), 0.2);
+        // Someone overtook
+        hide.observe(43, faded);
+        assert_eq!(hide.alpha(faded, &settings), 1.0);
+    }
+
+    #[test]
+    fn test_no_fade_duration() {
+        let start = Instant::now();
+        let hide = AutoHide::new(start);
+        let settings = AutoHideSettings {
+            fade_ms: 0,
+            ..settings()
+        };
+        assert_eq!(hide.alpha(start + Duration::from_secs(10), &settings), 0.2);
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings: AutoHideSettings = toml::from_str("").unwrap();
+        assert_eq!(settings, AutoHideSettings::default());
+        let settings: AutoHideSettings =
+            toml::from_str("enabled = true\nafter_secs = 30\nopacity = 0.1").unwrap();
+        assert!(settings.enabled);
+        assert_eq!(settings.after_secs, 30);
+        assert_eq!(settings.opacity, 0.1);
+        assert_eq!(settings.fade_ms, 1000);
+    }
+}
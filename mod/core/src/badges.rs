@@ -0,0 +1,85 @@
+//! Participant badges
+//!
+//! The server tags some participants (protocol 26+): the race organizer,
+//! past winners, players live on Twitch. The leaderboard shows a small
+//! colored tag after their name; the settings panel has the legend.
+
+use super::theme::Theme;
+
+type Rgba = [f32; 4];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Badge {
+    Organizer,
+    PreviousWinner,
+    Streamer,
+}
+
+impl Badge {
+    /// Legend order
+    pub const ALL: [Badge; 3] = [Badge::Organizer, Badge::PreviousWinner, Badge::Streamer];
+
+    /// Identifier sent by the server, None for badges this version doesn't know
+    pub fn parse(id: &str) -> Option<Badge> {
+        match id {
+            "organizer" => Some(Badge::Organizer),
+            "previous_winner" => Some(Badge::PreviousWinner),
+            "streamer" => Some(Badge::Streamer),
+            _ => None,
+        }
+    }
+
+    /// Locale key of the legend; the tag itself is `{key}_tag`
+    pub fn locale_key(self) -> &'static str {
+        match self {
+            Badge::Organizer => "badges.organizer",
+            Badge::PreviousWinner => "badges.previous_winner",
+            Badge::Streamer => "badges.streamer",
+        }
+    }
+
+    pub fn color(self, theme: &Theme) -> Rgba {
+        match self {
+            Badge::Organizer => theme.notice,
+            Badge::PreviousWinner => theme.waiting,
+            Badge::Streamer => theme.error,
+        }
+    }
+}
+
+/// Known badges of a participant, in the server's order, without duplicates
+pub fn badges(ids: &[String]) -> Vec<Badge> {
+    let mut badges = Vec::new();
+    for badge in ids.iter().filter_map(|id| Badge::parse(id)) {
+        if !badges.contains(&badge) {
+            badges.push(badge);
+        }
+    }
+    badges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_badges_in_server_order() {
+        assert_eq!(
+            badges(&ids(&["streamer", "organizer"])),
+            vec![Badge::Streamer, Badge::Organizer]
+        );
+        assert!(badges(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_and_duplicates_skipped() {
+        assert_eq!(
+            badges(&ids(&["sponsor", "previous_winner", "previous_winner"])),
+            vec![Badge::PreviousWinner]
+        );
+    }
+}
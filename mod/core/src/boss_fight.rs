@@ -0,0 +1,109 @@
+//! Boss fight focus mode
+//!
+//! During a boss fight the overlay's panels are a distraction and cost draw
+//! time when frames matter most. With `[boss_fight] minimize`, the overlay
+//! shrinks to the zone / IGT line while a boss health bar is on screen. Bars
+//! drop out between phases, on a death and during the following load, so the
+//! full overlay only comes back once no bar has been seen for
+//! `restore_delay_secs`.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// `[boss_fight]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BossFightSettings {
+    /// Minimize the overlay while a boss health bar is shown
+    #[serde(default)]
+    pub minimize: bool,
+    /// Seconds without a boss bar before the full overlay comes back
+    #[serde(default = "default_restore_delay_secs")]
+    pub restore_delay_secs: f32,
+}
+
+fn default_restore_delay_secs() -> f32 {
+    5.0
+}
+
+impl Default for BossFightSettings {
+    fn default() -> Self {
+        Self {
+            minimize: false,
+            restore_delay_secs: default_restore_delay_secs(),
+        }
+    }
+}
+
+impl BossFightSettings {
+    pub fn restore_delay(&self) -> Duration {
+        Duration::from_secs_f32(self.restore_delay_secs.max(0.0))
+    }
+}
+
+/// Whether a boss fight is going on, with the restore hysteresis
+#[derive(Debug, Default)]
+pub struct BossFightWatch {
+    in_fight: bool,
+    last_bar: Option<Instant>,
+}
+
+impl BossFightWatch {
+    pub fn in_fight(&self) -> bool {
+        self.in_fight
+    }
+
+    /// Feed the boss bar state at `now` (None while unreadable, counted as no
+    /// bar). Returns true when the fight started or ended.
+    pub fn observe(&mut self, now: Instant, bar: Option<bool>, restore_delay: Duration) -> bool {
+        let was_in_fight = self.in_fight;
+        if bar == Some(true) {
+            self.last_bar = Some(now);
+            self.in_fight = true;
+        } else if self
+            .last_bar
+            .is_some_and(|seen| now.duration_since(seen) >= restore_delay)
+        {
+            self.last_bar = None;
+            self.in_fight = false;
+        }
+        self.in_fight != was_in_fight
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELAY: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn test_fight_ends_after_restore_delay() {
+        let mut watch = BossFightWatch::default();
+        let t0 = Instant::now();
+        assert!(!watch.observe(t0, Some(false), DELAY));
+        assert!(watch.observe(t0 + Duration::from_secs(1), Some(true), DELAY));
+        assert!(watch.in_fight());
+
+        // Phase transition: the bar is gone for a moment
+        assert!(!watch.observe(t0 + Duration::from_secs(3), Some(false), DELAY));
+        assert!(!watch.observe(t0 + Duration::from_secs(4), Some(true), DELAY));
+        // Killed, then the death load screen
+        assert!(!watch.observe(t0 + Duration::from_secs(6), None, DELAY));
+        assert!(watch.in_fight());
+        assert!(watch.observe(t0 + Duration::from_secs(9), Some(false), DELAY));
+        assert!(!watch.in_fight());
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings: BossFightSettings = toml::from_str("minimize = true").unwrap();
+        assert!(settings.minimize);
+        assert_eq!(settings.restore_delay(), DELAY);
+        assert!(!BossFightSettings::default().minimize);
+    }
+}
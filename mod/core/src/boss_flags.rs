@@ -0,0 +1,124 @@
+//! Major bosses defeated on the save
+//!
+//! Each remembrance boss sets a defeat flag when it dies. The tracker reads
+//! this table's flags every couple of seconds, race or not, and the count is
+//! available to templates as `{bosses}` (race timer). It reflects the save
+//! as it is: a fresh seed starts at zero, a casual save shows its progress.
+
+/// A boss counted by `{bosses}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MajorBoss {
+    pub name: &'static str,
+    /// Event flag set when the boss is defeated
+    pub flag_id: u32,
+}
+
+/// The base game's remembrance bosses
+pub const MAJOR_BOSSES: &[MajorBoss] = &[
+    MajorBoss {
+        name: "Godrick the Grafted",
+        flag_id: 10000800,
+    },
+    MajorBoss {
+        name: "Rennala, Queen of the Full Moon",
+        flag_id: 14000800,
+    },
+    MajorBoss {
+        name: "Starscourge Radahn",
+        flag_id: 1252380800,
+    },
+    MajorBoss {
+        name: "Rykard, Lord of Blasphemy",
+        flag_id: 16000800,
+    },
+    MajorBoss {
+        name: "Morgott, the Omen King",
+        flag_id: 11000800,
+    },
+    MajorBoss {
+        name: "Lichdragon Fortissax",
+        flag_id: 12030800,
+    },
+    MajorBoss {
+        name: "Astel, Naturalborn of the Void",
+        flag_id: 12040800,
+    },
+    MajorBoss {
+        name: "Mohg, Lord of Blood",
+        flag_id: 12050800,
+    },
+    MajorBoss {
+        name: "Malenia, Blade of Miquella",
+        flag_id: 15000800,
+    },
+    MajorBoss {
+        name: "Maliketh, the Black Blade",
+        flag_id: 13000800,
+    },
+    MajorBoss {
+        name: "Hoarah Loux, Warrior",
+        flag_id: 11050800,
+    },
+    MajorBoss {
+        name: "Regal Ancestor Spirit",
+        flag_id: 12090800,
+    },
+    MajorBoss {
+        name: "Mimic Tear",
+        flag_id: 12020800,
+    },
+    MajorBoss {
+        name: "Dragonlord Placidusax",
+        flag_id: 13000830,
+    },
+    MajorBoss {
+        name: "Elden Beast",
+        flag_id: 19000800,
+    },
+];
+
+/// Major bosses defeated, None as soon as a flag can't be read
+pub fn count_defeated(mut is_set: impl FnMut(u32) -> Option<bool>) -> Option<u32> {
+    let mut defeated = 0;
+    for boss in MAJOR_BOSSES {
+        if is_set(boss.flag_id)? {
+            defeated += 1;
+        }
+    }
+    Some(defeated)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_count_defeated() {
+        assert_eq!(count_defeated(|_| Some(false)), Some(0));
+        let set = [10000800, 19000800, 1234];
+        assert_eq!(
+            count_defeated(|flag_id| Some(set.contains(&flag_id))),
+            Some(2)
+        );
+        assert_eq!(
+            count_defeated(|_| Some(true)),
+            Some(MAJOR_BOSSES.len() as u32)
+        );
+    }
+
+    #[test]
+    fn test_unreadable_flag() {
+        assert_eq!(
+            count_defeated(|flag_id| (flag_id != 12050800).then_some(true)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_flags_unique() {
+        let flags: HashSet<u32> = MAJOR_BOSSES.iter().map(|b| b.flag_id).collect();
+        assert_eq!(flags.len(), MAJOR_BOSSES.len());
+    }
+}
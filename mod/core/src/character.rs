@@ -0,0 +1,220 @@
+//! Character fingerprint checks
+//!
+//! Racers sometimes load the wrong save. The tracker reads the loaded
+//! character (save slot, name, level, starting class), warns before the start
+//! when it doesn't match the character the seed was generated for, and
+//! notices when another character is loaded mid-race.
+
+use super::locale::Locale;
+use super::protocol::{CharacterFingerprint, ExpectedCharacter};
+
+/// Starting classes, indexed by class id
+pub const CLASS_NAMES: [&str; 10] = [
+    "Vagabond",
+    "Warrior",
+    "Hero",
+    "Bandit",
+    "Astrologer",
+    "Prophet",
+    "Confessor",
+    "Samurai",
+    "Prisoner",
+    "Wretch",
+];
+
+pub fn class_name(class_id: u8) -> &'static str {
+    CLASS_NAMES
+        .get(class_id as usize)
+        .copied()
+        .unwrap_or("Unknown class")
+}
+
+/// Class name in the overlay language (the game's own translation)
+pub fn class_label(class_id: u8, locale: &Locale) -> &str {
+    let name = class_name(class_id);
+    locale
+        .lookup(&format!("class.{}", name.to_lowercase()))
+        .unwrap_or(name)
+}
+
+/// Character name from the game's fixed-size UTF-16 buffer (NUL terminated)
+pub fn decode_name(raw: &[u16]) -> String {
+    let len = raw.iter().position(|&c| c == 0).unwrap_or(raw.len());
+    String::from_utf16_lossy(&raw[..len])
+}
+
+/// Whether two fingerprints are different characters (leveling up doesn't count)
+pub fn is_other_character(a: &CharacterFingerprint, b: &CharacterFingerprint) -> bool {
+    a.slot != b.slot || a.name != b.name || a.class_id != b.class_id
+}
+
+/// Warning text when the character doesn't match the seed's starting character.
+/// Only meaningful before the start: the level goes up during the race.
+pub fn expectation_mismatch(
+    expected: &ExpectedCharacter,
+    character: &CharacterFingerprint,
+    locale: &Locale,
+) -> Option<String> {
+    let class_ok = expected.class_id.is_none_or(|id| id == character.class_id);
+    let level_ok = expected.level.is_none_or(|level| level == character.level);
+    if class_ok && level_ok {
+        return None;
+    }
+    let wanted = match (expected.class_id, expected.level) {
+        (Some(id), Some(level)) => locale.format(
+            "warning.expected_class_level",
+            &[("level", &level), ("class", &class_label(id, locale))],
+        ),
+        (Some(id), None) => class_label(id, locale).to_string(),
+        (None, Some(level)) => locale.format("warning.expected_level", &[("level", &level)]),
+        (None, None) => unreachable!("empty expectation always matches"),
+    };
+    Some(locale.format(
+        "warning.wrong_character",
+        &[
+            ("expected", &wanted),
+            ("level", &character.level),
+            ("class", &class_label(character.class_id, locale)),
+        ],
+    ))
+}
+
+/// How the character read this poll relates to the previous one
+#[derive(Debug, Clone, PartialEq)]
+pub enum CharacterChange {
+    /// First character read this session
+    First,
+    /// Another character than the previous one was loaded
+    Switched { previous: CharacterFingerprint },
+}
+
+/// Last character read from memory
+#[derive(Debug, Default)]
+pub struct CharacterMonitor {
+    current: Option<CharacterFingerprint>,
+}
+
+impl CharacterMonitor {
+    /// Record the character read this poll. Returns None for the same
+    /// character (its level is still updated).
+    pub fn observe(&mut self, character: CharacterFingerprint) -> Option<CharacterChange> {
+        let change = match self.current.take() {
+            None => Some(CharacterChange::First),
+            Some(previous) if is_other_character(&previous, &character) => {
+                Some(CharacterChange::Switched { previous })
+            }
+            Some(_) => None,
+        };
+        self.current = Some(character);
+        change
+    }
+
+    pub fn current(&self) -> Option<&CharacterFingerprint> {
+        self.current.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn character(slot: u32, level: u32, class_id: u8) -> CharacterFingerprint {
+        CharacterFingerprint {
+            slot: Some(slot),
+            name: "Tarnished".to_string(),
+            level,
+            class_id,
+        }
+    }
+
+    #[test]
+    fn test_decode_name() {
+        let mut raw = [0u16; 16];
+        for (i, c) in "Tarnished".encode_utf16().enumerate() {
+            raw[i] = c;
+        }
+        assert_eq!(decode_name(&raw), "Tarnished");
+        assert_eq!(decode_name(&[0u16; 16]), "");
+        // No terminator: the whole buffer is the name
+        let full: Vec<u16> = "ABCDEFGHIJKLMNOP".encode_utf16().collect();
+        assert_eq!(decode_name(&full), "ABCDEFGHIJKLMNOP");
+    }
+
+    #[test]
+    fn test_class_name() {
+        assert_eq!(class_name(0), "Vagabond");
+        assert_eq!(class_name(9), "Wretch");
+        assert_eq!(class_name(42), "Unknown class");
+    }
+
+    #[test]
+    fn test_leveling_up_is_same_character() {
+        let mut monitor = CharacterMonitor::default();
+        assert_eq!(
+            monitor.observe(character(0, 9, 8)),
+            Some(CharacterChange::First)
+        );
+        assert_eq!(monitor.observe(character(0, 30, 8)), None);
+        assert_eq!(monitor.current().map(|c| c.level), Some(30));
+    }
+
+    #[test]
+    fn test_switch_detected() {
+        let mut monitor = CharacterMonitor::default();
+        monitor.observe(character(0, 9, 8));
+        assert_eq!(
+            monitor.observe(character(1, 9, 8)),
+            Some(CharacterChange::Switched {
+                previous: character(0, 9, 8)
+            })
+        );
+        assert!(matches!(
+            monitor.observe(character(1, 9, 7)),
+            Some(CharacterChange::Switched { .. })
+        ));
+    }
+
+    #[test]
+    fn test_expectation_mismatch() {
+        let en = Locale::english();
+        let expected = ExpectedCharacter {
+            class_id: Some(8),
+            level: Some(9),
+        };
+        assert_eq!(
+            expectation_mismatch(&expected, &character(0, 9, 8), &en),
+            None
+        );
+        assert_eq!(
+            expectation_mismatch(&expected, &character(0, 9, 7), &en).as_deref(),
+            Some("Wrong character: seed expects a level 9 Prisoner, loaded level 9 Samurai")
+        );
+        assert!(expectation_mismatch(&expected, &character(0, 12, 8), &en).is_some());
+
+        let class_only = ExpectedCharacter {
+            class_id: Some(9),
+            level: None,
+        };
+        assert_eq!(
+            expectation_mismatch(&class_only, &character(0, 1, 9), &en),
+            None
+        );
+        assert_eq!(
+            expectation_mismatch(&ExpectedCharacter::default(), &character(0, 1, 0), &en),
+            None
+        );
+    }
+
+    #[test]
+    fn test_expectation_mismatch_localized() {
+        let fr = Locale::builtin("fr").unwrap();
+        let expected = ExpectedCharacter {
+            class_id: Some(9),
+            level: None,
+        };
+        assert_eq!(
+            expectation_mismatch(&expected, &character(0, 9, 8), &fr).as_deref(),
+            Some("Mauvais personnage : la seed attend Misérable, personnage chargé : Prisonnier niveau 9")
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Race checkpoints
+//!
+//! Races can define intermediate checkpoints in auth_ok: an event flag (boss
+//! kill, item) or a zone to enter. Flag checkpoints are polled like
+//! objectives, zone checkpoints are crossed on the zone_update of their node.
+//! Every crossing is sent with its IGT; the leaderboard then ranks the
+//! participants at each checkpoint from the times the server broadcasts.
+
+use super::protocol::{Checkpoint, ParticipantInfo};
+
+#[derive(Debug, Clone, Default)]
+pub struct CheckpointTracker {
+    checkpoints: Vec<Checkpoint>,
+    /// (checkpoint_id, igt_ms) in crossing order
+    crossed: Vec<(u32, u32)>,
+    /// Crossings not sent to the server yet
+    unsent: Vec<(u32, u32)>,
+}
+
+impl CheckpointTracker {
+    /// Checkpoints of the race (from auth_ok). Crossings already seen are kept.
+    pub fn set_checkpoints(&mut self, checkpoints: Vec<Checkpoint>) {
+        self.checkpoints = checkpoints;
+    }
+
+    /// Check the flags of the flag checkpoints and return the newly crossed
+    /// ones, which are also queued for sending. `is_flag_set` returns None
+    /// when unreadable.
+    pub fn poll(
+        &mut self,
+        igt_ms: u32,
+        mut is_flag_set: impl FnMut(u32) -> Option<bool>,
+    ) -> Vec<Checkpoint> {
+        self.cross(igt_ms, |checkpoint| {
+            checkpoint
+                .flag_id
+                .is_some_and(|flag| is_flag_set(flag) == Some(true))
+        })
+    }
+
+    /// The player entered a zone: zone checkpoints of that node are crossed
+    pub fn zone_entered(&mut self, node_id: &str, igt_ms: u32) -> Vec<Checkpoint> {
+        self.cross(igt_ms, |checkpoint| {
+            checkpoint.node_id.as_deref() == Some(node_id)
+        })
+    }
+
+    fn cross(
+        &mut self,
+        igt_ms: u32,
+        mut reached: impl FnMut(&Checkpoint) -> bool,
+    ) -> Vec<Checkpoint> {
+        let mut newly = Vec::new();
+        for checkpoint in &self.checkpoints {
+            if self.is_crossed(checkpoint.id) || !reached(checkpoint) {
+                continue;
+            }
+            self.crossed.push((checkpoint.id, igt_ms));
+            self.unsent.push((checkpoint.id, igt_ms));
+            newly.push(checkpoint.clone());
+        }
+        newly
+    }
+
+    /// Crossings waiting to be sent, as (checkpoint_id, igt_ms)
+    pub fn take_unsent(&mut self) -> Vec<(u32, u32)> {
+        std::mem::take(&mut self.unsent)
+    }
+
+    /// Queue every crossing again after a reconnect, in case the socket
+    /// dropped one silently. The server ignores checkpoints it already recorded.
+    pub fn requeue_all(&mut self) {
+        self.unsent = self.crossed.clone();
+    }
+
+    pub fn is_crossed(&self, checkpoint_id: u32) -> bool {
+        self.crossed.iter().any(|&(id, _)| id == checkpoint_id)
+    }
+
+    /// Checkpoints in race order
+    pub fn checkpoints(&self) -> &[Checkpoint] {
+        &self.checkpoints
+    }
+
+    pub fn has_flags(&self) -> bool {
+        self.checkpoints.iter().any(|c| c.flag_id.is_some())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checkpoints.is_empty()
+    }
+}
+
+/// Participants who crossed a checkpoint, fastest first
+#[derive(Debug)]
+pub struct CheckpointStanding<'a> {
+    pub checkpoint: &'a Checkpoint,
+    /// (participant, igt_ms)
+    pub times: Vec<(&'a ParticipantInfo, i32)>,
+}
+
+impl CheckpointStanding<'_> {
+    /// 1-based rank of a participant and their gap to the fastest
+    pub fn placement(&self, participant_id: &str) -> Option<Placement> {
+        let index = self
+            .times
+            .iter()
+            .position(|(p, _)| p.id == participant_id)?;
+        Some(Placement {
+            checkpoint_id: self.checkpoint.id,
+            rank: index + 1,
+            gap_ms: self.times[index].1 - self.times[0].1,
+        })
+    }
+}
+
+/// Standing of one participant at one checkpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub checkpoint_id: u32,
+    pub rank: usize,
+    /// Behind the fastest at this checkpoint (0 when first)
+    pub gap_ms: i32,
+}
+
+/// Standings at every checkpoint, in race order
+pub fn standings<'a>(
+    checkpoints: &'a [Checkpoint],
+    participants: &'a [ParticipantInfo],
+) -> Vec<CheckpointStanding<'a>> {
+    checkpoints
+        .iter()
+        .map(|checkpoint| {
+            let mut times: Vec<(&ParticipantInfo, i32)> = participants
+                .iter()
+                .filter_map(|p| {
+                    let time = p
+                        .checkpoints
+                        .iter()
+                        .find(|t| t.checkpoint_id == checkpoint.id)?;
+                    Some((p, time.igt_ms))
+                })
+                .collect();
+            times.sort_by_key(|&(_, igt_ms)| igt_ms);
+            CheckpointStanding { checkpoint, times }
+        })
+        .collect()
+}
+
+/// Best and worst placements of a participant over the checkpoints they
+/// crossed: lowest rank first, smaller gap on equal ranks. Earlier
+/// checkpoints win ties. None before their first checkpoint.
+pub fn best_and_worst(
+    standings: &[CheckpointStanding],
+    participant_id: &str,
+) -> Option<(Placement, Placement)> {
+    let placements: Vec<Placement> = standings
+        .iter()
+        .filter_map(|s| s.placement(participant_id))
+        .collect();
+    let key = |p: &&Placement| (p.rank, p.gap_ms);
+    let best = *placements.iter().min_by_key(key)?;
+    let worst = *placements.iter().rev().max_by_key(key)?;
+    Some((best, worst))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::CheckpointTime;
+
+    fn checkpoints() -> Vec<Checkpoint> {
+        vec![
+            Checkpoint {
+                id: 0,
+                name: "Margit".to_string(),
+                flag_id: Some(10000800),
+                node_id: None,
+            },
+            Checkpoint {
+                id: 1,
+                name: "Liurnia".to_string(),
+                flag_id: None,
+                node_id: Some("liurnia_main".to_string()),
+            },
+        ]
+    }
+
+    fn participant(id: &str, times: &[(u32, i32)]) -> ParticipantInfo {
+        ParticipantInfo {
+            id: id.to_string(),
+            twitch_username: id.to_string(),
+            twitch_display_name: None,
+            status: "playing".to_string(),
+            current_zone: None,
+            current_zone_name: None,
+            current_layer: 0,
+            current_layer_tier: None,
+            igt_ms: 0,
+            death_count: 0,
+            gap_ms: None,
+            layer_entry_igt: None,
+            zone_entry_igt: None,
+            key_items: Vec::new(),
+            objectives_completed: Vec::new(),
+            afk: false,
+            pause: None,
+            checkpoints: times
+                .iter()
+                .map(|&(checkpoint_id, igt_ms)| CheckpointTime {
+                    checkpoint_id,
+                    igt_ms,
+                })
+                .collect(),
+            badges: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_crossed_once() {
+        let mut tracker = CheckpointTracker::default();
+        tracker.set_checkpoints(checkpoints());
+        assert!(tracker.poll(1000, |_| None).is_empty());
+        let newly = tracker.poll(2000, |flag| Some(flag == 10000800));
+        assert_eq!(newly[0].name, "Margit");
+        assert!(tracker.poll(3000, |_| Some(true)).is_empty());
+
+        // Flag checkpoints aren't crossed by entering a zone, and vice versa
+        assert!(tracker.zone_entered("stormveil", 4000).is_empty());
+        assert_eq!(tracker.zone_entered("liurnia_main", 5000).len(), 1);
+        assert!(tracker.zone_entered("liurnia_main", 6000).is_empty());
+
+        assert_eq!(tracker.take_unsent(), vec![(0, 2000), (1, 5000)]);
+        assert!(tracker.take_unsent().is_empty());
+        tracker.requeue_all();
+        assert_eq!(tracker.take_unsent().len(), 2);
+    }
+
+    #[test]
+    fn test_standings() {
+        let checkpoints = checkpoints();
+        let participants = [
+            participant("a", &[(0, 60_000), (1, 200_000)]),
+            participant("b", &[(0, 50_000), (1, 210_000)]),
+            participant("c", &[(0, 70_000)]),
+            participant("d", &[]),
+        ];
+        let standings = standings(&checkpoints, &participants);
+        let order: Vec<&str> = standings[0]
+            .times
+            .iter()
+            .map(|(p, _)| p.id.as_str())
+            .collect();
+        assert_eq!(order, vec!["b", "a", "c"]);
+        assert_eq!(standings[1].times.len(), 2);
+
+        let (best, worst) = best_and_worst(&standings, "a").unwrap();
+        assert_eq!((best.checkpoint_id, best.rank, best.gap_ms), (1, 1, 0));
+        assert_eq!(
+            (worst.checkpoint_id, worst.rank, worst.gap_ms),
+            (0, 2, 10_000)
+        );
+        // A single checkpoint is both
+        let (best, worst) = best_and_worst(&standings, "c").unwrap();
+        assert_eq!(best, worst);
+        assert!(best_and_worst(&standings, "d").is_none());
+    }
+}
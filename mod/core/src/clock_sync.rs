@@ -0,0 +1,172 @@
+//! Race clock synchronization
+//!
+//! The race clock is derived from the server's authoritative `started_at`.
+//! Local and server wall clocks can disagree by seconds, so the mod sends
+//! `clock_sync` probes and estimates the offset NTP-style: the server timestamp
+//! is assumed to be taken halfway through the round trip.
+
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, NaiveDateTime};
+
+/// Number of recent probes considered when picking the best estimate
+const MAX_SAMPLES: usize = 8;
+
+/// One clock_sync round trip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSample {
+    /// server clock - local clock
+    pub offset_ms: i64,
+    pub rtt_ms: i64,
+}
+
+/// Estimates the offset between the local and server wall clocks
+#[derive(Debug, Default)]
+pub struct ClockSync {
+    samples: VecDeque<ClockSample>,
+}
+
+impl ClockSync {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a probe answered by the server.
+    ///
+    /// `client_sent_ms` / `client_received_ms` are local wall-clock times around
+    /// the round trip, `server_ms` the server's wall clock when it answered.
+    /// Returns None (and ignores the sample) if the local clock went backwards.
+    pub fn add_sample(
+        &mut self,
+        client_sent_ms: i64,
+        server_ms: i64,
+        client_received_ms: i64,
+    ) -> Option<ClockSample> {
+        let rtt_ms = client_received_ms - client_sent_ms;
+        if rtt_ms < 0 {
+            return None;
+        }
+        let sample = ClockSample {
+            offset_ms: server_ms - (client_sent_ms + rtt_ms / 2),
+            rtt_ms,
+        };
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+        Some(sample)
+    }
+
+    /// Best recent estimate: the sample with the lowest round trip has the
+    /// smallest uncertainty on when the server timestamp was taken.
+    pub fn best(&self) -> Option<ClockSample> {
+        self.samples.iter().min_by_key(|s| s.rtt_ms).copied()
+    }
+
+    /// Convert a local wall-clock time to server time.
+    /// Without any sample the clocks are assumed to agree.
+    pub fn to_server_ms(&self, local_ms: i64) -> i64 {
+        local_ms + self.best().map(|s| s.offset_ms).unwrap_or(0)
+    }
+
+    /// Authoritative race elapsed time, clamped to 0 before the start
+    pub fn race_elapsed_ms(&self, started_at_ms: i64, local_now_ms: i64) -> i64 {
+        (self.to_server_ms(local_now_ms) - started_at_ms).max(0)
+    }
+}
+
+/// Local wall clock in milliseconds since the Unix epoch
+pub fn unix_time_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Parse a server ISO 8601 timestamp (`started_at`) to ms since the Unix epoch.
+/// Timestamps without a timezone are taken as UTC.
+pub fn parse_timestamp_ms(s: &str) -> Option<i64> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.timestamp_millis());
+    }
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|dt| dt.and_utc().timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_from_symmetric_round_trip() {
+        let mut sync = ClockSync::new();
+        // Sent at 1000, received at 1200 → server stamped at local 1100, server says 6100
+        let sample = sync.add_sample(1000, 6100, 1200).unwrap();
+        assert_eq!(sample.rtt_ms, 200);
+        assert_eq!(sample.offset_ms, 5000);
+        assert_eq!(sync.to_server_ms(2000), 7000);
+    }
+
+    #[test]
+    fn test_best_sample_has_lowest_rtt() {
+        let mut sync = ClockSync::new();
+        sync.add_sample(0, 900, 1000); // rtt 1000, offset 400
+        sync.add_sample(2000, 2540, 2080); // rtt 80, offset 500
+        sync.add_sample(3000, 3700, 3400); // rtt 400, offset 500
+        assert_eq!(
+            sync.best(),
+            Some(ClockSample {
+                offset_ms: 500,
+                rtt_ms: 80
+            })
+        );
+    }
+
+    #[test]
+    fn test_old_samples_are_dropped() {
+        let mut sync = ClockSync::new();
+        sync.add_sample(0, 100, 0); // rtt 0, would win forever
+        for i in 0..MAX_SAMPLES as i64 {
+            sync.add_sample(i * 1000, i * 1000 + 50, i * 1000 + 20);
+        }
+        assert_eq!(sync.best().unwrap().offset_ms, 40);
+    }
+
+    #[test]
+    fn test_negative_rtt_rejected() {
+        let mut sync = ClockSync::new();
+        assert!(sync.add_sample(1000, 5000, 900).is_none());
+        assert!(sync.best().is_none());
+        // No sample: clocks assumed in agreement
+        assert_eq!(sync.to_server_ms(1234), 1234);
+    }
+
+    #[test]
+    fn test_race_elapsed() {
+        let mut sync = ClockSync::new();
+        sync.add_sample(10_000, 12_000, 10_000); // server 2s ahead
+        assert_eq!(sync.race_elapsed_ms(11_000, 10_000), 1000);
+        // Before the start
+        assert_eq!(sync.race_elapsed_ms(20_000, 10_000), 0);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(
+            parse_timestamp_ms("2026-02-28T20:00:00+00:00"),
+            Some(1_772_308_800_000)
+        );
+        assert_eq!(
+            parse_timestamp_ms("2026-02-28T21:00:00.250+01:00"),
+            Some(1_772_308_800_250)
+        );
+        // Python isoformat() of a naive datetime
+        assert_eq!(
+            parse_timestamp_ms("2026-02-28T20:00:00.123456"),
+            Some(1_772_308_800_123)
+        );
+        assert_eq!(parse_timestamp_ms("yesterday"), None);
+    }
+}
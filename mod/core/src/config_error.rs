@@ -0,0 +1,214 @@
+//! Config validation errors
+//!
+//! Problems in `speedfog_race.toml` that keep the mod from racing. Each error
+//! names the setting and what to change: the DLL lists them in an in-game
+//! window when the tracker can't start, since few players read the log.
+
+use std::fmt;
+
+/// Settings the mod can't race without
+const REQUIRED_SERVER_FIELDS: [&str; 3] = ["url", "mod_token", "race_id"];
+
+/// Schemes accepted for `server.url` (http(s) is turned into ws(s))
+const URL_SCHEMES: [&str; 4] = ["https://", "http://", "wss://", "ws://"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// No config next to the DLL
+    NotFound { path: String },
+    /// The file exists but couldn't be read
+    Unreadable { path: String, reason: String },
+    /// Invalid TOML, or a setting of the wrong type
+    Syntax { message: String },
+    /// A required setting is missing or empty (`server.mod_token`)
+    Missing { field: String },
+    /// `server.url` is not an http(s) or ws(s) address
+    MalformedUrl { url: String },
+    /// A color setting is not `#RRGGBB`
+    BadColor { field: String, value: String },
+    /// A keybinding names a key the mod doesn't know
+    InvalidKeybinding { action: String, key: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::NotFound { path } => write!(
+                f,
+                "{} not found: download the config from the race page and put it next to the DLL",
+                path
+            ),
+            ConfigError::Unreadable { path, reason } => {
+                write!(f, "Can't read {}: {}", path, reason)
+            }
+            ConfigError::Syntax { message } => write!(f, "Invalid config: {}", message.trim()),
+            ConfigError::Missing { field } => write!(
+                f,
+                "{} is missing: download the config again from the race page",
+                field
+            ),
+            ConfigError::MalformedUrl { url } => write!(
+                f,
+                "server.url \"{}\" is not a server address (expected https://...)",
+                url
+            ),
+            ConfigError::BadColor { field, value } => write!(
+                f,
+                "{} \"{}\" is not a color (expected \"#RRGGBB\")",
+                field, value
+            ),
+            ConfigError::InvalidKeybinding { action, key } => write!(
+                f,
+                "keybindings.{} \"{}\" is not a known key (e.g. \"f9\", \"home\")",
+                action, key
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Required `[server]` settings and the URL format
+pub fn check_server(url: &str, mod_token: &str, race_id: &str) -> Vec<ConfigError> {
+    let mut errors: Vec<ConfigError> = REQUIRED_SERVER_FIELDS
+        .iter()
+        .zip([url, mod_token, race_id])
+        .filter(|(_, value)| value.trim().is_empty())
+        .map(|(field, _)| ConfigError::Missing {
+            field: format!("server.{}", field),
+        })
+        .collect();
+    if !url.trim().is_empty() && !is_server_url(url) {
+        errors.push(ConfigError::MalformedUrl {
+            url: url.to_string(),
+        });
+    }
+    errors
+}
+
+/// http(s) or ws(s) scheme followed by a host
+fn is_server_url(url: &str) -> bool {
+    URL_SCHEMES.iter().any(|scheme| {
+        url.strip_prefix(scheme).is_some_and(|rest| {
+            let host = rest.split('/').next().unwrap_or("");
+            !host.is_empty() && !host.contains(char::is_whitespace)
+        })
+    })
+}
+
+/// A `#RRGGBB` color setting (the leading `#` is optional)
+pub fn check_color(field: &str, value: &str) -> Option<ConfigError> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    let valid = hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit());
+    (!valid).then(|| ConfigError::BadColor {
+        field: field.to_string(),
+        value: value.to_string(),
+    })
+}
+
+/// Keybindings of the raw config whose key name `is_known_key` rejects.
+/// Checked on the TOML table, before deserializing, so that every bad
+/// binding is listed instead of the first parse error.
+pub fn check_keybindings(
+    config: &toml::Table,
+    is_known_key: impl Fn(&str) -> bool,
+) -> Vec<ConfigError> {
+    let Some(bindings) = config.get("keybindings").and_then(|v| v.as_table()) else {
+        return Vec::new();
+    };
+    bindings
+        .iter()
+        .filter_map(|(action, value)| match value.as_str() {
+            Some(key) if is_known_key(key) => None,
+            Some(key) => Some(ConfigError::InvalidKeybinding {
+                action: action.clone(),
+                key: key.to_string(),
+            }),
+            None => Some(ConfigError::InvalidKeybinding {
+                action: action.clone(),
+                key: value.to_string(),
+            }),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_server() {
+        assert!(check_server("https://speedfog.example.com", "tok", "race").is_empty());
+        assert!(check_server("wss://speedfog.example.com/", "tok", "race").is_empty());
+        assert_eq!(
+            check_server("", "tok", " "),
+            vec![
+                ConfigError::Missing {
+                    field: "server.url".to_string()
+                },
+                ConfigError::Missing {
+                    field: "server.race_id".to_string()
+                },
+            ]
+        );
+        for url in [
+            "speedfog.example.com",
+            "https://",
+            "ftp://host",
+            "https:// host",
+        ] {
+            assert_eq!(
+                check_server(url, "tok", "race"),
+                vec![ConfigError::MalformedUrl {
+                    url: url.to_string()
+                }],
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_color() {
+        assert_eq!(check_color("overlay.text_color", "#FFaa00"), None);
+        assert_eq!(check_color("overlay.text_color", "ffaa00"), None);
+        for value in ["#FFF", "", "#GG0000", "#FFAA0011", "red"] {
+            assert!(
+                check_color("overlay.text_color", value).is_some(),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_keybindings() {
+        let config: toml::Table = toml::from_str(
+            "[keybindings]\ntoggle_ui = \"f9\"\ntoggle_map = \"f13\"\ncancel_afk = 6\n",
+        )
+        .unwrap();
+        let errors = check_keybindings(&config, |key| key.eq_ignore_ascii_case("f9"));
+        assert_eq!(errors.len(), 2);
+        assert!(errors.contains(&ConfigError::InvalidKeybinding {
+            action: "toggle_map".to_string(),
+            key: "f13".to_string()
+        }));
+        assert!(errors.contains(&ConfigError::InvalidKeybinding {
+            action: "cancel_afk".to_string(),
+            key: "6".to_string()
+        }));
+        assert!(check_keybindings(&toml::Table::new(), |_| false).is_empty());
+    }
+
+    #[test]
+    fn test_display_names_the_setting() {
+        let error = ConfigError::BadColor {
+            field: "overlay.border_color".to_string(),
+            value: "blue".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "overlay.border_color \"blue\" is not a color (expected \"#RRGGBB\")"
+        );
+    }
+}
@@ -0,0 +1,44 @@
+//! Game constants - memory offsets
+//!
+//! All magic numbers from Elden Ring that we need for tracking.
+
+// =============================================================================
+// MEMORY OFFSETS
+// =============================================================================
+
+/// Offset of PlayRegionId within CS::FieldArea structure
+pub const FIELD_AREA_PLAY_REGION_ID_OFFSET: usize = 0xE4;
+
+/// Invalid map_id value (during loading screens)
+pub const INVALID_MAP_ID: u32 = 0xFFFFFFFF;
+
+/// Offset of death_count in GameDataMan structure
+pub const GAMEDATAMAN_DEATH_COUNT_OFFSET: usize = 0x94;
+
+/// Offset of the in-game time (u32, milliseconds) in GameDataMan structure
+pub const GAMEDATAMAN_IGT_OFFSET: usize = 0xA0;
+
+/// Offset of the clear count (completed playthroughs, u32) in GameDataMan structure
+pub const GAMEDATAMAN_CLEAR_COUNT_OFFSET: usize = 0x120;
+
+/// Offset of the PlayerGameData pointer in GameDataMan structure
+pub const GAMEDATAMAN_PLAYER_GAME_DATA_OFFSET: usize = 0x8;
+
+/// Offset of the rune level (u32) in PlayerGameData
+pub const PLAYER_GAME_DATA_LEVEL_OFFSET: usize = 0x68;
+
+/// Offset of the runes held (u32) in PlayerGameData, right after the rune
+/// level. Not checked against a game build yet (only `{souls}` reads it).
+pub const PLAYER_GAME_DATA_RUNES_OFFSET: usize = 0x6C;
+
+/// Offset of the character name (16 UTF-16 code units) in PlayerGameData
+pub const PLAYER_GAME_DATA_NAME_OFFSET: usize = 0x9C;
+
+/// Offset of the current HP (u32) in PlayerGameData
+pub const PLAYER_GAME_DATA_HP_OFFSET: usize = 0x10;
+
+/// Offset of the starting class id (u8) in PlayerGameData
+pub const PLAYER_GAME_DATA_CLASS_OFFSET: usize = 0xBF;
+
+/// Offset of the active save slot (u32) in GameMan structure
+pub const GAMEMAN_SAVE_SLOT_OFFSET: usize = 0xAC0;
@@ -0,0 +1,102 @@
+//! Race start countdown
+//!
+//! The server may put the race start timestamp a few seconds after the
+//! organizer presses Start. The overlay counts down to it on the server's
+//! clock, so every player sees GO at the same moment.
+
+use serde::{Deserialize, Serialize};
+
+/// How long "GO!" stays on screen after the start
+pub const GO_DURATION_MS: i64 = 1500;
+
+/// `[countdown]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CountdownSettings {
+    /// Full-screen 3-2-1-GO before the race start
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Keep the participant list and ready count on screen while waiting for
+    /// the start, even with the leaderboard hidden
+    #[serde(default = "default_true")]
+    pub ready_room: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for CountdownSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ready_room: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountdownPhase {
+    /// Whole seconds left before the start, rounded up
+    Count(u32),
+    Go,
+}
+
+/// Countdown state at `server_now_ms`, None once GO has been shown
+/// (or when the start was missed, e.g. on reconnect)
+pub fn countdown_phase(started_at_ms: i64, server_now_ms: i64) -> Option<CountdownPhase> {
+    let remaining = started_at_ms - server_now_ms;
+    if remaining > 0 {
+        let secs = (remaining + 999) / 1000;
+        Some(CountdownPhase::Count(secs.min(u32::MAX as i64) as u32))
+    } else if -remaining < GO_DURATION_MS {
+        Some(CountdownPhase::Go)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_down_in_whole_seconds() {
+        let start = 100_000;
+        assert_eq!(
+            countdown_phase(start, start - 3000),
+            Some(CountdownPhase::Count(3))
+        );
+        assert_eq!(
+            countdown_phase(start, start - 2999),
+            Some(CountdownPhase::Count(3))
+        );
+        assert_eq!(
+            countdown_phase(start, start - 2000),
+            Some(CountdownPhase::Count(2))
+        );
+        assert_eq!(
+            countdown_phase(start, start - 1),
+            Some(CountdownPhase::Count(1))
+        );
+    }
+
+    #[test]
+    fn test_go_then_nothing() {
+        let start = 100_000;
+        assert_eq!(countdown_phase(start, start), Some(CountdownPhase::Go));
+        assert_eq!(
+            countdown_phase(start, start + GO_DURATION_MS - 1),
+            Some(CountdownPhase::Go)
+        );
+        assert_eq!(countdown_phase(start, start + GO_DURATION_MS), None);
+        // Joined long after the start
+        assert_eq!(countdown_phase(start, start + 600_000), None);
+    }
+
+    #[test]
+    fn test_settings_default() {
+        let settings: CountdownSettings = toml::from_str("ready_room = false").unwrap();
+        assert!(settings.enabled);
+        assert!(!settings.ready_room);
+    }
+}
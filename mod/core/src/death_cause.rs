@@ -0,0 +1,216 @@
+//! Death attribution
+//!
+//! The game only counts deaths. To tell a fall from a boss or a regular
+//! enemy, the tracker keeps a few seconds of history (player height, HP,
+//! boss health bar) and, when the death counter goes up, runs a summary of
+//! it through a classification table. It's a heuristic: a player knocked off
+//! a ledge by an enemy counts as a fall, a death with nothing recent in the
+//! history is `Unknown`.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// History kept before a death
+pub const WINDOW: Duration = Duration::from_secs(5);
+
+/// Height lost within the window that counts as a lethal fall. Fall damage
+/// starts around 8 m and kills from about 16 m without a fall damage buff.
+pub const FALL_HEIGHT_M: f32 = 14.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeathCause {
+    Fall,
+    Boss,
+    Enemy,
+    Unknown,
+}
+
+impl DeathCause {
+    pub fn label(self) -> &'static str {
+        match self {
+            DeathCause::Fall => "fall",
+            DeathCause::Boss => "boss",
+            DeathCause::Enemy => "enemy",
+            DeathCause::Unknown => "unknown",
+        }
+    }
+}
+
+/// What the history says about the seconds before a death
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DeathSample {
+    /// Highest point of the window minus the last height, same map only
+    pub height_lost_m: f32,
+    /// A boss health bar was on screen
+    pub boss_bar: bool,
+    /// HP went down at least once
+    pub took_damage: bool,
+}
+
+type Rule = (DeathCause, fn(&DeathSample) -> bool);
+
+/// Classification table, first match wins: a fall is checked before the
+/// boss bar since bosses can knock the player into a pit
+const RULES: &[Rule] = &[
+    (DeathCause::Fall, |s| s.height_lost_m >= FALL_HEIGHT_M),
+    (DeathCause::Boss, |s| s.boss_bar),
+    (DeathCause::Enemy, |s| s.took_damage),
+];
+
+pub fn classify(sample: &DeathSample) -> DeathCause {
+    RULES
+        .iter()
+        .find(|(_, matches)| matches(sample))
+        .map_or(DeathCause::Unknown, |&(cause, _)| cause)
+}
+
+/// One frame of history
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    pub map_id: u32,
+    pub height: f32,
+    pub hp: Option<u32>,
+    pub boss_bar: bool,
+}
+
+/// Rolling history and death counter
+#[derive(Debug, Default)]
+pub struct DeathWatch {
+    frames: VecDeque<(Instant, Frame)>,
+    deaths: Option<u32>,
+}
+
+impl DeathWatch {
+    /// Record a frame (position readable only)
+    pub fn record(&mut self, now: Instant, frame: Frame) {
+        while self
+            .frames
+            .front()
+            .is_some_and(|&(at, _)| now.duration_since(at) > WINDOW)
+        {
+            self.frames.pop_front();
+        }
+        self.frames.push_back((now, frame));
+    }
+
+    /// Cause of the death when the counter went up since the last call.
+    /// The first reading and decreases (save reload) only move the baseline.
+    pub fn observe_deaths(&mut self, deaths: u32) -> Option<DeathCause> {
+        let previous = self.deaths.replace(deaths)?;
+        if deaths <= previous {
+            return None;
+        }
+        let cause = classify(&self.sample());
+        self.frames.clear();
+        Some(cause)
+    }
+
+    /// Summary of the window
+    pub fn sample(&self) -> DeathSample {
+        let Some(&(_, last)) = self.frames.back() else {
+            return DeathSample::default();
+        };
+        let peak = self
+            .frames
+            .iter()
+            .filter(|(_, f)| f.map_id == last.map_id)
+            .map(|(_, f)| f.height)
+            .fold(last.height, f32::max);
+        let hp = self.frames.iter().filter_map(|(_, f)| f.hp);
+        DeathSample {
+            height_lost_m: peak - last.height,
+            boss_bar: self.frames.iter().any(|(_, f)| f.boss_bar),
+            took_damage: hp.clone().zip(hp.skip(1)).any(|(a, b)| b < a),
+        }
+    }
+
+    /// Forget history and baseline (new race)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(height: f32, hp: u32) -> Frame {
+        Frame {
+            map_id: 1,
+            height,
+            hp: Some(hp),
+            boss_bar: false,
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        let fall = DeathSample {
+            height_lost_m: 30.0,
+            boss_bar: true,
+            took_damage: true,
+        };
+        assert_eq!(classify(&fall), DeathCause::Fall);
+        let boss = DeathSample {
+            height_lost_m: 2.0,
+            ..fall
+        };
+        assert_eq!(classify(&boss), DeathCause::Boss);
+        let enemy = DeathSample {
+            boss_bar: false,
+            ..boss
+        };
+        assert_eq!(classify(&enemy), DeathCause::Enemy);
+        assert_eq!(classify(&DeathSample::default()), DeathCause::Unknown);
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut watch = DeathWatch::default();
+        let start = Instant::now();
+        watch.record(start, frame(50.0, 500));
+        watch.record(start + Duration::from_secs(1), frame(40.0, 500));
+        watch.record(start + Duration::from_secs(2), frame(20.0, 0));
+        let sample = watch.sample();
+        assert_eq!(sample.height_lost_m, 30.0);
+        assert!(sample.took_damage);
+        assert!(!sample.boss_bar);
+
+        // Other maps don't count towards the height (coordinates differ)
+        watch.record(
+            start + Duration::from_secs(3),
+            Frame {
+                map_id: 2,
+                ..frame(0.0, 0)
+            },
+        );
+        assert_eq!(watch.sample().height_lost_m, 0.0);
+    }
+
+    #[test]
+    fn test_window() {
+        let mut watch = DeathWatch::default();
+        let start = Instant::now();
+        watch.record(start, frame(100.0, 500));
+        watch.record(start + WINDOW + Duration::from_secs(1), frame(0.0, 500));
+        assert_eq!(watch.sample().height_lost_m, 0.0);
+    }
+
+    #[test]
+    fn test_observe_deaths() {
+        let mut watch = DeathWatch::default();
+        let start = Instant::now();
+        assert_eq!(watch.observe_deaths(3), None);
+        watch.record(start, frame(10.0, 500));
+        watch.record(start, frame(10.0, 200));
+        assert_eq!(watch.observe_deaths(4), Some(DeathCause::Enemy));
+        // History used up by the previous death
+        assert_eq!(watch.observe_deaths(5), Some(DeathCause::Unknown));
+        // Save reload: deaths going back only move the baseline
+        assert_eq!(watch.observe_deaths(2), None);
+        assert_eq!(watch.observe_deaths(2), None);
+    }
+}
@@ -0,0 +1,125 @@
+//! Zone detection tuning
+//!
+//! Timings and warp triggers used to detect zone changes. The defaults suit
+//! most setups; slow disks or laggy systems may need a longer warp timeout
+//! or reveal delay.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Accepted range for `warp_timeout_secs`
+const WARP_TIMEOUT_RANGE: (f32, f32) = (1.0, 60.0);
+
+/// Accepted range for `zone_reveal_delay_secs`
+const ZONE_REVEAL_DELAY_RANGE: (f32, f32) = (0.0, 10.0);
+
+/// `[detection]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectionSettings {
+    /// Time a captured warp destination stays valid while waiting for the
+    /// loading screen. A warp cancelled before loading is dropped after it.
+    #[serde(default = "default_warp_timeout_secs")]
+    pub warp_timeout_secs: f32,
+    /// Capture the grace of fast travels (map menu warps)
+    #[serde(default = "default_true")]
+    pub enable_vanilla_warp_trigger: bool,
+    /// Delay after a loading screen before the new zone is shown, covering
+    /// the fade-in
+    #[serde(default = "default_zone_reveal_delay_secs")]
+    pub zone_reveal_delay_secs: f32,
+    /// Write detection incidents (expired warps, dropped discoveries) next
+    /// to the log as they happen (`core::detection_health`)
+    #[serde(default)]
+    pub incident_report: bool,
+}
+
+fn default_warp_timeout_secs() -> f32 {
+    10.0
+}
+
+fn default_zone_reveal_delay_secs() -> f32 {
+    2.0
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for DetectionSettings {
+    fn default() -> Self {
+        Self {
+            warp_timeout_secs: default_warp_timeout_secs(),
+            enable_vanilla_warp_trigger: true,
+            zone_reveal_delay_secs: default_zone_reveal_delay_secs(),
+            incident_report: false,
+        }
+    }
+}
+
+/// Seconds to a Duration, clamped to `range` (NaN falls back to `fallback`)
+fn clamped_secs(secs: f32, range: (f32, f32), fallback: f32) -> Duration {
+    let secs = if secs.is_nan() { fallback } else { secs };
+    Duration::from_secs_f32(secs.clamp(range.0, range.1))
+}
+
+impl DetectionSettings {
+    pub fn warp_timeout(&self) -> Duration {
+        clamped_secs(
+            self.warp_timeout_secs,
+            WARP_TIMEOUT_RANGE,
+            default_warp_timeout_secs(),
+        )
+    }
+
+    pub fn zone_reveal_delay(&self) -> Duration {
+        clamped_secs(
+            self.zone_reveal_delay_secs,
+            ZONE_REVEAL_DELAY_RANGE,
+            default_zone_reveal_delay_secs(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let settings: DetectionSettings = toml::from_str("").unwrap();
+        assert_eq!(settings, DetectionSettings::default());
+        assert_eq!(settings.warp_timeout(), Duration::from_secs(10));
+        assert_eq!(settings.zone_reveal_delay(), Duration::from_secs(2));
+        assert!(settings.enable_vanilla_warp_trigger);
+        assert!(!settings.incident_report);
+    }
+
+    #[test]
+    fn test_partial_section() {
+        let settings: DetectionSettings =
+            toml::from_str("zone_reveal_delay_secs = 3.5\nenable_vanilla_warp_trigger = false")
+                .unwrap();
+        assert_eq!(settings.zone_reveal_delay(), Duration::from_millis(3500));
+        assert!(!settings.enable_vanilla_warp_trigger);
+        assert_eq!(settings.warp_timeout(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_out_of_range_values_clamped() {
+        let settings = DetectionSettings {
+            warp_timeout_secs: 0.0,
+            zone_reveal_delay_secs: -1.0,
+            ..Default::default()
+        };
+        assert_eq!(settings.warp_timeout(), Duration::from_secs(1));
+        assert_eq!(settings.zone_reveal_delay(), Duration::ZERO);
+
+        let settings = DetectionSettings {
+            warp_timeout_secs: 1000.0,
+            zone_reveal_delay_secs: f32::NAN,
+            ..Default::default()
+        };
+        assert_eq!(settings.warp_timeout(), Duration::from_secs(60));
+        assert_eq!(settings.zone_reveal_delay(), Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,274 @@
+//! Detection health
+//!
+//! Zone detection fails quietly: a captured warp that expires without a
+//! loading screen leaves the next zone to a zone_query, and fog gate flags
+//! dropped at a loading exit (offline, race not running) never reach the
+//! server. Each incident is kept with the frame it happened on (IGT, map,
+//! position, zone, connection) so reports from the wild show systematic
+//! misses; the debug panel shows the session's counts and the incidents can
+//! be written next to the log, to be sent along with it.
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::time::Instant;
+
+use super::post_race::file_safe_id;
+
+/// Incidents kept in memory, oldest dropped first (the counts keep going)
+pub const MAX_INCIDENTS: usize = 200;
+
+/// Why a discovered fog gate flag wasn't sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterReason {
+    /// Not connected to the server at the loading exit
+    Offline,
+    /// The race wasn't running (setup, finished for everyone)
+    RaceNotRunning,
+    /// The player had already finished
+    Finished,
+}
+
+impl FilterReason {
+    pub fn label(self) -> &'static str {
+        match self {
+            FilterReason::Offline => "offline",
+            FilterReason::RaceNotRunning => "race_not_running",
+            FilterReason::Finished => "finished",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IncidentKind {
+    /// A captured warp got no loading screen within the warp timeout
+    WarpExpired {
+        /// Captured fast travel grace
+        grace_entity_id: u32,
+    },
+    /// A discovered fog gate flag was dropped instead of sent
+    DiscoveryFiltered { flag_id: u32, reason: FilterReason },
+}
+
+impl IncidentKind {
+    pub fn describe(&self) -> String {
+        match self {
+            IncidentKind::WarpExpired { grace_entity_id } => {
+                format!("warp_expired grace={}", grace_entity_id)
+            }
+            IncidentKind::DiscoveryFiltered { flag_id, reason } => {
+                format!(
+                    "discovery_filtered flag={} reason={}",
+                    flag_id,
+                    reason.label()
+                )
+            }
+        }
+    }
+}
+
+/// What the tracker knew on the frame of an incident
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameContext {
+    pub igt_ms: Option<u32>,
+    pub map_id: Option<String>,
+    pub position: Option<[f32; 3]>,
+    /// Node id of the displayed zone
+    pub zone: Option<String>,
+    pub connected: bool,
+    pub race_status: Option<String>,
+}
+
+impl FrameContext {
+    fn describe(&self) -> String {
+        let or_dash = |s: Option<&str>| s.unwrap_or("-").to_string();
+        let position = self.position.map_or_else(
+            || "-".to_string(),
+            |[x, y, z]| format!("({:.1}, {:.1}, {:.1})", x, y, z),
+        );
+        format!(
+            "igt={} map={} pos={} zone={} connected={} race={}",
+            self.igt_ms
+                .map_or_else(|| "-".to_string(), |igt| igt.to_string()),
+            or_dash(self.map_id.as_deref()),
+            position,
+            or_dash(self.zone.as_deref()),
+            self.connected,
+            or_dash(self.race_status.as_deref()),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Incident {
+    /// Milliseconds since the first incident of the session
+    pub elapsed_ms: u64,
+    pub kind: IncidentKind,
+    pub context: FrameContext,
+}
+
+impl Incident {
+    /// One line: time, what happened, then the frame context
+    pub fn describe(&self) -> String {
+        format!(
+            "[{:>9.3}s] {} {}",
+            self.elapsed_ms as f64 / 1000.0,
+            self.kind.describe(),
+            self.context.describe()
+        )
+    }
+}
+
+/// Detection incidents of the session
+#[derive(Debug, Default)]
+pub struct DetectionHealth {
+    started: Option<Instant>,
+    incidents: VecDeque<Incident>,
+    filtered: u32,
+    expired: u32,
+}
+
+impl DetectionHealth {
+    pub fn record(&mut self, now: Instant, kind: IncidentKind, context: FrameContext) -> &Incident {
+        match kind {
+            IncidentKind::WarpExpired { .. } => self.expired += 1,
+            IncidentKind::DiscoveryFiltered { .. } => self.filtered += 1,
+        }
+        let started = *self.started.get_or_insert(now);
+        if self.incidents.len() == MAX_INCIDENTS {
+            self.incidents.pop_front();
+        }
+        self.incidents.push_back(Incident {
+            elapsed_ms: now.saturating_duration_since(started).as_millis() as u64,
+            kind,
+            context,
+        });
+        self.incidents.back().expect("just pushed")
+    }
+
+    /// Discoveries dropped this session
+    pub fn filtered(&self) -> u32 {
+        self.filtered
+    }
+
+    /// Captured warps expired this session
+    pub fn expired(&self) -> u32 {
+        self.expired
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.filtered == 0 && self.expired == 0
+    }
+
+    pub fn incidents(&self) -> impl Iterator<Item = &Incident> {
+        self.incidents.iter()
+    }
+
+    /// Counts, then one line per incident kept
+    pub fn export(&self) -> String {
+        let mut out = format!(
+            "SpeedFog detection incidents: {} filtered, {} expired\n",
+            self.filtered, self.expired
+        );
+        let dropped = (self.filtered + self.expired) as usize - self.incidents.len();
+        if dropped > 0 {
+            let _ = writeln!(out, "({} older incidents dropped)", dropped);
+        }
+        for incident in &self.incidents {
+            let _ = writeln!(out, "{}", incident.describe());
+        }
+        out
+    }
+
+    /// File written next to the DLL (and its log)
+    pub fn file_name(race_id: &str) -> String {
+        format!("speedfog_detection_{}.txt", file_safe_id(race_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn context() -> FrameContext {
+        FrameContext {
+            igt_ms: Some(754_000),
+            map_id: Some("m10_00_00_00".to_string()),
+            position: Some([1.0, 2.5, -3.0]),
+            zone: Some("stormveil".to_string()),
+            connected: false,
+            race_status: Some("running".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_counts_by_kind() {
+        let t0 = Instant::now();
+        let mut health = DetectionHealth::default();
+        assert!(health.is_healthy());
+        let filtered = IncidentKind::DiscoveryFiltered {
+            flag_id: 1040,
+            reason: FilterReason::Offline,
+        };
+        health.record(t0, filtered.clone(), context());
+        health.record(t0 + Duration::from_secs(2), filtered, context());
+        let expired = IncidentKind::WarpExpired {
+            grace_entity_id: 10001950,
+        };
+        let incident = health.record(t0 + Duration::from_millis(3500), expired, context());
+        assert_eq!(incident.elapsed_ms, 3500);
+        assert_eq!((health.filtered(), health.expired()), (2, 1));
+        assert!(!health.is_healthy());
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut health = DetectionHealth::default();
+        let incident = health.record(
+            Instant::now(),
+            IncidentKind::DiscoveryFiltered {
+                flag_id: 1040,
+                reason: FilterReason::RaceNotRunning,
+            },
+            context(),
+        );
+        assert_eq!(
+            incident.describe(),
+            "[    0.000s] discovery_filtered flag=1040 reason=race_not_running \
+             igt=754000 map=m10_00_00_00 pos=(1.0, 2.5, -3.0) zone=stormveil \
+             connected=false race=running"
+        );
+        let incident = health.record(
+            Instant::now(),
+            IncidentKind::WarpExpired {
+                grace_entity_id: 10001950,
+            },
+            FrameContext::default(),
+        );
+        assert!(incident.describe().ends_with(
+            "warp_expired grace=10001950 \
+             igt=- map=- pos=- zone=- connected=false race=-"
+        ));
+    }
+
+    #[test]
+    fn test_buffer_bounded() {
+        let t0 = Instant::now();
+        let mut health = DetectionHealth::default();
+        for flag_id in 0..MAX_INCIDENTS as u32 + 5 {
+            let kind = IncidentKind::DiscoveryFiltered {
+                flag_id,
+                reason: FilterReason::Offline,
+            };
+            health.record(t0, kind, FrameContext::default());
+        }
+        assert_eq!(health.incidents().count(), MAX_INCIDENTS);
+        assert_eq!(health.filtered(), MAX_INCIDENTS as u32 + 5);
+        let export = health.export();
+        assert!(export.starts_with("SpeedFog detection incidents: 205 filtered, 0 expired\n"));
+        assert!(export.contains("(5 older incidents dropped)"));
+        assert!(!export.contains("flag=4 "));
+        assert!(export.contains("flag=5 "));
+    }
+}
@@ -0,0 +1,261 @@
+//! Delivery tracking for discoveries
+//!
+//! Event flags (fog gate traversals, finish) are sent once and never retried
+//! if the WebSocket drops silently. From protocol 3 on, each one carries a
+//! correlation id (`seq`) the server acknowledges with `event_flag_ack`, and
+//! processes only once. Unacknowledged flags are resent on a timer, and
+//! acknowledged ones are never sent again.
+//!
+//! `seq` changes when the game restarts. From protocol 23 on, each flag also
+//! carries an idempotency key built from the race, the character and the flag,
+//! so the server still recognizes a flag sent again by a restarted game.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use super::protocol::CharacterFingerprint;
+
+/// Delay before an unacknowledged event flag is sent again
+pub const RESEND_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Event flag waiting for its acknowledgement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingFlag {
+    pub seq: u64,
+    pub flag_id: u32,
+    pub igt_ms: u32,
+    sent_at: Instant,
+}
+
+/// Unacknowledged event flags, keyed by correlation id
+#[derive(Debug)]
+pub struct DiscoveryOutbox {
+    next_seq: u64,
+    pending: Vec<PendingFlag>,
+    /// Flags the server acknowledged
+    acked: HashSet<u32>,
+}
+
+impl DiscoveryOutbox {
+    /// `session_base_ms` keeps ids unique across game restarts (the server
+    /// remembers them for the whole race): use the start time in ms.
+    pub fn new(session_base_ms: u64) -> Self {
+        Self {
+            next_seq: session_base_ms * 1000,
+            pending: Vec::new(),
+            acked: HashSet::new(),
+        }
+    }
+
+    /// Track a flag being sent and return its correlation id.
+    /// A flag still waiting for its ack keeps its id, so the server dedupes it.
+    pub fn track(&mut self, flag_id: u32, igt_ms: u32, now: Instant) -> u64 {
+        if let Some(p) = self.pending.iter_mut().find(|p| p.flag_id == flag_id) {
+            p.sent_at = now;
+            return p.seq;
+        }
+        self.next_seq += 1;
+        self.pending.push(PendingFlag {
+            seq: self.next_seq,
+            flag_id,
+            igt_ms,
+            sent_at: now,
+        });
+        self.next_seq
+    }
+
+    /// Server acknowledged `seq`. Returns the flag acknowledged, None for
+    /// unknown or repeated acks.
+    pub fn ack(&mut self, seq: u64) -> Option<u32> {
+        let index = self.pending.iter().position(|p| p.seq == seq)?;
+        let flag = self.pending.remove(index);
+        self.acked.insert(flag.flag_id);
+        Some(flag.flag_id)
+    }
+
+    /// The server already acknowledged `flag_id`: sending it again is useless
+    pub fn is_acked(&self, flag_id: u32) -> bool {
+        self.acked.contains(&flag_id)
+    }
+
+    /// Flags unacknowledged for RESEND_INTERVAL, marked as sent again
+    pub fn due(&mut self, now: Instant) -> Vec<PendingFlag> {
+        self.pending
+            .iter_mut()
+            .filter(|p| now.duration_since(p.sent_at) >= RESEND_INTERVAL)
+            .map(|p| {
+                p.sent_at = now;
+                p.clone()
+            })
+            .collect()
+    }
+
+    /// Every unacknowledged flag, marked as sent again (the game is closing:
+    /// their timer would never run out)
+    pub fn flush(&mut self, now: Instant) -> Vec<PendingFlag> {
+        for p in &mut self.pending {
+            p.sent_at = now;
+        }
+        self.pending.clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Idempotency key of an event flag (protocol 23+): the same for every send
+/// of the flag by the same character in the same race, across reconnects and
+/// game restarts. The character's level is left out, it changes during the race.
+pub fn idempotency_key(
+    race_id: &str,
+    character: Option<&CharacterFingerprint>,
+    flag_id: u32,
+) -> String {
+    let character = character.map_or(0, |c| {
+        let mut hash = Fnv1a::default();
+        hash.write(&c.slot.unwrap_or(u32::MAX).to_le_bytes());
+        hash.write(&[c.class_id]);
+        hash.write(c.name.as_bytes());
+        hash.0
+    });
+    format!("{}:{:016x}:{}", race_id, character, flag_id)
+}
+
+/// FNV-1a, stable across builds unlike `DefaultHasher`
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_and_ack() {
+        let now = Instant::now();
+        let mut outbox = DiscoveryOutbox::new(1);
+        let a = outbox.track(100, 5000, now);
+        let b = outbox.track(200, 6000, now);
+        assert_ne!(a, b);
+        assert_eq!(outbox.len(), 2);
+
+        assert_eq!(outbox.ack(a), Some(100));
+        assert_eq!(outbox.ack(a), None); // repeated ack
+        assert_eq!(outbox.ack(12345), None); // unknown
+        assert_eq!(outbox.len(), 1);
+        assert_eq!(outbox.ack(b), Some(200));
+        assert!(outbox.is_empty());
+        assert!(outbox.is_acked(100));
+        assert!(outbox.is_acked(200));
+        assert!(!outbox.is_acked(300));
+    }
+
+    #[test]
+    fn test_sequence_starts_from_session_base() {
+        let now = Instant::now();
+        let mut first = DiscoveryOutbox::new(1_700_000_000_000);
+        let mut later = DiscoveryOutbox::new(1_700_000_060_000);
+        assert!(later.track(1, 0, now) > first.track(1, 0, now));
+    }
+
+    #[test]
+    fn test_retrack_keeps_seq() {
+        let now = Instant::now();
+        let mut outbox = DiscoveryOutbox::new(1);
+        let seq = outbox.track(100, 5000, now);
+        // Re-queued after a reconnect: same id so the server can dedupe
+        assert_eq!(outbox.track(100, 5000, now), seq);
+        assert_eq!(outbox.len(), 1);
+    }
+
+    #[test]
+    fn test_due_after_resend_interval() {
+        let start = Instant::now();
+        let mut outbox = DiscoveryOutbox::new(1);
+        let seq = outbox.track(100, 5000, start);
+
+        assert!(outbox.due(start + Duration::from_secs(1)).is_empty());
+
+        let due = outbox.due(start + RESEND_INTERVAL);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].seq, seq);
+        assert_eq!(due[0].flag_id, 100);
+        assert_eq!(due[0].igt_ms, 5000);
+
+        // Timer restarts after a resend
+        assert!(outbox
+            .due(start + RESEND_INTERVAL + Duration::from_secs(1))
+            .is_empty());
+        assert_eq!(outbox.due(start + RESEND_INTERVAL * 2).len(), 1);
+
+        outbox.ack(seq);
+        assert!(outbox.due(start + RESEND_INTERVAL * 10).is_empty());
+    }
+
+    #[test]
+    fn test_flush_ignores_timer() {
+        let start = Instant::now();
+        let mut outbox = DiscoveryOutbox::new(1);
+        outbox.track(100, 5000, start);
+        outbox.track(200, 6000, start);
+        let flushed = outbox.flush(start + Duration::from_secs(1));
+        assert_eq!(flushed.len(), 2);
+        // Flushed flags wait a full interval again
+        assert!(outbox.due(start + RESEND_INTERVAL).is_empty());
+    }
+
+    fn character(name: &str, level: u32) -> CharacterFingerprint {
+        CharacterFingerprint {
+            slot: Some(2),
+            name: name.to_string(),
+            level,
+            class_id: 9,
+        }
+    }
+
+    #[test]
+    fn test_idempotency_key_stable() {
+        let key = idempotency_key("race-1", Some(&character("Tarnished", 1)), 100);
+        // Fixed value: keys must match between mod builds
+        assert_eq!(key, "race-1:ac3e35353be515b6:100");
+        // Leveling up keeps the key
+        assert_eq!(
+            key,
+            idempotency_key("race-1", Some(&character("Tarnished", 40)), 100)
+        );
+    }
+
+    #[test]
+    fn test_idempotency_key_differs() {
+        let tarnished = character("Tarnished", 1);
+        let key = idempotency_key("race-1", Some(&tarnished), 100);
+        assert_ne!(key, idempotency_key("race-1", Some(&tarnished), 101));
+        assert_ne!(key, idempotency_key("race-2", Some(&tarnished), 100));
+        assert_ne!(
+            key,
+            idempotency_key("race-1", Some(&character("Other", 1)), 100)
+        );
+        assert_eq!(
+            idempotency_key("race-1", None, 100),
+            "race-1:0000000000000000:100"
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! IGT drift against the wall clock
+//!
+//! While playing, IGT advances at the same rate as real time. A game speed
+//! hack (Cheat Engine speedhack, a patched frame limiter) makes it run fast;
+//! heavy frame drops, where the game caps the time counted per frame, make
+//! it run slow. Both skew race times, so IGT progression is compared with a
+//! monotonic high-resolution clock over a sliding window, and a window whose
+//! rate strays beyond the tolerance is reported to the server as an
+//! integrity signal. Loading screens and menu pauses stop IGT: the window
+//! restarts whenever it stalls, so they never count as drift.
+
+use serde::{Deserialize, Serialize};
+
+/// IGT stalls longer than this are a pause (loading screen, quit-out), not
+/// frame jitter (µs)
+const STALL_US: u64 = 250_000;
+
+/// `[drift]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriftSettings {
+    /// Report drift anomalies to the server
+    #[serde(default = "default_true")]
+    pub report: bool,
+    /// Largest IGT/wall clock rate difference considered normal (percent)
+    #[serde(default = "default_tolerance_percent")]
+    pub tolerance_percent: f32,
+    /// Length of the comparison window (seconds of uninterrupted IGT)
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u32,
+}
+
+fn default_true() -> bool {
+    true
+}
+fn default_tolerance_percent() -> f32 {
+    5.0
+}
+fn default_window_secs() -> u32 {
+    30
+}
+
+impl Default for DriftSettings {
+    fn default() -> Self {
+        Self {
+            report: true,
+            tolerance_percent: default_tolerance_percent(),
+            window_secs: default_window_secs(),
+        }
+    }
+}
+
+/// A window where IGT and the wall clock disagreed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DriftAnomaly {
+    /// IGT at the end of the window
+    pub igt_ms: u32,
+    /// IGT elapsed over the window
+    pub igt_delta_ms: u32,
+    /// Wall time elapsed over the window
+    pub wall_delta_ms: u32,
+}
+
+impl DriftAnomaly {
+    /// IGT rate relative to real time (1.0 = in sync, 2.0 = twice as fast)
+    pub fn ratio(&self) -> f64 {
+        self.igt_delta_ms as f64 / self.wall_delta_ms.max(1) as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    wall_us: u64,
+    igt_ms: u32,
+}
+
+/// Compares IGT progression with the wall clock, fed once per frame
+#[derive(Debug, Default)]
+pub struct DriftMonitor {
+    /// Start of the current window, None until IGT ticks again
+    window: Option<Sample>,
+    /// Last frame IGT changed on
+    last: Option<Sample>,
+}
+
+impl DriftMonitor {
+    /// Feed the monotonic clock (µs) and the IGT read this frame (None while
+    /// unreadable). Returns the anomaly when a window ends out of tolerance.
+    pub fn observe(
+        &mut self,
+        now_us: u64,
+        igt_ms: Option<u32>,
+        settings: &DriftSettings,
+    ) -> Option<DriftAnomaly> {
+        let Some(igt_ms) = igt_ms.filter(|&igt| igt > 0) else {
+            self.reset();
+            return None;
+        };
+        let sample = Sample {
+            wall_us: now_us,
+            igt_ms,
+        };
+        match self.last {
+            Some(last) if igt_ms == last.igt_ms => {
+                // IGT stopped: restart the window once it ticks again
+                if now_us.saturating_sub(last.wall_us) >= STALL_US {
+                    self.window = None;
+                }
+                return None;
+            }
+            // Ticking
+            Some(last)
+                if igt_ms > last.igt_ms && now_us.saturating_sub(last.wall_us) < STALL_US => {}
+            // First sample, reload (IGT rewound) or first tick after a stall
+            _ => self.window = None,
+        }
+        self.last = Some(sample);
+
+        let start = *self.window.get_or_insert(sample);
+        let wall_us = now_us.saturating_sub(start.wall_us);
+        if wall_us < u64::from(settings.window_secs.max(1)) * 1_000_000 {
+            return None;
+        }
+        self.window = Some(sample);
+        let anomaly = DriftAnomaly {
+            igt_ms,
+            igt_delta_ms: igt_ms - start.igt_ms,
+            wall_delta_ms: (wall_us / 1000).min(u32::MAX as u64) as u32,
+        };
+        let drift_percent = (anomaly.ratio() - 1.0).abs() * 100.0;
+        (drift_percent > settings.tolerance_percent as f64).then_some(anomaly)
+    }
+
+    /// Forget the current window (new seed, IGT unreadable)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME_US: u64 = 16_667;
+
+    /// Play `secs` seconds at 60 fps with IGT advancing `rate` times as fast
+    /// as real time. Returns the anomalies reported.
+    fn play(
+        monitor: &mut DriftMonitor,
+        wall_us: &mut u64,
+        igt_ms: &mut f64,
+        secs: u64,
+        rate: f64,
+    ) -> Vec<DriftAnomaly> {
+        let settings = DriftSettings::default();
+        let mut anomalies = Vec::new();
+        for _ in 0..secs * 60 {
+            *wall_us += FRAME_US;
+            *igt_ms += FRAME_US as f64 / 1000.0 * rate;
+            anomalies.extend(monitor.observe(*wall_us, Some(*igt_ms as u32), &settings));
+        }
+        anomalies
+    }
+
+    #[test]
+    fn test_in_sync_is_not_reported() {
+        let mut monitor = DriftMonitor::default();
+        let (mut wall, mut igt) = (0, 60_000.0);
+        assert!(play(&mut monitor, &mut wall, &mut igt, 120, 1.0).is_empty());
+        // Within the tolerance
+        assert!(play(&mut monitor, &mut wall, &mut igt, 120, 1.03).is_empty());
+    }
+
+    #[test]
+    fn test_speed_hack_reported() {
+        let mut monitor = DriftMonitor::default();
+        let (mut wall, mut igt) = (0, 60_000.0);
+        let anomalies = play(&mut monitor, &mut wall, &mut igt, 95, 1.5);
+        assert_eq!(anomalies.len(), 3);
+        let anomaly = anomalies[0];
+        assert!((29_900..=30_100).contains(&anomaly.wall_delta_ms));
+        assert!((anomaly.ratio() - 1.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_slow_igt_reported() {
+        let mut monitor = DriftMonitor::default();
+        let (mut wall, mut igt) = (0, 60_000.0);
+        let anomalies = play(&mut monitor, &mut wall, &mut igt, 31, 0.8);
+        assert_eq!(anomalies.len(), 1);
+        assert!(anomalies[0].ratio() < 0.81);
+    }
+
+    #[test]
+    fn test_pauses_restart_the_window() {
+        let settings = DriftSettings::default();
+        let mut monitor = DriftMonitor::default();
+        let (mut wall, mut igt) = (0, 60_000.0);
+        for _ in 0..10 {
+            assert!(play(&mut monitor, &mut wall, &mut igt, 20, 1.0).is_empty());
+            // 10 s loading screen: IGT stopped, then unreadable
+            for frame in 0..600 {
+                wall += FRAME_US;
+                let read = (frame < 300).then_some(igt as u32);
+                assert_eq!(monitor.observe(wall, read, &settings), None);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reload_restarts_the_window() {
+        let settings = DriftSettings::default();
+        let mut monitor = DriftMonitor::default();
+        let (mut wall, mut igt) = (0, 600_000.0);
+        assert!(play(&mut monitor, &mut wall, &mut igt, 20, 1.0).is_empty());
+        // Loaded an older save: IGT went back 5 minutes in one frame
+        igt -= 300_000.0;
+        wall += FRAME_US;
+        assert_eq!(monitor.observe(wall, Some(igt as u32), &settings), None);
+        assert!(play(&mut monitor, &mut wall, &mut igt, 20, 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings: DriftSettings = toml::from_str("").unwrap();
+        assert_eq!(settings, DriftSettings::default());
+        let settings: DriftSettings = toml::from_str("tolerance_percent = 2.5").unwrap();
+        assert!(settings.report);
+        assert_eq!(settings.tolerance_percent, 2.5);
+        assert_eq!(settings.window_secs, 30);
+    }
+}
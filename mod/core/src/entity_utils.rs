@@ -0,0 +1,122 @@
+//! Entity ID classification
+//!
+//! The warp hooks see entity IDs: the grace of a fast travel, the spawn point
+//! or character of a scripted warp. Map entities (graces, regions, placed
+//! enemies) carry their map in the ID: `AABBNNNN` for legacy dungeons and
+//! small maps, `10XXYYNNNN`-style for overworld tiles, always 8+ digits. The
+//! characters the game spawns at runtime use the low `1XXXX` slots instead:
+//! the player, other players of the session, Torrent and summons.
+//!
+//! Mounting or dismounting Torrent and spirit ash or NPC summons run the same
+//! warp code as the player, so only warps tied to the player or to a map
+//! entity count as zone change triggers.
+
+use std::ops::RangeInclusive;
+
+/// The local player
+pub const PLAYER: u32 = 10000;
+
+/// Other players in the session (co-op phantoms, invaders)
+const NET_PLAYERS: RangeInclusive<u32> = 10001..=10005;
+
+/// Torrent, spawned and despawned with every mount and dismount
+const TORRENT: RangeInclusive<u32> = 10006..=10009;
+
+/// Spirit ashes and NPC summons
+const SUMMONS: RangeInclusive<u32> = 10010..=19999;
+
+/// Smallest map entity ID (area 10, block 00)
+const MAP_ENTITY_MIN: u32 = 10_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityKind {
+    /// No entity (0)
+    None,
+    Player,
+    NetPlayer,
+    Torrent,
+    Summon,
+    /// Entity placed in a map: grace, region, asset, enemy
+    Map,
+    /// Any other ID, not tied to the player
+    Other,
+}
+
+pub fn classify(entity_id: u32) -> EntityKind {
+    match entity_id {
+        0 => EntityKind::None,
+        PLAYER => EntityKind::Player,
+        id if NET_PLAYERS.contains(&id) => EntityKind::NetPlayer,
+        id if TORRENT.contains(&id) => EntityKind::Torrent,
+        id if SUMMONS.contains(&id) => EntityKind::Summon,
+        id if id >= MAP_ENTITY_MIN => EntityKind::Map,
+        _ => EntityKind::Other,
+    }
+}
+
+/// Whether a scripted warp tied to this entity moves the player (no entity,
+/// the player, or a spawn point in a map)
+pub fn is_player_warp(entity_id: u32) -> bool {
+    matches!(
+        classify(entity_id),
+        EntityKind::None | EntityKind::Player | EntityKind::Map
+    )
+}
+
+/// Whether a fast travel destination is a grace (always a map entity)
+pub fn is_grace(entity_id: u32) -> bool {
+    classify(entity_id) == EntityKind::Map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_player() {
+        assert_eq!(classify(0), EntityKind::None);
+        assert_eq!(classify(PLAYER), EntityKind::Player);
+        assert!(is_player_warp(0));
+        assert!(is_player_warp(PLAYER));
+    }
+
+    #[test]
+    fn test_runtime_characters() {
+        for id in [10001, 10005] {
+            assert_eq!(classify(id), EntityKind::NetPlayer);
+            assert!(!is_player_warp(id));
+        }
+        for id in [10006, 10009] {
+            assert_eq!(classify(id), EntityKind::Torrent);
+            assert!(!is_player_warp(id));
+        }
+        for id in [10010, 12345, 19999] {
+            assert_eq!(classify(id), EntityKind::Summon);
+            assert!(!is_player_warp(id));
+        }
+    }
+
+    #[test]
+    fn test_map_entities() {
+        // Legacy dungeon grace (Stormveil), overworld grace (Church of Elleh)
+        for id in [10001950, 1042361950, MAP_ENTITY_MIN, u32::MAX] {
+            assert_eq!(classify(id), EntityKind::Map);
+            assert!(is_player_warp(id));
+            assert!(is_grace(id));
+        }
+        assert_eq!(classify(MAP_ENTITY_MIN - 1), EntityKind::Other);
+    }
+
+    #[test]
+    fn test_other_ids() {
+        for id in [1, 1000, 9999, 20000, 9_999_999] {
+            assert_eq!(classify(id), EntityKind::Other);
+            assert!(!is_player_warp(id));
+            assert!(!is_grace(id));
+        }
+        // Fast travel param 0 (grace_entity_id - 1000) is not a grace
+        assert!(!is_grace(1000));
+        assert!(!is_grace(0));
+        assert!(!is_grace(PLAYER));
+    }
+}
@@ -0,0 +1,120 @@
+//! Environment safety check
+//!
+//! The mod must never hook a game protected by Easy Anti-Cheat: injecting into
+//! an EAC session can get the player banned. EAC also gates online play (the
+//! servers reject unprotected clients), so an EAC-free session is offline.
+//! The check runs before any hook on what the DLL gathers about its own
+//! process: the parent launcher and the modules loaded in the game. Other
+//! processes are not looked at, as the EAC service lingers after any EOS game
+//! has run and says nothing about this session.
+
+use std::fmt;
+
+/// Launcher that starts Elden Ring under EAC
+pub const EAC_LAUNCHER: &str = "start_protected_game.exe";
+
+/// Name prefix of the EAC client modules loaded in a protected game
+/// (`EasyAntiCheat_EOS_x64.dll`, `EasyAntiCheat_x64.dll`)
+pub const EAC_MODULE_PREFIX: &str = "easyanticheat";
+
+/// Why the mod refuses to start
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvironmentViolation {
+    /// The game was started through the EAC launcher
+    LaunchedWithEac,
+    /// An EAC client module is loaded in the game
+    EacLoaded(String),
+}
+
+impl fmt::Display for EnvironmentViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvironmentViolation::LaunchedWithEac => write!(
+                f,
+                "Elden Ring was started with Easy Anti-Cheat ({}).",
+                EAC_LAUNCHER
+            ),
+            EnvironmentViolation::EacLoaded(name) => {
+                write!(f, "Easy Anti-Cheat is loaded in the game ({}).", name)
+            }
+        }
+    }
+}
+
+impl EnvironmentViolation {
+    /// Message shown to the player before the mod unloads
+    pub fn user_message(&self) -> String {
+        format!(
+            "{}\n\nSpeedFog Racing did not load to keep your account safe. \
+             Launch the game offline through the SpeedFog launcher (without EAC) to race.",
+            self
+        )
+    }
+}
+
+/// Check the game's parent process and its loaded modules for EAC
+pub fn check_game<S: AsRef<str>>(
+    parent: Option<&str>,
+    modules: &[S],
+) -> Result<(), EnvironmentViolation> {
+    if parent.is_some_and(|p| p.eq_ignore_ascii_case(EAC_LAUNCHER)) {
+        return Err(EnvironmentViolation::LaunchedWithEac);
+    }
+    let eac = modules.iter().map(AsRef::as_ref).find(|name| {
+        name.get(..EAC_MODULE_PREFIX.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(EAC_MODULE_PREFIX))
+    });
+    match eac {
+        Some(name) => Err(EnvironmentViolation::EacLoaded(name.to_string())),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_environment() {
+        let modules = ["eldenring.exe", "ntdll.dll", "speedfog_race_mod.dll"];
+        assert_eq!(
+            check_game(Some("modengine2_launcher.exe"), &modules),
+            Ok(())
+        );
+        assert_eq!(check_game::<&str>(None, &[]), Ok(()));
+    }
+
+    #[test]
+    fn test_eac_launcher_parent() {
+        assert_eq!(
+            check_game::<&str>(Some("Start_Protected_Game.exe"), &[]),
+            Err(EnvironmentViolation::LaunchedWithEac)
+        );
+    }
+
+    #[test]
+    fn test_eac_module_loaded() {
+        let modules = ["eldenring.exe", "easyanticheat_eos_x64.dll"];
+        assert_eq!(
+            check_game(None, &modules),
+            Err(EnvironmentViolation::EacLoaded(
+                "easyanticheat_eos_x64.dll".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_eac_service_elsewhere_ignored() {
+        // The service process lingers after other EOS games; only the
+        // game's own modules count
+        let modules = ["eldenring.exe", "EOSSDK-Win64-Shipping.dll"];
+        assert_eq!(check_game(Some("steam.exe"), &modules), Ok(()));
+    }
+
+    #[test]
+    fn test_user_message() {
+        let msg = EnvironmentViolation::LaunchedWithEac.user_message();
+        assert!(msg.starts_with("Elden Ring was started with Easy Anti-Cheat"));
+        assert!(msg.contains("did not load"));
+    }
+}
@@ -0,0 +1,94 @@
+//! Bounded event queue between game-facing code and its consumers
+//!
+//! The frame update publishes what it observed in the game (flags, loading
+//! screens, zone reveals); the queue is drained once per frame and each event
+//! handed to the subsystems in publication order. Publishing never blocks: a
+//! full queue rejects the event and counts it, which only happens if nothing
+//! drains it.
+
+use std::collections::vec_deque::{self, VecDeque};
+
+/// Events buffered before a drain, far above what a single frame publishes
+pub const DEFAULT_CAPACITY: usize = 256;
+
+#[derive(Debug)]
+pub struct EventBus<E> {
+    queue: VecDeque<E>,
+    capacity: usize,
+    dropped: u64,
+}
+
+impl<E> EventBus<E> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            queue: VecDeque::with_capacity(capacity),
+            capacity,
+            dropped: 0,
+        }
+    }
+
+    /// Queue an event. Returns false (and counts the event as dropped) when full.
+    pub fn publish(&mut self, event: E) -> bool {
+        if self.queue.len() >= self.capacity {
+            self.dropped += 1;
+            return false;
+        }
+        self.queue.push_back(event);
+        true
+    }
+
+    /// Take the queued events, oldest first
+    pub fn drain(&mut self) -> vec_deque::Drain<'_, E> {
+        self.queue.drain(..)
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Events rejected because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+impl<E> Default for EventBus<E> {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drain_in_publication_order() {
+        let mut bus = EventBus::default();
+        assert!(bus.publish("flag"));
+        assert!(bus.publish("loading_exit"));
+        assert_eq!(bus.len(), 2);
+        assert_eq!(
+            bus.drain().collect::<Vec<_>>(),
+            vec!["flag", "loading_exit"]
+        );
+        assert!(bus.is_empty());
+    }
+
+    #[test]
+    fn test_full_queue_rejects() {
+        let mut bus = EventBus::with_capacity(2);
+        assert!(bus.publish(1));
+        assert!(bus.publish(2));
+        assert!(!bus.publish(3));
+        assert_eq!(bus.dropped(), 1);
+        // Earlier events are kept
+        assert_eq!(bus.drain().collect::<Vec<_>>(), vec![1, 2]);
+        assert!(bus.publish(4));
+        assert_eq!(bus.dropped(), 1);
+    }
+}
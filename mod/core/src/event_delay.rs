@@ -0,0 +1,134 @@
+//! Broadcast delay of race events
+//!
+//! Broadcast races can have the server hold back the progress each mod
+//! reports (`auth_ok` `event_delay_secs`, protocol 21+): fog gates, zone
+//! queries, graces, key items, objectives and checkpoints are kept for that
+//! long before being sent, so the leaderboard shown on stream never runs ahead
+//! of the delayed stream itself and can't be used to stream-snipe a racer.
+//! The events keep their IGT, only the moment they are sent changes. The
+//! overlay shows the delay while it applies.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Longest delay the mod accepts from a server
+pub const MAX_DELAY: Duration = Duration::from_secs(600);
+
+/// Messages held back until their delay has passed, in order
+#[derive(Debug)]
+pub struct DelayQueue<T> {
+    delay: Duration,
+    queue: VecDeque<(Instant, T)>,
+}
+
+impl<T> Default for DelayQueue<T> {
+    fn default() -> Self {
+        Self {
+            delay: Duration::ZERO,
+            queue: VecDeque::new(),
+        }
+    }
+}
+
+impl<T> DelayQueue<T> {
+    /// Delay of the messages queued from now on, capped to `MAX_DELAY`.
+    /// Messages already queued keep their release time.
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay.min(MAX_DELAY);
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Queue `message`, or hand it back when there is no delay (and nothing
+    /// queued it could overtake)
+    pub fn push(&mut self, message: T, now: Instant) -> Option<T> {
+        if self.delay.is_zero() && self.queue.is_empty() {
+            return Some(message);
+        }
+        let release_at = now + self.delay;
+        // A shorter delay set meanwhile mustn't reorder the events
+        let release_at = self
+            .queue
+            .back()
+            .map_or(release_at, |&(last, _)| release_at.max(last));
+        self.queue.push_back((release_at, message));
+        None
+    }
+
+    /// Messages whose delay has passed, oldest first
+    pub fn due(&mut self, now: Instant) -> Vec<T> {
+        let ready = self
+            .queue
+            .iter()
+            .take_while(|(release_at, _)| *release_at <= now)
+            .count();
+        self.queue
+            .drain(..ready)
+            .map(|(_, message)| message)
+            .collect()
+    }
+
+    /// Every queued message, e.g. when the game closes
+    pub fn flush(&mut self) -> Vec<T> {
+        self.queue.drain(..).map(|(_, message)| message).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_delay_passes_through() {
+        let mut queue = DelayQueue::default();
+        assert_eq!(queue.push(1, Instant::now()), Some(1));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_released_after_delay_in_order() {
+        let start = Instant::now();
+        let mut queue = DelayQueue::default();
+        queue.set_delay(Duration::from_secs(30));
+        assert_eq!(queue.push(1, start), None);
+        assert_eq!(queue.push(2, start + Duration::from_secs(10)), None);
+        assert!(queue.due(start + Duration::from_secs(29)).is_empty());
+        assert_eq!(queue.due(start + Duration::from_secs(30)), vec![1]);
+        assert_eq!(queue.due(start + Duration::from_secs(45)), vec![2]);
+    }
+
+    #[test]
+    fn test_shorter_delay_keeps_order() {
+        let start = Instant::now();
+        let mut queue = DelayQueue::default();
+        queue.set_delay(Duration::from_secs(30));
+        queue.push(1, start);
+        queue.set_delay(Duration::ZERO);
+        // Can't overtake the delayed event
+        assert_eq!(queue.push(2, start + Duration::from_secs(1)), None);
+        assert_eq!(queue.due(start + Duration::from_secs(30)), vec![1, 2]);
+        assert_eq!(queue.push(3, start + Duration::from_secs(31)), Some(3));
+    }
+
+    #[test]
+    fn test_delay_capped_and_flushed() {
+        let mut queue = DelayQueue::default();
+        queue.set_delay(Duration::from_secs(3600));
+        assert_eq!(queue.delay(), MAX_DELAY);
+        queue.push("a", Instant::now());
+        queue.push("b", Instant::now());
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.flush(), vec!["a", "b"]);
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,251 @@
+//! Write-ahead log of the event flags sent
+//!
+//! The outbox (`discovery`) resends unacknowledged flags, but it lives in
+//! memory: a game crash in the frame the finish flag fires loses the finish.
+//! Each tracked flag is appended to a small log next to the DLL, and synced,
+//! before it is sent; its acknowledgement is appended when it arrives. On the
+//! next start, the flags of the race still unacknowledged are sent again (the
+//! server dedupes them with their idempotency key) and the log is compacted.
+//!
+//! One record per line, tab-separated, so a line torn by a crash is skipped:
+//! `sent <written_at_ms> <race_id> <flag_id> <igt_ms> <key or ->` and
+//! `ack <race_id> <flag_id>`.
+
+/// Entries older than this are dropped on load: their race is long over
+pub const MAX_AGE_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// A flag sent and not yet acknowledged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalRecord {
+    pub race_id: String,
+    pub flag_id: u32,
+    pub igt_ms: u32,
+    /// Idempotency key it was sent with (protocol 23+)
+    pub key: Option<String>,
+    /// Unix time the record was written (ms)
+    pub written_at_ms: i64,
+}
+
+/// One line of the log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalLine {
+    Sent(WalRecord),
+    Acked { race_id: String, flag_id: u32 },
+}
+
+impl WalLine {
+    pub fn encode(&self) -> String {
+        match self {
+            WalLine::Sent(r) => format!(
+                "sent\t{}\t{}\t{}\t{}\t{}",
+                r.written_at_ms,
+                r.race_id,
+                r.flag_id,
+                r.igt_ms,
+                r.key.as_deref().unwrap_or("-")
+            ),
+            WalLine::Acked { race_id, flag_id } => format!("ack\t{}\t{}", race_id, flag_id),
+        }
+    }
+
+    /// None for torn or unknown lines
+    pub fn parse(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        match fields.as_slice() {
+            ["sent", written_at_ms, race_id, flag_id, igt_ms, key] => {
+                Some(WalLine::Sent(WalRecord {
+                    race_id: race_id.to_string(),
+                    flag_id: flag_id.parse().ok()?,
+                    igt_ms: igt_ms.parse().ok()?,
+                    key: (*key != "-").then(|| key.to_string()),
+                    written_at_ms: written_at_ms.parse().ok()?,
+                }))
+            }
+            ["ack", race_id, flag_id] => Some(WalLine::Acked {
+                race_id: race_id.to_string(),
+                flag_id: flag_id.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Flags of the log still waiting for their acknowledgement
+#[derive(Debug, Default)]
+pub struct EventWal {
+    pending: Vec<WalRecord>,
+}
+
+impl EventWal {
+    pub const FILENAME: &'static str = "speedfog_events.wal";
+
+    /// Replay the log: what's still pending, without expired entries
+    pub fn load(contents: &str, now_ms: i64) -> Self {
+        let mut wal = Self::default();
+        for line in contents.lines().filter_map(WalLine::parse) {
+            wal.apply(line);
+        }
+        wal.pending
+            .retain(|r| now_ms.saturating_sub(r.written_at_ms) < MAX_AGE_MS);
+        wal
+    }
+
+    /// Line to append before sending a flag, None if it is already logged
+    pub fn sent(&mut self, record: WalRecord) -> Option<String> {
+        if self.find(&record.race_id, record.flag_id).is_some() {
+            return None;
+        }
+        let line = WalLine::Sent(record);
+        let encoded = line.encode();
+        self.apply(line);
+        Some(encoded)
+    }
+
+    /// Line to append for an acknowledgement, None if the flag wasn't logged
+    pub fn acked(&mut self, race_id: &str, flag_id: u32) -> Option<String> {
+        self.find(race_id, flag_id)?;
+        let line = WalLine::Acked {
+            race_id: race_id.to_string(),
+            flag_id,
+        };
+        let encoded = line.encode();
+        self.apply(line);
+        Some(encoded)
+    }
+
+    /// Unacknowledged flags of a race, in sending order
+    pub fn unacked<'a>(&'a self, race_id: &'a str) -> impl Iterator<Item = &'a WalRecord> {
+        self.pending.iter().filter(move |r| r.race_id == race_id)
+    }
+
+    /// Forget a race's flags without logging acks (the player has finished:
+    /// nothing to send again). Returns whether any was pending.
+    pub fn discard(&mut self, race_id: &str) -> bool {
+        let before = self.pending.len();
+        self.pending.retain(|r| r.race_id != race_id);
+        self.pending.len() != before
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Contents of the log with only the pending records
+    pub fn compacted(&self) -> String {
+        self.pending
+            .iter()
+            .map(|r| WalLine::Sent(r.clone()).encode() + "\n")
+            .collect()
+    }
+
+    fn find(&self, race_id: &str, flag_id: u32) -> Option<usize> {
+        self.pending
+            .iter()
+            .position(|r| r.race_id == race_id && r.flag_id == flag_id)
+    }
+
+    fn apply(&mut self, line: WalLine) {
+        match line {
+            WalLine::Sent(record) => {
+                if self.find(&record.race_id, record.flag_id).is_none() {
+                    self.pending.push(record);
+                }
+            }
+            WalLine::Acked { race_id, flag_id } => {
+                if let Some(index) = self.find(&race_id, flag_id) {
+                    self.pending.remove(index);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000_000;
+
+    fn record(race_id: &str, flag_id: u32) -> WalRecord {
+        WalRecord {
+            race_id: race_id.to_string(),
+            flag_id,
+            igt_ms: flag_id * 10,
+            key: Some(format!("{}:0000000000000000:{}", race_id, flag_id)),
+            written_at_ms: NOW,
+        }
+    }
+
+    #[test]
+    fn test_line_roundtrip() {
+        let sent = WalLine::Sent(record("race-1", 100));
+        assert_eq!(WalLine::parse(&sent.encode()), Some(sent));
+        let no_key = WalLine::Sent(WalRecord {
+            key: None,
+            ..record("race-1", 100)
+        });
+        assert_eq!(WalLine::parse(&no_key.encode()), Some(no_key));
+        let ack = WalLine::Acked {
+            race_id: "race-1".to_string(),
+            flag_id: 100,
+        };
+        assert_eq!(ack.encode(), "ack\trace-1\t100");
+        assert_eq!(WalLine::parse(&ack.encode()), Some(ack));
+    }
+
+    #[test]
+    fn test_torn_lines_skipped() {
+        assert_eq!(WalLine::parse("sent\t1700000000000\trace-1\t10"), None);
+        assert_eq!(WalLine::parse("ack\trace-1\t10x"), None);
+        assert_eq!(WalLine::parse(""), None);
+    }
+
+    #[test]
+    fn test_replay_after_crash() {
+        let mut wal = EventWal::default();
+        let mut log = String::new();
+        for line in [
+            wal.sent(record("race-1", 100)),
+            wal.sent(record("race-1", 200)),
+            wal.acked("race-1", 100),
+            wal.sent(record("race-2", 300)),
+        ] {
+            log.push_str(&line.unwrap());
+            log.push('\n');
+        }
+        // Crash while writing the finish
+        log.push_str("sent\t1700000000000\trace-1\t9");
+
+        let wal = EventWal::load(&log, NOW + 1000);
+        let unacked: Vec<u32> = wal.unacked("race-1").map(|r| r.flag_id).collect();
+        assert_eq!(unacked, vec![200]);
+        assert_eq!(wal.unacked("race-2").count(), 1);
+        assert_eq!(
+            EventWal::load(&wal.compacted(), NOW).compacted(),
+            wal.compacted()
+        );
+    }
+
+    #[test]
+    fn test_logged_once() {
+        let mut wal = EventWal::default();
+        assert!(wal.sent(record("race-1", 100)).is_some());
+        // Resent by the outbox: already in the log
+        assert!(wal.sent(record("race-1", 100)).is_none());
+        assert!(wal.acked("race-1", 100).is_some());
+        assert!(wal.acked("race-1", 100).is_none());
+        assert!(wal.is_empty());
+    }
+
+    #[test]
+    fn test_expired_and_discarded() {
+        let mut wal = EventWal::default();
+        let log = wal.sent(record("race-1", 100)).unwrap();
+        assert!(EventWal::load(&log, NOW + MAX_AGE_MS).is_empty());
+
+        wal.sent(record("race-2", 200));
+        assert!(wal.discard("race-1"));
+        assert!(!wal.discard("race-1"));
+        assert_eq!(wal.unacked("race-2").count(), 1);
+    }
+}
@@ -0,0 +1,170 @@
+//! Exit list ordering
+//!
+//! The exits panel lists the exits most likely to be useful first. Each exit
+//! gets a score: a bonus when it is undiscovered, minus a penalty per tier
+//! between its destination and the highest tier reached so far. Destination
+//! tiers are only known for zones already visited; the others count as
+//! `UNKNOWN_TIER_DISTANCE` tiers away. Equal scores are sorted by the label
+//! the panel shows (destination when discovered, directions otherwise), so
+//! the order never hints at an undiscovered destination.
+
+use serde::{Deserialize, Serialize};
+
+use super::protocol::ExitInfo;
+
+/// Tier distance assumed for a destination of unknown tier
+pub const UNKNOWN_TIER_DISTANCE: f32 = 10.0;
+
+/// `[exit_order]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExitOrderSettings {
+    /// Sort the exits (false: server order)
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Score bonus of an undiscovered exit
+    #[serde(default = "default_undiscovered_weight")]
+    pub undiscovered_weight: f32,
+    /// Score penalty per tier between the destination and the progression
+    #[serde(default = "default_tier_weight")]
+    pub tier_weight: f32,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_undiscovered_weight() -> f32 {
+    100.0
+}
+
+fn default_tier_weight() -> f32 {
+    1.0
+}
+
+impl Default for ExitOrderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            undiscovered_weight: default_undiscovered_weight(),
+            tier_weight: default_tier_weight(),
+        }
+    }
+}
+
+impl ExitOrderSettings {
+    /// Higher is listed first
+    pub fn score(&self, exit: &ExitInfo, tier: Option<i32>, progression: Option<i32>) -> f32 {
+        let distance = match (tier, progression) {
+            (Some(tier), Some(progression)) => (tier - progression).abs() as f32,
+            _ => UNKNOWN_TIER_DISTANCE,
+        };
+        let undiscovered = if exit.discovered {
+            0.0
+        } else {
+            self.undiscovered_weight
+        };
+        undiscovered - self.tier_weight * distance
+    }
+}
+
+/// Label the exits panel shows for an exit, lowercased for sorting
+fn sort_label(exit: &ExitInfo) -> String {
+    if exit.discovered {
+        exit.to_name.to_lowercase()
+    } else {
+        exit.text.to_lowercase()
+    }
+}
+
+/// Indices of `exits`, most useful first. `tier` gives the tier of an exit's
+/// destination when known, `progression` is the highest tier reached.
+pub fn rank(
+    exits: &[ExitInfo],
+    tier: impl Fn(&ExitInfo) -> Option<i32>,
+    progression: Option<i32>,
+    settings: &ExitOrderSettings,
+) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..exits.len()).collect();
+    if !settings.enabled {
+        return order;
+    }
+    let keys: Vec<(f32, String)> = exits
+        .iter()
+        .map(|exit| {
+            (
+                settings.score(exit, tier(exit), progression),
+                sort_label(exit),
+            )
+        })
+        .collect();
+    order.sort_by(|&a, &b| {
+        let (score_a, label_a) = &keys[a];
+        let (score_b, label_b) = &keys[b];
+        score_b
+            .total_cmp(score_a)
+            .then_with(|| label_a.cmp(label_b))
+    });
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exit(text: &str, to_name: &str, discovered: bool) -> ExitInfo {
+        ExitInfo {
+            text: text.to_string(),
+            to_name: to_name.to_string(),
+            discovered,
+            to_node_id: None,
+            map_id: None,
+            position: None,
+        }
+    }
+
+    fn tiers(exit: &ExitInfo) -> Option<i32> {
+        match exit.to_name.as_str() {
+            "Caelid" => Some(9),
+            "Limgrave" => Some(2),
+            "Liurnia" => Some(5),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_rank() {
+        let exits = [
+            exit("west door", "Caelid", true),
+            exit("north gate", "Secret", false),
+            exit("east cliff", "Liurnia", true),
+            exit("back door", "Limgrave", true),
+            exit("cellar", "Other", false),
+        ];
+        let settings = ExitOrderSettings::default();
+        // Undiscovered first (by directions, not by the hidden destination),
+        // then by tier distance from tier 6
+        assert_eq!(rank(&exits, tiers, Some(6), &settings), vec![4, 1, 2, 0, 3]);
+        // No progression yet: discovered exits are alphabetical
+        assert_eq!(rank(&exits, tiers, None, &settings), vec![4, 1, 0, 3, 2]);
+    }
+
+    #[test]
+    fn test_weights() {
+        let exits = [
+            exit("a", "Hidden", false),
+            exit("b", "Liurnia", true),
+            exit("c", "Caelid", true),
+        ];
+        // Without the undiscovered bonus, a known close tier comes first
+        let settings = ExitOrderSettings {
+            undiscovered_weight: 0.0,
+            ..ExitOrderSettings::default()
+        };
+        assert_eq!(rank(&exits, tiers, Some(5), &settings), vec![1, 2, 0]);
+        let disabled = ExitOrderSettings {
+            enabled: false,
+            ..settings
+        };
+        assert_eq!(rank(&exits, tiers, Some(5), &disabled), vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,96 @@
+//! Exits pinned by the player in interactive mode
+//!
+//! A click on an exit moves it to the top of the list until clicked again.
+//! Pins are kept per zone node for the session, so leaving and coming back to
+//! a zone shows the same pinned exits. Exits are identified by their
+//! directions text, which is unique within a zone.
+
+use std::collections::HashMap;
+
+use super::protocol::ExitInfo;
+
+#[derive(Debug, Default)]
+pub struct ExitPins {
+    /// Zone node id → pinned exit texts, in pin order
+    zones: HashMap<String, Vec<String>>,
+}
+
+impl ExitPins {
+    /// Pin or unpin an exit. Returns true when the exit is now pinned.
+    pub fn toggle(&mut self, node_id: &str, exit_text: &str) -> bool {
+        let pins = self.zones.entry(node_id.to_string()).or_default();
+        if let Some(index) = pins.iter().position(|p| p == exit_text) {
+            pins.remove(index);
+            false
+        } else {
+            pins.push(exit_text.to_string());
+            true
+        }
+    }
+
+    pub fn is_pinned(&self, node_id: &str, exit_text: &str) -> bool {
+        self.zones
+            .get(node_id)
+            .is_some_and(|pins| pins.iter().any(|p| p == exit_text))
+    }
+
+    /// Indices of `exits` in display order: pinned exits first (in pin order),
+    /// then the others in `ranked` order (see `core::exit_order`)
+    pub fn order(&self, node_id: &str, exits: &[ExitInfo], ranked: Vec<usize>) -> Vec<usize> {
+        let pins = self.zones.get(node_id).map(Vec::as_slice).unwrap_or(&[]);
+        let pinned: Vec<usize> = pins
+            .iter()
+            .filter_map(|pin| exits.iter().position(|e| &e.text == pin))
+            .collect();
+        let others = ranked.into_iter().filter(|i| !pinned.contains(i));
+        pinned.iter().copied().chain(others).collect()
+    }
+
+    /// Forget every pin (new seed)
+    pub fn clear(&mut self) {
+        self.zones.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exit(text: &str) -> ExitInfo {
+        ExitInfo {
+            text: text.to_string(),
+            to_name: String::new(),
+            discovered: false,
+            to_node_id: None,
+            map_id: None,
+            position: None,
+        }
+    }
+
+    #[test]
+    fn test_toggle() {
+        let mut pins = ExitPins::default();
+        assert!(pins.toggle("stormveil", "main gate"));
+        assert!(pins.is_pinned("stormveil", "main gate"));
+        // Pins are per zone
+        assert!(!pins.is_pinned("liurnia", "main gate"));
+        assert!(!pins.toggle("stormveil", "main gate"));
+        assert!(!pins.is_pinned("stormveil", "main gate"));
+    }
+
+    #[test]
+    fn test_order_pinned_first() {
+        let exits = [exit("a"), exit("b"), exit("c"), exit("d")];
+        let ranked = || vec![3, 0, 1, 2];
+        let mut pins = ExitPins::default();
+        assert_eq!(pins.order("zone", &exits, ranked()), vec![3, 0, 1, 2]);
+        pins.toggle("zone", "c");
+        pins.toggle("zone", "b");
+        assert_eq!(pins.order("zone", &exits, ranked()), vec![2, 1, 3, 0]);
+        // Pins of exits the zone no longer lists are ignored
+        pins.toggle("zone", "gone");
+        assert_eq!(pins.order("zone", &exits, ranked()), vec![2, 1, 3, 0]);
+        pins.clear();
+        assert_eq!(pins.order("zone", &exits, ranked()), vec![3, 0, 1, 2]);
+    }
+}
@@ -0,0 +1,83 @@
+//! Game exit hand-offs
+//!
+//! The game window procedure learns about the exit, but the state to flush
+//! lives with the tracker (render thread) and the WebSocket worker. Each of
+//! them sets an `ExitSignal` once done; the window procedure waits on it with
+//! a timeout, so quitting never hangs on a stuck thread.
+
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+pub struct ExitSignal {
+    set: Mutex<bool>,
+    cond: Condvar,
+}
+
+impl ExitSignal {
+    pub const fn new() -> Self {
+        Self {
+            set: Mutex::new(false),
+            cond: Condvar::new(),
+        }
+    }
+
+    /// Wake up the waiters (and any later `wait`)
+    pub fn set(&self) {
+        if let Ok(mut set) = self.set.lock() {
+            *set = true;
+            self.cond.notify_all();
+        }
+    }
+
+    pub fn reset(&self) {
+        if let Ok(mut set) = self.set.lock() {
+            *set = false;
+        }
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.set.lock().map(|set| *set).unwrap_or(false)
+    }
+
+    /// Block until set or `timeout` elapsed. Returns whether it was set.
+    pub fn wait(&self, timeout: Duration) -> bool {
+        let Ok(set) = self.set.lock() else {
+            return false;
+        };
+        self.cond
+            .wait_timeout_while(set, timeout, |set| !*set)
+            .map(|(set, _)| *set)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_wait_times_out() {
+        let signal = ExitSignal::new();
+        assert!(!signal.wait(Duration::from_millis(10)));
+        signal.set();
+        assert!(signal.is_set());
+        assert!(signal.wait(Duration::ZERO));
+        signal.reset();
+        assert!(!signal.is_set());
+    }
+
+    #[test]
+    fn test_set_from_another_thread() {
+        let signal = Arc::new(ExitSignal::new());
+        let setter = Arc::clone(&signal);
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            setter.set();
+        });
+        assert!(signal.wait(Duration::from_secs(5)));
+        handle.join().unwrap();
+    }
+}
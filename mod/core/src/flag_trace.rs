@@ -0,0 +1,299 @@
+//! Event flag trace
+//!
+//! Debug mode for missed finishes. Every frame (about 6 times the rate of
+//! the regular 10 Hz poll), all the seed's event flags are read into a
+//! bitset and each change is recorded with the time since the trace started
+//! and the IGT. The trace is written next to the DLL when the race finishes
+//! or from the debug window: it shows whether the game ever set a flag the
+//! regular poll missed.
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use super::post_race::file_safe_id;
+
+/// `[flag_trace]` section of the config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlagTraceSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Transitions kept in memory, oldest dropped first
+    #[serde(default = "default_max_transitions")]
+    pub max_transitions: usize,
+}
+
+fn default_max_transitions() -> usize {
+    10_000
+}
+
+impl Default for FlagTraceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_transitions: default_max_transitions(),
+        }
+    }
+}
+
+/// What a read returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagState {
+    Unset,
+    Set,
+    /// Reader returned None (loading screen, flag page not allocated)
+    Unreadable,
+}
+
+impl FlagState {
+    fn from_read(read: Option<bool>) -> Self {
+        match read {
+            Some(true) => FlagState::Set,
+            Some(false) => FlagState::Unset,
+            None => FlagState::Unreadable,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FlagState::Unset => "unset",
+            FlagState::Set => "SET",
+            FlagState::Unreadable => "unreadable",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+    /// Milliseconds since the first poll
+    pub elapsed_ms: u64,
+    pub igt_ms: u32,
+    pub flag_id: u32,
+    pub from: FlagState,
+    pub to: FlagState,
+}
+
+/// One bit per traced flag
+#[derive(Debug, Clone, Default)]
+struct Bitset(Vec<u64>);
+
+impl Bitset {
+    fn with_len(len: usize) -> Self {
+        Self(vec![0; len.div_ceil(64)])
+    }
+
+    fn get(&self, i: usize) -> bool {
+        self.0[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    fn set(&mut self, i: usize, value: bool) {
+        if value {
+            self.0[i / 64] |= 1 << (i % 64);
+        } else {
+            self.0[i / 64] &= !(1 << (i % 64));
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FlagTrace {
+    ids: Vec<u32>,
+    set: Bitset,
+    readable: Bitset,
+    /// False until the first poll has filled the bitsets
+    primed: bool,
+    started: Option<Instant>,
+    polls: u64,
+    transitions: VecDeque<Transition>,
+    capacity: usize,
+    dropped: u64,
+}
+
+impl FlagTrace {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            ids: Vec::new(),
+            set: Bitset::default(),
+            readable: Bitset::default(),
+            primed: false,
+            started: None,
+            polls: 0,
+            transitions: VecDeque::new(),
+            capacity: capacity.max(1),
+            dropped: 0,
+        }
+    }
+
+    /// Trace these flags from now on (new seed); the history is kept
+    pub fn set_ids(&mut self, ids: &[u32]) {
+        self.ids = ids.to_vec();
+        self.set = Bitset::with_len(ids.len());
+        self.readable = Bitset::with_len(ids.len());
+        self.primed = false;
+    }
+
+    fn state(&self, i: usize) -> FlagState {
+        if !self.readable.get(i) {
+            FlagState::Unreadable
+        } else if self.set.get(i) {
+            FlagState::Set
+        } else {
+            FlagState::Unset
+        }
+    }
+
+    /// Read every flag and record the changes. The first poll after
+    /// `set_ids` only records the flags already set.
+    pub fn poll(&mut self, now: Instant, igt_ms: u32, mut read: impl FnMut(u32) -> Option<bool>) {
+        let elapsed_ms = now
+            .duration_since(*self.started.get_or_insert(now))
+            .as_millis() as u64;
+        for i in 0..self.ids.len() {
+            let flag_id = self.ids[i];
+            let to = FlagState::from_read(read(flag_id));
+            let from = self.state(i);
+            if from != to && (self.primed || to == FlagState::Set) {
+                self.push(Transition {
+                    elapsed_ms,
+                    igt_ms,
+                    flag_id,
+                    from,
+                    to,
+                });
+            }
+            self.readable.set(i, to != FlagState::Unreadable);
+            self.set.set(i, to == FlagState::Set);
+        }
+        self.primed = true;
+        self.polls += 1;
+    }
+
+    fn push(&mut self, transition: Transition) {
+        if self.transitions.len() >= self.capacity {
+            self.transitions.pop_front();
+            self.dropped += 1;
+        }
+        self.transitions.push_back(transition);
+    }
+
+    /// Oldest first
+    pub fn transitions(&self) -> impl DoubleEndedIterator<Item = &Transition> {
+        self.transitions.iter()
+    }
+
+    pub fn polls(&self) -> u64 {
+        self.polls
+    }
+
+    /// Header, then one line per transition:
+    /// `+12.345s igt 0:01:02.345 1040292800 unset -> SET`
+    pub fn export(&self) -> String {
+        let mut out = format!(
+            "# SpeedFog flag trace: {} flags, {} polls, {} transitions ({} dropped)\n",
+            self.ids.len(),
+            self.polls,
+            self.transitions.len(),
+            self.dropped
+        );
+        for t in &self.transitions {
+            let secs = t.igt_ms / 1000;
+            out.push_str(&format!(
+                "+{}.{:03}s igt {}:{:02}:{:02}.{:03} {} {} -> {}\n",
+                t.elapsed_ms / 1000,
+                t.elapsed_ms % 1000,
+                secs / 3600,
+                secs / 60 % 60,
+                secs % 60,
+                t.igt_ms % 1000,
+                t.flag_id,
+                t.from.label(),
+                t.to.label()
+            ));
+        }
+        out
+    }
+
+    /// Export file name, e.g. `speedfog_flags_<race_id>.txt`
+    pub fn file_name(race_id: &str) -> String {
+        format!("speedfog_flags_{}.txt", file_safe_id(race_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn poll(trace: &mut FlagTrace, start: Instant, ms: u64, states: &HashMap<u32, Option<bool>>) {
+        let now = start + Duration::from_millis(ms);
+        trace.poll(now, 0, |id| states.get(&id).copied().flatten());
+    }
+
+    #[test]
+    fn test_bitset() {
+        let mut bits = Bitset::with_len(130);
+        bits.set(0, true);
+        bits.set(129, true);
+        assert!(bits.get(0) && bits.get(129) && !bits.get(64));
+        bits.set(0, false);
+        assert!(!bits.get(0));
+    }
+
+    #[test]
+    fn test_first_poll_records_set_flags_only() {
+        let start = Instant::now();
+        let mut trace = FlagTrace::new(10);
+        trace.set_ids(&[1, 2, 3]);
+        let states = HashMap::from([(1, Some(true)), (2, Some(false)), (3, None)]);
+        poll(&mut trace, start, 0, &states);
+        let flags: Vec<u32> = trace.transitions().map(|t| t.flag_id).collect();
+        assert_eq!(flags, vec![1]);
+        // Nothing changed
+        poll(&mut trace, start, 16, &states);
+        assert_eq!(trace.transitions().count(), 1);
+        assert_eq!(trace.polls(), 2);
+    }
+
+    #[test]
+    fn test_transitions() {
+        let start = Instant::now();
+        let mut trace = FlagTrace::new(10);
+        trace.set_ids(&[7]);
+        poll(&mut trace, start, 0, &HashMap::from([(7, Some(false))]));
+        poll(&mut trace, start, 16, &HashMap::from([(7, Some(true))]));
+        poll(&mut trace, start, 32, &HashMap::from([(7, None)]));
+        poll(&mut trace, start, 48, &HashMap::from([(7, Some(false))]));
+        let changes: Vec<(u64, FlagState, FlagState)> = trace
+            .transitions()
+            .map(|t| (t.elapsed_ms, t.from, t.to))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (16, FlagState::Unset, FlagState::Set),
+                (32, FlagState::Set, FlagState::Unreadable),
+                (48, FlagState::Unreadable, FlagState::Unset),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_capacity_and_export() {
+        let start = Instant::now();
+        let mut trace = FlagTrace::new(1);
+        trace.set_ids(&[5]);
+        poll(&mut trace, start, 0, &HashMap::from([(5, Some(false))]));
+        poll(&mut trace, start, 100, &HashMap::from([(5, Some(true))]));
+        trace.poll(start + Duration::from_millis(62_345), 3_723_456, |_| {
+            Some(false)
+        });
+        assert_eq!(
+            trace.export(),
+            "# SpeedFog flag trace: 1 flags, 3 polls, 1 transitions (1 dropped)\n\
+             +62.345s igt 1:02:03.456 5 SET -> unset\n"
+        );
+        assert_eq!(FlagTrace::file_name("race/1"), "speedfog_flags_race_1.txt");
+    }
+}
@@ -0,0 +1,202 @@
+//! Low-power mode while the game is in the background
+//!
+//! Races have breaks: the game sits alt-tabbed or minimized while the DLL
+//! keeps reading memory and building the overlay every frame. Without focus,
+//! the tracker update (memory reads, gate scans, flag polls) only runs at
+//! the poll interval; minimized, the overlay isn't built at all. Getting
+//! the focus back runs the next frame in full.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// `[focus]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FocusSettings {
+    /// Throttle the mod while the game doesn't have the focus
+    #[serde(default = "default_true")]
+    pub low_power: bool,
+    /// Time between updates in the background (the flag poll runs at 10 Hz,
+    /// so the default doesn't delay any detection)
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+fn default_poll_interval_ms() -> u64 {
+    100
+}
+
+impl Default for FocusSettings {
+    fn default() -> Self {
+        Self {
+            low_power: true,
+            poll_interval_ms: default_poll_interval_ms(),
+        }
+    }
+}
+
+/// State of the game window this frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowFocus {
+    Focused,
+    /// Visible but another window has the focus (alt-tab, second monitor)
+    Background,
+    Minimized,
+}
+
+/// What the frame should do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FramePlan {
+    /// Run the tracker update
+    pub update: bool,
+    /// Build the overlay
+    pub render: bool,
+}
+
+/// Decides, frame by frame, how much work the game's focus allows
+#[derive(Debug)]
+pub struct FocusThrottle {
+    focus: WindowFocus,
+    last_update: Option<Instant>,
+}
+
+impl Default for FocusThrottle {
+    fn default() -> Self {
+        Self {
+            focus: WindowFocus::Focused,
+            last_update: None,
+        }
+    }
+}
+
+impl FocusThrottle {
+    pub fn focus(&self) -> WindowFocus {
+        self.focus
+    }
+
+    /// Plan this frame. Returns the plan and the previous focus when it
+    /// changed (for logging).
+    pub fn plan(
+        &mut self,
+        now: Instant,
+        focus: WindowFocus,
+        settings: &FocusSettings,
+    ) -> (FramePlan, Option<WindowFocus>) {
+        let previous = (focus != self.focus).then_some(self.focus);
+        self.focus = focus;
+
+        let plan = if !settings.low_power || focus == WindowFocus::Focused {
+            FramePlan {
+                update: true,
+                render: true,
+            }
+        } else {
+            let interval = Duration::from_millis(settings.poll_interval_ms);
+            let due = self
+                .last_update
+                .is_none_or(|last| now.saturating_duration_since(last) >= interval);
+            FramePlan {
+                update: due,
+                render: focus == WindowFocus::Background,
+            }
+        };
+        if plan.update {
+            self.last_update = Some(now);
+        }
+        (plan, previous)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL: FramePlan = FramePlan {
+        update: true,
+        render: true,
+    };
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_focused_runs_every_frame() {
+        let t0 = Instant::now();
+        let settings = FocusSettings::default();
+        let mut throttle = FocusThrottle::default();
+        for frame in 0..5 {
+            let (plan, changed) =
+                throttle.plan(t0 + ms(frame * 16), WindowFocus::Focused, &settings);
+            assert_eq!(plan, FULL);
+            assert_eq!(changed, None);
+        }
+    }
+
+    #[test]
+    fn test_background_throttled() {
+        let t0 = Instant::now();
+        let settings = FocusSettings::default();
+        let mut throttle = FocusThrottle::default();
+        throttle.plan(t0, WindowFocus::Focused, &settings);
+
+        let (plan, changed) = throttle.plan(t0 + ms(16), WindowFocus::Background, &settings);
+        assert_eq!(changed, Some(WindowFocus::Focused));
+        // Updated on the focused frame 16ms ago: not due yet, still drawn
+        assert!(!plan.update && plan.render);
+        let (plan, _) = throttle.plan(t0 + ms(80), WindowFocus::Background, &settings);
+        assert!(!plan.update);
+        let (plan, changed) = throttle.plan(t0 + ms(100), WindowFocus::Background, &settings);
+        assert!(plan.update);
+        assert_eq!(changed, None);
+        let (plan, _) = throttle.plan(t0 + ms(116), WindowFocus::Background, &settings);
+        assert!(!plan.update);
+    }
+
+    #[test]
+    fn test_minimized_not_drawn() {
+        let t0 = Instant::now();
+        let settings = FocusSettings::default();
+        let mut throttle = FocusThrottle::default();
+        let (plan, _) = throttle.plan(t0, WindowFocus::Minimized, &settings);
+        assert!(plan.update && !plan.render);
+        let (plan, _) = throttle.plan(t0 + ms(50), WindowFocus::Minimized, &settings);
+        assert!(!plan.update && !plan.render);
+    }
+
+    #[test]
+    fn test_focus_back_resumes_instantly() {
+        let t0 = Instant::now();
+        let settings = FocusSettings::default();
+        let mut throttle = FocusThrottle::default();
+        throttle.plan(t0, WindowFocus::Minimized, &settings);
+        let (plan, changed) = throttle.plan(t0 + ms(10), WindowFocus::Focused, &settings);
+        assert_eq!(plan, FULL);
+        assert_eq!(changed, Some(WindowFocus::Minimized));
+    }
+
+    #[test]
+    fn test_low_power_off() {
+        let t0 = Instant::now();
+        let settings = FocusSettings {
+            low_power: false,
+            ..FocusSettings::default()
+        };
+        let mut throttle = FocusThrottle::default();
+        throttle.plan(t0, WindowFocus::Minimized, &settings);
+        let (plan, _) = throttle.plan(t0 + ms(10), WindowFocus::Minimized, &settings);
+        assert_eq!(plan, FULL);
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings: FocusSettings = toml::from_str("").unwrap();
+        assert_eq!(settings, FocusSettings::default());
+        let settings: FocusSettings = toml::from_str("poll_interval_ms = 500").unwrap();
+        assert!(settings.low_power);
+        assert_eq!(settings.poll_interval_ms, 500);
+    }
+}
@@ -0,0 +1,140 @@
+//! Per-frame cost of the overlay
+//!
+//! The overlay runs inside the game's present call, so every millisecond spent
+//! in tracker updates and UI layout is taken from the game's frame. The
+//! profiler keeps smoothed timings of both stages and flags the overlay as
+//! degraded while it goes over budget, so expensive layout work can be
+//! refreshed less often.
+
+use std::time::Duration;
+
+/// Time the overlay may take per frame before degrading
+pub const FRAME_BUDGET: Duration = Duration::from_millis(2);
+
+/// Refresh interval of cached layouts while degraded
+pub const DEGRADED_REFRESH: Duration = Duration::from_millis(250);
+
+/// Weight of the newest sample in the moving averages
+const SMOOTHING: f64 = 0.1;
+
+/// Leave degraded mode below this fraction of the budget (avoids flapping)
+const RECOVERY_RATIO: f64 = 0.75;
+
+/// Part of the frame being measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Tracker update: memory reads, flag polling, messages
+    Update,
+    /// ImGui layout of the overlay windows
+    Ui,
+}
+
+#[derive(Debug, Default)]
+pub struct FrameProfiler {
+    update_ms: f64,
+    ui_ms: f64,
+    degraded: bool,
+    samples: u32,
+}
+
+impl FrameProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a stage duration. Recording `Ui` closes the frame and
+    /// re-evaluates the budget.
+    pub fn record(&mut self, stage: Stage, elapsed: Duration) {
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        let first = self.samples == 0;
+        let avg = match stage {
+            Stage::Update => &mut self.update_ms,
+            Stage::Ui => &mut self.ui_ms,
+        };
+        *avg = if first {
+            ms
+        } else {
+            *avg + (ms - *avg) * SMOOTHING
+        };
+
+        if stage == Stage::Ui {
+            self.samples = self.samples.saturating_add(1);
+            let budget = FRAME_BUDGET.as_secs_f64() * 1000.0;
+            let total = self.total_ms();
+            if total > budget {
+                self.degraded = true;
+            } else if total < budget * RECOVERY_RATIO {
+                self.degraded = false;
+            }
+        }
+    }
+
+    pub fn update_ms(&self) -> f64 {
+        self.update_ms
+    }
+
+    pub fn ui_ms(&self) -> f64 {
+        self.ui_ms
+    }
+
+    pub fn total_ms(&self) -> f64 {
+        self.update_ms + self.ui_ms
+    }
+
+    /// Over budget: cached layouts should be reused when possible
+    pub fn is_degraded(&self) -> bool {
+        self.degraded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(profiler: &mut FrameProfiler, update_us: u64, ui_us: u64) {
+        profiler.record(Stage::Update, Duration::from_micros(update_us));
+        profiler.record(Stage::Ui, Duration::from_micros(ui_us));
+    }
+
+    #[test]
+    fn test_first_sample_sets_average() {
+        let mut profiler = FrameProfiler::new();
+        frame(&mut profiler, 300, 500);
+        assert!((profiler.update_ms() - 0.3).abs() < 1e-9);
+        assert!((profiler.ui_ms() - 0.5).abs() < 1e-9);
+        assert!((profiler.total_ms() - 0.8).abs() < 1e-9);
+        assert!(!profiler.is_degraded());
+    }
+
+    #[test]
+    fn test_single_spike_is_smoothed() {
+        let mut profiler = FrameProfiler::new();
+        for _ in 0..50 {
+            frame(&mut profiler, 300, 500);
+        }
+        // One 10ms hitch doesn't push the average over budget
+        frame(&mut profiler, 5000, 5000);
+        assert!(!profiler.is_degraded());
+    }
+
+    #[test]
+    fn test_degrades_and_recovers_with_hysteresis() {
+        let mut profiler = FrameProfiler::new();
+        for _ in 0..100 {
+            frame(&mut profiler, 1500, 1500);
+        }
+        assert!(profiler.is_degraded());
+
+        // Just under budget: stays degraded
+        for _ in 0..100 {
+            frame(&mut profiler, 900, 900);
+        }
+        assert!(profiler.is_degraded());
+
+        // Well under budget: recovers
+        for _ in 0..100 {
+            frame(&mut profiler, 300, 300);
+        }
+        assert!(!profiler.is_degraded());
+    }
+}
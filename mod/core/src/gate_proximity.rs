@@ -0,0 +1,205 @@
+//! Fog gate proximity hint
+//!
+//! Fog gates placed by the randomizer are map assets with entity IDs in the
+//! `755890xxx` range. When enabled, the tracker periodically lists the ones
+//! loaded around the player (see `eldenring::geom_scan`) and the overlay says
+//! "a fog gate is nearby" while one is within the configured radius, without
+//! telling which gate or where it leads. Meant for new players looking for a
+//! gate tucked in an obscure corner.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// First entity ID of the randomizer's fog gates
+pub const FOG_GATE_ENTITY_MIN: u32 = 755_890_000;
+
+/// Last entity ID of the randomizer's fog gates
+pub const FOG_GATE_ENTITY_MAX: u32 = 755_890_999;
+
+/// The hint clears once the player is this much farther than the radius, so
+/// it doesn't flicker at the edge
+const LEAVE_MARGIN: f32 = 1.2;
+
+/// `[gate_proximity]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GateProximitySettings {
+    /// Scan for fog gates around the player during a race
+    #[serde(default)]
+    pub enabled: bool,
+    /// Distance in meters under which a gate counts as nearby
+    #[serde(default = "default_radius_m")]
+    pub radius_m: f32,
+    /// Seconds between two scans of the loaded map assets
+    #[serde(default = "default_scan_interval_secs")]
+    pub scan_interval_secs: f32,
+}
+
+fn default_radius_m() -> f32 {
+    30.0
+}
+fn default_scan_interval_secs() -> f32 {
+    2.0
+}
+
+impl Default for GateProximitySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius_m: default_radius_m(),
+            scan_interval_secs: default_scan_interval_secs(),
+        }
+    }
+}
+
+impl GateProximitySettings {
+    pub fn scan_interval(&self) -> Duration {
+        Duration::from_secs_f32(self.scan_interval_secs.max(0.5))
+    }
+}
+
+pub fn is_fog_gate_entity(entity_id: u32) -> bool {
+    (FOG_GATE_ENTITY_MIN..=FOG_GATE_ENTITY_MAX).contains(&entity_id)
+}
+
+/// A fog gate asset loaded in memory
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GateCandidate {
+    pub entity_id: u32,
+    /// Map block the asset belongs to (same encoding as `PlayerPosition::map_id`)
+    pub map_id: u32,
+    /// Position local to `map_id`
+    pub position: [f32; 3],
+}
+
+/// Distance in meters to the closest gate on the player's map. Gates of other
+/// blocks are skipped: coordinates are local to each map.
+pub fn nearest_gate_distance(
+    player_map_id: u32,
+    player: [f32; 3],
+    gates: &[GateCandidate],
+) -> Option<f32> {
+    gates
+        .iter()
+        .filter(|g| g.map_id == player_map_id)
+        .map(|g| distance(player, g.position))
+        .min_by(f32::total_cmp)
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let dx = a[0] - b[0];
+    let dy = a[1] - b[1];
+    let dz = a[2] - b[2];
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+/// Scan pacing and the nearby state shown by the overlay
+#[derive(Debug, Default)]
+pub struct GateProximity {
+    last_scan: Option<Instant>,
+    nearby: bool,
+}
+
+impl GateProximity {
+    /// Whether a new scan is due
+    pub fn due(&self, now: Instant, settings: &GateProximitySettings) -> bool {
+        self.last_scan
+            .is_none_or(|last| now.duration_since(last) >= settings.scan_interval())
+    }
+
+    /// Record a scan result. Returns true when the nearby state changed.
+    pub fn observe(
+        &mut self,
+        now: Instant,
+        nearest: Option<f32>,
+        settings: &GateProximitySettings,
+    ) -> bool {
+        self.last_scan = Some(now);
+        let threshold = if self.nearby {
+            settings.radius_m * LEAVE_MARGIN
+        } else {
+            settings.radius_m
+        };
+        let nearby = nearest.is_some_and(|d| d <= threshold);
+        let changed = nearby != self.nearby;
+        self.nearby = nearby;
+        changed
+    }
+
+    pub fn is_nearby(&self) -> bool {
+        self.nearby
+    }
+
+    /// Forget the last result (disabled, not racing, loading screen)
+    pub fn reset(&mut self) {
+        self.nearby = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gate(map_id: u32, position: [f32; 3]) -> GateCandidate {
+        GateCandidate {
+            entity_id: FOG_GATE_ENTITY_MIN,
+            map_id,
+            position,
+        }
+    }
+
+    #[test]
+    fn test_fog_gate_entity_range() {
+        assert!(is_fog_gate_entity(755_890_000));
+        assert!(is_fog_gate_entity(755_890_123));
+        assert!(is_fog_gate_entity(755_890_999));
+        assert!(!is_fog_gate_entity(755_889_999));
+        assert!(!is_fog_gate_entity(755_891_000));
+        assert!(!is_fog_gate_entity(10_001_950));
+    }
+
+    #[test]
+    fn test_nearest_gate_same_map_only() {
+        let map = 0x0A01_0000;
+        let gates = [
+            gate(map, [30.0, 0.0, 40.0]),
+            gate(map, [3.0, 4.0, 0.0]),
+            // Closer, but coordinates of another block
+            gate(0x0B00_0000, [0.0, 0.0, 1.0]),
+        ];
+        assert_eq!(nearest_gate_distance(map, [0.0; 3], &gates), Some(5.0));
+        assert_eq!(nearest_gate_distance(0x0C00_0000, [0.0; 3], &gates), None);
+        assert_eq!(nearest_gate_distance(map, [0.0; 3], &[]), None);
+    }
+
+    #[test]
+    fn test_observe_hysteresis() {
+        let settings = GateProximitySettings::default();
+        let now = Instant::now();
+        let mut proximity = GateProximity::default();
+        assert!(proximity.due(now, &settings));
+
+        assert!(!proximity.observe(now, Some(31.0), &settings));
+        assert!(!proximity.is_nearby());
+        assert!(proximity.observe(now, Some(29.0), &settings));
+        assert!(proximity.is_nearby());
+        // Still nearby just past the radius
+        assert!(!proximity.observe(now, Some(35.0), &settings));
+        assert!(proximity.is_nearby());
+        assert!(proximity.observe(now, Some(37.0), &settings));
+        assert!(!proximity.is_nearby());
+
+        proximity.observe(now, Some(1.0), &settings);
+        assert!(proximity.observe(now, None, &settings));
+    }
+
+    #[test]
+    fn test_scan_pacing() {
+        let settings = GateProximitySettings::default();
+        let start = Instant::now();
+        let mut proximity = GateProximity::default();
+        proximity.observe(start, None, &settings);
+        assert!(!proximity.due(start + Duration::from_secs(1), &settings));
+        assert!(proximity.due(start + Duration::from_secs(2), &settings));
+    }
+}
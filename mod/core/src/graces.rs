@@ -0,0 +1,170 @@
+//! Sites of Grace found during the race
+//!
+//! Touching a grace sets its unlock flag (BonfireWarpParam `eventflagId`).
+//! Those flags sit in a few blocks of IDs: legacy dungeons from 71000,
+//! catacombs, caves and other minor dungeons from 73000 and the overworld
+//! from 76000 (the DLC continues each block). IDs without a grace are never
+//! set, so the tracker scans the blocks and counts the flags set since the
+//! race started. Graces the save already had then (the seed's starting
+//! graces) are the baseline and don't count.
+
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+
+/// Event flag blocks of the graces' unlock flags
+pub const GRACE_FLAG_RANGES: &[RangeInclusive<u32>] = &[71000..=73999, 76000..=76999];
+
+/// Most recent graces listed on the overlay
+pub const LIST_LEN: usize = 8;
+
+/// `[graces]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraceSettings {
+    /// Count the graces touched during a race and report them to the server
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// List the last graces found (zone and IGT) below the overlay header
+    #[serde(default)]
+    pub list: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Default for GraceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            list: false,
+        }
+    }
+}
+
+/// A grace touched during the race
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraceFound {
+    pub flag_id: u32,
+    pub igt_ms: u32,
+    /// Zone the player was in, for the overlay list
+    pub zone: Option<String>,
+}
+
+/// Unlock flags currently set, None as soon as one can't be read
+pub fn read_unlocked(mut is_set: impl FnMut(u32) -> Option<bool>) -> Option<Vec<u32>> {
+    let mut unlocked = Vec::new();
+    for flag_id in GRACE_FLAG_RANGES.iter().cloned().flatten() {
+        if is_set(flag_id)? {
+            unlocked.push(flag_id);
+        }
+    }
+    Some(unlocked)
+}
+
+/// Graces found since the race started
+#[derive(Debug, Default)]
+pub struct GraceTracker {
+    /// Unlock flags already seen set (baseline included)
+    known: Option<HashSet<u32>>,
+    found: Vec<GraceFound>,
+}
+
+impl GraceTracker {
+    pub fn found(&self) -> &[GraceFound] {
+        &self.found
+    }
+
+    /// Feed the unlock flags read in game (None while unreadable). Returns
+    /// the graces found since the last read.
+    pub fn observe(
+        &mut self,
+        unlocked: Option<Vec<u32>>,
+        igt_ms: u32,
+        zone: Option<&str>,
+    ) -> Vec<GraceFound> {
+        let Some(unlocked) = unlocked else {
+            return Vec::new();
+        };
+        let Some(known) = &mut self.known else {
+            self.known = Some(unlocked.into_iter().collect());
+            return Vec::new();
+        };
+        let new: Vec<GraceFound> = unlocked
+            .into_iter()
+            .filter(|flag_id| known.insert(*flag_id))
+            .filter(|flag_id| !self.found.iter().any(|g| g.flag_id == *flag_id))
+            .map(|flag_id| GraceFound {
+                flag_id,
+                igt_ms,
+                zone: zone.map(str::to_string),
+            })
+            .collect();
+        self.found.extend(new.iter().cloned());
+        new
+    }
+
+    /// Take a new baseline (another character loaded), keeping the graces
+    /// found so far
+    pub fn rebase(&mut self) {
+        self.known = None;
+    }
+
+    /// Forget everything (new seed)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_unlocked() {
+        let unlocked = read_unlocked(|flag_id| Some(flag_id == 71000 || flag_id == 76101));
+        assert_eq!(unlocked, Some(vec![71000, 76101]));
+        // Title screen: the flag manager isn't readable
+        assert_eq!(read_unlocked(|_| None), None);
+    }
+
+    #[test]
+    fn test_baseline_not_counted() {
+        let mut graces = GraceTracker::default();
+        // Starting graces
+        assert!(graces.observe(Some(vec![71190]), 0, None).is_empty());
+        assert!(graces.observe(None, 1000, None).is_empty());
+
+        let found = graces.observe(Some(vec![71190, 76101]), 2000, Some("Limgrave"));
+        assert_eq!(
+            found,
+            vec![GraceFound {
+                flag_id: 76101,
+                igt_ms: 2000,
+                zone: Some("Limgrave".to_string()),
+            }]
+        );
+        assert!(graces
+            .observe(Some(vec![71190, 76101]), 3000, None)
+            .is_empty());
+        assert_eq!(graces.found().len(), 1);
+    }
+
+    #[test]
+    fn test_rebase_keeps_found() {
+        let mut graces = GraceTracker::default();
+        graces.observe(Some(vec![]), 0, None);
+        graces.observe(Some(vec![76101]), 1000, None);
+
+        // Another character with more graces: a new baseline
+        graces.rebase();
+        assert!(graces
+            .observe(Some(vec![76101, 76102, 76103]), 2000, None)
+            .is_empty());
+        assert_eq!(graces.found().len(), 1);
+
+        graces.reset();
+        assert!(graces.found().is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+//! Community exit hints
+//!
+//! Optional spoiler-free annotations for undiscovered fog gates ("Leads to a
+//! legacy dungeon", "Dead end"), fetched from the server's community hint
+//! database one zone at a time. Hints never change for a seed, so they are
+//! cached next to the DLL and only requested once per zone.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::protocol::ExitHint;
+
+/// `[hints]` section of the config
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HintSettings {
+    /// Fetch community hints and show them under undiscovered exits
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Contents of the hint cache file: hints keyed by seed_id, then node_id
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HintCache {
+    #[serde(default)]
+    pub seeds: HashMap<String, HashMap<String, Vec<ExitHint>>>,
+}
+
+impl HintCache {
+    pub const FILENAME: &'static str = "speedfog_hints.json";
+
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        serde_json::from_str(contents).map_err(|e| format!("Failed to parse hints: {}", e))
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize hints: {}", e))
+    }
+
+    /// Whether the hints of a zone were already fetched (possibly none)
+    pub fn contains(&self, seed_id: &str, node_id: &str) -> bool {
+        self.seeds
+            .get(seed_id)
+            .is_some_and(|nodes| nodes.contains_key(node_id))
+    }
+
+    /// Hint for the exit at `exit_index` in the zone's zone_update exits
+    pub fn hint(&self, seed_id: &str, node_id: &str, exit_index: usize) -> Option<&str> {
+        self.seeds
+            .get(seed_id)?
+            .get(node_id)?
+            .iter()
+            .find(|h| h.exit_index as usize == exit_index)
+            .map(|h| h.text.as_str())
+    }
+
+    /// Store the hints of a zone. An empty list is kept too, so zones
+    /// without hints aren't requested again.
+    pub fn insert(&mut self, seed_id: &str, node_id: &str, hints: Vec<ExitHint>) {
+        self.seeds
+            .entry(seed_id.to_string())
+            .or_default()
+            .insert(node_id.to_string(), hints);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hint(exit_index: u32, text: &str) -> ExitHint {
+        ExitHint {
+            exit_index,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_hint_lookup() {
+        let mut cache = HintCache::default();
+        cache.insert("seed1", "stormveil", vec![hint(1, "Dead end")]);
+        assert_eq!(cache.hint("seed1", "stormveil", 1), Some("Dead end"));
+        assert_eq!(cache.hint("seed1", "stormveil", 0), None);
+        assert_eq!(cache.hint("seed1", "caelid", 1), None);
+        // Seeds are independent
+        assert_eq!(cache.hint("seed2", "stormveil", 1), None);
+    }
+
+    #[test]
+    fn test_zone_without_hints_is_cached() {
+        let mut cache = HintCache::default();
+        assert!(!cache.contains("seed1", "limgrave"));
+        cache.insert("seed1", "limgrave", Vec::new());
+        assert!(cache.contains("seed1", "limgrave"));
+        assert!(!cache.contains("seed2", "limgrave"));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut cache = HintCache::default();
+        cache.insert(
+            "seed1",
+            "stormveil",
+            vec![hint(0, "Leads to a legacy dungeon")],
+        );
+        let parsed = HintCache::parse(&cache.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, cache);
+    }
+
+    #[test]
+    fn test_parse_empty_and_invalid() {
+        assert_eq!(HintCache::parse("{}").unwrap(), HintCache::default());
+        assert!(HintCache::parse("not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings: HintSettings = toml::from_str("").unwrap();
+        assert!(!settings.enabled);
+        let settings: HintSettings = toml::from_str("enabled = true").unwrap();
+        assert!(settings.enabled);
+    }
+}
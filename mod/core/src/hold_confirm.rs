@@ -0,0 +1,148 @@
+//! Hold-to-confirm hotkeys
+//!
+//! Hotkeys with side effects (exporting the zone graph, asking the organizer
+//! for a pause) are easy to hit by accident in the middle of a fight. With
+//! `[hold_confirm]` enabled they only fire once the key has been held for
+//! `hold_secs`, while a progress ring fills up on screen. Releasing the key
+//! early cancels; holding it past the confirmation doesn't fire again.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// `[hold_confirm]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HoldConfirmSettings {
+    /// Require holding the key for actions with side effects
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Seconds the key must be held
+    #[serde(default = "default_hold_secs")]
+    pub hold_secs: f32,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+fn default_hold_secs() -> f32 {
+    2.0
+}
+
+impl Default for HoldConfirmSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            hold_secs: default_hold_secs(),
+        }
+    }
+}
+
+impl HoldConfirmSettings {
+    pub fn hold_duration(&self) -> Duration {
+        Duration::from_secs_f32(self.hold_secs.max(0.0))
+    }
+}
+
+/// Result of a frame of holding
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HoldState {
+    /// Key not held, or held again after a confirmation
+    Idle,
+    /// Held, with the ring progress from 0 to 1
+    Holding(f32),
+    /// Held long enough this frame: run the action
+    Confirmed,
+}
+
+/// Hold tracking of one hotkey
+#[derive(Debug, Default)]
+pub struct HoldConfirm {
+    /// When the current hold started
+    since: Option<Instant>,
+    /// The current hold already fired
+    fired: bool,
+}
+
+impl HoldConfirm {
+    /// Feed whether the key is held this frame
+    pub fn update(&mut self, held: bool, now: Instant, duration: Duration) -> HoldState {
+        if !held {
+            self.since = None;
+            self.fired = false;
+            return HoldState::Idle;
+        }
+        if self.fired {
+            return HoldState::Idle;
+        }
+        let since = *self.since.get_or_insert(now);
+        let elapsed = now.saturating_duration_since(since);
+        if elapsed >= duration {
+            self.fired = true;
+            return HoldState::Confirmed;
+        }
+        HoldState::Holding(elapsed.as_secs_f32() / duration.as_secs_f32())
+    }
+
+    /// Ring progress of the hold in progress, if any
+    pub fn progress(&self, now: Instant, duration: Duration) -> Option<f32> {
+        let since = self.since.filter(|_| !self.fired)?;
+        let elapsed = now.saturating_duration_since(since).as_secs_f32();
+        Some((elapsed / duration.as_secs_f32()).min(1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOLD: Duration = Duration::from_secs(2);
+
+    #[test]
+    fn test_fires_once_after_hold() {
+        let start = Instant::now();
+        let mut hold = HoldConfirm::default();
+        assert_eq!(hold.update(true, start, HOLD), HoldState::Holding(0.0));
+        assert_eq!(
+            hold.update(true, start + Duration::from_secs(1), HOLD),
+            HoldState::Holding(0.5)
+        );
+        assert_eq!(hold.update(true, start + HOLD, HOLD), HoldState::Confirmed);
+        // Keeping the key down doesn't repeat the action
+        assert_eq!(
+            hold.update(true, start + Duration::from_secs(5), HOLD),
+            HoldState::Idle
+        );
+        assert_eq!(hold.progress(start + Duration::from_secs(5), HOLD), None);
+    }
+
+    #[test]
+    fn test_early_release_cancels() {
+        let start = Instant::now();
+        let mut hold = HoldConfirm::default();
+        hold.update(true, start, HOLD);
+        hold.update(true, start + Duration::from_millis(1900), HOLD);
+        assert_eq!(
+            hold.update(false, start + Duration::from_millis(1950), HOLD),
+            HoldState::Idle
+        );
+        // A new press starts from zero
+        let again = start + Duration::from_secs(3);
+        assert_eq!(hold.update(true, again, HOLD), HoldState::Holding(0.0));
+        assert_eq!(
+            hold.progress(again + Duration::from_secs(1), HOLD),
+            Some(0.5)
+        );
+    }
+
+    #[test]
+    fn test_release_rearms_after_confirm() {
+        let start = Instant::now();
+        let mut hold = HoldConfirm::default();
+        hold.update(true, start, HOLD);
+        hold.update(true, start + HOLD, HOLD);
+        hold.update(false, start + Duration::from_secs(3), HOLD);
+        let again = start + Duration::from_secs(4);
+        hold.update(true, again, HOLD);
+        assert_eq!(hold.update(true, again + HOLD, HOLD), HoldState::Confirmed);
+    }
+}
@@ -0,0 +1,162 @@
+//! Icon atlas UV map
+//!
+//! Parses the TOML file describing named icons inside a user-provided atlas PNG
+//! and converts their pixel rectangles to normalized ImGui texture coordinates.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// Pixel rectangle of a single icon inside the atlas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct IconRect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+/// Named icon rectangles, as declared in `icons.toml`:
+///
+/// ```toml
+/// [icons]
+/// death = { x = 0, y = 0, w = 64, h = 64 }
+/// skull = { x = 64, y = 0, w = 64, h = 64 }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct IconMap {
+    #[serde(default)]
+    pub icons: HashMap<String, IconRect>,
+}
+
+/// Normalized texture coordinates for ImGui `Image::uv0` / `Image::uv1`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IconUv {
+    pub uv0: [f32; 2],
+    pub uv1: [f32; 2],
+}
+
+impl IconUv {
+    /// The whole texture (single-icon images)
+    pub const FULL: IconUv = IconUv {
+        uv0: [0.0, 0.0],
+        uv1: [1.0, 1.0],
+    };
+}
+
+impl IconMap {
+    /// Parse the TOML UV map. Icon names are lowercased so lookups are case-insensitive.
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let map: IconMap =
+            toml::from_str(contents).map_err(|e| format!("Failed to parse icon map: {}", e))?;
+        Ok(IconMap {
+            icons: map
+                .icons
+                .into_iter()
+                .map(|(name, rect)| (name.to_lowercase(), rect))
+                .collect(),
+        })
+    }
+
+    /// Convert every rectangle to UV coordinates for an atlas of the given size.
+    ///
+    /// Fails on the first empty or out-of-bounds rectangle so a typo in the map
+    /// is reported instead of silently rendering garbage.
+    pub fn resolve(
+        &self,
+        atlas_width: u32,
+        atlas_height: u32,
+    ) -> Result<HashMap<String, IconUv>, String> {
+        if atlas_width == 0 || atlas_height == 0 {
+            return Err("Icon atlas has zero size".to_string());
+        }
+        let (aw, ah) = (atlas_width as f32, atlas_height as f32);
+        let mut resolved = HashMap::with_capacity(self.icons.len());
+        for (name, r) in &self.icons {
+            if r.w == 0 || r.h == 0 {
+                return Err(format!("Icon '{}' has an empty rectangle", name));
+            }
+            if r.x.saturating_add(r.w) > atlas_width || r.y.saturating_add(r.h) > atlas_height {
+                return Err(format!(
+                    "Icon '{}' ({}x{} at {},{}) exceeds atlas bounds {}x{}",
+                    name, r.w, r.h, r.x, r.y, atlas_width, atlas_height
+                ));
+            }
+            resolved.insert(
+                name.clone(),
+                IconUv {
+                    uv0: [r.x as f32 / aw, r.y as f32 / ah],
+                    uv1: [(r.x + r.w) as f32 / aw, (r.y + r.h) as f32 / ah],
+                },
+            );
+        }
+        Ok(resolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_icon_map() {
+        let map = IconMap::parse(
+            r#"
+            [icons]
+            death = { x = 0, y = 0, w = 64, h = 64 }
+            Skull = { x = 64, y = 0, w = 32, h = 32 }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(map.icons.len(), 2);
+        assert_eq!(
+            map.icons.get("death"),
+            Some(&IconRect {
+                x: 0,
+                y: 0,
+                w: 64,
+                h: 64
+            })
+        );
+        // Names are lowercased
+        assert!(map.icons.contains_key("skull"));
+    }
+
+    #[test]
+    fn test_parse_empty_map() {
+        let map = IconMap::parse("").unwrap();
+        assert!(map.icons.is_empty());
+    }
+
+    #[test]
+    fn test_parse_invalid_map() {
+        assert!(IconMap::parse("[icons]\ndeath = { x = 0 }").is_err());
+    }
+
+    #[test]
+    fn test_resolve_uv() {
+        let map = IconMap::parse("[icons]\nright = { x = 64, y = 0, w = 64, h = 32 }").unwrap();
+        let uvs = map.resolve(128, 64).unwrap();
+        let uv = uvs.get("right").unwrap();
+        assert_eq!(uv.uv0, [0.5, 0.0]);
+        assert_eq!(uv.uv1, [1.0, 0.5]);
+    }
+
+    #[test]
+    fn test_resolve_out_of_bounds() {
+        let map = IconMap::parse("[icons]\nbig = { x = 64, y = 0, w = 128, h = 64 }").unwrap();
+        assert!(map.resolve(128, 64).is_err());
+    }
+
+    #[test]
+    fn test_resolve_empty_rect() {
+        let map = IconMap::parse("[icons]\nnone = { x = 0, y = 0, w = 0, h = 16 }").unwrap();
+        assert!(map.resolve(128, 64).is_err());
+    }
+
+    #[test]
+    fn test_resolve_zero_atlas() {
+        let map = IconMap::default();
+        assert!(map.resolve(0, 64).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+//! IGT pause detection
+//!
+//! Compares in-game time against wall-clock time to split a run into play
+//! time and load/menu time: whenever IGT stops ticking (loading screens,
+//! quit-outs) while the wall clock keeps running, that time is counted as
+//! loading. Samples are fed once per frame.
+
+use std::time::{Duration, Instant};
+
+/// IGT stalls shorter than this are frame jitter, not a pause
+const PAUSE_THRESHOLD: Duration = Duration::from_millis(250);
+
+/// Accumulates play vs load time from (wall time, IGT) samples
+#[derive(Debug, Default)]
+pub struct IgtAnalyzer {
+    /// Last sample: wall time and IGT (None while IGT is unreadable)
+    last: Option<(Instant, Option<u32>)>,
+    /// Start of the current stall, if IGT isn't ticking
+    stall_start: Option<Instant>,
+    play: Duration,
+    load: Duration,
+    pauses: u32,
+}
+
+impl IgtAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a sample. `igt_ms` is None while IGT can't be read (main menu).
+    pub fn add_sample(&mut self, now: Instant, igt_ms: Option<u32>) {
+        let Some((last_wall, last_igt)) = self.last.replace((now, igt_ms)) else {
+            return;
+        };
+        let elapsed = now.saturating_duration_since(last_wall);
+        // IGT of 0 is a quit-out, and a lower IGT a reload: neither is ticking
+        let ticking = match (last_igt, igt_ms) {
+            (Some(prev), Some(cur)) => cur > prev && prev > 0,
+            _ => false,
+        };
+
+        if ticking {
+            if let Some(start) = self.stall_start.take() {
+                self.end_stall(last_wall.saturating_duration_since(start));
+            }
+            self.play += elapsed;
+        } else if self.stall_start.is_none() {
+            self.stall_start = Some(last_wall);
+        }
+    }
+
+    fn end_stall(&mut self, stall: Duration) {
+        if stall >= PAUSE_THRESHOLD {
+            self.load += stall;
+            self.pauses += 1;
+        } else {
+            self.play += stall;
+        }
+    }
+
+    /// Whether IGT has been stalled long enough to count as a pause
+    pub fn is_paused(&self) -> bool {
+        self.ongoing_stall() >= PAUSE_THRESHOLD
+    }
+
+    fn ongoing_stall(&self) -> Duration {
+        match (self.stall_start, self.last) {
+            (Some(start), Some((last_wall, _))) => last_wall.saturating_duration_since(start),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Wall time spent with IGT ticking
+    pub fn play_time(&self) -> Duration {
+        let stall = self.ongoing_stall();
+        if stall < PAUSE_THRESHOLD {
+            self.play + stall
+        } else {
+            self.play
+        }
+    }
+
+    /// Wall time spent with IGT stopped (loads, quit-outs), including the current pause
+    pub fn load_time(&self) -> Duration {
+        let stall = self.ongoing_stall();
+        if stall >= PAUSE_THRESHOLD {
+            self.load + stall
+        } else {
+            self.load
+        }
+    }
+
+    /// Number of pauses detected so far, including the current one
+    pub fn pause_count(&self) -> u32 {
+        self.pauses + u32::from(self.is_paused())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    /// Feed IGT ticking in lockstep with wall time from `igt` for `len` ms
+    fn tick(a: &mut IgtAnalyzer, t0: Instant, from: u64, len: u64, igt: u32) -> u32 {
+        for step in (0..=len).step_by(16) {
+            a.add_sample(t0 + ms(from + step), Some(igt + step as u32));
+        }
+        igt + len as u32
+    }
+
+    #[test]
+    fn test_all_play() {
+        let t0 = Instant::now();
+        let mut a = IgtAnalyzer::new();
+        tick(&mut a, t0, 0, 1600, 1000);
+        assert_eq!(a.play_time(), ms(1600));
+        assert_eq!(a.load_time(), Duration::ZERO);
+        assert_eq!(a.pause_count(), 0);
+        assert!(!a.is_paused());
+    }
+
+    #[test]
+    fn test_loading_screen_counted() {
+        let t0 = Instant::now();
+        let mut a = IgtAnalyzer::new();
+        let igt = tick(&mut a, t0, 0, 1600, 1000);
+        // IGT frozen for 2s
+        for step in (0..=2000).step_by(16) {
+            a.add_sample(t0 + ms(1600 + step), Some(igt));
+        }
+        assert!(a.is_paused());
+        assert_eq!(a.pause_count(), 1);
+        tick(&mut a, t0, 3600, 1600, igt);
+
+        assert!(!a.is_paused());
+        assert_eq!(a.pause_count(), 1);
+        assert_eq!(a.load_time(), ms(2000));
+        assert_eq!(a.play_time(), ms(3200));
+    }
+
+    #[test]
+    fn test_short_stall_is_play() {
+        let t0 = Instant::now();
+        let mut a = IgtAnalyzer::new();
+        a.add_sample(t0, Some(1000));
+        a.add_sample(t0 + ms(16), Some(1016));
+        a.add_sample(t0 + ms(32), Some(1016));
+        a.add_sample(t0 + ms(48), Some(1048));
+        assert_eq!(a.play_time(), ms(48));
+        assert_eq!(a.load_time(), Duration::ZERO);
+        assert_eq!(a.pause_count(), 0);
+    }
+
+    #[test]
+    fn test_quit_out_counted_as_load() {
+        let t0 = Instant::now();
+        let mut a = IgtAnalyzer::new();
+        a.add_sample(t0, Some(5000));
+        a.add_sample(t0 + ms(100), Some(5100));
+        // Quit-out: IGT reads 0, then unreadable at the main menu
+        a.add_sample(t0 + ms(1100), Some(0));
+        a.add_sample(t0 + ms(5100), None);
+        // Reloaded: IGT resumes from the save
+        a.add_sample(t0 + ms(8100), Some(5100));
+        a.add_sample(t0 + ms(8200), Some(5200));
+        assert_eq!(a.load_time(), ms(8000));
+        assert_eq!(a.play_time(), ms(200));
+        assert_eq!(a.pause_count(), 1);
+    }
+}
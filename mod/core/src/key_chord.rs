@@ -0,0 +1,148 @@
+//! Key chords for hotkeys
+//!
+//! A binding is a key with optional Ctrl / Shift / Alt modifiers, written
+//! `"ctrl+shift+f9"` in the config (case-insensitive, modifiers first). The
+//! key names themselves are resolved by `dll::hotkey`. Also home of the
+//! conflict check used when a key is rebound from the settings panel.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers {
+        ctrl: false,
+        shift: false,
+        alt: false,
+    };
+
+    /// Names in chord order, for display or the config
+    fn names(self) -> impl Iterator<Item = &'static str> {
+        [
+            (self.ctrl, "Ctrl"),
+            (self.shift, "Shift"),
+            (self.alt, "Alt"),
+        ]
+        .into_iter()
+        .filter_map(|(held, name)| held.then_some(name))
+    }
+}
+
+/// Split `"ctrl+shift+f9"` into its modifiers and key name. None when a
+/// modifier is unknown or repeated, or the key is missing.
+pub fn parse_chord(text: &str) -> Option<(Modifiers, &str)> {
+    let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+    let key = parts.pop().filter(|k| !k.is_empty())?;
+    let mut modifiers = Modifiers::NONE;
+    for part in parts {
+        let flag = match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => &mut modifiers.ctrl,
+            "shift" => &mut modifiers.shift,
+            "alt" => &mut modifiers.alt,
+            _ => return None,
+        };
+        if *flag {
+            return None;
+        }
+        *flag = true;
+    }
+    Some((modifiers, key))
+}
+
+/// Chord as shown to the player, e.g. `Ctrl+F9`
+pub struct ChordLabel<'a> {
+    pub modifiers: Modifiers,
+    pub key: &'a str,
+}
+
+impl fmt::Display for ChordLabel<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for name in self.modifiers.names() {
+            write!(f, "{}+", name)?;
+        }
+        f.write_str(self.key)
+    }
+}
+
+/// Actions other than `action` already bound to `chord`
+pub fn conflicts<'a, T: PartialEq>(
+    bindings: &'a [(&'a str, T)],
+    action: &str,
+    chord: &T,
+) -> Vec<&'a str> {
+    bindings
+        .iter()
+        .filter(|(other, bound)| *other != action && bound == chord)
+        .map(|(other, _)| *other)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chord() {
+        assert_eq!(parse_chord("f9"), Some((Modifiers::NONE, "f9")));
+        assert_eq!(
+            parse_chord("Ctrl+Shift+F9"),
+            Some((
+                Modifiers {
+                    ctrl: true,
+                    shift: true,
+                    alt: false
+                },
+                "F9"
+            ))
+        );
+        assert_eq!(
+            parse_chord("alt + home"),
+            Some((
+                Modifiers {
+                    ctrl: false,
+                    shift: false,
+                    alt: true
+                },
+                "home"
+            ))
+        );
+        for bad in ["", "ctrl+", "super+f9", "ctrl+ctrl+f9"] {
+            assert_eq!(parse_chord(bad), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_label() {
+        let label = ChordLabel {
+            modifiers: Modifiers {
+                ctrl: true,
+                shift: false,
+                alt: true,
+            },
+            key: "F9",
+        };
+        assert_eq!(label.to_string(), "Ctrl+Alt+F9");
+        let plain = ChordLabel {
+            modifiers: Modifiers::NONE,
+            key: "Home",
+        };
+        assert_eq!(plain.to_string(), "Home");
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let bindings = [("toggle_ui", 1), ("toggle_map", 2), ("screenshot", 2)];
+        assert_eq!(
+            conflicts(&bindings, "toggle_ui", &2),
+            vec!["toggle_map", "screenshot"]
+        );
+        // Rebinding an action to its own key is not a conflict
+        assert!(conflicts(&bindings, "toggle_ui", &1).is_empty());
+        assert!(conflicts(&bindings, "toggle_ui", &3).is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+//! Key item pickups
+//!
+//! Some seeds gate progress behind key items (medallions, keys). The server
+//! lists them with the event flag the game sets on pickup. Pickup flags stay
+//! set once the item is obtained, so polling them also catches pickups made
+//! while disconnected.
+
+use super::protocol::KeyItem;
+
+#[derive(Debug, Default)]
+pub struct KeyItemWatcher {
+    items: Vec<KeyItem>,
+    /// (item_id, igt_ms) in pickup order
+    obtained: Vec<(u32, u32)>,
+    /// Pickups not sent to the server yet
+    unsent: Vec<(u32, u32)>,
+}
+
+impl KeyItemWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Items to watch (from auth_ok). Pickups already seen are kept.
+    pub fn set_items(&mut self, items: Vec<KeyItem>) {
+        self.items = items;
+    }
+
+    /// Check the pickup flags and return the newly obtained items, which are
+    /// also queued for sending. `is_flag_set` returns None when unreadable.
+    pub fn poll(
+        &mut self,
+        igt_ms: u32,
+        mut is_flag_set: impl FnMut(u32) -> Option<bool>,
+    ) -> Vec<KeyItem> {
+        let mut newly = Vec::new();
+        for item in &self.items {
+            if self.obtained.iter().any(|&(id, _)| id == item.id) {
+                continue;
+            }
+            if is_flag_set(item.flag_id) == Some(true) {
+                self.obtained.push((item.id, igt_ms));
+                self.unsent.push((item.id, igt_ms));
+                newly.push(item.clone());
+            }
+        }
+        newly
+    }
+
+    /// Pickups waiting to be sent, as (item_id, igt_ms)
+    pub fn take_unsent(&mut self) -> Vec<(u32, u32)> {
+        std::mem::take(&mut self.unsent)
+    }
+
+    /// Queue every pickup again after a reconnect, in case the socket dropped
+    /// one silently. The server ignores items it already recorded.
+    pub fn requeue_all(&mut self) {
+        self.unsent = self.obtained.clone();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn total(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn obtained_count(&self) -> usize {
+        self.obtained.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Vec<KeyItem> {
+        vec![
+            KeyItem {
+                id: 8107,
+                flag_id: 400001,
+                name: "Rold Medallion".to_string(),
+            },
+            KeyItem {
+                id: 8105,
+                flag_id: 400002,
+                name: "Dectus Medallion (Left)".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_pickup_reported_once() {
+        let mut watcher = KeyItemWatcher::new();
+        watcher.set_items(items());
+        assert!(watcher.poll(1000, |_| Some(false)).is_empty());
+
+        let newly = watcher.poll(2000, |flag| Some(flag == 400001));
+        assert_eq!(newly.len(), 1);
+        assert_eq!(newly[0].name, "Rold Medallion");
+        assert!(watcher.poll(3000, |flag| Some(flag == 400001)).is_empty());
+
+        assert_eq!(watcher.take_unsent(), vec![(8107, 2000)]);
+        assert!(watcher.take_unsent().is_empty());
+        assert_eq!(watcher.obtained_count(), 1);
+        assert_eq!(watcher.total(), 2);
+    }
+
+    #[test]
+    fn test_unreadable_flags_ignored() {
+        let mut watcher = KeyItemWatcher::new();
+        watcher.set_items(items());
+        assert!(watcher.poll(1000, |_| None).is_empty());
+        assert_eq!(watcher.obtained_count(), 0);
+    }
+
+    #[test]
+    fn test_requeue_after_reconnect() {
+        let mut watcher = KeyItemWatcher::new();
+        watcher.set_items(items());
+        watcher.poll(2000, |flag| Some(flag == 400001));
+        watcher.take_unsent();
+
+        // Reconnect: items are resent and pickups survive a new auth_ok
+        watcher.set_items(items());
+        watcher.requeue_all();
+        assert_eq!(watcher.take_unsent(), vec![(8107, 2000)]);
+        assert!(watcher.poll(4000, |flag| Some(flag == 400001)).is_empty());
+    }
+}
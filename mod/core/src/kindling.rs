@@ -0,0 +1,79 @@
+//! Messmer's Kindling usage
+//!
+//! DLC fog races often restrict where the kindling may be burnt, so using
+//! one is reported like a save reload. The kindling is a key item: it can't
+//! be dropped or sold, so its count only goes down when one is used. Reads
+//! taken on the title screen or during loads (inventory unreadable) are
+//! skipped, and the baseline is dropped when another character or an older
+//! save is loaded, so those don't count as uses.
+
+/// Goods ID of Messmer's Kindling (EquipParamGoods)
+pub const MESSMERS_KINDLING_ID: u32 = 2008021;
+
+/// A detected use: how many were burnt and how many are left
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KindlingUse {
+    pub igt_ms: u32,
+    pub used: u32,
+    pub remaining: u32,
+}
+
+/// Last kindling count read in game
+#[derive(Debug, Default)]
+pub struct KindlingWatch {
+    last_count: Option<u32>,
+}
+
+impl KindlingWatch {
+    /// Feed the kindling count read in game (None while unreadable)
+    pub fn observe(&mut self, count: Option<u32>, igt_ms: u32) -> Option<KindlingUse> {
+        let count = count?;
+        let previous = self.last_count.replace(count)?;
+        if count >= previous {
+            return None;
+        }
+        Some(KindlingUse {
+            igt_ms,
+            used: previous - count,
+            remaining: count,
+        })
+    }
+
+    /// Forget the baseline (new seed, another character or a save reload)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_going_down_is_a_use() {
+        let mut watch = KindlingWatch::default();
+        assert_eq!(watch.observe(Some(0), 1000), None);
+        // Picked up
+        assert_eq!(watch.observe(Some(2), 2000), None);
+        // Loading screen
+        assert_eq!(watch.observe(None, 3000), None);
+        assert_eq!(
+            watch.observe(Some(1), 4000),
+            Some(KindlingUse {
+                igt_ms: 4000,
+                used: 1,
+                remaining: 1,
+            })
+        );
+        assert_eq!(watch.observe(Some(1), 5000), None);
+    }
+
+    #[test]
+    fn test_reset_drops_baseline() {
+        let mut watch = KindlingWatch::default();
+        watch.observe(Some(2), 1000);
+        watch.reset();
+        // Another character with none isn't two uses
+        assert_eq!(watch.observe(Some(0), 2000), None);
+    }
+}
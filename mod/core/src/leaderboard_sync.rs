@@ -0,0 +1,166 @@
+//! Differential leaderboard updates
+//!
+//! From protocol 19, the server sends each connection the full leaderboard
+//! once (and again on request), then `leaderboard_delta`s that only carry the
+//! participants whose row changed, plus the new standings order. Broadcasts
+//! are numbered: a delta that doesn't follow the last generation applied
+//! means one was missed, so the mod asks for a full resync instead of
+//! patching a leaderboard it no longer has.
+
+use std::collections::HashMap;
+
+use super::protocol::ParticipantInfo;
+
+/// What became of a delta
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaResult {
+    Applied,
+    /// A generation was missed: ask the server for the full leaderboard
+    RequestResync,
+    /// Still waiting for the resync already requested
+    WaitingResync,
+}
+
+/// Generation of the leaderboard held by the mod
+#[derive(Debug, Clone, Default)]
+pub struct LeaderboardSync {
+    /// None before the first full update and after a gap
+    generation: Option<u64>,
+    resync_requested: bool,
+}
+
+impl LeaderboardSync {
+    pub fn generation(&self) -> Option<u64> {
+        self.generation
+    }
+
+    /// Full leaderboard received (no generation from servers before 19)
+    pub fn full(&mut self, generation: Option<u64>) {
+        self.generation = generation;
+        self.resync_requested = false;
+    }
+
+    /// Patch `participants` with the changed rows and reorder them as
+    /// `order` (participant ids, rows missing from it are dropped)
+    pub fn apply_delta(
+        &mut self,
+        participants: &mut Vec<ParticipantInfo>,
+        generation: u64,
+        changed: Vec<ParticipantInfo>,
+        order: &[String],
+    ) -> DeltaResult {
+        if self.generation.and_then(|g| g.checked_add(1)) != Some(generation) {
+            self.generation = None;
+            if self.resync_requested {
+                return DeltaResult::WaitingResync;
+            }
+            self.resync_requested = true;
+            return DeltaResult::RequestResync;
+        }
+        let mut rows: HashMap<String, ParticipantInfo> =
+            participants.drain(..).map(|p| (p.id.clone(), p)).collect();
+        for p in changed {
+            rows.insert(p.id.clone(), p);
+        }
+        participants.extend(order.iter().filter_map(|id| rows.remove(id)));
+        self.generation = Some(generation);
+        DeltaResult::Applied
+    }
+
+    /// New connection: the server starts over with a full update
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(id: &str, igt_ms: i32) -> ParticipantInfo {
+        ParticipantInfo {
+            id: id.to_string(),
+            twitch_username: id.to_string(),
+            twitch_display_name: None,
+            status: "playing".to_string(),
+            current_zone: None,
+            current_zone_name: None,
+            current_layer: 0,
+            current_layer_tier: None,
+            igt_ms,
+            death_count: 0,
+            gap_ms: None,
+            layer_entry_igt: None,
+            zone_entry_igt: None,
+            key_items: Vec::new(),
+            objectives_completed: Vec::new(),
+            afk: false,
+            pause: None,
+            checkpoints: Vec::new(),
+            badges: Vec::new(),
+        }
+    }
+
+    fn ids(participants: &[ParticipantInfo]) -> Vec<&str> {
+        participants.iter().map(|p| p.id.as_str()).collect()
+    }
+
+    fn order(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_delta_patches_and_reorders() {
+        let mut sync = LeaderboardSync::default();
+        let mut participants = vec![row("a", 1000), row("b", 900), row("c", 800)];
+        sync.full(Some(4));
+
+        // b overtakes a, c leaves the race
+        let result = sync.apply_delta(
+            &mut participants,
+            5,
+            vec![row("b", 2000)],
+            &order(&["b", "a"]),
+        );
+        assert_eq!(result, DeltaResult::Applied);
+        assert_eq!(ids(&participants), ["b", "a"]);
+        assert_eq!(participants[0].igt_ms, 2000);
+        assert_eq!(participants[1].igt_ms, 1000);
+        assert_eq!(sync.generation(), Some(5));
+
+        // New participant
+        sync.apply_delta(
+            &mut participants,
+            6,
+            vec![row("d", 0)],
+            &order(&["b", "a", "d"]),
+        );
+        assert_eq!(ids(&participants), ["b", "a", "d"]);
+    }
+
+    #[test]
+    fn test_gap_requests_one_resync() {
+        let mut sync = LeaderboardSync::default();
+        let mut participants = vec![row("a", 1000)];
+        sync.full(Some(4));
+
+        let missed = sync.apply_delta(&mut participants, 6, vec![row("a", 3000)], &order(&["a"]));
+        assert_eq!(missed, DeltaResult::RequestResync);
+        assert_eq!(participants[0].igt_ms, 1000);
+        let next = sync.apply_delta(&mut participants, 7, vec![], &order(&["a"]));
+        assert_eq!(next, DeltaResult::WaitingResync);
+
+        sync.full(Some(7));
+        let after = sync.apply_delta(&mut participants, 8, vec![], &order(&["a"]));
+        assert_eq!(after, DeltaResult::Applied);
+    }
+
+    #[test]
+    fn test_delta_before_full_update() {
+        let mut sync = LeaderboardSync::default();
+        let mut participants = Vec::new();
+        let result = sync.apply_delta(&mut participants, 1, vec![row("a", 0)], &order(&["a"]));
+        assert_eq!(result, DeltaResult::RequestResync);
+        assert!(participants.is_empty());
+    }
+}
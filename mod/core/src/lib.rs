@@ -0,0 +1,113 @@
+//! SpeedFog Racing core - platform-independent types
+//!
+//! Everything the mod knows that doesn't touch the game or Windows: protocol
+//! messages, settings sections, overlay layout and race bookkeeping. The DLL
+//! re-exports it as `speedfog_race_mod::core`; other tools (analyzers,
+//! viewers) can depend on this crate alone.
+//!
+//! The crate follows semver on its public items. Protocol changes that a
+//! peer must negotiate bump `protocol::PROTOCOL_VERSION`, not the crate's
+//! major version, as long as existing items keep their shape.
+//!
+//! Features:
+//! - `msgpack` (default): MessagePack wire encoding
+//! - `mock_game`: scripted game state for demos and tests without the game
+//!   (`mock_game::MockGame`)
+
+pub mod activity_feed;
+pub mod afk;
+pub mod anchor;
+pub mod aob;
+pub mod audio;
+pub mod auto_hide;
+pub mod badges;
+pub mod boss_fight;
+pub mod boss_flags;
+pub mod character;
+pub mod checkpoints;
+pub mod clock_sync;
+pub mod color;
+pub mod config_error;
+pub mod constants;
+pub mod countdown;
+pub mod death_cause;
+pub mod detection;
+pub mod detection_health;
+pub mod discovery;
+pub mod drift;
+pub mod entity_utils;
+pub mod environment;
+pub mod event_bus;
+pub mod event_delay;
+pub mod event_wal;
+pub mod exit_order;
+pub mod exit_pins;
+pub mod exit_signal;
+pub mod flag_trace;
+pub mod focus;
+pub mod format;
+pub mod frame_profiler;
+pub mod gate_proximity;
+pub mod graces;
+pub mod hint_cache;
+pub mod hold_confirm;
+pub mod icon_map;
+pub mod igt_analyzer;
+pub mod key_chord;
+pub mod key_items;
+pub mod kindling;
+pub mod leaderboard_sync;
+pub mod locale;
+pub mod map_utils;
+pub mod memory_inspector;
+pub mod message_trace;
+#[cfg(feature = "mock_game")]
+pub mod mock_game;
+pub mod module_conflicts;
+pub mod ng_cycle;
+pub mod notes;
+pub mod objectives;
+pub mod overlay_layout;
+pub mod overlay_profile;
+pub mod overlay_renderer;
+pub mod panel_cycle;
+pub mod pause;
+pub mod permissions;
+pub mod player_stats;
+pub mod position_watch;
+pub mod post_race;
+pub mod presence;
+pub mod protocol;
+pub mod race_phase;
+pub mod race_timer;
+pub mod read_audit;
+pub mod remote_flags;
+pub mod save_reload;
+pub mod scaling;
+pub mod screenshot;
+pub mod segments;
+pub mod self_test;
+pub mod splits;
+pub mod supervisor;
+pub mod theme;
+pub mod toast;
+pub mod traits;
+pub mod transport;
+pub mod types;
+pub mod ui_scale;
+pub mod warmup;
+pub mod window_layout;
+pub mod world_map;
+pub mod zone_cache;
+pub mod zone_confirm;
+pub mod zone_graph;
+pub mod zone_privacy;
+
+pub use color::parse_hex_color;
+pub use format::{compute_gap, format_gap};
+pub use map_utils::format_map_id;
+pub use protocol::{
+    ClientMessage, Encoding, ParticipantInfo, RaceInfo, SeedInfo, ServerMessage, PROTOCOL_VERSION,
+};
+pub use traits::{EventFlagSource, GameStateReader, WarpDetector};
+pub use types::PlayerPosition;
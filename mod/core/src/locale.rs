@@ -0,0 +1,265 @@
+//! Overlay localization
+//!
+//! Every user-facing overlay string (race status, toasts, warnings, settings
+//! and debug labels) is looked up by key in a locale table. English and
+//! French are built in; any other language can be added by dropping a
+//! `lang/<code>.toml` file next to the DLL, which also overrides a built-in
+//! table. Keys missing from a table fall back to English.
+//!
+//! Lang files group keys in TOML tables (`[toast] discovered = "..."` is the
+//! key `toast.discovered`). Variables are written `{name}` and are the same
+//! in every language, only their position in the sentence changes.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Language of the built-in fallback table
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Tables compiled into the DLL: (code, lang file contents)
+const BUILTIN: &[(&str, &str)] = &[
+    ("en", include_str!("../lang/en.toml")),
+    ("fr", include_str!("../lang/fr.toml")),
+];
+
+/// String table of one language, over the English fallback
+#[derive(Debug, Clone)]
+pub struct Locale {
+    code: String,
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+impl Locale {
+    pub fn english() -> Self {
+        let table = english_table();
+        Self {
+            code: DEFAULT_LANGUAGE.to_string(),
+            strings: table.clone(),
+            fallback: table,
+        }
+    }
+
+    /// Built-in table for a language code ("en", "fr")
+    pub fn builtin(code: &str) -> Option<Self> {
+        let (_, contents) = BUILTIN.iter().find(|(c, _)| c.eq_ignore_ascii_case(code))?;
+        Self::parse(code, contents).ok()
+    }
+
+    /// Table from the contents of a lang file
+    pub fn parse(code: &str, contents: &str) -> Result<Self, String> {
+        Ok(Self {
+            code: code.to_lowercase(),
+            strings: parse_table(contents)?,
+            fallback: english_table(),
+        })
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Translation of a key, None when neither this language nor English has it
+    pub fn lookup(&self, key: &str) -> Option<&str> {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map(String::as_str)
+    }
+
+    /// Translation of a key, or the key itself so a missing string is visible
+    /// rather than blank
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.lookup(key).unwrap_or(key)
+    }
+
+    /// Translation with its `{name}` variables filled in. Variables missing
+    /// from `args` are left as written.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        fill(self.get(key), args)
+    }
+
+    /// Label of a participant status ("ready", "registered"...), the raw
+    /// status when it has no translation
+    pub fn status<'a>(&'a self, status: &'a str) -> &'a str {
+        self.lookup(&format!("status.{}", status)).unwrap_or(status)
+    }
+
+    /// English keys this language doesn't translate
+    pub fn missing_keys(&self) -> Vec<&str> {
+        let mut missing: Vec<&str> = self
+            .fallback
+            .keys()
+            .filter(|key| !self.strings.contains_key(*key))
+            .map(String::as_str)
+            .collect();
+        missing.sort_unstable();
+        missing
+    }
+
+    /// Whether the strings use CJK characters, which the overlay font only
+    /// loads on demand
+    pub fn needs_cjk_glyphs(&self) -> bool {
+        self.strings.values().flat_map(|s| s.chars()).any(is_cjk)
+    }
+}
+
+fn english_table() -> HashMap<String, String> {
+    parse_table(BUILTIN[0].1).expect("built-in English table is valid")
+}
+
+/// Flatten a lang file into dotted keys
+fn parse_table(contents: &str) -> Result<HashMap<String, String>, String> {
+    let table: toml::Table =
+        toml::from_str(contents).map_err(|e| format!("Failed to parse lang file: {}", e))?;
+    let mut strings = HashMap::new();
+    flatten("", &table, &mut strings)?;
+    Ok(strings)
+}
+
+fn flatten(
+    prefix: &str,
+    table: &toml::Table,
+    out: &mut HashMap<String, String>,
+) -> Result<(), String> {
+    for (name, value) in table {
+        let key = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        match value {
+            toml::Value::String(s) => {
+                out.insert(key, s.clone());
+            }
+            toml::Value::Table(t) => flatten(&key, t, out)?,
+            _ => return Err(format!("Lang key {} is not a string", key)),
+        }
+    }
+    Ok(())
+}
+
+/// Replace `{name}` variables in a template
+pub(crate) fn fill(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after
+            .find('}')
+            .and_then(|end| args.iter().find(|(name, _)| *name == &after[..end]))
+            .map(|(name, value)| (name.len(), value));
+        match value {
+            Some((len, value)) => {
+                out.push_str(&value.to_string());
+                rest = &after[len + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `{name}` variables of a template, sorted
+#[cfg(test)]
+fn variables(template: &str) -> Vec<&str> {
+    let mut vars: Vec<&str> = template
+        .split('{')
+        .skip(1)
+        .filter_map(|s| s.split_once('}').map(|(name, _)| name))
+        .collect();
+    vars.sort_unstable();
+    vars
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3000..=0x30FF // CJK punctuation, hiragana, katakana
+        | 0x4E00..=0x9FFF // CJK ideographs
+        | 0xAC00..=0xD7AF // Hangul
+        | 0xFF00..=0xFFEF) // Fullwidth forms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_variables() {
+        let zone = "Stormveil Castle";
+        assert_eq!(
+            fill("Discovered: {zone}", &[("zone", &zone)]),
+            "Discovered: Stormveil Castle"
+        );
+        assert_eq!(
+            fill("{ready}/{total} ready", &[("total", &4), ("ready", &2)]),
+            "2/4 ready"
+        );
+        // Unknown or unclosed variables stay as written
+        assert_eq!(fill("{other} {zone", &[("zone", &zone)]), "{other} {zone");
+    }
+
+    #[test]
+    fn test_fallback_to_english() {
+        let locale = Locale::parse("xx", "[toast]\nconnected = \"Verbunden\"").unwrap();
+        assert_eq!(locale.get("toast.connected"), "Verbunden");
+        assert_eq!(locale.get("toast.disconnected"), "Disconnected");
+        assert_eq!(locale.get("no.such.key"), "no.such.key");
+        assert!(locale.missing_keys().contains(&"toast.disconnected"));
+    }
+
+    #[test]
+    fn test_status_label() {
+        let locale = Locale::builtin("fr").unwrap();
+        assert_eq!(locale.status("ready"), "prêt");
+        assert_eq!(locale.status("unknown_status"), "unknown_status");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Locale::parse("xx", "not toml [").is_err());
+        assert!(Locale::parse("xx", "count = 3").is_err());
+    }
+
+    #[test]
+    fn test_builtin_tables_complete() {
+        assert!(Locale::english().missing_keys().is_empty());
+        assert!(Locale::builtin("EN").is_some());
+        assert!(Locale::builtin("xx").is_none());
+        for (code, _) in BUILTIN {
+            let locale = Locale::builtin(code).unwrap();
+            assert_eq!(locale.missing_keys(), Vec::<&str>::new(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_builtin_variables_match_english() {
+        let english = english_table();
+        for (code, contents) in BUILTIN {
+            for (key, text) in parse_table(contents).unwrap() {
+                let reference = english
+                    .get(&key)
+                    .unwrap_or_else(|| panic!("{}: unknown key {}", code, key));
+                assert_eq!(variables(&text), variables(reference), "{}: {}", code, key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cjk_detection() {
+        assert!(!Locale::builtin("fr").unwrap().needs_cjk_glyphs());
+        let ja = Locale::parse("ja", "[toast]\nconnected = \"接続しました\"").unwrap();
+        assert!(ja.needs_cjk_glyphs());
+    }
+}
@@ -0,0 +1,272 @@
+//! Map ID utilities
+//!
+//! Functions for formatting and parsing Elden Ring map IDs.
+//! Map IDs are 32-bit values encoded as mWW_XX_YY_DD.
+//!
+//! Also the compass math for exit hints. Positions are local to their map,
+//! with Y up, +Z north and +X east; one unit is one meter.
+//!
+//! Overworld tiles (m60 base game, m61 Shadow of the Erdtree) share one grid,
+//! so their local positions can be placed on a whole-area world map.
+
+/// Format a map_id as a string "mWW_XX_YY_DD"
+///
+/// The map_id is a 32-bit value where:
+/// - WW (bits 24-31): World/region number
+/// - XX (bits 16-23): Area number
+/// - YY (bits 8-15): Sub-area number
+/// - DD (bits 0-7): Detail/variant number
+///
+/// # Examples
+///
+/// ```
+/// use speedfog_race_core::map_utils::format_map_id;
+///
+/// assert_eq!(format_map_id(0x3C2C2400), "m60_44_36_00");
+/// ```
+pub fn format_map_id(map_id: u32) -> String {
+    let ww = (map_id >> 24) & 0xFF;
+    let xx = (map_id >> 16) & 0xFF;
+    let yy = (map_id >> 8) & 0xFF;
+    let dd = map_id & 0xFF;
+    format!("m{:02}_{:02}_{:02}_{:02}", ww, xx, yy, dd)
+}
+
+/// Parse a map_id string "mWW_XX_YY_DD" back to u32
+///
+/// Returns None if the string is not a valid map_id format.
+///
+/// # Examples
+///
+/// ```
+/// use speedfog_race_core::map_utils::parse_map_id;
+///
+/// assert_eq!(parse_map_id("m60_44_36_00"), Some(0x3C2C2400));
+/// assert_eq!(parse_map_id("invalid"), None);
+/// ```
+pub fn parse_map_id(s: &str) -> Option<u32> {
+    let s = s.strip_prefix('m')?;
+    let parts: Vec<&str> = s.split('_').collect();
+    if parts.len() != 4 {
+        return None;
+    }
+
+    let ww = parts[0].parse::<u32>().ok()?;
+    let xx = parts[1].parse::<u32>().ok()?;
+    let yy = parts[2].parse::<u32>().ok()?;
+    let dd = parts[3].parse::<u32>().ok()?;
+
+    if ww > 255 || xx > 255 || yy > 255 || dd > 255 {
+        return None;
+    }
+
+    Some((ww << 24) | (xx << 16) | (yy << 8) | dd)
+}
+
+/// Compass bearing in degrees from `from` to `to` on the horizontal plane:
+/// 0 = north (+Z), 90 = east (+X), in [0, 360)
+pub fn bearing_deg(from: (f32, f32, f32), to: (f32, f32, f32)) -> f32 {
+    let dx = to.0 - from.0;
+    let dz = to.2 - from.2;
+    dx.atan2(dz).to_degrees().rem_euclid(360.0)
+}
+
+/// Arrow glyph pointing along a bearing, screen up = north (8 directions)
+pub fn compass_arrow(bearing_deg: f32) -> char {
+    const ARROWS: [char; 8] = [
+        '\u{2191}', // ↑ N
+        '\u{2197}', // ↗ NE
+        '\u{2192}', // → E
+        '\u{2198}', // ↘ SE
+        '\u{2193}', // ↓ S
+        '\u{2199}', // ↙ SW
+        '\u{2190}', // ← W
+        '\u{2196}', // ↖ NW
+    ];
+    let sector = (bearing_deg.rem_euclid(360.0) / 45.0).round() as usize % 8;
+    ARROWS[sector]
+}
+
+/// Distance and direction hint from the player to a fog gate, e.g. "120m ↗".
+///
+/// None when the gate is on another map: coordinates are map-local.
+pub fn exit_hint(
+    player_map_id: u32,
+    player: (f32, f32, f32),
+    gate_map: &str,
+    gate: [f32; 3],
+) -> Option<String> {
+    if parse_map_id(gate_map)? != player_map_id {
+        return None;
+    }
+    let gate = (gate[0], gate[1], gate[2]);
+    let dx = gate.0 - player.0;
+    let dy = gate.1 - player.1;
+    let dz = gate.2 - player.2;
+    let distance = (dx * dx + dy * dy + dz * dz).sqrt();
+    Some(format!(
+        "{}m {}",
+        distance.round() as u32,
+        compass_arrow(bearing_deg(player, gate))
+    ))
+}
+
+/// Overworld areas: m60 (Lands Between) and m61 (Realm of Shadow)
+pub const OVERWORLD_AREAS: [u8; 2] = [60, 61];
+
+/// Edge length in meters of an LOD-0 overworld tile
+const OVERWORLD_TILE_SIZE: f32 = 256.0;
+
+/// Overworld area (WW byte) of a map, or None for legacy dungeons and interiors
+pub fn overworld_area(map_id: u32) -> Option<u8> {
+    let ww = (map_id >> 24) as u8;
+    OVERWORLD_AREAS.contains(&ww).then_some(ww)
+}
+
+/// Position of a map-local point on its overworld area grid, in meters:
+/// (area, east, north), with the grid origin at the south-west corner.
+///
+/// Overworld tiles are mWW_XX_YY_DD where DD is the LOD level: a tile covers
+/// 256m × 2^DD and XX/YY count tiles of that size eastward and northward.
+/// Local coordinates are relative to the tile's south-west corner.
+pub fn overworld_position(map_id: u32, x: f32, z: f32) -> Option<(u8, f32, f32)> {
+    let area = overworld_area(map_id)?;
+    let xx = ((map_id >> 16) & 0xFF) as f32;
+    let yy = ((map_id >> 8) & 0xFF) as f32;
+    let lod = map_id & 0xFF;
+    if lod > 2 {
+        return None;
+    }
+    let tile = OVERWORLD_TILE_SIZE * (1 << lod) as f32;
+    Some((area, xx * tile + x, yy * tile + z))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_map_id_limgrave() {
+        // Limgrave: m60_44_36_00
+        assert_eq!(format_map_id(0x3C2C2400), "m60_44_36_00");
+    }
+
+    #[test]
+    fn test_format_map_id_stormveil() {
+        // Stormveil Castle area
+        assert_eq!(format_map_id(0x0A0A1000), "m10_10_16_00");
+    }
+
+    #[test]
+    fn test_format_map_id_boundaries() {
+        assert_eq!(format_map_id(0x00000000), "m00_00_00_00");
+        assert_eq!(format_map_id(0xFFFFFFFF), "m255_255_255_255");
+    }
+
+    #[test]
+    fn test_format_map_id_each_byte() {
+        // Test that each byte position is correctly extracted
+        assert_eq!(format_map_id(0x01000000), "m01_00_00_00");
+        assert_eq!(format_map_id(0x00010000), "m00_01_00_00");
+        assert_eq!(format_map_id(0x00000100), "m00_00_01_00");
+        assert_eq!(format_map_id(0x00000001), "m00_00_00_01");
+    }
+
+    #[test]
+    fn test_parse_map_id_valid() {
+        assert_eq!(parse_map_id("m60_44_36_00"), Some(0x3C2C2400));
+        assert_eq!(parse_map_id("m00_00_00_00"), Some(0x00000000));
+        assert_eq!(parse_map_id("m255_255_255_255"), Some(0xFFFFFFFF));
+    }
+
+    #[test]
+    fn test_parse_map_id_invalid() {
+        assert_eq!(parse_map_id("invalid"), None);
+        assert_eq!(parse_map_id("m60_44_36"), None); // Missing part
+        assert_eq!(parse_map_id("60_44_36_00"), None); // Missing 'm' prefix
+        assert_eq!(parse_map_id("m256_0_0_0"), None); // Value too large
+        assert_eq!(parse_map_id("m-1_0_0_0"), None); // Negative value
+        assert_eq!(parse_map_id(""), None);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let test_values = [0x3C2C2400, 0x0A0A1000, 0x00000000, 0xFFFFFFFF, 0x12345678];
+
+        for &original in &test_values {
+            let formatted = format_map_id(original);
+            let parsed = parse_map_id(&formatted);
+            assert_eq!(
+                parsed,
+                Some(original),
+                "Roundtrip failed for 0x{:08X} -> {} -> {:?}",
+                original,
+                formatted,
+                parsed
+            );
+        }
+    }
+
+    #[test]
+    fn test_bearing_cardinal_directions() {
+        let origin = (0.0, 0.0, 0.0);
+        assert!((bearing_deg(origin, (0.0, 0.0, 10.0)) - 0.0).abs() < 0.01);
+        assert!((bearing_deg(origin, (10.0, 0.0, 0.0)) - 90.0).abs() < 0.01);
+        assert!((bearing_deg(origin, (0.0, 0.0, -10.0)) - 180.0).abs() < 0.01);
+        assert!((bearing_deg(origin, (-10.0, 0.0, 0.0)) - 270.0).abs() < 0.01);
+        // Height doesn't change the bearing
+        assert!((bearing_deg(origin, (10.0, 50.0, 10.0)) - 45.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_compass_arrow_sectors() {
+        assert_eq!(compass_arrow(0.0), '\u{2191}');
+        assert_eq!(compass_arrow(44.0), '\u{2197}');
+        assert_eq!(compass_arrow(90.0), '\u{2192}');
+        assert_eq!(compass_arrow(200.0), '\u{2193}');
+        assert_eq!(compass_arrow(300.0), '\u{2196}');
+        // Wraps around north
+        assert_eq!(compass_arrow(350.0), '\u{2191}');
+        assert_eq!(compass_arrow(-10.0), '\u{2191}');
+    }
+
+    #[test]
+    fn test_exit_hint() {
+        let map = 0x3C2C2400;
+        assert_eq!(
+            exit_hint(map, (0.0, 0.0, 0.0), "m60_44_36_00", [30.0, 0.0, 40.0]),
+            Some("50m \u{2197}".to_string())
+        );
+        // Other map: coordinates aren't comparable
+        assert_eq!(
+            exit_hint(map, (0.0, 0.0, 0.0), "m10_00_00_00", [30.0, 0.0, 40.0]),
+            None
+        );
+        assert_eq!(exit_hint(map, (0.0, 0.0, 0.0), "bogus", [0.0; 3]), None);
+    }
+
+    #[test]
+    fn test_overworld_area() {
+        assert_eq!(overworld_area(0x3C2C2400), Some(60));
+        assert_eq!(overworld_area(0x3D2D2C00), Some(61));
+        // Stormveil Castle is a legacy dungeon
+        assert_eq!(overworld_area(0x0A000000), None);
+    }
+
+    #[test]
+    fn test_overworld_position() {
+        // m60_44_36_00, 10m east and 20m north of the tile corner
+        assert_eq!(
+            overworld_position(0x3C2C2400, 10.0, 20.0),
+            Some((60, 44.0 * 256.0 + 10.0, 36.0 * 256.0 + 20.0))
+        );
+        // m60_22_18_01 covers the same ground at twice the tile size
+        assert_eq!(
+            overworld_position(0x3C161201, 10.0, 20.0),
+            Some((60, 22.0 * 512.0 + 10.0, 18.0 * 512.0 + 20.0))
+        );
+        assert_eq!(overworld_position(0x0A000000, 0.0, 0.0), None);
+        // Unknown LOD level
+        assert_eq!(overworld_position(0x3C2C2407, 0.0, 0.0), None);
+    }
+}
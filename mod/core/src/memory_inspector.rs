@@ -0,0 +1,84 @@
+//! Raw memory values for the debug panel
+//!
+//! Helpers for the F3 memory inspector, used to check offsets on new game
+//! patches without attaching a debugger: recent event flag reads and
+//! animation IDs in the game's file naming.
+
+use std::collections::VecDeque;
+
+/// Number of flag reads kept for the debug panel
+pub const FLAG_READ_LOG_SIZE: usize = 10;
+
+/// One event flag read done by the tracker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlagRead {
+    pub flag_id: u32,
+    /// None when the flag manager couldn't be read
+    pub result: Option<bool>,
+}
+
+/// Most recent event flag reads, newest last
+#[derive(Debug, Default)]
+pub struct FlagReadLog {
+    reads: VecDeque<FlagRead>,
+}
+
+impl FlagReadLog {
+    pub fn record(&mut self, flag_id: u32, result: Option<bool>) {
+        if self.reads.len() == FLAG_READ_LOG_SIZE {
+            self.reads.pop_front();
+        }
+        self.reads.push_back(FlagRead { flag_id, result });
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &FlagRead> {
+        self.reads.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reads.is_empty()
+    }
+}
+
+/// Animation ID as named in the character's animation files:
+/// 60060 -> "a000_060060", 1040000 -> "a001_040000"
+pub fn animation_label(anim_id: u32) -> String {
+    format!("a{:03}_{:06}", anim_id / 1_000_000, anim_id % 1_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_read_log_keeps_last_reads() {
+        let mut log = FlagReadLog::default();
+        assert!(log.is_empty());
+        for id in 0..15 {
+            log.record(id, Some(id % 2 == 0));
+        }
+        let ids: Vec<u32> = log.iter().map(|r| r.flag_id).collect();
+        assert_eq!(ids, (5..15).collect::<Vec<_>>());
+        assert_eq!(
+            log.iter().last(),
+            Some(&FlagRead {
+                flag_id: 14,
+                result: Some(true)
+            })
+        );
+    }
+
+    #[test]
+    fn test_flag_read_log_unreadable() {
+        let mut log = FlagReadLog::default();
+        log.record(6, None);
+        assert_eq!(log.iter().next().unwrap().result, None);
+    }
+
+    #[test]
+    fn test_animation_label() {
+        assert_eq!(animation_label(60060), "a000_060060");
+        assert_eq!(animation_label(0), "a000_000000");
+        assert_eq!(animation_label(1_040_000), "a001_040000");
+    }
+}
@@ -0,0 +1,204 @@
+//! WebSocket message trace
+//!
+//! The websocket worker records every frame it sends or receives (pings and
+//! clock sync probes included) into a ring buffer of the last `CAPACITY`
+//! messages. The debug window lists them and can export them to a text file
+//! next to the DLL, to attach to a bug report.
+
+use std::collections::VecDeque;
+
+use chrono::DateTime;
+
+use super::post_race::file_safe_id;
+
+/// Messages kept, oldest dropped first
+pub const CAPACITY: usize = 200;
+
+/// Longer payloads (auth_ok, leaderboards) are cut to this many characters
+pub const MAX_PAYLOAD_CHARS: usize = 500;
+
+/// Bytes shown for binary (msgpack) frames
+const BINARY_PREVIEW_BYTES: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+impl Direction {
+    pub fn arrow(self) -> &'static str {
+        match self {
+            Direction::Sent => ">>",
+            Direction::Received => "<<",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TracedMessage {
+    pub direction: Direction,
+    /// Local wall clock when the frame was sent or read
+    pub unix_ms: i64,
+    /// Text payload, or a hex preview of a binary one, truncated
+    pub payload: String,
+}
+
+impl TracedMessage {
+    /// `HH:MM:SS.mmm` (UTC)
+    pub fn time_label(&self) -> String {
+        DateTime::from_timestamp_millis(self.unix_ms)
+            .map(|t| t.format("%H:%M:%S%.3f").to_string())
+            .unwrap_or_else(|| self.unix_ms.to_string())
+    }
+}
+
+#[derive(Debug)]
+pub struct MessageTrace {
+    messages: VecDeque<TracedMessage>,
+    capacity: usize,
+}
+
+impl MessageTrace {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            messages: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn record_text(&mut self, direction: Direction, unix_ms: i64, text: &str) {
+        self.push(direction, unix_ms, truncate_payload(text));
+    }
+
+    pub fn record_binary(&mut self, direction: Direction, unix_ms: i64, bytes: &[u8]) {
+        let hex: Vec<String> = bytes
+            .iter()
+            .take(BINARY_PREVIEW_BYTES)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let more = if bytes.len() > BINARY_PREVIEW_BYTES {
+            " \u{2026}"
+        } else {
+            ""
+        };
+        let payload = format!("[msgpack {} bytes] {}{}", bytes.len(), hex.join(" "), more);
+        self.push(direction, unix_ms, payload);
+    }
+
+    fn push(&mut self, direction: Direction, unix_ms: i64, payload: String) {
+        if self.messages.len() >= self.capacity {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(TracedMessage {
+            direction,
+            unix_ms,
+            payload,
+        });
+    }
+
+    /// Oldest first
+    pub fn messages(&self) -> impl DoubleEndedIterator<Item = &TracedMessage> {
+        self.messages.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// One line per message, oldest first: `12:34:56.789 >> {"type":"ready"}`
+    pub fn export(&self) -> String {
+        let mut out = String::new();
+        for msg in &self.messages {
+            out.push_str(&format!(
+                "{} {} {}\n",
+                msg.time_label(),
+                msg.direction.arrow(),
+                msg.payload
+            ));
+        }
+        out
+    }
+
+    /// Export file name, e.g. `speedfog_trace_<race_id>.txt`
+    pub fn file_name(race_id: &str) -> String {
+        format!("speedfog_trace_{}.txt", file_safe_id(race_id))
+    }
+}
+
+impl Default for MessageTrace {
+    fn default() -> Self {
+        Self::with_capacity(CAPACITY)
+    }
+}
+
+fn truncate_payload(text: &str) -> String {
+    match text.char_indices().nth(MAX_PAYLOAD_CHARS) {
+        Some((cut, _)) => format!("{}\u{2026}", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_drops_oldest() {
+        let mut trace = MessageTrace::with_capacity(3);
+        for i in 0..5 {
+            trace.record_text(Direction::Sent, i, &format!("msg{}", i));
+        }
+        assert_eq!(trace.len(), 3);
+        let payloads: Vec<&str> = trace.messages().map(|m| m.payload.as_str()).collect();
+        assert_eq!(payloads, vec!["msg2", "msg3", "msg4"]);
+    }
+
+    #[test]
+    fn test_truncates_payload() {
+        let mut trace = MessageTrace::default();
+        let long = "é".repeat(MAX_PAYLOAD_CHARS + 10);
+        trace.record_text(Direction::Received, 0, &long);
+        let payload = &trace.messages().next().unwrap().payload;
+        assert_eq!(payload.chars().count(), MAX_PAYLOAD_CHARS + 1);
+        assert!(payload.ends_with('\u{2026}'));
+
+        trace.record_text(Direction::Received, 0, "short");
+        assert_eq!(trace.messages().last().unwrap().payload, "short");
+    }
+
+    #[test]
+    fn test_binary_preview() {
+        let mut trace = MessageTrace::default();
+        trace.record_binary(Direction::Received, 0, &[0x81, 0xa4]);
+        assert_eq!(
+            trace.messages().next().unwrap().payload,
+            "[msgpack 2 bytes] 81 a4"
+        );
+        trace.record_binary(Direction::Received, 0, &[0u8; 100]);
+        let payload = &trace.messages().last().unwrap().payload;
+        assert!(payload.starts_with("[msgpack 100 bytes] 00 00"));
+        assert!(payload.ends_with(" \u{2026}"));
+    }
+
+    #[test]
+    fn test_export() {
+        let mut trace = MessageTrace::default();
+        assert_eq!(trace.export(), "");
+        // 2026-01-01 12:34:56.789 UTC
+        trace.record_text(Direction::Sent, 1_767_270_896_789, r#"{"type":"ready"}"#);
+        trace.record_text(Direction::Received, 1_767_270_897_000, r#"{"type":"ping"}"#);
+        assert_eq!(
+            trace.export(),
+            "12:34:56.789 >> {\"type\":\"ready\"}\n12:34:57.000 << {\"type\":\"ping\"}\n"
+        );
+        assert_eq!(
+            MessageTrace::file_name("race/1"),
+            "speedfog_trace_race_1.txt"
+        );
+    }
+}
@@ -0,0 +1,353 @@
+//! Scripted stand-in for Elden Ring
+//!
+//! `MockGame` plays a scenario file instead of reading the game's memory, so
+//! race logic can be demoed and tested on Linux and in CI without owning the
+//! game. A scenario is a list of timed steps, each changing part of the game
+//! state (map, position, loading screen, deaths, event flags, warps); the
+//! clock only moves when the caller advances it, so a run is deterministic.
+//!
+//! ```toml
+//! [[step]]
+//! at_ms = 0
+//! map_id = "m10_00_00_00"
+//! position = [-100.0, 50.0, 20.0]
+//!
+//! [[step]]
+//! at_ms = 5000
+//! loading = true
+//! set_flags = [1040292800]
+//! ```
+//!
+//! Requires the `mock_game` feature.
+
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use super::map_utils::parse_map_id;
+use super::traits::{EventFlagSource, GameStateReader, WarpDetector};
+use super::types::PlayerPosition;
+
+/// One change of the game state, applied once the clock reaches `at_ms`.
+/// Fields left out keep their previous value.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    /// Milliseconds since the start of the scenario
+    pub at_ms: u64,
+    /// Map of the player, e.g. "m10_00_00_00"
+    #[serde(default)]
+    pub map_id: Option<String>,
+    #[serde(default)]
+    pub position: Option<[f32; 3]>,
+    #[serde(default)]
+    pub play_region_id: Option<u32>,
+    #[serde(default)]
+    pub animation: Option<u32>,
+    /// Enter (true) or leave (false) a loading screen
+    #[serde(default)]
+    pub loading: Option<bool>,
+    /// Reset the in-game time, which otherwise runs outside loading screens
+    #[serde(default)]
+    pub igt_ms: Option<u32>,
+    #[serde(default)]
+    pub deaths: Option<u32>,
+    #[serde(default)]
+    pub set_flags: Vec<u32>,
+    #[serde(default)]
+    pub clear_flags: Vec<u32>,
+    /// Fast travel to this grace
+    #[serde(default)]
+    pub grace_entity_id: Option<u32>,
+}
+
+/// A scenario file
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    #[serde(default, rename = "step")]
+    pub steps: Vec<Step>,
+}
+
+impl Scenario {
+    /// Parse a scenario, with its steps sorted by time
+    pub fn from_toml(content: &str) -> Result<Self, toml::de::Error> {
+        let mut scenario: Scenario = toml::from_str(content)?;
+        scenario.steps.sort_by_key(|step| step.at_ms);
+        Ok(scenario)
+    }
+
+    /// Time of the last step
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.steps.last().map_or(0, |step| step.at_ms))
+    }
+
+    /// Every flag the scenario sets, e.g. to know which ones to watch
+    pub fn flags(&self) -> Vec<u32> {
+        let flags: BTreeSet<u32> = self
+            .steps
+            .iter()
+            .flat_map(|step| step.set_flags.iter().copied())
+            .collect();
+        flags.into_iter().collect()
+    }
+}
+
+/// Game state played from a `Scenario`
+#[derive(Debug)]
+pub struct MockGame {
+    scenario: Scenario,
+    /// Steps applied so far
+    next_step: usize,
+    now_ms: u64,
+    map_id: Option<u32>,
+    position: [f32; 3],
+    play_region_id: Option<u32>,
+    animation: Option<u32>,
+    loading: bool,
+    igt_ms: u32,
+    deaths: u32,
+    flags: BTreeSet<u32>,
+    grace_entity_id: Cell<Option<u32>>,
+}
+
+impl MockGame {
+    pub fn new(mut scenario: Scenario) -> Self {
+        scenario.steps.sort_by_key(|step| step.at_ms);
+        let mut game = Self {
+            scenario,
+            next_step: 0,
+            now_ms: 0,
+            map_id: None,
+            position: [0.0; 3],
+            play_region_id: None,
+            animation: None,
+            loading: false,
+            igt_ms: 0,
+            deaths: 0,
+            flags: BTreeSet::new(),
+            grace_entity_id: Cell::new(None),
+        };
+        game.apply_due_steps();
+        game
+    }
+
+    /// Move the clock forward, applying the steps reached on the way
+    pub fn advance(&mut self, elapsed: Duration) {
+        let target = self.now_ms + elapsed.as_millis() as u64;
+        while let Some(at_ms) = self.scenario.steps.get(self.next_step).map(|s| s.at_ms) {
+            if at_ms > target {
+                break;
+            }
+            self.run_clock(at_ms);
+            self.apply_due_steps();
+        }
+        self.run_clock(target);
+    }
+
+    /// Milliseconds since the start of the scenario
+    pub fn now_ms(&self) -> u64 {
+        self.now_ms
+    }
+
+    /// Every step has been applied
+    pub fn is_finished(&self) -> bool {
+        self.next_step >= self.scenario.steps.len()
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loading
+    }
+
+    pub fn read_igt(&self) -> Option<u32> {
+        Some(self.igt_ms)
+    }
+
+    pub fn read_deaths(&self) -> Option<u32> {
+        Some(self.deaths)
+    }
+
+    pub fn scenario(&self) -> &Scenario {
+        &self.scenario
+    }
+
+    fn run_clock(&mut self, to_ms: u64) {
+        if !self.loading {
+            let elapsed = to_ms.saturating_sub(self.now_ms);
+            self.igt_ms = self.igt_ms.saturating_add(elapsed as u32);
+        }
+        self.now_ms = self.now_ms.max(to_ms);
+    }
+
+    fn apply_due_steps(&mut self) {
+        while let Some(step) = self.scenario.steps.get(self.next_step) {
+            if step.at_ms > self.now_ms {
+                break;
+            }
+            let step = step.clone();
+            self.apply(&step);
+            self.next_step += 1;
+        }
+    }
+
+    fn apply(&mut self, step: &Step) {
+        if let Some(map_id) = step.map_id.as_deref() {
+            self.map_id = parse_map_id(map_id);
+        }
+        if let Some(position) = step.position {
+            self.position = position;
+        }
+        if step.play_region_id.is_some() {
+            self.play_region_id = step.play_region_id;
+        }
+        if step.animation.is_some() {
+            self.animation = step.animation;
+        }
+        if let Some(loading) = step.loading {
+            self.loading = loading;
+        }
+        if let Some(igt_ms) = step.igt_ms {
+            self.igt_ms = igt_ms;
+        }
+        if let Some(deaths) = step.deaths {
+            self.deaths = deaths;
+        }
+        self.flags.extend(&step.set_flags);
+        for flag in &step.clear_flags {
+            self.flags.remove(flag);
+        }
+        if step.grace_entity_id.is_some() {
+            self.grace_entity_id.set(step.grace_entity_id);
+        }
+    }
+}
+
+impl GameStateReader for MockGame {
+    fn wait_for_game_loaded(&self) {
+        // The scenario starts in game
+    }
+
+    fn read_position(&self) -> Option<PlayerPosition> {
+        if self.loading {
+            return None;
+        }
+        let [x, y, z] = self.position;
+        self.map_id
+            .map(|map_id| PlayerPosition::new(map_id, x, y, z, self.play_region_id))
+    }
+
+    fn read_animation(&self) -> Option<u32> {
+        self.animation.filter(|_| !self.loading)
+    }
+}
+
+impl EventFlagSource for MockGame {
+    fn is_flag_set(&self, flag_id: u32) -> Option<bool> {
+        Some(self.flags.contains(&flag_id))
+    }
+}
+
+impl WarpDetector for MockGame {
+    fn captured_grace_entity_id(&self) -> Option<u32> {
+        self.grace_entity_id.get()
+    }
+
+    fn clear_captured_warps(&self) {
+        self.grace_entity_id.set(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENARIO: &str = r#"
+        [[step]]
+        at_ms = 0
+        map_id = "m10_00_00_00"
+        position = [1.0, 2.0, 3.0]
+
+        [[step]]
+        at_ms = 5000
+        loading = true
+        set_flags = [1040292800]
+        grace_entity_id = 11052950
+
+        [[step]]
+        at_ms = 8000
+        loading = false
+        map_id = "m11_05_00_00"
+        position = [4.0, 5.0, 6.0]
+        deaths = 1
+    "#;
+
+    fn game() -> MockGame {
+        MockGame::new(Scenario::from_toml(SCENARIO).unwrap())
+    }
+
+    #[test]
+    fn test_scenario_plays_in_order() {
+        let mut game = game();
+        let position = game.read_position().unwrap();
+        assert_eq!(position.map_id_str, "m10_00_00_00");
+        assert_eq!(game.is_flag_set(1040292800), Some(false));
+
+        game.advance(Duration::from_millis(4999));
+        assert!(!game.is_loading());
+        game.advance(Duration::from_millis(1));
+        assert!(game.is_loading());
+        assert_eq!(game.read_position(), None);
+        assert_eq!(game.is_flag_set(1040292800), Some(true));
+        assert_eq!(game.captured_grace_entity_id(), Some(11052950));
+
+        game.advance(Duration::from_secs(10));
+        assert!(game.is_finished());
+        let position = game.read_position().unwrap();
+        assert_eq!(position.map_id_str, "m11_05_00_00");
+        assert_eq!(position.x, 4.0);
+        assert_eq!(game.read_deaths(), Some(1));
+    }
+
+    #[test]
+    fn test_igt_pauses_while_loading() {
+        let mut game = game();
+        game.advance(Duration::from_secs(12));
+        // 5s before the loading screen, 4s after it
+        assert_eq!(game.read_igt(), Some(9000));
+        assert_eq!(game.now_ms(), 12000);
+    }
+
+    #[test]
+    fn test_warps_cleared() {
+        let mut game = game();
+        game.advance(Duration::from_secs(6));
+        game.clear_captured_warps();
+        assert_eq!(game.captured_grace_entity_id(), None);
+    }
+
+    #[test]
+    fn test_steps_sorted_and_flags_listed() {
+        let scenario = Scenario::from_toml(
+            r#"
+            [[step]]
+            at_ms = 2000
+            set_flags = [20, 10]
+
+            [[step]]
+            at_ms = 1000
+            set_flags = [10]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(scenario.steps[0].at_ms, 1000);
+        assert_eq!(scenario.duration(), Duration::from_secs(2));
+        assert_eq!(scenario.flags(), vec![10, 20]);
+    }
+
+    #[test]
+    fn test_unknown_field_rejected() {
+        assert!(Scenario::from_toml("[[step]]\nat_ms = 0\nteleport = true\n").is_err());
+    }
+}
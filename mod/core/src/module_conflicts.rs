@@ -0,0 +1,174 @@
+//! Conflicting mods loaded in the game
+//!
+//! Some DLLs are known to break the race mod: other overlays hook the same
+//! DX12 present / command queue calls (the overlay flickers, disappears or
+//! the game crashes), some mods rewrite the game structures the mod reads,
+//! and an older copy of the tracker reports progress twice. The DLL lists the
+//! modules loaded in the game at startup and checks them against this list,
+//! so the player is warned before the race rather than after a broken run.
+
+use std::fmt;
+
+/// Prefix of the race mod's DLL names, current and older packagings
+pub const TRACKER_PREFIX: &str = "speedfog_race";
+
+/// What a conflicting module breaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// Hooks DX12 presentation: the overlay may vanish or crash the game
+    OverlayHook,
+    /// Changes the game memory the mod reads (event flags, player data)
+    MemoryLayout,
+    /// Another copy of the race mod
+    OtherTracker,
+}
+
+impl ConflictKind {
+    /// What goes wrong, for the log
+    pub fn description(self) -> &'static str {
+        match self {
+            ConflictKind::OverlayHook => "hooks DX12 presentation, the overlay may break",
+            ConflictKind::MemoryLayout => "changes game memory, progress tracking may break",
+            ConflictKind::OtherTracker => "another race mod copy, progress would be sent twice",
+        }
+    }
+}
+
+/// A DLL known to conflict with the race mod
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KnownConflict {
+    /// Module file name, compared case-insensitively
+    pub module: &'static str,
+    /// Name shown to the player
+    pub name: &'static str,
+    pub kind: ConflictKind,
+}
+
+/// DLLs known to break the DX12 hook or the memory reads
+pub const KNOWN_CONFLICTS: &[KnownConflict] = &[
+    KnownConflict {
+        module: "jdsd_er_practice_tool.dll",
+        name: "Practice Tool",
+        kind: ConflictKind::OverlayHook,
+    },
+    KnownConflict {
+        module: "RTSSHooks64.dll",
+        name: "RivaTuner Statistics Server",
+        kind: ConflictKind::OverlayHook,
+    },
+    KnownConflict {
+        module: "ReShade64.dll",
+        name: "ReShade",
+        kind: ConflictKind::OverlayHook,
+    },
+    KnownConflict {
+        module: "ersc.dll",
+        name: "Seamless Co-op",
+        kind: ConflictKind::MemoryLayout,
+    },
+];
+
+/// A module loaded in the game process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedModule {
+    /// File name, without the directory
+    pub name: String,
+    /// The race mod's own DLL
+    pub own: bool,
+}
+
+/// A conflicting module found loaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub module: String,
+    pub name: String,
+    pub kind: ConflictKind,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.module)
+    }
+}
+
+/// Known conflicts among the loaded modules, in load order
+pub fn find_conflicts(modules: &[LoadedModule]) -> Vec<Conflict> {
+    let mut conflicts: Vec<Conflict> = Vec::new();
+    for module in modules.iter().filter(|m| !m.own) {
+        let name = module.name.to_ascii_lowercase();
+        let conflict = if name.starts_with(TRACKER_PREFIX) && name.ends_with(".dll") {
+            Conflict {
+                module: module.name.clone(),
+                name: "SpeedFog Racing".to_string(),
+                kind: ConflictKind::OtherTracker,
+            }
+        } else if let Some(known) = KNOWN_CONFLICTS
+            .iter()
+            .find(|known| module.name.eq_ignore_ascii_case(known.module))
+        {
+            Conflict {
+                module: module.name.clone(),
+                name: known.name.to_string(),
+                kind: known.kind,
+            }
+        } else {
+            continue;
+        };
+        if !conflicts
+            .iter()
+            .any(|c| c.module.eq_ignore_ascii_case(&conflict.module))
+        {
+            conflicts.push(conflict);
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loaded(names: &[&str]) -> Vec<LoadedModule> {
+        names
+            .iter()
+            .map(|name| LoadedModule {
+                name: name.to_string(),
+                own: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_no_conflicts() {
+        let modules = loaded(&[
+            "eldenring.exe",
+            "ntdll.dll",
+            "dxgi.dll",
+            "RandomizerHelper.dll",
+        ]);
+        assert!(find_conflicts(&modules).is_empty());
+    }
+
+    #[test]
+    fn test_known_conflicts() {
+        let modules = loaded(&["eldenring.exe", "rtsshooks64.dll", "ERSC.dll", "ersc.dll"]);
+        let conflicts = find_conflicts(&modules);
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].kind, ConflictKind::OverlayHook);
+        assert_eq!(
+            conflicts[0].to_string(),
+            "RivaTuner Statistics Server (rtsshooks64.dll)"
+        );
+        assert_eq!(conflicts[1].kind, ConflictKind::MemoryLayout);
+    }
+
+    #[test]
+    fn test_other_tracker_copy() {
+        let mut modules = loaded(&["speedfog_race.dll", "speedfog_race_mod.dll"]);
+        modules[1].own = true;
+        let conflicts = find_conflicts(&modules);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].module, "speedfog_race.dll");
+        assert_eq!(conflicts[0].kind, ConflictKind::OtherTracker);
+    }
+}
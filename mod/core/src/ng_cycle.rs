@@ -0,0 +1,101 @@
+//! NG+ cycle detection
+//!
+//! Completing the game moves the character into NG+, which breaks most fog
+//! randomizer seeds (bosses and events are reset, enemy scaling changes). The
+//! clear count in GameDataMan goes up when the game is completed.
+
+/// NG+ state worth warning about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NgEvent {
+    /// First read: the character is already past its first playthrough
+    AlreadyNgPlus { clear_count: u32 },
+    /// The clear count went up during the session (game completed)
+    Entered { clear_count: u32 },
+}
+
+impl NgEvent {
+    pub fn clear_count(self) -> u32 {
+        match self {
+            NgEvent::AlreadyNgPlus { clear_count } | NgEvent::Entered { clear_count } => {
+                clear_count
+            }
+        }
+    }
+}
+
+/// Last clear count read from memory
+#[derive(Debug, Default)]
+pub struct NgCycleWatch {
+    clear_count: Option<u32>,
+}
+
+impl NgCycleWatch {
+    /// Record the clear count read this poll. A lower count (another
+    /// character loaded) only resets the baseline.
+    pub fn observe(&mut self, clear_count: u32) -> Option<NgEvent> {
+        let event = match self.clear_count {
+            None if clear_count > 0 => Some(NgEvent::AlreadyNgPlus { clear_count }),
+            Some(previous) if clear_count > previous => Some(NgEvent::Entered { clear_count }),
+            _ => None,
+        };
+        self.clear_count = Some(clear_count);
+        event
+    }
+
+    pub fn clear_count(&self) -> Option<u32> {
+        self.clear_count
+    }
+}
+
+/// Playthrough label: "NG", "NG+", "NG+2", ...
+pub fn ng_label(clear_count: u32) -> String {
+    match clear_count {
+        0 => "NG".to_string(),
+        1 => "NG+".to_string(),
+        n => format!("NG+{}", n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_playthrough_is_silent() {
+        let mut watch = NgCycleWatch::default();
+        assert_eq!(watch.observe(0), None);
+        assert_eq!(watch.observe(0), None);
+        assert_eq!(watch.clear_count(), Some(0));
+    }
+
+    #[test]
+    fn test_game_completion_detected() {
+        let mut watch = NgCycleWatch::default();
+        watch.observe(0);
+        assert_eq!(watch.observe(1), Some(NgEvent::Entered { clear_count: 1 }));
+        assert_eq!(watch.observe(1), None);
+    }
+
+    #[test]
+    fn test_already_ng_plus_at_load() {
+        let mut watch = NgCycleWatch::default();
+        let event = watch.observe(2);
+        assert_eq!(event, Some(NgEvent::AlreadyNgPlus { clear_count: 2 }));
+        assert_eq!(event.map(NgEvent::clear_count), Some(2));
+    }
+
+    #[test]
+    fn test_lower_count_resets_baseline() {
+        let mut watch = NgCycleWatch::default();
+        watch.observe(3);
+        assert_eq!(watch.observe(0), None);
+        assert_eq!(watch.observe(1), Some(NgEvent::Entered { clear_count: 1 }));
+    }
+
+    #[test]
+    fn test_ng_label() {
+        assert_eq!(ng_label(0), "NG");
+        assert_eq!(ng_label(1), "NG+");
+        assert_eq!(ng_label(7), "NG+7");
+    }
+}
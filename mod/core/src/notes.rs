@@ -0,0 +1,116 @@
+//! Personal zone notes
+//!
+//! Short notes the player writes about a zone ("fog gate behind the illusory
+//! wall in the kitchen") while racing a seed, persisted next to the DLL and
+//! shown again whenever the zone is entered on the same seed.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Longest note accepted, in bytes (the overlay shows it on a few lines)
+pub const MAX_NOTE_LEN: usize = 200;
+
+/// Contents of the notes file: notes keyed by seed_id, then node_id
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotesFile {
+    #[serde(default)]
+    pub seeds: HashMap<String, HashMap<String, String>>,
+}
+
+impl NotesFile {
+    pub const FILENAME: &'static str = "speedfog_notes.json";
+
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        serde_json::from_str(contents).map_err(|e| format!("Failed to parse notes: {}", e))
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize notes: {}", e))
+    }
+
+    pub fn note(&self, seed_id: &str, node_id: &str) -> Option<&str> {
+        self.seeds.get(seed_id)?.get(node_id).map(String::as_str)
+    }
+
+    /// Store the note of a zone, trimmed to `MAX_NOTE_LEN`. A blank note
+    /// deletes it. Returns true if the notes changed.
+    pub fn set(&mut self, seed_id: &str, node_id: &str, text: &str) -> bool {
+        let text = truncate(text.trim(), MAX_NOTE_LEN);
+        if text.is_empty() {
+            let Some(nodes) = self.seeds.get_mut(seed_id) else {
+                return false;
+            };
+            let removed = nodes.remove(node_id).is_some();
+            if nodes.is_empty() {
+                self.seeds.remove(seed_id);
+            }
+            return removed;
+        }
+        if self.note(seed_id, node_id) == Some(text) {
+            return false;
+        }
+        self.seeds
+            .entry(seed_id.to_string())
+            .or_default()
+            .insert(node_id.to_string(), text.to_string());
+        true
+    }
+}
+
+/// `text` cut to at most `max` bytes on a char boundary
+fn truncate(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text[..end].trim_end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_lookup() {
+        let mut notes = NotesFile::default();
+        assert!(notes.set("seed-1", "stormveil", "  illusory wall in the kitchen "));
+        assert_eq!(
+            notes.note("seed-1", "stormveil"),
+            Some("illusory wall in the kitchen")
+        );
+        assert_eq!(notes.note("seed-2", "stormveil"), None);
+        // Unchanged
+        assert!(!notes.set("seed-1", "stormveil", "illusory wall in the kitchen"));
+    }
+
+    #[test]
+    fn test_blank_note_deletes() {
+        let mut notes = NotesFile::default();
+        notes.set("seed-1", "stormveil", "note");
+        assert!(notes.set("seed-1", "stormveil", "   "));
+        assert_eq!(notes.note("seed-1", "stormveil"), None);
+        assert!(notes.seeds.is_empty());
+        assert!(!notes.set("seed-1", "stormveil", ""));
+    }
+
+    #[test]
+    fn test_long_note_truncated() {
+        let mut notes = NotesFile::default();
+        notes.set("seed-1", "caelid", &"é".repeat(MAX_NOTE_LEN));
+        let note = notes.note("seed-1", "caelid").unwrap();
+        assert_eq!(note.len(), MAX_NOTE_LEN);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut notes = NotesFile::default();
+        notes.set("seed-1", "stormveil", "note");
+        let parsed = NotesFile::parse(&notes.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, notes);
+        assert!(NotesFile::parse("not json").is_err());
+    }
+}
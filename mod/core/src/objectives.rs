@@ -0,0 +1,148 @@
+//! Race objectives
+//!
+//! Bingo / scavenger races list custom objectives in auth_ok, each completed
+//! when its event flag is set. Like key item pickups, completion flags stay
+//! set, so polling them also catches objectives completed while disconnected.
+
+use super::protocol::Objective;
+
+#[derive(Debug, Default)]
+pub struct ObjectiveTracker {
+    objectives: Vec<Objective>,
+    /// (objective_id, igt_ms) in completion order
+    completed: Vec<(u32, u32)>,
+    /// Completions not sent to the server yet
+    unsent: Vec<(u32, u32)>,
+}
+
+impl ObjectiveTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Objectives of the race (from auth_ok). Completions already seen are kept.
+    pub fn set_objectives(&mut self, objectives: Vec<Objective>) {
+        self.objectives = objectives;
+    }
+
+    /// Check the completion flags and return the newly completed objectives,
+    /// which are also queued for sending. `is_flag_set` returns None when unreadable.
+    pub fn poll(
+        &mut self,
+        igt_ms: u32,
+        mut is_flag_set: impl FnMut(u32) -> Option<bool>,
+    ) -> Vec<Objective> {
+        let mut newly = Vec::new();
+        for objective in &self.objectives {
+            if self.is_completed(objective.id) {
+                continue;
+            }
+            if is_flag_set(objective.flag_id) == Some(true) {
+                self.completed.push((objective.id, igt_ms));
+                self.unsent.push((objective.id, igt_ms));
+                newly.push(objective.clone());
+            }
+        }
+        newly
+    }
+
+    /// Completions waiting to be sent, as (objective_id, igt_ms)
+    pub fn take_unsent(&mut self) -> Vec<(u32, u32)> {
+        std::mem::take(&mut self.unsent)
+    }
+
+    /// Queue every completion again after a reconnect, in case the socket
+    /// dropped one silently. The server ignores objectives it already recorded.
+    pub fn requeue_all(&mut self) {
+        self.unsent = self.completed.clone();
+    }
+
+    pub fn is_completed(&self, objective_id: u32) -> bool {
+        self.completed.iter().any(|&(id, _)| id == objective_id)
+    }
+
+    /// Objectives in race order, with their completion state
+    pub fn objectives(&self) -> impl Iterator<Item = (&Objective, bool)> {
+        self.objectives.iter().map(|o| (o, self.is_completed(o.id)))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objectives.is_empty()
+    }
+
+    pub fn total(&self) -> usize {
+        self.objectives.len()
+    }
+
+    pub fn completed_count(&self) -> usize {
+        self.completed.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn objectives() -> Vec<Objective> {
+        vec![
+            Objective {
+                id: 0,
+                text: "Kill Margit".to_string(),
+                flag_id: 10000800,
+            },
+            Objective {
+                id: 2,
+                text: "Reach Leyndell".to_string(),
+                flag_id: 11000000,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_completion_reported_once() {
+        let mut tracker = ObjectiveTracker::new();
+        tracker.set_objectives(objectives());
+        assert!(tracker.poll(1000, |_| Some(false)).is_empty());
+
+        let newly = tracker.poll(2000, |flag| Some(flag == 11000000));
+        assert_eq!(newly.len(), 1);
+        assert_eq!(newly[0].text, "Reach Leyndell");
+        assert!(tracker.poll(3000, |flag| Some(flag == 11000000)).is_empty());
+
+        assert_eq!(tracker.take_unsent(), vec![(2, 2000)]);
+        assert!(tracker.take_unsent().is_empty());
+        assert_eq!(tracker.completed_count(), 1);
+        assert_eq!(tracker.total(), 2);
+    }
+
+    #[test]
+    fn test_objectives_in_race_order() {
+        let mut tracker = ObjectiveTracker::new();
+        tracker.set_objectives(objectives());
+        tracker.poll(2000, |flag| Some(flag == 11000000));
+        let states: Vec<(u32, bool)> = tracker.objectives().map(|(o, done)| (o.id, done)).collect();
+        assert_eq!(states, vec![(0, false), (2, true)]);
+    }
+
+    #[test]
+    fn test_unreadable_flags_ignored() {
+        let mut tracker = ObjectiveTracker::new();
+        tracker.set_objectives(objectives());
+        assert!(tracker.poll(1000, |_| None).is_empty());
+        assert_eq!(tracker.completed_count(), 0);
+    }
+
+    #[test]
+    fn test_requeue_after_reconnect() {
+        let mut tracker = ObjectiveTracker::new();
+        tracker.set_objectives(objectives());
+        tracker.poll(2000, |flag| Some(flag == 10000800));
+        tracker.take_unsent();
+
+        // Reconnect: completions are resent and survive a new auth_ok
+        tracker.set_objectives(objectives());
+        tracker.requeue_all();
+        assert_eq!(tracker.take_unsent(), vec![(0, 2000)]);
+        assert!(tracker.poll(4000, |flag| Some(flag == 10000800)).is_empty());
+    }
+}
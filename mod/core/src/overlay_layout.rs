@@ -0,0 +1,509 @@
+//! Layout of the player status and exit panels
+//!
+//! The tracker resolves what to show (texts, colors) into a view; these
+//! functions place it through an `OverlayRenderer`, so truncation and
+//! alignment can be tested without imgui.
+
+use super::icon_map::IconUv;
+use super::overlay_renderer::{truncate_to_width, wrap_text, OverlayRenderer, Rgba, TextureHandle};
+use super::protocol::ExitInfo;
+
+/// Gap between the death icon and the death count
+const ICON_GAP: f32 = 2.0;
+
+/// Everything the player status lines show
+#[derive(Debug, Clone)]
+pub struct PlayerStatusView<'a> {
+    /// Three lines (false: minimal profile, IGT and zone name only)
+    pub show_details: bool,
+    /// Connection dot color
+    pub dot_color: Rgba,
+    pub race_name: &'a str,
+    pub name_color: Rgba,
+    pub igt: &'a str,
+    pub igt_color: Rgba,
+    pub zone_name: Option<&'a str>,
+    pub zone_color: Rgba,
+    /// Personal best delta on arrival in this zone, left of the progress
+    pub pb_delta: Option<(String, Rgba)>,
+    /// Layer progress "X/Y", or the participant status before the start
+    pub progress: (String, Rgba),
+    /// Tier line ("tier 3, previously 2"), empty when unknown
+    pub tier: (String, Rgba),
+    pub deaths: u32,
+    pub deaths_color: Rgba,
+    pub death_icon: Option<(TextureHandle, IconUv)>,
+    /// Death icon size, relative to the line height
+    pub icon_scale: f32,
+    /// Tooltip of the death icon and count (interactive mode only)
+    pub death_tooltip: Option<&'a str>,
+}
+
+/// One exit of the current zone
+#[derive(Debug, Clone)]
+pub struct ExitRow<'a> {
+    pub exit: &'a ExitInfo,
+    /// Distance and compass arrow to the gate, when it is on the player's map
+    pub distance: Option<String>,
+    /// Community hint about what lies behind the gate
+    pub annotation: Option<&'a str>,
+    /// Pinned by the player (listed first, highlighted)
+    pub pinned: bool,
+    /// Destination already visited this run (checkmark, dimmed)
+    pub visited: bool,
+}
+
+/// Destination line colors of the exit list
+#[derive(Debug, Clone, Copy)]
+pub struct ExitColors {
+    pub discovered: Rgba,
+    pub undiscovered: Rgba,
+    pub pinned: Rgba,
+    pub visited: Rgba,
+}
+
+/// Exit tooltips in interactive mode
+#[derive(Debug, Clone, Copy)]
+pub struct PinHints<'a> {
+    pub pin: &'a str,
+    pub unpin: &'a str,
+}
+
+/// 3-line player status:
+/// Line 1: `● RaceName               HH:MM:SS` (name dimmed, IGT in blue)
+/// Line 2: `  ZoneName          -0:42    X/Y` (PB delta, then progress)
+/// Line 3: `  tier X, previously Y   [☠]N`     (tier yellow, deaths white)
+///
+/// Without `show_details` (minimal profile) only the IGT and zone name remain.
+pub fn render_player_status<R: OverlayRenderer + ?Sized>(
+    r: &mut R,
+    view: &PlayerStatusView,
+    max_width: f32,
+) {
+    if !view.show_details {
+        r.text_colored(view.igt_color, view.igt);
+        if let Some(zone) = view.zone_name {
+            let zone_truncated = truncate_to_width(r, zone, max_width);
+            r.text_colored(view.zone_color, &zone_truncated);
+        }
+        return;
+    }
+
+    // --- Line 1: connection dot + race name (left), IGT (right) ---
+    let igt_width = r.text_width(view.igt);
+    let dot_str = "\u{25CF} "; // "● "
+    let gap = r.text_width(" ");
+    let name_max = max_width - igt_width - gap - r.text_width(dot_str);
+
+    r.text_colored(view.dot_color, dot_str);
+    r.same_line(0.0);
+    let name_truncated = truncate_to_width(r, view.race_name, name_max);
+    r.text_colored(view.name_color, &name_truncated);
+    r.same_line_at(max_width - igt_width);
+    r.text_colored(view.igt_color, view.igt);
+
+    // --- Line 2: zone name (left), PB delta + progress (right) ---
+    let (progress, progress_color) = &view.progress;
+    let right_width = r.text_width(progress);
+    let delta_width = view
+        .pb_delta
+        .as_ref()
+        .map(|(text, _)| r.text_width(text) + gap)
+        .unwrap_or(0.0);
+
+    let zone_text = view
+        .zone_name
+        .map(|zone| format!("  {}", zone))
+        .unwrap_or_default();
+    let zone_max = max_width - right_width - delta_width - gap;
+    let zone_truncated = truncate_to_width(r, &zone_text, zone_max);
+    r.text_colored(view.zone_color, &zone_truncated);
+
+    if let Some((delta, delta_color)) = &view.pb_delta {
+        r.same_line_at(max_width - right_width - delta_width);
+        r.text_colored(*delta_color, delta);
+    }
+    r.same_line_at(max_width - right_width);
+    r.text_colored(*progress_color, progress);
+
+    // --- Line 3: tier (left), death icon + count (right) ---
+    let death_str = view.deaths.to_string();
+    let icon_size = r.line_height() * view.icon_scale;
+    let right_total = match view.death_icon {
+        Some(_) => icon_size + ICON_GAP + r.text_width(&death_str),
+        None => r.text_width(&death_str),
+    };
+
+    let (tier, tier_color) = &view.tier;
+    let tier_text = if tier.is_empty() {
+        String::new()
+    } else {
+        format!("  {}", tier)
+    };
+    let tier_max = max_width - right_total - gap;
+    let tier_truncated = truncate_to_width(r, &tier_text, tier_max);
+    r.text_colored(*tier_color, &tier_truncated);
+
+    r.same_line_at(max_width - right_total);
+    let mut deaths_hovered = false;
+    if let Some((texture, uv)) = view.death_icon {
+        r.image(texture, [icon_size, icon_size], uv.uv0, uv.uv1);
+        deaths_hovered = r.item_hovered();
+        r.same_line(ICON_GAP);
+    }
+    r.text_colored(view.deaths_color, &death_str);
+    deaths_hovered |= r.item_hovered();
+    if let Some(tooltip) = view.death_tooltip.filter(|_| deaths_hovered) {
+        r.tooltip(tooltip);
+    }
+}
+
+/// Exit list from zone_update:
+/// ```text
+/// → Ruin-Strewn Precipice          (green, discovered)
+/// ✓ Stormveil Castle                (dimmed, visited this run)
+///   Stranded Graveyard first door   (gray, word-wrapped)
+/// → ???                     120m ↗  (white, undiscovered, hint if on this map)
+///   (Leads to a legacy dungeon)     (white, community hint if enabled)
+///   Soldier of Godrick front        (gray, word-wrapped)
+/// ▸ Liurnia of the Lakes            (highlight, pinned)
+/// ```
+///
+/// With `pin_hints` (interactive mode), hovering a row shows the pin tooltip.
+/// Returns the index in `rows` of the row clicked this frame.
+pub fn render_exits<R: OverlayRenderer + ?Sized>(
+    r: &mut R,
+    rows: &[ExitRow],
+    colors: ExitColors,
+    max_width: f32,
+    pin_hints: Option<PinHints>,
+) -> Option<usize> {
+    let indent = "  ";
+    let mut clicked = None;
+    for (index, row) in rows.iter().enumerate() {
+        // Any line of the row counts for hover and click
+        let mut hovered = false;
+        let mut row_clicked = false;
+        let mut track = |r: &R| {
+            hovered |= r.item_hovered();
+            row_clicked |= r.item_clicked();
+        };
+
+        // Line 1: destination — green if discovered, dimmed with a checkmark
+        // once visited, white "???" if not, highlighted with a marker when pinned
+        let visited = row.visited && row.exit.discovered;
+        let arrow = match (row.pinned, visited) {
+            (true, _) => "\u{25B8}",      // ▸
+            (false, true) => "\u{2713}",  // ✓
+            (false, false) => "\u{2192}", // →
+        };
+        let (discovered, undiscovered) = if row.pinned {
+            (colors.pinned, colors.pinned)
+        } else if visited {
+            (colors.visited, colors.undiscovered)
+        } else {
+            (colors.discovered, colors.undiscovered)
+        };
+        if row.exit.discovered {
+            let dest = format!("{} {}", arrow, row.exit.to_name);
+            let truncated = truncate_to_width(r, &dest, max_width);
+            r.text_colored(discovered, &truncated);
+            track(r);
+        } else {
+            r.text_colored(undiscovered, &format!("{} ???", arrow));
+            track(r);
+            // Distance + compass arrow to the gate, right-aligned
+            if let Some(distance) = &row.distance {
+                r.same_line_at(max_width - r.text_width(distance));
+                r.text_disabled(distance);
+                track(r);
+            }
+            if let Some(annotation) = row.annotation {
+                for line in wrap_text(r, indent, &format!("({})", annotation), max_width) {
+                    r.text_colored(colors.undiscovered, &line);
+                    track(r);
+                }
+            }
+        }
+
+        // Lines 2+: directions to reach the fog gate (gray, word-wrapped)
+        for line in wrap_text(r, indent, &row.exit.text, max_width) {
+            r.text_disabled(&line);
+            track(r);
+        }
+
+        if let Some(hints) = pin_hints.filter(|_| hovered) {
+            r.tooltip(if row.pinned { hints.unpin } else { hints.pin });
+            if row_clicked {
+                clicked = Some(index);
+            }
+        }
+    }
+    clicked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::overlay_renderer::{Drawn, RecordingRenderer};
+
+    const BLUE: Rgba = [0.0, 0.0, 1.0, 1.0];
+    const GREEN: Rgba = [0.0, 1.0, 0.0, 1.0];
+    const WHITE: Rgba = [1.0; 4];
+    const GRAY: Rgba = [0.5, 0.5, 0.5, 1.0];
+    const MAX_WIDTH: f32 = 160.0;
+
+    fn status() -> PlayerStatusView<'static> {
+        PlayerStatusView {
+            show_details: true,
+            dot_color: GREEN,
+            race_name: "Sunday Race",
+            name_color: WHITE,
+            igt: "00:12:34",
+            igt_color: BLUE,
+            zone_name: Some("Stormveil Castle"),
+            zone_color: WHITE,
+            pb_delta: None,
+            progress: ("3/8".to_string(), GREEN),
+            tier: ("tier 4".to_string(), GREEN),
+            deaths: 7,
+            deaths_color: WHITE,
+            death_icon: None,
+            icon_scale: 1.0,
+            death_tooltip: None,
+        }
+    }
+
+    fn right_edge(r: &RecordingRenderer, text: &str) -> f32 {
+        let item = r.find(text).unwrap();
+        item.x + item.width
+    }
+
+    #[test]
+    fn test_status_right_aligned() {
+        let mut r = RecordingRenderer::default();
+        render_player_status(&mut r, &status(), MAX_WIDTH);
+        assert_eq!(r.lines().len(), 3);
+        assert_eq!(right_edge(&r, "00:12:34"), MAX_WIDTH);
+        assert_eq!(right_edge(&r, "3/8"), MAX_WIDTH);
+        assert_eq!(right_edge(&r, "7"), MAX_WIDTH);
+        assert_eq!(r.find("00:12:34").unwrap().line, 0);
+        assert_eq!(r.find("  tier 4").unwrap().line, 2);
+    }
+
+    #[test]
+    fn test_status_truncates_long_names() {
+        let mut r = RecordingRenderer::default();
+        let view = PlayerStatusView {
+            race_name: "A race name far too long for the overlay",
+            pb_delta: Some(("-0:42".to_string(), GREEN)),
+            ..status()
+        };
+        render_player_status(&mut r, &view, MAX_WIDTH);
+        // Name stops a space before the IGT
+        let name = &r.items[1];
+        assert!(matches!(&name.drawn, Drawn::Text(t, _) if t.ends_with('\u{2026}')));
+        assert!(name.x + name.width <= r.find("00:12:34").unwrap().x - 8.0);
+        // Zone makes room for the PB delta and the progress
+        let zone = &r.items[3];
+        assert_eq!(
+            zone.drawn,
+            Drawn::Text("  Stormve\u{2026}".to_string(), Some(WHITE))
+        );
+        assert!(zone.x + zone.width <= r.find("-0:42").unwrap().x - 8.0);
+        assert_eq!(right_edge(&r, "-0:42"), MAX_WIDTH - 24.0 - 8.0);
+    }
+
+    #[test]
+    fn test_status_death_icon() {
+        let mut r = RecordingRenderer::default();
+        let view = PlayerStatusView {
+            death_icon: Some((TextureHandle(3), IconUv::FULL)),
+            ..status()
+        };
+        render_player_status(&mut r, &view, MAX_WIDTH);
+        let icon = r
+            .items
+            .iter()
+            .find(|i| matches!(i.drawn, Drawn::Image(..)))
+            .unwrap();
+        assert_eq!(icon.drawn, Drawn::Image(TextureHandle(3), [16.0, 16.0]));
+        assert_eq!(icon.x, MAX_WIDTH - 16.0 - ICON_GAP - 8.0);
+        assert_eq!(r.find("7").unwrap().x, icon.x + 16.0 + ICON_GAP);
+    }
+
+    #[test]
+    fn test_status_death_tooltip() {
+        let view = PlayerStatusView {
+            death_tooltip: Some("Deaths"),
+            ..status()
+        };
+        // Hovering the count (last item) shows the tooltip once
+        let mut r = RecordingRenderer::with_mouse(6, false);
+        render_player_status(&mut r, &view, MAX_WIDTH);
+        assert_eq!(r.items[6].drawn, Drawn::Text("7".to_string(), Some(WHITE)));
+        assert_eq!(r.tooltips, vec![(6, "Deaths".to_string())]);
+
+        // Nothing hovered, or not in interactive mode
+        let mut r = RecordingRenderer::default();
+        render_player_status(&mut r, &view, MAX_WIDTH);
+        assert!(r.tooltips.is_empty());
+        let mut r = RecordingRenderer::with_mouse(6, false);
+        render_player_status(&mut r, &status(), MAX_WIDTH);
+        assert!(r.tooltips.is_empty());
+    }
+
+    #[test]
+    fn test_status_minimal() {
+        let mut r = RecordingRenderer::default();
+        let view = PlayerStatusView {
+            show_details: false,
+            ..status()
+        };
+        render_player_status(&mut r, &view, 64.0);
+        assert_eq!(r.lines(), vec!["00:12:34", "Stormve\u{2026}"]);
+    }
+
+    fn exit(discovered: bool, to_name: &str, text: &str) -> ExitInfo {
+        ExitInfo {
+            text: text.to_string(),
+            to_name: to_name.to_string(),
+            discovered,
+            to_node_id: None,
+            map_id: None,
+            position: None,
+        }
+    }
+
+    const COLORS: ExitColors = ExitColors {
+        discovered: GREEN,
+        undiscovered: WHITE,
+        pinned: BLUE,
+        visited: GRAY,
+    };
+
+    const HINTS: PinHints = PinHints {
+        pin: "pin",
+        unpin: "unpin",
+    };
+
+    #[test]
+    fn test_exits_layout() {
+        let found = exit(true, "Liurnia of the Lakes", "past the gatefront");
+        let hidden = exit(false, "Secret", "behind the castle gate");
+        let rows = [
+            ExitRow {
+                exit: &found,
+                distance: None,
+                annotation: None,
+                pinned: false,
+                visited: false,
+            },
+            ExitRow {
+                exit: &hidden,
+                distance: Some("120m \u{2197}".to_string()),
+                annotation: Some("Boss arena"),
+                pinned: false,
+                visited: false,
+            },
+        ];
+        let mut r = RecordingRenderer::default();
+        assert_eq!(render_exits(&mut r, &rows, COLORS, MAX_WIDTH, None), None);
+        assert_eq!(
+            r.lines(),
+            vec![
+                "\u{2192} Liurnia of the La\u{2026}",
+                "  past the gatefront",
+                "\u{2192} ???120m \u{2197}",
+                "  (Boss arena)",
+                "  behind the castle",
+                "  gate",
+            ]
+        );
+        // The undiscovered destination is never revealed
+        assert!(r.find("\u{2192} Secret").is_none());
+        assert_eq!(right_edge(&r, "120m \u{2197}"), MAX_WIDTH);
+    }
+
+    #[test]
+    fn test_exits_pinned_and_clicked() {
+        let found = exit(true, "Liurnia", "past the gatefront");
+        let hidden = exit(false, "Secret", "behind the gate");
+        let rows = [
+            ExitRow {
+                exit: &hidden,
+                distance: None,
+                annotation: None,
+                pinned: true,
+                visited: false,
+            },
+            ExitRow {
+                exit: &found,
+                distance: None,
+                annotation: None,
+                pinned: false,
+                visited: false,
+            },
+        ];
+        // Mouse on the directions of the second row
+        let mut r = RecordingRenderer::with_mouse(3, true);
+        let clicked = render_exits(&mut r, &rows, COLORS, MAX_WIDTH, Some(HINTS));
+        assert_eq!(clicked, Some(1));
+        assert_eq!(
+            r.lines(),
+            vec![
+                "\u{25B8} ???",
+                "  behind the gate",
+                "\u{2192} Liurnia",
+                "  past the gatefront",
+            ]
+        );
+        assert_eq!(
+            r.items[0].drawn,
+            Drawn::Text("\u{25B8} ???".to_string(), Some(BLUE))
+        );
+        assert_eq!(r.tooltips, vec![(3, "pin".to_string())]);
+
+        // Hover without click on the pinned row
+        let mut r = RecordingRenderer::with_mouse(0, false);
+        let clicked = render_exits(&mut r, &rows, COLORS, MAX_WIDTH, Some(HINTS));
+        assert_eq!(clicked, None);
+        // Shown once the whole row is drawn
+        assert_eq!(r.tooltips, vec![(1, "unpin".to_string())]);
+
+        // Not interactive: no tooltip and no click
+        let mut r = RecordingRenderer::with_mouse(0, true);
+        assert_eq!(render_exits(&mut r, &rows, COLORS, MAX_WIDTH, None), None);
+        assert!(r.tooltips.is_empty());
+    }
+
+    #[test]
+    fn test_exits_visited() {
+        let visited = exit(true, "Stormveil Castle", "past the gate");
+        let hidden = exit(false, "Secret", "behind the gate");
+        let rows = [
+            ExitRow {
+                exit: &visited,
+                distance: None,
+                annotation: None,
+                pinned: false,
+                visited: true,
+            },
+            // Never marked while undiscovered
+            ExitRow {
+                exit: &hidden,
+                distance: None,
+                annotation: None,
+                pinned: false,
+                visited: true,
+            },
+        ];
+        let mut r = RecordingRenderer::default();
+        render_exits(&mut r, &rows, COLORS, MAX_WIDTH, None);
+        assert_eq!(
+            r.items[0].drawn,
+            Drawn::Text("\u{2713} Stormveil Castle".to_string(), Some(GRAY))
+        );
+        assert!(r.find("\u{2192} ???").is_some());
+    }
+}
@@ -0,0 +1,124 @@
+//! Overlay profiles
+//!
+//! A profile is a preset picked with `overlay.profile` that overrides what the
+//! overlay shows: everything, only the zone and IGT, or everything except the
+//! details that shouldn't end up on a stream.
+
+use serde::{Deserialize, Serialize};
+
+/// Overlay preset selected in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayProfile {
+    #[default]
+    Full,
+    /// Zone and IGT only
+    Minimal,
+    /// Full overlay without identifiers, raw server messages or auth errors
+    Streamer,
+}
+
+/// What the overlay renders under a profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverlayPreset {
+    /// Race name, progress, tier and deaths around the zone / IGT
+    pub show_details: bool,
+    pub show_exits: bool,
+    pub show_leaderboard: bool,
+    pub show_toasts: bool,
+    /// Debug panel (still toggled by its hotkey)
+    pub allow_debug: bool,
+    /// Replace server-provided error texts and raw messages with generic ones
+    pub hide_sensitive: bool,
+}
+
+impl OverlayProfile {
+    pub fn preset(self) -> OverlayPreset {
+        match self {
+            OverlayProfile::Full => OverlayPreset {
+                show_details: true,
+                show_exits: true,
+                show_leaderboard: true,
+                show_toasts: true,
+                allow_debug: true,
+                hide_sensitive: false,
+            },
+            OverlayProfile::Minimal => OverlayPreset {
+                show_details: false,
+                show_exits: false,
+                show_leaderboard: false,
+                show_toasts: false,
+                allow_debug: true,
+                hide_sensitive: false,
+            },
+            OverlayProfile::Streamer => OverlayPreset {
+                show_details: true,
+                show_exits: true,
+                show_leaderboard: true,
+                show_toasts: true,
+                allow_debug: false,
+                hide_sensitive: true,
+            },
+        }
+    }
+}
+
+impl OverlayPreset {
+    /// Zone and IGT only, keeping the profile's debug and privacy choices
+    /// (boss fight mode)
+    pub fn minimized(self) -> Self {
+        Self {
+            show_details: false,
+            show_exits: false,
+            show_leaderboard: false,
+            show_toasts: false,
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Wrapper {
+        #[serde(default)]
+        profile: OverlayProfile,
+    }
+
+    #[test]
+    fn test_parse_profile() {
+        let parse = |s: &str| toml::from_str::<Wrapper>(s).unwrap().profile;
+        assert_eq!(parse(""), OverlayProfile::Full);
+        assert_eq!(parse("profile = \"minimal\""), OverlayProfile::Minimal);
+        assert_eq!(parse("profile = \"streamer\""), OverlayProfile::Streamer);
+        assert!(toml::from_str::<Wrapper>("profile = \"tiny\"").is_err());
+    }
+
+    #[test]
+    fn test_minimal_hides_everything_but_zone_and_igt() {
+        let preset = OverlayProfile::Minimal.preset();
+        assert!(!preset.show_details);
+        assert!(!preset.show_exits);
+        assert!(!preset.show_leaderboard);
+        assert!(!preset.show_toasts);
+    }
+
+    #[test]
+    fn test_minimized_keeps_privacy() {
+        let preset = OverlayProfile::Streamer.preset().minimized();
+        assert!(!preset.show_leaderboard && !preset.show_toasts);
+        assert!(preset.hide_sensitive && !preset.allow_debug);
+    }
+
+    #[test]
+    fn test_streamer_hides_sensitive_only() {
+        let full = OverlayProfile::Full.preset();
+        let streamer = OverlayProfile::Streamer.preset();
+        assert!(!full.hide_sensitive && full.allow_debug);
+        assert!(streamer.hide_sensitive && !streamer.allow_debug);
+        assert_eq!(streamer.show_leaderboard, full.show_leaderboard);
+        assert_eq!(streamer.show_exits, full.show_exits);
+    }
+}
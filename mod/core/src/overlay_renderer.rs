@@ -0,0 +1,324 @@
+//! Rendering abstraction for the overlay panels
+//!
+//! The panels only need a handful of ImGui calls. Going through this trait
+//! keeps their layout (truncation, word wrap, right alignment) free of imgui:
+//! the DLL implements it on top of an imgui `Ui`, and tests use a recorder
+//! that measures text with a fixed-width font.
+
+use std::borrow::Cow;
+
+/// RGBA color, as used by ImGui
+pub type Rgba = [f32; 4];
+
+/// Opaque texture handle (the value of an ImGui `TextureId`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureHandle(pub usize);
+
+/// Drawing calls made by the overlay panels. Every item starts a new line
+/// unless preceded by `same_line` or `same_line_at`.
+pub trait OverlayRenderer {
+    /// Text in the window's text color
+    fn text(&mut self, text: &str);
+    fn text_colored(&mut self, color: Rgba, text: &str);
+    /// Text in the window's dimmed text color
+    fn text_disabled(&mut self, text: &str);
+    fn image(&mut self, texture: TextureHandle, size: [f32; 2], uv0: [f32; 2], uv1: [f32; 2]);
+    /// Keep the next item on this line, `spacing` pixels after the last one
+    fn same_line(&mut self, spacing: f32);
+    /// Keep the next item on this line, starting `x` pixels from the left edge
+    fn same_line_at(&mut self, x: f32);
+    fn separator(&mut self);
+    /// Width of `text` in pixels in the current font
+    fn text_width(&self, text: &str) -> f32;
+    fn line_height(&self) -> f32;
+    /// Whether the mouse is over the last item (interactive mode only)
+    fn item_hovered(&self) -> bool;
+    /// Whether the last item was left-clicked this frame
+    fn item_clicked(&self) -> bool;
+    /// Tooltip next to the mouse cursor
+    fn tooltip(&mut self, text: &str);
+}
+
+/// Word-wrap `text` into lines that fit within `max_width`, prepending `indent` to each line.
+pub fn wrap_text<R: OverlayRenderer + ?Sized>(
+    r: &R,
+    indent: &str,
+    text: &str,
+    max_width: f32,
+) -> Vec<String> {
+    let full = format!("{}{}", indent, text);
+    if r.text_width(&full) <= max_width {
+        return vec![full];
+    }
+
+    let mut lines = Vec::new();
+    let mut current_line = indent.to_string();
+    for word in text.split_whitespace() {
+        let candidate = if current_line.len() == indent.len() {
+            format!("{}{}", current_line, word)
+        } else {
+            format!("{} {}", current_line, word)
+        };
+
+        if r.text_width(&candidate) <= max_width {
+            current_line = candidate;
+        } else if current_line.len() == indent.len() {
+            // Single word exceeds max_width — truncate it
+            let truncated = truncate_to_width(r, &candidate, max_width);
+            lines.push(truncated.into_owned());
+        } else {
+            lines.push(current_line);
+            current_line = format!("{}{}", indent, word);
+        }
+    }
+    if current_line.len() > indent.len() {
+        lines.push(current_line);
+    }
+
+    lines
+}
+
+/// Truncate text to fit within `max_width` pixels, adding "\u{2026}" if needed.
+///
+/// Returns `Cow::Borrowed` when the text fits (zero allocations in the common case).
+/// When truncation is needed, does a linear forward scan and one allocation for the result.
+pub fn truncate_to_width<'a, R: OverlayRenderer + ?Sized>(
+    r: &R,
+    text: &'a str,
+    max_width: f32,
+) -> Cow<'a, str> {
+    if r.text_width(text) <= max_width {
+        return Cow::Borrowed(text);
+    }
+
+    let ellipsis = "\u{2026}"; // …
+    let target_width = max_width - r.text_width(ellipsis);
+    if target_width <= 0.0 {
+        return Cow::Borrowed(ellipsis);
+    }
+
+    // Linear forward scan: find the longest byte prefix that fits
+    let mut last_fit = 0;
+    for (byte_pos, _) in text.char_indices().skip(1) {
+        if r.text_width(&text[..byte_pos]) > target_width {
+            break;
+        }
+        last_fit = byte_pos;
+    }
+
+    Cow::Owned(format!("{}{}", &text[..last_fit], ellipsis))
+}
+
+// =============================================================================
+// RECORDING RENDERER (tests)
+// =============================================================================
+
+/// What a recorded item drew
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Drawn {
+    /// Text with its explicit color (None: window text color)
+    Text(String, Option<Rgba>),
+    Disabled(String),
+    Image(TextureHandle, [f32; 2]),
+    Separator,
+}
+
+/// A drawn item and where it landed
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Item {
+    pub line: usize,
+    pub x: f32,
+    pub width: f32,
+    pub drawn: Drawn,
+}
+
+/// Records the drawing calls. Every character is `CHAR_WIDTH` pixels wide.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub(crate) struct RecordingRenderer {
+    pub items: Vec<Item>,
+    next_x: Option<f32>,
+    /// Index of the item under the simulated mouse
+    pub hovered: Option<usize>,
+    /// The simulated mouse button was clicked this frame
+    pub clicked: bool,
+    /// Tooltips shown, with the index of the item they were shown for
+    pub tooltips: Vec<(usize, String)>,
+}
+
+#[cfg(test)]
+impl RecordingRenderer {
+    pub const CHAR_WIDTH: f32 = 8.0;
+    pub const LINE_HEIGHT: f32 = 16.0;
+
+    /// Recorder with the simulated mouse over the `hovered`-th item
+    pub fn with_mouse(hovered: usize, clicked: bool) -> Self {
+        Self {
+            hovered: Some(hovered),
+            clicked,
+            ..Default::default()
+        }
+    }
+
+    fn push(&mut self, width: f32, drawn: Drawn) {
+        let last = self.items.last();
+        let (line, x) = match (self.next_x.take(), last) {
+            (Some(x), Some(last)) => (last.line, x),
+            (Some(x), None) => (0, x),
+            (None, Some(last)) => (last.line + 1, 0.0),
+            (None, None) => (0, 0.0),
+        };
+        self.items.push(Item {
+            line,
+            x,
+            width,
+            drawn,
+        });
+    }
+
+    /// Text of each line, items joined with no spacing
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+        for item in &self.items {
+            if lines.len() <= item.line {
+                lines.resize(item.line + 1, String::new());
+            }
+            match &item.drawn {
+                Drawn::Text(text, _) | Drawn::Disabled(text) => lines[item.line].push_str(text),
+                Drawn::Image(..) => lines[item.line].push_str("[img]"),
+                Drawn::Separator => lines[item.line].push_str("---"),
+            }
+        }
+        lines
+    }
+
+    /// First item drawing exactly `text`
+    pub fn find(&self, text: &str) -> Option<&Item> {
+        self.items.iter().find(|item| match &item.drawn {
+            Drawn::Text(t, _) | Drawn::Disabled(t) => t == text,
+            _ => false,
+        })
+    }
+}
+
+#[cfg(test)]
+impl OverlayRenderer for RecordingRenderer {
+    fn text(&mut self, text: &str) {
+        self.push(self.text_width(text), Drawn::Text(text.to_string(), None));
+    }
+
+    fn text_colored(&mut self, color: Rgba, text: &str) {
+        self.push(
+            self.text_width(text),
+            Drawn::Text(text.to_string(), Some(color)),
+        );
+    }
+
+    fn text_disabled(&mut self, text: &str) {
+        self.push(self.text_width(text), Drawn::Disabled(text.to_string()));
+    }
+
+    fn image(&mut self, texture: TextureHandle, size: [f32; 2], _uv0: [f32; 2], _uv1: [f32; 2]) {
+        self.push(size[0], Drawn::Image(texture, size));
+    }
+
+    fn same_line(&mut self, spacing: f32) {
+        let end = self.items.last().map(|i| i.x + i.width).unwrap_or(0.0);
+        self.next_x = Some(end + spacing);
+    }
+
+    fn same_line_at(&mut self, x: f32) {
+        self.next_x = Some(x);
+    }
+
+    fn separator(&mut self) {
+        self.push(0.0, Drawn::Separator);
+    }
+
+    fn text_width(&self, text: &str) -> f32 {
+        text.chars().count() as f32 * Self::CHAR_WIDTH
+    }
+
+    fn line_height(&self) -> f32 {
+        Self::LINE_HEIGHT
+    }
+
+    fn item_hovered(&self) -> bool {
+        self.hovered.is_some() && self.hovered == self.items.len().checked_sub(1)
+    }
+
+    fn item_clicked(&self) -> bool {
+        self.clicked && self.item_hovered()
+    }
+
+    fn tooltip(&mut self, text: &str) {
+        let index = self.items.len().saturating_sub(1);
+        self.tooltips.push((index, text.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_fits() {
+        let r = RecordingRenderer::default();
+        assert!(matches!(
+            truncate_to_width(&r, "Limgrave", 64.0),
+            Cow::Borrowed("Limgrave")
+        ));
+    }
+
+    #[test]
+    fn test_truncate_adds_ellipsis() {
+        let r = RecordingRenderer::default();
+        // 6 chars fit in 48px: 5 chars + the ellipsis
+        assert_eq!(truncate_to_width(&r, "Stormveil", 48.0), "Storm\u{2026}");
+        // Multibyte characters are never split
+        assert_eq!(truncate_to_width(&r, "Héros du Nord", 32.0), "Hér\u{2026}");
+        assert_eq!(truncate_to_width(&r, "Stormveil", 4.0), "\u{2026}");
+    }
+
+    #[test]
+    fn test_wrap_text() {
+        let r = RecordingRenderer::default();
+        assert_eq!(wrap_text(&r, "  ", "short", 80.0), vec!["  short"]);
+        assert_eq!(
+            wrap_text(&r, "  ", "behind the castle gate", 104.0),
+            vec!["  behind the", "  castle gate"]
+        );
+        // A word wider than the line is truncated
+        assert_eq!(wrap_text(&r, "  ", "Leyndell", 48.0), vec!["  Ley\u{2026}"]);
+    }
+
+    #[test]
+    fn test_recorder_positions() {
+        let mut r = RecordingRenderer::default();
+        r.text("ab");
+        r.same_line(4.0);
+        r.text("c");
+        r.same_line_at(100.0);
+        r.text_disabled("d");
+        r.separator();
+        r.text("e");
+        assert_eq!(r.lines(), vec!["abcd", "---", "e"]);
+        assert_eq!(r.find("c").unwrap().x, 20.0);
+        assert_eq!(r.find("d").unwrap().x, 100.0);
+        assert_eq!(r.find("e").unwrap().line, 2);
+    }
+
+    #[test]
+    fn test_recorder_hover() {
+        let mut r = RecordingRenderer::with_mouse(1, true);
+        r.text("a");
+        assert!(!r.item_hovered());
+        r.text("b");
+        assert!(r.item_hovered());
+        assert!(r.item_clicked());
+        r.tooltip("tip");
+        assert_eq!(r.tooltips, vec![(1, "tip".to_string())]);
+    }
+}
@@ -0,0 +1,248 @@
+//! Automatic panel cycling
+//!
+//! On a single monitor the overlay has little room, and toggling panels with
+//! hotkeys mid-run is awkward. Auto-cycle mode shows one panel at a time
+//! (exits, objectives, leaderboard) and rotates them on a timer, each with
+//! its own dwell time. Panels with nothing to show are skipped.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Panels taking turns under the player status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panel {
+    Exits,
+    Objectives,
+    Leaderboard,
+}
+
+impl Panel {
+    /// Rotation order
+    pub const ALL: [Panel; 3] = [Panel::Exits, Panel::Objectives, Panel::Leaderboard];
+}
+
+/// `[auto_cycle]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutoCycleSettings {
+    /// Rotate the panels on a timer instead of showing them all
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds each panel stays on screen (0 = never shown)
+    #[serde(default = "default_exits_secs")]
+    pub exits_secs: f32,
+    #[serde(default = "default_objectives_secs")]
+    pub objectives_secs: f32,
+    #[serde(default = "default_leaderboard_secs")]
+    pub leaderboard_secs: f32,
+}
+
+fn default_exits_secs() -> f32 {
+    10.0
+}
+fn default_objectives_secs() -> f32 {
+    6.0
+}
+fn default_leaderboard_secs() -> f32 {
+    8.0
+}
+
+impl Default for AutoCycleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            exits_secs: default_exits_secs(),
+            objectives_secs: default_objectives_secs(),
+            leaderboard_secs: default_leaderboard_secs(),
+        }
+    }
+}
+
+impl AutoCycleSettings {
+    /// How long a panel stays on screen (zero removes it from the rotation)
+    pub fn dwell(&self, panel: Panel) -> Duration {
+        let secs = match panel {
+            Panel::Exits => self.exits_secs,
+            Panel::Objectives => self.objectives_secs,
+            Panel::Leaderboard => self.leaderboard_secs,
+        };
+        Duration::from_secs_f32(secs.max(0.0))
+    }
+}
+
+/// Picks the panel to show each frame
+#[derive(Debug, Default)]
+pub struct PanelScheduler {
+    current: Option<Panel>,
+    shown_since: Option<Instant>,
+}
+
+impl PanelScheduler {
+    /// Panel to show at `now`, None when no panel is available. A panel that
+    /// becomes unavailable is replaced right away by the next one.
+    pub fn tick(
+        &mut self,
+        now: Instant,
+        settings: &AutoCycleSettings,
+        is_available: impl Fn(Panel) -> bool,
+    ) -> Option<Panel> {
+        let shown = |panel: Panel| is_available(panel) && !settings.dwell(panel).is_zero();
+
+        let expired = match (self.current, self.shown_since) {
+            (Some(panel), Some(since)) => {
+                !shown(panel) || now.saturating_duration_since(since) >= settings.dwell(panel)
+            }
+            _ => true,
+        };
+        if expired {
+            self.current = next_after(self.current, shown);
+            self.shown_since = Some(now);
+        }
+        self.current
+    }
+
+    /// Time left before the current panel is rotated out
+    pub fn remaining(&self, now: Instant, settings: &AutoCycleSettings) -> Option<Duration> {
+        let panel = self.current?;
+        let since = self.shown_since?;
+        Some(
+            settings
+                .dwell(panel)
+                .saturating_sub(now.saturating_duration_since(since)),
+        )
+    }
+}
+
+/// First shown panel after `current` in rotation order (wrapping around,
+/// `current` itself last)
+fn next_after(current: Option<Panel>, shown: impl Fn(Panel) -> bool) -> Option<Panel> {
+    let start = current
+        .and_then(|c| Panel::ALL.iter().position(|&p| p == c))
+        .map_or(0, |i| i + 1);
+    (0..Panel::ALL.len())
+        .map(|offset| Panel::ALL[(start + offset) % Panel::ALL.len()])
+        .find(|&p| shown(p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> AutoCycleSettings {
+        AutoCycleSettings {
+            enabled: true,
+            exits_secs: 10.0,
+            objectives_secs: 5.0,
+            leaderboard_secs: 8.0,
+        }
+    }
+
+    fn secs(s: u64) -> Duration {
+        Duration::from_secs(s)
+    }
+
+    #[test]
+    fn test_rotation_follows_dwell_times() {
+        let t0 = Instant::now();
+        let settings = settings();
+        let mut scheduler = PanelScheduler::default();
+        let all = |_| true;
+
+        assert_eq!(scheduler.tick(t0, &settings, all), Some(Panel::Exits));
+        assert_eq!(
+            scheduler.tick(t0 + secs(9), &settings, all),
+            Some(Panel::Exits)
+        );
+        assert_eq!(
+            scheduler.tick(t0 + secs(10), &settings, all),
+            Some(Panel::Objectives)
+        );
+        assert_eq!(
+            scheduler.tick(t0 + secs(15), &settings, all),
+            Some(Panel::Leaderboard)
+        );
+        assert_eq!(
+            scheduler.tick(t0 + secs(23), &settings, all),
+            Some(Panel::Exits)
+        );
+    }
+
+    #[test]
+    fn test_unavailable_panels_skipped() {
+        let t0 = Instant::now();
+        let settings = settings();
+        let mut scheduler = PanelScheduler::default();
+        // No objectives in this race
+        let available = |p| p != Panel::Objectives;
+
+        assert_eq!(scheduler.tick(t0, &settings, available), Some(Panel::Exits));
+        assert_eq!(
+            scheduler.tick(t0 + secs(10), &settings, available),
+            Some(Panel::Leaderboard)
+        );
+    }
+
+    #[test]
+    fn test_panel_replaced_when_it_disappears() {
+        let t0 = Instant::now();
+        let settings = settings();
+        let mut scheduler = PanelScheduler::default();
+        assert_eq!(scheduler.tick(t0, &settings, |_| true), Some(Panel::Exits));
+        // The zone has no exits anymore: switch immediately, with a full dwell
+        let no_exits = |p| p != Panel::Exits;
+        assert_eq!(
+            scheduler.tick(t0 + secs(1), &settings, no_exits),
+            Some(Panel::Objectives)
+        );
+        assert_eq!(scheduler.remaining(t0 + secs(1), &settings), Some(secs(5)));
+    }
+
+    #[test]
+    fn test_single_panel_stays() {
+        let t0 = Instant::now();
+        let settings = settings();
+        let mut scheduler = PanelScheduler::default();
+        let only_leaderboard = |p| p == Panel::Leaderboard;
+        assert_eq!(
+            scheduler.tick(t0, &settings, only_leaderboard),
+            Some(Panel::Leaderboard)
+        );
+        assert_eq!(
+            scheduler.tick(t0 + secs(30), &settings, only_leaderboard),
+            Some(Panel::Leaderboard)
+        );
+        assert_eq!(scheduler.tick(t0 + secs(31), &settings, |_| false), None);
+    }
+
+    #[test]
+    fn test_zero_dwell_removes_panel() {
+        let t0 = Instant::now();
+        let settings = AutoCycleSettings {
+            leaderboard_secs: 0.0,
+            ..settings()
+        };
+        let mut scheduler = PanelScheduler::default();
+        let all = |_| true;
+        scheduler.tick(t0, &settings, all);
+        scheduler.tick(t0 + secs(10), &settings, all);
+        assert_eq!(
+            scheduler.tick(t0 + secs(15), &settings, all),
+            Some(Panel::Exits)
+        );
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings: AutoCycleSettings = toml::from_str("").unwrap();
+        assert_eq!(settings, AutoCycleSettings::default());
+        assert!(!settings.enabled);
+        let settings: AutoCycleSettings =
+            toml::from_str("enabled = true\nleaderboard_secs = 4.5").unwrap();
+        assert!(settings.enabled);
+        assert_eq!(
+            settings.dwell(Panel::Leaderboard),
+            Duration::from_millis(4500)
+        );
+        assert_eq!(settings.dwell(Panel::Exits), secs(10));
+    }
+}
@@ -0,0 +1,143 @@
+//! Race pause granted by the organizer
+//!
+//! A runner hit by a technical issue (game crash, controller, stream) asks
+//! for a pause with the hotkey. The request goes to the server and only takes
+//! effect once the organizer grants it; until then the race goes on as usual.
+//! While paused the mod stops its IGT status updates and AFK detection and
+//! shows a PAUSED banner. The organizer's `resume` ends the pause, or
+//! declines a request still pending.
+
+use std::time::Instant;
+
+/// Where the player stands in the pause cycle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PauseState {
+    #[default]
+    Running,
+    /// Asked the organizer, still racing
+    Requested,
+    /// Granted, since this instant
+    Paused { since: Instant },
+}
+
+/// State change worth telling the player about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseEvent {
+    Granted,
+    /// The pause ended
+    Resumed,
+    /// The organizer turned the request down
+    Declined,
+}
+
+#[derive(Debug, Default)]
+pub struct RacePause {
+    state: PauseState,
+}
+
+impl RacePause {
+    pub fn state(&self) -> PauseState {
+        self.state
+    }
+
+    pub fn is_paused(&self) -> bool {
+        matches!(self.state, PauseState::Paused { .. })
+    }
+
+    /// Ask for a pause. Returns true when the request should be sent (not
+    /// already pending or granted).
+    pub fn request(&mut self) -> bool {
+        if self.state != PauseState::Running {
+            return false;
+        }
+        self.state = PauseState::Requested;
+        true
+    }
+
+    /// The server granted the pause. Also accepted without a pending request:
+    /// the server is the authority, e.g. after a reconnect.
+    pub fn grant(&mut self, now: Instant) -> Option<PauseEvent> {
+        if self.is_paused() {
+            return None;
+        }
+        self.state = PauseState::Paused { since: now };
+        Some(PauseEvent::Granted)
+    }
+
+    /// The server ended the pause, or declined the request
+    pub fn resume(&mut self) -> Option<PauseEvent> {
+        let event = match self.state {
+            PauseState::Running => return None,
+            PauseState::Requested => PauseEvent::Declined,
+            PauseState::Paused { .. } => PauseEvent::Resumed,
+        };
+        self.state = PauseState::Running;
+        Some(event)
+    }
+
+    /// Match the server's view (`pause` of the player's leaderboard entry,
+    /// after a reconnect). Silent: no event for the player.
+    pub fn sync(&mut self, server_pause: Option<&str>, now: Instant) {
+        self.state = match server_pause {
+            Some("paused") if self.is_paused() => self.state,
+            Some("paused") => PauseState::Paused { since: now },
+            Some("requested") => PauseState::Requested,
+            _ => PauseState::Running,
+        };
+    }
+
+    /// Back to running (race over, new seed)
+    pub fn reset(&mut self) {
+        self.state = PauseState::Running;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_grant_resume() {
+        let now = Instant::now();
+        let mut pause = RacePause::default();
+        assert!(pause.request());
+        assert_eq!(pause.state(), PauseState::Requested);
+        // One request at a time
+        assert!(!pause.request());
+        assert!(!pause.is_paused());
+
+        assert_eq!(pause.grant(now), Some(PauseEvent::Granted));
+        assert_eq!(pause.state(), PauseState::Paused { since: now });
+        assert_eq!(pause.grant(now), None);
+        assert!(!pause.request());
+
+        assert_eq!(pause.resume(), Some(PauseEvent::Resumed));
+        assert_eq!(pause.state(), PauseState::Running);
+        assert_eq!(pause.resume(), None);
+    }
+
+    #[test]
+    fn test_declined() {
+        let mut pause = RacePause::default();
+        pause.request();
+        assert_eq!(pause.resume(), Some(PauseEvent::Declined));
+        assert!(pause.request());
+    }
+
+    #[test]
+    fn test_sync_with_server() {
+        let start = Instant::now();
+        let mut pause = RacePause::default();
+        pause.sync(Some("paused"), start);
+        assert_eq!(pause.state(), PauseState::Paused { since: start });
+        // Still paused: keeps the original start
+        pause.sync(Some("paused"), Instant::now());
+        assert_eq!(pause.state(), PauseState::Paused { since: start });
+        pause.sync(None, start);
+        assert_eq!(pause.state(), PauseState::Running);
+        pause.sync(Some("requested"), start);
+        assert_eq!(pause.state(), PauseState::Requested);
+        pause.reset();
+        assert_eq!(pause.state(), PauseState::Running);
+    }
+}
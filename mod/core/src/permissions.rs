@@ -0,0 +1,78 @@
+//! Race permissions
+//!
+//! Organizers of "blind" races can turn off the overlay features that give
+//! away information. The server sends the allowed features as a bitfield in
+//! auth_ok; they take precedence over the local config and overlay profile.
+
+use serde::{Deserialize, Serialize};
+
+/// Mod features allowed by the race (auth_ok `permissions`, protocol 13+)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Permissions(pub u32);
+
+impl Permissions {
+    /// Exit list of the current zone
+    pub const EXITS_PANEL: u32 = 1 << 0;
+    /// Leaderboard and ready room participant list
+    pub const LEADERBOARD: u32 = 1 << 1;
+    /// Zone tiers and scaling warnings
+    pub const SCALING_INFO: u32 = 1 << 2;
+    /// World-map thumbnail and distances to undiscovered gates
+    pub const MAP: u32 = 1 << 3;
+
+    pub const ALL: Permissions =
+        Permissions(Self::EXITS_PANEL | Self::LEADERBOARD | Self::SCALING_INFO | Self::MAP);
+
+    fn allows(self, bit: u32) -> bool {
+        self.0 & bit != 0
+    }
+
+    pub fn exits_panel(self) -> bool {
+        self.allows(Self::EXITS_PANEL)
+    }
+
+    pub fn leaderboard(self) -> bool {
+        self.allows(Self::LEADERBOARD)
+    }
+
+    pub fn scaling_info(self) -> bool {
+        self.allows(Self::SCALING_INFO)
+    }
+
+    pub fn map(self) -> bool {
+        self.allows(Self::MAP)
+    }
+}
+
+/// Older servers don't restrict anything
+impl Default for Permissions {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_allows_everything() {
+        let permissions = Permissions::default();
+        assert!(permissions.exits_panel());
+        assert!(permissions.leaderboard());
+        assert!(permissions.scaling_info());
+        assert!(permissions.map());
+    }
+
+    #[test]
+    fn test_blind_race() {
+        let permissions = Permissions(Permissions::EXITS_PANEL | Permissions::SCALING_INFO);
+        assert!(permissions.exits_panel());
+        assert!(!permissions.leaderboard());
+        assert!(permissions.scaling_info());
+        assert!(!permissions.map());
+        // Bits from newer servers are ignored
+        assert!(!Permissions(1 << 10).exits_panel());
+    }
+}
@@ -0,0 +1,99 @@
+//! Player stats for a richer HUD
+//!
+//! Rune level and held runes, read once per frame with the other memory
+//! values, and flask charges, polled every second (an inventory scan), for
+//! the templates' `{level}`, `{souls}` and `{flasks}` (race timer). Flask
+//! charges are the quantity of the filled flask in the inventory: each
+//! upgrade level is a separate goods ID, with the empty flask on the next
+//! (odd) one.
+
+use std::fmt;
+
+/// Flask of Crimson Tears +0, filled; +N is `+ 2 * N`
+const CRIMSON_FLASK_GOODS: u32 = 1000;
+/// Flask of Cerulean Tears +0, filled
+const CERULEAN_FLASK_GOODS: u32 = 1050;
+/// Highest flask upgrade (+12)
+const MAX_FLASK_UPGRADE: u32 = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlaskKind {
+    /// HP
+    Crimson,
+    /// FP
+    Cerulean,
+}
+
+impl FlaskKind {
+    /// Kind of a filled flask goods ID, None for any other item
+    pub fn from_goods(goods_id: u32) -> Option<Self> {
+        // Filled flasks are on the even IDs from the +0 one
+        let filled = |base: u32| {
+            (base..=base + 2 * MAX_FLASK_UPGRADE).contains(&goods_id) && goods_id & 1 == base & 1
+        };
+        if filled(CRIMSON_FLASK_GOODS) {
+            Some(FlaskKind::Crimson)
+        } else if filled(CERULEAN_FLASK_GOODS) {
+            Some(FlaskKind::Cerulean)
+        } else {
+            None
+        }
+    }
+}
+
+/// Flask charges left, shown as "crimson/cerulean"
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Flasks {
+    pub crimson: u32,
+    pub cerulean: u32,
+}
+
+impl Flasks {
+    /// Count an inventory entry; other items are ignored
+    pub fn add(&mut self, goods_id: u32, quantity: u32) {
+        match FlaskKind::from_goods(goods_id) {
+            Some(FlaskKind::Crimson) => self.crimson += quantity,
+            Some(FlaskKind::Cerulean) => self.cerulean += quantity,
+            None => {}
+        }
+    }
+}
+
+impl fmt::Display for Flasks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.crimson, self.cerulean)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flask_goods() {
+        assert_eq!(FlaskKind::from_goods(1000), Some(FlaskKind::Crimson));
+        assert_eq!(FlaskKind::from_goods(1024), Some(FlaskKind::Crimson));
+        assert_eq!(FlaskKind::from_goods(1062), Some(FlaskKind::Cerulean));
+        // Empty flasks, past +12, other goods
+        assert_eq!(FlaskKind::from_goods(1001), None);
+        assert_eq!(FlaskKind::from_goods(1026), None);
+        assert_eq!(FlaskKind::from_goods(8010), None);
+    }
+
+    #[test]
+    fn test_flask_charges() {
+        let mut flasks = Flasks::default();
+        flasks.add(1006, 4); // Crimson +3
+        flasks.add(1007, 2); // its empty charges
+        flasks.add(1050, 3);
+        flasks.add(8010, 1);
+        assert_eq!(
+            flasks,
+            Flasks {
+                crimson: 4,
+                cerulean: 3
+            }
+        );
+        assert_eq!(flasks.to_string(), "4/3");
+    }
+}
@@ -0,0 +1,172 @@
+//! Leaderboard position changes of the local player
+//!
+//! Each leaderboard update is compared with the position last announced, and
+//! a net gain or loss raises a "you passed X" / "X passed you" toast. Swaps
+//! back and forth within the cooldown cancel out instead of spamming toasts:
+//! only the position at the end of the cooldown is compared.
+
+use std::time::{Duration, Instant};
+
+use super::protocol::ParticipantInfo;
+
+/// Minimum delay between two position toasts
+pub const COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PositionChange {
+    /// Moved up to `position` (1-based), now right ahead of `passed`
+    Gained { position: usize, passed: String },
+    /// Moved down to `position` (1-based), now right behind `by`
+    Lost { position: usize, by: String },
+}
+
+#[derive(Debug, Default)]
+pub struct PositionWatch {
+    /// Position (0-based) last announced, or seen on the first update
+    baseline: Option<usize>,
+    last_alert: Option<Instant>,
+}
+
+impl PositionWatch {
+    /// Compare a leaderboard update (sorted by the server) with the baseline
+    pub fn observe(
+        &mut self,
+        now: Instant,
+        participants: &[ParticipantInfo],
+        my_id: &str,
+    ) -> Option<PositionChange> {
+        let index = participants.iter().position(|p| p.id == my_id)?;
+        let Some(baseline) = self.baseline else {
+            self.baseline = Some(index);
+            return None;
+        };
+        if index == baseline
+            || self
+                .last_alert
+                .is_some_and(|last| now.duration_since(last) < COOLDOWN)
+        {
+            return None;
+        }
+        let change = if index < baseline {
+            PositionChange::Gained {
+                position: index + 1,
+                passed: display_name(participants.get(index + 1)?),
+            }
+        } else {
+            PositionChange::Lost {
+                position: index + 1,
+                by: display_name(participants.get(index.checked_sub(1)?)?),
+            }
+        };
+        self.baseline = Some(index);
+        self.last_alert = Some(now);
+        Some(change)
+    }
+
+    /// Forget the baseline (race over, new seed)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+fn display_name(p: &ParticipantInfo) -> String {
+    p.twitch_display_name
+        .clone()
+        .unwrap_or_else(|| p.twitch_username.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn participant(id: &str) -> ParticipantInfo {
+        ParticipantInfo {
+            id: id.to_string(),
+            twitch_username: id.to_string(),
+            twitch_display_name: Some(id.to_uppercase()),
+            status: "playing".to_string(),
+            current_zone: None,
+            current_zone_name: None,
+            current_layer: 0,
+            current_layer_tier: None,
+            igt_ms: 0,
+            death_count: 0,
+            gap_ms: None,
+            layer_entry_igt: None,
+            zone_entry_igt: None,
+            key_items: Vec::new(),
+            objectives_completed: Vec::new(),
+            afk: false,
+            pause: None,
+            checkpoints: Vec::new(),
+            badges: Vec::new(),
+        }
+    }
+
+    fn board(ids: &[&str]) -> Vec<ParticipantInfo> {
+        ids.iter().map(|id| participant(id)).collect()
+    }
+
+    #[test]
+    fn test_first_update_sets_baseline() {
+        let mut watch = PositionWatch::default();
+        let now = Instant::now();
+        assert_eq!(watch.observe(now, &board(&["a", "me", "b"]), "me"), None);
+        assert_eq!(watch.observe(now, &board(&["a", "me", "b"]), "me"), None);
+        // Not in the leaderboard
+        assert_eq!(watch.observe(now, &board(&["a", "b"]), "me"), None);
+    }
+
+    #[test]
+    fn test_gained_and_lost() {
+        let mut watch = PositionWatch::default();
+        let start = Instant::now();
+        watch.observe(start, &board(&["a", "me", "b"]), "me");
+        assert_eq!(
+            watch.observe(start, &board(&["me", "a", "b"]), "me"),
+            Some(PositionChange::Gained {
+                position: 1,
+                passed: "A".to_string()
+            })
+        );
+        let later = start + COOLDOWN;
+        assert_eq!(
+            watch.observe(later, &board(&["a", "b", "me"]), "me"),
+            Some(PositionChange::Lost {
+                position: 3,
+                by: "B".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_cooldown_keeps_net_change() {
+        let mut watch = PositionWatch::default();
+        let start = Instant::now();
+        watch.observe(start, &board(&["a", "me"]), "me");
+        assert!(watch.observe(start, &board(&["me", "a"]), "me").is_some());
+        // Passed back and forth during the cooldown: nothing to say
+        let soon = start + Duration::from_secs(5);
+        assert_eq!(watch.observe(soon, &board(&["a", "me"]), "me"), None);
+        assert_eq!(watch.observe(soon, &board(&["me", "a"]), "me"), None);
+        let later = start + COOLDOWN;
+        assert_eq!(watch.observe(later, &board(&["me", "a"]), "me"), None);
+        // Net loss once the cooldown is over
+        assert_eq!(
+            watch.observe(later, &board(&["a", "me"]), "me"),
+            Some(PositionChange::Lost {
+                position: 2,
+                by: "A".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut watch = PositionWatch::default();
+        let now = Instant::now();
+        watch.observe(now, &board(&["a", "me"]), "me");
+        watch.reset();
+        assert_eq!(watch.observe(now, &board(&["me", "a"]), "me"), None);
+    }
+}
@@ -0,0 +1,386 @@
+//! Post-race summary export
+//!
+//! Builds a standardized summary of the player's run (final IGT, local vs
+//! server finish time, play vs load time, deaths and their causes, zone
+//! route, segments, boss kills, placement) and renders it as JSON or
+//! Markdown. The DLL writes it next to itself when the race finishes.
+
+use serde::{Deserialize, Serialize};
+
+use super::death_cause::DeathCause;
+use super::protocol::ParticipantInfo;
+use super::segments::SegmentResult;
+
+/// Output format of the summary file (`[post_race] format` in the config)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryFormat {
+    /// Don't write a summary
+    Off,
+    #[default]
+    Json,
+    Markdown,
+}
+
+impl SummaryFormat {
+    /// File extension, or None when export is disabled
+    pub fn extension(self) -> Option<&'static str> {
+        match self {
+            SummaryFormat::Off => None,
+            SummaryFormat::Json => Some("json"),
+            SummaryFormat::Markdown => Some("md"),
+        }
+    }
+}
+
+/// A zone revealed on the overlay, in route order
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ZoneVisit {
+    pub name: String,
+    pub tier: Option<i32>,
+    pub igt_ms: u32,
+}
+
+/// A boss kill detected through its event flag
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BossKill {
+    pub flag_id: u32,
+    pub igt_ms: u32,
+    /// The seed's finish event (final boss)
+    pub final_boss: bool,
+}
+
+/// A death, attributed by `core::death_cause`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeathEntry {
+    pub igt_ms: u32,
+    /// Last zone revealed before the death
+    pub zone: Option<String>,
+    pub cause: DeathCause,
+}
+
+/// Summary of a single race run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RaceSummary {
+    pub race_id: String,
+    pub race_name: String,
+    pub player: Option<String>,
+    pub final_igt_ms: u32,
+    /// Wall time with IGT ticking
+    pub play_time_ms: u32,
+    /// Wall time with IGT stopped (loading screens, quit-outs)
+    pub load_time_ms: u32,
+    pub deaths: u32,
+    /// IGT read when the finish flag was detected locally, before the server
+    /// recorded it (None if the player didn't finish)
+    pub local_finish_igt_ms: Option<u32>,
+    /// Finish IGT recorded by the server
+    pub server_finish_igt_ms: Option<u32>,
+    /// 1-based leaderboard position, None if the player isn't listed
+    pub placement: Option<usize>,
+    pub participant_count: usize,
+    pub zones: Vec<ZoneVisit>,
+    /// Seed segments started during the run (see `core::segments`)
+    pub segments: Vec<SegmentResult>,
+    pub boss_kills: Vec<BossKill>,
+    pub death_log: Vec<DeathEntry>,
+}
+
+/// Race id usable in a file name (anything but ASCII alphanumerics and '-'
+/// becomes '_')
+pub fn file_safe_id(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+impl RaceSummary {
+    /// File name for this summary, e.g. `speedfog_summary_<race_id>.json`
+    pub fn file_name(&self, format: SummaryFormat) -> Option<String> {
+        let ext = format.extension()?;
+        Some(format!(
+            "speedfog_summary_{}.{}",
+            file_safe_id(&self.race_id),
+            ext
+        ))
+    }
+
+    /// Render in the given format, or None when export is disabled
+    pub fn render(&self, format: SummaryFormat) -> Option<Result<String, String>> {
+        match format {
+            SummaryFormat::Off => None,
+            SummaryFormat::Json => Some(
+                serde_json::to_string_pretty(self)
+                    .map_err(|e| format!("Failed to serialize summary: {}", e)),
+            ),
+            SummaryFormat::Markdown => Some(Ok(self.to_markdown())),
+        }
+    }
+
+    /// Server minus local finish IGT, when both are known
+    pub fn finish_delta_ms(&self) -> Option<i64> {
+        Some(self.server_finish_igt_ms? as i64 - self.local_finish_igt_ms? as i64)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# SpeedFog Race Summary — {}\n\n", self.race_name);
+        if let Some(ref player) = self.player {
+            out.push_str(&format!("- **Player:** {}\n", player));
+        }
+        out.push_str(&format!(
+            "- **Final IGT:** {}\n",
+            format_igt(self.final_igt_ms)
+        ));
+        out.push_str(&format!(
+            "- **Play time:** {}\n- **Load time:** {}\n",
+            format_igt(self.play_time_ms),
+            format_igt(self.load_time_ms)
+        ));
+        if let (Some(local), Some(server), Some(delta)) = (
+            self.local_finish_igt_ms,
+            self.server_finish_igt_ms,
+            self.finish_delta_ms(),
+        ) {
+            out.push_str(&format!(
+                "- **Finish (local / server):** {} / {} ({:+} ms)\n",
+                format_igt(local),
+                format_igt(server),
+                delta
+            ));
+        }
+        out.push_str(&format!("- **Deaths:** {}\n", self.deaths));
+        match self.placement {
+            Some(place) => out.push_str(&format!(
+                "- **Placement:** {} / {}\n",
+                place, self.participant_count
+            )),
+            None => out.push_str("- **Placement:** -\n"),
+        }
+
+        out.push_str("\n## Zones\n\n| # | Zone | Tier | IGT |\n|---|------|------|-----|\n");
+        for (i, zone) in self.zones.iter().enumerate() {
+            let tier = zone.tier.map(|t| t.to_string()).unwrap_or_default();
+            out.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                i + 1,
+                zone.name.replace('|', "\\|"),
+                tier,
+                format_igt(zone.igt_ms)
+            ));
+        }
+
+        if !self.segments.is_empty() {
+            out.push_str(
+                "\n## Segments\n\n| Segment | Time | Deaths |\n|---------|------|--------|\n",
+            );
+            for segment in &self.segments {
+                // Unfinished segments have no time
+                let time = match segment.end_igt_ms {
+                    Some(end) => format_igt(end - segment.start_igt_ms),
+                    None => "-".to_string(),
+                };
+                out.push_str(&format!(
+                    "| {} | {} | {} |\n",
+                    segment.name.replace('|', "\\|"),
+                    time,
+                    segment.deaths
+                ));
+            }
+        }
+
+        out.push_str("\n## Boss kills\n\n| Flag | IGT |\n|------|-----|\n");
+        for kill in &self.boss_kills {
+            let label = if kill.final_boss {
+                format!("{} (final)", kill.flag_id)
+            } else {
+                kill.flag_id.to_string()
+            };
+            out.push_str(&format!("| {} | {} |\n", label, format_igt(kill.igt_ms)));
+        }
+
+        out.push_str("\n## Deaths\n\n| # | Cause | Zone | IGT |\n|---|-------|------|-----|\n");
+        for (i, death) in self.death_log.iter().enumerate() {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                i + 1,
+                death.cause.label(),
+                death.zone.as_deref().unwrap_or("").replace('|', "\\|"),
+                format_igt(death.igt_ms)
+            ));
+        }
+        out
+    }
+}
+
+/// 1-based position of `participant_id` in the server-ordered leaderboard
+pub fn placement(participants: &[ParticipantInfo], participant_id: &str) -> Option<usize> {
+    participants
+        .iter()
+        .position(|p| p.id == participant_id)
+        .map(|i| i + 1)
+}
+
+/// Format IGT as `H:MM:SS.mmm`
+fn format_igt(ms: u32) -> String {
+    let secs = ms / 1000;
+    format!(
+        "{}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60,
+        ms % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> RaceSummary {
+        RaceSummary {
+            race_id: "abc-123".to_string(),
+            race_name: "Sunday Race".to_string(),
+            player: Some("Tarnished".to_string()),
+            final_igt_ms: 3_723_456,
+            play_time_ms: 3_725_000,
+            load_time_ms: 95_250,
+            deaths: 7,
+            local_finish_igt_ms: Some(3_723_400),
+            server_finish_igt_ms: Some(3_723_456),
+            placement: Some(2),
+            participant_count: 5,
+            zones: vec![
+                ZoneVisit {
+                    name: "Stormveil Castle".to_string(),
+                    tier: Some(3),
+                    igt_ms: 312_000,
+                },
+                ZoneVisit {
+                    name: "Chapel | Start".to_string(),
+                    tier: None,
+                    igt_ms: 0,
+                },
+            ],
+            segments: vec![
+                SegmentResult {
+                    name: "Castle".to_string(),
+                    start_igt_ms: 60_000,
+                    end_igt_ms: Some(312_000),
+                    deaths: 2,
+                },
+                SegmentResult {
+                    name: "Endgame".to_string(),
+                    start_igt_ms: 3_000_000,
+                    end_igt_ms: None,
+                    deaths: 0,
+                },
+            ],
+            boss_kills: vec![BossKill {
+                flag_id: 19000800,
+                igt_ms: 3_723_456,
+                final_boss: true,
+            }],
+            death_log: vec![DeathEntry {
+                igt_ms: 1_000_000,
+                zone: Some("Stormveil Castle".to_string()),
+                cause: DeathCause::Fall,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_format_igt() {
+        assert_eq!(format_igt(0), "0:00:00.000");
+        assert_eq!(format_igt(3_723_456), "1:02:03.456");
+    }
+
+    #[test]
+    fn test_file_name() {
+        let s = sample();
+        assert_eq!(
+            s.file_name(SummaryFormat::Json).as_deref(),
+            Some("speedfog_summary_abc-123.json")
+        );
+        assert_eq!(
+            s.file_name(SummaryFormat::Markdown).as_deref(),
+            Some("speedfog_summary_abc-123.md")
+        );
+        assert_eq!(s.file_name(SummaryFormat::Off), None);
+    }
+
+    #[test]
+    fn test_file_name_sanitizes_race_id() {
+        let mut s = sample();
+        s.race_id = "../x y".to_string();
+        assert_eq!(
+            s.file_name(SummaryFormat::Json).as_deref(),
+            Some("speedfog_summary____x_y.json")
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json = sample().render(SummaryFormat::Json).unwrap().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["final_igt_ms"], 3_723_456);
+        assert_eq!(value["placement"], 2);
+        assert_eq!(value["load_time_ms"], 95_250);
+        assert_eq!(value["local_finish_igt_ms"], 3_723_400);
+        assert_eq!(value["zones"][0]["name"], "Stormveil Castle");
+        assert_eq!(value["segments"][0]["deaths"], 2);
+        assert_eq!(value["boss_kills"][0]["final_boss"], true);
+        assert_eq!(value["death_log"][0]["cause"], "fall");
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let md = sample().render(SummaryFormat::Markdown).unwrap().unwrap();
+        assert!(md.starts_with("# SpeedFog Race Summary — Sunday Race"));
+        assert!(md.contains("- **Final IGT:** 1:02:03.456"));
+        assert!(md.contains("- **Placement:** 2 / 5"));
+        assert!(md.contains("- **Load time:** 0:01:35.250"));
+        assert!(md.contains("- **Finish (local / server):** 1:02:03.400 / 1:02:03.456 (+56 ms)"));
+        assert!(md.contains("| 1 | Stormveil Castle | 3 | 0:05:12.000 |"));
+        // Pipes in zone names don't break the table
+        assert!(md.contains("Chapel \\| Start"));
+        assert!(md.contains("| Castle | 0:04:12.000 | 2 |"));
+        assert!(md.contains("| Endgame | - | 0 |"));
+        assert!(md.contains("| 19000800 (final) | 1:02:03.456 |"));
+        assert!(md.contains("| 1 | fall | Stormveil Castle | 0:16:40.000 |"));
+    }
+
+    #[test]
+    fn test_finish_delta() {
+        let mut s = sample();
+        assert_eq!(s.finish_delta_ms(), Some(56));
+        s.server_finish_igt_ms = Some(3_723_000);
+        assert_eq!(s.finish_delta_ms(), Some(-400));
+        // Finish not confirmed by the server: no reconciliation line
+        s.server_finish_igt_ms = None;
+        assert_eq!(s.finish_delta_ms(), None);
+        let md = s.to_markdown();
+        assert!(!md.contains("Finish (local / server)"));
+    }
+
+    #[test]
+    fn test_render_off() {
+        assert!(sample().render(SummaryFormat::Off).is_none());
+    }
+
+    #[test]
+    fn test_summary_format_deserialize() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            format: SummaryFormat,
+        }
+        let w: Wrapper = toml::from_str("format = \"markdown\"").unwrap();
+        assert_eq!(w.format, SummaryFormat::Markdown);
+        assert!(toml::from_str::<Wrapper>("format = \"pdf\"").is_err());
+    }
+}
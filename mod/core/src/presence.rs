@@ -0,0 +1,135 @@
+//! Discord Rich Presence text
+//!
+//! Formats the race progress shown on the player's Discord profile. The DLL
+//! only publishes it when built with the `discord` feature.
+
+/// Discord rejects activity fields longer than this (in bytes)
+const MAX_FIELD_LEN: usize = 128;
+
+/// Snapshot of the race progress published as Rich Presence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RacePresence {
+    pub race_name: String,
+    pub zone: Option<String>,
+    /// Fog gates discovered so far
+    pub gates: usize,
+    /// 1-based leaderboard position, None if the player isn't listed
+    pub placement: Option<usize>,
+    pub participant_count: usize,
+    /// Race start (ms since epoch), shown by Discord as elapsed time
+    pub started_at_ms: Option<i64>,
+}
+
+impl RacePresence {
+    /// First line: race name and position, e.g. `Sunday Race — 2nd / 5`
+    pub fn details(&self) -> String {
+        let text = match self.placement {
+            Some(place) => format!(
+                "{} — {} / {}",
+                self.race_name,
+                ordinal(place),
+                self.participant_count
+            ),
+            None => self.race_name.clone(),
+        };
+        truncate(text)
+    }
+
+    /// Second line: current zone and discovered gates, e.g. `Stormveil Castle · 3 gates`
+    pub fn state(&self) -> String {
+        let gates = match self.gates {
+            1 => "1 gate".to_string(),
+            n => format!("{} gates", n),
+        };
+        let text = match self.zone {
+            Some(ref zone) => format!("{} · {}", zone, gates),
+            None => gates,
+        };
+        truncate(text)
+    }
+}
+
+/// English ordinal: 1st, 2nd, 3rd, 4th, 11th, 21st...
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// Cut to Discord's field limit on a char boundary, marking the cut with "…"
+fn truncate(mut text: String) -> String {
+    if text.len() <= MAX_FIELD_LEN {
+        return text;
+    }
+    let mut end = MAX_FIELD_LEN - '…'.len_utf8();
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    text.push('…');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> RacePresence {
+        RacePresence {
+            race_name: "Sunday Race".to_string(),
+            zone: Some("Stormveil Castle".to_string()),
+            gates: 3,
+            placement: Some(2),
+            participant_count: 5,
+            started_at_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_details() {
+        assert_eq!(sample().details(), "Sunday Race — 2nd / 5");
+        let p = RacePresence {
+            placement: None,
+            ..sample()
+        };
+        assert_eq!(p.details(), "Sunday Race");
+    }
+
+    #[test]
+    fn test_state() {
+        assert_eq!(sample().state(), "Stormveil Castle · 3 gates");
+        let p = RacePresence {
+            zone: None,
+            gates: 1,
+            ..sample()
+        };
+        assert_eq!(p.state(), "1 gate");
+    }
+
+    #[test]
+    fn test_ordinal() {
+        assert_eq!(ordinal(1), "1st");
+        assert_eq!(ordinal(2), "2nd");
+        assert_eq!(ordinal(3), "3rd");
+        assert_eq!(ordinal(4), "4th");
+        assert_eq!(ordinal(11), "11th");
+        assert_eq!(ordinal(12), "12th");
+        assert_eq!(ordinal(13), "13th");
+        assert_eq!(ordinal(21), "21st");
+        assert_eq!(ordinal(112), "112th");
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short".to_string()), "short");
+        let long = "é".repeat(100);
+        let cut = truncate(long);
+        assert!(cut.len() <= MAX_FIELD_LEN);
+        assert!(cut.ends_with('…'));
+    }
+}
@@ -0,0 +1,1993 @@
+//! WebSocket protocol types for SpeedFog Racing
+//!
+//! Messages exchanged between the mod and the racing server.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::activity_feed::ActivityKind;
+use super::permissions::Permissions;
+use super::zone_privacy::ZonePrivacy;
+
+/// Highest protocol version spoken by this mod.
+///
+/// - 1: original protocol, no negotiation
+/// - 2: version negotiation in auth / auth_ok, clock_sync
+/// - 3: event_flag correlation ids (`seq`) acknowledged by event_flag_ack
+/// - 4: key item pickups (seed `key_items`, item_obtained)
+/// - 5: race objectives (auth_ok `objectives`, objective_complete)
+/// - 6: seed_changed pushed after a seed re-roll
+/// - 7: character fingerprint (auth `character`, character, seed `expected_character`)
+/// - 8: ng_cycle when the character enters NG+
+/// - 9: community exit hints (exit_hints_request, exit_hints)
+/// - 10: afk status, participant `afk` in the leaderboard
+/// - 11: binary encoding negotiated at auth (auth `encodings`, auth_ok `encoding`)
+/// - 12: save_reloaded when IGT or the death count goes backwards
+/// - 13: auth_ok `permissions` (overlay features the race turns off)
+/// - 14: pause_request, pause_granted and resume (pause approved by the organizer)
+/// - 15: race checkpoints (auth_ok `checkpoints`, checkpoint_crossed, participant `checkpoints`)
+/// - 16: kindling_used when a Messmer's Kindling is burnt
+/// - 17: status_update `disconnecting` when the game closes
+/// - 18: grace_discovered when a Site of Grace is touched
+/// - 19: leaderboard `generation`, leaderboard_delta and leaderboard_resync
+/// - 20: set_flag (organizer-triggered event flags, opt-in on the mod)
+/// - 21: auth_ok `event_delay_secs` (broadcast delay of the progress events)
+/// - 22: auth_ok `zone_privacy`, participant `current_zone_name`
+/// - 23: event_flag `idempotency_key` (dedupe across game restarts)
+/// - 24: race_activity (the other racers' milestones for the activity feed)
+/// - 25: timing_drift when IGT and the wall clock disagree
+/// - 26: participant `badges` (organizer, previous winner, streamer)
+/// - 27: participant `zone_entry_igt` (time spent in the current zone)
+pub const PROTOCOL_VERSION: u32 = 27;
+
+/// Version assumed when the peer doesn't negotiate (older mod or server)
+pub const LEGACY_PROTOCOL_VERSION: u32 = 1;
+
+fn legacy_protocol_version() -> u32 {
+    LEGACY_PROTOCOL_VERSION
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Wire encoding of the messages exchanged after auth. The auth handshake is
+/// always JSON; MessagePack travels in binary frames and keeps the same
+/// message shapes (maps with named fields, `type` tag included).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    #[default]
+    Json,
+    Msgpack,
+}
+
+/// An encoded message, sent as a text or a binary WebSocket frame
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl Encoding {
+    /// Encodings this mod offers at auth, preferred first
+    #[cfg(feature = "msgpack")]
+    pub const SUPPORTED: &'static [Encoding] = &[Encoding::Msgpack];
+    /// Encodings this mod offers at auth, preferred first
+    #[cfg(not(feature = "msgpack"))]
+    pub const SUPPORTED: &'static [Encoding] = &[];
+
+    pub fn encode<T: Serialize>(self, msg: &T) -> Result<Frame, String> {
+        match self {
+            Encoding::Json => serde_json::to_string(msg)
+                .map(Frame::Text)
+                .map_err(|e| format!("JSON: {}", e)),
+            #[cfg(feature = "msgpack")]
+            Encoding::Msgpack => rmp_serde::to_vec_named(msg)
+                .map(Frame::Binary)
+                .map_err(|e| format!("MessagePack: {}", e)),
+            #[cfg(not(feature = "msgpack"))]
+            Encoding::Msgpack => Err("MessagePack: built without the msgpack feature".to_string()),
+        }
+    }
+}
+
+// =============================================================================
+// CLIENT -> SERVER MESSAGES
+// =============================================================================
+
+/// Messages sent from mod to server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Authentication with mod token
+    Auth {
+        mod_token: String,
+        /// Highest protocol version the mod speaks
+        #[serde(default = "legacy_protocol_version")]
+        protocol_version: u32,
+        /// Character loaded in the game, when already readable (protocol 7+)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        character: Option<CharacterFingerprint>,
+        /// Binary encodings the mod accepts, preferred first (protocol 11+)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        encodings: Vec<Encoding>,
+    },
+    /// Player is ready to race
+    Ready,
+    /// Periodic status update
+    StatusUpdate {
+        igt_ms: u32,
+        death_count: u32,
+        /// Last update before the game closes (protocol 17+)
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        disconnecting: bool,
+    },
+    /// EMEVD event flag triggered (fog gate traversal or boss kill)
+    EventFlag {
+        flag_id: u32,
+        igt_ms: u32,
+        /// Correlation id acknowledged by the server (protocol 3+)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seq: Option<u64>,
+        /// Same for every send of the flag in the race (protocol 23+)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        idempotency_key: Option<String>,
+    },
+    /// Zone query at loading screen exit (server resolves to graph node)
+    ZoneQuery {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        grace_entity_id: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        map_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        position: Option<[f32; 3]>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        play_region_id: Option<u32>,
+    },
+    /// Key item picked up (protocol 4+)
+    ItemObtained { item_id: u32, igt_ms: u32 },
+    /// Race objective completed (protocol 5+)
+    ObjectiveComplete { objective_id: u32, igt_ms: u32 },
+    /// Character loaded or switched (protocol 7+)
+    Character { character: CharacterFingerprint },
+    /// Character entered NG+, or was already past NG at load (protocol 8+)
+    NgCycle { clear_count: u32, igt_ms: u32 },
+    /// Ask for the community hints of a visited zone's exits (protocol 9+)
+    ExitHintsRequest { node_id: String },
+    /// Player went idle, or came back (protocol 10+)
+    Afk { afk: bool, igt_ms: u32 },
+    /// Save reloaded: IGT or deaths went back, deltas are negative (protocol 12+)
+    SaveReloaded {
+        igt_ms: u32,
+        igt_delta_ms: i32,
+        death_count: u32,
+        death_delta: i32,
+    },
+    /// Ask the organizer to pause the race (protocol 14+)
+    PauseRequest { igt_ms: u32 },
+    /// Race checkpoint crossed (protocol 15+)
+    CheckpointCrossed { checkpoint_id: u32, igt_ms: u32 },
+    /// Messmer's Kindling used: the count went down by `used` (protocol 16+)
+    KindlingUsed {
+        igt_ms: u32,
+        used: u32,
+        remaining: u32,
+    },
+    /// Site of Grace touched: its unlock flag got set (protocol 18+)
+    GraceDiscovered { flag_id: u32, igt_ms: u32 },
+    /// A leaderboard_delta was missed: ask for the full leaderboard (protocol 19+)
+    LeaderboardResync,
+    /// IGT ran faster or slower than the wall clock over a window (protocol 25+)
+    TimingDrift {
+        igt_ms: u32,
+        igt_delta_ms: u32,
+        wall_delta_ms: u32,
+    },
+    /// Heartbeat response
+    Pong,
+    /// Clock sync probe (local wall clock, ms since epoch)
+    ClockSync { client_time_ms: i64 },
+}
+
+// =============================================================================
+// SERVER -> CLIENT MESSAGES
+// =============================================================================
+
+/// Participant info in leaderboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantInfo {
+    pub id: String,
+    pub twitch_username: String,
+    pub twitch_display_name: Option<String>,
+    pub status: String,
+    pub current_zone: Option<String>,
+    /// Display name of `current_zone` (protocol 22+)
+    #[serde(default)]
+    pub current_zone_name: Option<String>,
+    pub current_layer: i32,
+    #[serde(default)]
+    pub current_layer_tier: Option<i32>,
+    pub igt_ms: i32,
+    pub death_count: i32,
+    #[serde(default)]
+    pub gap_ms: Option<i32>,
+    #[serde(default)]
+    pub layer_entry_igt: Option<i32>,
+    /// IGT when entering `current_zone`, its latest visit (protocol 27+)
+    #[serde(default)]
+    pub zone_entry_igt: Option<i32>,
+    /// Key items obtained, in pickup order (protocol 4+)
+    #[serde(default)]
+    pub key_items: Vec<u32>,
+    /// Race objectives completed, in completion order (protocol 5+)
+    #[serde(default)]
+    pub objectives_completed: Vec<u32>,
+    /// Idle for a while, as reported by their mod (protocol 10+)
+    #[serde(default)]
+    pub afk: bool,
+    /// "requested" or "paused" while the player's pause is pending or granted (protocol 14+)
+    #[serde(default)]
+    pub pause: Option<String>,
+    /// Checkpoints crossed, in crossing order (protocol 15+)
+    #[serde(default)]
+    pub checkpoints: Vec<CheckpointTime>,
+    /// Badge identifiers, see `badges::badges` (protocol 26+)
+    #[serde(default)]
+    pub badges: Vec<String>,
+}
+
+/// IGT at which a participant crossed a checkpoint (protocol 15+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointTime {
+    pub checkpoint_id: u32,
+    pub igt_ms: i32,
+}
+
+/// Race info from server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RaceInfo {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    /// Authoritative race start (ISO 8601), None before the race starts
+    #[serde(default)]
+    pub started_at: Option<String>,
+}
+
+/// Item to be spawned at runtime by the mod (e.g., Gem/Ash of War).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpawnItem {
+    pub id: u32,
+    #[serde(default = "default_qty")]
+    pub qty: u32,
+}
+
+fn default_qty() -> u32 {
+    1
+}
+
+/// Seed-relevant item, detected by its pickup event flag (protocol 4+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyItem {
+    pub id: u32,
+    pub flag_id: u32,
+    pub name: String,
+}
+
+/// Run segment defined by the seed: from the first arrival in `from_node`
+/// to the next arrival in `to_node`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Segment {
+    pub name: String,
+    pub from_node: String,
+    pub to_node: String,
+}
+
+/// Start of the race: the graph's start node, and the starting grace's
+/// location when the seed provides it (checked by `core::warmup`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StartLocation {
+    pub node_id: String,
+    #[serde(default)]
+    pub map_id: Option<String>,
+    /// Local to `map_id`
+    #[serde(default)]
+    pub position: Option<[f32; 3]>,
+}
+
+/// Custom race objective (bingo / scavenger races), completed when its event
+/// flag is set (protocol 5+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Objective {
+    pub id: u32,
+    pub text: String,
+    pub flag_id: u32,
+}
+
+/// Intermediate race checkpoint, crossed when its event flag is set or on
+/// entering its zone (protocol 15+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub id: u32,
+    pub name: String,
+    #[serde(default)]
+    pub flag_id: Option<u32>,
+    /// Graph node, as in zone_update
+    #[serde(default)]
+    pub node_id: Option<String>,
+}
+
+/// Character loaded in the game (protocol 7+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CharacterFingerprint {
+    /// Save slot, None when unreadable
+    pub slot: Option<u32>,
+    pub name: String,
+    pub level: u32,
+    /// Starting class (0 = Vagabond ... 9 = Wretch)
+    pub class_id: u8,
+}
+
+/// Starting class and level the seed was generated for (protocol 7+)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedCharacter {
+    #[serde(default)]
+    pub class_id: Option<u8>,
+    #[serde(default)]
+    pub level: Option<u32>,
+}
+
+/// Seed info from server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeedInfo {
+    pub total_layers: i32,
+    #[serde(default)]
+    pub event_ids: Vec<u32>,
+    /// Flag ID for the final boss kill — sent immediately (no loading screen).
+    #[serde(default)]
+    pub finish_event: Option<u32>,
+    #[serde(default)]
+    pub spawn_items: Vec<SpawnItem>,
+    #[serde(default)]
+    pub key_items: Vec<KeyItem>,
+    /// Seed ID — compared against config to detect stale seed packs after re-roll
+    #[serde(default)]
+    pub seed_id: Option<String>,
+    #[serde(default)]
+    pub expected_character: Option<ExpectedCharacter>,
+    /// Segments timed separately (see `core::segments`)
+    #[serde(default)]
+    pub segments: Vec<Segment>,
+    #[serde(default)]
+    pub start: Option<StartLocation>,
+}
+
+/// Exit info in zone_update message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExitInfo {
+    pub text: String,
+    pub to_name: String,
+    pub discovered: bool,
+    /// Node id of the destination, to match the zones visited this run
+    /// (older servers don't send it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_node_id: Option<String>,
+    /// Map of the fog gate ("mWW_XX_YY_DD"), when the seed provides its location
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map_id: Option<String>,
+    /// Fog gate position, local to `map_id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<[f32; 3]>,
+}
+
+/// Community hint for one exit of a zone (protocol 9+)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExitHint {
+    /// Index of the exit in the zone_update `exits` list
+    pub exit_index: u32,
+    pub text: String,
+}
+
+/// Messages received from server
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Authentication successful
+    AuthOk {
+        participant_id: String,
+        race: RaceInfo,
+        seed: SeedInfo,
+        participants: Vec<ParticipantInfo>,
+        /// Version negotiated for this connection (absent from older servers)
+        #[serde(default = "legacy_protocol_version")]
+        protocol_version: u32,
+        /// Race objectives (protocol 5+, empty for regular races)
+        #[serde(default)]
+        objectives: Vec<Objective>,
+        /// Race checkpoints (protocol 15+, empty when the race has none)
+        #[serde(default)]
+        checkpoints: Vec<Checkpoint>,
+        /// Encoding chosen for the rest of the connection (protocol 11+)
+        #[serde(default)]
+        encoding: Encoding,
+        /// Overlay features allowed by the race (protocol 13+, all when absent)
+        #[serde(default)]
+        permissions: Permissions,
+        /// Seconds the mod holds its progress events back before sending
+        /// them (protocol 21+, broadcast races; 0 when absent)
+        #[serde(default)]
+        event_delay_secs: u32,
+        /// How much of the other participants' zones the leaderboard shows
+        /// (protocol 22+, names when absent)
+        #[serde(default)]
+        zone_privacy: ZonePrivacy,
+    },
+    /// Authentication failed
+    AuthError { message: String },
+    /// Race has started
+    RaceStart {
+        #[serde(default)]
+        started_at: Option<String>,
+    },
+    /// Leaderboard update
+    LeaderboardUpdate {
+        participants: Vec<ParticipantInfo>,
+        #[serde(default)]
+        leader_splits: Option<HashMap<String, i32>>,
+        /// Broadcast counter the deltas follow (protocol 19+)
+        #[serde(default)]
+        generation: Option<u64>,
+    },
+    /// Participants changed since the previous generation, and the new
+    /// standings as participant ids (protocol 19+)
+    LeaderboardDelta {
+        generation: u64,
+        #[serde(default)]
+        participants: Vec<ParticipantInfo>,
+        order: Vec<String>,
+        #[serde(default)]
+        leader_splits: Option<HashMap<String, i32>>,
+    },
+    /// Race status changed
+    RaceStatusChange { status: String },
+    /// Single player update
+    PlayerUpdate { player: ParticipantInfo },
+    /// Zone update (unicast to originating mod)
+    ZoneUpdate {
+        node_id: String,
+        display_name: String,
+        tier: Option<i32>,
+        #[serde(default)]
+        original_tier: Option<i32>,
+        #[serde(default)]
+        exits: Vec<ExitInfo>,
+    },
+    /// Heartbeat ping
+    Ping,
+    /// Answer to a clock sync probe
+    ClockSync {
+        client_time_ms: i64,
+        server_time_ms: i64,
+    },
+    /// Event flag with this correlation id was processed (protocol 3+)
+    EventFlagAck { seq: u64 },
+    /// A participant picked up a key item (protocol 4+)
+    ItemObtained {
+        participant_id: String,
+        player_name: String,
+        item_id: u32,
+        item_name: String,
+        igt_ms: u32,
+    },
+    /// A participant reached a milestone: new zone, boss down, finish
+    /// (protocol 24+)
+    RaceActivity {
+        participant_id: String,
+        player_name: String,
+        kind: ActivityKind,
+        /// None when the race hides the zones (`zone_privacy`)
+        #[serde(default)]
+        zone_name: Option<String>,
+        igt_ms: u32,
+    },
+    /// The organizer re-rolled the seed (protocol 6+)
+    SeedChanged { seed: SeedInfo },
+    /// Community hints of a zone's exits, answering exit_hints_request (protocol 9+)
+    ExitHints {
+        node_id: String,
+        #[serde(default)]
+        hints: Vec<ExitHint>,
+    },
+    /// The organizer granted the pause request (protocol 14+)
+    PauseGranted,
+    /// The organizer ended the pause or declined the request (protocol 14+)
+    Resume,
+    /// The organizer sets an event flag in the game (protocol 20+), applied
+    /// only when `[remote_flags]` allows it
+    SetFlag {
+        flag_id: u32,
+        #[serde(default = "default_true")]
+        value: bool,
+    },
+    /// Generic error from server (e.g., race not running)
+    Error { message: String },
+    /// Message type this mod doesn't know (newer server)
+    #[serde(other)]
+    Unknown,
+}
+
+impl ServerMessage {
+    /// Protocol version that introduced this message
+    pub fn min_version(&self) -> u32 {
+        match self {
+            ServerMessage::ClockSync { .. } => 2,
+            ServerMessage::EventFlagAck { .. } => 3,
+            ServerMessage::ItemObtained { .. } => 4,
+            ServerMessage::SeedChanged { .. } => 6,
+            ServerMessage::ExitHints { .. } => 9,
+            ServerMessage::PauseGranted | ServerMessage::Resume => 14,
+            ServerMessage::LeaderboardDelta { .. } => 19,
+            ServerMessage::SetFlag { .. } => 20,
+            ServerMessage::RaceActivity { .. } => 24,
+            _ => LEGACY_PROTOCOL_VERSION,
+        }
+    }
+}
+
+/// Outcome of decoding a server message
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decoded {
+    Message(Box<ServerMessage>),
+    /// Unknown type, or not part of the negotiated version. `first` is true the
+    /// first time this type is seen, so callers log it once.
+    Unsupported {
+        msg_type: String,
+        first: bool,
+    },
+    /// Malformed message of a known type
+    Invalid(String),
+}
+
+/// Decodes server messages according to the negotiated protocol version.
+///
+/// Unknown fields are ignored by serde; unknown message types are reported
+/// instead of failing, so an older mod keeps working against a newer server.
+#[derive(Debug)]
+pub struct ServerDecoder {
+    version: u32,
+    unsupported_seen: HashSet<String>,
+}
+
+impl Default for ServerDecoder {
+    fn default() -> Self {
+        Self {
+            version: LEGACY_PROTOCOL_VERSION,
+            unsupported_seen: HashSet::new(),
+        }
+    }
+}
+
+impl ServerDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Adopt the version from auth_ok, capped to what this mod speaks
+    pub fn set_version(&mut self, negotiated: u32) {
+        self.version = negotiated.clamp(LEGACY_PROTOCOL_VERSION, PROTOCOL_VERSION);
+    }
+
+    pub fn decode(&mut self, text: &str) -> Decoded {
+        match serde_json::from_str(text) {
+            Ok(value) => self.decode_value(value),
+            Err(e) => Decoded::Invalid(format!("Parse: {}", e)),
+        }
+    }
+
+    /// Decode a binary (MessagePack) frame
+    #[cfg(feature = "msgpack")]
+    pub fn decode_binary(&mut self, bytes: &[u8]) -> Decoded {
+        match rmp_serde::from_slice(bytes) {
+            Ok(value) => self.decode_value(value),
+            Err(e) => Decoded::Invalid(format!("Parse: {}", e)),
+        }
+    }
+
+    /// Decode a binary (MessagePack) frame
+    #[cfg(not(feature = "msgpack"))]
+    pub fn decode_binary(&mut self, _bytes: &[u8]) -> Decoded {
+        Decoded::Invalid("Parse: built without the msgpack feature".to_string())
+    }
+
+    fn decode_value(&mut self, value: serde_json::Value) -> Decoded {
+        let msg_type = value
+            .get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or_default()
+            .to_string();
+        match serde_json::from_value::<ServerMessage>(value) {
+            Ok(ServerMessage::Unknown) => self.unsupported(msg_type),
+            Ok(msg) if msg.min_version() > self.version => self.unsupported(msg_type),
+            Ok(msg) => Decoded::Message(Box::new(msg)),
+            Err(e) => Decoded::Invalid(format!("Invalid {}: {}", msg_type, e)),
+        }
+    }
+
+    fn unsupported(&mut self, msg_type: String) -> Decoded {
+        let first = self.unsupported_seen.insert(msg_type.clone());
+        Decoded::Unsupported { msg_type, first }
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unwrap a decoded message, for matching on its variant
+    fn message(decoded: Decoded) -> ServerMessage {
+        match decoded {
+            Decoded::Message(msg) => *msg,
+            other => panic!("Expected a message, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_client_auth_serialize() {
+        let msg = ClientMessage::Auth {
+            mod_token: "test123".to_string(),
+            protocol_version: PROTOCOL_VERSION,
+            character: None,
+            encodings: vec![Encoding::Msgpack],
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains(r#""type":"auth""#));
+        assert!(json.contains(r#""mod_token":"test123""#));
+        assert!(json.contains(r#""protocol_version":27"#));
+        assert!(json.contains(r#""encodings":["msgpack"]"#));
+        assert!(!json.contains("character"));
+    }
+
+    #[test]
+    fn test_client_auth_without_version_is_legacy() {
+        let msg: ClientMessage =
+            serde_json::from_str(r#"{"type": "auth", "mod_token": "t"}"#).unwrap();
+        assert_eq!(
+            msg,
+            ClientMessage::Auth {
+                mod_token: "t".to_string(),
+                protocol_version: LEGACY_PROTOCOL_VERSION,
+                character: None,
+                encodings: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_client_status_update_serialize() {
+        let msg = ClientMessage::StatusUpdate {
+            igt_ms: 123456,
+            death_count: 5,
+            disconnecting: false,
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains(r#""type":"status_update""#));
+        assert!(json.contains(r#""igt_ms":123456"#));
+        assert!(json.contains(r#""death_count":5"#));
+        // Should NOT contain current_zone or current_layer
+        assert!(!json.contains("current_zone"));
+        assert!(!json.contains("current_layer"));
+        assert!(!json.contains("disconnecting"));
+
+        let last = ClientMessage::StatusUpdate {
+            igt_ms: 123456,
+            death_count: 5,
+            disconnecting: true,
+        };
+        let json = serde_json::to_string(&last).unwrap();
+        assert!(json.contains(r#""disconnecting":true"#));
+    }
+
+    #[test]
+    fn test_client_event_flag_serialize() {
+        let msg = ClientMessage::EventFlag {
+            flag_id: 9000042,
+            igt_ms: 60000,
+            seq: None,
+            idempotency_key: None,
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains(r#""type":"event_flag""#));
+        assert!(json.contains(r#""flag_id":9000042"#));
+        assert!(json.contains(r#""igt_ms":60000"#));
+        assert!(!json.contains("seq"));
+    }
+
+    #[test]
+    fn test_client_event_flag_with_seq() {
+        let msg = ClientMessage::EventFlag {
+            flag_id: 9000042,
+            igt_ms: 60000,
+            seq: Some(1_700_000_000_000_001),
+            idempotency_key: None,
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains(r#""seq":1700000000000001"#));
+        assert!(!json.contains("idempotency_key"));
+    }
+
+    #[test]
+    fn test_client_event_flag_with_idempotency_key() {
+        let msg = ClientMessage::EventFlag {
+            flag_id: 9000042,
+            igt_ms: 60000,
+            seq: Some(1_700_000_000_000_001),
+            idempotency_key: Some("race-1:00000000000000ff:9000042".to_string()),
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains(r#""idempotency_key":"race-1:00000000000000ff:9000042""#));
+    }
+
+    #[test]
+    fn test_decoder_event_flag_ack_requires_v3() {
+        let msg = r#"{"type": "event_flag_ack", "seq": 42}"#;
+        let mut decoder = ServerDecoder::new();
+        decoder.set_version(2);
+        assert!(matches!(decoder.decode(msg), Decoded::Unsupported { .. }));
+        decoder.set_version(3);
+        assert_eq!(
+            decoder.decode(msg),
+            Decoded::Message(Box::new(ServerMessage::EventFlagAck { seq: 42 }))
+        );
+    }
+
+    #[test]
+    fn test_server_auth_ok_deserialize() {
+        let json = r#"{
+            "type": "auth_ok",
+            "participant_id": "abc-123",
+            "race": {"id": "123", "name": "Test Race", "status": "setup"},
+            "seed": {"total_layers": 5},
+            "participants": []
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::AuthOk {
+                participant_id,
+                race,
+                seed,
+                ..
+            } => {
+                assert_eq!(participant_id, "abc-123");
+                assert_eq!(race.name, "Test Race");
+                assert_eq!(seed.total_layers, 5);
+                // event_ids defaults to empty vec when absent
+                assert!(seed.event_ids.is_empty());
+            }
+            _ => panic!("Expected AuthOk"),
+        }
+    }
+
+    #[test]
+    fn test_server_auth_ok_permissions() {
+        let json = r#"{
+            "type": "auth_ok",
+            "participant_id": "abc-123",
+            "race": {"id": "123", "name": "Blind Race", "status": "setup"},
+            "seed": {"total_layers": 5},
+            "participants": [],
+            "permissions": 5
+        }"#;
+        match serde_json::from_str(json).unwrap() {
+            ServerMessage::AuthOk { permissions, .. } => {
+                assert!(permissions.exits_panel());
+                assert!(!permissions.leaderboard());
+                assert!(!permissions.map());
+            }
+            _ => panic!("Expected AuthOk"),
+        }
+    }
+
+    #[test]
+    fn test_server_auth_ok_with_event_ids_deserialize() {
+        let json = r#"{
+            "type": "auth_ok",
+            "participant_id": "def-456",
+            "race": {"id": "456", "name": "Flag Race", "status": "running"},
+            "seed": {"total_layers": 3, "event_ids": [9000001, 9000042, 9000100]},
+            "participants": []
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::AuthOk { seed, .. } => {
+                assert_eq!(seed.event_ids, vec![9000001, 9000042, 9000100]);
+            }
+            _ => panic!("Expected AuthOk"),
+        }
+    }
+
+    #[test]
+    fn test_seed_info_without_event_ids() {
+        // Backward compat: old server sends no event_ids field
+        let json = r#"{"total_layers": 5}"#;
+        let seed: SeedInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(seed.total_layers, 5);
+        assert!(seed.event_ids.is_empty());
+        assert!(seed.segments.is_empty());
+    }
+
+    #[test]
+    fn test_seed_info_segments() {
+        let json = r#"{"total_layers": 5,
+            "segments": [{"name": "Castle", "from_node": "stormveil", "to_node": "liurnia"}]}"#;
+        let seed: SeedInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(seed.segments.len(), 1);
+        assert_eq!(seed.segments[0].from_node, "stormveil");
+        assert_eq!(seed.segments[0].to_node, "liurnia");
+    }
+
+    #[test]
+    fn test_seed_info_start() {
+        let json = r#"{"total_layers": 5,
+            "start": {"node_id": "chapel_start", "map_id": "m10_01_00_00", "position": [1.0, 2.0, 3.0]}}"#;
+        let seed: SeedInfo = serde_json::from_str(json).unwrap();
+        let start = seed.start.unwrap();
+        assert_eq!(start.node_id, "chapel_start");
+        assert_eq!(start.position, Some([1.0, 2.0, 3.0]));
+
+        let json = r#"{"total_layers": 5, "start": {"node_id": "chapel_start", "map_id": null}}"#;
+        let seed: SeedInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(seed.start.unwrap().map_id, None);
+        let seed: SeedInfo = serde_json::from_str(r#"{"total_layers": 5}"#).unwrap();
+        assert!(seed.start.is_none());
+    }
+
+    #[test]
+    fn test_server_race_start_deserialize() {
+        let json = r#"{"type": "race_start"}"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        assert!(matches!(msg, ServerMessage::RaceStart { started_at: None }));
+    }
+
+    #[test]
+    fn test_server_race_start_with_started_at() {
+        let json = r#"{"type": "race_start", "started_at": "2026-02-28T20:00:00+00:00"}"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::RaceStart { started_at } => {
+                assert_eq!(started_at.as_deref(), Some("2026-02-28T20:00:00+00:00"));
+            }
+            _ => panic!("Expected RaceStart"),
+        }
+    }
+
+    #[test]
+    fn test_clock_sync_round_trip() {
+        let probe = ClientMessage::ClockSync {
+            client_time_ms: 1_772_308_800_000,
+        };
+        assert_eq!(
+            serde_json::to_string(&probe).unwrap(),
+            r#"{"type":"clock_sync","client_time_ms":1772308800000}"#
+        );
+
+        let json = r#"{"type": "clock_sync", "client_time_ms": 1000, "server_time_ms": 1250}"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            msg,
+            ServerMessage::ClockSync {
+                client_time_ms: 1000,
+                server_time_ms: 1250
+            }
+        );
+    }
+
+    #[test]
+    fn test_server_leaderboard_update_deserialize() {
+        let json = r#"{
+            "type": "leaderboard_update",
+            "participants": [{
+                "id": "1",
+                "twitch_username": "player1",
+                "twitch_display_name": "Player One",
+                "status": "playing",
+                "current_zone": "Limgrave",
+                "current_layer": 2,
+                "current_layer_tier": 3,
+                "igt_ms": 60000,
+                "death_count": 1
+            }]
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::LeaderboardUpdate {
+                participants,
+                leader_splits,
+                generation,
+            } => {
+                assert_eq!(participants.len(), 1);
+                assert_eq!(participants[0].twitch_username, "player1");
+                assert_eq!(participants[0].current_layer_tier, Some(3));
+                assert_eq!(leader_splits, None);
+                // Servers before protocol 19 don't number their broadcasts
+                assert_eq!(generation, None);
+            }
+            _ => panic!("Expected LeaderboardUpdate"),
+        }
+    }
+
+    #[test]
+    fn test_server_ping_deserialize() {
+        let json = r#"{"type": "ping"}"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        assert!(matches!(msg, ServerMessage::Ping));
+    }
+
+    #[test]
+    fn test_client_pong_serialize() {
+        let msg = ClientMessage::Pong;
+        let json = serde_json::to_string(&msg).unwrap();
+        assert_eq!(json, r#"{"type":"pong"}"#);
+    }
+
+    #[test]
+    fn test_server_zone_update_deserialize() {
+        let json = r#"{
+            "type": "zone_update",
+            "node_id": "graveyard_cave_e235",
+            "display_name": "Cave of Knowledge",
+            "tier": 5,
+            "exits": [
+                { "text": "Soldier of Godrick front", "to_name": "Road's End Catacombs", "discovered": false },
+                { "text": "Stranded Graveyard first door", "to_name": "Ruin-Strewn Precipice", "discovered": true }
+            ]
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::ZoneUpdate {
+                node_id,
+                display_name,
+                tier,
+                original_tier,
+                exits,
+            } => {
+                assert_eq!(node_id, "graveyard_cave_e235");
+                assert_eq!(display_name, "Cave of Knowledge");
+                assert_eq!(tier, Some(5));
+                assert_eq!(original_tier, None);
+                assert_eq!(exits.len(), 2);
+                assert_eq!(exits[0].text, "Soldier of Godrick front");
+                assert_eq!(exits[0].to_name, "Road's End Catacombs");
+                assert!(!exits[0].discovered);
+                assert!(exits[1].discovered);
+            }
+            _ => panic!("Expected ZoneUpdate"),
+        }
+    }
+
+    #[test]
+    fn test_server_zone_update_exit_position() {
+        let json = r#"{
+            "type": "zone_update",
+            "node_id": "graveyard_cave_e235",
+            "display_name": "Cave of Knowledge",
+            "exits": [
+                { "text": "a", "to_name": "A", "discovered": false, "map_id": "m31_17_00_00", "position": [1.5, -2.0, 30.0] },
+                { "text": "b", "to_name": "B", "discovered": false }
+            ]
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::ZoneUpdate { exits, .. } => {
+                assert_eq!(exits[0].map_id.as_deref(), Some("m31_17_00_00"));
+                assert_eq!(exits[0].position, Some([1.5, -2.0, 30.0]));
+                assert_eq!(exits[1].map_id, None);
+                assert_eq!(exits[1].position, None);
+            }
+            _ => panic!("Expected ZoneUpdate"),
+        }
+    }
+
+    #[test]
+    fn test_server_zone_update_no_tier() {
+        let json = r#"{
+            "type": "zone_update",
+            "node_id": "start_node",
+            "display_name": "Chapel of Anticipation",
+            "tier": null,
+            "exits": []
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::ZoneUpdate { tier, exits, .. } => {
+                assert_eq!(tier, None);
+                assert!(exits.is_empty());
+            }
+            _ => panic!("Expected ZoneUpdate"),
+        }
+    }
+
+    #[test]
+    fn test_seed_info_with_spawn_items() {
+        let json = r#"{"total_layers": 5, "event_ids": [100], "spawn_items": [{"id": 10500, "qty": 1}, {"id": 16300}]}"#;
+        let seed: SeedInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(seed.spawn_items.len(), 2);
+        assert_eq!(seed.spawn_items[0].id, 10500);
+        assert_eq!(seed.spawn_items[0].qty, 1);
+        assert_eq!(seed.spawn_items[1].id, 16300);
+        assert_eq!(seed.spawn_items[1].qty, 1); // default
+    }
+
+    #[test]
+    fn test_seed_info_without_spawn_items() {
+        // Backward compat: old server sends no spawn_items field
+        let json = r#"{"total_layers": 5}"#;
+        let seed: SeedInfo = serde_json::from_str(json).unwrap();
+        assert!(seed.spawn_items.is_empty());
+    }
+
+    #[test]
+    fn test_auth_ok_with_spawn_items() {
+        let json = r#"{
+            "type": "auth_ok",
+            "participant_id": "abc-123",
+            "race": {"id": "123", "name": "Test Race", "status": "setup"},
+            "seed": {"total_layers": 5, "spawn_items": [{"id": 42, "qty": 2}]},
+            "participants": []
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::AuthOk { seed, .. } => {
+                assert_eq!(seed.spawn_items.len(), 1);
+                assert_eq!(seed.spawn_items[0].id, 42);
+                assert_eq!(seed.spawn_items[0].qty, 2);
+            }
+            _ => panic!("Expected AuthOk"),
+        }
+    }
+
+    #[test]
+    fn test_auth_ok_with_key_items() {
+        let json = r#"{
+            "type": "auth_ok",
+            "participant_id": "abc-123",
+            "race": {"id": "123", "name": "Test Race", "status": "setup"},
+            "seed": {"total_layers": 5, "key_items": [{"id": 8107, "flag_id": 400001, "name": "Rold Medallion"}]},
+            "participants": []
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::AuthOk { seed, .. } => {
+                assert_eq!(
+                    seed.key_items,
+                    vec![KeyItem {
+                        id: 8107,
+                        flag_id: 400001,
+                        name: "Rold Medallion".to_string(),
+                    }]
+                );
+            }
+            _ => panic!("Expected AuthOk"),
+        }
+    }
+
+    #[test]
+    fn test_item_obtained_roundtrip() {
+        let json = serde_json::to_string(&ClientMessage::ItemObtained {
+            item_id: 8107,
+            igt_ms: 5000,
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"item_obtained","item_id":8107,"igt_ms":5000}"#
+        );
+
+        let msg = r#"{"type": "item_obtained", "participant_id": "p1", "player_name": "Player One",
+            "item_id": 8107, "item_name": "Rold Medallion", "igt_ms": 5000}"#;
+        let mut decoder = ServerDecoder::new();
+        decoder.set_version(3);
+        assert!(matches!(decoder.decode(msg), Decoded::Unsupported { .. }));
+        decoder.set_version(4);
+        match message(decoder.decode(msg)) {
+            ServerMessage::ItemObtained {
+                player_name,
+                item_name,
+                ..
+            } => {
+                assert_eq!(player_name, "Player One");
+                assert_eq!(item_name, "Rold Medallion");
+            }
+            other => panic!("Expected ItemObtained, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_auth_with_character() {
+        let msg = ClientMessage::Auth {
+            mod_token: "t".to_string(),
+            protocol_version: PROTOCOL_VERSION,
+            character: Some(CharacterFingerprint {
+                slot: Some(2),
+                name: "Tarnished".to_string(),
+                level: 9,
+                class_id: 8,
+            }),
+            encodings: Vec::new(),
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(
+            json.contains(r#""character":{"slot":2,"name":"Tarnished","level":9,"class_id":8}"#)
+        );
+    }
+
+    #[test]
+    fn test_ng_cycle_serialize() {
+        let json = serde_json::to_string(&ClientMessage::NgCycle {
+            clear_count: 1,
+            igt_ms: 5000,
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"type":"ng_cycle","clear_count":1,"igt_ms":5000}"#);
+    }
+
+    #[test]
+    fn test_exit_hints_request_serialize() {
+        let json = serde_json::to_string(&ClientMessage::ExitHintsRequest {
+            node_id: "stormveil_db4a".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"exit_hints_request","node_id":"stormveil_db4a"}"#
+        );
+    }
+
+    #[test]
+    fn test_afk_serialize() {
+        let json = serde_json::to_string(&ClientMessage::Afk {
+            afk: true,
+            igt_ms: 5000,
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"type":"afk","afk":true,"igt_ms":5000}"#);
+    }
+
+    #[test]
+    fn test_save_reloaded_serialize() {
+        let json = serde_json::to_string(&ClientMessage::SaveReloaded {
+            igt_ms: 540000,
+            igt_delta_ms: -60000,
+            death_count: 3,
+            death_delta: -2,
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"save_reloaded","igt_ms":540000,"igt_delta_ms":-60000,"death_count":3,"death_delta":-2}"#
+        );
+    }
+
+    #[test]
+    fn test_pause_messages() {
+        let json = serde_json::to_string(&ClientMessage::PauseRequest { igt_ms: 5000 }).unwrap();
+        assert_eq!(json, r#"{"type":"pause_request","igt_ms":5000}"#);
+
+        let mut decoder = ServerDecoder::new();
+        decoder.set_version(13);
+        assert!(matches!(
+            decoder.decode(r#"{"type": "pause_granted"}"#),
+            Decoded::Unsupported { .. }
+        ));
+        decoder.set_version(14);
+        assert_eq!(
+            decoder.decode(r#"{"type": "pause_granted"}"#),
+            Decoded::Message(Box::new(ServerMessage::PauseGranted))
+        );
+        assert_eq!(
+            decoder.decode(r#"{"type": "resume"}"#),
+            Decoded::Message(Box::new(ServerMessage::Resume))
+        );
+    }
+
+    #[test]
+    fn test_set_flag_gated() {
+        let mut decoder = ServerDecoder::new();
+        decoder.set_version(19);
+        assert!(matches!(
+            decoder.decode(r#"{"type": "set_flag", "flag_id": 10010500}"#),
+            Decoded::Unsupported { .. }
+        ));
+        decoder.set_version(20);
+        assert_eq!(
+            decoder.decode(r#"{"type": "set_flag", "flag_id": 10010500}"#),
+            Decoded::Message(Box::new(ServerMessage::SetFlag {
+                flag_id: 10010500,
+                value: true
+            }))
+        );
+        assert_eq!(
+            decoder.decode(r#"{"type": "set_flag", "flag_id": 10010500, "value": false}"#),
+            Decoded::Message(Box::new(ServerMessage::SetFlag {
+                flag_id: 10010500,
+                value: false
+            }))
+        );
+    }
+
+    #[test]
+    fn test_race_activity_gated() {
+        let msg = r#"{"type": "race_activity", "participant_id": "p2",
+            "player_name": "Racer2", "kind": "boss", "zone_name": "Stormveil Castle",
+            "igt_ms": 754000}"#;
+        let mut decoder = ServerDecoder::new();
+        decoder.set_version(23);
+        assert!(matches!(decoder.decode(msg), Decoded::Unsupported { .. }));
+        decoder.set_version(24);
+        assert_eq!(
+            decoder.decode(msg),
+            Decoded::Message(Box::new(ServerMessage::RaceActivity {
+                participant_id: "p2".to_string(),
+                player_name: "Racer2".to_string(),
+                kind: ActivityKind::Boss,
+                zone_name: Some("Stormveil Castle".to_string()),
+                igt_ms: 754000,
+            }))
+        );
+        // Hidden zones, and a kind from a newer server
+        let msg = r#"{"type": "race_activity", "participant_id": "p2",
+            "player_name": "Racer2", "kind": "great_rune", "igt_ms": 754000}"#;
+        assert!(matches!(
+            message(decoder.decode(msg)),
+            ServerMessage::RaceActivity {
+                kind: ActivityKind::Unknown,
+                zone_name: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_exit_hints_gated() {
+        let msg = r#"{"type": "exit_hints", "node_id": "stormveil_db4a",
+            "hints": [{"exit_index": 1, "text": "Dead end"}]}"#;
+        let mut decoder = ServerDecoder::new();
+        decoder.set_version(8);
+        assert!(matches!(decoder.decode(msg), Decoded::Unsupported { .. }));
+        decoder.set_version(9);
+        match message(decoder.decode(msg)) {
+            ServerMessage::ExitHints { node_id, hints } => {
+                assert_eq!(node_id, "stormveil_db4a");
+                assert_eq!(
+                    hints,
+                    vec![ExitHint {
+                        exit_index: 1,
+                        text: "Dead end".to_string(),
+                    }]
+                );
+            }
+            other => panic!("Expected ExitHints, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_seed_expected_character() {
+        let json = r#"{"total_layers": 5, "expected_character": {"class_id": 8, "level": null}}"#;
+        let seed: SeedInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            seed.expected_character,
+            Some(ExpectedCharacter {
+                class_id: Some(8),
+                level: None,
+            })
+        );
+        let seed: SeedInfo = serde_json::from_str(r#"{"total_layers": 5}"#).unwrap();
+        assert_eq!(seed.expected_character, None);
+    }
+
+    #[test]
+    fn test_seed_changed_gated() {
+        let msg = r#"{"type": "seed_changed", "seed": {"seed_id": "new-seed", "total_layers": 8,
+            "event_ids": [9000000], "finish_event": 9000042}}"#;
+        let mut decoder = ServerDecoder::new();
+        decoder.set_version(5);
+        assert!(matches!(decoder.decode(msg), Decoded::Unsupported { .. }));
+        decoder.set_version(6);
+        match message(decoder.decode(msg)) {
+            ServerMessage::SeedChanged { seed } => {
+                assert_eq!(seed.seed_id.as_deref(), Some("new-seed"));
+                assert_eq!(seed.event_ids, vec![9000000]);
+                assert_eq!(seed.finish_event, Some(9000042));
+            }
+            other => panic!("Expected SeedChanged, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_auth_ok_with_objectives() {
+        let json = r#"{
+            "type": "auth_ok",
+            "participant_id": "abc-123",
+            "race": {"id": "123", "name": "Bingo", "status": "setup"},
+            "seed": {"total_layers": 5},
+            "participants": [],
+            "protocol_version": 5,
+            "objectives": [{"id": 0, "text": "Kill Margit", "flag_id": 10000800}]
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::AuthOk { objectives, .. } => {
+                assert_eq!(
+                    objectives,
+                    vec![Objective {
+                        id: 0,
+                        text: "Kill Margit".to_string(),
+                        flag_id: 10000800,
+                    }]
+                );
+            }
+            _ => panic!("Expected AuthOk"),
+        }
+    }
+
+    #[test]
+    fn test_objective_complete_serialize() {
+        let json = serde_json::to_string(&ClientMessage::ObjectiveComplete {
+            objective_id: 2,
+            igt_ms: 5000,
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"objective_complete","objective_id":2,"igt_ms":5000}"#
+        );
+    }
+
+    #[test]
+    fn test_checkpoints() {
+        let json = r#"{
+            "type": "auth_ok",
+            "participant_id": "abc-123",
+            "race": {"id": "123", "name": "Relay", "status": "running"},
+            "seed": {"total_layers": 5},
+            "participants": [{
+                "id": "p1", "twitch_username": "a", "twitch_display_name": null,
+                "status": "playing", "current_zone": null, "current_layer": 2,
+                "igt_ms": 90000, "death_count": 0,
+                "checkpoints": [{"checkpoint_id": 1, "igt_ms": 80000}]
+            }],
+            "protocol_version": 15,
+            "checkpoints": [
+                {"id": 0, "name": "Margit", "flag_id": 10000800},
+                {"id": 1, "name": "Liurnia", "node_id": "liurnia_main"}
+            ]
+        }"#;
+        match serde_json::from_str(json).unwrap() {
+            ServerMessage::AuthOk {
+                checkpoints,
+                participants,
+                ..
+            } => {
+                assert_eq!(checkpoints[0].flag_id, Some(10000800));
+                assert_eq!(checkpoints[1].node_id.as_deref(), Some("liurnia_main"));
+                assert_eq!(
+                    participants[0].checkpoints,
+                    vec![CheckpointTime {
+                        checkpoint_id: 1,
+                        igt_ms: 80000,
+                    }]
+                );
+            }
+            other => panic!("Expected AuthOk, got {:?}", other),
+        }
+        let json = serde_json::to_string(&ClientMessage::CheckpointCrossed {
+            checkpoint_id: 1,
+            igt_ms: 80000,
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"checkpoint_crossed","checkpoint_id":1,"igt_ms":80000}"#
+        );
+    }
+
+    #[test]
+    fn test_kindling_used_serialize() {
+        let json = serde_json::to_string(&ClientMessage::KindlingUsed {
+            igt_ms: 3600000,
+            used: 1,
+            remaining: 0,
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"kindling_used","igt_ms":3600000,"used":1,"remaining":0}"#
+        );
+    }
+
+    #[test]
+    fn test_timing_drift_serialize() {
+        let json = serde_json::to_string(&ClientMessage::TimingDrift {
+            igt_ms: 600000,
+            igt_delta_ms: 45000,
+            wall_delta_ms: 30000,
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"timing_drift","igt_ms":600000,"igt_delta_ms":45000,"wall_delta_ms":30000}"#
+        );
+    }
+
+    #[test]
+    fn test_grace_discovered_serialize() {
+        let json = serde_json::to_string(&ClientMessage::GraceDiscovered {
+            flag_id: 76101,
+            igt_ms: 120000,
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"grace_discovered","flag_id":76101,"igt_ms":120000}"#
+        );
+    }
+
+    #[test]
+    fn test_leaderboard_delta_gated() {
+        let msg = r#"{"type": "leaderboard_delta", "generation": 8,
+            "participants": [], "order": ["1", "2"]}"#;
+        let mut decoder = ServerDecoder::new();
+        decoder.set_version(18);
+        assert!(matches!(decoder.decode(msg), Decoded::Unsupported { .. }));
+        decoder.set_version(19);
+        match message(decoder.decode(msg)) {
+            ServerMessage::LeaderboardDelta {
+                generation,
+                participants,
+                order,
+                leader_splits,
+            } => {
+                assert_eq!(generation, 8);
+                assert!(participants.is_empty());
+                assert_eq!(order, ["1", "2"]);
+                assert_eq!(leader_splits, None);
+            }
+            other => panic!("Expected LeaderboardDelta, got {:?}", other),
+        }
+        let resync = serde_json::to_string(&ClientMessage::LeaderboardResync).unwrap();
+        assert_eq!(resync, r#"{"type":"leaderboard_resync"}"#);
+    }
+
+    #[test]
+    fn test_server_error_deserialize() {
+        let json = r#"{"type": "error", "message": "Race not running"}"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::Error { message } => {
+                assert_eq!(message, "Race not running");
+            }
+            _ => panic!("Expected Error"),
+        }
+    }
+
+    #[test]
+    fn test_seed_info_with_seed_id() {
+        let json = r#"{"total_layers": 5, "seed_id": "abc-123"}"#;
+        let seed: SeedInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(seed.seed_id, Some("abc-123".to_string()));
+    }
+
+    #[test]
+    fn test_seed_info_without_seed_id() {
+        // Backward compat: old server sends no seed_id field
+        let json = r#"{"total_layers": 5}"#;
+        let seed: SeedInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(seed.seed_id, None);
+    }
+
+    #[test]
+    fn test_seed_info_with_finish_event() {
+        let json = r#"{"total_layers":5,"event_ids":[100,101],"finish_event":102}"#;
+        let seed: SeedInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(seed.finish_event, Some(102));
+    }
+
+    #[test]
+    fn test_seed_info_without_finish_event() {
+        let json = r#"{"total_layers":5}"#;
+        let seed: SeedInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(seed.finish_event, None);
+    }
+
+    #[test]
+    fn test_auth_ok_with_seed_id() {
+        let json = r#"{
+            "type": "auth_ok",
+            "participant_id": "abc-123",
+            "race": {"id": "123", "name": "Test Race", "status": "setup"},
+            "seed": {"total_layers": 5, "seed_id": "seed-xyz"},
+            "participants": []
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::AuthOk { seed, .. } => {
+                assert_eq!(seed.seed_id, Some("seed-xyz".to_string()));
+            }
+            _ => panic!("Expected AuthOk"),
+        }
+    }
+
+    #[test]
+    fn test_client_zone_query_grace_only() {
+        let msg = ClientMessage::ZoneQuery {
+            grace_entity_id: Some(10002950),
+            map_id: None,
+            position: None,
+            play_region_id: None,
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains(r#""type":"zone_query""#));
+        assert!(json.contains(r#""grace_entity_id":10002950"#));
+        assert!(!json.contains("map_id"));
+    }
+
+    #[test]
+    fn test_client_zone_query_map_only() {
+        let msg = ClientMessage::ZoneQuery {
+            grace_entity_id: None,
+            map_id: Some("m10_00_00_00".into()),
+            position: Some([100.0, 50.0, 200.0]),
+            play_region_id: Some(12345),
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains(r#""type":"zone_query""#));
+        assert!(json.contains(r#""map_id":"m10_00_00_00""#));
+        assert!(!json.contains("grace_entity_id"));
+    }
+
+    #[test]
+    fn test_server_zone_update_with_original_tier() {
+        let json = r#"{
+            "type": "zone_update",
+            "node_id": "cave_e235",
+            "display_name": "Cave of Knowledge",
+            "tier": 2,
+            "original_tier": 8,
+            "exits": []
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::ZoneUpdate {
+                tier,
+                original_tier,
+                ..
+            } => {
+                assert_eq!(tier, Some(2));
+                assert_eq!(original_tier, Some(8));
+            }
+            _ => panic!("Expected ZoneUpdate"),
+        }
+    }
+
+    #[test]
+    fn test_server_zone_update_without_original_tier() {
+        // Backward compat: old server sends no original_tier field
+        let json = r#"{
+            "type": "zone_update",
+            "node_id": "start_node",
+            "display_name": "Chapel of Anticipation",
+            "tier": null,
+            "exits": []
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::ZoneUpdate {
+                tier,
+                original_tier,
+                ..
+            } => {
+                assert_eq!(tier, None);
+                assert_eq!(original_tier, None);
+            }
+            _ => panic!("Expected ZoneUpdate"),
+        }
+    }
+
+    #[test]
+    fn test_participant_info_tier_defaults_none() {
+        // Backward compat: old server sends no current_layer_tier field
+        let json = r#"{
+            "id": "1",
+            "twitch_username": "player1",
+            "twitch_display_name": null,
+            "status": "registered",
+            "current_zone": null,
+            "current_layer": 0,
+            "igt_ms": 0,
+            "death_count": 0
+        }"#;
+        let p: ParticipantInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(p.current_layer_tier, None);
+        // gap_ms also defaults to None when absent
+        assert_eq!(p.gap_ms, None);
+        assert!(!p.afk);
+    }
+
+    #[test]
+    fn test_participant_info_with_gap_ms() {
+        let json = r#"{
+            "id": "1",
+            "twitch_username": "player1",
+            "twitch_display_name": null,
+            "status": "playing",
+            "current_zone": null,
+            "current_layer": 2,
+            "igt_ms": 90000,
+            "death_count": 1,
+            "gap_ms": 15000
+        }"#;
+        let p: ParticipantInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(p.gap_ms, Some(15000));
+    }
+
+    #[test]
+    fn test_participant_info_gap_ms_null() {
+        let json = r#"{
+            "id": "1",
+            "twitch_username": "player1",
+            "twitch_display_name": null,
+            "status": "playing",
+            "current_zone": null,
+            "current_layer": 0,
+            "igt_ms": 0,
+            "death_count": 0,
+            "gap_ms": null
+        }"#;
+        let p: ParticipantInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(p.gap_ms, None);
+    }
+
+    #[test]
+    fn test_leaderboard_update_with_leader_splits() {
+        let json = r#"{
+            "type": "leaderboard_update",
+            "participants": [],
+            "leader_splits": {"0": 0, "1": 30000, "2": 75000}
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::LeaderboardUpdate {
+                participants,
+                leader_splits,
+                ..
+            } => {
+                assert!(participants.is_empty());
+                let splits = leader_splits.unwrap();
+                assert_eq!(splits.get("0"), Some(&0));
+                assert_eq!(splits.get("1"), Some(&30000));
+                assert_eq!(splits.get("2"), Some(&75000));
+            }
+            _ => panic!("Expected LeaderboardUpdate"),
+        }
+    }
+
+    #[test]
+    fn test_leaderboard_update_without_leader_splits() {
+        // Backward compat: old server sends no leader_splits
+        let json = r#"{
+            "type": "leaderboard_update",
+            "participants": []
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::LeaderboardUpdate { leader_splits, .. } => {
+                assert_eq!(leader_splits, None);
+            }
+            _ => panic!("Expected LeaderboardUpdate"),
+        }
+    }
+
+    #[test]
+    fn test_participant_info_with_layer_entry_igt() {
+        let json = r#"{
+            "id": "1",
+            "twitch_username": "player1",
+            "twitch_display_name": null,
+            "status": "playing",
+            "current_zone": null,
+            "current_layer": 2,
+            "igt_ms": 90000,
+            "death_count": 1,
+            "gap_ms": 15000,
+            "layer_entry_igt": 80000
+        }"#;
+        let p: ParticipantInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(p.layer_entry_igt, Some(80000));
+    }
+
+    #[test]
+    fn test_participant_info_zone_entry_igt() {
+        let json = r#"{
+            "id": "1",
+            "twitch_username": "player1",
+            "twitch_display_name": null,
+            "status": "playing",
+            "current_zone": "stormveil",
+            "current_layer": 2,
+            "igt_ms": 90000,
+            "death_count": 1,
+            "zone_entry_igt": 85000
+        }"#;
+        let p: ParticipantInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(p.zone_entry_igt, Some(85000));
+        // Older server
+        let json = json.replace(r#""zone_entry_igt": 85000"#, r#""layer_entry_igt": 80000"#);
+        let p: ParticipantInfo = serde_json::from_str(&json).unwrap();
+        assert_eq!(p.zone_entry_igt, None);
+    }
+
+    // --- Versioned decoder / compatibility ---
+
+    const AUTH_OK: &str = r#"{
+        "type": "auth_ok",
+        "participant_id": "abc",
+        "race": {"id": "1", "name": "R", "status": "setup"},
+        "seed": {"total_layers": 5},
+        "participants": []
+    }"#;
+
+    #[test]
+    fn test_auth_ok_protocol_version() {
+        // Older server: no protocol_version
+        match serde_json::from_str::<ServerMessage>(AUTH_OK).unwrap() {
+            ServerMessage::AuthOk {
+                protocol_version, ..
+            } => assert_eq!(protocol_version, LEGACY_PROTOCOL_VERSION),
+            _ => panic!("Expected AuthOk"),
+        }
+        let json = AUTH_OK.replace(
+            "\"participants\": []",
+            "\"participants\": [], \"protocol_version\": 2",
+        );
+        match serde_json::from_str::<ServerMessage>(&json).unwrap() {
+            ServerMessage::AuthOk {
+                protocol_version, ..
+            } => assert_eq!(protocol_version, 2),
+            _ => panic!("Expected AuthOk"),
+        }
+    }
+
+    #[test]
+    fn test_auth_ok_event_delay() {
+        match serde_json::from_str::<ServerMessage>(AUTH_OK).unwrap() {
+            ServerMessage::AuthOk {
+                event_delay_secs, ..
+            } => assert_eq!(event_delay_secs, 0),
+            _ => panic!("Expected AuthOk"),
+        }
+        let json = AUTH_OK.replace(
+            "\"participants\": []",
+            "\"participants\": [], \"event_delay_secs\": 45",
+        );
+        match serde_json::from_str::<ServerMessage>(&json).unwrap() {
+            ServerMessage::AuthOk {
+                event_delay_secs, ..
+            } => assert_eq!(event_delay_secs, 45),
+            _ => panic!("Expected AuthOk"),
+        }
+    }
+
+    #[test]
+    fn test_auth_ok_zone_privacy() {
+        match serde_json::from_str::<ServerMessage>(AUTH_OK).unwrap() {
+            ServerMessage::AuthOk { zone_privacy, .. } => {
+                assert_eq!(zone_privacy, ZonePrivacy::Name)
+            }
+            _ => panic!("Expected AuthOk"),
+        }
+        let json = AUTH_OK.replace(
+            "\"participants\": []",
+            "\"participants\": [], \"zone_privacy\": \"tier\"",
+        );
+        match serde_json::from_str::<ServerMessage>(&json).unwrap() {
+            ServerMessage::AuthOk { zone_privacy, .. } => {
+                assert_eq!(zone_privacy, ZonePrivacy::Tier)
+            }
+            _ => panic!("Expected AuthOk"),
+        }
+    }
+
+    #[test]
+    fn test_decoder_ignores_unknown_fields() {
+        let mut decoder = ServerDecoder::new();
+        let decoded = decoder
+            .decode(r#"{"type": "race_status_change", "status": "running", "reason": "admin"}"#);
+        assert_eq!(
+            decoded,
+            Decoded::Message(Box::new(ServerMessage::RaceStatusChange {
+                status: "running".to_string()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_decoder_reports_unknown_type_once() {
+        let mut decoder = ServerDecoder::new();
+        let msg = r#"{"type": "spectator_count", "count": 3}"#;
+        assert_eq!(
+            decoder.decode(msg),
+            Decoded::Unsupported {
+                msg_type: "spectator_count".to_string(),
+                first: true
+            }
+        );
+        assert_eq!(
+            decoder.decode(msg),
+            Decoded::Unsupported {
+                msg_type: "spectator_count".to_string(),
+                first: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_decoder_gates_messages_by_version() {
+        let msg = r#"{"type": "clock_sync", "client_time_ms": 1, "server_time_ms": 2}"#;
+        let mut decoder = ServerDecoder::new();
+        assert!(matches!(decoder.decode(msg), Decoded::Unsupported { .. }));
+        decoder.set_version(2);
+        assert!(matches!(
+            message(decoder.decode(msg)),
+            ServerMessage::ClockSync { .. }
+        ));
+    }
+
+    #[test]
+    fn test_decoder_caps_negotiated_version() {
+        let mut decoder = ServerDecoder::new();
+        decoder.set_version(99);
+        assert_eq!(decoder.version(), PROTOCOL_VERSION);
+        decoder.set_version(0);
+        assert_eq!(decoder.version(), LEGACY_PROTOCOL_VERSION);
+    }
+
+    #[test]
+    fn test_auth_ok_encoding() {
+        match serde_json::from_str::<ServerMessage>(AUTH_OK).unwrap() {
+            ServerMessage::AuthOk { encoding, .. } => assert_eq!(encoding, Encoding::Json),
+            _ => panic!("Expected AuthOk"),
+        }
+        let json = AUTH_OK.replace(
+            "\"participants\": []",
+            "\"participants\": [], \"encoding\": \"msgpack\"",
+        );
+        match serde_json::from_str::<ServerMessage>(&json).unwrap() {
+            ServerMessage::AuthOk { encoding, .. } => assert_eq!(encoding, Encoding::Msgpack),
+            _ => panic!("Expected AuthOk"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "msgpack")]
+    fn test_msgpack_client_round_trip() {
+        let messages = [
+            ClientMessage::Ready,
+            ClientMessage::StatusUpdate {
+                igt_ms: 123456,
+                death_count: 5,
+                disconnecting: false,
+            },
+            ClientMessage::EventFlag {
+                flag_id: 9000042,
+                igt_ms: 60000,
+                seq: Some(1_700_000_000_000_001),
+                idempotency_key: Some("race-1:00000000000000ff:9000042".to_string()),
+            },
+            ClientMessage::ZoneQuery {
+                grace_entity_id: None,
+                map_id: Some("m10_00_00_00".to_string()),
+                position: Some([100.5, 50.0, -200.25]),
+                play_region_id: Some(1000000),
+            },
+        ];
+        for msg in messages {
+            let Frame::Binary(bytes) = Encoding::Msgpack.encode(&msg).unwrap() else {
+                panic!("Expected a binary frame");
+            };
+            let decoded: ClientMessage = rmp_serde::from_slice(&bytes).unwrap();
+            assert_eq!(decoded, msg);
+        }
+        assert_eq!(
+            Encoding::Json.encode(&ClientMessage::Ready).unwrap(),
+            Frame::Text(r#"{"type":"ready"}"#.to_string())
+        );
+    }
+
+    #[cfg(feature = "msgpack")]
+    fn leaderboard(count: usize) -> ServerMessage {
+        let participants = (0..count)
+            .map(|i| ParticipantInfo {
+                id: format!("00000000-0000-0000-0000-{:012}", i),
+                twitch_username: format!("player{}", i),
+                twitch_display_name: Some(format!("Player {}", i)),
+                status: "playing".to_string(),
+                current_zone: Some("m60_51_36_00".to_string()),
+                current_zone_name: None,
+                current_layer: 3,
+                current_layer_tier: Some(5),
+                igt_ms: 1_234_567,
+                death_count: 12,
+                gap_ms: Some(-4500),
+                layer_entry_igt: Some(1_200_000),
+                zone_entry_igt: None,
+                key_items: vec![8105, 8106],
+                objectives_completed: Vec::new(),
+                afk: i == 0,
+                pause: None,
+                checkpoints: Vec::new(),
+                badges: Vec::new(),
+            })
+            .collect();
+        ServerMessage::LeaderboardUpdate {
+            participants,
+            leader_splits: Some(HashMap::from([("3".to_string(), 1_100_000)])),
+            generation: Some(42),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "msgpack")]
+    fn test_msgpack_server_round_trip() {
+        let msg = leaderboard(8);
+        let bytes = rmp_serde::to_vec_named(&msg).unwrap();
+        let mut decoder = ServerDecoder::new();
+        assert_eq!(
+            decoder.decode_binary(&bytes),
+            Decoded::Message(Box::new(msg.clone()))
+        );
+
+        // Same shapes on both encodings, smaller on the wire
+        let json = serde_json::to_string(&msg).unwrap();
+        assert_eq!(decoder.decode(&json), Decoded::Message(Box::new(msg)));
+        assert!(bytes.len() < json.len());
+    }
+
+    #[test]
+    #[cfg(feature = "msgpack")]
+    fn test_decoder_binary_unknown_and_invalid() {
+        let mut decoder = ServerDecoder::new();
+        let unknown = rmp_serde::to_vec_named(&serde_json::json!({
+            "type": "spectator_count",
+            "count": 3,
+        }))
+        .unwrap();
+        assert!(matches!(
+            decoder.decode_binary(&unknown),
+            Decoded::Unsupported { first: true, .. }
+        ));
+        assert!(matches!(
+            decoder.decode_binary(&[0xc1]),
+            Decoded::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn test_decoder_invalid_messages() {
+        let mut decoder = ServerDecoder::new();
+        assert!(matches!(decoder.decode("not json"), Decoded::Invalid(_)));
+        // Known type with a missing required field
+        assert!(matches!(
+            decoder.decode(r#"{"type": "zone_update"}"#),
+            Decoded::Invalid(_)
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+//! Overlay content per race phase
+//!
+//! What a runner wants on screen changes over a race: the race details and
+//! the participants in the lobby, a minimal HUD while running, the full
+//! leaderboard once finished. The `[phases]` section overrides, per phase,
+//! the overlay profile, single panels and the race timer's text. The phase
+//! follows the race status (`race_status_change`) and the player's finish.
+
+use serde::{Deserialize, Serialize};
+
+use super::overlay_profile::{OverlayPreset, OverlayProfile};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RacePhase {
+    /// Race in setup, waiting for the start
+    Lobby,
+    Running,
+    /// Race over, or the player crossed the finish
+    Finished,
+}
+
+impl RacePhase {
+    /// Phase of a race status, None for statuses this version doesn't know
+    pub fn from_status(status: &str, player_finished: bool) -> Option<Self> {
+        match status {
+            "setup" => Some(RacePhase::Lobby),
+            "running" if player_finished => Some(RacePhase::Finished),
+            "running" => Some(RacePhase::Running),
+            "finished" => Some(RacePhase::Finished),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RacePhase::Lobby => "lobby",
+            RacePhase::Running => "running",
+            RacePhase::Finished => "finished",
+        }
+    }
+}
+
+/// Overrides of one phase; unset values keep the `[overlay]` / `[race_timer]` ones
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhaseOverrides {
+    /// Profile replacing `overlay.profile`
+    #[serde(default)]
+    pub profile: Option<OverlayProfile>,
+    /// Race name, progress, tier and deaths
+    #[serde(default)]
+    pub show_details: Option<bool>,
+    #[serde(default)]
+    pub show_exits: Option<bool>,
+    #[serde(default)]
+    pub show_leaderboard: Option<bool>,
+    #[serde(default)]
+    pub show_toasts: Option<bool>,
+    /// Text replacing `race_timer.format`
+    #[serde(default)]
+    pub timer_format: Option<String>,
+}
+
+/// `[phases]` section of the config: `[phases.lobby]`, `[phases.running]`
+/// and `[phases.finished]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhaseSettings {
+    #[serde(default)]
+    pub lobby: PhaseOverrides,
+    #[serde(default)]
+    pub running: PhaseOverrides,
+    #[serde(default)]
+    pub finished: PhaseOverrides,
+}
+
+impl PhaseSettings {
+    pub fn overrides(&self, phase: RacePhase) -> &PhaseOverrides {
+        match phase {
+            RacePhase::Lobby => &self.lobby,
+            RacePhase::Running => &self.running,
+            RacePhase::Finished => &self.finished,
+        }
+    }
+
+    /// What the overlay shows in this phase (no race: the base profile)
+    pub fn preset(&self, base: OverlayProfile, phase: Option<RacePhase>) -> OverlayPreset {
+        let Some(overrides) = phase.map(|p| self.overrides(p)) else {
+            return base.preset();
+        };
+        let preset = overrides.profile.unwrap_or(base).preset();
+        OverlayPreset {
+            show_details: overrides.show_details.unwrap_or(preset.show_details),
+            show_exits: overrides.show_exits.unwrap_or(preset.show_exits),
+            show_leaderboard: overrides
+                .show_leaderboard
+                .unwrap_or(preset.show_leaderboard),
+            show_toasts: overrides.show_toasts.unwrap_or(preset.show_toasts),
+            ..preset
+        }
+    }
+
+    /// Race timer text in this phase
+    pub fn timer_format<'a>(&'a self, base: &'a str, phase: Option<RacePhase>) -> &'a str {
+        phase
+            .and_then(|p| self.overrides(p).timer_format.as_deref())
+            .unwrap_or(base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_from_status() {
+        assert_eq!(
+            RacePhase::from_status("setup", false),
+            Some(RacePhase::Lobby)
+        );
+        assert_eq!(
+            RacePhase::from_status("running", false),
+            Some(RacePhase::Running)
+        );
+        assert_eq!(
+            RacePhase::from_status("running", true),
+            Some(RacePhase::Finished)
+        );
+        assert_eq!(
+            RacePhase::from_status("finished", false),
+            Some(RacePhase::Finished)
+        );
+        assert_eq!(RacePhase::from_status("archived", false), None);
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings: PhaseSettings = toml::from_str(
+            r#"
+            [lobby]
+            show_leaderboard = true
+            timer_format = "{race}"
+
+            [running]
+            profile = "minimal"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(settings.lobby.show_leaderboard, Some(true));
+        assert_eq!(settings.lobby.timer_format.as_deref(), Some("{race}"));
+        assert_eq!(settings.running.profile, Some(OverlayProfile::Minimal));
+        assert_eq!(settings.finished, PhaseOverrides::default());
+    }
+
+    #[test]
+    fn test_default_keeps_base_profile() {
+        let settings = PhaseSettings::default();
+        for phase in [None, Some(RacePhase::Lobby), Some(RacePhase::Finished)] {
+            assert_eq!(
+                settings.preset(OverlayProfile::Streamer, phase),
+                OverlayProfile::Streamer.preset()
+            );
+            assert_eq!(settings.timer_format("{igt}", phase), "{igt}");
+        }
+    }
+
+    #[test]
+    fn test_phase_overrides() {
+        let settings = PhaseSettings {
+            running: PhaseOverrides {
+                profile: Some(OverlayProfile::Minimal),
+                show_exits: Some(true),
+                timer_format: Some("{igt} ({deaths})".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let running = settings.preset(OverlayProfile::Full, Some(RacePhase::Running));
+        assert!(running.show_exits);
+        assert!(!running.show_leaderboard && !running.show_details);
+        assert_eq!(
+            settings.timer_format("{igt}", Some(RacePhase::Running)),
+            "{igt} ({deaths})"
+        );
+        // Other phases and no race keep the base
+        assert_eq!(
+            settings.preset(OverlayProfile::Full, Some(RacePhase::Finished)),
+            OverlayProfile::Full.preset()
+        );
+        assert_eq!(settings.timer_format("{igt}", None), "{igt}");
+    }
+
+    #[test]
+    fn test_profile_privacy_kept() {
+        let settings = PhaseSettings {
+            finished: PhaseOverrides {
+                show_leaderboard: Some(true),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let preset = settings.preset(OverlayProfile::Streamer, Some(RacePhase::Finished));
+        assert!(preset.hide_sensitive && !preset.allow_debug);
+    }
+}
@@ -0,0 +1,196 @@
+//! Big race timer window
+//!
+//! Some runners want a large clock on screen, away from the info overlay.
+//! The optional timer window shows a template filled from the same per-frame
+//! values as the overlay: `{igt}` the in-game time (frozen at the finish),
+//! `{race}` the race clock since the server's start time, `{deaths}`, and
+//! `{bosses}` the major bosses defeated on the save (`boss_flags`), which
+//! works outside races too, and the player stats `{level}`, `{souls}` and
+//! `{flasks}` (`player_stats`).
+//! It is centered at the top of the screen by default.
+
+use serde::{Deserialize, Serialize};
+
+use super::anchor::{Anchor, Offset};
+use super::locale::fill;
+use super::player_stats::Flasks;
+
+/// Shown while a value is unknown (no race, IGT unreadable)
+const UNKNOWN_TIME: &str = "--:--:--";
+
+/// `[race_timer]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RaceTimerSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Font size of the timer (pixels at 1080p, scaled like the overlay)
+    #[serde(default = "default_font_size")]
+    pub font_size: f32,
+    /// Text of the timer: `{igt}`, `{race}`, `{deaths}`, `{bosses}`, `{level}`,
+    /// `{souls}` and `{flasks}` are replaced
+    #[serde(default = "default_format")]
+    pub format: String,
+    /// Screen point the timer is attached to
+    #[serde(default = "default_anchor")]
+    pub anchor: Anchor,
+    /// Horizontal margin from the anchored edge: pixels or percent ("2%")
+    #[serde(default)]
+    pub position_offset_x: Offset,
+    /// Vertical margin from the anchored edge: pixels or percent ("2%")
+    #[serde(default = "default_position_offset_y")]
+    pub position_offset_y: Offset,
+}
+
+fn default_font_size() -> f32 {
+    48.0
+}
+fn default_format() -> String {
+    "{igt}".to_string()
+}
+fn default_anchor() -> Anchor {
+    Anchor::TopCenter
+}
+fn default_position_offset_y() -> Offset {
+    Offset::Pixels(20.0)
+}
+
+impl Default for RaceTimerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            font_size: default_font_size(),
+            format: default_format(),
+            anchor: default_anchor(),
+            position_offset_x: Offset::default(),
+            position_offset_y: default_position_offset_y(),
+        }
+    }
+}
+
+/// Values of one frame, shared with the info overlay
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimerValues {
+    pub igt_ms: Option<u32>,
+    /// Race clock on the server's time, None until the start time is known
+    pub race_ms: Option<i64>,
+    pub deaths: Option<u32>,
+    /// Major bosses defeated, None until the flags could be read
+    pub bosses: Option<u32>,
+    /// Rune level
+    pub level: Option<u32>,
+    /// Runes held
+    pub souls: Option<u32>,
+    pub flasks: Option<Flasks>,
+}
+
+/// "HH:MM:SS", the overlay's IGT format
+pub fn format_clock(ms: u32) -> String {
+    let secs = ms / 1000;
+    let mins = secs / 60;
+    let hours = mins / 60;
+    format!("{:02}:{:02}:{:02}", hours, mins % 60, secs % 60)
+}
+
+impl RaceTimerSettings {
+    /// The timer text for this frame
+    pub fn text(&self, values: &TimerValues) -> String {
+        timer_text(&self.format, values)
+    }
+}
+
+/// A timer format filled with this frame's values (the race phase can
+/// replace `format`, see `race_phase`)
+pub fn timer_text(format: &str, values: &TimerValues) -> String {
+    let clock = |ms: Option<u32>| ms.map_or_else(|| UNKNOWN_TIME.to_string(), format_clock);
+    let igt = clock(values.igt_ms);
+    let race = clock(values.race_ms.map(|ms| ms.clamp(0, u32::MAX as i64) as u32));
+    let count = |n: Option<u32>| n.map_or_else(|| "-".to_string(), |n| n.to_string());
+    fill(
+        format,
+        &[
+            ("igt", &igt),
+            ("race", &race),
+            ("deaths", &count(values.deaths)),
+            ("bosses", &count(values.bosses)),
+            ("level", &count(values.level)),
+            ("souls", &count(values.souls)),
+            (
+                "flasks",
+                &values
+                    .flasks
+                    .map_or_else(|| "-".to_string(), |f| f.to_string()),
+            ),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALUES: TimerValues = TimerValues {
+        igt_ms: Some(3_723_000),
+        race_ms: Some(3_750_400),
+        deaths: Some(7),
+        bosses: Some(4),
+        level: Some(52),
+        souls: Some(18_340),
+        flasks: Some(Flasks {
+            crimson: 9,
+            cerulean: 5,
+        }),
+    };
+
+    #[test]
+    fn test_default_shows_igt() {
+        let settings = RaceTimerSettings::default();
+        assert_eq!(settings.text(&VALUES), "01:02:03");
+        assert_eq!(settings.text(&TimerValues::default()), "--:--:--");
+    }
+
+    #[test]
+    fn test_template() {
+        let settings = RaceTimerSettings {
+            format: "IGT {igt} | RTA {race} | {deaths} deaths | {bosses}/15 {unknown}".to_string(),
+            ..RaceTimerSettings::default()
+        };
+        assert_eq!(
+            settings.text(&VALUES),
+            "IGT 01:02:03 | RTA 01:02:30 | 7 deaths | 4/15 {unknown}"
+        );
+        let before_start = TimerValues {
+            race_ms: Some(-5000),
+            deaths: None,
+            bosses: None,
+            ..VALUES
+        };
+        assert_eq!(
+            settings.text(&before_start),
+            "IGT 01:02:03 | RTA 00:00:00 | - deaths | -/15 {unknown}"
+        );
+    }
+
+    #[test]
+    fn test_player_stats() {
+        let settings = RaceTimerSettings {
+            format: "RL{level} {souls} runes {flasks}".to_string(),
+            ..RaceTimerSettings::default()
+        };
+        assert_eq!(settings.text(&VALUES), "RL52 18340 runes 9/5");
+        // Title screen: nothing readable
+        assert_eq!(settings.text(&TimerValues::default()), "RL- - runes -");
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings: RaceTimerSettings = toml::from_str("").unwrap();
+        assert_eq!(settings, RaceTimerSettings::default());
+        let settings: RaceTimerSettings =
+            toml::from_str("enabled = true\nanchor = \"bottom_right\"\nposition_offset_x = \"2%\"")
+                .unwrap();
+        assert!(settings.enabled);
+        assert_eq!(settings.anchor, Anchor::BottomRight);
+        assert_eq!(settings.position_offset_x, Offset::Percent(2.0));
+        assert_eq!(settings.format, "{igt}");
+    }
+}
@@ -0,0 +1,166 @@
+//! Memory read audit
+//!
+//! Every read of game memory goes through a named pointer chain ("igt",
+//! "event_flags.tree", ...). The audit counts reads and failures per chain
+//! and, after a run of consecutive failures, skips the chain for a while
+//! (doubling up to `BACKOFF_MAX`) instead of hammering memory a game patch
+//! may have moved. A successful retry clears the backoff.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Consecutive failures before a chain backs off. Loading screens fail a few
+/// hundred reads in a row on the player chains; backing off then only
+/// delays the next read.
+pub const BACKOFF_THRESHOLD: u32 = 120;
+
+/// First backoff, doubled on each failed retry
+pub const BACKOFF_MIN: Duration = Duration::from_millis(250);
+
+pub const BACKOFF_MAX: Duration = Duration::from_secs(8);
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChainStats {
+    pub reads: u64,
+    pub failures: u64,
+    /// Reads not attempted during a backoff
+    pub skipped: u64,
+    pub consecutive_failures: u32,
+    backoff: Duration,
+    retry_at: Option<Instant>,
+}
+
+impl ChainStats {
+    /// Time left before the chain is read again, None when not backing off
+    pub fn backoff_left(&self, now: Instant) -> Option<Duration> {
+        self.retry_at
+            .map(|at| at.saturating_duration_since(now))
+            .filter(|left| !left.is_zero())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ReadAudit {
+    chains: BTreeMap<&'static str, ChainStats>,
+}
+
+impl ReadAudit {
+    pub const fn new() -> Self {
+        Self {
+            chains: BTreeMap::new(),
+        }
+    }
+
+    /// Whether `chain` may be read now. Reads refused during a backoff are
+    /// counted as skipped.
+    pub fn allow(&mut self, chain: &'static str, now: Instant) -> bool {
+        let stats = self.chains.entry(chain).or_default();
+        if stats.backoff_left(now).is_some() {
+            stats.skipped += 1;
+            return false;
+        }
+        true
+    }
+
+    /// Outcome of a read of `chain`. Returns true when this failure starts a
+    /// backoff (not on failed retries), so callers can log it once.
+    pub fn record(&mut self, chain: &'static str, ok: bool, now: Instant) -> bool {
+        let stats = self.chains.entry(chain).or_default();
+        stats.reads += 1;
+        if ok {
+            stats.consecutive_failures = 0;
+            stats.backoff = Duration::ZERO;
+            stats.retry_at = None;
+            return false;
+        }
+        stats.failures += 1;
+        stats.consecutive_failures = stats.consecutive_failures.saturating_add(1);
+        if stats.consecutive_failures < BACKOFF_THRESHOLD {
+            return false;
+        }
+        stats.backoff = if stats.backoff.is_zero() {
+            BACKOFF_MIN
+        } else {
+            (stats.backoff * 2).min(BACKOFF_MAX)
+        };
+        stats.retry_at = Some(now + stats.backoff);
+        stats.consecutive_failures == BACKOFF_THRESHOLD
+    }
+
+    /// Chains by name
+    pub fn chains(&self) -> impl Iterator<Item = (&'static str, &ChainStats)> {
+        self.chains.iter().map(|(&name, stats)| (name, stats))
+    }
+
+    pub fn total_failures(&self) -> u64 {
+        self.chains.values().map(|s| s.failures).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fail(audit: &mut ReadAudit, times: u32, now: Instant) -> bool {
+        let mut backed_off = false;
+        for _ in 0..times {
+            backed_off = audit.record("igt", false, now);
+        }
+        backed_off
+    }
+
+    #[test]
+    fn test_backoff_after_consecutive_failures() {
+        let mut audit = ReadAudit::new();
+        let t0 = Instant::now();
+        assert!(!fail(&mut audit, BACKOFF_THRESHOLD - 1, t0));
+        assert!(audit.allow("igt", t0));
+        assert!(fail(&mut audit, 1, t0));
+        assert!(!audit.allow("igt", t0));
+        assert!(!audit.allow("igt", t0 + BACKOFF_MIN / 2));
+        // Other chains are unaffected
+        assert!(audit.allow("deaths", t0));
+
+        // Failed retry: twice as long
+        let t1 = t0 + BACKOFF_MIN;
+        assert!(audit.allow("igt", t1));
+        assert!(!fail(&mut audit, 1, t1));
+        assert!(!audit.allow("igt", t1 + BACKOFF_MIN));
+        assert!(audit.allow("igt", t1 + BACKOFF_MIN * 2));
+
+        let (_, stats) = audit.chains().find(|(name, _)| *name == "igt").unwrap();
+        assert_eq!(stats.failures, u64::from(BACKOFF_THRESHOLD) + 1);
+        assert_eq!(stats.skipped, 3);
+    }
+
+    #[test]
+    fn test_success_clears_backoff() {
+        let mut audit = ReadAudit::new();
+        let t0 = Instant::now();
+        fail(&mut audit, BACKOFF_THRESHOLD, t0);
+        let t1 = t0 + BACKOFF_MIN;
+        assert!(!audit.record("igt", true, t1));
+        assert!(audit.allow("igt", t1));
+        let (_, stats) = audit.chains().next().unwrap();
+        assert_eq!(stats.consecutive_failures, 0);
+        assert_eq!(stats.backoff_left(t1), None);
+        // The next run of failures starts from the shortest backoff again
+        fail(&mut audit, BACKOFF_THRESHOLD, t1);
+        let (_, stats) = audit.chains().next().unwrap();
+        assert_eq!(stats.backoff_left(t1), Some(BACKOFF_MIN));
+    }
+
+    #[test]
+    fn test_backoff_capped() {
+        let mut audit = ReadAudit::new();
+        let mut now = Instant::now();
+        fail(&mut audit, BACKOFF_THRESHOLD, now);
+        for _ in 0..10 {
+            now += BACKOFF_MAX;
+            fail(&mut audit, 1, now);
+        }
+        let (_, stats) = audit.chains().next().unwrap();
+        assert_eq!(stats.backoff_left(now), Some(BACKOFF_MAX));
+        assert_eq!(audit.total_failures(), u64::from(BACKOFF_THRESHOLD) + 10);
+    }
+}
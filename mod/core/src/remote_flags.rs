@@ -0,0 +1,108 @@
+//! Event flags set by the organizer
+//!
+//! Casual races can use catch-up mechanics: the organizer opens a shortcut or
+//! a door for a player who fell behind by having the server send `set_flag`.
+//! Writing game memory on the server's behalf is off unless the player opts
+//! in, and only the flags listed in the player's own config can be written.
+//! Flags the mod tracks for progress (fog gates, finish, objectives,
+//! checkpoints) are always refused, so a write can never count as a
+//! discovery.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// `[remote_flags]` section of the config
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteFlagSettings {
+    /// Let the race server set event flags in the game
+    #[serde(default)]
+    pub enabled: bool,
+    /// The only flags the server may set
+    #[serde(default)]
+    pub allowed_flags: Vec<u32>,
+}
+
+/// Why a `set_flag` from the server was not applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagWriteRefusal {
+    /// `[remote_flags] enabled` is off
+    Disabled,
+    /// Not in `allowed_flags`
+    NotAllowed,
+    /// Tracked for race progress
+    Tracked,
+}
+
+impl fmt::Display for FlagWriteRefusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlagWriteRefusal::Disabled => write!(f, "remote flags disabled"),
+            FlagWriteRefusal::NotAllowed => write!(f, "flag not in allowed_flags"),
+            FlagWriteRefusal::Tracked => write!(f, "flag tracked for race progress"),
+        }
+    }
+}
+
+impl RemoteFlagSettings {
+    /// Whether the server may write `flag_id`. `is_tracked` tells the flags
+    /// the mod reports to the server.
+    pub fn check(
+        &self,
+        flag_id: u32,
+        is_tracked: impl Fn(u32) -> bool,
+    ) -> Result<(), FlagWriteRefusal> {
+        if !self.enabled {
+            return Err(FlagWriteRefusal::Disabled);
+        }
+        if !self.allowed_flags.contains(&flag_id) {
+            return Err(FlagWriteRefusal::NotAllowed);
+        }
+        if is_tracked(flag_id) {
+            return Err(FlagWriteRefusal::Tracked);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_by_default() {
+        let settings = RemoteFlagSettings {
+            allowed_flags: vec![10010500],
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.check(10010500, |_| false),
+            Err(FlagWriteRefusal::Disabled)
+        );
+    }
+
+    #[test]
+    fn test_whitelist() {
+        let settings = RemoteFlagSettings {
+            enabled: true,
+            allowed_flags: vec![10010500],
+        };
+        assert_eq!(settings.check(10010500, |_| false), Ok(()));
+        assert_eq!(
+            settings.check(10010501, |_| false),
+            Err(FlagWriteRefusal::NotAllowed)
+        );
+    }
+
+    #[test]
+    fn test_tracked_flags_refused() {
+        let settings = RemoteFlagSettings {
+            enabled: true,
+            allowed_flags: vec![9000001],
+        };
+        assert_eq!(
+            settings.check(9000001, |flag| flag == 9000001),
+            Err(FlagWriteRefusal::Tracked)
+        );
+    }
+}
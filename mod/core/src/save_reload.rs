@@ -0,0 +1,133 @@
+//! Save reload (save-scum) detection
+//!
+//! IGT and the death count are stored in the save file and only ever go up
+//! while playing. Restoring a backup of the save, or any reload that loses
+//! progress, takes them back: IGT rewinding more than a few seconds, or
+//! deaths going down, is reported so organizers can enforce no-savescum
+//! rules. Samples taken on the title screen or during loads (IGT unreadable
+//! or 0) are skipped, so quit-outs are not reloads.
+
+/// IGT rewinds shorter than this are quit-out jitter, not a reload
+const MIN_IGT_REWIND_MS: u32 = 10_000;
+
+/// A detected reload: the values read after it, and how far they went back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SaveReload {
+    pub igt_ms: u32,
+    /// IGT change across the reload (negative or 0)
+    pub igt_delta_ms: i32,
+    pub death_count: u32,
+    /// Death count change across the reload (negative or 0)
+    pub death_delta: i32,
+}
+
+/// Last IGT and death count read in game
+#[derive(Debug, Default)]
+pub struct SaveReloadWatch {
+    last_igt_ms: Option<u32>,
+    last_deaths: Option<u32>,
+}
+
+impl SaveReloadWatch {
+    /// Feed the IGT and death count read this frame (None while unreadable)
+    pub fn observe(&mut self, igt_ms: Option<u32>, deaths: Option<u32>) -> Option<SaveReload> {
+        let igt_ms = igt_ms.filter(|&igt| igt > 0)?;
+        let previous_igt = self.last_igt_ms.replace(igt_ms);
+        let previous_deaths = match deaths {
+            Some(deaths) => self.last_deaths.replace(deaths),
+            None => None,
+        };
+
+        let igt_delta = previous_igt.map_or(0, |prev| igt_ms as i64 - prev as i64);
+        let death_delta = match (previous_deaths, deaths) {
+            (Some(prev), Some(cur)) => cur as i64 - prev as i64,
+            _ => 0,
+        };
+        let igt_rewound = igt_delta <= -(MIN_IGT_REWIND_MS as i64);
+        if !igt_rewound && death_delta >= 0 {
+            return None;
+        }
+        Some(SaveReload {
+            igt_ms,
+            igt_delta_ms: igt_delta.min(0) as i32,
+            death_count: deaths.or(self.last_deaths).unwrap_or(0),
+            death_delta: death_delta.min(0) as i32,
+        })
+    }
+
+    /// Forget the baseline (new seed, another character loaded)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_is_not_a_reload() {
+        let mut watch = SaveReloadWatch::default();
+        assert_eq!(watch.observe(Some(60_000), Some(2)), None);
+        assert_eq!(watch.observe(Some(61_000), Some(3)), None);
+        // Quit-out: title screen and loading reads are skipped
+        assert_eq!(watch.observe(None, None), None);
+        assert_eq!(watch.observe(Some(0), Some(0)), None);
+        assert_eq!(watch.observe(Some(61_500), Some(3)), None);
+    }
+
+    #[test]
+    fn test_igt_rewind() {
+        let mut watch = SaveReloadWatch::default();
+        watch.observe(Some(600_000), Some(5));
+        assert_eq!(
+            watch.observe(Some(540_000), Some(5)),
+            Some(SaveReload {
+                igt_ms: 540_000,
+                igt_delta_ms: -60_000,
+                death_count: 5,
+                death_delta: 0,
+            })
+        );
+        // The reloaded values are the new baseline
+        assert_eq!(watch.observe(Some(541_000), Some(5)), None);
+    }
+
+    #[test]
+    fn test_small_igt_rewind_ignored() {
+        let mut watch = SaveReloadWatch::default();
+        watch.observe(Some(600_000), Some(5));
+        assert_eq!(watch.observe(Some(595_000), Some(5)), None);
+    }
+
+    #[test]
+    fn test_deaths_going_down() {
+        let mut watch = SaveReloadWatch::default();
+        watch.observe(Some(600_000), Some(5));
+        let reload = watch.observe(Some(598_000), Some(3)).unwrap();
+        assert_eq!(reload.igt_delta_ms, -2_000);
+        assert_eq!(reload.death_count, 3);
+        assert_eq!(reload.death_delta, -2);
+    }
+
+    #[test]
+    fn test_unreadable_deaths_keep_baseline() {
+        let mut watch = SaveReloadWatch::default();
+        watch.observe(Some(600_000), Some(5));
+        let reload = watch.observe(Some(100_000), None).unwrap();
+        assert_eq!(reload.death_count, 5);
+        assert_eq!(reload.death_delta, 0);
+        assert_eq!(
+            watch.observe(Some(101_000), Some(1)).unwrap().death_delta,
+            -4
+        );
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut watch = SaveReloadWatch::default();
+        watch.observe(Some(600_000), Some(5));
+        watch.reset();
+        assert_eq!(watch.observe(Some(1_000), Some(0)), None);
+    }
+}
@@ -0,0 +1,152 @@
+//! Zone scaling warnings
+//!
+//! Fog gates can lead into zones scaled far above what the player has
+//! reached so far. The tracker remembers the highest tier visited and flags
+//! zones whose tier exceeds it by at least the configured delta. Zones above
+//! an absolute danger tier get their tier line colored as well.
+
+use serde::{Deserialize, Serialize};
+
+/// `[scaling]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScalingSettings {
+    /// Warn when entering a zone this many tiers above the highest tier
+    /// reached so far (0 = never warn)
+    #[serde(default = "default_warn_tier_delta")]
+    pub warn_tier_delta: i32,
+    /// Tier line as "T4→T7" (original tier → scaled tier) instead of
+    /// "tier 7, previously 4"
+    #[serde(default)]
+    pub compact_tier: bool,
+    /// Tier line in the error color above this tier (0 = never)
+    #[serde(default)]
+    pub danger_tier: i32,
+}
+
+fn default_warn_tier_delta() -> i32 {
+    3
+}
+
+impl Default for ScalingSettings {
+    fn default() -> Self {
+        Self {
+            warn_tier_delta: default_warn_tier_delta(),
+            compact_tier: false,
+            danger_tier: 0,
+        }
+    }
+}
+
+impl ScalingSettings {
+    /// Whether a zone of `tier` is above the danger tier
+    pub fn is_dangerous(&self, tier: i32) -> bool {
+        self.danger_tier > 0 && tier > self.danger_tier
+    }
+}
+
+/// A zone scaled above the player's progression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScalingWarning {
+    /// Tier of the zone entered
+    pub tier: i32,
+    /// Tiers above the highest tier reached before entering it
+    pub delta: i32,
+}
+
+/// Highest tier reached during the race
+#[derive(Debug, Default)]
+pub struct ProgressionTracker {
+    highest_tier: Option<i32>,
+}
+
+impl ProgressionTracker {
+    pub fn highest_tier(&self) -> Option<i32> {
+        self.highest_tier
+    }
+
+    /// Record entering a zone of `tier`. Returns a warning when it is at
+    /// least `threshold` tiers above the progression so far. The first zone
+    /// with a tier only sets the baseline.
+    pub fn enter(&mut self, tier: Option<i32>, threshold: i32) -> Option<ScalingWarning> {
+        let tier = tier?;
+        let warning = match self.highest_tier {
+            Some(highest) if threshold > 0 && tier - highest >= threshold => Some(ScalingWarning {
+                tier,
+                delta: tier - highest,
+            }),
+            _ => None,
+        };
+        self.highest_tier = Some(self.highest_tier.map_or(tier, |h| h.max(tier)));
+        warning
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_zone_sets_baseline() {
+        let mut progression = ProgressionTracker::default();
+        assert_eq!(progression.enter(Some(8), 3), None);
+        assert_eq!(progression.highest_tier(), Some(8));
+    }
+
+    #[test]
+    fn test_warns_at_threshold() {
+        let mut progression = ProgressionTracker::default();
+        progression.enter(Some(1), 3);
+        assert_eq!(progression.enter(Some(3), 3), None);
+        assert_eq!(
+            progression.enter(Some(6), 3),
+            Some(ScalingWarning { tier: 6, delta: 3 })
+        );
+    }
+
+    #[test]
+    fn test_progression_only_goes_up() {
+        let mut progression = ProgressionTracker::default();
+        progression.enter(Some(5), 3);
+        progression.enter(Some(2), 3);
+        assert_eq!(progression.highest_tier(), Some(5));
+        // Returning to an already reached tier doesn't warn
+        assert_eq!(progression.enter(Some(7), 3), None);
+    }
+
+    #[test]
+    fn test_zone_without_tier_is_ignored() {
+        let mut progression = ProgressionTracker::default();
+        assert_eq!(progression.enter(None, 3), None);
+        assert_eq!(progression.highest_tier(), None);
+        progression.enter(Some(2), 3);
+        assert_eq!(progression.enter(None, 3), None);
+        assert_eq!(progression.highest_tier(), Some(2));
+    }
+
+    #[test]
+    fn test_zero_threshold_disables_warnings() {
+        let mut progression = ProgressionTracker::default();
+        progression.enter(Some(1), 0);
+        assert_eq!(progression.enter(Some(10), 0), None);
+        assert_eq!(progression.highest_tier(), Some(10));
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings: ScalingSettings = toml::from_str("").unwrap();
+        assert_eq!(settings.warn_tier_delta, 3);
+        let settings: ScalingSettings = toml::from_str("warn_tier_delta = 5").unwrap();
+        assert_eq!(settings.warn_tier_delta, 5);
+        assert!(!settings.compact_tier);
+        assert_eq!(settings.danger_tier, 0);
+    }
+
+    #[test]
+    fn test_danger_tier() {
+        let mut settings = ScalingSettings::default();
+        assert!(!settings.is_dangerous(20));
+        settings.danger_tier = 10;
+        assert!(!settings.is_dangerous(10));
+        assert!(settings.is_dangerous(11));
+    }
+}
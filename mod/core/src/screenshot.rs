@@ -0,0 +1,142 @@
+//! Race screenshots
+//!
+//! PNG captures of the game window on finish, on boss kills or with the
+//! screenshot hotkey, kept in a folder next to the DLL as evidence for
+//! verification disputes. The file name carries the race, seed and IGT of
+//! the capture. The capture itself is platform code (`dll::screenshot`).
+
+use serde::{Deserialize, Serialize};
+
+use super::post_race::file_safe_id;
+
+/// `[screenshots]` section of the config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenshotSettings {
+    /// Capture automatically on race events (the hotkey always works)
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_true")]
+    pub on_finish: bool,
+    #[serde(default = "default_true")]
+    pub on_boss_kill: bool,
+    /// Folder next to the DLL (or absolute path)
+    #[serde(default = "default_folder")]
+    pub folder: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+fn default_folder() -> String {
+    "screenshots".to_string()
+}
+
+impl Default for ScreenshotSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            on_finish: true,
+            on_boss_kill: true,
+            folder: default_folder(),
+        }
+    }
+}
+
+impl ScreenshotSettings {
+    /// Whether this event takes a screenshot
+    pub fn captures(&self, trigger: ScreenshotTrigger) -> bool {
+        match trigger {
+            ScreenshotTrigger::Manual => true,
+            ScreenshotTrigger::Finish => self.enabled && self.on_finish,
+            ScreenshotTrigger::BossKill => self.enabled && self.on_boss_kill,
+        }
+    }
+}
+
+/// What took the screenshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenshotTrigger {
+    Finish,
+    BossKill,
+    /// Screenshot hotkey
+    Manual,
+}
+
+impl ScreenshotTrigger {
+    pub fn label(self) -> &'static str {
+        match self {
+            ScreenshotTrigger::Finish => "finish",
+            ScreenshotTrigger::BossKill => "boss",
+            ScreenshotTrigger::Manual => "manual",
+        }
+    }
+}
+
+/// `speedfog_<race>_<seed>_<IGT>_<trigger>.png`, IGT as `01h23m45s678`
+/// so files sort in race order
+pub fn file_name(
+    race_id: &str,
+    seed_id: Option<&str>,
+    igt_ms: u32,
+    trigger: ScreenshotTrigger,
+) -> String {
+    let seconds = igt_ms / 1000;
+    format!(
+        "speedfog_{}_{}_{:02}h{:02}m{:02}s{:03}_{}.png",
+        file_safe_id(race_id),
+        file_safe_id(seed_id.unwrap_or("noseed")),
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        igt_ms % 1000,
+        trigger.label()
+    )
+}
+
+/// Turn the BGRA pixels of a GDI capture into opaque RGBA, in place
+pub fn bgra_to_rgba(pixels: &mut [u8]) {
+    for px in pixels.chunks_exact_mut(4) {
+        px.swap(0, 2);
+        px[3] = 0xFF;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(
+            file_name(
+                "race/1",
+                Some("seed-42"),
+                5_025_678,
+                ScreenshotTrigger::Finish
+            ),
+            "speedfog_race_1_seed-42_01h23m45s678_finish.png"
+        );
+        assert_eq!(
+            file_name("r", None, 999, ScreenshotTrigger::Manual),
+            "speedfog_r_noseed_00h00m00s999_manual.png"
+        );
+    }
+
+    #[test]
+    fn test_captures() {
+        let mut settings = ScreenshotSettings::default();
+        assert!(settings.captures(ScreenshotTrigger::Manual));
+        assert!(!settings.captures(ScreenshotTrigger::Finish));
+        settings.enabled = true;
+        settings.on_boss_kill = false;
+        assert!(settings.captures(ScreenshotTrigger::Finish));
+        assert!(!settings.captures(ScreenshotTrigger::BossKill));
+    }
+
+    #[test]
+    fn test_bgra_to_rgba() {
+        let mut pixels = vec![1, 2, 3, 0, 10, 20, 30, 40];
+        bgra_to_rgba(&mut pixels);
+        assert_eq!(pixels, vec![3, 2, 1, 255, 30, 20, 10, 255]);
+    }
+}
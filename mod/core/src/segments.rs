@@ -0,0 +1,211 @@
+//! Run segments
+//!
+//! A seed can split the run into named segments, each running from the first
+//! arrival in one zone to the next arrival in another ("Stormveil → Liurnia").
+//! The tracker times them on IGT, counts the deaths inside each one, shows
+//! the segment in progress on the overlay and lists them all in the post-race
+//! summary. Segments may overlap; a segment whose start zone is never reached
+//! doesn't appear.
+
+use serde::Serialize;
+
+use super::protocol::Segment;
+
+/// Progress of one segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SegmentRun {
+    start_igt_ms: u32,
+    end_igt_ms: Option<u32>,
+    deaths: u32,
+}
+
+/// A started segment, for display and the summary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SegmentResult {
+    pub name: String,
+    pub start_igt_ms: u32,
+    /// None while the segment is in progress
+    pub end_igt_ms: Option<u32>,
+    pub deaths: u32,
+}
+
+impl SegmentResult {
+    /// Time spent in the segment, up to `now_igt_ms` while in progress
+    pub fn elapsed_ms(&self, now_igt_ms: u32) -> u32 {
+        self.end_igt_ms
+            .unwrap_or(now_igt_ms)
+            .saturating_sub(self.start_igt_ms)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SegmentTracker {
+    segments: Vec<Segment>,
+    /// Parallel to `segments`, None until the start zone is reached
+    runs: Vec<Option<SegmentRun>>,
+}
+
+impl SegmentTracker {
+    /// Segments of the seed. Progress is kept when they are unchanged
+    /// (reconnects resend the seed).
+    pub fn set_segments(&mut self, segments: Vec<Segment>) {
+        if segments != self.segments {
+            self.runs = vec![None; segments.len()];
+            self.segments = segments;
+        }
+    }
+
+    /// Forget the progress, keep the segments
+    pub fn reset(&mut self) {
+        self.runs = vec![None; self.segments.len()];
+    }
+
+    /// The player entered a zone: start the segments beginning here and end
+    /// those in progress that end here. Returns the segments just completed.
+    pub fn zone_entered(&mut self, node_id: &str, igt_ms: u32) -> Vec<SegmentResult> {
+        let mut completed = Vec::new();
+        for (i, segment) in self.segments.iter().enumerate() {
+            match &mut self.runs[i] {
+                None if segment.from_node == node_id => {
+                    self.runs[i] = Some(SegmentRun {
+                        start_igt_ms: igt_ms,
+                        end_igt_ms: None,
+                        deaths: 0,
+                    });
+                }
+                Some(run) if run.end_igt_ms.is_none() && segment.to_node == node_id => {
+                    run.end_igt_ms = Some(igt_ms.max(run.start_igt_ms));
+                    completed.push(result(segment, run));
+                }
+                _ => {}
+            }
+        }
+        completed
+    }
+
+    /// The player died: counts in every segment in progress
+    pub fn death(&mut self) {
+        for run in self.runs.iter_mut().flatten() {
+            if run.end_igt_ms.is_none() {
+                run.deaths += 1;
+            }
+        }
+    }
+
+    /// The segment in progress started last
+    pub fn current(&self) -> Option<SegmentResult> {
+        self.segments
+            .iter()
+            .zip(&self.runs)
+            .filter_map(|(segment, run)| Some((segment, run.as_ref()?)))
+            .filter(|(_, run)| run.end_igt_ms.is_none())
+            .max_by_key(|(_, run)| run.start_igt_ms)
+            .map(|(segment, run)| result(segment, run))
+    }
+
+    /// Started segments, in seed order
+    pub fn results(&self) -> Vec<SegmentResult> {
+        self.segments
+            .iter()
+            .zip(&self.runs)
+            .filter_map(|(segment, run)| Some(result(segment, run.as_ref()?)))
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+}
+
+fn result(segment: &Segment, run: &SegmentRun) -> SegmentResult {
+    SegmentResult {
+        name: segment.name.clone(),
+        start_igt_ms: run.start_igt_ms,
+        end_igt_ms: run.end_igt_ms,
+        deaths: run.deaths,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(name: &str, from: &str, to: &str) -> Segment {
+        Segment {
+            name: name.to_string(),
+            from_node: from.to_string(),
+            to_node: to.to_string(),
+        }
+    }
+
+    fn tracker() -> SegmentTracker {
+        let mut tracker = SegmentTracker::default();
+        tracker.set_segments(vec![
+            segment("Castle", "stormveil", "liurnia"),
+            segment("Academy", "liurnia", "raya_lucaria"),
+        ]);
+        tracker
+    }
+
+    #[test]
+    fn test_segment_times_and_deaths() {
+        let mut tracker = tracker();
+        // Deaths before the first segment don't count
+        tracker.death();
+        assert!(tracker.zone_entered("stormveil", 60_000).is_empty());
+        tracker.death();
+        tracker.death();
+        let completed = tracker.zone_entered("liurnia", 300_000);
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].name, "Castle");
+        assert_eq!(completed[0].elapsed_ms(999_999), 240_000);
+        assert_eq!(completed[0].deaths, 2);
+
+        // Entering liurnia also started the next segment
+        let current = tracker.current().unwrap();
+        assert_eq!(current.name, "Academy");
+        assert_eq!(current.elapsed_ms(330_000), 30_000);
+        assert_eq!(current.deaths, 0);
+    }
+
+    #[test]
+    fn test_revisits_dont_restart() {
+        let mut tracker = tracker();
+        tracker.zone_entered("stormveil", 60_000);
+        tracker.zone_entered("stormveil", 90_000);
+        tracker.zone_entered("liurnia", 120_000);
+        // Back to the start zone after completing the segment
+        tracker.zone_entered("stormveil", 150_000);
+        tracker.zone_entered("liurnia", 200_000);
+        let results = tracker.results();
+        assert_eq!(results[0].start_igt_ms, 60_000);
+        assert_eq!(results[0].end_igt_ms, Some(120_000));
+    }
+
+    #[test]
+    fn test_unstarted_segments_hidden() {
+        let mut tracker = tracker();
+        assert!(tracker.current().is_none());
+        // The end zone alone doesn't complete anything
+        assert!(tracker.zone_entered("liurnia", 10_000).is_empty());
+        let results = tracker.results();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "Academy");
+    }
+
+    #[test]
+    fn test_reconnect_keeps_progress() {
+        let mut tracker = tracker();
+        tracker.zone_entered("stormveil", 60_000);
+        tracker.set_segments(vec![
+            segment("Castle", "stormveil", "liurnia"),
+            segment("Academy", "liurnia", "raya_lucaria"),
+        ]);
+        assert_eq!(tracker.current().unwrap().name, "Castle");
+        // A different seed starts over
+        tracker.set_segments(vec![segment("Caelid", "caelid", "redmane")]);
+        assert!(tracker.current().is_none());
+        tracker.reset();
+        assert!(tracker.results().is_empty());
+    }
+}
@@ -0,0 +1,494 @@
+//! Setup self-test
+//!
+//! A hotkey (Ctrl+F3 by default) or `[self_test] on_startup` checks what a
+//! race needs, in one go: the game memory reads (position, IGT, deaths,
+//! event flags), the server connection and auth, the seed pack, the overlay
+//! font and icons. The overlay lists the results as a pass/fail checklist, and
+//! the report is written next to the DLL so players can attach it to a
+//! support request.
+//!
+//! The IGT is sampled twice, `SAMPLE_DELAY` apart, to see that it runs; every
+//! other check reads the state once at the end.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::types::PlayerPosition;
+
+/// Time between the two IGT samples
+pub const SAMPLE_DELAY: Duration = Duration::from_secs(2);
+
+/// Vanilla event flag present in every save (category 0), read to tell a
+/// broken flag reader from seed flags the game doesn't know
+pub const VANILLA_FLAG: u32 = 6;
+
+/// `[self_test]` section of the config
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SelfTestSettings {
+    /// Run the self-test once the game is loaded
+    #[serde(default)]
+    pub on_startup: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckStatus {
+    Pass,
+    /// Works, but may not during the race (e.g. still connecting)
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        }
+    }
+}
+
+/// What a check covers, in checklist order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckId {
+    Position,
+    Igt,
+    Deaths,
+    EventFlags,
+    Connection,
+    Auth,
+    SeedPack,
+    Font,
+    Icons,
+}
+
+impl CheckId {
+    /// Name in the report, and locale key under `self_test.check`
+    pub fn key(self) -> &'static str {
+        match self {
+            CheckId::Position => "position",
+            CheckId::Igt => "igt",
+            CheckId::Deaths => "deaths",
+            CheckId::EventFlags => "event_flags",
+            CheckId::Connection => "connection",
+            CheckId::Auth => "auth",
+            CheckId::SeedPack => "seed_pack",
+            CheckId::Font => "font",
+            CheckId::Icons => "icons",
+        }
+    }
+}
+
+/// Outcome of one check. `detail` is in English: it goes into the report
+/// for support as much as on screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub id: CheckId,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(id: CheckId, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            id,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+pub fn check_position(position: Option<&PlayerPosition>) -> Check {
+    match position {
+        Some(p) if [p.x, p.y, p.z].iter().all(|c| c.is_finite()) => Check::new(
+            CheckId::Position,
+            CheckStatus::Pass,
+            format!("{} ({:.1}, {:.1}, {:.1})", p.map_id_str, p.x, p.y, p.z),
+        ),
+        Some(_) => Check::new(CheckId::Position, CheckStatus::Fail, "invalid coordinates"),
+        None => Check::new(CheckId::Position, CheckStatus::Fail, "not readable"),
+    }
+}
+
+/// The IGT must run between the two samples, unless a loading screen
+/// paused it
+pub fn check_igt(first: Option<u32>, second: Option<u32>, loading: bool) -> Check {
+    match (first, second) {
+        (Some(first), Some(second)) if second > first => Check::new(
+            CheckId::Igt,
+            CheckStatus::Pass,
+            format!("{} ms, running", second),
+        ),
+        (Some(_), Some(second)) if loading => Check::new(
+            CheckId::Igt,
+            CheckStatus::Warn,
+            format!("{} ms, paused by a loading screen", second),
+        ),
+        (Some(_), Some(second)) => Check::new(
+            CheckId::Igt,
+            CheckStatus::Fail,
+            format!("{} ms, not running", second),
+        ),
+        _ => Check::new(CheckId::Igt, CheckStatus::Fail, "not readable"),
+    }
+}
+
+pub fn check_deaths(deaths: Option<u32>) -> Check {
+    match deaths {
+        Some(deaths) => Check::new(CheckId::Deaths, CheckStatus::Pass, deaths.to_string()),
+        None => Check::new(CheckId::Deaths, CheckStatus::Fail, "not readable"),
+    }
+}
+
+/// `vanilla` is the read of `VANILLA_FLAG`, `seed_reads` those of the
+/// seed's event flags (None: unreadable)
+pub fn check_event_flags(vanilla: Option<bool>, seed_reads: &[Option<bool>]) -> Check {
+    if vanilla.is_none() {
+        return Check::new(
+            CheckId::EventFlags,
+            CheckStatus::Fail,
+            "flag reader broken (vanilla flag unreadable)",
+        );
+    }
+    if seed_reads.is_empty() {
+        return Check::new(
+            CheckId::EventFlags,
+            CheckStatus::Warn,
+            "reader works, no seed flags to check yet",
+        );
+    }
+    let readable = seed_reads.iter().filter(|r| r.is_some()).count();
+    let status = if readable == seed_reads.len() {
+        CheckStatus::Pass
+    } else {
+        CheckStatus::Fail
+    };
+    Check::new(
+        CheckId::EventFlags,
+        status,
+        format!("{}/{} seed flags readable", readable, seed_reads.len()),
+    )
+}
+
+/// `connected` is the current connection status, `error` the last one seen
+pub fn check_connection(configured: bool, connected: bool, error: Option<&str>) -> Check {
+    match (configured, connected, error) {
+        (false, _, _) => Check::new(
+            CheckId::Connection,
+            CheckStatus::Fail,
+            "[server] url, mod_token or race_id missing",
+        ),
+        (true, true, _) => Check::new(CheckId::Connection, CheckStatus::Pass, "connected"),
+        (true, false, Some(error)) => Check::new(CheckId::Connection, CheckStatus::Fail, error),
+        (true, false, None) => Check::new(CheckId::Connection, CheckStatus::Warn, "connecting"),
+    }
+}
+
+/// `auth_error` is the server's last auth_error message
+pub fn check_auth(authenticated: bool, auth_error: Option<&str>) -> Check {
+    match (authenticated, auth_error) {
+        (true, _) => Check::new(CheckId::Auth, CheckStatus::Pass, "authenticated"),
+        (false, Some(error)) => Check::new(CheckId::Auth, CheckStatus::Fail, error),
+        (false, None) => Check::new(CheckId::Auth, CheckStatus::Warn, "not authenticated yet"),
+    }
+}
+
+/// Files of the seed pack around the DLL (`<pack>/lib/` holds the DLL)
+const SEED_PACK_FILES: [&str; 2] = ["graph.json", "ModEngine"];
+
+/// The seed pack is extracted around `dll_dir`, and is the server's seed
+pub fn check_seed_pack(
+    dll_dir: Option<&Path>,
+    config_seed_id: &str,
+    server_seed_id: Option<&str>,
+) -> Check {
+    let Some(pack_dir) = dll_dir.and_then(Path::parent) else {
+        return Check::new(CheckId::SeedPack, CheckStatus::Fail, "DLL folder unknown");
+    };
+    let missing: Vec<&str> = SEED_PACK_FILES
+        .into_iter()
+        .filter(|file| !pack_dir.join(file).exists())
+        .collect();
+    if !missing.is_empty() {
+        return Check::new(
+            CheckId::SeedPack,
+            CheckStatus::Fail,
+            format!("missing in {}: {}", pack_dir.display(), missing.join(", ")),
+        );
+    }
+    match server_seed_id {
+        Some(server) if !config_seed_id.is_empty() && server != config_seed_id => Check::new(
+            CheckId::SeedPack,
+            CheckStatus::Fail,
+            format!(
+                "outdated: seed {} installed, the race uses {}",
+                config_seed_id, server
+            ),
+        ),
+        Some(_) => Check::new(
+            CheckId::SeedPack,
+            CheckStatus::Pass,
+            "installed, up to date",
+        ),
+        None => Check::new(
+            CheckId::SeedPack,
+            CheckStatus::Pass,
+            "installed (race seed not known yet)",
+        ),
+    }
+}
+
+/// `font_path` as in `[overlay]` (empty for the default font), `loaded`
+/// whether the file could be read
+pub fn check_font(font_path: &str, loaded: bool) -> Check {
+    let name = if font_path.is_empty() {
+        "default font"
+    } else {
+        font_path
+    };
+    if loaded {
+        Check::new(CheckId::Font, CheckStatus::Pass, name)
+    } else {
+        Check::new(
+            CheckId::Font,
+            CheckStatus::Fail,
+            format!("{} not found, using the built-in font", name),
+        )
+    }
+}
+
+pub fn check_icons(loaded: bool) -> Check {
+    if loaded {
+        Check::new(CheckId::Icons, CheckStatus::Pass, "loaded")
+    } else {
+        Check::new(CheckId::Icons, CheckStatus::Fail, "not loaded")
+    }
+}
+
+/// A self-test in progress: waits `SAMPLE_DELAY` after the first IGT sample
+#[derive(Debug)]
+pub struct SelfTestRun {
+    started_at: Instant,
+    first_igt: Option<u32>,
+}
+
+impl SelfTestRun {
+    pub fn start(now: Instant, igt: Option<u32>) -> Self {
+        Self {
+            started_at: now,
+            first_igt: igt,
+        }
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started_at) >= SAMPLE_DELAY
+    }
+
+    pub fn first_igt(&self) -> Option<u32> {
+        self.first_igt
+    }
+}
+
+/// Results of a self-test, in checklist order
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfTestReport {
+    pub checks: Vec<Check>,
+    /// Local time of the test, e.g. "2026-03-01 20:15:42"
+    pub timestamp: String,
+    pub mod_version: String,
+    pub game_version: Option<String>,
+}
+
+impl SelfTestReport {
+    pub fn new(mut checks: Vec<Check>, timestamp: String, mod_version: String) -> Self {
+        checks.sort_by_key(|check| check.id as u8);
+        Self {
+            checks,
+            timestamp,
+            mod_version,
+            game_version: None,
+        }
+    }
+
+    /// Worst status of the checks
+    pub fn status(&self) -> CheckStatus {
+        self.checks
+            .iter()
+            .map(|check| check.status)
+            .max()
+            .unwrap_or(CheckStatus::Pass)
+    }
+
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+
+    /// `speedfog_self_test_<timestamp>.txt`
+    pub fn file_name(&self) -> String {
+        let stamp: String = self
+            .timestamp
+            .chars()
+            .map(|c| match c {
+                ':' | ' ' => '-',
+                c => c,
+            })
+            .collect();
+        format!("speedfog_self_test_{}.txt", stamp)
+    }
+
+    /// Plain text report, one line per check
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "SpeedFog Racing self-test\n\
+             Date: {}\n\
+             Mod version: {}\n\
+             Game version: {}\n\
+             Result: {} ({} passed, {} warnings, {} failed)\n\n",
+            self.timestamp,
+            self.mod_version,
+            self.game_version.as_deref().unwrap_or("unknown"),
+            self.status().label(),
+            self.count(CheckStatus::Pass),
+            self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail),
+        );
+        for check in &self.checks {
+            text.push_str(&format!(
+                "[{}] {:<12} {}\n",
+                check.status.label(),
+                check.id.key(),
+                check.detail
+            ));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_igt_must_run() {
+        assert_eq!(
+            check_igt(Some(1000), Some(3000), false).status,
+            CheckStatus::Pass
+        );
+        assert_eq!(
+            check_igt(Some(1000), Some(1000), true).status,
+            CheckStatus::Warn
+        );
+        assert_eq!(
+            check_igt(Some(1000), Some(1000), false).status,
+            CheckStatus::Fail
+        );
+        assert_eq!(check_igt(None, Some(1000), false).status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn test_event_flags() {
+        assert_eq!(check_event_flags(None, &[]).status, CheckStatus::Fail);
+        assert_eq!(check_event_flags(Some(true), &[]).status, CheckStatus::Warn);
+        let check = check_event_flags(Some(false), &[Some(false), None]);
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert_eq!(check.detail, "1/2 seed flags readable");
+        assert_eq!(
+            check_event_flags(Some(false), &[Some(true), Some(false)]).status,
+            CheckStatus::Pass
+        );
+    }
+
+    #[test]
+    fn test_connection_and_auth() {
+        assert_eq!(
+            check_connection(false, false, None).status,
+            CheckStatus::Fail
+        );
+        assert_eq!(
+            check_connection(true, true, Some("old")).status,
+            CheckStatus::Pass
+        );
+        assert_eq!(
+            check_connection(true, false, None).status,
+            CheckStatus::Warn
+        );
+        let check = check_connection(true, false, Some("Connect failed: refused"));
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert_eq!(check.detail, "Connect failed: refused");
+
+        assert_eq!(check_auth(true, None).status, CheckStatus::Pass);
+        assert_eq!(
+            check_auth(false, Some("Invalid mod token")).status,
+            CheckStatus::Fail
+        );
+    }
+
+    #[test]
+    fn test_seed_pack() {
+        let pack = std::env::temp_dir().join(format!("speedfog_self_test_{}", std::process::id()));
+        let lib = pack.join("lib");
+        std::fs::create_dir_all(&lib).unwrap();
+
+        let check = check_seed_pack(Some(&lib), "abc", None);
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.detail.contains("graph.json, ModEngine"));
+
+        std::fs::write(pack.join("graph.json"), "{}").unwrap();
+        std::fs::create_dir_all(pack.join("ModEngine")).unwrap();
+        assert_eq!(
+            check_seed_pack(Some(&lib), "abc", Some("abc")).status,
+            CheckStatus::Pass
+        );
+        assert_eq!(
+            check_seed_pack(Some(&lib), "abc", Some("def")).status,
+            CheckStatus::Fail
+        );
+        assert_eq!(
+            check_seed_pack(Some(&lib), "", Some("def")).status,
+            CheckStatus::Pass
+        );
+
+        std::fs::remove_dir_all(&pack).unwrap();
+    }
+
+    #[test]
+    fn test_report() {
+        let mut report = SelfTestReport::new(
+            vec![
+                check_icons(true),
+                check_deaths(Some(3)),
+                check_connection(true, false, None),
+            ],
+            "2026-03-01 20:15:42".to_string(),
+            "1.3.2".to_string(),
+        );
+        report.game_version = Some("2.6.1".to_string());
+        assert_eq!(report.status(), CheckStatus::Warn);
+        assert_eq!(
+            report.file_name(),
+            "speedfog_self_test_2026-03-01-20-15-42.txt"
+        );
+        let text = report.to_text();
+        assert!(text.contains("Result: WARN (2 passed, 1 warnings, 0 failed)"));
+        let lines: Vec<&str> = text.lines().skip(6).collect();
+        assert_eq!(
+            lines,
+            [
+                "[PASS] deaths       3",
+                "[WARN] connection   connecting",
+                "[PASS] icons        loaded",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_waits_for_second_sample() {
+        let start = Instant::now();
+        let run = SelfTestRun::start(start, Some(1000));
+        assert!(!run.is_due(start + Duration::from_secs(1)));
+        assert!(run.is_due(start + SAMPLE_DELAY));
+        assert_eq!(run.first_igt(), Some(1000));
+    }
+}
@@ -0,0 +1,132 @@
+//! Personal best splits per seed
+//!
+//! Zone arrival times of the best finished run on each seed, persisted next to
+//! the DLL. On later attempts at the same seed the overlay shows the delta
+//! against the personal best as each zone is entered.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Arrival in a zone, first visit only
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Split {
+    pub node_id: String,
+    /// Display name at the time, for humans reading the file
+    pub name: String,
+    pub igt_ms: u32,
+}
+
+/// Best finished run on a seed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonalBest {
+    pub final_igt_ms: u32,
+    pub splits: Vec<Split>,
+}
+
+impl PersonalBest {
+    /// Time vs the personal best when arriving in `node_id` at `igt_ms`
+    /// (negative = ahead), None if the PB never entered that zone
+    pub fn delta(&self, node_id: &str, igt_ms: u32) -> Option<i32> {
+        self.splits
+            .iter()
+            .find(|s| s.node_id == node_id)
+            .map(|s| igt_ms as i32 - s.igt_ms as i32)
+    }
+}
+
+/// Contents of the splits file: personal bests keyed by seed_id
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitsFile {
+    #[serde(default)]
+    pub seeds: HashMap<String, PersonalBest>,
+}
+
+impl SplitsFile {
+    pub const FILENAME: &'static str = "speedfog_splits.json";
+
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        serde_json::from_str(contents).map_err(|e| format!("Failed to parse splits: {}", e))
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize splits: {}", e))
+    }
+
+    pub fn personal_best(&self, seed_id: &str) -> Option<&PersonalBest> {
+        self.seeds.get(seed_id)
+    }
+
+    /// Store a finished run if it beats the personal best. Returns true if it did.
+    pub fn record_run(&mut self, seed_id: &str, final_igt_ms: u32, splits: Vec<Split>) -> bool {
+        if self
+            .seeds
+            .get(seed_id)
+            .is_some_and(|pb| pb.final_igt_ms <= final_igt_ms)
+        {
+            return false;
+        }
+        self.seeds.insert(
+            seed_id.to_string(),
+            PersonalBest {
+                final_igt_ms,
+                splits,
+            },
+        );
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(node_id: &str, igt_ms: u32) -> Split {
+        Split {
+            node_id: node_id.to_string(),
+            name: node_id.to_uppercase(),
+            igt_ms,
+        }
+    }
+
+    #[test]
+    fn test_delta() {
+        let pb = PersonalBest {
+            final_igt_ms: 600_000,
+            splits: vec![split("start", 0), split("stormveil", 120_000)],
+        };
+        assert_eq!(pb.delta("stormveil", 125_000), Some(5_000));
+        assert_eq!(pb.delta("stormveil", 110_000), Some(-10_000));
+        assert_eq!(pb.delta("caelid", 110_000), None);
+    }
+
+    #[test]
+    fn test_record_run_keeps_best() {
+        let mut file = SplitsFile::default();
+        assert!(file.record_run("seed1", 600_000, vec![split("a", 1000)]));
+        // Slower run doesn't replace the PB
+        assert!(!file.record_run("seed1", 700_000, vec![split("a", 500)]));
+        // Ties don't either
+        assert!(!file.record_run("seed1", 600_000, vec![split("a", 500)]));
+        assert!(file.record_run("seed1", 550_000, vec![split("a", 900)]));
+        let pb = file.personal_best("seed1").unwrap();
+        assert_eq!(pb.final_igt_ms, 550_000);
+        assert_eq!(pb.splits[0].igt_ms, 900);
+        // Seeds are independent
+        assert!(file.personal_best("seed2").is_none());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut file = SplitsFile::default();
+        file.record_run("seed1", 600_000, vec![split("a", 1000)]);
+        let parsed = SplitsFile::parse(&file.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, file);
+    }
+
+    #[test]
+    fn test_parse_empty_and_invalid() {
+        assert_eq!(SplitsFile::parse("{}").unwrap(), SplitsFile::default());
+        assert!(SplitsFile::parse("not json").is_err());
+    }
+}
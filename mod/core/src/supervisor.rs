@@ -0,0 +1,288 @@
+//! Background thread supervision
+//!
+//! Worker threads (WebSocket client, item spawner) report their state and a
+//! heartbeat through a shared `WorkerHealth`. A panicking worker is caught and
+//! its cause kept, so the tracker can restart it with a backoff instead of
+//! silently running without it, and the debug panel can show which threads
+//! are alive, stalled or dead.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A running worker that hasn't beaten for this long is reported as stalled
+pub const STALL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Delay before the first restart of a panicked worker, doubled on each
+/// restart up to `MAX_RESTART_DELAY`
+pub const INITIAL_RESTART_DELAY: Duration = Duration::from_secs(1);
+pub const MAX_RESTART_DELAY: Duration = Duration::from_secs(60);
+
+/// A restarted worker running this long without panicking resets the backoff
+pub const STABLE_RUN: Duration = Duration::from_secs(300);
+
+/// Lifecycle of a worker thread
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkerState {
+    /// Not started yet
+    Idle,
+    Running,
+    /// Returned normally (shutdown, or one-shot work done)
+    Finished,
+    /// Died with this panic message
+    Panicked(String),
+}
+
+/// Health of one worker, shared between the worker and the supervisor
+#[derive(Debug)]
+pub struct WorkerHealth {
+    name: &'static str,
+    state: Mutex<WorkerState>,
+    epoch: Instant,
+    /// Last heartbeat, in ms since `epoch`
+    last_beat_ms: AtomicU64,
+    restarts: AtomicU32,
+}
+
+impl WorkerHealth {
+    pub fn new(name: &'static str) -> Arc<Self> {
+        Arc::new(Self {
+            name,
+            state: Mutex::new(WorkerState::Idle),
+            epoch: Instant::now(),
+            last_beat_ms: AtomicU64::new(0),
+            restarts: AtomicU32::new(0),
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Called by the worker from its loop to show it isn't stuck
+    pub fn beat(&self) {
+        let now_ms = self.epoch.elapsed().as_millis() as u64;
+        self.last_beat_ms.store(now_ms, Ordering::Relaxed);
+    }
+
+    /// Time since the last heartbeat
+    pub fn since_beat(&self) -> Duration {
+        let last = Duration::from_millis(self.last_beat_ms.load(Ordering::Relaxed));
+        self.epoch.elapsed().saturating_sub(last)
+    }
+
+    pub fn state(&self) -> WorkerState {
+        self.state
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
+    }
+
+    fn set_state(&self, state: WorkerState) {
+        match self.state.lock() {
+            Ok(mut s) => *s = state,
+            Err(poisoned) => *poisoned.into_inner() = state,
+        }
+    }
+
+    pub fn restarts(&self) -> u32 {
+        self.restarts.load(Ordering::Relaxed)
+    }
+
+    pub fn record_restart(&self) {
+        self.restarts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Running, but no heartbeat for `STALL_TIMEOUT`
+    pub fn is_stalled(&self) -> bool {
+        self.state() == WorkerState::Running && self.since_beat() >= STALL_TIMEOUT
+    }
+
+    /// One-line status for the debug panel
+    pub fn status_label(&self) -> String {
+        let restarts = match self.restarts() {
+            0 => String::new(),
+            n => format!(", {} restart{}", n, if n == 1 { "" } else { "s" }),
+        };
+        let state = match self.state() {
+            WorkerState::Idle => "idle".to_string(),
+            WorkerState::Running if self.is_stalled() => {
+                format!("stalled ({}s)", self.since_beat().as_secs())
+            }
+            WorkerState::Running => "running".to_string(),
+            WorkerState::Finished => "finished".to_string(),
+            WorkerState::Panicked(msg) => format!("panicked: {}", msg),
+        };
+        format!("{}{}", state, restarts)
+    }
+}
+
+/// Run a worker body, recording its state. A panic is caught and returned
+/// as its message instead of unwinding out of the thread.
+pub fn run_supervised<F: FnOnce()>(health: &WorkerHealth, body: F) -> Result<(), String> {
+    health.set_state(WorkerState::Running);
+    health.beat();
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(()) => {
+            health.set_state(WorkerState::Finished);
+            Ok(())
+        }
+        Err(payload) => {
+            let msg = panic_message(payload.as_ref());
+            health.set_state(WorkerState::Panicked(msg.clone()));
+            Err(msg)
+        }
+    }
+}
+
+/// Text of a panic payload caught by catch_unwind
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Exponential backoff between restarts of a worker that keeps panicking
+#[derive(Debug)]
+pub struct RestartBackoff {
+    delay: Duration,
+    /// When the worker died, None while it runs
+    died_at: Option<Instant>,
+    started_at: Instant,
+}
+
+impl Default for RestartBackoff {
+    fn default() -> Self {
+        Self {
+            delay: INITIAL_RESTART_DELAY,
+            died_at: None,
+            started_at: Instant::now(),
+        }
+    }
+}
+
+impl RestartBackoff {
+    /// The worker was found dead at `now`; starts the wait before the restart
+    pub fn died(&mut self, now: Instant) {
+        if self.died_at.is_some() {
+            return;
+        }
+        // A long healthy run means the previous panics are unrelated
+        if now.saturating_duration_since(self.started_at) >= STABLE_RUN {
+            self.delay = INITIAL_RESTART_DELAY;
+        }
+        self.died_at = Some(now);
+    }
+
+    /// Whether the worker may be restarted at `now`
+    pub fn ready(&self, now: Instant) -> bool {
+        self.died_at
+            .is_some_and(|died| now.saturating_duration_since(died) >= self.delay)
+    }
+
+    /// The worker was restarted at `now`: the next restart waits longer
+    pub fn restarted(&mut self, now: Instant) {
+        self.died_at = None;
+        self.started_at = now;
+        self.delay = (self.delay * 2).min(MAX_RESTART_DELAY);
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_supervised_catches_panic() {
+        let health = WorkerHealth::new("test");
+        assert_eq!(health.state(), WorkerState::Idle);
+
+        let result = run_supervised(&health, || panic!("socket exploded"));
+        assert_eq!(result, Err("socket exploded".to_string()));
+        assert_eq!(
+            health.state(),
+            WorkerState::Panicked("socket exploded".to_string())
+        );
+
+        let result = run_supervised(&health, || {});
+        assert_eq!(result, Ok(()));
+        assert_eq!(health.state(), WorkerState::Finished);
+    }
+
+    #[test]
+    fn test_panic_message_formats() {
+        let result = run_supervised(&WorkerHealth::new("test"), || {
+            panic!("flag {} unreadable", 42)
+        });
+        assert_eq!(result, Err("flag 42 unreadable".to_string()));
+        let payload: Box<dyn Any + Send> = Box::new(7u32);
+        assert_eq!(panic_message(payload.as_ref()), "unknown panic");
+    }
+
+    #[test]
+    fn test_fresh_heartbeat_not_stalled() {
+        let health = WorkerHealth::new("test");
+        run_supervised(&health, || {}).unwrap();
+        // Only running workers can stall
+        assert!(!health.is_stalled());
+        health.set_state(WorkerState::Running);
+        health.beat();
+        assert!(!health.is_stalled());
+        assert!(health.since_beat() < STALL_TIMEOUT);
+    }
+
+    #[test]
+    fn test_status_label() {
+        let health = WorkerHealth::new("websocket");
+        assert_eq!(health.status_label(), "idle");
+        let _ = run_supervised(&health, || panic!("boom"));
+        health.record_restart();
+        assert_eq!(health.status_label(), "panicked: boom, 1 restart");
+        health.record_restart();
+        health.set_state(WorkerState::Running);
+        assert_eq!(health.status_label(), "running, 2 restarts");
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let start = Instant::now();
+        let mut backoff = RestartBackoff::default();
+        assert!(!backoff.ready(start));
+
+        backoff.died(start);
+        assert!(!backoff.ready(start));
+        assert!(backoff.ready(start + INITIAL_RESTART_DELAY));
+        backoff.restarted(start + INITIAL_RESTART_DELAY);
+        assert_eq!(backoff.delay(), INITIAL_RESTART_DELAY * 2);
+
+        for _ in 0..10 {
+            backoff.died(start);
+            backoff.restarted(start);
+        }
+        assert_eq!(backoff.delay(), MAX_RESTART_DELAY);
+    }
+
+    #[test]
+    fn test_backoff_resets_after_stable_run() {
+        let start = Instant::now();
+        let mut backoff = RestartBackoff::default();
+        backoff.died(start);
+        backoff.restarted(start);
+        backoff.died(start);
+        backoff.restarted(start);
+        assert_eq!(backoff.delay(), INITIAL_RESTART_DELAY * 4);
+
+        backoff.died(start + STABLE_RUN);
+        assert_eq!(backoff.delay(), INITIAL_RESTART_DELAY);
+    }
+}
@@ -0,0 +1,223 @@
+//! Overlay color themes
+//!
+//! Status colors (discovered exits, participant status, gaps, connection)
+//! are resolved through a theme instead of being hardcoded per widget, so a
+//! colorblind-friendly palette applies to the whole overlay at once. Text and
+//! background colors still come from the `[overlay]` hex settings.
+
+use serde::{Deserialize, Serialize};
+
+type Rgba = [f32; 4];
+
+/// Theme picked with `overlay.theme`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeName {
+    #[default]
+    Default,
+    /// Saturated colors and pure white for unknown exits
+    HighContrast,
+    /// Blue / orange instead of green / red
+    Deuteranopia,
+    /// Like deuteranopia, with brighter warm colors (dark reds are hard to see)
+    Protanopia,
+}
+
+impl ThemeName {
+    pub const ALL: [ThemeName; 4] = [
+        ThemeName::Default,
+        ThemeName::HighContrast,
+        ThemeName::Deuteranopia,
+        ThemeName::Protanopia,
+    ];
+
+    /// Config value, as written in the TOML
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ThemeName::Default => "default",
+            ThemeName::HighContrast => "high_contrast",
+            ThemeName::Deuteranopia => "deuteranopia",
+            ThemeName::Protanopia => "protanopia",
+        }
+    }
+
+    /// Name shown in the settings panel
+    pub fn label(self) -> &'static str {
+        match self {
+            ThemeName::Default => "Default",
+            ThemeName::HighContrast => "High contrast",
+            ThemeName::Deuteranopia => "Deuteranopia (red-green)",
+            ThemeName::Protanopia => "Protanopia (red-green, dim reds)",
+        }
+    }
+}
+
+/// Resolved overlay colors, by meaning
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// Discovered exits, discovery toasts
+    pub discovered: Rgba,
+    /// "???" exits
+    pub undiscovered: Rgba,
+    /// Finished players, GO, connected dot
+    pub finished: Rgba,
+    /// Ready players, connecting dot
+    pub ready: Rgba,
+    /// Waiting for the start, countdown digits
+    pub waiting: Rgba,
+    /// Disconnected dot, outdated seed
+    pub error: Rgba,
+    /// Warning toasts
+    pub warning: Rgba,
+    /// Negative gap / PB delta
+    pub ahead: Rgba,
+    /// Positive gap / PB delta
+    pub behind: Rgba,
+    /// Local IGT
+    pub igt: Rgba,
+    /// Tier and layer progress
+    pub highlight: Rgba,
+    /// Connection and info toasts
+    pub notice: Rgba,
+    /// Key item toasts
+    pub item: Rgba,
+}
+
+impl Theme {
+    /// Colors of `name`. `text` is the configured text color, used for
+    /// undiscovered exits unless the theme overrides it.
+    pub fn resolve(name: ThemeName, text: Rgba) -> Self {
+        match name {
+            ThemeName::Default => Self {
+                discovered: [0.0, 1.0, 0.0, 1.0],
+                undiscovered: text,
+                finished: [0.0, 1.0, 0.0, 1.0],
+                ready: [1.0, 0.65, 0.0, 1.0],
+                waiting: [1.0, 0.75, 0.0, 1.0],
+                error: [1.0, 0.2, 0.2, 1.0],
+                warning: [1.0, 0.5, 0.0, 1.0],
+                ahead: [0.3, 0.9, 0.3, 1.0],
+                behind: [0.9, 0.35, 0.35, 1.0],
+                igt: [0.4, 0.6, 1.0, 1.0],
+                highlight: [1.0, 1.0, 0.0, 1.0],
+                notice: [1.0, 1.0, 0.0, 1.0],
+                item: [0.4, 0.8, 1.0, 1.0],
+            },
+            ThemeName::HighContrast => Self {
+                discovered: [0.2, 1.0, 0.2, 1.0],
+                undiscovered: [1.0, 1.0, 1.0, 1.0],
+                finished: [0.2, 1.0, 0.2, 1.0],
+                ready: [1.0, 0.6, 0.0, 1.0],
+                waiting: [1.0, 0.8, 0.0, 1.0],
+                error: [1.0, 0.1, 0.1, 1.0],
+                warning: [1.0, 0.5, 0.0, 1.0],
+                ahead: [0.2, 1.0, 0.2, 1.0],
+                behind: [1.0, 0.2, 0.2, 1.0],
+                igt: [0.3, 0.85, 1.0, 1.0],
+                highlight: [1.0, 1.0, 0.0, 1.0],
+                notice: [1.0, 1.0, 0.0, 1.0],
+                item: [0.3, 0.85, 1.0, 1.0],
+            },
+            // Okabe-Ito palette: good = sky blue, bad = vermillion / orange
+            ThemeName::Deuteranopia => Self {
+                discovered: [0.34, 0.71, 0.91, 1.0],
+                undiscovered: text,
+                finished: [0.34, 0.71, 0.91, 1.0],
+                ready: [0.90, 0.62, 0.0, 1.0],
+                waiting: [0.90, 0.62, 0.0, 1.0],
+                error: [0.84, 0.37, 0.0, 1.0],
+                warning: [0.90, 0.62, 0.0, 1.0],
+                ahead: [0.34, 0.71, 0.91, 1.0],
+                behind: [0.84, 0.37, 0.0, 1.0],
+                igt: [0.80, 0.47, 0.65, 1.0],
+                highlight: [0.94, 0.89, 0.26, 1.0],
+                notice: [0.94, 0.89, 0.26, 1.0],
+                item: [0.80, 0.47, 0.65, 1.0],
+            },
+            ThemeName::Protanopia => Self {
+                discovered: [0.34, 0.71, 0.91, 1.0],
+                undiscovered: text,
+                finished: [0.34, 0.71, 0.91, 1.0],
+                ready: [1.0, 0.70, 0.0, 1.0],
+                waiting: [1.0, 0.70, 0.0, 1.0],
+                error: [1.0, 0.55, 0.0, 1.0],
+                warning: [1.0, 0.70, 0.0, 1.0],
+                ahead: [0.34, 0.71, 0.91, 1.0],
+                behind: [1.0, 0.55, 0.0, 1.0],
+                igt: [0.75, 0.75, 0.95, 1.0],
+                highlight: [0.94, 0.89, 0.26, 1.0],
+                notice: [0.94, 0.89, 0.26, 1.0],
+                item: [0.75, 0.75, 0.95, 1.0],
+            },
+        }
+    }
+
+    /// Leaderboard color of a participant status (None = text color)
+    pub fn status_color(&self, status: &str) -> Option<Rgba> {
+        match status {
+            "finished" => Some(self.finished),
+            "ready" => Some(self.ready),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Rgba = [1.0, 1.0, 1.0, 1.0];
+
+    #[derive(Deserialize)]
+    struct Wrapper {
+        #[serde(default)]
+        theme: ThemeName,
+    }
+
+    #[test]
+    fn test_parse_theme() {
+        let parse = |s: &str| toml::from_str::<Wrapper>(s).unwrap().theme;
+        assert_eq!(parse(""), ThemeName::Default);
+        assert_eq!(parse("theme = \"high_contrast\""), ThemeName::HighContrast);
+        assert_eq!(parse("theme = \"protanopia\""), ThemeName::Protanopia);
+        assert!(toml::from_str::<Wrapper>("theme = \"neon\"").is_err());
+    }
+
+    #[test]
+    fn test_as_str_roundtrip() {
+        for name in ThemeName::ALL {
+            let parsed = toml::from_str::<Wrapper>(&format!("theme = \"{}\"", name.as_str()));
+            assert_eq!(parsed.unwrap().theme, name);
+        }
+    }
+
+    #[test]
+    fn test_undiscovered_follows_text_color() {
+        let gray = [0.7, 0.7, 0.7, 1.0];
+        assert_eq!(Theme::resolve(ThemeName::Default, gray).undiscovered, gray);
+        assert_eq!(
+            Theme::resolve(ThemeName::HighContrast, gray).undiscovered,
+            WHITE
+        );
+    }
+
+    #[test]
+    fn test_colorblind_themes_avoid_green_red() {
+        for name in [ThemeName::Deuteranopia, ThemeName::Protanopia] {
+            let theme = Theme::resolve(name, WHITE);
+            for color in [theme.discovered, theme.finished, theme.ahead] {
+                // Good outcomes lean blue rather than green
+                assert!(color[2] > color[1], "{:?}", name);
+            }
+            assert_ne!(theme.ahead, theme.behind);
+        }
+    }
+
+    #[test]
+    fn test_status_color() {
+        let theme = Theme::resolve(ThemeName::Default, WHITE);
+        assert_eq!(theme.status_color("finished"), Some(theme.finished));
+        assert_eq!(theme.status_color("ready"), Some(theme.ready));
+        assert_eq!(theme.status_color("playing"), None);
+    }
+}
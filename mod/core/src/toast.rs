@@ -0,0 +1,232 @@
+//! Toast notifications
+//!
+//! Queue of short-lived overlay messages (zone reveals, discoveries,
+//! warnings, connection changes) that fade in and out. Time is passed in
+//! explicitly so the animation can be tested without a clock.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Fade-in duration at the start of a toast
+const FADE_IN: Duration = Duration::from_millis(250);
+/// Fade-out duration at the end of a toast
+const FADE_OUT: Duration = Duration::from_millis(500);
+/// Oldest toasts are dropped beyond this many
+const MAX_TOASTS: usize = 4;
+
+/// Toast category, each with its own duration and config switch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToastKind {
+    /// Zone name revealed after a loading screen
+    Zone,
+    /// First visit of a zone acknowledged by the server
+    Discovery,
+    /// Something the player should act on (seed mismatch, auth error)
+    Warning,
+    /// Server connection changes
+    Connection,
+    /// Feedback on player actions (settings saved, summary written)
+    Info,
+    /// Key item picked up by the player or a rival
+    Item,
+    /// The player gained or lost a leaderboard position
+    Position,
+}
+
+impl ToastKind {
+    pub fn duration(self) -> Duration {
+        match self {
+            ToastKind::Zone => Duration::from_secs(3),
+            ToastKind::Discovery => Duration::from_secs(4),
+            ToastKind::Warning => Duration::from_secs(8),
+            ToastKind::Connection => Duration::from_secs(3),
+            ToastKind::Info => Duration::from_secs(3),
+            ToastKind::Item => Duration::from_secs(4),
+            ToastKind::Position => Duration::from_secs(4),
+        }
+    }
+}
+
+/// Which toast categories are shown (`[toasts]` in the config)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToastSettings {
+    #[serde(default = "default_true")]
+    pub zone: bool,
+    #[serde(default = "default_true")]
+    pub discovery: bool,
+    #[serde(default = "default_true")]
+    pub warning: bool,
+    #[serde(default = "default_true")]
+    pub connection: bool,
+    #[serde(default = "default_true")]
+    pub info: bool,
+    #[serde(default = "default_true")]
+    pub item: bool,
+    #[serde(default = "default_true")]
+    pub position: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for ToastSettings {
+    fn default() -> Self {
+        Self {
+            zone: true,
+            discovery: true,
+            warning: true,
+            connection: true,
+            info: true,
+            item: true,
+            position: true,
+        }
+    }
+}
+
+impl ToastSettings {
+    pub fn is_enabled(&self, kind: ToastKind) -> bool {
+        match kind {
+            ToastKind::Zone => self.zone,
+            ToastKind::Discovery => self.discovery,
+            ToastKind::Warning => self.warning,
+            ToastKind::Connection => self.connection,
+            ToastKind::Info => self.info,
+            ToastKind::Item => self.item,
+            ToastKind::Position => self.position,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toast {
+    pub kind: ToastKind,
+    pub text: String,
+    created: Instant,
+}
+
+impl Toast {
+    /// Opacity at `now`: ramps up over FADE_IN, down over the last FADE_OUT.
+    /// 0.0 once expired.
+    pub fn alpha(&self, now: Instant) -> f32 {
+        let age = now.saturating_duration_since(self.created);
+        let duration = self.kind.duration();
+        if age >= duration {
+            return 0.0;
+        }
+        let remaining = duration - age;
+        let fade_in = age.as_secs_f32() / FADE_IN.as_secs_f32();
+        let fade_out = remaining.as_secs_f32() / FADE_OUT.as_secs_f32();
+        fade_in.min(fade_out).min(1.0)
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.created) >= self.kind.duration()
+    }
+}
+
+/// Active toasts, oldest first
+#[derive(Debug, Default)]
+pub struct ToastQueue {
+    toasts: VecDeque<Toast>,
+}
+
+impl ToastQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a toast. A toast identical to the newest one restarts it instead
+    /// of stacking (e.g. repeated reconnection attempts).
+    pub fn push(&mut self, kind: ToastKind, text: String, now: Instant) {
+        if let Some(last) = self.toasts.back_mut() {
+            if last.kind == kind && last.text == text {
+                last.created = now;
+                return;
+            }
+        }
+        self.toasts.retain(|t| !t.is_expired(now));
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back(Toast {
+            kind,
+            text,
+            created: now,
+        });
+    }
+
+    /// Toasts still visible at `now`
+    pub fn active(&self, now: Instant) -> impl Iterator<Item = &Toast> {
+        self.toasts.iter().filter(move |t| !t.is_expired(now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alpha_fades_in_and_out() {
+        let t0 = Instant::now();
+        let mut queue = ToastQueue::new();
+        queue.push(ToastKind::Zone, "Stormveil".to_string(), t0);
+        let toast = queue.active(t0).next().unwrap().clone();
+
+        assert_eq!(toast.alpha(t0), 0.0);
+        assert!((toast.alpha(t0 + Duration::from_millis(125)) - 0.5).abs() < 1e-3);
+        assert_eq!(toast.alpha(t0 + Duration::from_secs(1)), 1.0);
+        // 250ms left out of a 500ms fade-out
+        assert!((toast.alpha(t0 + Duration::from_millis(2750)) - 0.5).abs() < 1e-3);
+        assert_eq!(toast.alpha(t0 + Duration::from_secs(3)), 0.0);
+    }
+
+    #[test]
+    fn test_expired_toasts_hidden() {
+        let t0 = Instant::now();
+        let mut queue = ToastQueue::new();
+        queue.push(ToastKind::Zone, "a".to_string(), t0);
+        queue.push(ToastKind::Warning, "b".to_string(), t0);
+
+        let later = t0 + Duration::from_secs(5);
+        let texts: Vec<_> = queue.active(later).map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["b"]);
+        assert_eq!(queue.active(t0 + Duration::from_secs(9)).count(), 0);
+    }
+
+    #[test]
+    fn test_duplicate_restarts_toast() {
+        let t0 = Instant::now();
+        let mut queue = ToastQueue::new();
+        queue.push(ToastKind::Connection, "Reconnecting".to_string(), t0);
+        queue.push(
+            ToastKind::Connection,
+            "Reconnecting".to_string(),
+            t0 + Duration::from_secs(2),
+        );
+        let active: Vec<_> = queue.active(t0 + Duration::from_secs(4)).collect();
+        assert_eq!(active.len(), 1);
+    }
+
+    #[test]
+    fn test_queue_is_bounded() {
+        let t0 = Instant::now();
+        let mut queue = ToastQueue::new();
+        for i in 0..MAX_TOASTS + 2 {
+            queue.push(ToastKind::Info, i.to_string(), t0);
+        }
+        let texts: Vec<_> = queue.active(t0).map(|t| t.text.clone()).collect();
+        assert_eq!(texts.len(), MAX_TOASTS);
+        assert_eq!(texts[0], "2");
+    }
+
+    #[test]
+    fn test_settings_disable_categories() {
+        let settings: ToastSettings = toml::from_str("zone = false").unwrap();
+        assert!(!settings.is_enabled(ToastKind::Zone));
+        assert!(settings.is_enabled(ToastKind::Discovery));
+        assert!(settings.is_enabled(ToastKind::Warning));
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! These traits define the interface for reading game state. The actual
 //! implementations live in the `platform` module and use Windows APIs.
-//! For testing, mock implementations can be provided.
+//! For testing, mock implementations can be provided; the `mock_game`
+//! feature has one driven by a scripted scenario (`mock_game::MockGame`).
 
 use super::types::PlayerPosition;
 
@@ -27,6 +28,29 @@ pub trait GameStateReader {
     fn read_animation(&self) -> Option<u32>;
 }
 
+// =============================================================================
+// EVENT FLAGS
+// =============================================================================
+
+/// Read EMEVD event flags (fog gate traversals, boss kills)
+pub trait EventFlagSource {
+    /// Whether `flag_id` is set, None when the flag can't be read
+    fn is_flag_set(&self, flag_id: u32) -> Option<bool>;
+}
+
+// =============================================================================
+// WARP DETECTOR
+// =============================================================================
+
+/// Destinations of the last warps, captured before the loading screen
+pub trait WarpDetector {
+    /// Grace the player fast traveled to, if any since the last clear
+    fn captured_grace_entity_id(&self) -> Option<u32>;
+
+    /// Forget the captured warps once the zone query used them
+    fn clear_captured_warps(&self);
+}
+
 // =============================================================================
 // TEST MOCKS
 // =============================================================================
@@ -0,0 +1,260 @@
+//! Transport to the race server
+//!
+//! The mod talks to the server over a WebSocket. Some networks (corporate
+//! proxies, strict firewalls) block the WebSocket upgrade, so after a few
+//! handshakes fail in a row the mod falls back to HTTP long-polling: the same
+//! messages, posted in batches and fetched by polls the server holds open.
+//! It's slower, so the WebSocket is tried again from time to time. Long-poll
+//! sessions always use JSON.
+//!
+//! `[server] transport` forces either one instead.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// WebSocket handshakes failing in a row before falling back to long-polling
+pub const FALLBACK_AFTER_FAILURES: u32 = 3;
+
+/// Time on long-polling before the WebSocket is tried again
+pub const WEBSOCKET_RETRY_INTERVAL: Duration = Duration::from_secs(600);
+
+/// How long the server may hold a poll open
+pub const POLL_WAIT: Duration = Duration::from_secs(25);
+
+/// `[server] transport` setting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransportMode {
+    /// WebSocket, falling back to long-polling when it's blocked
+    #[default]
+    Auto,
+    #[serde(rename = "websocket")]
+    WebSocket,
+    LongPoll,
+}
+
+/// Transport of one connection attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    WebSocket,
+    LongPoll,
+}
+
+/// Picks the transport of each connection attempt
+#[derive(Debug)]
+pub struct TransportSelector {
+    mode: TransportMode,
+    /// WebSocket handshakes failed in a row
+    failures: u32,
+    /// When the long-poll fallback started (or the WebSocket last failed
+    /// again), None while on the WebSocket
+    fell_back_at: Option<Instant>,
+}
+
+impl TransportSelector {
+    pub fn new(mode: TransportMode) -> Self {
+        Self {
+            mode,
+            failures: 0,
+            fell_back_at: None,
+        }
+    }
+
+    /// Transport to use for the next connection attempt
+    pub fn next(&self, now: Instant) -> Transport {
+        match self.mode {
+            TransportMode::WebSocket => Transport::WebSocket,
+            TransportMode::LongPoll => Transport::LongPoll,
+            TransportMode::Auto => match self.fell_back_at {
+                Some(since) if now.duration_since(since) < WEBSOCKET_RETRY_INTERVAL => {
+                    Transport::LongPoll
+                }
+                _ => Transport::WebSocket,
+            },
+        }
+    }
+
+    /// The transport couldn't be opened (not an auth error). Returns true
+    /// when this switched to long-polling.
+    pub fn handshake_failed(&mut self, transport: Transport, now: Instant) -> bool {
+        if self.mode != TransportMode::Auto || transport != Transport::WebSocket {
+            return false;
+        }
+        self.failures += 1;
+        if self.failures < FALLBACK_AFTER_FAILURES {
+            return false;
+        }
+        let switched = self.fell_back_at.is_none();
+        self.fell_back_at = Some(now);
+        switched
+    }
+
+    /// The transport was opened
+    pub fn connected(&mut self, transport: Transport) {
+        if transport == Transport::WebSocket {
+            self.failures = 0;
+            self.fell_back_at = None;
+        }
+    }
+
+    /// Whether the connections currently go over long-polling
+    pub fn is_fallback(&self) -> bool {
+        self.fell_back_at.is_some()
+    }
+}
+
+/// Server URL of a transport: `wss://host/ws/mod/{id}` for the WebSocket,
+/// `https://host/poll/mod/{id}` to open a long-poll session. `base` may use
+/// either scheme.
+pub fn endpoint_url(base: &str, transport: Transport, training: bool, id: &str) -> String {
+    let prefix = match transport {
+        Transport::WebSocket => "ws",
+        Transport::LongPoll => "poll",
+    };
+    let endpoint = if training { "training" } else { "mod" };
+    let base = with_scheme(base, transport);
+    format!("{}/{}/{}/{}", base, prefix, endpoint, id)
+}
+
+/// URL of an open long-poll session: POST to send, GET to poll, DELETE to close
+pub fn session_url(base: &str, session_id: &str) -> String {
+    format!(
+        "{}/poll/session/{}",
+        with_scheme(base, Transport::LongPoll),
+        session_id
+    )
+}
+
+/// `base` without its trailing slash, with the scheme of `transport`
+fn with_scheme(base: &str, transport: Transport) -> String {
+    let base = base.trim_end_matches('/');
+    let schemes = match transport {
+        Transport::WebSocket => [("https://", "wss://"), ("http://", "ws://")],
+        Transport::LongPoll => [("wss://", "https://"), ("ws://", "http://")],
+    };
+    for (from, to) in schemes {
+        if let Some(rest) = base.strip_prefix(from) {
+            return format!("{}{}", to, rest);
+        }
+    }
+    base.to_string()
+}
+
+/// Answer to opening a long-poll session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LongPollOpened {
+    pub session_id: String,
+}
+
+/// Messages posted to a session, each one a JSON client message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LongPollSend {
+    pub messages: Vec<String>,
+}
+
+/// Answer to a poll: server messages in order, and whether the server closed
+/// the session (after the last message)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LongPollBatch {
+    pub messages: Vec<String>,
+    pub closed: bool,
+    #[serde(default)]
+    pub close_reason: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_falls_back_after_failures() {
+        let now = Instant::now();
+        let mut selector = TransportSelector::new(TransportMode::Auto);
+        assert_eq!(selector.next(now), Transport::WebSocket);
+        assert!(!selector.handshake_failed(Transport::WebSocket, now));
+        assert!(!selector.handshake_failed(Transport::WebSocket, now));
+        assert!(selector.handshake_failed(Transport::WebSocket, now));
+        assert!(selector.is_fallback());
+        assert_eq!(selector.next(now), Transport::LongPoll);
+
+        // Long-poll failures don't count
+        assert!(!selector.handshake_failed(Transport::LongPoll, now));
+        selector.connected(Transport::LongPoll);
+        assert_eq!(selector.next(now), Transport::LongPoll);
+    }
+
+    #[test]
+    fn test_websocket_retried() {
+        let start = Instant::now();
+        let mut selector = TransportSelector::new(TransportMode::Auto);
+        for _ in 0..FALLBACK_AFTER_FAILURES {
+            selector.handshake_failed(Transport::WebSocket, start);
+        }
+        let later = start + WEBSOCKET_RETRY_INTERVAL;
+        assert_eq!(selector.next(later), Transport::WebSocket);
+
+        // Still blocked: back to long-polling for another interval
+        assert!(!selector.handshake_failed(Transport::WebSocket, later));
+        assert_eq!(selector.next(later), Transport::LongPoll);
+
+        // Unblocked: the WebSocket is kept
+        let much_later = later + WEBSOCKET_RETRY_INTERVAL;
+        selector.connected(selector.next(much_later));
+        assert!(!selector.is_fallback());
+        assert_eq!(selector.next(much_later), Transport::WebSocket);
+        assert!(!selector.handshake_failed(Transport::WebSocket, much_later));
+    }
+
+    #[test]
+    fn test_forced_modes() {
+        let now = Instant::now();
+        let mut selector = TransportSelector::new(TransportMode::WebSocket);
+        for _ in 0..10 {
+            assert!(!selector.handshake_failed(Transport::WebSocket, now));
+        }
+        assert_eq!(selector.next(now), Transport::WebSocket);
+        let selector = TransportSelector::new(TransportMode::LongPoll);
+        assert_eq!(selector.next(now), Transport::LongPoll);
+
+        let mode: TransportMode = serde_json::from_str(r#""long_poll""#).unwrap();
+        assert_eq!(mode, TransportMode::LongPoll);
+        let mode: TransportMode = serde_json::from_str(r#""websocket""#).unwrap();
+        assert_eq!(mode, TransportMode::WebSocket);
+    }
+
+    #[test]
+    fn test_endpoint_urls() {
+        let id = "abc";
+        assert_eq!(
+            endpoint_url("https://race.example.com/", Transport::WebSocket, false, id),
+            "wss://race.example.com/ws/mod/abc"
+        );
+        assert_eq!(
+            endpoint_url("http://localhost:8000", Transport::WebSocket, true, id),
+            "ws://localhost:8000/ws/training/abc"
+        );
+        assert_eq!(
+            endpoint_url("wss://race.example.com", Transport::LongPoll, false, id),
+            "https://race.example.com/poll/mod/abc"
+        );
+        assert_eq!(
+            endpoint_url("https://race.example.com", Transport::LongPoll, true, id),
+            "https://race.example.com/poll/training/abc"
+        );
+        assert_eq!(
+            session_url("wss://race.example.com/", "s1"),
+            "https://race.example.com/poll/session/s1"
+        );
+    }
+
+    #[test]
+    fn test_batch_parsing() {
+        let batch: LongPollBatch =
+            serde_json::from_str(r#"{"messages": ["{\"type\":\"ping\"}"], "closed": false}"#)
+                .unwrap();
+        assert_eq!(batch.messages, vec![r#"{"type":"ping"}"#]);
+        assert!(!batch.closed);
+        assert_eq!(batch.close_reason, None);
+    }
+}
@@ -0,0 +1,60 @@
+//! Resolution-based overlay scaling
+//!
+//! Sizes in the config (font size, pixel margins, map size) are meant for a
+//! 1080p screen. With `overlay.auto_scale` they follow the height of the game's
+//! swapchain, so the overlay covers the same share of the screen at 1440p or 4K
+//! and on ultrawide monitors. `overlay.scale` multiplies on top of it.
+
+/// Display height the configured sizes are meant for
+pub const REFERENCE_HEIGHT: f32 = 1080.0;
+
+/// Bounds of the combined factor, so a bogus display size or multiplier
+/// can't make the overlay vanish or cover the screen
+const MIN_SCALE: f32 = 0.5;
+const MAX_SCALE: f32 = 4.0;
+
+/// Factor applied to the configured sizes for this frame.
+///
+/// The display height is in the same pixels ImGui draws in, so desktop DPI
+/// settings don't change the overlay's share of the screen.
+pub fn overlay_scale(display_size: [f32; 2], auto_scale: bool, multiplier: f32) -> f32 {
+    let height = display_size[1];
+    let auto = if auto_scale && height > 0.0 {
+        height / REFERENCE_HEIGHT
+    } else {
+        1.0
+    };
+    let multiplier = if multiplier > 0.0 { multiplier } else { 1.0 };
+    (auto * multiplier).clamp(MIN_SCALE, MAX_SCALE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_follows_display_height() {
+        assert_eq!(overlay_scale([1920.0, 1080.0], true, 1.0), 1.0);
+        assert_eq!(overlay_scale([3840.0, 2160.0], true, 1.0), 2.0);
+        // Ultrawide 1440p: the height decides
+        assert!((overlay_scale([3440.0, 1440.0], true, 1.0) - 1.333).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_multiplier() {
+        assert_eq!(overlay_scale([3840.0, 2160.0], true, 0.75), 1.5);
+        assert_eq!(overlay_scale([3840.0, 2160.0], false, 1.25), 1.25);
+        // Invalid multipliers are ignored
+        assert_eq!(overlay_scale([1920.0, 1080.0], true, 0.0), 1.0);
+        assert_eq!(overlay_scale([1920.0, 1080.0], true, f32::NAN), 1.0);
+    }
+
+    #[test]
+    fn test_disabled_or_unknown_display() {
+        assert_eq!(overlay_scale([3840.0, 2160.0], false, 1.0), 1.0);
+        // Display size not reported yet on the first frames
+        assert_eq!(overlay_scale([0.0, 0.0], true, 1.0), 1.0);
+        assert_eq!(overlay_scale([100.0, 100.0], true, 1.0), MIN_SCALE);
+        assert_eq!(overlay_scale([15360.0, 8640.0], true, 1.0), MAX_SCALE);
+    }
+}
@@ -0,0 +1,305 @@
+//! Warmup lobby before the race start
+//!
+//! Between joining a race and its start the player may roam freely. Fog
+//! gates crossed during the warmup aren't race progress, so while the lobby
+//! holds the tracker neither records nor sends event flags. With
+//! `require_start`, the race only begins for the player once they are back
+//! at the start: the starting grace's position when the seed provides it,
+//! else the start zone the server resolves. Until then flags stay
+//! suppressed and a banner asks them to return.
+
+use serde::{Deserialize, Serialize};
+
+use super::protocol::StartLocation;
+use super::types::PlayerPosition;
+
+/// `[warmup]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WarmupSettings {
+    /// Ignore event flags until the race starts (free roam before the start)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Hold the race until the player is back at the start
+    #[serde(default)]
+    pub require_start: bool,
+    /// Distance (in meters) from the starting grace that counts as at the start
+    #[serde(default = "default_start_radius")]
+    pub start_radius: f32,
+}
+
+fn default_true() -> bool {
+    true
+}
+fn default_start_radius() -> f32 {
+    15.0
+}
+
+impl Default for WarmupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            require_start: false,
+            start_radius: default_start_radius(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LobbyState {
+    /// Before the start: roaming, flags ignored
+    Warmup,
+    /// The race started with the player away from the start: flags ignored
+    /// until they are back
+    AwayFromStart,
+    Racing,
+}
+
+/// State change worth telling the player about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LobbyEvent {
+    /// The race started and the player is racing
+    Started,
+    /// The race started while the player was elsewhere
+    AwayFromStart,
+    /// Back at the start after an `AwayFromStart`
+    BackAtStart,
+}
+
+/// Whether the player is at the start, None while unknown (loading screen,
+/// zone not resolved yet). Checked by position when the seed locates the
+/// starting grace, else by the zone the server resolved.
+pub fn is_at_start(
+    start: &StartLocation,
+    position: Option<&PlayerPosition>,
+    zone_node_id: Option<&str>,
+    radius: f32,
+) -> Option<bool> {
+    if let (Some(map_id), Some(grace)) = (start.map_id.as_deref(), start.position) {
+        let pos = position?;
+        let [x, y, z] = grace;
+        let (dx, dy, dz) = (pos.x - x, pos.y - y, pos.z - z);
+        return Some(pos.map_id_str == map_id && (dx * dx + dy * dy + dz * dz).sqrt() <= radius);
+    }
+    zone_node_id.map(|zone| zone == start.node_id)
+}
+
+/// Lobby state machine of the local player
+#[derive(Debug)]
+pub struct Lobby {
+    state: LobbyState,
+    /// Last known answer of `is_at_start`
+    at_start: Option<bool>,
+    /// The race was seen before its start. Joined after it (late join, game
+    /// restarted mid-race), the player races from where they are.
+    saw_warmup: bool,
+}
+
+impl Default for Lobby {
+    fn default() -> Self {
+        Self {
+            state: LobbyState::Warmup,
+            at_start: None,
+            saw_warmup: false,
+        }
+    }
+}
+
+impl Lobby {
+    pub fn state(&self) -> LobbyState {
+        self.state
+    }
+
+    /// Race flags are ignored (not recorded, not sent)
+    pub fn suppresses_flags(&self) -> bool {
+        self.state != LobbyState::Racing
+    }
+
+    /// Last known location check: Some(false) shows the "return to start"
+    /// warning, also during the countdown
+    pub fn at_start(&self) -> Option<bool> {
+        self.at_start
+    }
+
+    /// Feed each frame of a joined race whether it has started (start time
+    /// reached) and whether the player is at the start (`is_at_start`).
+    pub fn observe(
+        &mut self,
+        started: bool,
+        at_start: Option<bool>,
+        settings: &WarmupSettings,
+    ) -> Option<LobbyEvent> {
+        if at_start.is_some() {
+            self.at_start = at_start;
+        }
+        if !settings.enabled {
+            self.state = LobbyState::Racing;
+            return None;
+        }
+        if !started {
+            self.state = LobbyState::Warmup;
+            self.saw_warmup = true;
+            return None;
+        }
+        if !self.saw_warmup {
+            self.state = LobbyState::Racing;
+            return None;
+        }
+        let confirmed = !settings.require_start || self.at_start == Some(true);
+        match self.state {
+            LobbyState::Racing => None,
+            LobbyState::Warmup if confirmed => {
+                self.state = LobbyState::Racing;
+                Some(LobbyEvent::Started)
+            }
+            // Wait for a location rather than warn during a loading screen
+            LobbyState::Warmup if self.at_start.is_some() => {
+                self.state = LobbyState::AwayFromStart;
+                Some(LobbyEvent::AwayFromStart)
+            }
+            LobbyState::Warmup => None,
+            LobbyState::AwayFromStart if confirmed => {
+                self.state = LobbyState::Racing;
+                Some(LobbyEvent::BackAtStart)
+            }
+            LobbyState::AwayFromStart => None,
+        }
+    }
+
+    /// Back to the warmup (new race, new seed)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start(located: bool) -> StartLocation {
+        StartLocation {
+            node_id: "chapel_start".to_string(),
+            map_id: located.then(|| "m10_01_00_00".to_string()),
+            position: located.then_some([10.0, 0.0, 0.0]),
+        }
+    }
+
+    fn require_start() -> WarmupSettings {
+        WarmupSettings {
+            require_start: true,
+            ..WarmupSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_at_start_by_position() {
+        let start = start(true);
+        let near = PlayerPosition::new(0x0A010000, 20.0, 0.0, 0.0, None);
+        let far = PlayerPosition::new(0x0A010000, 30.0, 0.0, 0.0, None);
+        let other_map = PlayerPosition::new(0x0A000000, 10.0, 0.0, 0.0, None);
+        assert_eq!(is_at_start(&start, Some(&near), None, 15.0), Some(true));
+        assert_eq!(is_at_start(&start, Some(&far), None, 15.0), Some(false));
+        assert_eq!(
+            is_at_start(&start, Some(&other_map), None, 15.0),
+            Some(false)
+        );
+        // The position wins over the zone
+        assert_eq!(is_at_start(&start, None, Some("chapel_start"), 15.0), None);
+    }
+
+    #[test]
+    fn test_at_start_by_zone() {
+        let start = start(false);
+        let pos = PlayerPosition::new(0x0A010000, 10.0, 0.0, 0.0, None);
+        assert_eq!(
+            is_at_start(&start, Some(&pos), Some("chapel_start"), 15.0),
+            Some(true)
+        );
+        assert_eq!(
+            is_at_start(&start, Some(&pos), Some("stormveil"), 15.0),
+            Some(false)
+        );
+        assert_eq!(is_at_start(&start, Some(&pos), None, 15.0), None);
+    }
+
+    #[test]
+    fn test_flags_suppressed_until_start() {
+        let settings = WarmupSettings::default();
+        let mut lobby = Lobby::default();
+        assert_eq!(lobby.observe(false, Some(false), &settings), None);
+        assert!(lobby.suppresses_flags());
+        // Not required at the start: racing wherever the player is
+        assert_eq!(
+            lobby.observe(true, Some(false), &settings),
+            Some(LobbyEvent::Started)
+        );
+        assert!(!lobby.suppresses_flags());
+        assert_eq!(lobby.observe(true, Some(false), &settings), None);
+        assert_eq!(lobby.state(), LobbyState::Racing);
+    }
+
+    #[test]
+    fn test_away_from_start_until_back() {
+        let settings = require_start();
+        let mut lobby = Lobby::default();
+        lobby.observe(false, Some(false), &settings);
+        assert_eq!(lobby.at_start(), Some(false));
+        assert_eq!(
+            lobby.observe(true, None, &settings),
+            Some(LobbyEvent::AwayFromStart)
+        );
+        assert!(lobby.suppresses_flags());
+        assert_eq!(lobby.observe(true, Some(false), &settings), None);
+        assert_eq!(
+            lobby.observe(true, Some(true), &settings),
+            Some(LobbyEvent::BackAtStart)
+        );
+        assert!(!lobby.suppresses_flags());
+        // Leaving the start once racing is the race itself
+        assert_eq!(lobby.observe(true, Some(false), &settings), None);
+    }
+
+    #[test]
+    fn test_joined_after_start() {
+        let settings = require_start();
+        let mut lobby = Lobby::default();
+        assert_eq!(lobby.observe(true, Some(false), &settings), None);
+        assert_eq!(lobby.state(), LobbyState::Racing);
+    }
+
+    #[test]
+    fn test_unknown_location_waits() {
+        let settings = require_start();
+        let mut lobby = Lobby::default();
+        lobby.observe(false, None, &settings);
+        assert_eq!(lobby.observe(true, None, &settings), None);
+        assert_eq!(lobby.state(), LobbyState::Warmup);
+        assert_eq!(
+            lobby.observe(true, Some(true), &settings),
+            Some(LobbyEvent::Started)
+        );
+    }
+
+    #[test]
+    fn test_disabled_never_suppresses() {
+        let settings = WarmupSettings {
+            enabled: false,
+            require_start: true,
+            ..WarmupSettings::default()
+        };
+        let mut lobby = Lobby::default();
+        assert_eq!(lobby.observe(false, Some(false), &settings), None);
+        assert!(!lobby.suppresses_flags());
+        lobby.reset();
+        assert_eq!(lobby.state(), LobbyState::Warmup);
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings: WarmupSettings = toml::from_str("").unwrap();
+        assert_eq!(settings, WarmupSettings::default());
+        let settings: WarmupSettings = toml::from_str("require_start = true").unwrap();
+        assert!(settings.enabled);
+        assert!(settings.require_start);
+        assert_eq!(settings.start_radius, 15.0);
+    }
+}
@@ -0,0 +1,245 @@
+//! Overlay window layout
+//!
+//! The main overlay sits at its anchor; other windows (the message trace)
+//! open at a default spot moved off the windows already on screen. In
+//! interactive mode windows can be dragged: their positions are saved per
+//! resolution in the `[layout]` section of the config and win over the
+//! defaults on that resolution.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+/// Top-left corner, in pixels
+pub type Pos = [f32; 2];
+
+/// Gap kept between a moved window and the one it avoids
+pub const MARGIN: f32 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowId {
+    Overlay,
+    MessageTrace,
+    RaceTimer,
+}
+
+impl WindowId {
+    /// Key in the `[layout]` section
+    pub fn key(self) -> &'static str {
+        match self {
+            WindowId::Overlay => "overlay",
+            WindowId::MessageTrace => "message_trace",
+            WindowId::RaceTimer => "race_timer",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub pos: Pos,
+    pub size: [f32; 2],
+}
+
+impl Rect {
+    fn overlaps(&self, other: &Rect) -> bool {
+        self.pos[0] < other.pos[0] + other.size[0]
+            && other.pos[0] < self.pos[0] + self.size[0]
+            && self.pos[1] < other.pos[1] + other.size[1]
+            && other.pos[1] < self.pos[1] + self.size[1]
+    }
+}
+
+/// Keep a window of `size` on screen (top-left wins when it doesn't fit)
+fn clamp_to_display(pos: Pos, size: [f32; 2], display: [f32; 2]) -> Pos {
+    [
+        pos[0].min(display[0] - size[0]).max(0.0),
+        pos[1].min(display[1] - size[1]).max(0.0),
+    ]
+}
+
+/// Position closest to `preferred` where the window overlaps none of
+/// `others`: as is, or moved next to (below, above, left or right of) one of
+/// them. `preferred` when nothing fits on screen.
+pub fn avoid_overlap(display: [f32; 2], preferred: Rect, others: &[Rect]) -> Pos {
+    let size = preferred.size;
+    let start = clamp_to_display(preferred.pos, size, display);
+    let fits = |pos: Pos| others.iter().all(|o| !Rect { pos, size }.overlaps(o));
+    if fits(start) {
+        return start;
+    }
+    let distance = |pos: &Pos| (pos[0] - start[0]).powi(2) + (pos[1] - start[1]).powi(2);
+    others
+        .iter()
+        .flat_map(|o| {
+            [
+                [start[0], o.pos[1] + o.size[1] + MARGIN],
+                [start[0], o.pos[1] - size[1] - MARGIN],
+                [o.pos[0] - size[0] - MARGIN, start[1]],
+                [o.pos[0] + o.size[0] + MARGIN, start[1]],
+            ]
+        })
+        .map(|pos| clamp_to_display(pos, size, display))
+        .filter(|&pos| fits(pos))
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+        .unwrap_or(start)
+}
+
+/// `[layout]` section of the config: resolution ("1920x1080") → window key →
+/// saved position
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SavedLayouts(pub BTreeMap<String, BTreeMap<String, Pos>>);
+
+impl SavedLayouts {
+    pub fn resolution_key(display: [f32; 2]) -> String {
+        format!("{}x{}", display[0].round(), display[1].round())
+    }
+
+    pub fn get(&self, display: [f32; 2], id: WindowId) -> Option<Pos> {
+        self.0
+            .get(&Self::resolution_key(display))?
+            .get(id.key())
+            .copied()
+    }
+
+    pub fn set(&mut self, display: [f32; 2], id: WindowId, pos: Pos) {
+        self.0
+            .entry(Self::resolution_key(display))
+            .or_default()
+            .insert(id.key().to_string(), [pos[0].round(), pos[1].round()]);
+    }
+}
+
+/// Where the windows were last frame, and which ones the player dragged
+#[derive(Debug, Default)]
+pub struct WindowLayout {
+    display: [f32; 2],
+    rects: HashMap<WindowId, Rect>,
+    /// Windows positioned since the display size last changed
+    placed: HashSet<WindowId>,
+    /// Windows dragged since the last `take_moved`
+    moved: HashSet<WindowId>,
+}
+
+impl WindowLayout {
+    /// Start of a frame. A new display size places every window again.
+    pub fn set_display(&mut self, display: [f32; 2]) {
+        if display != self.display {
+            self.display = display;
+            self.rects.clear();
+            self.placed.clear();
+        }
+    }
+
+    /// Position to force on a window this frame, None to leave it where it
+    /// is (possibly dragged). Each window is placed once per display size:
+    /// at its saved position, otherwise at `preferred` moved off the other
+    /// windows.
+    pub fn placement(&mut self, id: WindowId, saved: Option<Pos>, preferred: Rect) -> Option<Pos> {
+        if !self.placed.insert(id) {
+            return None;
+        }
+        let previous = self.rects.remove(&id);
+        if saved.is_some() {
+            return saved;
+        }
+        let preferred = Rect {
+            size: previous.map_or(preferred.size, |r| r.size),
+            ..preferred
+        };
+        let others: Vec<Rect> = self.rects.values().copied().collect();
+        Some(avoid_overlap(self.display, preferred, &others))
+    }
+
+    /// Where a window was drawn this frame. A position change while the
+    /// player can drag windows is remembered for saving.
+    pub fn record(&mut self, id: WindowId, rect: Rect, draggable: bool) {
+        if let Some(previous) = self.rects.insert(id, rect) {
+            if draggable && previous.pos != rect.pos {
+                self.moved.insert(id);
+            }
+        }
+    }
+
+    /// Windows dragged since the last call, with their position
+    pub fn take_moved(&mut self) -> Vec<(WindowId, Pos)> {
+        self.moved
+            .drain()
+            .filter_map(|id| Some((id, self.rects.get(&id)?.pos)))
+            .collect()
+    }
+
+    pub fn display(&self) -> [f32; 2] {
+        self.display
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISPLAY: [f32; 2] = [1920.0, 1080.0];
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Rect {
+        Rect {
+            pos: [x, y],
+            size: [w, h],
+        }
+    }
+
+    #[test]
+    fn test_avoid_overlap() {
+        let overlay = rect(1580.0, 20.0, 320.0, 200.0);
+        // Free spot: kept
+        let free = rect(20.0, 20.0, 560.0, 320.0);
+        assert_eq!(avoid_overlap(DISPLAY, free, &[overlay]), [20.0, 20.0]);
+        // Top-right default over the overlay: moved just below it
+        let trace = rect(1340.0, 20.0, 560.0, 320.0);
+        assert_eq!(avoid_overlap(DISPLAY, trace, &[overlay]), [1340.0, 228.0]);
+        // Off screen: clamped
+        let off = rect(1800.0, 1000.0, 560.0, 320.0);
+        assert_eq!(avoid_overlap(DISPLAY, off, &[]), [1360.0, 760.0]);
+    }
+
+    #[test]
+    fn test_saved_layouts() {
+        let mut saved = SavedLayouts::default();
+        saved.set(DISPLAY, WindowId::Overlay, [12.4, 30.6]);
+        assert_eq!(saved.get(DISPLAY, WindowId::Overlay), Some([12.0, 31.0]));
+        assert_eq!(saved.get([2560.0, 1440.0], WindowId::Overlay), None);
+        assert_eq!(saved.get(DISPLAY, WindowId::MessageTrace), None);
+        assert!(saved.0.contains_key("1920x1080"));
+    }
+
+    #[test]
+    fn test_placement_once_per_display() {
+        let mut layout = WindowLayout::default();
+        layout.set_display(DISPLAY);
+        layout.record(WindowId::Overlay, rect(1580.0, 20.0, 320.0, 200.0), false);
+        let trace = rect(1340.0, 20.0, 560.0, 320.0);
+        assert_eq!(
+            layout.placement(WindowId::MessageTrace, None, trace),
+            Some([1340.0, 228.0])
+        );
+        assert_eq!(layout.placement(WindowId::MessageTrace, None, trace), None);
+        // Saved positions win
+        layout.set_display([2560.0, 1440.0]);
+        assert_eq!(
+            layout.placement(WindowId::MessageTrace, Some([5.0, 5.0]), trace),
+            Some([5.0, 5.0])
+        );
+    }
+
+    #[test]
+    fn test_drag_recorded() {
+        let mut layout = WindowLayout::default();
+        layout.set_display(DISPLAY);
+        layout.record(WindowId::Overlay, rect(10.0, 10.0, 100.0, 100.0), true);
+        // Moved while not draggable (anchor, resize): ignored
+        layout.record(WindowId::Overlay, rect(20.0, 10.0, 100.0, 100.0), false);
+        assert!(layout.take_moved().is_empty());
+        layout.record(WindowId::Overlay, rect(50.0, 60.0, 100.0, 100.0), true);
+        assert_eq!(layout.take_moved(), vec![(WindowId::Overlay, [50.0, 60.0])]);
+        assert!(layout.take_moved().is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+//! World-map thumbnail calibration
+//!
+//! Places the player on a map image of their overworld area. The calibration
+//! table gives, for each area, the ground its texture covers; the bundled
+//! table matches the in-game map screen and a `worldmap.toml` next to the DLL
+//! replaces it for custom images.
+
+use serde::{Deserialize, Serialize};
+
+use super::map_utils::overworld_position;
+
+const BUILTIN_TABLE: &str = include_str!("../assets/worldmap.toml");
+
+/// `[world_map]` section of the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldMapSettings {
+    /// Allow the thumbnail (toggled in game with the toggle_map hotkey)
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Show the thumbnail from the start instead of waiting for the hotkey
+    #[serde(default)]
+    pub show_on_start: bool,
+    /// Thumbnail edge length in pixels at 1080p (scaled with the overlay)
+    #[serde(default = "default_size")]
+    pub size: f32,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+fn default_size() -> f32 {
+    160.0
+}
+
+impl Default for WorldMapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            show_on_start: false,
+            size: default_size(),
+        }
+    }
+}
+
+/// Ground covered by one area's map texture, in meters on the area grid
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MapCalibration {
+    /// Overworld area (60, 61)
+    pub area: u8,
+    /// Image file next to the DLL
+    pub texture: String,
+    pub west: f32,
+    pub east: f32,
+    pub south: f32,
+    pub north: f32,
+}
+
+impl MapCalibration {
+    /// Texture coordinates of a grid position (north up), or None when it
+    /// falls outside the image
+    pub fn uv(&self, east: f32, north: f32) -> Option<[f32; 2]> {
+        let u = (east - self.west) / (self.east - self.west);
+        let v = (self.north - north) / (self.north - self.south);
+        ((0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v)).then_some([u, v])
+    }
+}
+
+/// Calibration of every known overworld area
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct WorldMapTable {
+    #[serde(default)]
+    pub areas: Vec<MapCalibration>,
+}
+
+impl WorldMapTable {
+    pub const FILENAME: &'static str = "worldmap.toml";
+
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let table: WorldMapTable = toml::from_str(contents)
+            .map_err(|e| format!("Failed to parse world map table: {}", e))?;
+        for c in &table.areas {
+            if c.west >= c.east || c.south >= c.north {
+                return Err(format!("Area {} has empty map bounds", c.area));
+            }
+        }
+        Ok(table)
+    }
+
+    /// The table bundled with the mod
+    pub fn builtin() -> Self {
+        Self::parse(BUILTIN_TABLE).expect("bundled worldmap.toml is valid")
+    }
+
+    /// Calibration of the area the player stands in, with their texture
+    /// coordinates. None off the overworld or outside the calibrated image.
+    pub fn locate(&self, map_id: u32, x: f32, z: f32) -> Option<(&MapCalibration, [f32; 2])> {
+        let (area, east, north) = overworld_position(map_id, x, z)?;
+        let calibration = self.areas.iter().find(|c| c.area == area)?;
+        Some((calibration, calibration.uv(east, north)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> WorldMapTable {
+        WorldMapTable::parse(
+            r#"
+            [[areas]]
+            area = 60
+            texture = "m60.png"
+            west = 0.0
+            east = 1024.0
+            south = 0.0
+            north = 512.0
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_builtin_table() {
+        let table = WorldMapTable::builtin();
+        assert!(table.areas.iter().any(|c| c.area == 60));
+        assert!(table.areas.iter().any(|c| c.area == 61));
+        // Limgrave
+        assert!(table.locate(0x3C2A2600, 100.0, 100.0).is_some());
+    }
+
+    #[test]
+    fn test_locate_north_up() {
+        let table = table();
+        // m60_01_01_00 starts at (256, 256): 256m further east is the centre
+        let (c, uv) = table.locate(0x3C010100, 256.0, 0.0).unwrap();
+        assert_eq!(c.texture, "m60.png");
+        assert_eq!(uv, [0.5, 0.5]);
+        // South-west corner is the bottom-left of the image
+        assert_eq!(table.locate(0x3C000000, 0.0, 0.0).unwrap().1, [0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_locate_outside() {
+        let table = table();
+        // North of the image
+        assert_eq!(table.locate(0x3C000300, 0.0, 0.0), None);
+        // Uncalibrated area, legacy dungeon
+        assert_eq!(table.locate(0x3D000000, 0.0, 0.0), None);
+        assert_eq!(table.locate(0x0A000000, 0.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_parse_invalid_bounds() {
+        let err = WorldMapTable::parse(
+            r#"
+            [[areas]]
+            area = 60
+            texture = "m60.png"
+            west = 5.0
+            east = 5.0
+            south = 0.0
+            north = 1.0
+            "#,
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings: WorldMapSettings = toml::from_str("").unwrap();
+        assert_eq!(settings, WorldMapSettings::default());
+        let settings: WorldMapSettings = toml::from_str("size = 240.0").unwrap();
+        assert_eq!(settings.size, 240.0);
+        assert!(settings.enabled);
+    }
+}
@@ -0,0 +1,224 @@
+//! Zone metadata cached per seed
+//!
+//! Zones are only described by the server's zone_update, so a fog gate
+//! crossed while disconnected leads to an unknown zone. Every zone_update
+//! is kept here per seed (name, tiers, exits), with the zone each fog gate
+//! flag led to, and saved next to the DLL. Offline, a flag resolves
+//! locally: to the zone it led to before (a previous attempt, or before a
+//! reconnect), else to the destination of the current zone's exit nearest
+//! to where the player crossed, when the seed locates its gates.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::protocol::ExitInfo;
+
+/// Farthest a fog gate can be from the player crossing it (meters)
+pub const MAX_GATE_DISTANCE: f32 = 30.0;
+
+/// A zone as the server last described it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedZone {
+    pub display_name: String,
+    #[serde(default)]
+    pub tier: Option<i32>,
+    #[serde(default)]
+    pub original_tier: Option<i32>,
+    #[serde(default)]
+    pub exits: Vec<ExitInfo>,
+}
+
+/// Zones of one seed, and the zone each fog gate flag led to
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SeedZones {
+    #[serde(default)]
+    pub zones: HashMap<String, CachedZone>,
+    #[serde(default)]
+    pub flags: HashMap<u32, String>,
+}
+
+/// Contents of the zone cache file, keyed by seed_id
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ZoneCache {
+    #[serde(default)]
+    pub seeds: HashMap<String, SeedZones>,
+}
+
+impl ZoneCache {
+    pub const FILENAME: &'static str = "speedfog_zones.json";
+
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        serde_json::from_str(contents).map_err(|e| format!("Failed to parse zones: {}", e))
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize zones: {}", e))
+    }
+
+    pub fn zone(&self, seed_id: &str, node_id: &str) -> Option<&CachedZone> {
+        self.seeds.get(seed_id)?.zones.get(node_id)
+    }
+
+    /// Store a zone_update. Returns true when the cache changed (worth saving).
+    pub fn store_zone(&mut self, seed_id: &str, node_id: &str, zone: CachedZone) -> bool {
+        let zones = &mut self.seeds.entry(seed_id.to_string()).or_default().zones;
+        if zones.get(node_id) == Some(&zone) {
+            return false;
+        }
+        zones.insert(node_id.to_string(), zone);
+        true
+    }
+
+    /// Remember the zone a fog gate flag led to. Returns true when new.
+    pub fn link_flag(&mut self, seed_id: &str, flag_id: u32, node_id: &str) -> bool {
+        let flags = &mut self.seeds.entry(seed_id.to_string()).or_default().flags;
+        if flags.get(&flag_id).is_some_and(|n| n == node_id) {
+            return false;
+        }
+        flags.insert(flag_id, node_id.to_string());
+        true
+    }
+
+    /// Zone a fog gate flag leads to, from its link, else from the exit of
+    /// `from_node` nearest to `position` (map id, map-local position where
+    /// the player crossed). Only zones in the cache are returned.
+    pub fn resolve_flag(
+        &self,
+        seed_id: &str,
+        flag_id: u32,
+        from_node: Option<&str>,
+        position: Option<(&str, [f32; 3])>,
+    ) -> Option<(&str, &CachedZone)> {
+        let seed = self.seeds.get(seed_id)?;
+        let node_id = match seed.flags.get(&flag_id) {
+            Some(node_id) => node_id.as_str(),
+            None => {
+                let (map_id, pos) = position?;
+                seed.zones
+                    .get(from_node?)?
+                    .exits
+                    .iter()
+                    .filter(|e| e.map_id.as_deref() == Some(map_id))
+                    .filter_map(|e| Some((e.to_node_id.as_deref()?, distance(pos, e.position?))))
+                    .filter(|&(_, d)| d <= MAX_GATE_DISTANCE)
+                    .min_by(|a, b| a.1.total_cmp(&b.1))?
+                    .0
+            }
+        };
+        let (node_id, zone) = seed.zones.get_key_value(node_id)?;
+        Some((node_id.as_str(), zone))
+    }
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let dx = a[0] - b[0];
+    let dy = a[1] - b[1];
+    let dz = a[2] - b[2];
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "m10_00_00_00";
+
+    fn exit(to: &str, position: [f32; 3]) -> ExitInfo {
+        ExitInfo {
+            text: format!("To {}", to),
+            to_name: to.to_string(),
+            discovered: false,
+            to_node_id: Some(to.to_string()),
+            map_id: Some(MAP.to_string()),
+            position: Some(position),
+        }
+    }
+
+    fn zone(name: &str, exits: Vec<ExitInfo>) -> CachedZone {
+        CachedZone {
+            display_name: name.to_string(),
+            tier: Some(2),
+            original_tier: None,
+            exits,
+        }
+    }
+
+    fn cache() -> ZoneCache {
+        let mut cache = ZoneCache::default();
+        cache.store_zone(
+            "seed1",
+            "stormveil",
+            zone(
+                "Stormveil Castle",
+                vec![exit("liurnia", [0.0; 3]), exit("caelid", [100.0, 0.0, 0.0])],
+            ),
+        );
+        cache.store_zone("seed1", "liurnia", zone("Liurnia", Vec::new()));
+        cache.store_zone("seed1", "caelid", zone("Caelid", Vec::new()));
+        cache
+    }
+
+    #[test]
+    fn test_store_reports_changes() {
+        let mut cache = cache();
+        assert!(!cache.store_zone("seed1", "caelid", zone("Caelid", Vec::new())));
+        assert!(cache.store_zone("seed1", "caelid", zone("Caelid (scaled)", Vec::new())));
+        assert_eq!(
+            cache.zone("seed1", "caelid").unwrap().display_name,
+            "Caelid (scaled)"
+        );
+        assert!(cache.zone("seed2", "caelid").is_none());
+        assert!(cache.link_flag("seed1", 1040, "caelid"));
+        assert!(!cache.link_flag("seed1", 1040, "caelid"));
+    }
+
+    #[test]
+    fn test_resolve_by_flag_link() {
+        let mut cache = cache();
+        cache.link_flag("seed1", 1040, "caelid");
+        let (node_id, zone) = cache.resolve_flag("seed1", 1040, None, None).unwrap();
+        assert_eq!(node_id, "caelid");
+        assert_eq!(zone.display_name, "Caelid");
+        // Links are per seed
+        assert!(cache.resolve_flag("seed2", 1040, None, None).is_none());
+    }
+
+    #[test]
+    fn test_resolve_by_nearest_exit() {
+        let cache = cache();
+        let near = |pos| cache.resolve_flag("seed1", 1041, Some("stormveil"), Some((MAP, pos)));
+        assert_eq!(near([95.0, 0.0, 0.0]).unwrap().0, "caelid");
+        assert_eq!(near([5.0, 0.0, 0.0]).unwrap().0, "liurnia");
+        // Too far from every gate, or another map
+        assert!(near([50.0, 0.0, 0.0]).is_none());
+        assert!(cache
+            .resolve_flag(
+                "seed1",
+                1041,
+                Some("stormveil"),
+                Some(("m11_00_00_00", [0.0; 3]))
+            )
+            .is_none());
+        assert!(cache
+            .resolve_flag("seed1", 1041, None, Some((MAP, [0.0; 3])))
+            .is_none());
+    }
+
+    #[test]
+    fn test_uncached_destination_unresolved() {
+        let mut cache = cache();
+        cache.link_flag("seed1", 1042, "farum_azula");
+        assert!(cache.resolve_flag("seed1", 1042, None, None).is_none());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut cache = cache();
+        cache.link_flag("seed1", 1040, "caelid");
+        let parsed = ZoneCache::parse(&cache.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, cache);
+        assert_eq!(ZoneCache::parse("{}").unwrap(), ZoneCache::default());
+        assert!(ZoneCache::parse("not json").is_err());
+    }
+}
@@ -0,0 +1,146 @@
+//! Zone confirmation after an unanswered zone_query
+//!
+//! The server doesn't answer a zone_query it can't resolve (ambiguous map,
+//! no grace captured), which used to leave the overlay on the previous zone
+//! until the next fog gate. The query is retried a few times once the player
+//! has moved, since a new position may be enough to narrow the map down to a
+//! single zone.
+
+use std::time::{Duration, Instant};
+
+/// Delay without a zone_update before the query is retried
+pub const RESCAN_DELAY: Duration = Duration::from_secs(5);
+
+/// Retries sent before giving up until the next loading screen
+pub const MAX_RESCANS: u32 = 3;
+
+/// Distance the player must move for a retry to carry new information
+const MIN_MOVE: f32 = 10.0;
+
+#[derive(Debug, Clone)]
+struct Query {
+    map_id: String,
+    position: [f32; 3],
+    sent_at: Instant,
+    retries: u32,
+}
+
+/// Zone query waiting for its zone_update
+#[derive(Debug, Default)]
+pub struct ZoneConfirmation {
+    pending: Option<Query>,
+}
+
+impl ZoneConfirmation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A zone_query was sent at loading exit
+    pub fn query_sent(&mut self, map_id: &str, position: [f32; 3], now: Instant) {
+        self.pending = Some(Query {
+            map_id: map_id.to_string(),
+            position,
+            sent_at: now,
+            retries: 0,
+        });
+    }
+
+    /// A zone_update arrived, or a loading screen made the query obsolete
+    pub fn clear(&mut self) {
+        self.pending = None;
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Whether the query should be retried from `position` now. A retry is
+    /// recorded as sent; the position must differ from the last query's.
+    pub fn should_retry(&mut self, map_id: &str, position: [f32; 3], now: Instant) -> bool {
+        let Some(query) = self.pending.as_mut() else {
+            return false;
+        };
+        if now.saturating_duration_since(query.sent_at) < RESCAN_DELAY {
+            return false;
+        }
+        if query.retries >= MAX_RESCANS {
+            self.pending = None;
+            return false;
+        }
+        let dx = position[0] - query.position[0];
+        let dy = position[1] - query.position[1];
+        let dz = position[2] - query.position[2];
+        let moved = (dx * dx + dy * dy + dz * dz).sqrt() >= MIN_MOVE;
+        if query.map_id == map_id && !moved {
+            return false;
+        }
+        query.map_id = map_id.to_string();
+        query.position = position;
+        query.sent_at = now;
+        query.retries += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "m60_42_36_00";
+
+    #[test]
+    fn test_nothing_pending() {
+        let mut confirm = ZoneConfirmation::new();
+        assert!(!confirm.is_pending());
+        assert!(!confirm.should_retry(MAP, [0.0; 3], Instant::now()));
+    }
+
+    #[test]
+    fn test_retry_after_delay_when_moved() {
+        let start = Instant::now();
+        let mut confirm = ZoneConfirmation::new();
+        confirm.query_sent(MAP, [0.0, 0.0, 0.0], start);
+
+        // Too early
+        assert!(!confirm.should_retry(MAP, [50.0, 0.0, 0.0], start + Duration::from_secs(1)));
+        // Late enough but standing still
+        assert!(!confirm.should_retry(MAP, [2.0, 0.0, 0.0], start + RESCAN_DELAY));
+        // Moved
+        assert!(confirm.should_retry(MAP, [50.0, 0.0, 0.0], start + RESCAN_DELAY));
+        // Timer restarts from the retry
+        assert!(!confirm.should_retry(MAP, [100.0, 0.0, 0.0], start + RESCAN_DELAY));
+    }
+
+    #[test]
+    fn test_map_change_triggers_retry() {
+        let start = Instant::now();
+        let mut confirm = ZoneConfirmation::new();
+        confirm.query_sent(MAP, [0.0, 0.0, 0.0], start);
+        assert!(confirm.should_retry("m60_43_36_00", [0.0, 0.0, 0.0], start + RESCAN_DELAY));
+    }
+
+    #[test]
+    fn test_gives_up_after_max_rescans() {
+        let start = Instant::now();
+        let mut confirm = ZoneConfirmation::new();
+        confirm.query_sent(MAP, [0.0, 0.0, 0.0], start);
+        let mut now = start;
+        for i in 1..=MAX_RESCANS {
+            now += RESCAN_DELAY;
+            assert!(confirm.should_retry(MAP, [i as f32 * 100.0, 0.0, 0.0], now));
+        }
+        now += RESCAN_DELAY;
+        assert!(!confirm.should_retry(MAP, [0.0, 0.0, 0.0], now));
+        assert!(!confirm.is_pending());
+    }
+
+    #[test]
+    fn test_clear_stops_retries() {
+        let start = Instant::now();
+        let mut confirm = ZoneConfirmation::new();
+        confirm.query_sent(MAP, [0.0, 0.0, 0.0], start);
+        confirm.clear();
+        assert!(!confirm.should_retry(MAP, [50.0, 0.0, 0.0], start + RESCAN_DELAY));
+    }
+}
@@ -0,0 +1,400 @@
+//! Discovered zone graph
+//!
+//! The zones revealed during a run, how the player moved between them and the
+//! exits still undiscovered, exported on demand as GraphViz (.dot) and JSON
+//! next to the DLL for post-run visualization and sharing.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::post_race::file_safe_id;
+use super::protocol::ExitInfo;
+
+/// How the player got from one zone to the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    /// Fog gate traversal (event flag)
+    FogGate,
+    /// Fast travel from the map menu
+    FastTravel,
+    /// Death or quit-out
+    Respawn,
+}
+
+impl Transport {
+    pub fn label(self) -> &'static str {
+        match self {
+            Transport::FogGate => "fog gate",
+            Transport::FastTravel => "fast travel",
+            Transport::Respawn => "respawn",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphNode {
+    pub node_id: String,
+    pub name: String,
+    pub tier: Option<i32>,
+    /// IGT of the first arrival
+    pub igt_ms: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub transport: Transport,
+    /// IGT of the first traversal
+    pub igt_ms: u32,
+    pub count: u32,
+}
+
+/// Exit of a visited zone whose destination is still unknown
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpenExit {
+    pub from: String,
+    pub text: String,
+}
+
+/// Contents of the JSON export
+#[derive(Debug, Serialize)]
+struct GraphExport<'a> {
+    seed_id: Option<&'a str>,
+    nodes: &'a [GraphNode],
+    edges: &'a [GraphEdge],
+    undiscovered_exits: Vec<OpenExit>,
+}
+
+/// Zones and traversals seen this run, in discovery order
+#[derive(Debug, Default)]
+pub struct ZoneGraph {
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+    /// Latest exits received for each zone
+    exits: HashMap<String, Vec<ExitInfo>>,
+    current: Option<String>,
+}
+
+impl ZoneGraph {
+    /// Export file name, e.g. `speedfog_graph_<race_id>.dot`
+    pub fn file_name(race_id: &str, ext: &str) -> String {
+        format!("speedfog_graph_{}.{}", file_safe_id(race_id), ext)
+    }
+
+    /// Record arriving in a zone (revealed on the overlay). Staying in the
+    /// same zone only refreshes its exits.
+    pub fn visit(
+        &mut self,
+        node_id: &str,
+        name: &str,
+        tier: Option<i32>,
+        exits: &[ExitInfo],
+        transport: Transport,
+        igt_ms: u32,
+    ) {
+        if !self.nodes.iter().any(|n| n.node_id == node_id) {
+            self.nodes.push(GraphNode {
+                node_id: node_id.to_string(),
+                name: name.to_string(),
+                tier,
+                igt_ms,
+            });
+        }
+        self.exits.insert(node_id.to_string(), exits.to_vec());
+
+        if let Some(from) = self.current.as_deref().filter(|&from| from != node_id) {
+            match self
+                .edges
+                .iter_mut()
+                .find(|e| e.from == from && e.to == node_id && e.transport == transport)
+            {
+                Some(edge) => edge.count += 1,
+                None => self.edges.push(GraphEdge {
+                    from: from.to_string(),
+                    to: node_id.to_string(),
+                    transport,
+                    igt_ms,
+                    count: 1,
+                }),
+            }
+        }
+        self.current = Some(node_id.to_string());
+    }
+
+    /// Tier of a visited zone, by display name
+    pub fn tier_of(&self, name: &str) -> Option<i32> {
+        self.nodes.iter().find(|n| n.name == name)?.tier
+    }
+
+    /// Whether the destination of an exit was visited this run, by node id
+    /// (by display name from servers that don't send it)
+    pub fn has_visited(&self, exit: &ExitInfo) -> bool {
+        match &exit.to_node_id {
+            Some(to) => self.nodes.iter().any(|n| &n.node_id == to),
+            None => self.nodes.iter().any(|n| n.name == exit.to_name),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn nodes(&self) -> &[GraphNode] {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> &[GraphEdge] {
+        &self.edges
+    }
+
+    /// Undiscovered exits of the visited zones, in discovery order
+    pub fn open_exits(&self) -> Vec<OpenExit> {
+        self.nodes
+            .iter()
+            .flat_map(|node| {
+                self.exits
+                    .get(&node.node_id)
+                    .into_iter()
+                    .flatten()
+                    .filter(|exit| !exit.discovered)
+                    .map(|exit| OpenExit {
+                        from: node.node_id.clone(),
+                        text: exit.text.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self, seed_id: Option<&str>) -> Result<String, String> {
+        let export = GraphExport {
+            seed_id,
+            nodes: &self.nodes,
+            edges: &self.edges,
+            undiscovered_exits: self.open_exits(),
+        };
+        serde_json::to_string_pretty(&export)
+            .map_err(|e| format!("Failed to serialize zone graph: {}", e))
+    }
+
+    /// GraphViz rendering: zones as boxes, traversals labeled with their
+    /// transport, undiscovered exits as dashed "???" leaves
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph speedfog {\n");
+        dot.push_str("  rankdir=LR;\n");
+        dot.push_str("  node [shape=box];\n");
+        for node in &self.nodes {
+            let label = match node.tier {
+                Some(tier) => format!("{}\\ntier {}", escape(&node.name), tier),
+                None => escape(&node.name),
+            };
+            dot.push_str(&format!(
+                "  \"{}\" [label=\"{}\"];\n",
+                escape(&node.node_id),
+                label
+            ));
+        }
+        for edge in &self.edges {
+            let label = match edge.count {
+                1 => edge.transport.label().to_string(),
+                n => format!("{} x{}", edge.transport.label(), n),
+            };
+            let style = match edge.transport {
+                Transport::FogGate => "",
+                _ => ", style=dotted",
+            };
+            dot.push_str(&format!(
+                "  \"{}\" -> \"{}\" [label=\"{}\"{}];\n",
+                escape(&edge.from),
+                escape(&edge.to),
+                label,
+                style
+            ));
+        }
+        for (i, exit) in self.open_exits().iter().enumerate() {
+            let id = format!("exit_{}", i);
+            dot.push_str(&format!(
+                "  \"{}\" [label=\"???\", shape=ellipse, style=dashed];\n",
+                id
+            ));
+            dot.push_str(&format!(
+                "  \"{}\" -> \"{}\" [label=\"{}\", style=dashed];\n",
+                escape(&exit.from),
+                id,
+                escape(&exit.text)
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Quote-safe text for a DOT string
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exit(text: &str, discovered: bool) -> ExitInfo {
+        ExitInfo {
+            text: text.to_string(),
+            to_name: if discovered { "Somewhere" } else { "???" }.to_string(),
+            discovered,
+            to_node_id: None,
+            map_id: None,
+            position: None,
+        }
+    }
+
+    fn sample() -> ZoneGraph {
+        let mut graph = ZoneGraph::default();
+        graph.visit(
+            "chapel",
+            "Chapel of Anticipation",
+            Some(1),
+            &[exit("Front door", true), exit("Back \"door\"", false)],
+            Transport::Respawn,
+            0,
+        );
+        graph.visit(
+            "stormveil",
+            "Stormveil Castle",
+            Some(5),
+            &[],
+            Transport::FogGate,
+            60_000,
+        );
+        graph
+    }
+
+    #[test]
+    fn test_first_zone_has_no_edge() {
+        let mut graph = ZoneGraph::default();
+        assert!(graph.is_empty());
+        graph.visit("chapel", "Chapel", None, &[], Transport::Respawn, 0);
+        assert_eq!(graph.nodes().len(), 1);
+        assert!(graph.edges().is_empty());
+        assert_eq!(graph.tier_of("Chapel"), None);
+    }
+
+    #[test]
+    fn test_traversals_recorded_once_per_transport() {
+        let mut graph = sample();
+        graph.visit(
+            "chapel",
+            "Chapel of Anticipation",
+            Some(1),
+            &[],
+            Transport::Respawn,
+            90_000,
+        );
+        graph.visit(
+            "stormveil",
+            "Stormveil Castle",
+            Some(5),
+            &[],
+            Transport::FogGate,
+            95_000,
+        );
+        graph.visit(
+            "stormveil",
+            "Stormveil Castle",
+            Some(5),
+            &[],
+            Transport::Respawn,
+            99_000,
+        );
+
+        assert_eq!(graph.nodes().len(), 2);
+        assert_eq!(graph.tier_of("Stormveil Castle"), Some(5));
+        let edges: Vec<(&str, &str, Transport, u32)> = graph
+            .edges()
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.transport, e.count))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("chapel", "stormveil", Transport::FogGate, 2),
+                ("stormveil", "chapel", Transport::Respawn, 1),
+            ]
+        );
+        assert_eq!(graph.edges()[0].igt_ms, 60_000);
+    }
+
+    #[test]
+    fn test_visited_destinations() {
+        let graph = sample();
+        let mut to_stormveil = exit("Front door", true);
+        to_stormveil.to_node_id = Some("stormveil".to_string());
+        assert!(graph.has_visited(&to_stormveil));
+
+        // Same display name, other zone
+        to_stormveil.to_node_id = Some("stormveil_throne".to_string());
+        to_stormveil.to_name = "Stormveil Castle".to_string();
+        assert!(!graph.has_visited(&to_stormveil));
+
+        // Older servers: matched by name
+        to_stormveil.to_node_id = None;
+        assert!(graph.has_visited(&to_stormveil));
+        assert!(!graph.has_visited(&exit("Back door", false)));
+    }
+
+    #[test]
+    fn test_open_exits_use_latest_zone_update() {
+        let mut graph = sample();
+        assert_eq!(
+            graph.open_exits(),
+            vec![OpenExit {
+                from: "chapel".to_string(),
+                text: "Back \"door\"".to_string(),
+            }]
+        );
+        // Back in the chapel once the back door was found
+        graph.visit(
+            "chapel",
+            "Chapel of Anticipation",
+            Some(1),
+            &[exit("Front door", true), exit("Back \"door\"", true)],
+            Transport::FastTravel,
+            120_000,
+        );
+        assert!(graph.open_exits().is_empty());
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = sample().to_dot();
+        assert!(dot.starts_with("digraph speedfog {\n"));
+        assert!(dot.contains("  \"chapel\" [label=\"Chapel of Anticipation\\ntier 1\"];\n"));
+        assert!(dot.contains("  \"chapel\" -> \"stormveil\" [label=\"fog gate\"];\n"));
+        assert!(dot
+            .contains("  \"chapel\" -> \"exit_0\" [label=\"Back \\\"door\\\"\", style=dashed];\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(
+            ZoneGraph::file_name("race/1 a", "dot"),
+            "speedfog_graph_race_1_a.dot"
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&sample().to_json(Some("seed1")).unwrap()).unwrap();
+        assert_eq!(json["seed_id"], "seed1");
+        assert_eq!(json["nodes"][1]["name"], "Stormveil Castle");
+        assert_eq!(json["edges"][0]["transport"], "fog_gate");
+        assert_eq!(json["undiscovered_exits"][0]["from"], "chapel");
+    }
+}
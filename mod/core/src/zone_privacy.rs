@@ -0,0 +1,77 @@
+//! Zone privacy of the leaderboard
+//!
+//! Each leaderboard row can show where the other racers are. Races choose
+//! how much of it the overlay reveals (`auth_ok` `zone_privacy`, protocol
+//! 22+): the zone name, only its tier, or nothing but a placeholder. The
+//! local player's own zone is never affected.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZonePrivacy {
+    /// Zone name, or its tier when the server didn't name it
+    #[default]
+    Name,
+    /// Tier of the zone only
+    Tier,
+    /// A placeholder instead of the zone. Also used for modes this mod
+    /// doesn't know, so a newer server never reveals more than intended.
+    #[serde(other)]
+    Hidden,
+}
+
+/// What a leaderboard row shows of another participant's zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoneLabel<'a> {
+    Name(&'a str),
+    Tier(i32),
+    Hidden,
+}
+
+impl ZonePrivacy {
+    /// Label of a participant's zone, None when nothing is known about it
+    pub fn label<'a>(self, zone_name: Option<&'a str>, tier: Option<i32>) -> Option<ZoneLabel<'a>> {
+        match self {
+            ZonePrivacy::Name => zone_name.map(ZoneLabel::Name).or(tier.map(ZoneLabel::Tier)),
+            ZonePrivacy::Tier => tier.map(ZoneLabel::Tier),
+            ZonePrivacy::Hidden => Some(ZoneLabel::Hidden),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels() {
+        let name = ZonePrivacy::Name;
+        assert_eq!(
+            name.label(Some("Stormveil Castle"), Some(3)),
+            Some(ZoneLabel::Name("Stormveil Castle"))
+        );
+        assert_eq!(name.label(None, Some(3)), Some(ZoneLabel::Tier(3)));
+        assert_eq!(name.label(None, None), None);
+
+        let tier = ZonePrivacy::Tier;
+        assert_eq!(
+            tier.label(Some("Stormveil Castle"), Some(3)),
+            Some(ZoneLabel::Tier(3))
+        );
+        assert_eq!(tier.label(Some("Stormveil Castle"), None), None);
+
+        assert_eq!(
+            ZonePrivacy::Hidden.label(Some("Stormveil Castle"), Some(3)),
+            Some(ZoneLabel::Hidden)
+        );
+    }
+
+    #[test]
+    fn test_unknown_mode_hides() {
+        let privacy: ZonePrivacy = serde_json::from_str(r#""tier""#).unwrap();
+        assert_eq!(privacy, ZonePrivacy::Tier);
+        let privacy: ZonePrivacy = serde_json::from_str(r#""blurred""#).unwrap();
+        assert_eq!(privacy, ZonePrivacy::Hidden);
+    }
+}
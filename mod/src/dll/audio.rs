@@ -0,0 +1,70 @@
+//! Audio cue playback
+//!
+//! The cues' WAV files are read from the DLL directory once at startup and
+//! scaled to the configured volume (`core::audio`), then played from memory
+//! with PlaySound. Playback is asynchronous: a new cue cuts the previous one.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use tracing::{info, warn};
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::HMODULE;
+use windows::Win32::Media::Audio::{PlaySoundW, SND_ASYNC, SND_MEMORY, SND_NODEFAULT};
+
+use crate::core::audio::{scale_wav, AudioCue, AudioSettings};
+
+/// Loaded cues, ready to play
+#[derive(Default)]
+pub(crate) struct AudioPlayer {
+    /// WAV images passed to PlaySound; kept alive while the sound may play
+    sounds: HashMap<AudioCue, Vec<u8>>,
+}
+
+impl AudioPlayer {
+    /// Load the WAV files of the enabled cues. Missing or unsupported files
+    /// leave their cue silent.
+    pub fn load(settings: &AudioSettings, dll_dir: Option<&Path>) -> Self {
+        let mut sounds = HashMap::new();
+        let Some(dir) = dll_dir.filter(|_| settings.enabled) else {
+            return Self { sounds };
+        };
+        for cue in AudioCue::ALL.into_iter().filter(|&cue| settings.plays(cue)) {
+            let path = dir.join(cue.file_name());
+            if !path.exists() {
+                continue;
+            }
+            match fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|data| scale_wav(&data, settings.volume))
+            {
+                Ok(sound) => {
+                    sounds.insert(cue, sound);
+                }
+                Err(e) => warn!(error = %e, path = %path.display(), "[AUDIO] Ignoring sound"),
+            }
+        }
+        info!(cues = sounds.len(), "[AUDIO] Sounds loaded");
+        Self { sounds }
+    }
+
+    /// Play a cue if its sound is loaded
+    pub fn play(&self, cue: AudioCue) {
+        let Some(sound) = self.sounds.get(&cue) else {
+            return;
+        };
+        // SAFETY: with SND_MEMORY the pointer is the WAV image, which lives in
+        // `self.sounds` for the tracker's lifetime, past the async playback.
+        let played = unsafe {
+            PlaySoundW(
+                PCWSTR(sound.as_ptr() as *const u16),
+                HMODULE::default(),
+                SND_MEMORY | SND_ASYNC | SND_NODEFAULT,
+            )
+        };
+        if !played.as_bool() {
+            warn!(?cue, "[AUDIO] PlaySound failed");
+        }
+    }
+}
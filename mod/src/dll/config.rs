@@ -4,12 +4,47 @@
 
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use toml_edit::{value, Array, DocumentMut};
 use tracing::info;
 use windows::Win32::Foundation::HINSTANCE;
 use windows::Win32::System::LibraryLoader::GetModuleFileNameW;
 
 use super::hotkey::Hotkey;
+use crate::core::activity_feed::ActivityFeedSettings;
+use crate::core::afk::AfkSettings;
+use crate::core::anchor::{Anchor, Offset};
+use crate::core::audio::AudioSettings;
+use crate::core::auto_hide::AutoHideSettings;
+use crate::core::boss_fight::BossFightSettings;
+use crate::core::config_error::{check_color, check_keybindings, check_server, ConfigError};
+use crate::core::countdown::CountdownSettings;
+use crate::core::detection::DetectionSettings;
+use crate::core::drift::DriftSettings;
+use crate::core::exit_order::ExitOrderSettings;
+use crate::core::flag_trace::FlagTraceSettings;
+use crate::core::focus::FocusSettings;
+use crate::core::gate_proximity::GateProximitySettings;
+use crate::core::graces::GraceSettings;
+use crate::core::hint_cache::HintSettings;
+use crate::core::hold_confirm::HoldConfirmSettings;
+use crate::core::key_chord::Modifiers;
+use crate::core::locale::DEFAULT_LANGUAGE;
+use crate::core::overlay_profile::OverlayProfile;
+use crate::core::panel_cycle::AutoCycleSettings;
+use crate::core::post_race::SummaryFormat;
+use crate::core::race_phase::PhaseSettings;
+use crate::core::race_timer::RaceTimerSettings;
+use crate::core::remote_flags::RemoteFlagSettings;
+use crate::core::scaling::ScalingSettings;
+use crate::core::screenshot::ScreenshotSettings;
+use crate::core::self_test::SelfTestSettings;
+use crate::core::theme::ThemeName;
+use crate::core::toast::ToastSettings;
+use crate::core::transport::TransportMode;
+use crate::core::warmup::WarmupSettings;
+use crate::core::window_layout::SavedLayouts;
+use crate::core::world_map::WorldMapSettings;
 
 /// Server connection settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +61,14 @@ pub struct ServerSettings {
     /// Seed ID from seed pack — used to detect stale packs after seed re-roll
     #[serde(default)]
     pub seed_id: String,
+    /// Offer MessagePack (binary frames) at auth; JSON is kept when the
+    /// server doesn't support it
+    #[serde(default = "default_enabled")]
+    pub binary_protocol: bool,
+    /// "auto" (WebSocket, HTTP long-polling when it's blocked),
+    /// "websocket" or "long_poll"
+    #[serde(default)]
+    pub transport: TransportMode,
 }
 
 impl Default for ServerSettings {
@@ -36,6 +79,8 @@ impl Default for ServerSettings {
             race_id: String::new(),
             training: false,
             seed_id: String::new(),
+            binary_protocol: true,
+            transport: TransportMode::Auto,
         }
     }
 }
@@ -47,6 +92,20 @@ pub struct OverlaySettings {
     #[serde(default = "default_enabled")]
     pub enabled: bool,
 
+    /// What the overlay shows: "full", "minimal" (zone + IGT only) or
+    /// "streamer" (no auth errors, raw server messages or debug panel)
+    #[serde(default)]
+    pub profile: OverlayProfile,
+
+    /// Status color palette: "default", "high_contrast", "deuteranopia" or
+    /// "protanopia" (colorblind-friendly blue/orange instead of green/red)
+    #[serde(default)]
+    pub theme: ThemeName,
+
+    /// Overlay language: "en", "fr", or any `lang/<code>.toml` next to the DLL
+    #[serde(default = "default_language")]
+    pub language: String,
+
     /// Path to TTF font file.
     ///   - Empty "": uses Windows system font (Segoe UI)
     ///   - Filename only "arial.ttf": looks in C:\Windows\Fonts\ then DLL directory
@@ -55,10 +114,19 @@ pub struct OverlaySettings {
     #[serde(default)]
     pub font_path: String,
 
-    /// Font size in pixels (32.0 recommended for 1080p, 64.0 for 4K)
+    /// Font size in pixels at 1080p (see `auto_scale`)
     #[serde(default = "default_font_size")]
     pub font_size: f32,
 
+    /// Scale the font size, pixel margins and world map with the game's
+    /// resolution (sizes are given for 1080p: doubled at 4K)
+    #[serde(default = "default_auto_scale")]
+    pub auto_scale: bool,
+
+    /// Overlay size multiplier, on top of `auto_scale`
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+
     /// Background color as hex "#RRGGBB"
     #[serde(default = "default_background_color")]
     pub background_color: String,
@@ -75,6 +143,10 @@ pub struct OverlaySettings {
     #[serde(default = "default_text_disabled_color")]
     pub text_disabled_color: String,
 
+    /// Icon size relative to the text line height (1.0 = same height as text)
+    #[serde(default = "default_icon_scale")]
+    pub icon_scale: f32,
+
     /// Show window border
     #[serde(default)]
     pub show_border: bool,
@@ -83,21 +155,37 @@ pub struct OverlaySettings {
     #[serde(default = "default_border_color")]
     pub border_color: String,
 
-    /// Horizontal margin from the right edge of the screen in pixels.
+    /// Screen corner (or center) the overlay is attached to:
+    /// "top_left", "top_right", "top_center", "bottom_left", "bottom_right", "center"
+    #[serde(default)]
+    pub anchor: Anchor,
+
+    /// Horizontal margin from the anchored edge: pixels (20.0) or percent of
+    /// the display width ("2%"), which survives resolution changes.
     #[serde(default = "default_position_offset_x")]
-    pub position_offset_x: f32,
+    pub position_offset_x: Offset,
 
-    /// Vertical margin from the top edge of the screen in pixels.
+    /// Vertical margin from the anchored edge: pixels (20.0) or percent of
+    /// the display height ("2%").
     #[serde(default = "default_position_offset_y")]
-    pub position_offset_y: f32,
+    pub position_offset_y: Offset,
 }
 
 fn default_enabled() -> bool {
     true
 }
+fn default_language() -> String {
+    DEFAULT_LANGUAGE.to_string()
+}
 fn default_font_size() -> f32 {
     18.0
 }
+fn default_auto_scale() -> bool {
+    true
+}
+fn default_scale() -> f32 {
+    1.0
+}
 fn default_background_color() -> String {
     "#141414".to_string()
 }
@@ -110,28 +198,38 @@ fn default_text_color() -> String {
 fn default_text_disabled_color() -> String {
     "#808080".to_string()
 }
+fn default_icon_scale() -> f32 {
+    1.0
+}
 fn default_border_color() -> String {
     "#404040".to_string()
 }
-fn default_position_offset_x() -> f32 {
-    20.0
+fn default_position_offset_x() -> Offset {
+    Offset::Pixels(20.0)
 }
-fn default_position_offset_y() -> f32 {
-    20.0
+fn default_position_offset_y() -> Offset {
+    Offset::Pixels(20.0)
 }
 
 impl Default for OverlaySettings {
     fn default() -> Self {
         Self {
             enabled: default_enabled(),
+            profile: OverlayProfile::default(),
+            theme: ThemeName::default(),
+            language: default_language(),
             font_path: String::new(),
             font_size: default_font_size(),
+            auto_scale: default_auto_scale(),
+            scale: default_scale(),
             background_color: default_background_color(),
             background_opacity: default_background_opacity(),
             text_color: default_text_color(),
             text_disabled_color: default_text_disabled_color(),
+            icon_scale: default_icon_scale(),
             show_border: false,
             border_color: default_border_color(),
+            anchor: Anchor::default(),
             position_offset_x: default_position_offset_x(),
             position_offset_y: default_position_offset_y(),
         }
@@ -150,14 +248,77 @@ pub struct KeyBindings {
     /// Toggle leaderboard visibility
     #[serde(default = "default_toggle_leaderboard")]
     pub toggle_leaderboard: Hotkey,
+    /// Toggle in-game settings panel
+    #[serde(default = "default_toggle_settings")]
+    pub toggle_settings: Hotkey,
+    /// Export the discovered zone graph (.dot and .json next to the DLL)
+    #[serde(default = "default_export_graph")]
+    pub export_graph: Hotkey,
+    /// Dismiss the AFK warning (or come back from AFK) without moving
+    #[serde(default = "default_cancel_afk")]
+    pub cancel_afk: Hotkey,
+    /// Toggle the world-map position thumbnail
+    #[serde(default = "default_toggle_map")]
+    pub toggle_map: Hotkey,
+    /// Toggle interactive mode: mouse cursor, tooltips and exit pinning
+    #[serde(default = "default_toggle_interactive")]
+    pub toggle_interactive: Hotkey,
+    /// Ask the organizer to pause the race (technical issue)
+    #[serde(default = "default_request_pause")]
+    pub request_pause: Hotkey,
+    /// Save a screenshot of the game window
+    #[serde(default = "default_screenshot")]
+    pub screenshot: Hotkey,
+    /// Check the whole setup and write a report for support requests
+    #[serde(default = "default_self_test")]
+    pub self_test: Hotkey,
 }
 
 fn default_toggle_debug() -> Hotkey {
-    Hotkey { key: 0x72 } // F3
+    Hotkey::new(0x72) // F3
 }
 
 fn default_toggle_leaderboard() -> Hotkey {
-    Hotkey { key: 0x79 } // F10
+    Hotkey::new(0x79) // F10
+}
+
+fn default_toggle_settings() -> Hotkey {
+    Hotkey::new(0x77) // F8
+}
+
+fn default_export_graph() -> Hotkey {
+    Hotkey::new(0x76) // F7
+}
+
+fn default_cancel_afk() -> Hotkey {
+    Hotkey::new(0x75) // F6
+}
+
+fn default_toggle_map() -> Hotkey {
+    Hotkey::new(0x74) // F5
+}
+
+fn default_toggle_interactive() -> Hotkey {
+    Hotkey::new(0x71) // F2
+}
+
+fn default_request_pause() -> Hotkey {
+    Hotkey::new(0x73) // F4
+}
+
+fn default_screenshot() -> Hotkey {
+    Hotkey::new(0x7A) // F11
+}
+
+fn default_self_test() -> Hotkey {
+    // Ctrl+F3: all the plain F keys are taken
+    Hotkey {
+        key: 0x72,
+        modifiers: Modifiers {
+            ctrl: true,
+            ..Modifiers::NONE
+        },
+    }
 }
 
 impl Default for KeyBindings {
@@ -166,10 +327,75 @@ impl Default for KeyBindings {
             toggle_ui: Hotkey::default(),
             toggle_debug: default_toggle_debug(),
             toggle_leaderboard: default_toggle_leaderboard(),
+            toggle_settings: default_toggle_settings(),
+            export_graph: default_export_graph(),
+            cancel_afk: default_cancel_afk(),
+            toggle_map: default_toggle_map(),
+            toggle_interactive: default_toggle_interactive(),
+            request_pause: default_request_pause(),
+            screenshot: default_screenshot(),
+            self_test: default_self_test(),
         }
     }
 }
 
+impl KeyBindings {
+    /// Every action with its binding, named as in `[keybindings]`
+    pub fn actions(&self) -> [(&'static str, Hotkey); 11] {
+        [
+            ("toggle_ui", self.toggle_ui),
+            ("toggle_debug", self.toggle_debug),
+            ("toggle_leaderboard", self.toggle_leaderboard),
+            ("toggle_settings", self.toggle_settings),
+            ("export_graph", self.export_graph),
+            ("cancel_afk", self.cancel_afk),
+            ("toggle_map", self.toggle_map),
+            ("toggle_interactive", self.toggle_interactive),
+            ("request_pause", self.request_pause),
+            ("screenshot", self.screenshot),
+            ("self_test", self.self_test),
+        ]
+    }
+
+    /// Binding of an action named as in `[keybindings]`
+    pub fn get_mut(&mut self, action: &str) -> Option<&mut Hotkey> {
+        Some(match action {
+            "toggle_ui" => &mut self.toggle_ui,
+            "toggle_debug" => &mut self.toggle_debug,
+            "toggle_leaderboard" => &mut self.toggle_leaderboard,
+            "toggle_settings" => &mut self.toggle_settings,
+            "export_graph" => &mut self.export_graph,
+            "cancel_afk" => &mut self.cancel_afk,
+            "toggle_map" => &mut self.toggle_map,
+            "toggle_interactive" => &mut self.toggle_interactive,
+            "request_pause" => &mut self.request_pause,
+            "screenshot" => &mut self.screenshot,
+            "self_test" => &mut self.self_test,
+            _ => return None,
+        })
+    }
+}
+
+/// Post-race export settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PostRaceSettings {
+    /// Summary file written next to the DLL when the race finishes:
+    /// "json", "markdown" or "off"
+    #[serde(default)]
+    pub format: SummaryFormat,
+}
+
+/// Discord Rich Presence settings (used when built with the `discord` feature)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiscordSettings {
+    /// Publish race progress to the local Discord client
+    #[serde(default)]
+    pub enabled: bool,
+    /// Discord application ID the presence is published under
+    #[serde(default)]
+    pub application_id: String,
+}
+
 /// Main config structure
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RaceConfig {
@@ -179,6 +405,63 @@ pub struct RaceConfig {
     pub overlay: OverlaySettings,
     #[serde(default)]
     pub keybindings: KeyBindings,
+    #[serde(default)]
+    pub post_race: PostRaceSettings,
+    #[serde(default)]
+    pub toasts: ToastSettings,
+    #[serde(default)]
+    pub discord: DiscordSettings,
+    #[serde(default)]
+    pub detection: DetectionSettings,
+    #[serde(default)]
+    pub countdown: CountdownSettings,
+    #[serde(default)]
+    pub scaling: ScalingSettings,
+    #[serde(default)]
+    pub hints: HintSettings,
+    #[serde(default)]
+    pub exit_order: ExitOrderSettings,
+    /// Window positions dragged in interactive mode, per resolution
+    #[serde(default)]
+    pub layout: SavedLayouts,
+    #[serde(default)]
+    pub auto_cycle: AutoCycleSettings,
+    #[serde(default)]
+    pub afk: AfkSettings,
+    #[serde(default)]
+    pub world_map: WorldMapSettings,
+    #[serde(default)]
+    pub gate_proximity: GateProximitySettings,
+    #[serde(default)]
+    pub screenshots: ScreenshotSettings,
+    #[serde(default)]
+    pub flag_trace: FlagTraceSettings,
+    #[serde(default)]
+    pub boss_fight: BossFightSettings,
+    #[serde(default)]
+    pub graces: GraceSettings,
+    #[serde(default)]
+    pub audio: AudioSettings,
+    #[serde(default)]
+    pub remote_flags: RemoteFlagSettings,
+    #[serde(default)]
+    pub hold_confirm: HoldConfirmSettings,
+    #[serde(default)]
+    pub activity_feed: ActivityFeedSettings,
+    #[serde(default)]
+    pub self_test: SelfTestSettings,
+    #[serde(default)]
+    pub warmup: WarmupSettings,
+    #[serde(default)]
+    pub drift: DriftSettings,
+    #[serde(default)]
+    pub race_timer: RaceTimerSettings,
+    #[serde(default)]
+    pub focus: FocusSettings,
+    #[serde(default)]
+    pub phases: PhaseSettings,
+    #[serde(default)]
+    pub auto_hide: AutoHideSettings,
 }
 
 impl RaceConfig {
@@ -195,29 +478,159 @@ impl RaceConfig {
         PathBuf::from(dll_path).parent().map(|p| p.to_path_buf())
     }
 
-    /// Load config from file next to DLL
-    pub fn load(hmodule: HINSTANCE) -> Result<Self, String> {
-        let dir = Self::get_dll_directory(hmodule).ok_or("Could not get DLL directory")?;
+    /// Load config from file next to DLL. Bad keybindings are all reported at
+    /// once; other parse errors stop at the first one.
+    pub fn load(hmodule: HINSTANCE) -> Result<Self, Vec<ConfigError>> {
+        let dir = Self::get_dll_directory(hmodule).ok_or_else(|| {
+            vec![ConfigError::Unreadable {
+                path: Self::CONFIG_FILENAME.to_string(),
+                reason: "could not get the DLL directory".to_string(),
+            }]
+        })?;
         let config_path = dir.join(Self::CONFIG_FILENAME);
 
         if !config_path.exists() {
-            return Err(format!("Config file not found: {}", config_path.display()));
+            return Err(vec![ConfigError::NotFound {
+                path: config_path.display().to_string(),
+            }]);
+        }
+
+        let contents = fs::read_to_string(&config_path).map_err(|e| {
+            vec![ConfigError::Unreadable {
+                path: config_path.display().to_string(),
+                reason: e.to_string(),
+            }]
+        })?;
+
+        let table: toml::Table = toml::from_str(&contents).map_err(|e| {
+            vec![ConfigError::Syntax {
+                message: e.to_string(),
+            }]
+        })?;
+        let bad_keys = check_keybindings(&table, |key| Hotkey::from_name(key).is_some());
+        if !bad_keys.is_empty() {
+            return Err(bad_keys);
         }
+        let config: RaceConfig = table.try_into().map_err(|e: toml::de::Error| {
+            vec![ConfigError::Syntax {
+                message: e.to_string(),
+            }]
+        })?;
 
+        info!(path = %config_path.display(), "Loaded race config");
+        Ok(config)
+    }
+
+    /// Seed ID of the seed pack currently extracted next to the DLL.
+    ///
+    /// Re-read while the pack is outdated, to notice the new one once installed.
+    pub fn read_seed_id(dir: &Path) -> Result<String, String> {
+        let config_path = dir.join(Self::CONFIG_FILENAME);
         let contents = fs::read_to_string(&config_path)
             .map_err(|e| format!("Failed to read config: {}", e))?;
-
         let config: RaceConfig =
             toml::from_str(&contents).map_err(|e| format!("Failed to parse config: {}", e))?;
+        Ok(config.server.seed_id)
+    }
 
-        info!(path = %config_path.display(), "Loaded race config");
-        Ok(config)
+    /// Write the settings editable from the in-game panel back to the config file.
+    ///
+    /// Only those keys are rewritten, so comments and other settings are preserved.
+    pub fn save_settings(&self, dir: &Path) -> Result<(), String> {
+        let config_path = Self::edit_file(dir, |doc| self.write_settings(doc))?;
+        info!(path = %config_path.display(), "Saved overlay settings and keybindings");
+        Ok(())
+    }
+
+    /// Write the `[layout]` section (window positions) back to the config file.
+    pub fn save_layout(&self, dir: &Path) -> Result<(), String> {
+        let config_path = Self::edit_file(dir, |doc| self.write_layout(doc))?;
+        info!(path = %config_path.display(), "Saved window layout");
+        Ok(())
+    }
+
+    /// Apply `edit` to the config file, keeping everything it doesn't touch
+    fn edit_file(
+        dir: &Path,
+        edit: impl FnOnce(&mut DocumentMut) -> Result<(), String>,
+    ) -> Result<PathBuf, String> {
+        let config_path = dir.join(Self::CONFIG_FILENAME);
+        let contents = fs::read_to_string(&config_path)
+            .map_err(|e| format!("Failed to read config: {}", e))?;
+        let mut doc = contents
+            .parse::<DocumentMut>()
+            .map_err(|e| format!("Failed to parse config: {}", e))?;
+        edit(&mut doc)?;
+        fs::write(&config_path, doc.to_string())
+            .map_err(|e| format!("Failed to write config: {}", e))?;
+        Ok(config_path)
+    }
+
+    fn write_settings(&self, doc: &mut DocumentMut) -> Result<(), String> {
+        let overlay = doc
+            .entry("overlay")
+            .or_insert(toml_edit::table())
+            .as_table_mut()
+            .ok_or_else(|| "[overlay] is not a table".to_string())?;
+        // Round so f32 noise (0.30000001) doesn't end up in the file
+        let round = |v: f32| (v as f64 * 100.0).round() / 100.0;
+        overlay["background_opacity"] = value(round(self.overlay.background_opacity));
+        overlay["font_size"] = value(round(self.overlay.font_size));
+        overlay["auto_scale"] = value(self.overlay.auto_scale);
+        overlay["scale"] = value(round(self.overlay.scale));
+        overlay["icon_scale"] = value(round(self.overlay.icon_scale));
+        overlay["theme"] = value(self.overlay.theme.as_str());
+
+        let keybindings = doc
+            .entry("keybindings")
+            .or_insert(toml_edit::table())
+            .as_table_mut()
+            .ok_or_else(|| "[keybindings] is not a table".to_string())?;
+        for (action, hotkey) in self.keybindings.actions() {
+            keybindings[action] = value(hotkey.name().to_lowercase());
+        }
+        Ok(())
+    }
+
+    fn write_layout(&self, doc: &mut DocumentMut) -> Result<(), String> {
+        let layout = doc
+            .entry("layout")
+            .or_insert(toml_edit::table())
+            .as_table_mut()
+            .ok_or_else(|| "[layout] is not a table".to_string())?;
+        // One [layout."WxH"] table per resolution, no empty [layout] header
+        layout.set_implicit(true);
+        for (resolution, windows) in &self.layout.0 {
+            let table = layout
+                .entry(resolution)
+                .or_insert(toml_edit::table())
+                .as_table_mut()
+                .ok_or_else(|| format!("[layout.\"{}\"] is not a table", resolution))?;
+            for (window, pos) in windows {
+                let pos: Array = pos.iter().map(|&v| f64::from(v)).collect();
+                table[window.as_str()] = value(pos);
+            }
+        }
+        Ok(())
     }
 
-    /// Check if config is valid for racing
-    pub fn is_valid(&self) -> bool {
-        !self.server.url.is_empty()
-            && !self.server.mod_token.is_empty()
-            && !self.server.race_id.is_empty()
+    /// Settings the mod can't race with: missing server credentials, a
+    /// malformed URL, unparsable colors. Empty when the config is usable.
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let server = &self.server;
+        let mut errors = check_server(&server.url, &server.mod_token, &server.race_id);
+        let overlay = &self.overlay;
+        let colors = [
+            ("overlay.background_color", &overlay.background_color),
+            ("overlay.text_color", &overlay.text_color),
+            ("overlay.text_disabled_color", &overlay.text_disabled_color),
+            ("overlay.border_color", &overlay.border_color),
+        ];
+        errors.extend(
+            colors
+                .into_iter()
+                .filter_map(|(field, value)| check_color(field, value)),
+        );
+        errors
     }
 }
@@ -0,0 +1,54 @@
+//! In-game list of config errors
+//!
+//! Installed instead of the tracker when `speedfog_race.toml` is missing or
+//! unusable: a window at the top of the screen lists every problem and stays
+//! there until the game is restarted with a fixed config. Drawn with the
+//! default imgui font and colors, since the overlay settings may be the ones
+//! that are broken.
+
+use hudhook::imgui::{Condition, Ui, WindowFlags};
+use hudhook::ImguiRenderLoop;
+
+use crate::core::config_error::ConfigError;
+
+const TITLE_COLOR: [f32; 4] = [1.0, 0.3, 0.3, 1.0];
+
+pub struct ConfigErrorOverlay {
+    errors: Vec<ConfigError>,
+}
+
+impl ConfigErrorOverlay {
+    pub fn new(errors: Vec<ConfigError>) -> Self {
+        Self { errors }
+    }
+}
+
+impl ImguiRenderLoop for ConfigErrorOverlay {
+    fn render(&mut self, ui: &mut Ui) {
+        let display_size = ui.io().display_size;
+        let wrap_width = (display_size[0] * 0.5).max(400.0);
+        ui.window("SpeedFog Racing##config_errors")
+            .position([display_size[0] * 0.5, 40.0], Condition::Always)
+            .position_pivot([0.5, 0.0])
+            .flags(
+                WindowFlags::NO_TITLE_BAR
+                    | WindowFlags::ALWAYS_AUTO_RESIZE
+                    | WindowFlags::NO_INPUTS
+                    | WindowFlags::NO_SAVED_SETTINGS,
+            )
+            .build(|| {
+                ui.text_colored(
+                    TITLE_COLOR,
+                    "SpeedFog Racing can't start: fix speedfog_race.toml",
+                );
+                ui.separator();
+                let _wrap = ui.push_text_wrap_pos_with_pos(ui.cursor_pos()[0] + wrap_width);
+                for error in &self.errors {
+                    ui.bullet();
+                    ui.text(error.to_string());
+                }
+                ui.separator();
+                ui.text_disabled("The file is next to the DLL. Restart the game once it's fixed.");
+            });
+    }
+}
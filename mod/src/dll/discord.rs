@@ -0,0 +1,154 @@
+//! Discord Rich Presence
+//!
+//! Publishes the race progress (race name, position, current zone, discovered
+//! gates) to the local Discord client. Discord IPC calls block, so they run on
+//! a worker thread fed through a channel; the tracker only pushes snapshots.
+
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{bounded, Receiver, Sender};
+use discord_rich_presence::{activity, DiscordIpc, DiscordIpcClient};
+use tracing::{debug, info, warn};
+
+use crate::core::post_race;
+use crate::core::presence::RacePresence;
+
+use super::config::DiscordSettings;
+use super::tracker::RaceTracker;
+
+/// How often the tracker pushes a presence snapshot. Unchanged snapshots are
+/// dropped by the worker, so this also bounds the reconnection attempts.
+pub(crate) const PRESENCE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Handle to the Rich Presence worker thread
+pub(crate) struct DiscordPresence {
+    tx: Sender<RacePresence>,
+}
+
+impl DiscordPresence {
+    /// Start the worker, or None if disabled in the config
+    pub fn start(settings: &DiscordSettings) -> Option<Self> {
+        if !settings.enabled {
+            return None;
+        }
+        if settings.application_id.trim().is_empty() {
+            warn!("[DISCORD] Rich Presence enabled but application_id is empty");
+            return None;
+        }
+
+        let (tx, rx) = bounded(4);
+        let application_id = settings.application_id.trim().to_string();
+        thread::spawn(move || presence_loop(application_id, rx));
+        info!("[DISCORD] Rich Presence enabled");
+        Some(Self { tx })
+    }
+
+    /// Queue a snapshot; dropped if the worker is busy (the next one supersedes it)
+    pub fn publish(&self, presence: RacePresence) {
+        let _ = self.tx.try_send(presence);
+    }
+}
+
+fn presence_loop(application_id: String, rx: Receiver<RacePresence>) {
+    let mut client: Option<DiscordIpcClient> = None;
+    let mut published: Option<RacePresence> = None;
+
+    // Ends when the tracker (and its Sender) is dropped
+    while let Ok(mut presence) = rx.recv() {
+        // Only the latest snapshot matters
+        while let Ok(newer) = rx.try_recv() {
+            presence = newer;
+        }
+        if client.is_some() && published.as_ref() == Some(&presence) {
+            continue;
+        }
+
+        if client.is_none() {
+            client = connect(&application_id);
+            if client.is_none() {
+                continue;
+            }
+        }
+        let Some(ref mut ipc) = client else {
+            continue;
+        };
+
+        match set_activity(ipc, &presence) {
+            Ok(()) => {
+                debug!(details = %presence.details(), state = %presence.state(), "[DISCORD] Presence updated");
+                published = Some(presence);
+            }
+            Err(e) => {
+                // Discord closed or restarted: reconnect on the next snapshot
+                warn!(error = %e, "[DISCORD] Failed to update presence");
+                let _ = ipc.close();
+                client = None;
+                published = None;
+            }
+        }
+    }
+
+    if let Some(mut ipc) = client {
+        let _ = ipc.clear_activity();
+        let _ = ipc.close();
+    }
+}
+
+fn connect(application_id: &str) -> Option<DiscordIpcClient> {
+    let mut client = match DiscordIpcClient::new(application_id) {
+        Ok(client) => client,
+        Err(e) => {
+            warn!(error = %e, "[DISCORD] Invalid application_id");
+            return None;
+        }
+    };
+    match client.connect() {
+        Ok(()) => {
+            info!("[DISCORD] Connected to Discord");
+            Some(client)
+        }
+        Err(e) => {
+            // Discord not running: not worth more than a debug line every interval
+            debug!(error = %e, "[DISCORD] Discord client not reachable");
+            None
+        }
+    }
+}
+
+fn set_activity(
+    client: &mut DiscordIpcClient,
+    presence: &RacePresence,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let details = presence.details();
+    let state = presence.state();
+    let mut activity = activity::Activity::new().details(&details).state(&state);
+    if let Some(start_ms) = presence.started_at_ms {
+        // Discord shows "elapsed" from this start (seconds since epoch)
+        activity = activity.timestamps(activity::Timestamps::new().start(start_ms / 1000));
+    }
+    client.set_activity(activity)
+}
+
+impl RaceTracker {
+    /// Current race progress for Rich Presence, None until auth_ok
+    pub(crate) fn race_presence(&self) -> Option<RacePresence> {
+        let race = self.race_info()?;
+        let placement = self
+            .my_participant_id()
+            .and_then(|id| post_race::placement(self.participants(), id));
+        let finish_event = self.finish_event;
+        Some(RacePresence {
+            race_name: race.name.clone(),
+            zone: self.current_zone_info().map(|z| z.display_name.clone()),
+            gates: self
+                .triggered_flags
+                .iter()
+                .filter(|&&flag| Some(flag) != finish_event)
+                .count(),
+            placement,
+            participant_count: self.participants().len(),
+            started_at_ms: self.race_state.started_at_ms,
+        })
+    }
+}
@@ -0,0 +1,130 @@
+//! Event log writer
+//!
+//! Keeps `core::event_wal` on disk. Every record is synced so a game crash
+//! loses none, which takes milliseconds on a slow disk: the writes run on a
+//! worker thread fed through a channel, and the records queued while one is
+//! being synced go out together in the next sync. Writes are numbered
+//! (tickets) so the tracker can hold a flag until its record is synced.
+
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use tracing::warn;
+
+enum LogWrite {
+    /// One record, appended
+    Append(String),
+    /// The whole log, replacing the file
+    Replace(String),
+}
+
+/// Handle to the event log worker thread
+pub(crate) struct EventLogWriter {
+    tx: Sender<(u64, LogWrite)>,
+    /// Ticket of the last write queued
+    queued: u64,
+    /// Ticket of the last write the worker is done with
+    done: Arc<AtomicU64>,
+}
+
+impl EventLogWriter {
+    /// Start the worker writing to `path`
+    pub fn start(path: PathBuf) -> Self {
+        // Unbounded: a record must never be dropped, and they are rare
+        let (tx, rx) = unbounded();
+        let done = Arc::new(AtomicU64::new(0));
+        let worker_done = Arc::clone(&done);
+        thread::spawn(move || writer_loop(&path, rx, &worker_done));
+        Self {
+            tx,
+            queued: 0,
+            done,
+        }
+    }
+
+    /// Queue a record to append
+    pub fn append(&mut self, line: String) {
+        self.queue(LogWrite::Append(line));
+    }
+
+    /// Queue a rewrite of the whole log (compaction)
+    pub fn replace(&mut self, contents: String) {
+        self.queue(LogWrite::Replace(contents));
+    }
+
+    /// Ticket of the last write queued: once it is done, so is every record
+    /// queued before it
+    pub fn last_ticket(&self) -> u64 {
+        self.queued
+    }
+
+    /// Whether the write of `ticket` is synced. A write that failed counts as
+    /// done too: a broken disk must not hold the flags back for good.
+    pub fn is_done(&self, ticket: u64) -> bool {
+        self.done.load(Ordering::Acquire) >= ticket
+    }
+
+    fn queue(&mut self, write: LogWrite) {
+        self.queued += 1;
+        if self.tx.send((self.queued, write)).is_err() {
+            // Worker gone: nothing will ever sync it
+            self.done.store(self.queued, Ordering::Release);
+        }
+    }
+}
+
+fn writer_loop(path: &Path, rx: Receiver<(u64, LogWrite)>, done: &AtomicU64) {
+    // Ends when the tracker (and its Sender) is dropped, once the queue is drained
+    while let Ok(first) = rx.recv() {
+        let mut last = 0;
+        let mut replace: Option<String> = None;
+        let mut lines: Vec<String> = Vec::new();
+        for (ticket, write) in std::iter::once(first).chain(rx.try_iter()) {
+            last = ticket;
+            match write {
+                LogWrite::Append(line) => lines.push(line),
+                LogWrite::Replace(contents) => {
+                    // Supersedes everything queued before it
+                    replace = Some(contents);
+                    lines.clear();
+                }
+            }
+        }
+        if let Err(e) = write_batch(path, replace.as_deref(), &lines) {
+            warn!(error = %e, path = %path.display(), "[RACE] Failed to write the event log");
+        }
+        done.store(last, Ordering::Release);
+    }
+}
+
+fn write_batch(path: &Path, replace: Option<&str>, lines: &[String]) -> io::Result<()> {
+    if let Some(contents) = replace {
+        replace_file(path, contents)?;
+    }
+    if lines.is_empty() {
+        return Ok(());
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for line in lines {
+        writeln!(file, "{}", line)?;
+    }
+    file.sync_data()
+}
+
+/// Replace the log through a synced `<name>.tmp` renamed over it, so a crash
+/// mid-write leaves either the old log or the new one, never a truncated file
+pub(crate) fn replace_file(path: &Path, contents: &str) -> io::Result<()> {
+    let mut tmp = OsString::from(path.as_os_str());
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let mut file = File::create(&tmp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_data()?;
+    fs::rename(&tmp, path)
+}
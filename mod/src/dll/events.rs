@@ -0,0 +1,275 @@
+//! Race events
+//!
+//! `RaceTracker::update` reads the game and publishes what it observed on the
+//! tracker's event bus. Once the game-facing part of the frame is done, the
+//! queue is drained and every event goes through the subscribers in order:
+//! logger, race record (boss kills, splits, route), websocket sender, overlay
+//! and Rich Presence. New consumers hook in here instead of in `update`.
+
+use std::time::Instant;
+
+use tracing::{error, info};
+
+use crate::core::audio::AudioCue;
+use crate::core::detection_health::{FilterReason, IncidentKind};
+use crate::core::post_race::ZoneVisit;
+use crate::core::toast::ToastKind;
+use crate::core::zone_graph::Transport;
+
+#[cfg(feature = "discord")]
+use super::discord::PRESENCE_INTERVAL;
+use super::tracker::{RaceTracker, ZoneUpdateData};
+
+/// Where a newly set event flag was caught
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FlagSource {
+    /// Regular 10Hz poll
+    Poll,
+    /// Forced scan when a loading screen ends (flags set while loading)
+    LoadingExit,
+}
+
+/// Something the frame update observed in the game
+#[derive(Debug)]
+pub(crate) enum RaceEvent {
+    /// One of the seed's event flags became set
+    FlagTriggered {
+        flag_id: u32,
+        igt_ms: u32,
+        source: FlagSource,
+    },
+    /// The player position became readable again after a loading screen
+    LoadingExited { igt_ms: u32 },
+    /// The zone update received during the loading screen is now shown
+    ZoneRevealed {
+        zone: Box<ZoneUpdateData>,
+        igt_ms: u32,
+    },
+}
+
+impl RaceTracker {
+    /// Queue an event for the end-of-frame dispatch
+    pub(crate) fn publish(&mut self, event: RaceEvent) {
+        if !self.events.publish(event) {
+            error!(
+                dropped = self.events.dropped(),
+                "[EVENTS] Event queue full, event dropped"
+            );
+        }
+    }
+
+    /// Hand the queued events to every subscriber, oldest first
+    pub(crate) fn dispatch_events(&mut self) {
+        if self.events.is_empty() {
+            return;
+        }
+        let events: Vec<RaceEvent> = self.events.drain().collect();
+        self.auto_hide.wake(Instant::now());
+        for event in events {
+            log_event(&event);
+            self.record_event(&event);
+            self.send_event(&event);
+            #[cfg(feature = "discord")]
+            if matches!(event, RaceEvent::ZoneRevealed { .. }) {
+                // Refresh the presence this frame instead of on the next tick
+                self.last_presence_update = Instant::now() - PRESENCE_INTERVAL;
+            }
+            self.show_event(event);
+        }
+    }
+
+    /// Race record: boss kills, local finish, splits, transport and route
+    fn record_event(&mut self, event: &RaceEvent) {
+        match event {
+            RaceEvent::FlagTriggered {
+                flag_id, igt_ms, ..
+            } => {
+                if self.finish_event == Some(*flag_id) {
+                    self.record_boss_kill(*flag_id, *igt_ms);
+                    self.capture_local_finish(*igt_ms);
+                }
+            }
+            RaceEvent::LoadingExited { .. } => {
+                self.last_transport = if !self.deferred_event_flags.is_empty() {
+                    Transport::FogGate
+                } else if crate::eldenring::warp_hook::get_captured_grace_entity_id() != 0 {
+                    Transport::FastTravel
+                } else {
+                    Transport::Respawn
+                };
+            }
+            RaceEvent::ZoneRevealed { zone, igt_ms } => {
+                self.record_split(zone);
+                self.record_segments(&zone.node_id, *igt_ms);
+                self.zone_log.push(ZoneVisit {
+                    name: zone.display_name.clone(),
+                    tier: zone.tier,
+                    igt_ms: *igt_ms,
+                });
+                self.zone_graph.visit(
+                    &zone.node_id,
+                    &zone.display_name,
+                    zone.tier,
+                    &zone.exits,
+                    self.last_transport,
+                    *igt_ms,
+                );
+            }
+        }
+    }
+
+    /// Websocket sender: event flags (finish now, others at the next loading
+    /// exit) and zone queries when no fog gate was crossed
+    fn send_event(&mut self, event: &RaceEvent) {
+        let can_send =
+            self.ws_client.is_connected() && self.is_race_running() && !self.am_i_finished();
+        match *event {
+            RaceEvent::FlagTriggered {
+                flag_id,
+                igt_ms,
+                source,
+            } => {
+                if self.finish_event != Some(flag_id) {
+                    // Regular fog gate → defer until loading exit
+                    self.deferred_event_flags.push((flag_id, igt_ms));
+                    if !self.ws_client.is_connected()
+                        && self.is_race_running()
+                        && !self.am_i_finished()
+                    {
+                        // Still at the gate: its position picks the exit
+                        self.resolve_offline_flag(flag_id);
+                    }
+                    return;
+                }
+                // finish_event: no loading screen → send immediately
+                if can_send {
+                    self.send_event_flag(flag_id, igt_ms);
+                    let tag = match source {
+                        FlagSource::Poll => "finish",
+                        FlagSource::LoadingExit => "finish/loading-exit",
+                    };
+                    info!(flag_id, source = tag, "[RACE] Finish event sent");
+                } else if !self.am_i_finished() {
+                    self.pending_event_flags.push((flag_id, igt_ms));
+                }
+            }
+            RaceEvent::LoadingExited { .. } => {
+                let grace_id = crate::eldenring::warp_hook::get_captured_grace_entity_id();
+                self.zone_flag = None;
+                if !can_send {
+                    // Not connected or race not running — clean up
+                    let reason = if !self.ws_client.is_connected() {
+                        FilterReason::Offline
+                    } else if !self.is_race_running() {
+                        FilterReason::RaceNotRunning
+                    } else {
+                        FilterReason::Finished
+                    };
+                    for (flag_id, _) in std::mem::take(&mut self.deferred_event_flags) {
+                        // Outside a race (practice) nothing is expected to be sent
+                        if self.race_info().is_some() {
+                            self.record_incident(IncidentKind::DiscoveryFiltered {
+                                flag_id,
+                                reason,
+                            });
+                        }
+                    }
+                    if grace_id > 0 {
+                        crate::eldenring::warp_hook::clear_captured_grace_entity_id();
+                    }
+                    // Offline fog gate: show the zone from the cache
+                    if let Some(zone) = self.take_offline_zone() {
+                        self.pending_zone_update = Some(zone);
+                    }
+                } else if !self.deferred_event_flags.is_empty() {
+                    // Fog gate traversal — send deferred flags now that loading is done
+                    for (flag_id, igt_ms) in std::mem::take(&mut self.deferred_event_flags) {
+                        self.send_event_flag(flag_id, igt_ms);
+                        info!(flag_id, "[RACE] Deferred event flag sent at loading exit");
+                        // The zone_update that follows is where this gate leads
+                        self.zone_flag = Some(flag_id);
+                    }
+                } else {
+                    // No fog gate — death/respawn/quit-out/fast-travel
+                    self.send_zone_query(grace_id);
+                    if grace_id > 0 {
+                        crate::eldenring::warp_hook::clear_captured_grace_entity_id();
+                    }
+                }
+            }
+            RaceEvent::ZoneRevealed { .. } => {}
+        }
+    }
+
+    /// Ask the server which zone the player respawned or warped into
+    pub(crate) fn send_zone_query(&mut self, grace_id: u32) {
+        let pos = self.read_position();
+        let grace_opt = if grace_id > 0 { Some(grace_id) } else { None };
+        let map_id = pos.as_ref().map(|p| p.map_id_str.clone());
+        let position = pos.as_ref().map(|p| [p.x, p.y, p.z]);
+        let play_region_id = pos.as_ref().and_then(|p| p.play_region_id);
+
+        if grace_opt.is_some() || map_id.is_some() {
+            self.ws_client
+                .send_zone_query(grace_opt, map_id.clone(), position, play_region_id);
+            info!(?grace_opt, "[RACE] Zone query sent at loading exit");
+        }
+        if let (Some(map_id), Some(position)) = (&map_id, position) {
+            let sent_at = self.send_clock();
+            self.zone_confirmation.query_sent(map_id, position, sent_at);
+        }
+    }
+
+    /// Overlay: toasts and the displayed zone
+    fn show_event(&mut self, event: RaceEvent) {
+        let RaceEvent::ZoneRevealed { zone, .. } = event else {
+            return;
+        };
+        let mut zone = *zone;
+        if zone.newly_discovered {
+            self.audio.play(AudioCue::Discovery);
+            self.notify(
+                ToastKind::Discovery,
+                self.locale
+                    .format("toast.discovered", &[("zone", &zone.display_name)]),
+            );
+        } else {
+            self.audio.play(AudioCue::ZoneReveal);
+            self.notify(ToastKind::Zone, zone.display_name.clone());
+        }
+        // Progression is still tracked when the race hides scaling info
+        zone.scaling_warning = self
+            .progression
+            .enter(zone.tier, self.config.scaling.warn_tier_delta)
+            .filter(|_| self.permissions.scaling_info());
+        if let Some(w) = zone.scaling_warning {
+            info!(
+                tier = w.tier,
+                delta = w.delta,
+                "[RACE] Zone scaled above progression"
+            );
+            self.notify(
+                ToastKind::Warning,
+                self.locale
+                    .format("toast.scaling", &[("tier", &w.tier), ("delta", &w.delta)]),
+            );
+        }
+        self.race_state.current_zone = Some(zone);
+    }
+}
+
+/// Logger: one line per event
+fn log_event(event: &RaceEvent) {
+    match event {
+        RaceEvent::FlagTriggered {
+            flag_id, source, ..
+        } => match source {
+            FlagSource::Poll => info!(flag_id, "[RACE] Event flag set"),
+            FlagSource::LoadingExit => info!(flag_id, "[RACE] Event flag caught at loading exit"),
+        },
+        RaceEvent::LoadingExited { igt_ms } => info!(igt_ms, "[RACE] Loading screen exited"),
+        RaceEvent::ZoneRevealed { zone, .. } => {
+            info!(name = %zone.display_name, "[RACE] Zone revealed")
+        }
+    }
+}
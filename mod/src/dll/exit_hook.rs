@@ -0,0 +1,137 @@
+//! Clean shutdown on game exit
+//!
+//! Closing the game (Alt+F4, "Quit Game", Windows logoff) ends the process
+//! soon after the window gets WM_CLOSE: the tracker never runs again and the
+//! server would show the runner frozen mid-race until its heartbeat times
+//! out. The game window is subclassed to catch the close: the tracker gets a
+//! frame to queue what it hasn't sent yet (unacknowledged discoveries, the
+//! current IGT), then the last status_update goes out with `disconnecting`
+//! set and the socket is closed. Each step is bounded so quitting never hangs.
+
+use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tracing::{info, warn};
+use windows::Win32::Foundation::{BOOL, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::Threading::GetCurrentProcessId;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallWindowProcW, DefWindowProcW, EnumWindows, GetWindow, GetWindowThreadProcessId,
+    IsWindowVisible, SetWindowLongPtrW, GWLP_WNDPROC, GW_OWNER, WM_CLOSE, WM_QUERYENDSESSION,
+    WNDPROC,
+};
+
+use super::websocket::ExitHandle;
+use crate::core::exit_signal::ExitSignal;
+
+/// Time given to the tracker to queue what it hasn't sent yet. Runs out
+/// when the window and render threads are the same one.
+const TRACKER_FLUSH_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Time given to the WebSocket worker to send its queue and close the socket
+const SOCKET_FLUSH_TIMEOUT: Duration = Duration::from_millis(1500);
+
+static EXIT_REQUESTED: AtomicBool = AtomicBool::new(false);
+static TRACKER_FLUSHED: ExitSignal = ExitSignal::new();
+/// Last status (igt_ms, death_count) queued by the tracker's flush
+static FINAL_STATUS: Mutex<Option<(u32, u32)>> = Mutex::new(None);
+static SOCKET: Mutex<Option<ExitHandle>> = Mutex::new(None);
+static ORIGINAL_WNDPROC: AtomicIsize = AtomicIsize::new(0);
+
+/// Subclass the game window. Returns false while it doesn't exist yet.
+pub fn install() -> bool {
+    if ORIGINAL_WNDPROC.load(Ordering::SeqCst) != 0 {
+        return true;
+    }
+    let Some(hwnd) = find_game_window() else {
+        return false;
+    };
+    // SAFETY: the window belongs to this process and outlives the DLL;
+    // exit_wndproc forwards every message to the procedure it replaces.
+    let original = unsafe { SetWindowLongPtrW(hwnd, GWLP_WNDPROC, exit_wndproc as isize) };
+    if original == 0 {
+        warn!("[EXIT] Failed to hook the game window, no flush on exit");
+        // Don't retry every frame
+        ORIGINAL_WNDPROC.store(-1, Ordering::SeqCst);
+        return true;
+    }
+    ORIGINAL_WNDPROC.store(original, Ordering::SeqCst);
+    info!("[EXIT] Game window hooked");
+    true
+}
+
+/// Connection to close on exit (refreshed when the worker is restarted)
+pub fn set_socket(handle: Option<ExitHandle>) {
+    if let Ok(mut socket) = SOCKET.lock() {
+        *socket = handle;
+    }
+}
+
+/// The game is closing: the tracker should flush, then call `tracker_flushed`
+pub fn exit_requested() -> bool {
+    EXIT_REQUESTED.load(Ordering::SeqCst)
+}
+
+/// The tracker queued everything; `status` goes out as the last status_update
+pub fn tracker_flushed(status: Option<(u32, u32)>) {
+    if let Ok(mut last) = FINAL_STATUS.lock() {
+        *last = status;
+    }
+    TRACKER_FLUSHED.set();
+}
+
+/// Runs on the window thread, before the message reaches the game
+fn flush() {
+    if EXIT_REQUESTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    info!("[EXIT] Game closing, flushing race state");
+    if !TRACKER_FLUSHED.wait(TRACKER_FLUSH_TIMEOUT) {
+        warn!("[EXIT] Tracker didn't flush in time");
+    }
+    let status = FINAL_STATUS.lock().ok().and_then(|s| *s);
+    let socket = SOCKET.lock().ok().and_then(|s| s.clone());
+    if let Some(socket) = socket {
+        if !socket.quit(status, SOCKET_FLUSH_TIMEOUT) {
+            warn!("[EXIT] Connection not closed in time");
+        }
+    }
+}
+
+unsafe extern "system" fn exit_wndproc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_CLOSE || msg == WM_QUERYENDSESSION {
+        flush();
+    }
+    let original = ORIGINAL_WNDPROC.load(Ordering::SeqCst);
+    if original <= 0 {
+        return DefWindowProcW(hwnd, msg, wparam, lparam);
+    }
+    // SAFETY: `original` is the non-null procedure returned by SetWindowLongPtrW
+    let original: WNDPROC = std::mem::transmute(original);
+    CallWindowProcW(original, hwnd, msg, wparam, lparam)
+}
+
+/// Visible top-level window of this process
+pub(crate) fn find_game_window() -> Option<HWND> {
+    unsafe extern "system" fn visit(hwnd: HWND, found: LPARAM) -> BOOL {
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        let owned = GetWindow(hwnd, GW_OWNER).0 != 0;
+        if pid == GetCurrentProcessId() && !owned && IsWindowVisible(hwnd).as_bool() {
+            *(found.0 as *mut HWND) = hwnd;
+            return BOOL(0);
+        }
+        BOOL(1)
+    }
+
+    let mut found = HWND::default();
+    // SAFETY: `found` outlives the enumeration; stopping early makes
+    // EnumWindows return an error, which is expected.
+    let _ = unsafe { EnumWindows(Some(visit), LPARAM(&mut found as *mut HWND as isize)) };
+    (found.0 != 0).then_some(found)
+}
@@ -5,6 +5,8 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use windows::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
 
+use crate::core::key_chord::{parse_chord, ChordLabel, Modifiers};
+
 // =============================================================================
 // KEY STATE CACHE
 // =============================================================================
@@ -16,6 +18,8 @@ thread_local! {
 struct KeyStateCache {
     states: HashMap<i32, (bool, bool)>,
     frame: u64,
+    /// Hotkeys don't fire while the settings panel captures a new binding
+    suspended: bool,
 }
 
 impl KeyStateCache {
@@ -23,6 +27,7 @@ impl KeyStateCache {
         Self {
             states: HashMap::new(),
             frame: 0,
+            suspended: false,
         }
     }
 
@@ -52,6 +57,69 @@ fn get_cached_key_state(key_code: i32) -> (bool, bool) {
     KEY_STATE_CACHE.with(|cache| cache.borrow_mut().get_key_state(key_code))
 }
 
+/// Stop (or resume) every hotkey, e.g. while capturing a new binding
+pub fn set_hotkeys_suspended(suspended: bool) {
+    KEY_STATE_CACHE.with(|cache| cache.borrow_mut().suspended = suspended);
+}
+
+fn hotkeys_suspended() -> bool {
+    KEY_STATE_CACHE.with(|cache| cache.borrow().suspended)
+}
+
+const VK_SHIFT: i32 = 0x10;
+const VK_CONTROL: i32 = 0x11;
+const VK_MENU: i32 = 0x12;
+const VK_ESCAPE: i32 = 0x1B;
+
+/// Modifiers held this frame
+fn held_modifiers() -> Modifiers {
+    let held = |key| get_cached_key_state(key).1;
+    Modifiers {
+        ctrl: held(VK_CONTROL),
+        shift: held(VK_SHIFT),
+        alt: held(VK_MENU),
+    }
+}
+
+// =============================================================================
+// KEY CAPTURE
+// =============================================================================
+
+/// Outcome of a frame of "press a key" capture
+pub enum KeyCapture {
+    /// Still waiting for a key
+    Waiting,
+    Pressed(Hotkey),
+    /// Escape pressed
+    Cancelled,
+}
+
+/// Forget key presses made before the capture started (GetAsyncKeyState
+/// remembers the last press until it is queried)
+pub fn start_key_capture() {
+    for &(_, code) in KEY_MAPPINGS {
+        get_cached_key_state(code);
+    }
+}
+
+/// Look for a key pressed this frame, with the modifiers held along with it
+pub fn poll_key_capture() -> KeyCapture {
+    if get_cached_key_state(VK_ESCAPE).0 {
+        return KeyCapture::Cancelled;
+    }
+    let pressed = KEY_MAPPINGS
+        .iter()
+        .map(|&(_, code)| code)
+        .find(|&code| code != VK_ESCAPE && get_cached_key_state(code).0);
+    match pressed {
+        Some(key) => KeyCapture::Pressed(Hotkey {
+            key,
+            modifiers: held_modifiers(),
+        }),
+        None => KeyCapture::Waiting,
+    }
+}
+
 // =============================================================================
 // KEY CODE MAPPING
 // =============================================================================
@@ -202,22 +270,55 @@ fn keycode_to_name(code: i32) -> &'static str {
 // HOTKEY TYPE
 // =============================================================================
 
-/// A simple hotkey (single key, no modifiers for Phase 1)
-#[derive(Debug, Clone, Copy)]
+/// A key, optionally with Ctrl / Shift / Alt held
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Hotkey {
     pub key: i32,
+    pub modifiers: Modifiers,
 }
 
 impl Hotkey {
-    /// Create a hotkey from a key name (e.g., "f9")
+    /// A key without modifiers
+    pub const fn new(key: i32) -> Self {
+        Hotkey {
+            key,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    /// Create a hotkey from a key name or chord (e.g., "f9", "ctrl+f9")
     pub fn from_name(name: &str) -> Option<Self> {
-        name_to_keycode(name).map(|key| Hotkey { key })
+        let (modifiers, key) = parse_chord(name)?;
+        name_to_keycode(key).map(|key| Hotkey { key, modifiers })
+    }
+
+    /// Key name for display (e.g., "F9", "Ctrl+F9")
+    pub fn name(&self) -> String {
+        ChordLabel {
+            modifiers: self.modifiers,
+            key: keycode_to_name(self.key),
+        }
+        .to_string()
     }
 
-    /// Check if this hotkey was just pressed
+    /// Check if this hotkey was just pressed, with exactly its modifiers held
+    /// (so Ctrl+F9 doesn't also fire F9)
     pub fn is_just_pressed(&self) -> bool {
+        if hotkeys_suspended() {
+            return false;
+        }
         let (just_pressed, _) = get_cached_key_state(self.key);
-        just_pressed
+        just_pressed && held_modifiers() == self.modifiers
+    }
+
+    /// Check if this hotkey is down this frame, with exactly its modifiers
+    /// held (for hold-to-confirm actions)
+    pub fn is_held(&self) -> bool {
+        if hotkeys_suspended() {
+            return false;
+        }
+        let (_, is_held) = get_cached_key_state(self.key);
+        is_held && held_modifiers() == self.modifiers
     }
 }
 
@@ -226,7 +327,7 @@ impl Serialize for Hotkey {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&keycode_to_name(self.key).to_lowercase())
+        serializer.serialize_str(&self.name().to_lowercase())
     }
 }
 
@@ -243,6 +344,6 @@ impl<'de> Deserialize<'de> for Hotkey {
 
 impl Default for Hotkey {
     fn default() -> Self {
-        Hotkey { key: 0x78 } // F9
+        Hotkey::new(0x78) // F9
     }
 }
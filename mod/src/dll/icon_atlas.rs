@@ -0,0 +1,208 @@
+//! Icon atlas textures for the overlay
+//!
+//! The built-in atlas is the embedded death icon. Players can restyle icons by
+//! dropping `icons.png` + `icons.toml` (UV map, see `core::icon_map`) next to the
+//! DLL. Icons declared in the user atlas take precedence over built-in ones, and
+//! both files are watched so edits show up without restarting the game.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use hudhook::imgui::TextureId;
+use hudhook::RenderContext;
+use tracing::{debug, error, info};
+
+use crate::core::icon_map::{IconMap, IconUv};
+
+const DEATH_PNG: &[u8] = include_bytes!("../../assets/death.png");
+
+/// User atlas image, looked up in the DLL directory
+pub const ATLAS_PNG_FILENAME: &str = "icons.png";
+/// User atlas UV map, looked up in the DLL directory
+pub const ATLAS_MAP_FILENAME: &str = "icons.toml";
+
+/// How often the user atlas files are checked for modification
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A GPU texture with its named icon regions.
+struct AtlasTexture {
+    texture_id: TextureId,
+    icons: HashMap<String, IconUv>,
+}
+
+/// Built-in icons plus an optional user-provided atlas.
+pub struct IconAtlas {
+    builtin: AtlasTexture,
+    user: Option<AtlasTexture>,
+    /// GPU texture backing the user atlas, kept across reloads and removals so
+    /// it is overwritten in place rather than leaked
+    user_texture: Option<TextureId>,
+}
+
+impl IconAtlas {
+    /// Upload the built-in icons, then the user atlas from `dll_dir` if present.
+    ///
+    /// A broken user atlas is logged and ignored — the built-in icons still load.
+    pub fn load(
+        render_context: &mut dyn RenderContext,
+        dll_dir: Option<&Path>,
+    ) -> Result<Self, String> {
+        info!("Loading death icon texture");
+        let (rgba, width, height) = decode_png(DEATH_PNG)?;
+        let texture_id = render_context
+            .load_texture(&rgba, width, height)
+            .map_err(|e| format!("Failed to load death icon texture: {:?}", e))?;
+        let builtin = AtlasTexture {
+            texture_id,
+            icons: HashMap::from([("death".to_string(), IconUv::FULL)]),
+        };
+
+        let mut atlas = Self {
+            builtin,
+            user: None,
+            user_texture: None,
+        };
+        if let Some(dir) = dll_dir {
+            atlas.reload_user(render_context, dir);
+        }
+        Ok(atlas)
+    }
+
+    /// (Re)load the user atlas. Keeps the previous one if the new files are invalid.
+    pub fn reload_user(&mut self, render_context: &mut dyn RenderContext, dll_dir: &Path) {
+        let png_path = dll_dir.join(ATLAS_PNG_FILENAME);
+        let map_path = dll_dir.join(ATLAS_MAP_FILENAME);
+        if !png_path.exists() || !map_path.exists() {
+            if self.user.take().is_some() {
+                info!("User icon atlas removed, using built-in icons");
+            }
+            return;
+        }
+
+        match load_user_atlas(render_context, self.user_texture, &png_path, &map_path) {
+            Ok(user) => {
+                self.user_texture = Some(user.texture_id);
+                info!(
+                    path = %png_path.display(),
+                    icons = user.icons.len(),
+                    "Loaded user icon atlas"
+                );
+                self.user = Some(user);
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to load user icon atlas");
+            }
+        }
+    }
+
+    /// Look up a named icon: user atlas first, then built-in icons.
+    pub fn icon(&self, name: &str) -> Option<(TextureId, IconUv)> {
+        self.user
+            .iter()
+            .chain(std::iter::once(&self.builtin))
+            .find_map(|atlas| atlas.icons.get(name).map(|uv| (atlas.texture_id, *uv)))
+    }
+}
+
+fn load_user_atlas(
+    render_context: &mut dyn RenderContext,
+    existing: Option<TextureId>,
+    png_path: &Path,
+    map_path: &Path,
+) -> Result<AtlasTexture, String> {
+    let map_contents = fs::read_to_string(map_path)
+        .map_err(|e| format!("Failed to read {}: {}", map_path.display(), e))?;
+    let map = IconMap::parse(&map_contents)?;
+
+    let png =
+        fs::read(png_path).map_err(|e| format!("Failed to read {}: {}", png_path.display(), e))?;
+    let (rgba, width, height) = decode_png(&png)?;
+    let icons = map.resolve(width, height)?;
+
+    // Hot reloads overwrite the texture uploaded the first time instead of
+    // allocating a new one each time the player saves their atlas.
+    let texture_id = match existing {
+        Some(texture_id) => render_context
+            .replace_texture(texture_id, &rgba, width, height)
+            .map(|()| texture_id)
+            .map_err(|e| format!("Failed to replace icon atlas texture: {:?}", e))?,
+        None => render_context
+            .load_texture(&rgba, width, height)
+            .map_err(|e| format!("Failed to load icon atlas texture: {:?}", e))?,
+    };
+
+    Ok(AtlasTexture { texture_id, icons })
+}
+
+/// Decode a PNG into raw RGBA8 pixels.
+pub(crate) fn decode_png(bytes: &[u8]) -> Result<(Vec<u8>, u32, u32), String> {
+    use image::ImageReader;
+    use std::io::Cursor;
+
+    let img = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to guess format: {}", e))?
+        .decode()
+        .map_err(|e| format!("Failed to decode PNG: {}", e))?;
+
+    let rgba = img.to_rgba8();
+    let (width, height) = rgba.dimensions();
+    let raw_data = rgba.into_raw();
+
+    debug!(width, height, bytes = raw_data.len(), "Decoded PNG");
+
+    Ok((raw_data, width, height))
+}
+
+// =============================================================================
+// HOT RELOAD
+// =============================================================================
+
+/// Watches the user atlas files and reports when they change on disk.
+pub struct AtlasWatcher {
+    dll_dir: Option<PathBuf>,
+    last_check: Instant,
+    last_stamp: Option<(SystemTime, SystemTime)>,
+}
+
+impl AtlasWatcher {
+    pub fn new(dll_dir: Option<PathBuf>) -> Self {
+        let last_stamp = dll_dir.as_deref().and_then(atlas_stamp);
+        Self {
+            dll_dir,
+            last_check: Instant::now(),
+            last_stamp,
+        }
+    }
+
+    pub fn dll_dir(&self) -> Option<&Path> {
+        self.dll_dir.as_deref()
+    }
+
+    /// Returns true once per modification (or creation/removal) of the atlas files.
+    /// Throttled to one filesystem check per second.
+    pub fn poll_changed(&mut self) -> bool {
+        if self.last_check.elapsed() < RELOAD_CHECK_INTERVAL {
+            return false;
+        }
+        self.last_check = Instant::now();
+
+        let Some(dir) = self.dll_dir.as_deref() else {
+            return false;
+        };
+        let stamp = atlas_stamp(dir);
+        if stamp != self.last_stamp {
+            self.last_stamp = stamp;
+            return true;
+        }
+        false
+    }
+}
+
+/// Modification times of (png, toml), or None if either file is missing.
+fn atlas_stamp(dir: &Path) -> Option<(SystemTime, SystemTime)> {
+    let mtime = |name: &str| fs::metadata(dir.join(name)).and_then(|m| m.modified()).ok();
+    Some((mtime(ATLAS_PNG_FILENAME)?, mtime(ATLAS_MAP_FILENAME)?))
+}
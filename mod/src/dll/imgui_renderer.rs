@@ -0,0 +1,69 @@
+//! `OverlayRenderer` backed by an imgui frame
+
+use hudhook::imgui::{Image, TextureId, Ui};
+
+use crate::core::overlay_renderer::{OverlayRenderer, Rgba, TextureHandle};
+
+/// Draws into the current imgui window
+pub struct ImguiRenderer<'ui> {
+    ui: &'ui Ui,
+}
+
+impl<'ui> ImguiRenderer<'ui> {
+    pub fn new(ui: &'ui Ui) -> Self {
+        Self { ui }
+    }
+}
+
+impl OverlayRenderer for ImguiRenderer<'_> {
+    fn text(&mut self, text: &str) {
+        self.ui.text(text);
+    }
+
+    fn text_colored(&mut self, color: Rgba, text: &str) {
+        self.ui.text_colored(color, text);
+    }
+
+    fn text_disabled(&mut self, text: &str) {
+        self.ui.text_disabled(text);
+    }
+
+    fn image(&mut self, texture: TextureHandle, size: [f32; 2], uv0: [f32; 2], uv1: [f32; 2]) {
+        Image::new(TextureId::new(texture.0), size)
+            .uv0(uv0)
+            .uv1(uv1)
+            .build(self.ui);
+    }
+
+    fn same_line(&mut self, spacing: f32) {
+        self.ui.same_line_with_spacing(0.0, spacing);
+    }
+
+    fn same_line_at(&mut self, x: f32) {
+        self.ui.same_line_with_pos(x);
+    }
+
+    fn separator(&mut self) {
+        self.ui.separator();
+    }
+
+    fn text_width(&self, text: &str) -> f32 {
+        self.ui.calc_text_size(text)[0]
+    }
+
+    fn line_height(&self) -> f32 {
+        self.ui.text_line_height()
+    }
+
+    fn item_hovered(&self) -> bool {
+        self.ui.is_item_hovered()
+    }
+
+    fn item_clicked(&self) -> bool {
+        self.ui.is_item_clicked()
+    }
+
+    fn tooltip(&mut self, text: &str) {
+        self.ui.tooltip_text(text);
+    }
+}
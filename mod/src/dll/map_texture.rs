@@ -0,0 +1,62 @@
+//! World-map textures for the position thumbnail
+//!
+//! The map images are game assets and don't ship with the mod: players drop
+//! them next to the DLL under the names given in the calibration table (see
+//! `core::world_map`). Areas without an image get a plain grid instead.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use hudhook::imgui::TextureId;
+use hudhook::RenderContext;
+use tracing::{error, info};
+
+use super::icon_atlas::decode_png;
+
+use crate::core::world_map::WorldMapTable;
+
+/// Uploaded map images, by overworld area
+#[derive(Default)]
+pub struct WorldMapTextures {
+    textures: HashMap<u8, TextureId>,
+}
+
+impl WorldMapTextures {
+    /// Upload the image of every calibrated area found in `dll_dir`.
+    ///
+    /// Missing images are skipped silently, broken ones are logged.
+    pub fn load(
+        render_context: &mut dyn RenderContext,
+        dll_dir: &Path,
+        table: &WorldMapTable,
+    ) -> Self {
+        let mut textures = HashMap::new();
+        for calibration in &table.areas {
+            let path = dll_dir.join(&calibration.texture);
+            if !path.exists() {
+                continue;
+            }
+            match load_texture(render_context, &path) {
+                Ok(texture_id) => {
+                    info!(area = calibration.area, path = %path.display(), "Loaded world map");
+                    textures.insert(calibration.area, texture_id);
+                }
+                Err(e) => error!(error = %e, "Failed to load world map"),
+            }
+        }
+        Self { textures }
+    }
+
+    pub fn texture(&self, area: u8) -> Option<TextureId> {
+        self.textures.get(&area).copied()
+    }
+}
+
+fn load_texture(render_context: &mut dyn RenderContext, path: &Path) -> Result<TextureId, String> {
+    let png = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let (rgba, width, height) = decode_png(&png)?;
+    render_context
+        .load_texture(&rgba, width, height)
+        .map_err(|e| format!("Failed to load world map texture: {:?}", e))
+}
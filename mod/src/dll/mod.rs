@@ -1,11 +1,26 @@
 //! DLL module - SpeedFog Racing mod
 
+pub mod audio;
 pub mod config;
-pub mod death_icon;
+pub mod config_errors;
+#[cfg(feature = "discord")]
+pub mod discord;
+pub mod event_log;
+pub mod events;
+pub mod exit_hook;
 pub mod hotkey;
+pub mod icon_atlas;
+pub mod imgui_renderer;
+pub mod map_texture;
+pub mod notes;
+pub mod precise_clock;
+pub mod screenshot;
+pub mod settings;
 pub mod tracker;
+pub mod transport;
 pub mod ui;
 pub mod websocket;
+pub mod window_focus;
 
 // Re-export tracker for lib.rs
 pub use tracker::RaceTracker;
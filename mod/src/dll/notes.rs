@@ -0,0 +1,161 @@
+//! Zone note editor
+//!
+//! In interactive mode the overlay offers to write a note about the current
+//! zone. The editor window takes the keyboard while it is open (hotkeys are
+//! suspended so typing doesn't trigger them) and Enter or Save writes the
+//! note to speedfog_notes.json. An empty note deletes it.
+
+use std::fs;
+
+use hudhook::imgui::{Condition, Ui};
+use tracing::{error, info};
+
+use crate::core::notes::NotesFile;
+use crate::core::toast::ToastKind;
+
+use super::hotkey::set_hotkeys_suspended;
+use super::tracker::RaceTracker;
+
+/// Note editor state
+#[derive(Default)]
+pub(crate) struct NoteEditor {
+    /// Note being edited; Some while the editor is open
+    draft: Option<NoteDraft>,
+}
+
+struct NoteDraft {
+    seed_id: String,
+    node_id: String,
+    zone: String,
+    text: String,
+}
+
+impl NoteEditor {
+    pub fn is_open(&self) -> bool {
+        self.draft.is_some()
+    }
+}
+
+enum NoteAction {
+    Save,
+    Close,
+}
+
+impl RaceTracker {
+    /// The player's note on the current zone for this seed
+    pub(crate) fn current_zone_note(&self) -> Option<&str> {
+        let zone = self.current_zone_info()?;
+        self.notes.note(self.seed_key()?, &zone.node_id)
+    }
+
+    /// Edit the note of the current zone (needs a seed and a known zone)
+    pub(crate) fn open_note_editor(&mut self) {
+        let (Some(seed_id), Some(zone)) = (self.seed_key(), self.current_zone_info()) else {
+            return;
+        };
+        let draft = NoteDraft {
+            seed_id: seed_id.to_string(),
+            node_id: zone.node_id.clone(),
+            zone: zone.display_name.clone(),
+            text: self
+                .notes
+                .note(seed_id, &zone.node_id)
+                .unwrap_or_default()
+                .to_string(),
+        };
+        info!(zone = %draft.node_id, "[NOTES] Editing zone note");
+        self.note_editor.draft = Some(draft);
+        set_hotkeys_suspended(true);
+    }
+
+    fn close_note_editor(&mut self) {
+        self.note_editor.draft = None;
+        set_hotkeys_suspended(false);
+    }
+
+    pub(crate) fn render_note_editor(&mut self, ui: &Ui) {
+        let display_size = ui.io().display_size;
+        let Some(draft) = &mut self.note_editor.draft else {
+            return;
+        };
+        let locale = &self.locale;
+
+        let mut open = true;
+        let mut action = None;
+        let title = locale.format("notes.title", &[("zone", &draft.zone)]);
+        ui.window(format!("{}###zone_note", title))
+            .opened(&mut open)
+            .position(
+                [display_size[0] * 0.5, display_size[1] * 0.5],
+                Condition::Appearing,
+            )
+            .position_pivot([0.5, 0.5])
+            .always_auto_resize(true)
+            .collapsible(false)
+            .build(|| {
+                if ui.is_window_appearing() {
+                    ui.set_keyboard_focus_here();
+                }
+                ui.set_next_item_width(ui.calc_text_size("M")[0] * 40.0);
+                if ui
+                    .input_text("##note", &mut draft.text)
+                    .hint(locale.get("notes.hint"))
+                    .enter_returns_true(true)
+                    .build()
+                {
+                    action = Some(NoteAction::Save);
+                }
+                ui.text_disabled(locale.get("notes.help"));
+                if ui.button(locale.get("notes.save")) {
+                    action = Some(NoteAction::Save);
+                }
+                ui.same_line();
+                if ui.button(locale.get("notes.cancel")) {
+                    action = Some(NoteAction::Close);
+                }
+            });
+        if !open {
+            action = Some(NoteAction::Close);
+        }
+
+        match action {
+            Some(NoteAction::Save) => self.save_note(),
+            Some(NoteAction::Close) => self.close_note_editor(),
+            None => {}
+        }
+    }
+
+    /// Store the note being edited and write the notes file
+    fn save_note(&mut self) {
+        let Some(draft) = self.note_editor.draft.take() else {
+            return;
+        };
+        set_hotkeys_suspended(false);
+        if !self.notes.set(&draft.seed_id, &draft.node_id, &draft.text) {
+            return;
+        }
+        let Some(dir) = self.dll_dir.clone() else {
+            error!("[NOTES] DLL directory unknown, zone note not saved");
+            return;
+        };
+        let path = dir.join(NotesFile::FILENAME);
+        match self.notes.to_json().and_then(|contents| {
+            fs::write(&path, contents).map_err(|e| format!("Failed to write notes: {}", e))
+        }) {
+            Ok(()) => {
+                info!(seed = %draft.seed_id, zone = %draft.node_id, "[NOTES] Zone note saved");
+                self.notify(
+                    ToastKind::Info,
+                    self.locale.get("toast.note_saved").to_string(),
+                );
+            }
+            Err(e) => {
+                error!(error = %e, "[NOTES] Failed to save zone note");
+                self.notify(
+                    ToastKind::Warning,
+                    self.locale.get("toast.note_failed").to_string(),
+                );
+            }
+        }
+    }
+}
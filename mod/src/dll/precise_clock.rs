@@ -0,0 +1,54 @@
+//! High-resolution monotonic race clock
+//!
+//! Race timing used to read the system wall clock every frame: its
+//! resolution can be as coarse as the 15.6 ms timer tick, and NTP or a user
+//! changing the time makes it jump mid-race. QueryPerformanceCounter is
+//! monotonic with sub-microsecond resolution. The wall clock is read once,
+//! on first use, to anchor the counter to the Unix epoch; every later time
+//! is the anchor plus the counter's progress, so the race clock, clock_sync
+//! probes and drift detection (`core::drift`) all share one timeline.
+
+use std::sync::OnceLock;
+
+use windows::Win32::System::Performance::{QueryPerformanceCounter, QueryPerformanceFrequency};
+
+use crate::core::clock_sync;
+
+struct Anchor {
+    counter: i64,
+    frequency: i64,
+    unix_ms: i64,
+}
+
+static ANCHOR: OnceLock<Anchor> = OnceLock::new();
+
+fn counter() -> i64 {
+    let mut value = 0;
+    // Cannot fail since Windows XP
+    let _ = unsafe { QueryPerformanceCounter(&mut value) };
+    value
+}
+
+fn anchor() -> &'static Anchor {
+    ANCHOR.get_or_init(|| {
+        let mut frequency = 0;
+        let _ = unsafe { QueryPerformanceFrequency(&mut frequency) };
+        Anchor {
+            counter: counter(),
+            frequency: frequency.max(1),
+            unix_ms: clock_sync::unix_time_ms(),
+        }
+    })
+}
+
+/// Microseconds elapsed since the clock was first used
+pub fn now_us() -> u64 {
+    let anchor = anchor();
+    let ticks = (counter() - anchor.counter).max(0) as i128;
+    (ticks * 1_000_000 / anchor.frequency as i128) as u64
+}
+
+/// Milliseconds since the Unix epoch, on the monotonic clock
+pub fn unix_time_ms() -> i64 {
+    anchor().unix_ms + (now_us() / 1000) as i64
+}
@@ -0,0 +1,131 @@
+//! Game window capture
+//!
+//! hudhook's render context only uploads textures, it can't read the back
+//! buffer back, so the capture copies the game window's area of the
+//! composited screen with GDI: what the player sees, overlay included. Works
+//! in windowed and borderless modes; exclusive fullscreen may come out black.
+//! PNG encoding runs on a worker thread so a 4K frame doesn't stall the game.
+
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+
+use windows::Win32::Foundation::{HWND, POINT, RECT};
+use windows::Win32::Graphics::Gdi::{
+    BitBlt, ClientToScreen, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject,
+    GetDC, GetDIBits, ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB,
+    DIB_RGB_COLORS, SRCCOPY,
+};
+use windows::Win32::System::Threading::GetCurrentProcessId;
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetClientRect, GetForegroundWindow, GetWindowThreadProcessId,
+};
+
+use crate::core::screenshot::bgra_to_rgba;
+
+/// A captured frame, RGBA
+pub struct Capture {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Copy the game window's client area. Fails when the game isn't the
+/// foreground window (alt-tabbed: the capture would show another app).
+pub fn capture_game_window() -> Result<Capture, String> {
+    // SAFETY: plain Win32 queries on the foreground window handle.
+    let hwnd = unsafe { GetForegroundWindow() };
+    let mut pid = 0u32;
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+    if hwnd.0 == 0 || pid != unsafe { GetCurrentProcessId() } {
+        return Err("game window is not in the foreground".to_string());
+    }
+
+    let mut rect = RECT::default();
+    unsafe { GetClientRect(hwnd, &mut rect) }.map_err(|e| format!("GetClientRect: {}", e))?;
+    let width = rect.right - rect.left;
+    let height = rect.bottom - rect.top;
+    if width <= 0 || height <= 0 {
+        return Err("game window has no client area".to_string());
+    }
+    let mut origin = POINT::default();
+    unsafe { ClientToScreen(hwnd, &mut origin) };
+
+    // SAFETY: every GDI object created here is released before returning.
+    unsafe {
+        let screen = GetDC(HWND::default());
+        let memory = CreateCompatibleDC(screen);
+        let bitmap = CreateCompatibleBitmap(screen, width, height);
+        let previous = SelectObject(memory, bitmap);
+
+        let copied = BitBlt(
+            memory, 0, 0, width, height, screen, origin.x, origin.y, SRCCOPY,
+        );
+
+        let mut info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                // Negative height: top-down rows
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut pixels = vec![0u8; width as usize * height as usize * 4];
+        let lines = if copied.is_ok() {
+            GetDIBits(
+                memory,
+                bitmap,
+                0,
+                height as u32,
+                Some(pixels.as_mut_ptr().cast()),
+                &mut info,
+                DIB_RGB_COLORS,
+            )
+        } else {
+            0
+        };
+
+        SelectObject(memory, previous);
+        let _ = DeleteObject(bitmap);
+        let _ = DeleteDC(memory);
+        ReleaseDC(HWND::default(), screen);
+
+        copied.map_err(|e| format!("BitBlt: {}", e))?;
+        if lines != height {
+            return Err("GetDIBits failed".to_string());
+        }
+        bgra_to_rgba(&mut pixels);
+        Ok(Capture {
+            width: width as u32,
+            height: height as u32,
+            pixels,
+        })
+    }
+}
+
+/// Encode and write the PNG on a worker thread; the handle yields the path
+pub fn save_png(
+    capture: Capture,
+    dir: &Path,
+    file_name: &str,
+) -> JoinHandle<Result<PathBuf, String>> {
+    let path = dir.join(file_name);
+    let dir = dir.to_path_buf();
+    thread::spawn(move || {
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        image::save_buffer(
+            &path,
+            &capture.pixels,
+            capture.width,
+            capture.height,
+            image::ExtendedColorType::Rgba8,
+        )
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(path)
+    })
+}
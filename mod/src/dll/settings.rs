@@ -0,0 +1,311 @@
+//! In-game settings panel
+//!
+//! Hotkey-toggled ImGui window to tweak the overlay and rebind hotkeys at
+//! runtime. Edits go to a draft copy of the settings: Apply makes them live,
+//! Save also writes them to speedfog_race.toml.
+//!
+//! Rebinding a hotkey captures the next key (with its Ctrl / Shift / Alt
+//! modifiers) pressed while the panel waits for it; other hotkeys are
+//! suspended meanwhile. A key already bound to another action is refused.
+
+use std::borrow::Cow;
+
+use hudhook::imgui::{Condition, Ui};
+use tracing::{error, info};
+
+use crate::core::badges::Badge;
+use crate::core::key_chord::conflicts;
+use crate::core::theme::ThemeName;
+use crate::core::toast::ToastKind;
+
+use super::config::{KeyBindings, OverlaySettings, RaceConfig};
+use super::hotkey::{poll_key_capture, set_hotkeys_suspended, start_key_capture, KeyCapture};
+use super::tracker::{CachedColors, RaceTracker};
+
+/// Settings panel state
+#[derive(Default)]
+pub(crate) struct SettingsPanel {
+    /// Settings being edited; Some while the panel is open
+    draft: Option<Draft>,
+    /// Action waiting for its new key
+    capture: Option<&'static str>,
+    /// Why the last captured key was refused
+    capture_error: Option<String>,
+}
+
+#[derive(Clone)]
+struct Draft {
+    overlay: OverlaySettings,
+    keybindings: KeyBindings,
+}
+
+impl Draft {
+    fn from_config(config: &RaceConfig) -> Self {
+        Self {
+            overlay: config.overlay.clone(),
+            keybindings: config.keybindings.clone(),
+        }
+    }
+}
+
+impl SettingsPanel {
+    pub fn is_open(&self) -> bool {
+        self.draft.is_some()
+    }
+}
+
+enum SettingsAction {
+    /// Wait for the new key of an action
+    Rebind(&'static str),
+    Apply,
+    Save,
+    Revert,
+    Close,
+}
+
+impl RaceTracker {
+    /// Open the panel with the current settings, or close it discarding unapplied edits
+    pub(crate) fn toggle_settings(&mut self) {
+        self.settings.draft = match self.settings.draft {
+            Some(_) => None,
+            None => Some(Draft::from_config(&self.config)),
+        };
+        self.stop_key_capture();
+        info!(open = self.settings.is_open(), "[HOTKEY] Toggle settings");
+    }
+
+    pub(crate) fn render_settings(&mut self, ui: &Ui) {
+        let display_size = ui.io().display_size;
+        self.poll_rebind();
+        let warning_color = self.cached_colors.theme.warning;
+        let theme = self.cached_colors.theme;
+        let SettingsPanel {
+            draft:
+                Some(Draft {
+                    overlay: draft,
+                    keybindings,
+                }),
+            capture,
+            capture_error,
+        } = &mut self.settings
+        else {
+            return;
+        };
+        let locale = &self.locale;
+
+        let mut open = true;
+        let mut action = None;
+        ui.window(format!("{}###settings", locale.get("settings.title")))
+            .opened(&mut open)
+            .position(
+                [display_size[0] * 0.5, display_size[1] * 0.5],
+                Condition::Appearing,
+            )
+            .position_pivot([0.5, 0.5])
+            .always_auto_resize(true)
+            .collapsible(false)
+            .build(|| {
+                ui.text_disabled(locale.get("settings.help"));
+                ui.slider_config(locale.get("settings.opacity"), 0.0, 1.0)
+                    .display_format("%.2f")
+                    .build(&mut draft.background_opacity);
+                ui.slider_config(locale.get("settings.font_size"), 8.0, 96.0)
+                    .display_format("%.0f px")
+                    .build(&mut draft.font_size);
+                ui.slider_config(locale.get("settings.icon_size"), 0.5, 3.0)
+                    .display_format("%.2fx")
+                    .build(&mut draft.icon_scale);
+                ui.checkbox(locale.get("settings.auto_scale"), &mut draft.auto_scale);
+                ui.slider_config(locale.get("settings.scale"), 0.5, 2.0)
+                    .display_format("%.2fx")
+                    .build(&mut draft.scale);
+                let mut theme_index = ThemeName::ALL
+                    .iter()
+                    .position(|&t| t == draft.theme)
+                    .unwrap_or(0);
+                if ui.combo(
+                    locale.get("settings.theme"),
+                    &mut theme_index,
+                    &ThemeName::ALL,
+                    |t| Cow::Borrowed(t.label()),
+                ) {
+                    draft.theme = ThemeName::ALL[theme_index];
+                }
+
+                ui.separator();
+                ui.text(locale.get("settings.hotkeys"));
+                for (name, hotkey) in keybindings.actions() {
+                    let label = if *capture == Some(name) {
+                        locale.get("settings.press_key").to_string()
+                    } else {
+                        hotkey.name()
+                    };
+                    if ui.button(format!("{}###key_{}", label, name)) {
+                        action = Some(SettingsAction::Rebind(name));
+                    }
+                    ui.same_line();
+                    ui.text(locale.get(&format!("settings.action.{}", name)));
+                }
+                if let Some(message) = capture_error {
+                    ui.text_colored(warning_color, message.as_str());
+                }
+
+                ui.separator();
+                ui.text(locale.get("settings.badges"));
+                for badge in Badge::ALL {
+                    let key = badge.locale_key();
+                    ui.text_colored(badge.color(&theme), locale.get(&format!("{}_tag", key)));
+                    ui.same_line();
+                    ui.text(locale.get(key));
+                }
+
+                ui.separator();
+                if ui.button(locale.get("settings.apply")) {
+                    action = Some(SettingsAction::Apply);
+                }
+                ui.same_line();
+                if ui.button(locale.get("settings.save")) {
+                    action = Some(SettingsAction::Save);
+                }
+                ui.same_line();
+                if ui.button(locale.get("settings.revert")) {
+                    action = Some(SettingsAction::Revert);
+                }
+            });
+        if !open {
+            action = Some(SettingsAction::Close);
+        }
+
+        match action {
+            Some(SettingsAction::Rebind(name)) => {
+                self.settings.capture = Some(name);
+                self.settings.capture_error = None;
+                set_hotkeys_suspended(true);
+                start_key_capture();
+            }
+            Some(SettingsAction::Apply) => self.apply_settings(),
+            Some(SettingsAction::Save) => {
+                self.apply_settings();
+                self.save_settings();
+            }
+            Some(SettingsAction::Revert) => {
+                self.settings.draft = Some(Draft::from_config(&self.config));
+                self.stop_key_capture();
+            }
+            Some(SettingsAction::Close) => {
+                self.settings.draft = None;
+                self.stop_key_capture();
+            }
+            None => {}
+        }
+    }
+
+    /// Assign the key pressed this frame to the action waiting for one,
+    /// unless another action already uses it
+    fn poll_rebind(&mut self) {
+        let Some(name) = self.settings.capture else {
+            return;
+        };
+        let hotkey = match poll_key_capture() {
+            KeyCapture::Waiting => return,
+            KeyCapture::Cancelled => {
+                self.stop_key_capture();
+                return;
+            }
+            KeyCapture::Pressed(hotkey) => hotkey,
+        };
+        let Some(draft) = self.settings.draft.as_mut() else {
+            return;
+        };
+        let bindings = draft.keybindings.actions();
+        if let Some(&other) = conflicts(&bindings, name, &hotkey).first() {
+            self.settings.capture_error = Some(self.locale.format(
+                "settings.key_conflict",
+                &[
+                    ("key", &hotkey.name()),
+                    (
+                        "action",
+                        &self.locale.get(&format!("settings.action.{}", other)),
+                    ),
+                ],
+            ));
+        } else if let Some(binding) = draft.keybindings.get_mut(name) {
+            *binding = hotkey;
+            info!(action = name, key = %hotkey.name(), "[SETTINGS] Hotkey rebound");
+        }
+        self.stop_key_capture();
+    }
+
+    /// Leave key capture mode; hotkeys work again
+    fn stop_key_capture(&mut self) {
+        if self.settings.capture.take().is_some() {
+            set_hotkeys_suspended(false);
+        }
+    }
+
+    /// Make the draft live and re-parse the colors derived from it
+    fn apply_settings(&mut self) {
+        if let Some(ref draft) = self.settings.draft {
+            self.config.overlay = draft.overlay.clone();
+            self.config.keybindings = draft.keybindings.clone();
+            self.cached_colors = CachedColors::from_settings(&self.config.overlay);
+            info!("[SETTINGS] Settings applied");
+        }
+    }
+
+    fn save_settings(&mut self) {
+        let Some(dir) = self.dll_dir.as_ref() else {
+            self.notify(
+                ToastKind::Warning,
+                self.locale.get("toast.settings_no_dir").to_string(),
+            );
+            return;
+        };
+        match self.config.save_settings(dir) {
+            Ok(()) => self.notify(
+                ToastKind::Info,
+                self.locale.get("toast.settings_saved").to_string(),
+            ),
+            Err(e) => {
+                error!(error = %e, "[SETTINGS] Failed to save settings");
+                self.notify(
+                    ToastKind::Warning,
+                    self.locale.get("toast.settings_failed").to_string(),
+                );
+            }
+        }
+    }
+
+    /// Save the positions of the windows dragged since the last save, for
+    /// the current resolution
+    pub(crate) fn save_window_layout(&mut self) {
+        let moved = self.window_layout.take_moved();
+        if moved.is_empty() {
+            return;
+        }
+        let display = self.window_layout.display();
+        for (id, pos) in moved {
+            self.config.layout.set(display, id, pos);
+        }
+        let Some(dir) = self.dll_dir.as_ref() else {
+            self.notify(
+                ToastKind::Warning,
+                self.locale.get("toast.settings_no_dir").to_string(),
+            );
+            return;
+        };
+        match self.config.save_layout(dir) {
+            Ok(()) => self.notify(
+                ToastKind::Info,
+                self.locale.get("toast.layout_saved").to_string(),
+            ),
+            Err(e) => {
+                error!(error = %e, "[SETTINGS] Failed to save window layout");
+                self.notify(
+                    ToastKind::Warning,
+                    self.locale.get("toast.layout_failed").to_string(),
+                );
+            }
+        }
+    }
+}
@@ -5,24 +5,110 @@
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 use windows::Win32::Foundation::HINSTANCE;
-
+use windows::Win32::System::SystemInformation::GetLocalTime;
+
+use crate::core::activity_feed::{Activity, ActivityFeed};
+use crate::core::afk::{AfkDetector, AfkEvent, AfkState};
+use crate::core::audio::AudioCue;
+use crate::core::auto_hide::AutoHide;
+use crate::core::boss_fight::BossFightWatch;
+use crate::core::boss_flags;
+use crate::core::character::{self, CharacterChange, CharacterMonitor};
+use crate::core::checkpoints::CheckpointTracker;
+use crate::core::clock_sync::{self, ClockSync};
 use crate::core::color::parse_hex_color;
-use crate::core::protocol::{ExitInfo, ParticipantInfo, RaceInfo, SeedInfo};
+use crate::core::config_error::ConfigError;
+use crate::core::countdown::{countdown_phase, CountdownPhase};
+use crate::core::death_cause::{DeathWatch, Frame as DeathFrame};
+use crate::core::detection_health::{DetectionHealth, FrameContext, IncidentKind};
+use crate::core::discovery::{self, DiscoveryOutbox};
+use crate::core::drift::{DriftAnomaly, DriftMonitor};
+use crate::core::event_bus::EventBus;
+use crate::core::event_wal::{EventWal, WalRecord};
+use crate::core::exit_pins::ExitPins;
+use crate::core::flag_trace::FlagTrace;
+use crate::core::focus::FocusThrottle;
+use crate::core::frame_profiler::FrameProfiler;
+use crate::core::gate_proximity::{nearest_gate_distance, GateProximity};
+use crate::core::graces::{self, GraceFound, GraceTracker};
+use crate::core::hint_cache::HintCache;
+use crate::core::hold_confirm::{HoldConfirm, HoldState};
+use crate::core::igt_analyzer::IgtAnalyzer;
+use crate::core::key_items::KeyItemWatcher;
+use crate::core::kindling::{KindlingUse, KindlingWatch, MESSMERS_KINDLING_ID};
+use crate::core::leaderboard_sync::{DeltaResult, LeaderboardSync};
+use crate::core::locale::Locale;
+use crate::core::memory_inspector::FlagReadLog;
+use crate::core::message_trace::MessageTrace;
+use crate::core::ng_cycle::{ng_label, NgCycleWatch, NgEvent};
+use crate::core::notes::NotesFile;
+use crate::core::objectives::ObjectiveTracker;
+use crate::core::panel_cycle::PanelScheduler;
+use crate::core::pause::{PauseEvent, RacePause};
+use crate::core::permissions::Permissions;
+use crate::core::player_stats::Flasks;
+use crate::core::position_watch::{PositionChange, PositionWatch};
+use crate::core::post_race::{self, BossKill, DeathEntry, RaceSummary, ZoneVisit};
+use crate::core::protocol::{
+    Checkpoint, ExitHint, ExitInfo, ParticipantInfo, RaceInfo, SeedInfo, LEGACY_PROTOCOL_VERSION,
+};
+use crate::core::race_phase::RacePhase;
+use crate::core::read_audit::ChainStats;
+use crate::core::save_reload::{SaveReload, SaveReloadWatch};
+use crate::core::scaling::{ProgressionTracker, ScalingWarning};
+use crate::core::screenshot::{self, ScreenshotTrigger};
+use crate::core::segments::SegmentTracker;
+use crate::core::self_test::{self, SelfTestReport, SelfTestRun, VANILLA_FLAG};
+use crate::core::splits::{Split, SplitsFile};
+use crate::core::supervisor::{run_supervised, WorkerHealth};
+use crate::core::theme::Theme;
+use crate::core::toast::{ToastKind, ToastQueue};
 use crate::core::traits::GameStateReader;
-use crate::eldenring::{EventFlagReader, FlagReaderStatus, GameState};
-
-use super::config::RaceConfig;
-use super::death_icon::DeathIcon;
-use super::hotkey::begin_hotkey_frame;
+use crate::core::types::PlayerPosition;
+use crate::core::warmup::{self, Lobby, LobbyEvent, LobbyState};
+use crate::core::window_layout::WindowLayout;
+use crate::core::world_map::WorldMapTable;
+use crate::core::zone_cache::{CachedZone, ZoneCache};
+use crate::core::zone_confirm::ZoneConfirmation;
+use crate::core::zone_graph::{Transport, ZoneGraph};
+use crate::core::zone_privacy::ZonePrivacy;
+use crate::eldenring::geom_scan::GateScanner;
+use crate::eldenring::{checked_read, version, EventFlagReader, FlagReaderStatus, GameState};
+
+use super::audio::AudioPlayer;
+use super::config::{OverlaySettings, RaceConfig};
+#[cfg(feature = "discord")]
+use super::discord::{DiscordPresence, PRESENCE_INTERVAL};
+use super::event_log::{self, EventLogWriter};
+use super::events::{FlagSource, RaceEvent};
+use super::exit_hook;
+use super::hotkey::{begin_hotkey_frame, Hotkey};
+use super::icon_atlas::{AtlasWatcher, IconAtlas};
+use super::map_texture::WorldMapTextures;
+use super::notes::NoteEditor;
+use super::precise_clock;
+use super::screenshot::{capture_game_window, save_png};
+use super::settings::SettingsPanel;
+use super::ui::{format_time_u32, LeaderboardLayout};
 use super::websocket::{ConnectionStatus, IncomingMessage, RaceWebSocketClient};
 
-/// Delay after a loading screen before revealing the zone name on the overlay.
-/// Covers fade-in / spawn animation so the overlay doesn't update while the screen is still black.
-const ZONE_REVEAL_DELAY: Duration = Duration::from_secs(2);
+/// How often the config is re-read while the seed pack is outdated
+const SEED_PACK_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often the loaded character is read (save slot, name, level, class)
+const CHARACTER_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often the flask charges are read for the race timer (an inventory scan)
+const FLASK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the zone is queried while the player must return to the start
+/// and the seed doesn't locate the starting grace
+const LOBBY_ZONE_QUERY_INTERVAL: Duration = Duration::from_secs(5);
 
 // =============================================================================
 // RACE STATE
@@ -31,10 +117,17 @@ const ZONE_REVEAL_DELAY: Duration = Duration::from_secs(2);
 /// Zone update data received from server
 #[derive(Debug, Clone)]
 pub struct ZoneUpdateData {
+    pub node_id: String,
     pub display_name: String,
     pub tier: Option<i32>,
     pub original_tier: Option<i32>,
     pub exits: Vec<ExitInfo>,
+    /// First visit of this zone (server acknowledged a new discovery)
+    pub newly_discovered: bool,
+    /// Set on reveal when the zone is scaled well above the player's progression
+    pub scaling_warning: Option<ScalingWarning>,
+    /// Resolved from the zone cache while offline, not by the server
+    pub cached: bool,
 }
 
 /// Current race state from server
@@ -44,11 +137,16 @@ pub struct RaceState {
     pub seed: Option<SeedInfo>,
     pub participants: Vec<ParticipantInfo>,
     pub leader_splits: Option<HashMap<String, i32>>,
-    pub race_started_at: Option<Instant>,
+    /// Authoritative race start from the server (ms since epoch, server clock)
+    pub started_at_ms: Option<i64>,
     pub current_zone: Option<ZoneUpdateData>,
     /// Wall-clock time when the last leaderboard update was received,
     /// used to interpolate other players' IGT between broadcasts.
     pub leaderboard_received_at: Option<Instant>,
+    /// Intermediate checkpoints of the race and the local crossings
+    pub checkpoints: CheckpointTracker,
+    /// Generation of `participants`, for the leaderboard deltas
+    pub leaderboard_sync: LeaderboardSync,
 }
 
 /// Result of reading a single flag for debug display
@@ -63,24 +161,52 @@ pub enum FlagReadResult {
 
 /// Debug overlay info
 pub struct DebugInfo<'a> {
-    pub last_sent: Option<&'a str>,
-    pub last_received: Option<&'a str>,
     pub flag_reader_status: FlagReaderStatus,
     /// Vanilla flag 6 sanity check (category 0 should always exist)
     pub vanilla_sanity: FlagReadResult,
     pub sample_reads: Vec<(u32, FlagReadResult)>,
+    /// Memory inspector: raw values read this frame
+    pub animation: Option<u32>,
+    pub position: Option<PlayerPosition>,
+    /// Fast travel grace captured by the warp hook (0 = none)
+    pub captured_grace: u32,
+    /// Last event flag reads done by the tracker
+    pub flag_reads: &'a FlagReadLog,
+    /// Supervised background threads
+    pub threads: Vec<&'a WorkerHealth>,
+    /// Read counters of every memory pointer chain, by name
+    pub memory_reads: Vec<(&'static str, ChainStats)>,
 }
 
 // =============================================================================
 // CACHED COLORS
 // =============================================================================
 
-/// Pre-parsed overlay colors, computed once from config hex strings.
+/// Pre-parsed overlay colors, computed once from config hex strings
+/// and the selected theme.
 pub(crate) struct CachedColors {
     pub bg: [f32; 4],
     pub text: [f32; 4],
     pub text_disabled: [f32; 4],
     pub border: [f32; 4],
+    pub theme: Theme,
+}
+
+impl CachedColors {
+    pub(crate) fn from_settings(s: &OverlaySettings) -> Self {
+        let text = parse_hex_color(&s.text_color, 1.0);
+        Self {
+            bg: parse_hex_color(&s.background_color, s.background_opacity),
+            text,
+            text_disabled: parse_hex_color(&s.text_disabled_color, 1.0),
+            border: if s.show_border {
+                parse_hex_color(&s.border_color, 1.0)
+            } else {
+                [0.0, 0.0, 0.0, 0.0]
+            },
+            theme: Theme::resolve(s.theme, text),
+        }
+    }
 }
 
 // =============================================================================
@@ -103,9 +229,16 @@ pub struct RaceTracker {
 
     // Font data loaded from file (for ImGui registration)
     pub(crate) font_data: Option<Vec<u8>>,
+    // Size the ImGui font was built at; later font_size changes scale from it
+    pub(crate) loaded_font_size: f32,
 
-    // Death icon texture (loaded during ImGui initialization)
-    pub(crate) death_icon: Option<DeathIcon>,
+    // Icon textures (loaded during ImGui initialization)
+    pub(crate) icons: Option<IconAtlas>,
+    // Detects edits to the user icon atlas for hot reload
+    pub(crate) icon_watcher: AtlasWatcher,
+    // World-map calibration, and the map images (loaded during ImGui initialization)
+    pub(crate) world_map: WorldMapTable,
+    pub(crate) map_textures: Option<WorldMapTextures>,
 
     // Race state
     pub(crate) race_state: RaceState,
@@ -114,8 +247,23 @@ pub struct RaceTracker {
     pub(crate) show_ui: bool,
     pub(crate) show_debug: bool,
     pub(crate) show_leaderboard: bool,
-    last_sent_debug: Option<String>,
-    last_received_debug: Option<String>,
+    pub(crate) show_world_map: bool,
+    /// Interactive mode: the mouse drives the overlay (tooltips, exit pins)
+    pub(crate) interactive: bool,
+    /// Exits pinned in interactive mode, per zone, for the session
+    pub(crate) exit_pins: ExitPins,
+    /// Where the overlay windows are, and which ones were dragged
+    pub(crate) window_layout: WindowLayout,
+    /// Display size seen last frame; a change re-anchors the overlay windows
+    pub(crate) last_display_size: [f32; 2],
+    /// Resolution scale of the last frame (`core::ui_scale`)
+    pub(crate) ui_scale: f32,
+    pub(crate) settings: SettingsPanel,
+    /// Overlay strings in the configured language
+    pub(crate) locale: Locale,
+
+    // Local/server wall clock offset for the authoritative race clock
+    clock_sync: ClockSync,
 
     // Identity (set from auth_ok)
     my_participant_id: Option<String>,
@@ -124,11 +272,35 @@ pub struct RaceTracker {
     event_ids: Vec<u32>,
     pub(crate) triggered_flags: HashSet<u32>,
     /// Event flags detected while disconnected, pending re-send on reconnection
-    pending_event_flags: Vec<(u32, u32)>,
+    pub(crate) pending_event_flags: Vec<(u32, u32)>,
     /// Event flags detected this loading cycle, sent at loading exit
-    deferred_event_flags: Vec<(u32, u32)>,
+    pub(crate) deferred_event_flags: Vec<(u32, u32)>,
+    /// Key item pickups (seed key_items), polled by their pickup flags
+    pub(crate) key_items: KeyItemWatcher,
+    /// Race objectives (bingo / scavenger races), polled by their completion flags
+    pub(crate) objectives: ObjectiveTracker,
+    last_item_poll: Instant,
     /// finish_event from server — sent immediately (no loading screen on boss kill)
-    finish_event: Option<u32>,
+    pub(crate) finish_event: Option<u32>,
+    /// Event flags sent but not acknowledged yet (protocol 3+), resent on a timer
+    discoveries: DiscoveryOutbox,
+    /// The same flags on disk (see `event_wal`), so a game crash loses none,
+    /// and whether the ones left by the last session were sent again
+    event_wal: EventWal,
+    event_wal_replayed: bool,
+    /// Writes `event_wal` from a worker thread, None without a DLL directory
+    event_log: Option<EventLogWriter>,
+    /// Event flags held until the log write of their record is synced:
+    /// (log ticket, flag_id, igt_ms, idempotency key)
+    unsynced_event_flags: Vec<(u64, u32, u32, Option<String>)>,
+    /// Game observations waiting for the end-of-frame dispatch (see `events`)
+    pub(crate) events: EventBus<RaceEvent>,
+    /// Protocol version negotiated in the last auth_ok
+    protocol_version: u32,
+    /// Overlay features allowed by the race (from auth_ok), over the local config
+    pub(crate) permissions: Permissions,
+    /// How much of the other participants' zones the leaderboard shows (from auth_ok)
+    pub(crate) zone_privacy: ZonePrivacy,
 
     // Status update throttle
     last_status_update: Instant,
@@ -139,14 +311,17 @@ pub struct RaceTracker {
     // Ready sent flag
     ready_sent: bool,
 
-    // Temporary status message (yellow banner, auto-expires after 3s)
-    status_message: Option<(String, Instant)>,
+    // Transient notifications (zone reveals, discoveries, connection changes)
+    pub(crate) toasts: ToastQueue,
+    /// Overlay fade after inactivity (`[auto_hide]`)
+    pub(crate) auto_hide: AutoHide,
 
     // One-time diagnostic log flag
     flags_diagnosed: bool,
 
     // Item spawner thread handle (prevents double-spawn on reconnect)
     spawner_thread: Option<JoinHandle<()>>,
+    spawner_health: Arc<WorkerHealth>,
 
     // Items already spawned this session (in-process guard for reconnects).
     // The event flag in game memory is unreliable across reconnects — the game
@@ -154,7 +329,7 @@ pub struct RaceTracker {
     items_spawned: bool,
 
     // Zone update received during loading screen, waiting for load to finish
-    pending_zone_update: Option<ZoneUpdateData>,
+    pub(crate) pending_zone_update: Option<ZoneUpdateData>,
 
     // Timestamp when position became readable after a loading screen.
     // Used to delay zone reveal so the player has finished fading in / spawning.
@@ -163,8 +338,19 @@ pub struct RaceTracker {
     // Whether position was readable last frame (for detecting loading screen exit)
     was_position_readable: bool,
 
+    // When the current warp capture was first seen with no loading screen yet.
+    // Captures older than the configured warp timeout are dropped (cancelled warp).
+    warp_captured_at: Option<Instant>,
+
+    // zone_query sent at loading exit with no zone_update yet, retried as the player moves
+    pub(crate) zone_confirmation: ZoneConfirmation,
+
     // Seed mismatch: config seed_id doesn't match server seed_id (stale seed pack)
     pub(crate) seed_mismatch: bool,
+    // The mismatch comes from a re-roll during this session (overlay shows install steps)
+    pub(crate) seed_rerolled: bool,
+    // While mismatched, the config is re-read to notice the new seed pack
+    last_seed_pack_check: Instant,
 
     // Last auth error message from server.
     // AuthError is always enqueued before StatusChanged(Error) in the same
@@ -176,24 +362,180 @@ pub struct RaceTracker {
     // finished. The mod's local participant igt_ms is stale (only updated via
     // leaderboard_update on events), so we freeze the live game IGT instead.
     pub(crate) frozen_igt_ms: Option<u32>,
+
+    // IGT read when the finish flag was detected locally. Unlike the server's
+    // finish time it doesn't depend on the connection, so the overlay freezes
+    // on it right away and the summary reports both.
+    pub(crate) local_finish_igt_ms: Option<u32>,
+
+    // Play vs load/menu time, sampled every frame while racing
+    pub(crate) igt_analyzer: IgtAnalyzer,
+
+    // Overlay cost per frame, and the leaderboard layout reused while over budget
+    pub(crate) frame_profiler: FrameProfiler,
+    pub(crate) leaderboard_layout: Option<LeaderboardLayout>,
+
+    // Update/render budget while the game window is in the background
+    pub(crate) focus_throttle: FocusThrottle,
+
+    // Post-race summary data: revealed zones, boss kills and deaths, in order
+    pub(crate) dll_dir: Option<PathBuf>,
+    pub(crate) zone_log: Vec<ZoneVisit>,
+    boss_kills: Vec<BossKill>,
+    death_watch: DeathWatch,
+    death_log: Vec<DeathEntry>,
+    summary_written: bool,
+
+    // Discovered zone graph, exported on demand; transport of the last loading screen
+    pub(crate) zone_graph: ZoneGraph,
+    pub(crate) last_transport: Transport,
+
+    // Last event flag reads, for the debug panel's memory inspector
+    flag_reads: FlagReadLog,
+    // Every-frame read of all event flags (`[flag_trace]`, off by default)
+    pub(crate) flag_trace: Option<FlagTrace>,
+    // Expired warps and dropped discoveries, for the debug panel's
+    // detection health line
+    pub(crate) detection_health: DetectionHealth,
+
+    // Zone nodes received in zone_update, to tell discoveries from revisits
+    known_nodes: HashSet<String>,
+
+    // Highest zone tier revealed, for scaling warnings
+    pub(crate) progression: ProgressionTracker,
+
+    // Loaded character, and the warning shown when it's the wrong one
+    character: CharacterMonitor,
+    last_character_check: Instant,
+    pub(crate) character_warning: Option<String>,
+
+    // NG+ cycle of the loaded character, and the transition not sent yet
+    ng_cycle: NgCycleWatch,
+    unsent_ng_cycle: Option<(u32, u32)>,
+    pub(crate) ng_warning: Option<String>,
+
+    // Idle detection, and the AFK status the server was last told about
+    pub(crate) afk: AfkDetector,
+    afk_sent: bool,
+
+    // Warmup lobby before the start, and the zone the server resolved while
+    // waiting for the player to be back at the start (with its last query)
+    pub(crate) lobby: Lobby,
+    lobby_zone: Option<String>,
+    last_lobby_query: Option<Instant>,
+
+    // Boss bar on screen, to minimize the overlay during fights
+    pub(crate) boss_fight: BossFightWatch,
+
+    // Pause asked by the player, granted and ended by the organizer
+    pub(crate) pause: RacePause,
+
+    // Leaderboard position last announced with a toast
+    position_watch: PositionWatch,
+
+    // Screenshots being encoded and written by worker threads
+    screenshot_jobs: Vec<JoinHandle<Result<PathBuf, String>>>,
+
+    // Setup self-test: the run sampling the IGT, then the checklist on screen
+    // until dismissed. The startup run waits for the character to be loaded.
+    self_test_run: Option<SelfTestRun>,
+    pub(crate) self_test_report: Option<SelfTestReport>,
+    self_test_on_startup: bool,
+    // Connection and auth failures kept for the self-test (the toasts fade)
+    last_connection_error: Option<String>,
+    auth_failure: Option<String>,
+    game_version: Option<String>,
+
+    // Fog gate proximity hint (None when disabled or CSWorldGeomMan wasn't found)
+    gate_scanner: Option<GateScanner>,
+    pub(crate) gate_proximity: GateProximity,
+
+    // Save reloads (IGT or deaths going backwards), kept until a server that
+    // knows save_reloaded is connected
+    save_reloads: SaveReloadWatch,
+    unsent_save_reloads: Vec<SaveReload>,
+
+    // Messmer's Kindling uses, kept until a server that knows kindling_used
+    // is connected
+    kindling: KindlingWatch,
+    unsent_kindling: Vec<KindlingUse>,
+
+    // IGT running faster or slower than the wall clock, kept until a server
+    // that knows timing_drift is connected
+    drift: DriftMonitor,
+    unsent_timing_drifts: Vec<DriftAnomaly>,
+
+    // Sites of Grace touched during the race, kept until a server that knows
+    // grace_discovered is connected
+    pub(crate) graces: GraceTracker,
+    unsent_graces: Vec<GraceFound>,
+
+    // Major bosses defeated on the save, race or not (`{bosses}`)
+    pub(crate) bosses_defeated: Option<u32>,
+
+    // Flask charges (`{flasks}`), None when the race timer doesn't show them
+    pub(crate) flasks: Option<Flasks>,
+    last_flask_poll: Instant,
+
+    // Personal best splits per seed, and this run's first arrival in each zone
+    splits: SplitsFile,
+    run_splits: Vec<Split>,
+    // The seed's segments and this run's times and deaths in each
+    pub(crate) segments: SegmentTracker,
+
+    // Hold-to-confirm progress of the hotkeys with side effects
+    hold_export_graph: HoldConfirm,
+    hold_request_pause: HoldConfirm,
+
+    // The other racers' last milestones, listed below the leaderboard
+    pub(crate) activity_feed: ActivityFeed,
+
+    // The player's notes per seed and zone, and the note being edited
+    pub(crate) notes: NotesFile,
+    pub(crate) note_editor: NoteEditor,
+
+    // Sounds played on zone reveals, discoveries, overtakes and the finish
+    pub(crate) audio: AudioPlayer,
+    /// Delta vs the personal best on arrival in the current zone (negative = ahead)
+    pub(crate) pb_delta_ms: Option<i32>,
+    pb_checked: bool,
+
+    // Panel shown in auto-cycle mode
+    pub(crate) panel_scheduler: PanelScheduler,
+
+    // Community exit hints cached per seed, and zones asked on this connection
+    exit_hints: HintCache,
+    hints_requested: HashSet<String>,
+
+    // Zones described by the server, cached per seed for offline gaps: the
+    // flag sent at the last loading exit (linked to the next zone_update),
+    // and the zone a flag crossed offline resolved to
+    zone_cache: ZoneCache,
+    pub(crate) zone_flag: Option<u32>,
+    pub(crate) offline_zone: Option<String>,
+
+    // Game window hooked for the flush on exit, and the flush done
+    exit_hooked: bool,
+    exit_flushed: bool,
+
+    // Discord Rich Presence worker (None when disabled in the config)
+    #[cfg(feature = "discord")]
+    discord: Option<DiscordPresence>,
+    #[cfg(feature = "discord")]
+    pub(crate) last_presence_update: Instant,
 }
 
 impl RaceTracker {
-    pub fn new(hmodule: HINSTANCE) -> Option<Self> {
+    /// Load the config and start tracking. Config problems are returned so
+    /// the DLL can show them in game instead.
+    pub fn new(hmodule: HINSTANCE) -> Result<Self, Vec<ConfigError>> {
         info!("Initializing RaceTracker...");
 
         // Load config
-        let config = match RaceConfig::load(hmodule) {
-            Ok(cfg) => cfg,
-            Err(e) => {
-                error!(error = %e, "Failed to load config");
-                return None;
-            }
-        };
-
-        if !config.is_valid() {
-            error!("Config is invalid (missing server/mod_token/race_id)");
-            return None;
+        let config = RaceConfig::load(hmodule)?;
+        let errors = config.validate();
+        if !errors.is_empty() {
+            return Err(errors);
         }
 
         // Load font data
@@ -202,75 +544,251 @@ impl RaceTracker {
             .as_ref()
             .and_then(|dir| load_font_data(dir, &config.overlay.font_path));
 
-        // Init game state
-        let game_state = GameState::new();
+        // Init game state with the offsets for the running game version
+        let version_support = version::detect();
+        let game_state = GameState::new(&version_support);
         game_state.wait_for_game_loaded();
 
+        // Other mods known to break the overlay hook or the memory reads
+        let module_conflicts = crate::eldenring::environment::module_conflicts(hmodule);
+
         // Init event flag reader
-        let event_flag_reader =
-            EventFlagReader::new(game_state.base_addresses().csfd4_virtual_memory_flag);
+        let event_flag_reader = EventFlagReader::new(
+            game_state.virtual_memory_flag(),
+            version_support.offsets.flag_manager,
+        );
 
         // Install warp hook for grace entity ID capture (fast travel zone tracking)
+        let detection = &config.detection;
         unsafe {
-            let lua_warp = game_state.base_addresses().lua_warp;
-            if let Err(e) = crate::eldenring::warp_hook::install(lua_warp) {
-                error!(error = %e, "Failed to install warp hook (fast travel zone tracking disabled)");
+            if detection.enable_vanilla_warp_trigger {
+                match game_state.base_addresses() {
+                    Some(base) => {
+                        if let Err(e) = crate::eldenring::warp_hook::install(base.lua_warp) {
+                            error!(error = %e, "Failed to install warp hook (fast travel zone tracking disabled)");
+                        }
+                    }
+                    None => warn!("Warp hook not installed (fast travel zone tracking disabled)"),
+                }
+            } else {
+                info!("Warp hook disabled in config (fast travel zone tracking disabled)");
             }
         }
+        let zone_reveal_delay = detection.zone_reveal_delay();
 
-        // Pre-parse overlay colors
-        let s = &config.overlay;
-        let cached_colors = CachedColors {
-            bg: parse_hex_color(&s.background_color, s.background_opacity),
-            text: parse_hex_color(&s.text_color, 1.0),
-            text_disabled: parse_hex_color(&s.text_disabled_color, 1.0),
-            border: if s.show_border {
-                parse_hex_color(&s.border_color, 1.0)
-            } else {
-                [0.0, 0.0, 0.0, 0.0]
-            },
+        // Map asset scanner for the fog gate proximity hint (opt-in)
+        let gate_scanner = if config.gate_proximity.enabled {
+            GateScanner::locate(version_support.offsets.world_geom)
+        } else {
+            None
         };
 
+        let flag_trace = config
+            .flag_trace
+            .enabled
+            .then(|| FlagTrace::new(config.flag_trace.max_transitions));
+
+        // Pre-parse overlay colors
+        let cached_colors = CachedColors::from_settings(&config.overlay);
+        let loaded_font_size = config.overlay.font_size;
+
+        let icon_watcher = AtlasWatcher::new(dll_dir.clone());
+        let world_map = load_world_map(dll_dir.as_deref());
+        let show_world_map = config.world_map.show_on_start;
+        let splits = dll_dir
+            .as_ref()
+            .map(|dir| load_splits(dir))
+            .unwrap_or_default();
+        let notes = dll_dir
+            .as_ref()
+            .map(|dir| load_notes(dir))
+            .unwrap_or_default();
+        let audio = AudioPlayer::load(&config.audio, dll_dir.as_deref());
+        let exit_hints = dll_dir
+            .as_ref()
+            .map(|dir| load_exit_hints(dir))
+            .unwrap_or_default();
+        let zone_cache = dll_dir
+            .as_ref()
+            .map(|dir| load_zone_cache(dir))
+            .unwrap_or_default();
+        let event_wal = dll_dir
+            .as_ref()
+            .map(|dir| load_event_wal(dir))
+            .unwrap_or_default();
+        let event_log = dll_dir
+            .as_ref()
+            .map(|dir| EventLogWriter::start(dir.join(EventWal::FILENAME)));
+        let locale = load_locale(dll_dir.as_deref(), &config.overlay.language);
+
+        #[cfg(feature = "discord")]
+        let discord = DiscordPresence::start(&config.discord);
+        #[cfg(not(feature = "discord"))]
+        if config.discord.enabled {
+            warn!("[DISCORD] Rich Presence enabled in config but this build lacks the discord feature");
+        }
+
         // Create WebSocket client
         let mut ws_client = RaceWebSocketClient::new(config.server.clone());
         ws_client.connect();
+        exit_hook::set_socket(ws_client.exit_handle());
 
         info!("RaceTracker initialized");
 
-        Some(Self {
+        let mut tracker = Self {
             game_state,
             event_flag_reader,
             ws_client,
             config,
             cached_colors,
             font_data,
-            death_icon: None,
+            loaded_font_size,
+            icons: None,
+            icon_watcher,
+            world_map,
+            map_textures: None,
             race_state: RaceState::default(),
             show_ui: true,
             show_debug: false,
             show_leaderboard: true,
-            last_sent_debug: None,
-            last_received_debug: None,
+            show_world_map,
+            interactive: false,
+            exit_pins: ExitPins::default(),
+            window_layout: WindowLayout::default(),
+            last_display_size: [0.0, 0.0],
+            ui_scale: 1.0,
+            settings: SettingsPanel::default(),
+            locale,
+            clock_sync: ClockSync::new(),
             my_participant_id: None,
             event_ids: Vec::new(),
             triggered_flags: HashSet::new(),
             pending_event_flags: Vec::new(),
             deferred_event_flags: Vec::new(),
             finish_event: None,
+            discoveries: DiscoveryOutbox::new(precise_clock::unix_time_ms().max(0) as u64),
+            event_wal,
+            event_wal_replayed: false,
+            event_log,
+            unsynced_event_flags: Vec::new(),
+            events: EventBus::default(),
+            protocol_version: LEGACY_PROTOCOL_VERSION,
+            permissions: Permissions::default(),
+            zone_privacy: ZonePrivacy::default(),
             last_status_update: Instant::now(),
             last_flag_poll: Instant::now(),
+            key_items: KeyItemWatcher::new(),
+            objectives: ObjectiveTracker::new(),
+            last_item_poll: Instant::now(),
             ready_sent: false,
-            status_message: None,
+            toasts: ToastQueue::new(),
+            auto_hide: AutoHide::new(Instant::now()),
             flags_diagnosed: false,
             spawner_thread: None,
+            spawner_health: WorkerHealth::new("item_spawner"),
             items_spawned: false,
             pending_zone_update: None,
-            loading_exit_time: Some(Instant::now() - ZONE_REVEAL_DELAY), // Already elapsed → immediate reveal
+            loading_exit_time: Some(Instant::now() - zone_reveal_delay), // Already elapsed → immediate reveal
             was_position_readable: true,
+            warp_captured_at: None,
+            zone_confirmation: ZoneConfirmation::new(),
             seed_mismatch: false,
+            seed_rerolled: false,
+            last_seed_pack_check: Instant::now(),
             last_auth_error: None,
             frozen_igt_ms: None,
-        })
+            local_finish_igt_ms: None,
+            igt_analyzer: IgtAnalyzer::new(),
+            frame_profiler: FrameProfiler::new(),
+            leaderboard_layout: None,
+            focus_throttle: FocusThrottle::default(),
+            dll_dir,
+            zone_log: Vec::new(),
+            boss_kills: Vec::new(),
+            death_watch: DeathWatch::default(),
+            death_log: Vec::new(),
+            summary_written: false,
+            zone_graph: ZoneGraph::default(),
+            last_transport: Transport::Respawn,
+            flag_reads: FlagReadLog::default(),
+            flag_trace,
+            detection_health: DetectionHealth::default(),
+            known_nodes: HashSet::new(),
+            progression: ProgressionTracker::default(),
+            character: CharacterMonitor::default(),
+            last_character_check: Instant::now(),
+            character_warning: None,
+            ng_cycle: NgCycleWatch::default(),
+            unsent_ng_cycle: None,
+            ng_warning: None,
+            afk: AfkDetector::default(),
+            afk_sent: false,
+            lobby: Lobby::default(),
+            lobby_zone: None,
+            last_lobby_query: None,
+            boss_fight: BossFightWatch::default(),
+            pause: RacePause::default(),
+            position_watch: PositionWatch::default(),
+            screenshot_jobs: Vec::new(),
+            self_test_run: None,
+            self_test_report: None,
+            self_test_on_startup: config.self_test.on_startup,
+            last_connection_error: None,
+            auth_failure: None,
+            game_version: version_support.version.map(|v| v.to_string()),
+            gate_scanner,
+            gate_proximity: GateProximity::default(),
+            save_reloads: SaveReloadWatch::default(),
+            unsent_save_reloads: Vec::new(),
+            kindling: KindlingWatch::default(),
+            unsent_kindling: Vec::new(),
+            drift: DriftMonitor::default(),
+            unsent_timing_drifts: Vec::new(),
+            graces: GraceTracker::default(),
+            unsent_graces: Vec::new(),
+            bosses_defeated: None,
+            flasks: None,
+            last_flask_poll: Instant::now(),
+            splits,
+            run_splits: Vec::new(),
+            segments: SegmentTracker::default(),
+            hold_export_graph: HoldConfirm::default(),
+            hold_request_pause: HoldConfirm::default(),
+            activity_feed: ActivityFeed::default(),
+            notes,
+            note_editor: NoteEditor::default(),
+            audio,
+            pb_delta_ms: None,
+            pb_checked: false,
+            panel_scheduler: PanelScheduler::default(),
+            exit_hints,
+            hints_requested: HashSet::new(),
+            zone_cache,
+            zone_flag: None,
+            offline_zone: None,
+            exit_hooked: false,
+            exit_flushed: false,
+            #[cfg(feature = "discord")]
+            discord,
+            #[cfg(feature = "discord")]
+            last_presence_update: Instant::now() - PRESENCE_INTERVAL,
+        };
+        if !module_conflicts.is_empty() {
+            let mods: Vec<String> = module_conflicts.iter().map(|c| c.to_string()).collect();
+            let message = tracker
+                .locale
+                .format("toast.module_conflicts", &[("mods", &mods.join(", "))]);
+            tracker.notify(ToastKind::Warning, message);
+        }
+        Ok(tracker)
+    }
+
+    pub fn is_race_setup(&self) -> bool {
+        self.race_state
+            .race
+            .as_ref()
+            .map(|r| r.status == "setup")
+            .unwrap_or(false)
     }
 
     pub fn is_race_running(&self) -> bool {
@@ -281,6 +799,12 @@ impl RaceTracker {
             .unwrap_or(false)
     }
 
+    /// Race phase picking the overlay content (`[phases]`), None without a race
+    pub(crate) fn race_phase(&self) -> Option<RacePhase> {
+        let race = self.race_state.race.as_ref()?;
+        RacePhase::from_status(&race.status, self.am_i_finished())
+    }
+
     /// Check if the local player has finished the race.
     /// Once finished, the mod should stop sending status_update and event_flag
     /// to preserve the frozen IGT at finish time.
@@ -290,10 +814,56 @@ impl RaceTracker {
             .unwrap_or(false)
     }
 
+    /// Hook the game window once it exists, and flush when the game closes
+    /// (see `exit_hook`). Called after `update`, so the unsent queues have
+    /// just been handed to the WebSocket worker.
+    pub(crate) fn check_exit(&mut self) {
+        if !self.exit_hooked {
+            self.exit_hooked = exit_hook::install();
+        }
+        if self.exit_flushed || !exit_hook::exit_requested() {
+            return;
+        }
+        self.exit_flushed = true;
+
+        let mut status = None;
+        if self.is_race_running() && !self.am_i_finished() && self.ws_client.is_connected() {
+            // Their resend timer would never run out
+            for pending in self.discoveries.flush(Instant::now()) {
+                let key = self.event_flag_key(pending.flag_id);
+                self.ws_client.send_event_flag(
+                    pending.flag_id,
+                    pending.igt_ms,
+                    Some(pending.seq),
+                    key,
+                );
+            }
+            // The game is closing: flags still waiting for their log record go out as is
+            for (_, flag_id, igt_ms, key) in std::mem::take(&mut self.unsynced_event_flags) {
+                self.transmit_event_flag(flag_id, igt_ms, key);
+            }
+            // Nothing would send the events held back by the broadcast delay
+            self.ws_client.flush_delayed();
+            if !self.pause.is_paused() {
+                let deaths = self.game_state.read_deaths().unwrap_or(0);
+                status = self
+                    .game_state
+                    .read_igt()
+                    .filter(|&igt_ms| igt_ms > 0)
+                    .map(|igt_ms| (igt_ms, deaths));
+            }
+        }
+        info!(status = ?status, "[EXIT] Race state flushed");
+        exit_hook::tracker_flushed(status);
+    }
+
     pub fn update(&mut self) {
         // Process hotkeys at start of frame
         begin_hotkey_frame();
 
+        // Memory reads below are cached until the next frame
+        self.game_state.begin_frame();
+
         // Check toggle_ui hotkey
         if self.config.keybindings.toggle_ui.is_just_pressed() {
             self.show_ui = !self.show_ui;
@@ -315,11 +885,108 @@ impl RaceTracker {
             );
         }
 
-        // Poll WebSocket
+        // Check toggle_settings hotkey
+        if self.config.keybindings.toggle_settings.is_just_pressed() {
+            self.toggle_settings();
+        }
+
+        // Check toggle_map hotkey
+        if self.config.keybindings.toggle_map.is_just_pressed() {
+            self.show_world_map = !self.show_world_map;
+            info!(
+                show_world_map = self.show_world_map,
+                "[HOTKEY] Toggle world map"
+            );
+        }
+
+        // Check toggle_interactive hotkey
+        if self.config.keybindings.toggle_interactive.is_just_pressed() {
+            self.interactive = !self.interactive;
+            info!(
+                interactive = self.interactive,
+                "[HOTKEY] Toggle interactive mode"
+            );
+            // Windows dragged during interactive mode keep their new spot
+            if !self.interactive {
+                self.save_window_layout();
+            }
+        }
+
+        // Check export_graph hotkey (hold to confirm)
+        let export_graph = self.config.keybindings.export_graph;
+        if self.confirm_hotkey(export_graph, |t| &mut t.hold_export_graph) {
+            self.export_zone_graph();
+        }
+
+        // Check request_pause hotkey (hold to confirm)
+        let request_pause = self.config.keybindings.request_pause;
+        if self.confirm_hotkey(request_pause, |t| &mut t.hold_request_pause) {
+            self.request_pause();
+        }
+
+        // Check screenshot hotkey
+        if self.config.keybindings.screenshot.is_just_pressed() {
+            let igt_ms = self.game_state.read_igt().unwrap_or(0);
+            self.take_screenshot(ScreenshotTrigger::Manual, igt_ms);
+        }
+        self.poll_screenshot_jobs();
+
+        // Check self_test hotkey: runs the self-test, or dismisses its checklist
+        if self.config.keybindings.self_test.is_just_pressed() {
+            self.toggle_self_test();
+        }
+        self.update_self_test();
+
+        // Any hotkey brings a faded overlay back
+        if self
+            .config
+            .keybindings
+            .actions()
+            .iter()
+            .any(|(_, hotkey)| hotkey.is_held())
+        {
+            self.auto_hide.wake(Instant::now());
+        }
+
+        // Send the event flags whose log record is synced, what the broadcast
+        // delay held back long enough, then poll WebSocket
+        self.release_synced_event_flags();
+        self.ws_client.release_delayed();
         while let Some(msg) = self.ws_client.poll() {
             self.handle_ws_message(msg);
         }
 
+        // Restart the WebSocket worker if it panicked
+        if let Some(cause) = self.ws_client.supervise() {
+            exit_hook::set_socket(self.ws_client.exit_handle());
+            self.notify(
+                ToastKind::Warning,
+                self.locale
+                    .format("toast.worker_crashed", &[("cause", &cause)]),
+            );
+        }
+
+        // Outdated seed pack: pick up the new one as soon as it is extracted
+        if self.seed_mismatch && self.last_seed_pack_check.elapsed() >= SEED_PACK_CHECK_INTERVAL {
+            self.last_seed_pack_check = Instant::now();
+            self.check_installed_seed_pack();
+        }
+
+        // Finished: keep this run's splits if it's a new personal best
+        if !self.pb_checked && self.am_i_finished() {
+            self.pb_checked = true;
+            self.save_personal_best();
+        }
+
+        // Split wall time into play vs loading while racing (frozen once finished)
+        if self.is_race_running() && !self.am_i_finished() {
+            let igt_ms = self.game_state.read_igt();
+            self.igt_analyzer.add_sample(Instant::now(), igt_ms);
+            self.check_save_reload(igt_ms);
+            self.check_drift(igt_ms);
+            self.check_deaths(igt_ms);
+        }
+
         // Read position once per frame for loading screen detection
         let position_readable = self.game_state.read_position().is_some();
 
@@ -331,133 +998,191 @@ impl RaceTracker {
                 if self.loading_exit_time.is_none() {
                     self.loading_exit_time = Some(Instant::now());
                 }
-                if self.loading_exit_time.unwrap().elapsed() >= ZONE_REVEAL_DELAY {
-                    let zone = self.pending_zone_update.take().unwrap();
-                    info!(name = %zone.display_name, "[RACE] Zone revealed");
-                    self.race_state.current_zone = Some(zone);
+                if self.loading_exit_time.unwrap().elapsed()
+                    >= self.config.detection.zone_reveal_delay()
+                {
+                    let zone = Box::new(self.pending_zone_update.take().unwrap());
+                    let igt_ms = self.game_state.read_igt().unwrap_or(0);
+                    self.publish(RaceEvent::ZoneRevealed { zone, igt_ms });
                 }
             } else {
                 self.loading_exit_time = None;
             }
         }
 
+        // A captured warp is followed by a loading screen; if none came within the
+        // timeout the warp was cancelled and its destination must not be reused.
+        if position_readable && self.was_position_readable {
+            let captured = crate::eldenring::warp_hook::get_captured_grace_entity_id() != 0;
+            match self.warp_captured_at {
+                _ if !captured => self.warp_captured_at = None,
+                None => self.warp_captured_at = Some(Instant::now()),
+                Some(at) if at.elapsed() >= self.config.detection.warp_timeout() => {
+                    debug!("[RACE] Warp capture expired without a loading screen");
+                    let grace_entity_id =
+                        crate::eldenring::warp_hook::get_captured_grace_entity_id();
+                    self.record_incident(IncidentKind::WarpExpired { grace_entity_id });
+                    crate::eldenring::warp_hook::clear_captured_grace_entity_id();
+                    self.warp_captured_at = None;
+                }
+                Some(_) => {}
+            }
+        }
+
         // Loading screen exit: send deferred event_flags (certain) or zone_query (probabilistic)
         if position_readable && !self.was_position_readable {
+            self.warp_captured_at = None;
+            // A new loading screen supersedes any unanswered zone_query
+            self.zone_confirmation.clear();
+
             // Force one immediate flag scan — catches flags set during loading
             // (e.g. Erdtree burn, Maliketh warp) that the 10Hz poll couldn't read
             // because is_flag_set() returns None while position is unreadable.
-            if !self.event_ids.is_empty() {
-                let igt_ms = self.game_state.read_igt().unwrap_or(0);
-                for &flag_id in &self.event_ids {
-                    if !self.triggered_flags.contains(&flag_id) {
-                        if let Some(true) = self.event_flag_reader.is_flag_set(flag_id) {
-                            self.triggered_flags.insert(flag_id);
-                            if self.finish_event == Some(flag_id) {
-                                if self.ws_client.is_connected()
-                                    && self.is_race_running()
-                                    && !self.am_i_finished()
-                                {
-                                    self.ws_client.send_event_flag(flag_id, igt_ms);
-                                    self.last_sent_debug = Some(format!(
-                                        "event_flag({}, igt={}ms) [finish/loading-exit]",
-                                        flag_id, igt_ms
-                                    ));
-                                    info!(flag_id, "[RACE] Finish event caught at loading exit");
-                                } else if !self.am_i_finished() {
-                                    self.pending_event_flags.push((flag_id, igt_ms));
-                                }
-                            } else {
-                                self.deferred_event_flags.push((flag_id, igt_ms));
-                                info!(flag_id, "[RACE] Event flag caught at loading exit");
-                            }
-                        }
-                    }
-                }
+            let igt_ms = self.game_state.read_igt().unwrap_or(0);
+            for flag_id in self.take_newly_set_flags() {
+                self.publish(RaceEvent::FlagTriggered {
+                    flag_id,
+                    igt_ms,
+                    source: FlagSource::LoadingExit,
+                });
             }
+            // Deferred event_flags (certain) or zone_query (probabilistic), see `events`
+            self.publish(RaceEvent::LoadingExited { igt_ms });
+        }
+        self.was_position_readable = position_readable;
 
-            if self.ws_client.is_connected() && self.is_race_running() && !self.am_i_finished() {
-                if !self.deferred_event_flags.is_empty() {
-                    // Fog gate traversal — send deferred flags now that loading is done
-                    for (flag_id, igt_ms) in self.deferred_event_flags.drain(..) {
-                        self.ws_client.send_event_flag(flag_id, igt_ms);
-                        self.last_sent_debug = Some(format!(
-                            "event_flag({}, igt={}ms) [deferred]",
-                            flag_id, igt_ms
-                        ));
-                        info!(flag_id, "[RACE] Deferred event flag sent at loading exit");
-                    }
-                } else {
-                    // No fog gate — death/respawn/quit-out/fast-travel
-                    let pos = self.game_state.read_position();
-                    let grace_id = crate::eldenring::warp_hook::get_captured_grace_entity_id();
-                    let grace_opt = if grace_id > 0 { Some(grace_id) } else { None };
-                    let map_id = pos.as_ref().map(|p| p.map_id_str.clone());
-                    let position = pos.as_ref().map(|p| [p.x, p.y, p.z]);
-                    let play_region_id = pos.as_ref().and_then(|p| p.play_region_id);
-
-                    if grace_opt.is_some() || map_id.is_some() {
-                        self.ws_client.send_zone_query(
-                            grace_opt,
-                            map_id.clone(),
-                            position,
-                            play_region_id,
-                        );
-                        self.last_sent_debug = Some(format!(
-                            "zone_query(grace={:?}, map={:?})",
-                            grace_opt, map_id
-                        ));
-                        info!(?grace_opt, "[RACE] Zone query sent at loading exit");
-                    }
-
-                    if grace_id > 0 {
-                        crate::eldenring::warp_hook::clear_captured_grace_entity_id();
-                    }
-                }
-            } else {
-                // Not connected or race not running — clean up
-                self.deferred_event_flags.clear();
-                let grace_id = crate::eldenring::warp_hook::get_captured_grace_entity_id();
-                if grace_id > 0 {
-                    crate::eldenring::warp_hook::clear_captured_grace_entity_id();
+        // Unanswered zone_query: the server couldn't resolve it (ambiguous map).
+        // Retry from the player's new position, which may narrow it to one zone.
+        if self.zone_confirmation.is_pending()
+            && position_readable
+            && self.ws_client.is_connected()
+            && self.is_race_running()
+            && !self.am_i_finished()
+        {
+            if let Some(pos) = self.game_state.read_position() {
+                let position = [pos.x, pos.y, pos.z];
+                let now = self.send_clock();
+                if self
+                    .zone_confirmation
+                    .should_retry(&pos.map_id_str, position, now)
+                {
+                    self.ws_client.send_zone_query(
+                        None,
+                        Some(pos.map_id_str.clone()),
+                        Some(position),
+                        pos.play_region_id,
+                    );
+                    info!(map = %pos.map_id_str, "[RACE] Zone query retried (unresolved)");
                 }
             }
         }
-        self.was_position_readable = position_readable;
+
+        // Flag trace: every flag, every frame, apart from the regular poll below
+        if let Some(trace) = self.flag_trace.as_mut() {
+            let igt_ms = self.game_state.read_igt().unwrap_or(0);
+            let reader = &self.event_flag_reader;
+            trace.poll(Instant::now(), igt_ms, |flag| reader.is_flag_set(flag));
+        }
+
+        // Warmup lobby: flags set before the race starts for the player are
+        // neither recorded nor sent, the fog gates can be crossed again
+        self.check_lobby();
+        let flags_held = self.lobby.suppresses_flags();
 
         // Event flag polling runs ALWAYS (even when disconnected).
         // Flags are transient in game memory (~seconds), so we must detect them immediately.
         // Regular flags are deferred until loading exit; finish_event is sent immediately.
-        if !self.event_ids.is_empty() && self.last_flag_poll.elapsed() >= Duration::from_millis(100)
+        if !flags_held
+            && !self.event_ids.is_empty()
+            && self.last_flag_poll.elapsed() >= Duration::from_millis(100)
         {
             self.last_flag_poll = Instant::now();
             let igt_ms = self.game_state.read_igt().unwrap_or(0);
-            for &flag_id in &self.event_ids {
-                if !self.triggered_flags.contains(&flag_id) {
-                    if let Some(true) = self.event_flag_reader.is_flag_set(flag_id) {
-                        self.triggered_flags.insert(flag_id);
-
-                        if self.finish_event == Some(flag_id) {
-                            // finish_event: no loading screen → send immediately
-                            if self.ws_client.is_connected()
-                                && self.is_race_running()
-                                && !self.am_i_finished()
-                            {
-                                self.ws_client.send_event_flag(flag_id, igt_ms);
-                                self.last_sent_debug = Some(format!(
-                                    "event_flag({}, igt={}ms) [finish]",
-                                    flag_id, igt_ms
-                                ));
-                                info!(flag_id, "[RACE] Finish event sent immediately");
-                            } else if !self.am_i_finished() {
-                                self.pending_event_flags.push((flag_id, igt_ms));
-                            }
-                        } else {
-                            // Regular fog gate → defer until loading exit
-                            self.deferred_event_flags.push((flag_id, igt_ms));
-                            info!(flag_id, "[RACE] Event flag deferred until loading exit");
-                        }
-                    }
-                }
+            for flag_id in self.take_newly_set_flags() {
+                self.publish(RaceEvent::FlagTriggered {
+                    flag_id,
+                    igt_ms,
+                    source: FlagSource::Poll,
+                });
+            }
+        }
+
+        // Subscribers act on this frame's events (sends, splits, overlay)
+        self.dispatch_events();
+
+        // Key item pickup and objective flags stay set, so a slower poll is enough
+        if !(self.key_items.is_empty()
+            && self.objectives.is_empty()
+            && !self.race_state.checkpoints.has_flags())
+            && self.is_race_running()
+            && self.last_item_poll.elapsed() >= Duration::from_millis(500)
+        {
+            self.last_item_poll = Instant::now();
+            let igt_ms = self.game_state.read_igt().unwrap_or(0);
+            let reader = &self.event_flag_reader;
+            let flag_reads = &mut self.flag_reads;
+            let obtained = self.key_items.poll(igt_ms, |flag| {
+                let result = reader.is_flag_set(flag);
+                flag_reads.record(flag, result);
+                result
+            });
+            let completed = self.objectives.poll(igt_ms, |flag| {
+                let result = reader.is_flag_set(flag);
+                flag_reads.record(flag, result);
+                result
+            });
+            let crossed = self.race_state.checkpoints.poll(igt_ms, |flag| {
+                let result = reader.is_flag_set(flag);
+                flag_reads.record(flag, result);
+                result
+            });
+            for item in obtained {
+                info!(item_id = item.id, name = %item.name, "[RACE] Key item obtained");
+                let text = self
+                    .locale
+                    .format("toast.obtained", &[("item", &item.name)]);
+                self.notify(ToastKind::Item, text);
+            }
+            for objective in completed {
+                info!(objective_id = objective.id, text = %objective.text, "[RACE] Objective complete");
+                self.notify(
+                    ToastKind::Discovery,
+                    self.locale.format(
+                        "toast.objective_complete",
+                        &[("objective", &objective.text)],
+                    ),
+                );
+            }
+            self.announce_checkpoints(crossed);
+        }
+
+        if self.last_character_check.elapsed() >= CHARACTER_CHECK_INTERVAL {
+            self.last_character_check = Instant::now();
+            self.check_character();
+            self.check_ng_cycle();
+            self.check_bosses();
+            if self.is_race_running() && !self.am_i_finished() {
+                self.check_kindling();
+                self.check_graces();
+            }
+        }
+
+        if self.last_flask_poll.elapsed() >= FLASK_POLL_INTERVAL {
+            self.last_flask_poll = Instant::now();
+            let shown = self.config.race_timer.enabled && self.timer_format().contains("{flasks}");
+            self.flasks = shown.then(|| self.game_state.read_flasks()).flatten();
+        }
+
+        self.check_afk();
+        self.check_gate_proximity();
+        self.check_boss_fight();
+
+        // Rich Presence runs regardless of the server connection
+        #[cfg(feature = "discord")]
+        if self.last_presence_update.elapsed() >= PRESENCE_INTERVAL {
+            self.last_presence_update = Instant::now();
+            if let (Some(discord), Some(presence)) = (self.discord.as_ref(), self.race_presence()) {
+                discord.publish(presence);
             }
         }
 
@@ -474,35 +1199,113 @@ impl RaceTracker {
         if !self.ready_sent {
             if !self.config.server.training {
                 self.ws_client.send_ready();
-                self.last_sent_debug = Some("ready".to_string());
                 info!("[RACE] Sent ready signal");
             }
             self.ready_sent = true;
+            // Requests still queued on the old socket were dropped
+            self.hints_requested.clear();
+            // A new connection starts out active
+            self.afk_sent = false;
 
             if self.is_race_running() && !self.am_i_finished() {
                 // Drain event flags buffered during disconnection
-                for (flag_id, flag_igt) in self.pending_event_flags.drain(..) {
-                    self.ws_client.send_event_flag(flag_id, flag_igt);
-                    self.last_sent_debug =
-                        Some(format!("event_flag({}, igt={})", flag_id, flag_igt));
+                for (flag_id, flag_igt) in std::mem::take(&mut self.pending_event_flags) {
+                    self.send_event_flag(flag_id, flag_igt);
                     info!(flag_id, "[RACE] Buffered event flag sent");
                 }
 
                 // Safety-net rescan: catch any flags still set in memory that polling missed
-                for &flag_id in &self.event_ids {
-                    if !self.triggered_flags.contains(&flag_id) {
-                        if let Some(true) = self.event_flag_reader.is_flag_set(flag_id) {
-                            self.triggered_flags.insert(flag_id);
-                            self.ws_client.send_event_flag(flag_id, igt_ms);
-                            self.last_sent_debug =
-                                Some(format!("event_flag({}, igt={})", flag_id, igt_ms));
-                            info!(flag_id, "[RACE] Event flag re-sent after reconnect");
-                        }
-                    }
+                for flag_id in self.take_newly_set_flags() {
+                    self.send_event_flag(flag_id, igt_ms);
+                    info!(flag_id, "[RACE] Event flag re-sent after reconnect");
+                }
+
+                // Pickups may have been lost with the old socket; the server ignores repeats
+                self.key_items.requeue_all();
+                self.objectives.requeue_all();
+                self.race_state.checkpoints.requeue_all();
+            }
+        }
+
+        // Kept until a server that knows ng_cycle is connected
+        if self.protocol_version >= 8 {
+            if let Some((clear_count, ng_igt)) = self.unsent_ng_cycle.take() {
+                self.ws_client.send_ng_cycle(clear_count, ng_igt);
+            }
+        }
+
+        if self.protocol_version >= 12 {
+            for reload in std::mem::take(&mut self.unsent_save_reloads) {
+                self.ws_client.send_save_reloaded(reload);
+            }
+        }
+
+        if self.protocol_version >= 16 {
+            for kindling in std::mem::take(&mut self.unsent_kindling) {
+                self.ws_client.send_kindling_used(kindling);
+            }
+        }
+
+        if self.protocol_version >= 25 {
+            for anomaly in std::mem::take(&mut self.unsent_timing_drifts) {
+                self.ws_client.send_timing_drift(anomaly);
+            }
+        }
+
+        if self.protocol_version >= 18 {
+            for grace in std::mem::take(&mut self.unsent_graces) {
+                self.ws_client
+                    .send_grace_discovered(grace.flag_id, grace.igt_ms);
+            }
+        }
+
+        // Kept in sync with the detector, the leaderboard greys out idle players
+        if self.protocol_version >= 10 {
+            let afk = self.config.afk.notify_server && self.afk.is_afk();
+            if afk != self.afk_sent {
+                self.ws_client.send_afk(afk, igt_ms);
+                self.afk_sent = afk;
+            }
+        }
+
+        if self.config.hints.enabled && self.protocol_version >= 9 {
+            self.request_exit_hints();
+        }
+
+        if self.is_race_running() && !self.am_i_finished() {
+            for (item_id, item_igt) in self.key_items.take_unsent() {
+                self.ws_client.send_item_obtained(item_id, item_igt);
+            }
+            for (objective_id, objective_igt) in self.objectives.take_unsent() {
+                self.ws_client
+                    .send_objective_complete(objective_id, objective_igt);
+            }
+            if self.protocol_version >= 15 {
+                for (checkpoint_id, checkpoint_igt) in self.race_state.checkpoints.take_unsent() {
+                    self.ws_client
+                        .send_checkpoint_crossed(checkpoint_id, checkpoint_igt);
                 }
             }
         }
 
+        // Resend event flags the server never acknowledged (silently dropped socket)
+        if self.is_race_running() && !self.am_i_finished() {
+            for pending in self.discoveries.due(self.send_clock()) {
+                warn!(
+                    flag_id = pending.flag_id,
+                    seq = pending.seq,
+                    "[RACE] Event flag not acknowledged, resending"
+                );
+                let key = self.event_flag_key(pending.flag_id);
+                self.ws_client.send_event_flag(
+                    pending.flag_id,
+                    pending.igt_ms,
+                    Some(pending.seq),
+                    key,
+                );
+            }
+        }
+
         // One-time flag reader diagnostic (first poll with event_ids)
         if !self.flags_diagnosed && !self.event_ids.is_empty() {
             self.flags_diagnosed = true;
@@ -554,10 +1357,12 @@ impl RaceTracker {
         // Send periodic status updates (every 1 second, only when IGT is ticking and race running)
         // During quit-outs IGT is 0 — skip to avoid erroneous data
         // Stop once finished — IGT is frozen at finish time
+        // Paused by the organizer — the leaderboard keeps the IGT of the pause
         if self.last_status_update.elapsed() >= Duration::from_secs(1)
             && igt_ms > 0
             && self.is_race_running()
             && !self.am_i_finished()
+            && !self.pause.is_paused()
         {
             self.ws_client.send_status_update(igt_ms, deaths);
             self.last_status_update = Instant::now();
@@ -571,22 +1376,36 @@ impl RaceTracker {
                 match status {
                     ConnectionStatus::Connected => {
                         self.ready_sent = false; // Reset for reconnection
-                        self.set_status("Server connected".to_string());
+                        self.last_connection_error = None;
+                        self.notify(
+                            ToastKind::Connection,
+                            self.locale.get("toast.connected").to_string(),
+                        );
                     }
                     ConnectionStatus::Reconnecting => {
                         self.pending_event_flags
                             .extend(self.deferred_event_flags.drain(..));
-                        self.set_status("Reconnecting to server...".to_string());
+                        self.notify(
+                            ToastKind::Connection,
+                            self.locale.get("toast.reconnecting").to_string(),
+                        );
                     }
                     ConnectionStatus::Error => {
-                        let msg = self
-                            .last_auth_error
-                            .take()
-                            .unwrap_or_else(|| "Server maintenance".to_string());
-                        self.set_status(msg);
+                        let msg = match self.last_auth_error.take() {
+                            // Auth errors can echo the token or race: keep them off stream
+                            Some(_) if self.config.overlay.profile.preset().hide_sensitive => {
+                                self.locale.get("toast.auth_failed").to_string()
+                            }
+                            Some(msg) => msg,
+                            None => self.locale.get("toast.maintenance").to_string(),
+                        };
+                        self.notify(ToastKind::Warning, msg);
                     }
                     ConnectionStatus::Disconnected => {
-                        self.set_status("Disconnected".to_string());
+                        self.notify(
+                            ToastKind::Connection,
+                            self.locale.get("toast.disconnected").to_string(),
+                        );
                     }
                     ConnectionStatus::Connecting => {
                         // Silent — the dot indicator handles initial connection
@@ -598,39 +1417,55 @@ impl RaceTracker {
                 race,
                 seed,
                 participants,
+                protocol_version,
+                objectives,
+                checkpoints,
+                permissions,
+                event_delay_secs,
+                zone_privacy,
             } => {
                 info!(race = %race.name, participant_id = %participant_id, participants = participants.len(), "[WS] Auth OK");
-                self.last_received_debug = Some(format!(
-                    "auth_ok(race={}, {} players)",
-                    race.name,
-                    participants.len()
-                ));
+                // A new connection starts over with a full leaderboard
+                self.race_state.leaderboard_sync.reset();
                 self.my_participant_id = Some(participant_id);
+                self.auth_failure = None;
+                self.protocol_version = protocol_version;
+                if permissions != self.permissions {
+                    info!(permissions = permissions.0, "[RACE] Race permissions");
+                }
+                self.permissions = permissions;
+                self.zone_privacy = zone_privacy;
+                let event_delay = Duration::from_secs(event_delay_secs.into());
+                if event_delay != self.ws_client.event_delay() {
+                    info!(event_delay_secs, "[RACE] Broadcast delay");
+                }
+                self.ws_client.set_event_delay(event_delay);
                 self.event_ids = seed.event_ids.clone();
                 self.finish_event = seed.finish_event;
+                self.update_flag_trace_ids();
+                self.key_items.set_items(seed.key_items.clone());
+                self.segments.set_segments(seed.segments.clone());
+                self.objectives.set_objectives(objectives);
+                self.race_state.checkpoints.set_checkpoints(checkpoints);
                 // Don't clear triggered_flags on reconnect: they track which flags
                 // have already been detected. Pending flags are in pending_event_flags.
                 // After (re)auth, the server sends the player's current zone — reveal
                 // it immediately without requiring a loading cycle.
-                self.loading_exit_time = Some(Instant::now() - ZONE_REVEAL_DELAY);
+                self.loading_exit_time =
+                    Some(Instant::now() - self.config.detection.zone_reveal_delay());
+                self.race_state.started_at_ms = race
+                    .started_at
+                    .as_deref()
+                    .and_then(clock_sync::parse_timestamp_ms);
                 self.race_state.race = Some(race);
                 self.frozen_igt_ms = None;
 
                 // Detect seed mismatch (stale seed pack after re-roll)
-                let config_seed_id = &self.config.server.seed_id;
-                if !config_seed_id.is_empty() {
-                    if let Some(ref server_seed_id) = seed.seed_id {
-                        if config_seed_id != server_seed_id {
-                            warn!(
-                                config = %config_seed_id,
-                                server = %server_seed_id,
-                                "Seed mismatch — seed pack is outdated"
-                            );
-                            self.seed_mismatch = true;
-                        } else {
-                            self.seed_mismatch = false;
-                        }
-                    }
+                if self.check_seed_mismatch(seed.seed_id.as_deref()) {
+                    self.notify(
+                        ToastKind::Warning,
+                        self.locale.get("toast.seed_outdated").to_string(),
+                    );
                 }
 
                 self.race_state.seed = Some(seed);
@@ -662,27 +1497,81 @@ impl RaceTracker {
                                 // (event flag in item_spawner covers game restarts).
                                 self.items_spawned = true;
                                 let flag_reader = self.event_flag_reader.clone();
+                                let health = Arc::clone(&self.spawner_health);
                                 self.spawner_thread = Some(std::thread::spawn(move || {
-                                    crate::eldenring::item_spawner::spawn_items_blocking(
-                                        items,
-                                        &flag_reader,
-                                    );
+                                    let result = run_supervised(&health, || {
+                                        crate::eldenring::item_spawner::spawn_items_blocking(
+                                            items,
+                                            &flag_reader,
+                                            &health,
+                                        );
+                                    });
+                                    // Not restarted: items may already be half given
+                                    if let Err(msg) = result {
+                                        error!(cause = %msg, "[RACE] Item spawner thread panicked");
+                                    }
                                 }));
                             }
                         }
                     }
                 }
                 self.race_state.participants = participants;
+                // Pause granted or ended while disconnected
+                if protocol_version >= 14 {
+                    let server_pause = self.my_participant().and_then(|p| p.pause.clone());
+                    self.pause.sync(server_pause.as_deref(), Instant::now());
+                }
+                self.replay_event_wal();
+            }
+            IncomingMessage::SeedChanged(seed) => {
+                info!(seed_id = ?seed.seed_id, "[WS] Seed re-rolled");
+                self.reset_for_new_seed(seed);
+            }
+            IncomingMessage::ExitHints { node_id, hints } => {
+                debug!(node = %node_id, count = hints.len(), "[WS] Exit hints received");
+                self.store_exit_hints(&node_id, hints);
+            }
+            IncomingMessage::PauseGranted => {
+                if self.pause.grant(Instant::now()) == Some(PauseEvent::Granted) {
+                    info!("[RACE] Pause granted");
+                    self.notify(
+                        ToastKind::Warning,
+                        self.locale.get("toast.pause_granted").to_string(),
+                    );
+                }
             }
+            IncomingMessage::Resume => match self.pause.resume() {
+                Some(PauseEvent::Resumed) => {
+                    info!("[RACE] Pause ended, race resumed");
+                    // Report the IGT right away instead of on the next tick
+                    self.last_status_update = Instant::now() - Duration::from_secs(1);
+                    self.notify(
+                        ToastKind::Info,
+                        self.locale.get("toast.pause_resumed").to_string(),
+                    );
+                }
+                Some(PauseEvent::Declined) => {
+                    info!("[RACE] Pause request declined");
+                    self.notify(
+                        ToastKind::Warning,
+                        self.locale.get("toast.pause_declined").to_string(),
+                    );
+                }
+                _ => {}
+            },
+            IncomingMessage::SetFlag { flag_id, value } => self.apply_remote_flag(flag_id, value),
             IncomingMessage::AuthError(msg) => {
-                self.last_received_debug = Some(format!("auth_error({})", msg));
                 error!(message = %msg, "[WS] Auth failed");
+                self.auth_failure = Some(msg.clone());
                 self.last_auth_error = Some(msg);
             }
-            IncomingMessage::RaceStart => {
-                self.last_received_debug = Some("race_start".to_string());
-                info!("[WS] Race started!");
-                self.race_state.race_started_at = Some(Instant::now());
+            IncomingMessage::RaceStart { started_at } => {
+                info!(started_at = ?started_at, "[WS] Race started!");
+                // Older servers don't send started_at: start the clock now (server time)
+                self.race_state.started_at_ms = started_at
+                    .as_deref()
+                    .and_then(clock_sync::parse_timestamp_ms)
+                    .or_else(|| Some(self.clock_sync.to_server_ms(precise_clock::unix_time_ms())));
                 // Immediately reflect running status so is_race_running() gates open
                 // without waiting for the race_status_change message that follows.
                 if let Some(ref mut race) = self.race_state.race {
@@ -692,29 +1581,71 @@ impl RaceTracker {
             IncomingMessage::LeaderboardUpdate {
                 participants,
                 leader_splits,
+                generation,
             } => {
-                self.last_received_debug = Some(format!(
-                    "leaderboard_update({} players)",
-                    participants.len()
-                ));
-                debug!(count = participants.len(), "[WS] Leaderboard update");
+                debug!(
+                    count = participants.len(),
+                    ?generation,
+                    "[WS] Leaderboard update"
+                );
+                self.race_state.leaderboard_sync.full(generation);
                 self.race_state.participants = participants;
                 self.race_state.leader_splits = leader_splits;
                 self.race_state.leaderboard_received_at = Some(Instant::now());
+                self.check_position_change();
+            }
+            IncomingMessage::LeaderboardDelta {
+                generation,
+                participants,
+                order,
+                leader_splits,
+            } => {
+                let changed = participants.len();
+                match self.race_state.leaderboard_sync.apply_delta(
+                    &mut self.race_state.participants,
+                    generation,
+                    participants,
+                    &order,
+                ) {
+                    DeltaResult::Applied => {
+                        debug!(changed, generation, "[WS] Leaderboard delta");
+                        self.race_state.leader_splits = leader_splits;
+                        self.race_state.leaderboard_received_at = Some(Instant::now());
+                        self.check_position_change();
+                    }
+                    DeltaResult::RequestResync => {
+                        warn!(generation, "[WS] Leaderboard delta missed, resyncing");
+                        self.ws_client.send_leaderboard_resync();
+                    }
+                    DeltaResult::WaitingResync => {}
+                }
             }
             IncomingMessage::RaceStatusChange(status) => {
-                self.last_received_debug = Some(format!("race_status_change({})", status));
                 info!(status = %status, "[WS] Race status changed");
                 // If race ends and we haven't finished, freeze our current game IGT.
                 // The mod's local participant igt_ms is stale (only updated via
                 // leaderboard_update on events, not on every status_update).
+                // A finish seen locally but not recorded yet keeps its own IGT.
                 if status == "finished" && !self.am_i_finished() {
-                    self.frozen_igt_ms = self.game_state.read_igt();
+                    self.frozen_igt_ms = self
+                        .local_finish_igt_ms
+                        .or_else(|| self.game_state.read_igt());
                     info!(frozen_igt_ms = ?self.frozen_igt_ms, "[WS] Froze game IGT (race ended, player not finished)");
                 }
+                let race_finished = status == "finished";
+                let phase = self.race_phase();
                 if let Some(ref mut race) = self.race_state.race {
                     race.status = status;
                 }
+                if let Some(new_phase) = self.race_phase().filter(|p| Some(*p) != phase) {
+                    info!(phase = new_phase.name(), "[RACE] Overlay phase changed");
+                }
+                if race_finished {
+                    self.pause.reset();
+                    self.position_watch.reset();
+                    self.write_race_summary();
+                    self.export_flag_trace();
+                }
             }
             IncomingMessage::PlayerUpdate(player) => {
                 // Skip debug capture for player_update (too frequent)
@@ -737,17 +1668,96 @@ impl RaceTracker {
                 original_tier,
                 exits,
             } => {
-                self.last_received_debug = Some(format!("zone_update({})", display_name));
+                self.zone_confirmation.clear();
                 info!(node = %node_id, name = %display_name, "[WS] Zone update (pending reveal)");
                 // Last-writer-wins: if two flags fire in rapid succession, only the
                 // final destination zone is shown (intermediate corridor zones are skipped).
+                // The first zone_update (start zone, or current zone on reconnect)
+                // isn't a discovery
+                let newly_discovered =
+                    !self.known_nodes.is_empty() && !self.known_nodes.contains(&node_id);
+                self.known_nodes.insert(node_id.clone());
+                if self.lobby.state() != LobbyState::Racing && self.is_race_running() {
+                    self.lobby_zone = Some(node_id.clone());
+                }
+                if self.is_race_running() && !self.am_i_finished() {
+                    let igt_ms = self.game_state.read_igt().unwrap_or(0);
+                    let crossed = self.race_state.checkpoints.zone_entered(&node_id, igt_ms);
+                    self.announce_checkpoints(crossed);
+                }
+                self.cache_zone(&node_id, &display_name, tier, original_tier, &exits);
                 self.pending_zone_update = Some(ZoneUpdateData {
+                    node_id,
                     display_name,
                     tier,
                     original_tier,
                     exits,
+                    newly_discovered,
+                    scaling_warning: None,
+                    cached: false,
                 });
             }
+            IncomingMessage::ClockSync {
+                client_sent_ms,
+                server_ms,
+                client_received_ms,
+            } => {
+                if let Some(sample) =
+                    self.clock_sync
+                        .add_sample(client_sent_ms, server_ms, client_received_ms)
+                {
+                    debug!(
+                        offset_ms = sample.offset_ms,
+                        rtt_ms = sample.rtt_ms,
+                        "[WS] Clock sync sample"
+                    );
+                }
+            }
+            IncomingMessage::EventFlagAck(seq) => {
+                if let Some(flag_id) = self.discoveries.ack(seq) {
+                    debug!(seq, flag_id, "[WS] Event flag acknowledged");
+                    if let Some(race_id) = self.race_state.race.as_ref().map(|r| r.id.clone()) {
+                        let line = self.event_wal.acked(&race_id, flag_id);
+                        self.append_event_wal(line);
+                    }
+                }
+            }
+            IncomingMessage::ItemObtained {
+                participant_id,
+                player_name,
+                item_name,
+            } => {
+                // Our own pickups were already shown when detected
+                if self.my_participant_id.as_deref() != Some(participant_id.as_str()) {
+                    self.notify(
+                        ToastKind::Item,
+                        self.locale.format(
+                            "toast.item_other",
+                            &[("player", &player_name), ("item", &item_name)],
+                        ),
+                    );
+                }
+            }
+            IncomingMessage::RaceActivity {
+                participant_id,
+                player_name,
+                kind,
+                zone_name,
+                igt_ms,
+            } => {
+                // The player's own milestones are already on the overlay
+                if self.my_participant_id.as_deref() != Some(participant_id.as_str()) {
+                    let activity = Activity {
+                        player_name,
+                        kind,
+                        zone_name,
+                        igt_ms,
+                        received_at: Instant::now(),
+                    };
+                    self.activity_feed
+                        .push(activity, &self.config.activity_feed);
+                }
+            }
             IncomingMessage::RequeueEventFlag { flag_id, igt_ms } => {
                 // Event flag was in the outgoing channel but never transmitted before
                 // disconnect. Re-buffer it so it gets sent after reconnection.
@@ -755,15 +1765,1264 @@ impl RaceTracker {
                 info!(flag_id, "[WS] Re-queued drained event flag");
             }
             IncomingMessage::Error(e) => {
-                self.last_received_debug = Some(format!("error({})", e));
                 warn!(error = %e, "[WS] Error");
+                self.last_connection_error = Some(e);
             }
         }
     }
 
-    // Public getters for UI
-    pub fn ws_status(&self) -> ConnectionStatus {
-        self.ws_client.status()
+    /// Compare the installed seed pack (config seed_id) with the server's seed.
+    /// Packs without a seed_id are never flagged. Returns true when the
+    /// mismatch is new.
+    fn check_seed_mismatch(&mut self, server_seed_id: Option<&str>) -> bool {
+        let config_seed_id = &self.config.server.seed_id;
+        let Some(server_seed_id) = server_seed_id else {
+            return false;
+        };
+        if config_seed_id.is_empty() {
+            return false;
+        }
+        if config_seed_id != server_seed_id {
+            warn!(
+                config = %config_seed_id,
+                server = %server_seed_id,
+                "Seed mismatch — seed pack is outdated"
+            );
+            let newly = !self.seed_mismatch;
+            self.seed_mismatch = true;
+            newly
+        } else {
+            self.seed_mismatch = false;
+            self.seed_rerolled = false;
+            false
+        }
+    }
+
+    /// The organizer re-rolled the seed: forget every flag and zone of the old
+    /// one. The race is still in setup, so no progress is lost.
+    fn reset_for_new_seed(&mut self, seed: SeedInfo) {
+        self.event_ids = seed.event_ids.clone();
+        self.finish_event = seed.finish_event;
+        self.update_flag_trace_ids();
+        self.triggered_flags.clear();
+        self.pending_event_flags.clear();
+        self.deferred_event_flags.clear();
+        self.discoveries = DiscoveryOutbox::new(precise_clock::unix_time_ms().max(0) as u64);
+        self.key_items = KeyItemWatcher::new();
+        self.key_items.set_items(seed.key_items.clone());
+        self.race_state.current_zone = None;
+        self.pending_zone_update = None;
+        self.zone_flag = None;
+        self.offline_zone = None;
+        self.zone_confirmation.clear();
+        self.known_nodes.clear();
+        self.progression = ProgressionTracker::default();
+        self.zone_log.clear();
+        self.zone_graph = ZoneGraph::default();
+        self.exit_pins.clear();
+        self.boss_kills.clear();
+        self.death_watch.reset();
+        self.death_log.clear();
+        self.run_splits.clear();
+        self.segments.set_segments(seed.segments.clone());
+        self.segments.reset();
+        self.activity_feed.clear();
+        self.pb_delta_ms = None;
+        self.local_finish_igt_ms = None;
+        self.flags_diagnosed = false;
+        self.hints_requested.clear();
+        self.afk.reset();
+        self.lobby.reset();
+        self.lobby_zone = None;
+        self.last_lobby_query = None;
+        self.boss_fight.reset();
+        self.pause.reset();
+        self.position_watch.reset();
+        self.save_reloads.reset();
+        self.unsent_save_reloads.clear();
+        self.kindling.reset();
+        self.unsent_kindling.clear();
+        self.drift.reset();
+        self.unsent_timing_drifts.clear();
+        self.graces.reset();
+        self.unsent_graces.clear();
+
+        self.check_seed_mismatch(seed.seed_id.as_deref());
+        self.seed_rerolled = self.seed_mismatch;
+        self.last_seed_pack_check = Instant::now();
+        self.race_state.seed = Some(seed);
+        self.notify(
+            ToastKind::Warning,
+            self.locale.get("toast.seed_rerolled").to_string(),
+        );
+    }
+
+    /// Re-read the config's seed_id, so extracting the new seed pack clears
+    /// the mismatch without restarting the game or editing the config.
+    fn check_installed_seed_pack(&mut self) {
+        let Some(dir) = self.dll_dir.as_deref() else {
+            return;
+        };
+        let seed_id = match RaceConfig::read_seed_id(dir) {
+            Ok(seed_id) => seed_id,
+            Err(e) => {
+                debug!(error = %e, "[SEED] Could not re-read config");
+                return;
+            }
+        };
+        if seed_id == self.config.server.seed_id {
+            return;
+        }
+        info!(seed_id = %seed_id, "[SEED] Seed pack changed on disk");
+        self.config.server.seed_id = seed_id;
+        let server_seed_id = self
+            .race_state
+            .seed
+            .as_ref()
+            .and_then(|s| s.seed_id.clone());
+        self.check_seed_mismatch(server_seed_id.as_deref());
+        if !self.seed_mismatch {
+            self.notify(
+                ToastKind::Info,
+                self.locale.get("toast.seed_installed").to_string(),
+            );
+        }
+    }
+
+    pub(crate) fn seed_key(&self) -> Option<&str> {
+        self.race_state
+            .seed
+            .as_ref()
+            .and_then(|s| s.seed_id.as_deref())
+            .or(Some(self.config.server.seed_id.as_str()).filter(|id| !id.is_empty()))
+    }
+
+    /// Ask the server for the community hints of the current zone's exits,
+    /// unless they are cached for this seed or already asked for.
+    fn request_exit_hints(&mut self) {
+        let Some(node_id) = self.race_state.current_zone.as_ref().map(|z| &z.node_id) else {
+            return;
+        };
+        let Some(seed) = self.seed_key() else {
+            return;
+        };
+        if self.exit_hints.contains(seed, node_id) || self.hints_requested.contains(node_id) {
+            return;
+        }
+        let node_id = node_id.clone();
+        self.hints_requested.insert(node_id.clone());
+        self.ws_client.send_exit_hints_request(node_id);
+    }
+
+    /// Cache the hints of a zone and write the cache file
+    fn store_exit_hints(&mut self, node_id: &str, hints: Vec<ExitHint>) {
+        let Some(seed) = self.seed_key().map(str::to_string) else {
+            return;
+        };
+        self.exit_hints.insert(&seed, node_id, hints);
+        let Some(dir) = self.dll_dir.as_deref() else {
+            return;
+        };
+        let path = dir.join(HintCache::FILENAME);
+        if let Err(e) = self.exit_hints.to_json().and_then(|contents| {
+            fs::write(&path, contents).map_err(|e| format!("Failed to write hints: {}", e))
+        }) {
+            warn!(error = %e, "[HINTS] Failed to save exit hints");
+        }
+    }
+
+    /// Cache a zone_update for this seed, linked to the flag sent at the
+    /// last loading exit, and write the cache file when it changed
+    fn cache_zone(
+        &mut self,
+        node_id: &str,
+        display_name: &str,
+        tier: Option<i32>,
+        original_tier: Option<i32>,
+        exits: &[ExitInfo],
+    ) {
+        let flag = self.zone_flag.take();
+        let Some(seed) = self.seed_key().map(str::to_string) else {
+            return;
+        };
+        let zone = CachedZone {
+            display_name: display_name.to_string(),
+            tier,
+            original_tier,
+            exits: exits.to_vec(),
+        };
+        let mut changed = self.zone_cache.store_zone(&seed, node_id, zone);
+        if let Some(flag_id) = flag {
+            changed |= self.zone_cache.link_flag(&seed, flag_id, node_id);
+        }
+        let Some(dir) = self.dll_dir.as_deref().filter(|_| changed) else {
+            return;
+        };
+        let path = dir.join(ZoneCache::FILENAME);
+        if let Err(e) = self.zone_cache.to_json().and_then(|contents| {
+            fs::write(&path, contents).map_err(|e| format!("Failed to write zones: {}", e))
+        }) {
+            warn!(error = %e, "[ZONES] Failed to save the zone cache");
+        }
+    }
+
+    /// Resolve a fog gate flag crossed offline from the zone cache, so the
+    /// zone can still be shown at the loading exit
+    pub(crate) fn resolve_offline_flag(&mut self, flag_id: u32) {
+        let Some(seed) = self.seed_key() else {
+            return;
+        };
+        let pos = self.game_state.read_position();
+        let from = self
+            .race_state
+            .current_zone
+            .as_ref()
+            .map(|z| z.node_id.as_str());
+        let resolved = self.zone_cache.resolve_flag(
+            seed,
+            flag_id,
+            from,
+            pos.as_ref()
+                .map(|p| (p.map_id_str.as_str(), [p.x, p.y, p.z])),
+        );
+        if let Some((node_id, _)) = resolved {
+            info!(flag_id, node = %node_id, "[ZONES] Offline flag resolved from the cache");
+        }
+        self.offline_zone = resolved.map(|(node_id, _)| node_id.to_string());
+    }
+
+    /// The cached zone an offline flag resolved to, to reveal at the loading exit
+    pub(crate) fn take_offline_zone(&mut self) -> Option<ZoneUpdateData> {
+        let node_id = self.offline_zone.take()?;
+        let zone = self.zone_cache.zone(self.seed_key()?, &node_id)?;
+        Some(ZoneUpdateData {
+            display_name: zone.display_name.clone(),
+            tier: zone.tier,
+            original_tier: zone.original_tier,
+            exits: zone.exits.clone(),
+            newly_discovered: false,
+            scaling_warning: None,
+            cached: true,
+            node_id,
+        })
+    }
+
+    /// Record the first arrival in a zone and compare it with the personal best
+    pub(crate) fn record_split(&mut self, zone: &ZoneUpdateData) {
+        if !self.is_race_running() || self.am_i_finished() {
+            return;
+        }
+        if self.run_splits.iter().any(|s| s.node_id == zone.node_id) {
+            // Revisit: no split, no delta
+            self.pb_delta_ms = None;
+            return;
+        }
+        let Some(igt_ms) = self.game_state.read_igt() else {
+            return;
+        };
+        self.pb_delta_ms = self
+            .seed_key()
+            .and_then(|seed| self.splits.personal_best(seed))
+            .and_then(|pb| pb.delta(&zone.node_id, igt_ms));
+        self.run_splits.push(Split {
+            node_id: zone.node_id.clone(),
+            name: zone.display_name.clone(),
+            igt_ms,
+        });
+    }
+
+    /// Start and end the seed's segments on arrival in a zone
+    pub(crate) fn record_segments(&mut self, node_id: &str, igt_ms: u32) {
+        if !self.is_race_running() || self.am_i_finished() {
+            return;
+        }
+        for segment in self.segments.zone_entered(node_id, igt_ms) {
+            let time = format_time_u32(segment.elapsed_ms(igt_ms));
+            info!(
+                segment = %segment.name,
+                %time,
+                deaths = segment.deaths,
+                "[RACE] Segment complete"
+            );
+            let text = self.locale.format(
+                "toast.segment_complete",
+                &[
+                    ("segment", &segment.name),
+                    ("time", &time),
+                    ("deaths", &segment.deaths),
+                ],
+            );
+            self.notify(ToastKind::Info, text);
+        }
+    }
+
+    fn save_personal_best(&mut self) {
+        let Some(final_igt_ms) = self.my_participant().map(|p| p.igt_ms) else {
+            return;
+        };
+        let (Some(dir), Some(seed)) = (self.dll_dir.clone(), self.seed_key().map(str::to_string))
+        else {
+            return;
+        };
+        if final_igt_ms <= 0
+            || !self
+                .splits
+                .record_run(&seed, final_igt_ms as u32, self.run_splits.clone())
+        {
+            return;
+        }
+        let path = dir.join(SplitsFile::FILENAME);
+        match self.splits.to_json().and_then(|contents| {
+            fs::write(&path, contents).map_err(|e| format!("Failed to write splits: {}", e))
+        }) {
+            Ok(()) => {
+                info!(seed = %seed, final_igt_ms, "[RACE] New personal best saved");
+                self.notify(
+                    ToastKind::Info,
+                    self.locale.get("toast.personal_best").to_string(),
+                );
+            }
+            Err(e) => error!(error = %e, "[RACE] Failed to save personal best"),
+        }
+    }
+
+    /// Read the loaded character: report it to the server when it's new, warn
+    /// when it doesn't match the seed before the start or is switched mid-race.
+    fn check_character(&mut self) {
+        let Some(current) = self.game_state.read_character() else {
+            return;
+        };
+        // Kept up to date for the auth of the next reconnection
+        self.ws_client.set_character(current.clone());
+        if let Some(change) = self.character.observe(current.clone()) {
+            info!(
+                slot = ?current.slot,
+                level = current.level,
+                class = character::class_name(current.class_id),
+                "[CHARACTER] Character loaded"
+            );
+            if self.protocol_version >= 7 && self.ws_client.is_connected() {
+                self.ws_client.send_character(current.clone());
+            }
+            // The other character's kindling count and graces aren't a baseline
+            self.kindling.reset();
+            self.graces.rebase();
+            if let CharacterChange::Switched { previous } = change {
+                if self.is_race_running() {
+                    warn!(
+                        previous = ?previous.slot,
+                        current = ?current.slot,
+                        "[CHARACTER] Character switched mid-race"
+                    );
+                    let warning = self.locale.format(
+                        "warning.character_switched",
+                        &[("name", &current.name), ("level", &current.level)],
+                    );
+                    self.notify(ToastKind::Warning, warning.clone());
+                    self.character_warning = Some(warning);
+                    return;
+                }
+            }
+        }
+
+        // The level goes up once racing: only check the seed's expectation before
+        if self.is_race_running() || self.am_i_finished() {
+            return;
+        }
+        let mismatch = self
+            .race_state
+            .seed
+            .as_ref()
+            .and_then(|s| s.expected_character.as_ref())
+            .and_then(|expected| character::expectation_mismatch(expected, &current, &self.locale));
+        if mismatch.is_some() && mismatch != self.character_warning {
+            warn!(warning = ?mismatch, "[CHARACTER] Unexpected character");
+            self.notify(
+                ToastKind::Warning,
+                self.locale.get("toast.wrong_character").to_string(),
+            );
+        }
+        self.character_warning = mismatch;
+    }
+
+    /// Read the clear count: completing the game (NG+) breaks the seed, so warn
+    /// the player and tell the server.
+    fn check_ng_cycle(&mut self) {
+        let Some(clear_count) = self.game_state.read_clear_count() else {
+            return;
+        };
+        if clear_count == 0 {
+            // A fresh save was loaded
+            self.ng_warning = None;
+        }
+        let Some(event) = self.ng_cycle.observe(clear_count) else {
+            return;
+        };
+        let label = ng_label(event.clear_count());
+        let warning = match event {
+            NgEvent::Entered { .. } => {
+                warn!(
+                    clear_count,
+                    "[NG+] Game completed, character entered {}", label
+                );
+                self.locale
+                    .format("warning.game_completed", &[("cycle", &label)])
+            }
+            NgEvent::AlreadyNgPlus { .. } => {
+                warn!(clear_count, "[NG+] Character already in {}", label);
+                self.locale
+                    .format("warning.already_ng_plus", &[("cycle", &label)])
+            }
+        };
+        self.notify(ToastKind::Warning, warning.clone());
+        self.ng_warning = Some(warning);
+        let igt_ms = self.game_state.read_igt().unwrap_or(0);
+        self.unsent_ng_cycle = Some((clear_count, igt_ms));
+    }
+
+    /// Read the Messmer's Kindling count: a drop means one was burnt, which
+    /// DLC races often restrict, so tell the player and the server.
+    fn check_kindling(&mut self) {
+        let count = self.game_state.read_key_item_count(MESSMERS_KINDLING_ID);
+        let igt_ms = self.game_state.read_igt().unwrap_or(0);
+        let Some(kindling) = self.kindling.observe(count, igt_ms) else {
+            return;
+        };
+        info!(
+            igt_ms,
+            used = kindling.used,
+            remaining = kindling.remaining,
+            "[RACE] Messmer's Kindling used"
+        );
+        self.notify(
+            ToastKind::Item,
+            self.locale
+                .format("toast.kindling_used", &[("remaining", &kindling.remaining)]),
+        );
+        self.unsent_kindling.push(kindling);
+    }
+
+    /// Count the major bosses defeated on the save
+    fn check_bosses(&mut self) {
+        let reader = &self.event_flag_reader;
+        let defeated = boss_flags::count_defeated(|flag_id| reader.is_flag_set(flag_id));
+        if defeated.is_some() && defeated != self.bosses_defeated {
+            info!(defeated = ?defeated, "[RACE] Major bosses defeated");
+            self.bosses_defeated = defeated;
+        }
+    }
+
+    /// Scan the graces' unlock flags for the ones touched since the last scan
+    fn check_graces(&mut self) {
+        if !self.config.graces.enabled {
+            return;
+        }
+        let reader = &self.event_flag_reader;
+        let unlocked = graces::read_unlocked(|flag_id| reader.is_flag_set(flag_id));
+        let igt_ms = self.game_state.read_igt().unwrap_or(0);
+        let zone = self.current_zone_info().map(|z| z.display_name.clone());
+        for grace in self.graces.observe(unlocked, igt_ms, zone.as_deref()) {
+            info!(
+                flag_id = grace.flag_id,
+                igt_ms,
+                zone = ?grace.zone,
+                found = self.graces.found().len(),
+                "[RACE] Site of Grace found"
+            );
+            self.unsent_graces.push(grace);
+        }
+    }
+
+    /// IGT or deaths going backwards mean a save was reloaded: drop the zone
+    /// state captured before it and report the reload to the server.
+    fn check_save_reload(&mut self, igt_ms: Option<u32>) {
+        let deaths = self.game_state.read_deaths();
+        let Some(reload) = self.save_reloads.observe(igt_ms, deaths) else {
+            return;
+        };
+        warn!(
+            igt_ms = reload.igt_ms,
+            igt_delta_ms = reload.igt_delta_ms,
+            death_delta = reload.death_delta,
+            "[RACE] Save reload detected"
+        );
+        // The pending zone and any captured warp belong to the old save; the
+        // next loading exit queries the zone from the reloaded position
+        self.pending_zone_update = None;
+        self.loading_exit_time = None;
+        self.zone_confirmation.clear();
+        self.warp_captured_at = None;
+        crate::eldenring::warp_hook::clear_captured_grace_entity_id();
+        // The reloaded save may hold more kindling than was left
+        self.kindling.reset();
+
+        let delta = crate::core::format_gap(reload.igt_delta_ms);
+        self.notify(
+            ToastKind::Warning,
+            self.locale
+                .format("toast.save_reloaded", &[("delta", &delta)]),
+        );
+        self.unsent_save_reloads.push(reload);
+    }
+
+    /// Compare IGT progression with the monotonic clock (see `core::drift`)
+    fn check_drift(&mut self, igt_ms: Option<u32>) {
+        let Some(anomaly) = self
+            .drift
+            .observe(precise_clock::now_us(), igt_ms, &self.config.drift)
+        else {
+            return;
+        };
+        warn!(
+            igt_ms = anomaly.igt_ms,
+            igt_delta_ms = anomaly.igt_delta_ms,
+            wall_delta_ms = anomaly.wall_delta_ms,
+            "[RACE] IGT drift: {:.3}x real time",
+            anomaly.ratio()
+        );
+        if self.config.drift.report {
+            self.unsent_timing_drifts.push(anomaly);
+        }
+    }
+
+    /// Feed the death history and attribute each new death (see
+    /// `core::death_cause`)
+    fn check_deaths(&mut self, igt_ms: Option<u32>) {
+        if let Some(pos) = self.game_state.read_position() {
+            self.death_watch.record(
+                Instant::now(),
+                DeathFrame {
+                    map_id: pos.map_id,
+                    height: pos.y,
+                    hp: self.game_state.read_hp(),
+                    boss_bar: self.game_state.read_boss_bar().unwrap_or(false),
+                },
+            );
+        }
+        let Some(deaths) = self.game_state.read_deaths() else {
+            return;
+        };
+        let Some(cause) = self.death_watch.observe_deaths(deaths) else {
+            return;
+        };
+        let igt_ms = igt_ms.unwrap_or(0);
+        info!(igt_ms, cause = cause.label(), "[RACE] Death");
+        self.death_log.push(DeathEntry {
+            igt_ms,
+            zone: self.zone_log.last().map(|z| z.name.clone()),
+            cause,
+        });
+        self.segments.death();
+    }
+
+    /// Ask the organizer for a pause (hotkey). The race goes on until the
+    /// server grants it.
+    /// Whether a hotkey with side effects fires this frame: on press, or
+    /// once held for `[hold_confirm] hold_secs`
+    fn confirm_hotkey(
+        &mut self,
+        hotkey: Hotkey,
+        hold: impl FnOnce(&mut Self) -> &mut HoldConfirm,
+    ) -> bool {
+        if !self.config.hold_confirm.enabled {
+            return hotkey.is_just_pressed();
+        }
+        let duration = self.config.hold_confirm.hold_duration();
+        let state = hold(self).update(hotkey.is_held(), Instant::now(), duration);
+        state == HoldState::Confirmed
+    }
+
+    /// The hold-to-confirm hotkey being held, as its `[keybindings]` name,
+    /// binding and ring progress
+    pub(crate) fn hold_progress(&self) -> Option<(&'static str, Hotkey, f32)> {
+        if !self.config.hold_confirm.enabled {
+            return None;
+        }
+        let now = Instant::now();
+        let duration = self.config.hold_confirm.hold_duration();
+        let bindings = &self.config.keybindings;
+        [
+            (
+                "export_graph",
+                bindings.export_graph,
+                &self.hold_export_graph,
+            ),
+            (
+                "request_pause",
+                bindings.request_pause,
+                &self.hold_request_pause,
+            ),
+        ]
+        .into_iter()
+        .find_map(|(action, hotkey, hold)| {
+            hold.progress(now, duration)
+                .map(|progress| (action, hotkey, progress))
+        })
+    }
+
+    fn request_pause(&mut self) {
+        if !self.is_race_running() || self.am_i_finished() || !self.ws_client.is_connected() {
+            debug!("[HOTKEY] Pause request ignored, not racing");
+            return;
+        }
+        if self.protocol_version < 14 {
+            self.notify(
+                ToastKind::Warning,
+                self.locale.get("toast.pause_unsupported").to_string(),
+            );
+            return;
+        }
+        if !self.pause.request() {
+            return;
+        }
+        let igt_ms = self.game_state.read_igt().unwrap_or(0);
+        info!(igt_ms, "[HOTKEY] Pause requested");
+        self.ws_client.send_pause_request(igt_ms);
+        self.notify(
+            ToastKind::Info,
+            self.locale.get("toast.pause_requested").to_string(),
+        );
+    }
+
+    /// Toast when the player gained or lost a leaderboard position (running
+    /// race, until they finish)
+    fn check_position_change(&mut self) {
+        if !self.is_race_running() || self.am_i_finished() {
+            return;
+        }
+        let Some(my_id) = self.my_participant_id.as_deref() else {
+            return;
+        };
+        let change =
+            self.position_watch
+                .observe(Instant::now(), &self.race_state.participants, my_id);
+        let text = match change {
+            Some(PositionChange::Gained { position, passed }) => self.locale.format(
+                "toast.position_gained",
+                &[("player", &passed), ("position", &position)],
+            ),
+            Some(PositionChange::Lost { position, by }) => {
+                self.audio.play(AudioCue::Overtaken);
+                self.locale.format(
+                    "toast.position_lost",
+                    &[("player", &by), ("position", &position)],
+                )
+            }
+            None => return,
+        };
+        info!(text = %text, "[RACE] Leaderboard position changed");
+        self.notify(ToastKind::Position, text);
+    }
+
+    /// Toast the checkpoints just crossed; they are sent with the next sync
+    fn announce_checkpoints(&mut self, crossed: Vec<Checkpoint>) {
+        for checkpoint in crossed {
+            info!(checkpoint_id = checkpoint.id, name = %checkpoint.name, "[RACE] Checkpoint crossed");
+            self.notify(
+                ToastKind::Discovery,
+                self.locale
+                    .format("toast.checkpoint", &[("checkpoint", &checkpoint.name)]),
+            );
+        }
+    }
+
+    /// Scan the loaded map assets for a fog gate near the player. The hint
+    /// only says one is close, so it follows the race's map permission.
+    fn check_gate_proximity(&mut self) {
+        let active = self.config.gate_proximity.enabled
+            && self.permissions.map()
+            && self.is_race_running()
+            && !self.am_i_finished();
+        let scanner = match &self.gate_scanner {
+            Some(scanner) if active => scanner,
+            _ => {
+                self.gate_proximity.reset();
+                return;
+            }
+        };
+        let now = Instant::now();
+        let settings = &self.config.gate_proximity;
+        if !self.gate_proximity.due(now, settings) {
+            return;
+        }
+        let Some(pos) = self.game_state.read_position() else {
+            // Loading screen: the assets of the old blocks are going away
+            self.gate_proximity.reset();
+            return;
+        };
+        let gates = scanner.scan();
+        let nearest = nearest_gate_distance(pos.map_id, [pos.x, pos.y, pos.z], &gates);
+        if self.gate_proximity.observe(now, nearest, settings) {
+            debug!(
+                nearby = self.gate_proximity.is_nearby(),
+                gates = gates.len(),
+                "[RACE] Fog gate proximity changed"
+            );
+        }
+    }
+
+    /// Track the character's movement during the race: warn after the idle
+    /// timeout, then mark the player AFK unless they move or cancel.
+    fn check_afk(&mut self) {
+        // Polled every frame so an old press doesn't cancel a later warning
+        let cancel = self.config.keybindings.cancel_afk.is_just_pressed();
+        if !self.config.afk.enabled
+            || !self.is_race_running()
+            || self.am_i_finished()
+            || self.pause.is_paused()
+        {
+            self.afk.reset();
+            return;
+        }
+        let now = Instant::now();
+        if cancel && self.afk.state() != AfkState::Active {
+            self.afk.cancel(now);
+            info!("[AFK] Canceled by the player");
+            return;
+        }
+        let location = self
+            .game_state
+            .read_position()
+            .map(|p| (p.map_id, [p.x, p.y, p.z]));
+        match self.afk.observe(now, location, &self.config.afk) {
+            Some(AfkEvent::Warned) => {
+                info!(
+                    timeout_minutes = self.config.afk.timeout_minutes,
+                    "[AFK] Idle, warning shown"
+                );
+                self.notify(
+                    ToastKind::Warning,
+                    self.locale.get("toast.afk_warning").to_string(),
+                );
+            }
+            Some(AfkEvent::WentAfk) => info!("[AFK] Player is AFK"),
+            Some(AfkEvent::Returned { was_afk }) => {
+                info!(was_afk, "[AFK] Player moved again");
+                if was_afk {
+                    self.notify(
+                        ToastKind::Info,
+                        self.locale.get("toast.afk_back").to_string(),
+                    );
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Hold the race flags until the start and, with `require_start`, until
+    /// the player is back at the start
+    fn check_lobby(&mut self) {
+        if self.race_state.race.is_none() {
+            return;
+        }
+        // Running with the countdown still on is not started yet
+        let started =
+            !self.is_race_setup() && !matches!(self.countdown(), Some(CountdownPhase::Count(_)));
+        let require_start = self.config.warmup.require_start;
+        // Without the grace's location, the server resolves the zone
+        let by_zone = self
+            .race_state
+            .seed
+            .as_ref()
+            .and_then(|s| s.start.as_ref())
+            .is_some_and(|start| start.map_id.is_none() || start.position.is_none());
+        if require_start
+            && by_zone
+            && self.lobby.state() != LobbyState::Racing
+            && self.is_race_running()
+            && self.ws_client.is_connected()
+            && self
+                .last_lobby_query
+                .is_none_or(|at| at.elapsed() >= LOBBY_ZONE_QUERY_INTERVAL)
+        {
+            self.last_lobby_query = Some(Instant::now());
+            self.send_zone_query(0);
+        }
+        let at_start = match self.race_state.seed.as_ref().and_then(|s| s.start.as_ref()) {
+            _ if !require_start => None,
+            // Nothing to check against: the race isn't held
+            None => Some(true),
+            Some(start) => warmup::is_at_start(
+                start,
+                self.game_state.read_position().as_ref(),
+                self.lobby_zone.as_deref(),
+                self.config.warmup.start_radius,
+            ),
+        };
+        match self.lobby.observe(started, at_start, &self.config.warmup) {
+            Some(LobbyEvent::Started) => info!("[WARMUP] Race started, flags counted"),
+            Some(LobbyEvent::AwayFromStart) => {
+                info!("[WARMUP] Race started away from the start, flags held");
+                self.notify(
+                    ToastKind::Warning,
+                    self.locale.get("toast.return_to_start").to_string(),
+                );
+            }
+            Some(LobbyEvent::BackAtStart) => {
+                info!("[WARMUP] Back at the start, flags counted");
+                self.notify(
+                    ToastKind::Info,
+                    self.locale.get("toast.back_at_start").to_string(),
+                );
+            }
+            None => {}
+        }
+    }
+
+    /// Follow the boss health bar for the overlay's boss fight mode
+    fn check_boss_fight(&mut self) {
+        let settings = &self.config.boss_fight;
+        if !settings.minimize {
+            self.boss_fight.reset();
+            return;
+        }
+        let bar = self.game_state.read_boss_bar();
+        if self
+            .boss_fight
+            .observe(Instant::now(), bar, settings.restore_delay())
+        {
+            debug!(
+                in_fight = self.boss_fight.in_fight(),
+                "[RACE] Boss fight overlay mode changed"
+            );
+        }
+    }
+
+    /// Keep the IGT of the finish flag detection, before the server ack
+    pub(crate) fn capture_local_finish(&mut self, igt_ms: u32) {
+        if self.local_finish_igt_ms.is_some()
+            || igt_ms == 0
+            || !self.is_race_running()
+            || self.am_i_finished()
+        {
+            return;
+        }
+        self.local_finish_igt_ms = Some(igt_ms);
+        info!(igt_ms, "[RACE] Finish detected locally");
+        self.audio.play(AudioCue::Finish);
+        self.take_screenshot(ScreenshotTrigger::Finish, igt_ms);
+    }
+
+    pub(crate) fn record_boss_kill(&mut self, flag_id: u32, igt_ms: u32) {
+        if self.boss_kills.iter().any(|k| k.flag_id == flag_id) {
+            return;
+        }
+        self.boss_kills.push(BossKill {
+            flag_id,
+            igt_ms,
+            final_boss: self.finish_event == Some(flag_id),
+        });
+        // The final boss gets its finish screenshot instead
+        if self.finish_event != Some(flag_id) {
+            self.take_screenshot(ScreenshotTrigger::BossKill, igt_ms);
+        }
+    }
+
+    /// Capture the game window now and save it in the background, if the
+    /// settings ask for this trigger
+    fn take_screenshot(&mut self, trigger: ScreenshotTrigger, igt_ms: u32) {
+        let settings = &self.config.screenshots;
+        if !settings.captures(trigger) {
+            return;
+        }
+        let Some(dir) = self.dll_dir.as_ref().map(|d| d.join(&settings.folder)) else {
+            return;
+        };
+        let file_name = screenshot::file_name(&self.export_id(), self.seed_key(), igt_ms, trigger);
+        match capture_game_window() {
+            Ok(capture) => {
+                info!(trigger = trigger.label(), file = %file_name, "[RACE] Screenshot captured");
+                self.screenshot_jobs
+                    .push(save_png(capture, &dir, &file_name));
+            }
+            Err(e) => {
+                warn!(trigger = trigger.label(), error = %e, "[RACE] Screenshot failed");
+                self.notify(
+                    ToastKind::Warning,
+                    self.locale
+                        .format("toast.screenshot_failed", &[("error", &e)]),
+                );
+            }
+        }
+    }
+
+    /// Toast the screenshots whose file has been written
+    fn poll_screenshot_jobs(&mut self) {
+        let (done, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.screenshot_jobs)
+            .into_iter()
+            .partition(|job| job.is_finished());
+        self.screenshot_jobs = pending;
+        for job in done {
+            let result = job
+                .join()
+                .unwrap_or_else(|_| Err("screenshot worker panicked".to_string()));
+            match result {
+                Ok(path) => {
+                    info!(path = %path.display(), "[RACE] Screenshot saved");
+                    let file = path
+                        .file_name()
+                        .map(|f| f.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    self.notify(
+                        ToastKind::Info,
+                        self.locale
+                            .format("toast.screenshot_saved", &[("file", &file)]),
+                    );
+                }
+                Err(e) => {
+                    warn!(error = %e, "[RACE] Screenshot not saved");
+                    self.notify(
+                        ToastKind::Warning,
+                        self.locale
+                            .format("toast.screenshot_failed", &[("error", &e)]),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Self-test hotkey: dismiss the checklist on screen, or start a run
+    fn toggle_self_test(&mut self) {
+        if self.self_test_report.take().is_some() || self.self_test_run.is_some() {
+            return;
+        }
+        self.start_self_test();
+    }
+
+    fn start_self_test(&mut self) {
+        info!("[SELF-TEST] Started");
+        self.self_test_run = Some(SelfTestRun::start(
+            Instant::now(),
+            self.game_state.read_igt(),
+        ));
+        self.notify(
+            ToastKind::Info,
+            self.locale.get("self_test.running").to_string(),
+        );
+    }
+
+    /// Start the startup run once the character is loaded, and finish a run
+    /// once the second IGT sample is due
+    fn update_self_test(&mut self) {
+        if self.self_test_on_startup && self.game_state.read_position().is_some() {
+            self.self_test_on_startup = false;
+            self.start_self_test();
+        }
+        let Some(run) = &self.self_test_run else {
+            return;
+        };
+        if !run.is_due(Instant::now()) {
+            return;
+        }
+        let first_igt = run.first_igt();
+        self.self_test_run = None;
+
+        let position = self.game_state.read_position();
+        let loading = position.is_none();
+        let seed_reads: Vec<Option<bool>> = self
+            .event_ids
+            .iter()
+            .map(|&flag| self.event_flag_reader.is_flag_set(flag))
+            .collect();
+        let server_seed_id = self
+            .race_state
+            .seed
+            .as_ref()
+            .and_then(|s| s.seed_id.as_deref());
+        let checks = vec![
+            self_test::check_position(position.as_ref()),
+            self_test::check_igt(first_igt, self.game_state.read_igt(), loading),
+            self_test::check_deaths(self.game_state.read_deaths()),
+            self_test::check_event_flags(
+                self.event_flag_reader.is_flag_set(VANILLA_FLAG),
+                &seed_reads,
+            ),
+            self_test::check_connection(
+                self.ws_client.is_enabled(),
+                self.ws_client.is_connected(),
+                self.last_connection_error.as_deref(),
+            ),
+            self_test::check_auth(
+                self.my_participant_id.is_some(),
+                self.auth_failure.as_deref(),
+            ),
+            self_test::check_seed_pack(
+                self.dll_dir.as_deref(),
+                &self.config.server.seed_id,
+                server_seed_id,
+            ),
+            self_test::check_font(&self.config.overlay.font_path, self.font_data.is_some()),
+            self_test::check_icons(self.icons.is_some()),
+        ];
+        let mut report = SelfTestReport::new(
+            checks,
+            local_timestamp(),
+            env!("CARGO_PKG_VERSION").to_string(),
+        );
+        report.game_version = self.game_version.clone();
+        self.write_self_test_report(&report);
+        self.self_test_report = Some(report);
+    }
+
+    /// Write the self-test report next to the DLL, for support requests
+    fn write_self_test_report(&mut self, report: &SelfTestReport) {
+        let text = report.to_text();
+        info!(report = %text, "[SELF-TEST] Done");
+        let Some(dir) = self.dll_dir.clone() else {
+            return;
+        };
+        let file = report.file_name();
+        match fs::write(dir.join(&file), text) {
+            Ok(()) => {
+                self.notify(
+                    ToastKind::Info,
+                    self.locale.format("self_test.saved", &[("file", &file)]),
+                );
+            }
+            Err(e) => {
+                warn!(error = %e, "[SELF-TEST] Report not saved");
+                self.notify(
+                    ToastKind::Warning,
+                    self.locale.get("self_test.save_failed").to_string(),
+                );
+            }
+        }
+    }
+
+    /// Write the discovered zone graph next to the DLL as .dot and .json.
+    fn export_zone_graph(&mut self) {
+        if self.zone_graph.is_empty() {
+            self.notify(
+                ToastKind::Warning,
+                self.locale.get("toast.graph_empty").to_string(),
+            );
+            return;
+        }
+        let Some(dir) = self.dll_dir.clone() else {
+            return;
+        };
+        let id = self.export_id();
+        let dot_path = dir.join(ZoneGraph::file_name(&id, "dot"));
+        let json_path = dir.join(ZoneGraph::file_name(&id, "json"));
+        let result = fs::write(&dot_path, self.zone_graph.to_dot())
+            .map_err(|e| format!("Failed to write zone graph: {}", e))
+            .and_then(|()| self.zone_graph.to_json(self.seed_key()))
+            .and_then(|json| {
+                fs::write(&json_path, json)
+                    .map_err(|e| format!("Failed to write zone graph: {}", e))
+            });
+        match result {
+            Ok(()) => {
+                info!(
+                    path = %dot_path.display(),
+                    zones = self.zone_graph.nodes().len(),
+                    "[HOTKEY] Zone graph exported"
+                );
+                self.notify(
+                    ToastKind::Info,
+                    self.locale.format(
+                        "toast.graph_exported",
+                        &[("count", &self.zone_graph.nodes().len())],
+                    ),
+                );
+            }
+            Err(e) => {
+                warn!(error = %e, "[HOTKEY] Zone graph export failed");
+                self.notify(
+                    ToastKind::Warning,
+                    self.locale.get("toast.graph_failed").to_string(),
+                );
+            }
+        }
+    }
+
+    /// Write the WebSocket message trace next to the DLL (debug window button)
+    pub(crate) fn export_message_trace(&mut self) {
+        let Some(dir) = self.dll_dir.clone() else {
+            return;
+        };
+        let path = dir.join(MessageTrace::file_name(&self.export_id()));
+        let (text, count) = match self.ws_client.trace().lock() {
+            Ok(trace) => (trace.export(), trace.len()),
+            Err(_) => return,
+        };
+        match fs::write(&path, text) {
+            Ok(()) => {
+                info!(path = %path.display(), count, "[WS] Message trace exported");
+                self.notify(
+                    ToastKind::Info,
+                    self.locale
+                        .format("toast.trace_exported", &[("count", &count)]),
+                );
+            }
+            Err(e) => {
+                warn!(error = %e, "[WS] Message trace export failed");
+                self.notify(
+                    ToastKind::Warning,
+                    self.locale.get("toast.trace_failed").to_string(),
+                );
+            }
+        }
+    }
+
+    /// Trace the seed's event flags, finish event included
+    fn update_flag_trace_ids(&mut self) {
+        let Some(trace) = self.flag_trace.as_mut() else {
+            return;
+        };
+        let mut ids = self.event_ids.clone();
+        if let Some(finish) = self.finish_event.filter(|id| !ids.contains(id)) {
+            ids.push(finish);
+        }
+        trace.set_ids(&ids);
+    }
+
+    /// Write the flag trace next to the DLL (race finished, debug window button)
+    pub(crate) fn export_flag_trace(&mut self) {
+        let (Some(dir), Some(trace)) = (self.dll_dir.as_ref(), self.flag_trace.as_ref()) else {
+            return;
+        };
+        let path = dir.join(FlagTrace::file_name(&self.export_id()));
+        let count = trace.transitions().count();
+        match fs::write(&path, trace.export()) {
+            Ok(()) => {
+                info!(path = %path.display(), count, "[RACE] Flag trace exported");
+                self.notify(
+                    ToastKind::Info,
+                    self.locale
+                        .format("toast.flag_trace_exported", &[("count", &count)]),
+                );
+            }
+            Err(e) => {
+                warn!(error = %e, "[RACE] Flag trace export failed");
+                self.notify(
+                    ToastKind::Warning,
+                    self.locale.get("toast.flag_trace_failed").to_string(),
+                );
+            }
+        }
+    }
+
+    /// Keep a detection incident with what this frame knew, and write the
+    /// incident report with `[detection] incident_report`
+    pub(crate) fn record_incident(&mut self, kind: IncidentKind) {
+        let pos = self.read_position();
+        let context = FrameContext {
+            igt_ms: self.read_igt(),
+            map_id: pos.as_ref().map(|p| p.map_id_str.clone()),
+            position: pos.as_ref().map(|p| [p.x, p.y, p.z]),
+            zone: self.current_zone_info().map(|z| z.node_id.clone()),
+            connected: self.ws_client.is_connected(),
+            race_status: self.race_info().map(|r| r.status.clone()),
+        };
+        let incident = self.detection_health.record(Instant::now(), kind, context);
+        warn!("[DETECTION] {}", incident.describe());
+
+        if !self.config.detection.incident_report {
+            return;
+        }
+        let Some(dir) = self.dll_dir.as_ref() else {
+            return;
+        };
+        let path = dir.join(DetectionHealth::file_name(&self.export_id()));
+        if let Err(e) = fs::write(&path, self.detection_health.export()) {
+            warn!(error = %e, "[DETECTION] Incident report write failed");
+        }
+    }
+
+    /// Race id for export file names (seed key or "practice" outside races)
+    fn export_id(&self) -> String {
+        self.race_state
+            .race
+            .as_ref()
+            .map(|race| race.id.clone())
+            .or_else(|| self.seed_key().map(str::to_string))
+            .unwrap_or_else(|| "practice".to_string())
+    }
+
+    /// Write the post-race summary next to the DLL (once per session).
+    fn write_race_summary(&mut self) {
+        let format = self.config.post_race.format;
+        if self.summary_written || format.extension().is_none() {
+            return;
+        }
+        let (Some(dir), Some(race)) = (self.dll_dir.as_ref(), self.race_state.race.as_ref()) else {
+            return;
+        };
+        let me = self.my_participant();
+        let server_finish_igt_ms = me
+            .filter(|p| p.status == "finished")
+            .map(|p| p.igt_ms.max(0) as u32);
+        let summary = RaceSummary {
+            race_id: race.id.clone(),
+            race_name: race.name.clone(),
+            player: me.map(|p| {
+                p.twitch_display_name
+                    .clone()
+                    .unwrap_or_else(|| p.twitch_username.clone())
+            }),
+            final_igt_ms: server_finish_igt_ms
+                .or(self.local_finish_igt_ms)
+                .or(self.frozen_igt_ms)
+                .or_else(|| self.game_state.read_igt())
+                .unwrap_or(0),
+            play_time_ms: self.igt_analyzer.play_time().as_millis() as u32,
+            load_time_ms: self.igt_analyzer.load_time().as_millis() as u32,
+            deaths: self.game_state.read_deaths().unwrap_or(0),
+            local_finish_igt_ms: self.local_finish_igt_ms,
+            server_finish_igt_ms,
+            placement: self
+                .my_participant_id
+                .as_deref()
+                .and_then(|id| post_race::placement(&self.race_state.participants, id)),
+            participant_count: self.race_state.participants.len(),
+            zones: self.zone_log.clone(),
+            segments: self.segments.results(),
+            boss_kills: self.boss_kills.clone(),
+            death_log: self.death_log.clone(),
+        };
+
+        let (Some(file_name), Some(rendered)) = (summary.file_name(format), summary.render(format))
+        else {
+            return;
+        };
+        self.summary_written = true;
+        let path = dir.join(file_name);
+        match rendered.and_then(|contents| {
+            fs::write(&path, contents).map_err(|e| format!("Failed to write summary: {}", e))
+        }) {
+            Ok(()) => {
+                info!(path = %path.display(), "[RACE] Race summary written");
+                self.notify(
+                    ToastKind::Info,
+                    self.locale.get("toast.summary_saved").to_string(),
+                );
+            }
+            Err(e) => error!(error = %e, "[RACE] Race summary export failed"),
+        }
+    }
+
+    // Public getters for UI
+    pub fn ws_status(&self) -> ConnectionStatus {
+        self.ws_client.status()
+    }
+
+    /// Race elapsed time on the server's clock, None until the start time is known
+    pub fn race_elapsed_ms(&self) -> Option<i64> {
+        let started_at_ms = self.race_state.started_at_ms?;
+        Some(
+            self.clock_sync
+                .race_elapsed_ms(started_at_ms, precise_clock::unix_time_ms()),
+        )
+    }
+
+    /// Start countdown on the server's clock, None outside the countdown
+    pub fn countdown(&self) -> Option<CountdownPhase> {
+        let started_at_ms = self.race_state.started_at_ms?;
+        countdown_phase(
+            started_at_ms,
+            self.clock_sync.to_server_ms(precise_clock::unix_time_ms()),
+        )
     }
 
     pub fn race_info(&self) -> Option<&RaceInfo> {
@@ -786,10 +3045,32 @@ impl RaceTracker {
         self.game_state.read_deaths()
     }
 
+    pub fn read_level(&self) -> Option<u32> {
+        self.game_state.read_level()
+    }
+
+    pub fn read_runes(&self) -> Option<u32> {
+        self.game_state.read_runes()
+    }
+
+    pub fn read_position(&self) -> Option<PlayerPosition> {
+        self.game_state.read_position()
+    }
+
     pub fn current_zone_info(&self) -> Option<&ZoneUpdateData> {
         self.race_state.current_zone.as_ref()
     }
 
+    /// Community hint for an exit of the current zone, when hints are enabled
+    pub fn exit_hint(&self, exit_index: usize) -> Option<&str> {
+        if !self.config.hints.enabled {
+            return None;
+        }
+        let zone = self.race_state.current_zone.as_ref()?;
+        self.exit_hints
+            .hint(self.seed_key()?, &zone.node_id, exit_index)
+    }
+
     pub fn my_participant_id(&self) -> Option<&String> {
         self.my_participant_id.as_ref()
     }
@@ -799,20 +3080,198 @@ impl RaceTracker {
         self.race_state.participants.iter().find(|p| &p.id == id)
     }
 
-    /// Set a status message that will be displayed temporarily (3 seconds).
-    pub fn set_status(&mut self, message: String) {
-        self.status_message = Some((message, Instant::now()));
+    /// Event flags that just became set, marked as triggered
+    fn take_newly_set_flags(&mut self) -> Vec<u32> {
+        let mut newly_set = Vec::new();
+        for &id in self.event_ids.iter() {
+            if self.triggered_flags.contains(&id) {
+                continue;
+            }
+            let result = self.event_flag_reader.is_flag_set(id);
+            self.flag_reads.record(id, result);
+            if result == Some(true) {
+                newly_set.push(id);
+            }
+        }
+        self.triggered_flags.extend(&newly_set);
+        newly_set
     }
 
-    /// Get current status message if still valid (within 3 seconds).
-    pub fn get_status(&self) -> Option<&str> {
-        self.status_message.as_ref().and_then(|(msg, time)| {
-            if time.elapsed() < Duration::from_secs(3) {
-                Some(msg.as_str())
-            } else {
-                None
+    /// Send an event flag, tracked until acknowledged when the server supports it.
+    /// Flags the server already acknowledged aren't sent again.
+    pub(crate) fn send_event_flag(&mut self, flag_id: u32, igt_ms: u32) {
+        if self.discoveries.is_acked(flag_id) {
+            debug!(
+                flag_id,
+                "[RACE] Event flag already acknowledged, not resent"
+            );
+            return;
+        }
+        let key = self.event_flag_key(flag_id);
+        // Logged first, and sent once the record is synced: only tracked
+        // flags (protocol 3+) ever get an ack
+        if self.protocol_version >= 3 {
+            if let Some(race) = self.race_state.race.as_ref() {
+                let line = self.event_wal.sent(WalRecord {
+                    race_id: race.id.clone(),
+                    flag_id,
+                    igt_ms,
+                    key: key.clone(),
+                    written_at_ms: precise_clock::unix_time_ms(),
+                });
+                self.append_event_wal(line);
+                if let Some(log) = &self.event_log {
+                    let ticket = log.last_ticket();
+                    self.unsynced_event_flags
+                        .push((ticket, flag_id, igt_ms, key));
+                    return;
+                }
             }
-        })
+        }
+        self.transmit_event_flag(flag_id, igt_ms, key);
+    }
+
+    /// Send the held event flags whose log record is synced, in order
+    fn release_synced_event_flags(&mut self) {
+        let Some(log) = &self.event_log else {
+            return;
+        };
+        let synced = self
+            .unsynced_event_flags
+            .iter()
+            .take_while(|(ticket, ..)| log.is_done(*ticket))
+            .count();
+        let released: Vec<_> = self.unsynced_event_flags.drain(..synced).collect();
+        for (_, flag_id, igt_ms, key) in released {
+            self.transmit_event_flag(flag_id, igt_ms, key);
+        }
+    }
+
+    /// Hand an event flag to the WebSocket, tracked on protocol 3+
+    fn transmit_event_flag(&mut self, flag_id: u32, igt_ms: u32, key: Option<String>) {
+        let now = self.send_clock();
+        let seq =
+            (self.protocol_version >= 3).then(|| self.discoveries.track(flag_id, igt_ms, now));
+        self.ws_client.send_event_flag(flag_id, igt_ms, seq, key);
+    }
+
+    /// Once per session, after the first auth_ok: send again the flags of this
+    /// race the last session left unacknowledged (crash before the ack)
+    fn replay_event_wal(&mut self) {
+        if self.event_wal_replayed || self.protocol_version < 3 {
+            return;
+        }
+        let Some(race_id) = self.race_state.race.as_ref().map(|r| r.id.clone()) else {
+            return;
+        };
+        self.event_wal_replayed = true;
+        if self.am_i_finished() {
+            // The finish made it: nothing left to send for this race
+            if self.event_wal.discard(&race_id) {
+                self.rewrite_event_wal();
+            }
+            return;
+        }
+        if !self.is_race_running() {
+            return;
+        }
+        let records: Vec<WalRecord> = self.event_wal.unacked(&race_id).cloned().collect();
+        let now = self.send_clock();
+        for record in records {
+            if self.discoveries.is_acked(record.flag_id) {
+                continue;
+            }
+            warn!(
+                flag_id = record.flag_id,
+                igt_ms = record.igt_ms,
+                "[RACE] Event flag left unacknowledged by the last session, resending"
+            );
+            self.triggered_flags.insert(record.flag_id);
+            let seq = self.discoveries.track(record.flag_id, record.igt_ms, now);
+            // The key it was first sent with, so the server dedupes it
+            let key = record.key.or_else(|| self.event_flag_key(record.flag_id));
+            self.ws_client
+                .send_event_flag(record.flag_id, record.igt_ms, Some(seq), key);
+        }
+    }
+
+    /// Append a record to the event log, synced (off the game thread) so it
+    /// survives a crash. The log is emptied once nothing is pending.
+    fn append_event_wal(&mut self, line: Option<String>) {
+        let Some(line) = line else {
+            return;
+        };
+        if self.event_wal.is_empty() {
+            self.rewrite_event_wal();
+        } else if let Some(log) = &mut self.event_log {
+            log.append(line);
+        }
+    }
+
+    /// Replace the event log with its pending records
+    fn rewrite_event_wal(&mut self) {
+        if let Some(log) = &mut self.event_log {
+            log.replace(self.event_wal.compacted());
+        }
+    }
+
+    /// Idempotency key of an event flag, when the server dedupes them (protocol 23+)
+    fn event_flag_key(&self, flag_id: u32) -> Option<String> {
+        if self.protocol_version < 23 {
+            return None;
+        }
+        let race = self.race_state.race.as_ref()?;
+        Some(discovery::idempotency_key(
+            &race.id,
+            self.character.current(),
+            flag_id,
+        ))
+    }
+
+    /// Time at which a progress event queued now goes out: resend and retry
+    /// timers start after the broadcast delay
+    pub(crate) fn send_clock(&self) -> Instant {
+        Instant::now() + self.ws_client.event_delay()
+    }
+
+    /// Set an event flag on the server's request, if `[remote_flags]` allows it
+    fn apply_remote_flag(&mut self, flag_id: u32, value: bool) {
+        let allowed = self.config.remote_flags.check(flag_id, |id| {
+            self.event_ids.contains(&id)
+                || self.finish_event == Some(id)
+                || self.objectives.objectives().any(|(o, _)| o.flag_id == id)
+                || self
+                    .race_state
+                    .checkpoints
+                    .checkpoints()
+                    .iter()
+                    .any(|c| c.flag_id == Some(id))
+        });
+        if let Err(refusal) = allowed {
+            warn!(flag_id, %refusal, "[FLAGS] Refused set_flag from the server");
+            let text = self
+                .locale
+                .format("toast.remote_flag_refused", &[("flag", &flag_id)]);
+            self.notify(ToastKind::Warning, text);
+            return;
+        }
+        if self.event_flag_reader.set_flag(flag_id, value) {
+            info!(flag_id, value, "[FLAGS] Event flag set by the organizer");
+            self.notify(
+                ToastKind::Info,
+                self.locale.get("toast.remote_flag_set").to_string(),
+            );
+        } else {
+            warn!(flag_id, "[FLAGS] set_flag failed (flag page not loaded)");
+        }
+    }
+
+    /// Show a toast, unless its category is disabled in `[toasts]`
+    pub fn notify(&mut self, kind: ToastKind, message: String) {
+        if self.config.toasts.is_enabled(kind) {
+            self.toasts.push(kind, message, Instant::now());
+            self.auto_hide.wake(Instant::now());
+        }
     }
 
     pub fn debug_info(&self) -> DebugInfo<'_> {
@@ -839,11 +3298,15 @@ impl RaceTracker {
         };
 
         DebugInfo {
-            last_sent: self.last_sent_debug.as_deref(),
-            last_received: self.last_received_debug.as_deref(),
             flag_reader_status,
             vanilla_sanity,
             sample_reads,
+            animation: self.game_state.read_animation(),
+            position: self.game_state.read_position(),
+            captured_grace: crate::eldenring::warp_hook::get_captured_grace_entity_id(),
+            flag_reads: &self.flag_reads,
+            threads: vec![self.ws_client.health(), &self.spawner_health],
+            memory_reads: checked_read::stats(),
         }
     }
 }
@@ -852,6 +3315,174 @@ impl RaceTracker {
 // FONT LOADING
 // =============================================================================
 
+/// Load personal best splits; a missing or unreadable file starts empty
+fn load_splits(dll_dir: &Path) -> SplitsFile {
+    let path = dll_dir.join(SplitsFile::FILENAME);
+    if !path.exists() {
+        return SplitsFile::default();
+    }
+    match fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read splits: {}", e))
+        .and_then(|contents| SplitsFile::parse(&contents))
+    {
+        Ok(splits) => {
+            info!(seeds = splits.seeds.len(), "Loaded personal best splits");
+            splits
+        }
+        Err(e) => {
+            warn!(error = %e, path = %path.display(), "Ignoring splits file");
+            SplitsFile::default()
+        }
+    }
+}
+
+/// Load the zone notes; a missing or unreadable file starts empty
+fn load_notes(dll_dir: &Path) -> NotesFile {
+    let path = dll_dir.join(NotesFile::FILENAME);
+    if !path.exists() {
+        return NotesFile::default();
+    }
+    match fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read notes: {}", e))
+        .and_then(|contents| NotesFile::parse(&contents))
+    {
+        Ok(notes) => {
+            info!(seeds = notes.seeds.len(), "Loaded zone notes");
+            notes
+        }
+        Err(e) => {
+            warn!(error = %e, path = %path.display(), "Ignoring notes file");
+            NotesFile::default()
+        }
+    }
+}
+
+/// Load cached exit hints; a missing or unreadable file starts empty
+fn load_exit_hints(dll_dir: &Path) -> HintCache {
+    let path = dll_dir.join(HintCache::FILENAME);
+    if !path.exists() {
+        return HintCache::default();
+    }
+    match fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read hints: {}", e))
+        .and_then(|contents| HintCache::parse(&contents))
+    {
+        Ok(hints) => {
+            info!(seeds = hints.seeds.len(), "Loaded cached exit hints");
+            hints
+        }
+        Err(e) => {
+            warn!(error = %e, path = %path.display(), "Ignoring exit hints file");
+            HintCache::default()
+        }
+    }
+}
+
+/// Load the cached zones; a missing or unreadable file starts empty
+fn load_zone_cache(dll_dir: &Path) -> ZoneCache {
+    let path = dll_dir.join(ZoneCache::FILENAME);
+    if !path.exists() {
+        return ZoneCache::default();
+    }
+    match fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read zones: {}", e))
+        .and_then(|contents| ZoneCache::parse(&contents))
+    {
+        Ok(zones) => {
+            info!(seeds = zones.seeds.len(), "Loaded cached zones");
+            zones
+        }
+        Err(e) => {
+            warn!(error = %e, path = %path.display(), "Ignoring zone cache file");
+            ZoneCache::default()
+        }
+    }
+}
+
+/// Load the event flags the last session left unacknowledged, and compact
+/// the log; a missing or unreadable file starts empty
+fn load_event_wal(dll_dir: &Path) -> EventWal {
+    let path = dll_dir.join(EventWal::FILENAME);
+    if !path.exists() {
+        return EventWal::default();
+    }
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            warn!(error = %e, path = %path.display(), "Ignoring event log file");
+            return EventWal::default();
+        }
+    };
+    let wal = EventWal::load(&contents, precise_clock::unix_time_ms());
+    if !wal.is_empty() {
+        info!("Loaded unacknowledged event flags");
+    }
+    if let Err(e) = event_log::replace_file(&path, &wal.compacted()) {
+        warn!(error = %e, path = %path.display(), "Failed to compact the event log");
+    }
+    wal
+}
+
+/// Load the world-map calibration: a `worldmap.toml` next to the DLL replaces
+/// the bundled table
+fn load_world_map(dll_dir: Option<&Path>) -> WorldMapTable {
+    let path = dll_dir.map(|dir| dir.join(WorldMapTable::FILENAME));
+    if let Some(path) = path.filter(|p| p.exists()) {
+        match fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read world map table: {}", e))
+            .and_then(|contents| WorldMapTable::parse(&contents))
+        {
+            Ok(table) => {
+                info!(areas = table.areas.len(), "Loaded world map calibration");
+                return table;
+            }
+            Err(e) => warn!(error = %e, path = %path.display(), "Ignoring world map table"),
+        }
+    }
+    WorldMapTable::builtin()
+}
+
+/// Load the overlay strings: a `lang/<code>.toml` next to the DLL first (new
+/// languages, or edits of a built-in one), then the built-in tables, then English
+fn load_locale(dll_dir: Option<&Path>, code: &str) -> Locale {
+    let path = dll_dir.map(|dir| dir.join("lang").join(format!("{}.toml", code)));
+    if let Some(path) = path.filter(|p| p.exists()) {
+        match fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read lang file: {}", e))
+            .and_then(|contents| Locale::parse(code, &contents))
+        {
+            Ok(locale) => {
+                let missing = locale.missing_keys();
+                if !missing.is_empty() {
+                    warn!(
+                        language = code,
+                        count = missing.len(),
+                        first = missing[0],
+                        "Lang file incomplete, missing strings shown in English"
+                    );
+                }
+                info!(language = code, path = %path.display(), "Loaded lang file");
+                return locale;
+            }
+            Err(e) => warn!(error = %e, path = %path.display(), "Ignoring lang file"),
+        }
+    }
+    Locale::builtin(code).unwrap_or_else(|| {
+        warn!(language = code, "Unknown overlay language, using English");
+        Locale::english()
+    })
+}
+
+/// Local time as "2026-03-01 20:15:42", for reports
+fn local_timestamp() -> String {
+    // SAFETY: GetLocalTime has no preconditions
+    let t = unsafe { GetLocalTime() };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        t.wYear, t.wMonth, t.wDay, t.wHour, t.wMinute, t.wSecond
+    )
+}
+
 /// Load font data from file, following the same resolution strategy as er-fog-vizu:
 ///   - Empty path → system default (Segoe UI from C:\Windows\Fonts\)
 ///   - Filename only → try C:\Windows\Fonts\, then DLL directory
@@ -0,0 +1,234 @@
+//! Frames to and from the race server, over a WebSocket or HTTP long-polling
+//!
+//! The WebSocket worker only sees `ServerTransport`, so the auth exchange and
+//! the message loop are the same on both. See `core::transport` for when the
+//! long-poll fallback is used.
+
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, TryRecvError};
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tracing::warn;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{connect, Message, WebSocket};
+
+use crate::core::transport::{
+    endpoint_url, session_url, LongPollBatch, LongPollOpened, LongPollSend, Transport, POLL_WAIT,
+};
+
+/// Longest wait for the server's answer during auth
+const AUTH_READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// Timeout of a long-poll request, beyond the time the server holds a poll
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A connection to the race server carrying protocol frames
+pub trait ServerTransport {
+    fn send(&mut self, frame: Message) -> Result<(), String>;
+
+    /// Next text or binary frame from the server. Blocks until one arrives
+    /// until `set_nonblocking` is called, then returns None when none is
+    /// waiting. A closed connection is an error.
+    fn read(&mut self) -> Result<Option<Message>, String>;
+
+    /// Switch reads to non-blocking, for the message loop
+    fn set_nonblocking(&mut self);
+
+    fn close(&mut self);
+}
+
+/// Open a connection to the race server over `transport`
+pub fn open_transport(
+    base_url: &str,
+    transport: Transport,
+    training: bool,
+    id: &str,
+) -> Result<Box<dyn ServerTransport>, String> {
+    let url = endpoint_url(base_url, transport, training, id);
+    match transport {
+        Transport::WebSocket => {
+            let (socket, _) = connect(url).map_err(|e| format!("Connect failed: {}", e))?;
+            Ok(Box::new(WebSocketTransport { socket }))
+        }
+        Transport::LongPoll => Ok(Box::new(LongPollTransport::open(base_url, &url)?)),
+    }
+}
+
+// =============================================================================
+// WEBSOCKET
+// =============================================================================
+
+struct WebSocketTransport {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+}
+
+impl ServerTransport for WebSocketTransport {
+    fn send(&mut self, frame: Message) -> Result<(), String> {
+        self.socket.send(frame).map_err(|e| format!("Send: {}", e))
+    }
+
+    fn read(&mut self) -> Result<Option<Message>, String> {
+        match self.socket.read() {
+            Ok(frame @ (Message::Text(_) | Message::Binary(_))) => Ok(Some(frame)),
+            Ok(Message::Close(_)) => Err("Server closed".to_string()),
+            Ok(_) => Ok(None),
+            Err(tungstenite::Error::Io(ref e))
+                if e.kind() == std::io::ErrorKind::WouldBlock
+                    || e.kind() == std::io::ErrorKind::Interrupted =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(format!("Read error: {}", e)),
+        }
+    }
+
+    fn set_nonblocking(&mut self) {
+        match self.socket.get_ref() {
+            MaybeTlsStream::Plain(tcp) => {
+                let _ = tcp.set_nonblocking(true);
+            }
+            MaybeTlsStream::NativeTls(tls) => {
+                let _ = tls.get_ref().set_nonblocking(true);
+            }
+            _ => {}
+        }
+    }
+
+    fn close(&mut self) {
+        let _ = self.socket.close(None);
+    }
+}
+
+// =============================================================================
+// HTTP LONG-POLLING
+// =============================================================================
+
+/// A long-poll session: frames are posted one request each, and a poller
+/// thread keeps a poll open to receive the server's
+struct LongPollTransport {
+    agent: ureq::Agent,
+    session_url: String,
+    /// Server messages, then the reason the session ended
+    incoming: Receiver<Result<String, String>>,
+    stop: Arc<AtomicBool>,
+    blocking: bool,
+}
+
+impl LongPollTransport {
+    fn open(base_url: &str, open_url: &str) -> Result<Self, String> {
+        let tls = native_tls::TlsConnector::new().map_err(|e| format!("TLS: {}", e))?;
+        let agent = ureq::AgentBuilder::new()
+            .tls_connector(Arc::new(tls))
+            .timeout_connect(HTTP_TIMEOUT)
+            .timeout_read(POLL_WAIT + HTTP_TIMEOUT)
+            .build();
+
+        let opened: LongPollOpened = agent
+            .post(open_url)
+            .call()
+            .map_err(|e| format!("Connect failed: {}", e))?
+            .into_json()
+            .map_err(|e| format!("Connect failed: {}", e))?;
+        let session_url = session_url(base_url, &opened.session_id);
+
+        let (incoming_tx, incoming) = unbounded();
+        let stop = Arc::new(AtomicBool::new(false));
+        let poller_stop = Arc::clone(&stop);
+        let poller_agent = agent.clone();
+        let poll_url = format!("{}?wait={}", session_url, POLL_WAIT.as_secs());
+        thread::spawn(move || loop {
+            if poller_stop.load(Ordering::SeqCst) {
+                break;
+            }
+            let batch: Result<LongPollBatch, String> = poller_agent
+                .get(&poll_url)
+                .call()
+                .map_err(|e| format!("Poll: {}", e))
+                .and_then(|r| r.into_json().map_err(|e| format!("Poll: {}", e)));
+            match batch {
+                Ok(batch) => {
+                    for message in batch.messages {
+                        let _ = incoming_tx.send(Ok(message));
+                    }
+                    if batch.closed {
+                        let reason = match batch.close_reason {
+                            Some(reason) => format!("Server closed: {}", reason),
+                            None => "Server closed".to_string(),
+                        };
+                        let _ = incoming_tx.send(Err(reason));
+                        break;
+                    }
+                }
+                Err(e) => {
+                    let _ = incoming_tx.send(Err(e));
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            agent,
+            session_url,
+            incoming,
+            stop,
+            blocking: true,
+        })
+    }
+}
+
+impl ServerTransport for LongPollTransport {
+    fn send(&mut self, frame: Message) -> Result<(), String> {
+        let Message::Text(text) = frame else {
+            return Err("Long-polling only carries JSON".to_string());
+        };
+        let body = LongPollSend {
+            messages: vec![text],
+        };
+        let body = serde_json::to_string(&body).map_err(|e| format!("JSON: {}", e))?;
+        self.agent
+            .post(&self.session_url)
+            .set("Content-Type", "application/json")
+            .send_string(&body)
+            .map_err(|e| format!("Send: {}", e))?;
+        Ok(())
+    }
+
+    fn read(&mut self) -> Result<Option<Message>, String> {
+        let received = if self.blocking {
+            match self.incoming.recv_timeout(AUTH_READ_TIMEOUT) {
+                Ok(received) => received,
+                Err(RecvTimeoutError::Timeout) => return Err("Read: timed out".to_string()),
+                Err(RecvTimeoutError::Disconnected) => return Err("Poller stopped".to_string()),
+            }
+        } else {
+            match self.incoming.try_recv() {
+                Ok(received) => received,
+                Err(TryRecvError::Empty) => return Ok(None),
+                Err(TryRecvError::Disconnected) => return Err("Poller stopped".to_string()),
+            }
+        };
+        received.map(|text| Some(Message::Text(text)))
+    }
+
+    fn set_nonblocking(&mut self) {
+        self.blocking = false;
+    }
+
+    fn close(&mut self) {
+        if self.stop.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        // The poller ends with the session; it isn't joined, a poll in
+        // flight can take a while to return
+        if let Err(e) = self.agent.delete(&self.session_url).call() {
+            warn!(error = %e, "[WS] Long-poll session not closed");
+        }
+    }
+}
+
+impl Drop for LongPollTransport {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
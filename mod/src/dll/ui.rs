@@ -1,19 +1,50 @@
 //! Race UI - ImGui overlay for SpeedFog Racing
 
 use std::borrow::Cow;
-use std::time::Duration;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::Instant;
 
 use hudhook::imgui::{
-    Condition, FontConfig, FontGlyphRanges, FontSource, Image, StyleColor, WindowFlags,
+    Condition, ConfigFlags, FontConfig, FontGlyphRanges, FontSource, Io, StyleColor, StyleVar,
+    WindowFlags,
 };
-use hudhook::{ImguiRenderLoop, RenderContext};
+use hudhook::{ImguiRenderLoop, MessageFilter, RenderContext};
 use tracing::{error, info};
 
-use super::death_icon::DeathIcon;
-
+use super::icon_atlas::IconAtlas;
+use super::imgui_renderer::ImguiRenderer;
+use super::map_texture::WorldMapTextures;
+use super::window_focus;
+
+use crate::core::activity_feed::{Activity, ActivityKind};
+use crate::core::afk::AfkState;
+use crate::core::anchor::anchored_position;
+use crate::core::badges;
+use crate::core::checkpoints;
+use crate::core::countdown::CountdownPhase;
+use crate::core::exit_order;
+use crate::core::frame_profiler::{Stage, DEGRADED_REFRESH};
+use crate::core::graces;
+use crate::core::locale::Locale;
+use crate::core::map_utils::exit_hint;
+use crate::core::memory_inspector::animation_label;
+use crate::core::message_trace::Direction;
+use crate::core::overlay_layout::{self, ExitColors, ExitRow, PinHints, PlayerStatusView};
+use crate::core::overlay_profile::OverlayPreset;
+use crate::core::overlay_renderer::{self, TextureHandle};
+use crate::core::panel_cycle::Panel;
+use crate::core::pause::PauseState;
+use crate::core::race_timer::{self, TimerValues};
+use crate::core::self_test::CheckStatus;
+use crate::core::supervisor::WorkerState;
+use crate::core::toast::ToastKind;
+use crate::core::ui_scale::overlay_scale;
+use crate::core::warmup::LobbyState;
+use crate::core::window_layout::{Rect, WindowId};
+use crate::core::zone_privacy::ZoneLabel;
 use crate::eldenring::FlagReaderStatus;
 
-use super::tracker::{FlagReadResult, RaceTracker};
+use super::tracker::{DebugInfo, FlagReadResult, RaceTracker};
 use super::websocket::ConnectionStatus;
 
 impl ImguiRenderLoop for RaceTracker {
@@ -25,14 +56,31 @@ impl ImguiRenderLoop for RaceTracker {
         if let Some(ref font_data) = self.font_data {
             let font_size = self.config.overlay.font_size;
 
-            // Glyph ranges: Basic Latin + Punctuation + Box/Geometric + Arrows + Dagger
-            let glyph_ranges = FontGlyphRanges::from_slice(&[
-                0x0020, 0x00FF, // Basic Latin + Latin Supplement
-                0x2000, 0x206F, // General Punctuation (…, –)
-                0x2500, 0x25FF, // Box Drawing + Block Elements + Geometric Shapes (●)
-                0x2190, 0x21FF, // Arrows (→)
-                0,
-            ]);
+            // Glyph ranges: Basic Latin + Punctuation + Box/Geometric + Arrows + Dagger,
+            // Check Mark, plus CJK when the overlay language needs it (needs a CJK-capable font)
+            let glyph_ranges = if self.locale.needs_cjk_glyphs() {
+                FontGlyphRanges::from_slice(&[
+                    0x0020, 0x00FF, // Basic Latin + Latin Supplement
+                    0x2000, 0x206F, // General Punctuation (…, –)
+                    0x2500, 0x25FF, // Box Drawing + Block Elements + Geometric Shapes (●)
+                    0x2190, 0x21FF, // Arrows (→)
+                    0x2713, 0x2713, // Check Mark (✓)
+                    0x3000, 0x30FF, // CJK Punctuation, Hiragana, Katakana
+                    0x4E00, 0x9FFF, // CJK Ideographs
+                    0xAC00, 0xD7AF, // Hangul
+                    0xFF00, 0xFFEF, // Fullwidth Forms
+                    0,
+                ])
+            } else {
+                FontGlyphRanges::from_slice(&[
+                    0x0020, 0x00FF, // Basic Latin + Latin Supplement
+                    0x2000, 0x206F, // General Punctuation (…, –)
+                    0x2500, 0x25FF, // Box Drawing + Block Elements + Geometric Shapes (●)
+                    0x2190, 0x21FF, // Arrows (→)
+                    0x2713, 0x2713, // Check Mark (✓)
+                    0,
+                ])
+            };
 
             ctx.fonts().add_font(&[FontSource::TtfData {
                 data: font_data,
@@ -43,44 +91,149 @@ impl ImguiRenderLoop for RaceTracker {
                 }),
             }]);
 
+            self.loaded_font_size = font_size;
             info!(size = font_size, "Custom font registered with imgui");
         } else {
             info!("Using default imgui font");
         }
 
-        // Load death icon texture.
+        // Load icon textures.
         // Wrapped in catch_unwind because render_context.load_texture() can panic
         // when the DX12 command queue isn't fully initialized yet.
+        let dll_dir = self.icon_watcher.dll_dir();
         match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            DeathIcon::load(render_context)
+            IconAtlas::load(render_context, dll_dir)
         })) {
-            Ok(Ok(icon)) => {
-                info!("Loaded death icon texture");
-                self.death_icon = Some(icon);
+            Ok(Ok(icons)) => {
+                info!("Loaded icon textures");
+                self.icons = Some(icons);
             }
             Ok(Err(e)) => {
-                error!(error = %e, "Failed to load death icon");
+                error!(error = %e, "Failed to load icons");
             }
             Err(_) => {
-                error!("Death icon texture load panicked (DX12 not ready?)");
+                error!("Icon texture load panicked (DX12 not ready?)");
+            }
+        }
+
+        // World-map images for the position thumbnail (same DX12 caveat)
+        if let Some(dir) = dll_dir.filter(|_| self.config.world_map.enabled) {
+            let table = &self.world_map;
+            match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                WorldMapTextures::load(render_context, dir, table)
+            })) {
+                Ok(textures) => self.map_textures = Some(textures),
+                Err(_) => error!("World map texture load panicked (DX12 not ready?)"),
             }
         }
     }
 
+    fn before_render<'a>(
+        &'a mut self,
+        ctx: &mut hudhook::imgui::Context,
+        render_context: &'a mut dyn RenderContext,
+    ) {
+        // Keyboard navigation and a visible cursor only while the settings panel is open,
+        // so the overlay never steals arrow keys from the game otherwise. Interactive
+        // mode only needs the cursor.
+        let settings_open = self.settings.is_open();
+        let io = ctx.io_mut();
+        io.config_flags
+            .set(ConfigFlags::NAV_ENABLE_KEYBOARD, settings_open);
+        io.mouse_draw_cursor = settings_open || self.note_editor.is_open() || self.interactive;
+
+        // Hot-reload the user icon atlas when its files change on disk
+        if !self.icon_watcher.poll_changed() {
+            return;
+        }
+        if let (Some(icons), Some(dir)) = (self.icons.as_mut(), self.icon_watcher.dll_dir()) {
+            info!("User icon atlas changed on disk, reloading");
+            icons.reload_user(render_context, dir);
+        }
+    }
+
     fn render(&mut self, ui: &mut hudhook::imgui::Ui) {
+        // Throttled while the game is alt-tabbed or minimized
+        let focus = window_focus::current();
+        let (plan, previous) = self
+            .focus_throttle
+            .plan(Instant::now(), focus, &self.config.focus);
+        if let Some(previous) = previous {
+            info!("[FOCUS] {:?} -> {:?}", previous, focus);
+        }
+
         // Per-frame update
-        self.update();
+        if plan.update {
+            let update_start = Instant::now();
+            self.update();
+            self.frame_profiler
+                .record(Stage::Update, update_start.elapsed());
+        }
+        self.check_exit();
+
+        if !plan.render {
+            render_hidden_window(ui);
+            return;
+        }
+        let ui_start = Instant::now();
+        self.render_overlay(ui);
+        self.frame_profiler.record(Stage::Ui, ui_start.elapsed());
+    }
+
+    fn message_filter(&self, _io: &Io) -> MessageFilter {
+        // Keep menu inputs away from the game while the settings panel or the
+        // note editor has them; interactive mode takes the mouse and leaves the
+        // keyboard to the game
+        if self.settings.is_open() || self.note_editor.is_open() {
+            MessageFilter::InputAll
+        } else if self.interactive {
+            MessageFilter::InputMouse
+        } else {
+            MessageFilter::empty()
+        }
+    }
+}
+
+/// Always build a window, even with nothing to show (hudhook crashes otherwise)
+fn render_hidden_window(ui: &hudhook::imgui::Ui) {
+    ui.window("##hidden")
+        .position([-100.0, -100.0], Condition::Always)
+        .size([1.0, 1.0], Condition::Always)
+        .no_decoration()
+        .build(|| {});
+}
+
+/// Leaderboard columns computed from every participant, reused between frames
+/// while the overlay is over its frame budget
+pub(crate) struct LeaderboardLayout {
+    gaps: Vec<Option<i32>>,
+    max_gap_width: f32,
+    max_right_width: f32,
+    computed_at: Instant,
+}
+
+impl RaceTracker {
+    fn render_overlay(&mut self, ui: &hudhook::imgui::Ui) {
+        self.render_settings(ui);
+        self.render_note_editor(ui);
+        // Shown with the overlay hidden too: it's what the player asked for
+        self.render_self_test(ui);
 
-        // Always build a window (hudhook crashes otherwise)
         if !self.show_ui {
-            ui.window("##hidden")
-                .position([-100.0, -100.0], Condition::Always)
-                .size([1.0, 1.0], Condition::Always)
-                .no_decoration()
-                .build(|| {});
+            render_hidden_window(ui);
             return;
         }
 
+        // Faded after a while without changes (`[auto_hide]`)
+        let now = Instant::now();
+        if self.settings.is_open() || self.interactive {
+            self.auto_hide.wake(now);
+        }
+        let fingerprint = self.activity_fingerprint();
+        self.auto_hide.observe(fingerprint, now);
+        let alpha = self.auto_hide.alpha(now, &self.config.auto_hide);
+        let _alpha_token = ui.push_style_var(StyleVar::Alpha(alpha));
+
         let c = &self.cached_colors;
 
         // Push style colors (auto-popped when tokens drop)
@@ -89,64 +242,407 @@ impl ImguiRenderLoop for RaceTracker {
         let _text_disabled_token = ui.push_style_color(StyleColor::TextDisabled, c.text_disabled);
         let _border_token = ui.push_style_color(StyleColor::Border, c.border);
 
-        let [dw, _dh] = ui.io().display_size;
-        let scale = self.config.overlay.font_size / 16.0;
-        let max_width = 320.0 * scale;
+        let display_size = ui.io().display_size;
+        self.ui_scale = overlay_scale(
+            display_size,
+            self.config.overlay.auto_scale,
+            self.config.overlay.scale,
+        );
+        let font_size = self.config.overlay.font_size * self.ui_scale;
+        let font_scale = font_size / self.loaded_font_size;
+        let max_width = 320.0 * font_size / 16.0;
+
+        self.window_layout.set_display(display_size);
+        // Re-anchor whenever the resolution changes (window mode switch, monitor swap)
+        let anchor_cond = if display_size != self.last_display_size {
+            self.last_display_size = display_size;
+            Condition::Always
+        } else {
+            Condition::FirstUseEver
+        };
+        let overlay = &self.config.overlay;
+        let (pos, pivot) = anchored_position(
+            display_size,
+            overlay.anchor,
+            overlay.position_offset_x.scaled(self.ui_scale),
+            overlay.position_offset_y.scaled(self.ui_scale),
+        );
+
+        // Boss fight mode: zone and IGT only until the fight is over
+        let boss_fight = self.boss_fight.in_fight();
+        // Profile and panels of the race phase (`[phases]`)
+        let phase_preset = self
+            .config
+            .phases
+            .preset(overlay.profile, self.race_phase());
+        let preset = if boss_fight {
+            phase_preset.minimized()
+        } else {
+            phase_preset
+        };
+        // Race permissions win over the profile and the hotkeys
+        let allow_leaderboard = !self.config.server.training
+            && preset.show_leaderboard
+            && self.permissions.leaderboard();
+        // Ready room: participants stay listed until the start, even if hidden with F10
+        let ready_room = self.config.countdown.ready_room && self.is_race_setup();
+        let show_leaderboard = (self.show_leaderboard || ready_room) && allow_leaderboard;
+        let show_exits = preset.show_exits && self.permissions.exits_panel();
+        let show_objectives = preset.show_details && !self.objectives.is_empty();
+
+        // Auto-cycle: one panel at a time on a timer, hotkey toggles ignored
+        let (show_exits, show_objectives, show_leaderboard) = if self.config.auto_cycle.enabled {
+            let has_exits = self
+                .current_zone_info()
+                .is_some_and(|z| !z.exits.is_empty());
+            let available = |panel| match panel {
+                Panel::Exits => show_exits && has_exits,
+                Panel::Objectives => show_objectives,
+                Panel::Leaderboard => allow_leaderboard,
+            };
+            let panel =
+                self.panel_scheduler
+                    .tick(Instant::now(), &self.config.auto_cycle, available);
+            (
+                panel == Some(Panel::Exits),
+                panel == Some(Panel::Objectives),
+                panel == Some(Panel::Leaderboard),
+            )
+        } else {
+            (show_exits, show_objectives, show_leaderboard)
+        };
 
-        let flags =
+        let mut flags =
+            WindowFlags::NO_TITLE_BAR | WindowFlags::ALWAYS_AUTO_RESIZE | WindowFlags::NO_SCROLLBAR;
+        // Windows are dragged in interactive mode only
+        if !self.interactive {
+            flags |= WindowFlags::NO_MOVE;
+        }
+
+        // A position dragged on this resolution replaces the anchor
+        let window = ui.window("SpeedFog Race").flags(flags);
+        let window = match self.config.layout.get(display_size, WindowId::Overlay) {
+            Some(saved) => window.position(saved, anchor_cond),
+            None => window.position(pos, anchor_cond).position_pivot(pivot),
+        };
+        // One snapshot of the clocks for every window of this frame
+        let timer = self.timer_values();
+        let mut overlay_rect = None;
+        let mut edit_note = false;
+        window.build(|| {
+            // Live font size changes scale the font built at startup
+            ui.set_window_font_scale(font_scale);
+            self.render_state_banner(ui);
+            self.render_seed_mismatch_warning(ui);
+            self.render_character_warning(ui);
+            self.render_afk_banner(ui);
+            self.render_lobby_banner(ui);
+            self.render_event_delay(ui);
+            self.render_player_status(ui, max_width, &preset, &timer);
+            if preset.show_details {
+                edit_note = self.render_zone_note(ui, max_width);
+                self.render_segment(ui);
+            }
+            if preset.show_details && self.config.graces.enabled {
+                self.render_graces(ui);
+            }
+            if self.show_world_map
+                && self.config.world_map.enabled
+                && self.permissions.map()
+                && !boss_fight
+            {
+                self.render_world_map(ui);
+            }
+            if self.gate_proximity.is_nearby() && !boss_fight {
+                let radius = self.config.gate_proximity.radius_m.round();
+                ui.text_colored(
+                    self.cached_colors.theme.highlight,
+                    self.locale
+                        .format("map.gate_nearby", &[("radius", &radius)]),
+                );
+            }
+            if show_exits {
+                self.render_exits(ui, max_width);
+            }
+            if show_objectives {
+                ui.separator();
+                self.render_objectives(ui, max_width);
+            }
+            if show_leaderboard {
+                ui.separator();
+                self.render_leaderboard(ui, max_width);
+                self.render_activity_feed(ui);
+            }
+            if preset.show_toasts {
+                self.render_toasts(ui);
+            }
+            if self.show_debug && preset.allow_debug {
+                ui.separator();
+                self.render_debug(ui);
+            }
+            overlay_rect = Some(Rect {
+                pos: ui.window_pos(),
+                size: ui.window_size(),
+            });
+        });
+        if let Some(rect) = overlay_rect {
+            self.window_layout
+                .record(WindowId::Overlay, rect, self.interactive);
+        }
+        if edit_note {
+            self.open_note_editor();
+        }
+
+        if self.config.race_timer.enabled {
+            self.render_race_timer(ui, display_size, anchor_cond, &timer);
+        }
+
+        if self.show_debug && preset.allow_debug {
+            self.render_message_trace(ui, display_size);
+        }
+
+        if self.config.countdown.enabled {
+            self.render_countdown(ui, display_size, font_scale);
+        }
+        self.render_hold_confirm(ui, display_size);
+    }
+
+    /// Big race clock in its own window, anchored on its own (top center by
+    /// default) and draggable in interactive mode like the overlay
+    fn render_race_timer(
+        &mut self,
+        ui: &hudhook::imgui::Ui,
+        display_size: [f32; 2],
+        anchor_cond: Condition,
+        timer: &TimerValues,
+    ) {
+        let settings = &self.config.race_timer;
+        let (pos, pivot) = anchored_position(
+            display_size,
+            settings.anchor,
+            settings.position_offset_x.scaled(self.ui_scale),
+            settings.position_offset_y.scaled(self.ui_scale),
+        );
+        let font_scale = settings.font_size * self.ui_scale / self.loaded_font_size;
+        let text = race_timer::timer_text(self.timer_format(), timer);
+        let color = if self.am_i_finished() {
+            self.cached_colors.theme.finished
+        } else {
+            self.cached_colors.theme.igt
+        };
+
+        let mut flags =
             WindowFlags::NO_TITLE_BAR | WindowFlags::ALWAYS_AUTO_RESIZE | WindowFlags::NO_SCROLLBAR;
+        if !self.interactive {
+            flags |= WindowFlags::NO_MOVE | WindowFlags::NO_INPUTS;
+        }
+        let window = ui.window("##race_timer").flags(flags);
+        let window = match self.config.layout.get(display_size, WindowId::RaceTimer) {
+            Some(saved) => window.position(saved, anchor_cond),
+            None => window.position(pos, anchor_cond).position_pivot(pivot),
+        };
+        let mut timer_rect = None;
+        window.build(|| {
+            ui.set_window_font_scale(font_scale);
+            ui.text_colored(color, &text);
+            timer_rect = Some(Rect {
+                pos: ui.window_pos(),
+                size: ui.window_size(),
+            });
+        });
+        if let Some(rect) = timer_rect {
+            self.window_layout
+                .record(WindowId::RaceTimer, rect, self.interactive);
+        }
+    }
 
-        ui.window("SpeedFog Race")
+    /// Progress ring of a hold-to-confirm hotkey being held, below the
+    /// center of the screen
+    fn render_hold_confirm(&self, ui: &hudhook::imgui::Ui, display_size: [f32; 2]) {
+        let Some((action, hotkey, progress)) = self.hold_progress() else {
+            return;
+        };
+        let text = self.locale.format(
+            "overlay.hold_confirm",
+            &[
+                ("key", &hotkey.name()),
+                (
+                    "action",
+                    &self.locale.get(&format!("settings.action.{}", action)),
+                ),
+            ],
+        );
+        let flags =
+            WindowFlags::NO_DECORATION | WindowFlags::NO_INPUTS | WindowFlags::ALWAYS_AUTO_RESIZE;
+        ui.window("##hold_confirm")
             .position(
-                [
-                    dw - max_width - self.config.overlay.position_offset_x,
-                    self.config.overlay.position_offset_y,
-                ],
-                Condition::FirstUseEver,
+                [display_size[0] * 0.5, display_size[1] * 0.6],
+                Condition::Always,
             )
+            .position_pivot([0.5, 0.5])
             .flags(flags)
             .build(|| {
-                self.render_state_banner(ui);
-                self.render_seed_mismatch_warning(ui);
-                self.render_player_status(ui, max_width);
-                self.render_exits(ui, max_width);
-                if !self.config.server.training && self.show_leaderboard {
-                    ui.separator();
-                    self.render_leaderboard(ui, max_width);
+                let radius = 10.0 * self.ui_scale;
+                let thickness = 3.0 * self.ui_scale;
+                let origin = ui.cursor_screen_pos();
+                let center = [origin[0] + radius, origin[1] + radius];
+                {
+                    let draw_list = ui.get_window_draw_list();
+                    draw_list
+                        .add_circle(center, radius, self.cached_colors.text_disabled)
+                        .thickness(thickness)
+                        .build();
+                    // Clockwise from the top, one point per 1/32 turn
+                    let steps = (progress * 32.0).ceil().max(1.0) as usize;
+                    let arc: Vec<[f32; 2]> = (0..=steps)
+                        .map(|i| {
+                            let angle = std::f32::consts::TAU * progress * i as f32 / steps as f32
+                                - std::f32::consts::FRAC_PI_2;
+                            [
+                                center[0] + radius * angle.cos(),
+                                center[1] + radius * angle.sin(),
+                            ]
+                        })
+                        .collect();
+                    draw_list
+                        .add_polyline(arc, self.cached_colors.theme.highlight)
+                        .thickness(thickness)
+                        .build();
                 }
-                self.render_status_message(ui);
-                if self.show_debug {
-                    ui.separator();
-                    self.render_debug(ui);
+                ui.dummy([radius * 2.0, radius * 2.0]);
+                ui.same_line();
+                ui.text(&text);
+            });
+    }
+
+    /// Large 3-2-1-GO centered on screen, synchronized with the server's start time
+    fn render_countdown(&self, ui: &hudhook::imgui::Ui, display_size: [f32; 2], font_scale: f32) {
+        let Some(phase) = self.countdown() else {
+            return;
+        };
+        let theme = &self.cached_colors.theme;
+        let (text, color) = match phase {
+            CountdownPhase::Count(n) => (n.to_string(), theme.waiting),
+            CountdownPhase::Go => (self.locale.get("banner.go").to_string(), theme.finished),
+        };
+        let flags = WindowFlags::NO_DECORATION
+            | WindowFlags::NO_BACKGROUND
+            | WindowFlags::NO_INPUTS
+            | WindowFlags::ALWAYS_AUTO_RESIZE;
+        ui.window("##countdown")
+            .position(
+                [display_size[0] * 0.5, display_size[1] * 0.4],
+                Condition::Always,
+            )
+            .position_pivot([0.5, 0.5])
+            .flags(flags)
+            .build(|| {
+                ui.set_window_font_scale(4.0 * font_scale);
+                ui.text_colored(color, &text);
+            });
+    }
+
+    /// Self-test checklist, centered until dismissed with the hotkey
+    fn render_self_test(&self, ui: &hudhook::imgui::Ui) {
+        let Some(report) = &self.self_test_report else {
+            return;
+        };
+        let c = &self.cached_colors;
+        let _bg_token = ui.push_style_color(StyleColor::WindowBg, c.bg);
+        let _text_token = ui.push_style_color(StyleColor::Text, c.text);
+        let locale = &self.locale;
+        let display_size = ui.io().display_size;
+        let flags = WindowFlags::NO_TITLE_BAR
+            | WindowFlags::NO_INPUTS
+            | WindowFlags::ALWAYS_AUTO_RESIZE
+            | WindowFlags::NO_SCROLLBAR;
+        ui.window("##self_test")
+            .position(
+                [display_size[0] * 0.5, display_size[1] * 0.5],
+                Condition::Always,
+            )
+            .position_pivot([0.5, 0.5])
+            .flags(flags)
+            .build(|| {
+                ui.set_window_font_scale(self.ui_scale);
+                let color = |status| match status {
+                    CheckStatus::Pass => c.theme.finished,
+                    CheckStatus::Warn => c.theme.warning,
+                    CheckStatus::Fail => c.theme.error,
+                };
+                ui.text(locale.get("self_test.title"));
+                ui.same_line();
+                ui.text_colored(color(report.status()), report.status().label());
+                ui.separator();
+                for check in &report.checks {
+                    ui.text_colored(color(check.status), check.status.label());
+                    ui.same_line();
+                    ui.text(locale.get(&format!("self_test.check.{}", check.id.key())));
+                    ui.same_line();
+                    ui.text_disabled(&check.detail);
                 }
+                ui.separator();
+                ui.text_disabled(locale.format(
+                    "self_test.dismiss",
+                    &[("key", &self.config.keybindings.self_test.name())],
+                ));
             });
     }
-}
 
-impl RaceTracker {
     /// Render state banner above player status.
-    /// - SETUP: orange "WAITING FOR START"
+    /// - SETUP: orange "WAITING FOR START" (with the ready count in the ready room)
+    /// - RUNNING (countdown): orange "STARTING IN N"
     /// - RUNNING (first 3s): green "GO!"
     /// - FINISHED: green "RACE FINISHED"
     /// - RUNNING (after 3s): nothing
     fn render_state_banner(&self, ui: &hudhook::imgui::Ui) {
-        let orange = [1.0, 0.75, 0.0, 1.0];
-        let green = [0.0, 1.0, 0.0, 1.0];
+        let orange = self.cached_colors.theme.waiting;
+        let green = self.cached_colors.theme.finished;
+        let locale = &self.locale;
 
         if let Some(race) = self.race_info() {
             match race.status.as_str() {
                 "setup" => {
-                    ui.text_colored(orange, "WAITING FOR START");
+                    ui.text_colored(orange, locale.get("banner.waiting"));
+                    if self.config.countdown.ready_room {
+                        let participants = &self.race_state.participants;
+                        let ready = participants.iter().filter(|p| p.status == "ready").count();
+                        ui.same_line();
+                        ui.text_disabled(locale.format(
+                            "banner.ready_count",
+                            &[("ready", &ready), ("total", &participants.len())],
+                        ));
+                    }
                 }
                 "running" => {
-                    if let Some(started_at) = self.race_state.race_started_at {
-                        if started_at.elapsed() < Duration::from_secs(3) {
-                            ui.text_colored(green, "GO!");
+                    match self.pause.state() {
+                        PauseState::Paused { since } => {
+                            let paused_for = since.elapsed().as_millis().min(u32::MAX as u128);
+                            ui.text_colored(
+                                orange,
+                                locale.format(
+                                    "banner.paused",
+                                    &[("time", &format_time_u32(paused_for as u32))],
+                                ),
+                            );
+                            ui.text_disabled(locale.get("banner.paused_hint"));
+                        }
+                        PauseState::Requested => {
+                            ui.text_disabled(locale.get("banner.pause_requested"));
                         }
+                        PauseState::Running => {}
+                    }
+                    if let Some(CountdownPhase::Count(n)) = self.countdown() {
+                        ui.text_colored(
+                            orange,
+                            locale.format("banner.starting_in", &[("seconds", &n)]),
+                        );
+                    } else if self.race_elapsed_ms().is_some_and(|ms| ms < 3000) {
+                        ui.text_colored(green, locale.get("banner.go"));
                     }
                 }
                 "finished" => {
-                    ui.text_colored(green, "RACE FINISHED");
+                    ui.text_colored(green, locale.get("banner.finished"));
                 }
                 _ => {}
             }
@@ -155,192 +651,521 @@ impl RaceTracker {
 
     /// Red warning when the config's seed_id doesn't match the server's seed_id.
     /// This means the player has an outdated seed pack after a re-roll.
+    /// A re-roll seen during this session also lists the install steps; the
+    /// warning clears by itself once the new pack is extracted.
     fn render_seed_mismatch_warning(&self, ui: &hudhook::imgui::Ui) {
-        if self.seed_mismatch {
-            let red = [1.0, 0.2, 0.2, 1.0];
-            ui.text_colored(red, "SEED OUTDATED");
-            ui.text_colored(red, "Re-download your seed pack");
+        if !self.seed_mismatch {
+            return;
+        }
+        let red = self.cached_colors.theme.error;
+        let locale = &self.locale;
+        if self.seed_rerolled {
+            ui.text_colored(red, locale.get("seed.rerolled"));
+            ui.text(locale.get("seed.reroll_download"));
+            ui.text(locale.get("seed.reroll_extract"));
+            ui.text(locale.get("seed.reroll_clears"));
+        } else {
+            ui.text_colored(red, locale.get("seed.outdated"));
+            ui.text_colored(red, locale.get("seed.redownload"));
         }
     }
 
-    /// 3-line player status:
-    /// Line 1: `● RaceName               HH:MM:SS` (name dimmed, IGT in blue)
-    /// Line 2: `  ZoneName                    X/Y` (X yellow→green on finish, /Y white)
-    /// Line 3: `  tier X, previously Y   [☠]N`     (tier yellow, deaths white)
-    fn render_player_status(&self, ui: &hudhook::imgui::Ui, max_width: f32) {
-        let blue = [0.4, 0.6, 1.0, 1.0];
-        let yellow = [1.0, 1.0, 0.0, 1.0];
-        let green = [0.0, 1.0, 0.0, 1.0];
+    /// Wrong save loaded: doesn't match the seed's starting character, was
+    /// switched mid-race, or is past its first playthrough (NG+).
+    fn render_character_warning(&self, ui: &hudhook::imgui::Ui) {
+        let warning_color = self.cached_colors.theme.warning;
+        for warning in [&self.character_warning, &self.ng_warning]
+            .into_iter()
+            .flatten()
+        {
+            ui.text_colored(warning_color, warning);
+        }
+    }
 
-        // --- Line 1: connection dot + race name (left), local IGT in blue (right) ---
-        let dot_color = match self.ws_status() {
-            ConnectionStatus::Connected => green,
-            ConnectionStatus::Connecting | ConnectionStatus::Reconnecting => [1.0, 0.65, 0.0, 1.0],
-            _ => [1.0, 0.0, 0.0, 1.0],
+    /// Idle warning with its countdown, then the AFK notice until the player
+    /// moves or cancels
+    fn render_afk_banner(&self, ui: &hudhook::imgui::Ui) {
+        let key = self.config.keybindings.cancel_afk.name();
+        let text = match self.afk.state() {
+            AfkState::Active => return,
+            AfkState::Warning { .. } => {
+                let remaining = self
+                    .afk
+                    .grace_remaining(Instant::now(), &self.config.afk)
+                    .unwrap_or_default();
+                self.locale.format(
+                    "afk.warning",
+                    &[("seconds", &remaining.as_secs()), ("key", &key)],
+                )
+            }
+            AfkState::Afk => self.locale.format("afk.idle", &[("key", &key)]),
+        };
+        ui.text_colored(self.cached_colors.theme.warning, text);
+    }
+
+    /// Warmup note before the start; "return to start" warning from the
+    /// countdown on, while the player is away from the start
+    fn render_lobby_banner(&self, ui: &hudhook::imgui::Ui) {
+        let settings = &self.config.warmup;
+        if !settings.enabled {
+            return;
+        }
+        match self.lobby.state() {
+            LobbyState::Racing => {}
+            LobbyState::Warmup if self.is_race_setup() => {
+                ui.text_disabled(self.locale.get("banner.warmup"));
+            }
+            _ if settings.require_start && self.lobby.at_start() == Some(false) => {
+                ui.text_colored(
+                    self.cached_colors.theme.warning,
+                    self.locale.get("banner.return_to_start"),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// 3-line player status (layout in `core::overlay_layout`):
+    /// race name and IGT, zone and progress, tier and deaths.
+    /// Broadcast delay the race enforces on the progress events, with the
+    /// events still held back
+    fn render_event_delay(&self, ui: &hudhook::imgui::Ui) {
+        let delay = self.ws_client.event_delay();
+        if delay.is_zero() || !self.is_race_running() || self.am_i_finished() {
+            return;
+        }
+        let seconds = delay.as_secs();
+        let text = match self.ws_client.delayed_events() {
+            0 => self
+                .locale
+                .format("overlay.event_delay", &[("seconds", &seconds)]),
+            pending => self.locale.format(
+                "overlay.event_delay_pending",
+                &[("seconds", &seconds), ("count", &pending)],
+            ),
         };
+        ui.text_disabled(text);
+    }
 
+    /// IGT and race clock of this frame, shared by the overlay and the race timer
+    fn timer_values(&self) -> TimerValues {
         // When player has finished, show server-frozen IGT (accurate finish time).
+        // Until the server records it, show the IGT captured at the local finish.
         // When race ended but player didn't finish, show locally captured game IGT
         // (the mod's participant igt_ms from leaderboard_update is stale).
-        let igt_str = if self.am_i_finished() {
-            if let Some(me) = self.my_participant().filter(|p| p.igt_ms > 0) {
-                format_time_u32(me.igt_ms as u32)
-            } else {
-                "--:--:--".to_string()
-            }
-        } else if let Some(frozen) = self.frozen_igt_ms {
-            format_time_u32(frozen)
+        let igt_ms = if self.am_i_finished() {
+            self.my_participant()
+                .filter(|p| p.igt_ms > 0)
+                .map(|p| p.igt_ms as u32)
+        } else if let Some(frozen) = self.local_finish_igt_ms.or(self.frozen_igt_ms) {
+            Some(frozen)
         } else if !self.is_race_running() {
             // Race finished but no frozen IGT captured (shouldn't happen normally)
-            "--:--:--".to_string()
-        } else if let Some(igt_ms) = self.read_igt() {
-            format_time_u32(igt_ms)
+            None
         } else {
-            "--:--:--".to_string()
+            self.read_igt()
         };
-        let igt_width = ui.calc_text_size(&igt_str)[0];
+        TimerValues {
+            igt_ms,
+            race_ms: self.race_elapsed_ms(),
+            deaths: self.read_deaths(),
+            bosses: self.bosses_defeated,
+            ..self.player_stats()
+        }
+    }
 
-        let dot_str = "\u{25CF} "; // "● "
-        let dot_width = ui.calc_text_size(dot_str)[0];
-        let gap = ui.calc_text_size(" ")[0];
-        let name_max = max_width - igt_width - gap - dot_width;
+    /// Race status, zone and leaderboard order: what the overlay changes
+    /// with, hashed for the auto-hide
+    fn activity_fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.race_state
+            .race
+            .as_ref()
+            .map(|r| &r.status)
+            .hash(&mut hasher);
+        self.current_zone_info()
+            .map(|z| &z.node_id)
+            .hash(&mut hasher);
+        for p in self.participants() {
+            p.id.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
 
-        ui.text_colored(dot_color, dot_str);
-        ui.same_line_with_spacing(0.0, 0.0);
+    /// Race timer text of the current phase
+    pub(crate) fn timer_format(&self) -> &str {
+        self.config
+            .phases
+            .timer_format(&self.config.race_timer.format, self.race_phase())
+    }
 
-        let name_text = if let Some(race) = self.race_info() {
-            race.name.to_string()
-        } else {
-            "Connecting...".to_string()
+    /// Player stats used by the race timer, read only for a timer that shows
+    /// them. The flasks scan the inventory: the tracker polls them slowly.
+    fn player_stats(&self) -> TimerValues {
+        if !self.config.race_timer.enabled {
+            return TimerValues::default();
+        }
+        let format = self.timer_format();
+        TimerValues {
+            level: format
+                .contains("{level}")
+                .then(|| self.read_level())
+                .flatten(),
+            souls: format
+                .contains("{souls}")
+                .then(|| self.read_runes())
+                .flatten(),
+            flasks: self.flasks,
+            ..TimerValues::default()
+        }
+    }
+
+    fn render_player_status(
+        &self,
+        ui: &hudhook::imgui::Ui,
+        max_width: f32,
+        preset: &OverlayPreset,
+        timer: &TimerValues,
+    ) {
+        let theme = &self.cached_colors.theme;
+
+        let dot_color = match self.ws_status() {
+            ConnectionStatus::Connected => theme.finished,
+            ConnectionStatus::Connecting | ConnectionStatus::Reconnecting => theme.ready,
+            _ => theme.error,
         };
-        let truncated = truncate_to_width(ui, &name_text, name_max);
-        ui.text_colored(self.cached_colors.text_disabled, &truncated);
 
-        ui.same_line_with_pos(max_width - igt_width);
-        ui.text_colored(blue, &igt_str);
+        let igt_str = timer
+            .igt_ms
+            .map_or_else(|| "--:--:--".to_string(), format_time_u32);
 
-        // --- Line 2: zone name (left, white), progress X/Y (right, X=yellow/green Y=white) ---
         let me = self.my_participant();
         let total_layers = self.seed_info().map(|s| s.total_layers).unwrap_or(0);
         let zone = self.current_zone_info();
 
+        // Zones scaled well above the player's progression stand out; zones
+        // resolved offline from the cache are dimmed and marked
+        let zone_color = match zone {
+            Some(z) if z.scaling_warning.is_some() => theme.warning,
+            Some(z) if z.cached => self.cached_colors.text_disabled,
+            _ => self.cached_colors.text,
+        };
+        let zone_name = zone.map(|z| {
+            if z.cached {
+                format!("{} {}", z.display_name, self.locale.get("overlay.cached"))
+            } else {
+                z.display_name.clone()
+            }
+        });
+
+        let race_name = match self.race_info() {
+            Some(race) => race.name.as_str(),
+            None => self.locale.get("overlay.connecting"),
+        };
+
         let is_setup = self
             .race_info()
             .is_some_and(|r| r.status.as_str() == "setup");
 
         // In setup phase, show participant status instead of layer progress
-        let (right_str, right_color) = if is_setup {
+        let progress = if is_setup {
             let status = me.map(|p| p.status.as_str()).unwrap_or("registered");
-            let orange = [1.0, 0.65, 0.0, 1.0];
-            let color = match status {
-                "ready" => orange,
-                _ => self.cached_colors.text_disabled,
-            };
-            (status.to_string(), color)
+            let color = theme
+                .status_color(status)
+                .unwrap_or(self.cached_colors.text_disabled);
+            (self.locale.status(status).to_string(), color)
         } else {
             let layer = me.map(|p| p.current_layer).unwrap_or(0);
             let display_layer = (layer + 1).min(total_layers);
-            let color = if self.am_i_finished() { green } else { yellow };
+            let color = if self.am_i_finished() {
+                theme.finished
+            } else {
+                theme.highlight
+            };
             (format!("{}/{}", display_layer, total_layers), color)
         };
-        let right_width = ui.calc_text_size(&right_str)[0];
 
-        let zone_text = if let Some(z) = zone {
-            format!("  {}", z.display_name)
+        // Personal best delta on arrival in this zone
+        let pb_delta = self.pb_delta_ms.map(|d| {
+            let color = if d <= 0 { theme.ahead } else { theme.behind };
+            (crate::core::format_gap(d), color)
+        });
+
+        // "tier 7, previously 4", or "T4→T7" with `compact_tier`
+        let scaling = &self.config.scaling;
+        let (tier_key, previously_key) = if scaling.compact_tier {
+            ("overlay.tier_compact", "overlay.tier_compact_previously")
         } else {
-            String::new()
+            ("overlay.tier", "overlay.tier_previously")
         };
-        let zone_max = max_width - right_width - gap;
-        let zone_truncated = truncate_to_width(ui, &zone_text, zone_max);
-        ui.text(&zone_truncated);
-
-        ui.same_line_with_pos(max_width - right_width);
-        ui.text_colored(right_color, &right_str);
-
-        // --- Line 3: tier info (left, yellow), death icon + count (right, white) ---
-        let deaths = self.read_deaths().unwrap_or(0);
-        let death_str = format!("{}", deaths);
-        let font_height = ui.text_line_height();
-        let icon_size = font_height;
-        let icon_gap = 2.0;
-        let right_total = if self.death_icon.is_some() {
-            icon_size + icon_gap + ui.calc_text_size(&death_str)[0]
+        let tier = zone
+            .and_then(|z| z.tier)
+            .or_else(|| me.and_then(|p| p.current_layer_tier));
+        let tier_text = if !self.permissions.scaling_info() {
+            String::new()
+        } else if let Some(z) = zone {
+            match (z.tier, z.original_tier) {
+                (Some(t), Some(ot)) if ot != t => self
+                    .locale
+                    .format(previously_key, &[("tier", &t), ("original", &ot)]),
+                (Some(t), _) => self.locale.format(tier_key, &[("tier", &t)]),
+                (None, _) => String::new(),
+            }
+        } else if let Some(tier) = tier {
+            self.locale.format(tier_key, &[("tier", &tier)])
         } else {
-            ui.calc_text_size(&death_str)[0]
+            String::new()
+        };
+        let tier_color = match tier {
+            Some(t) if scaling.is_dangerous(t) => theme.error,
+            Some(_) => theme.highlight,
+            None => self.cached_colors.text,
         };
 
-        let tier_text = if let Some(z) = zone {
-            if let Some(t) = z.tier {
-                if let Some(ot) = z.original_tier.filter(|&ot| ot != t) {
-                    format!("  tier {}, previously {}", t, ot)
+        let death_icon = self
+            .icons
+            .as_ref()
+            .and_then(|icons| icons.icon("death"))
+            .map(|(texture_id, uv)| (TextureHandle(texture_id.id()), uv));
+
+        let view = PlayerStatusView {
+            show_details: preset.show_details,
+            dot_color,
+            race_name,
+            name_color: self.cached_colors.text_disabled,
+            igt: &igt_str,
+            igt_color: theme.igt,
+            zone_name: zone_name.as_deref(),
+            zone_color,
+            pb_delta,
+            progress,
+            tier: (tier_text, tier_color),
+            deaths: timer.deaths.unwrap_or(0),
+            deaths_color: self.cached_colors.text,
+            death_icon,
+            icon_scale: self.config.overlay.icon_scale,
+            death_tooltip: Some(self.locale.get("overlay.deaths")).filter(|_| self.interactive),
+        };
+        overlay_layout::render_player_status(&mut ImguiRenderer::new(ui), &view, max_width);
+    }
+
+    /// The player's note on the current zone, word-wrapped. In interactive
+    /// mode a button edits it; returns true when clicked.
+    fn render_zone_note(&self, ui: &hudhook::imgui::Ui, max_width: f32) -> bool {
+        if let Some(note) = self.current_zone_note() {
+            for (i, line) in wrap_text(ui, "  ", note, max_width).into_iter().enumerate() {
+                // First line gets the marker in place of the indent
+                let line = if i == 0 {
+                    format!("\u{25B8} {}", line.trim_start()) // ▸
                 } else {
-                    format!("  tier {}", t)
-                }
-            } else {
-                String::new()
+                    line
+                };
+                ui.text_colored(self.cached_colors.theme.highlight, &line);
             }
-        } else if let Some(tier) = me.and_then(|p| p.current_layer_tier) {
-            format!("  tier {}", tier)
+        }
+        if !self.interactive || self.current_zone_info().is_none() || self.seed_key().is_none() {
+            return false;
+        }
+        let label = if self.current_zone_note().is_some() {
+            self.locale.get("notes.edit")
         } else {
-            String::new()
+            self.locale.get("notes.add")
         };
-        let has_tier = zone.is_some_and(|z| z.tier.is_some())
-            || me.is_some_and(|p| p.current_layer_tier.is_some());
-        let tier_color = if has_tier {
-            yellow
-        } else {
-            self.cached_colors.text
+        ui.small_button(format!("{}###edit_note", label))
+    }
+
+    /// The seed segment in progress and its time so far
+    fn render_segment(&self, ui: &hudhook::imgui::Ui) {
+        let (Some(segment), Some(igt_ms)) = (self.segments.current(), self.read_igt()) else {
+            return;
         };
+        ui.text_disabled(self.locale.format(
+            "overlay.segment",
+            &[
+                ("segment", &segment.name),
+                ("time", &format_time_u32(segment.elapsed_ms(igt_ms))),
+            ],
+        ));
+    }
 
-        let tier_max = max_width - right_total - gap;
-        let tier_truncated = truncate_to_width(ui, &tier_text, tier_max);
-        ui.text_colored(tier_color, &tier_truncated);
+    /// Count of the graces found during the race, and the last ones with
+    /// their zone and IGT when `[graces] list` is on
+    fn render_graces(&self, ui: &hudhook::imgui::Ui) {
+        let found = self.graces.found();
+        if found.is_empty() {
+            return;
+        }
+        ui.text_disabled(
+            self.locale
+                .format("overlay.graces", &[("count", &found.len())]),
+        );
+        if !self.config.graces.list {
+            return;
+        }
+        let skip = found.len().saturating_sub(graces::LIST_LEN);
+        for grace in &found[skip..] {
+            let zone = grace.zone.as_deref().unwrap_or("?");
+            ui.text_disabled(format!("  {}  {}", format_time_u32(grace.igt_ms), zone));
+        }
+    }
 
-        ui.same_line_with_pos(max_width - right_total);
-        if let Some(ref icon) = self.death_icon {
-            Image::new(icon.texture_id(), [icon_size, icon_size]).build(ui);
-            ui.same_line_with_spacing(0.0, icon_gap);
+    /// World-map thumbnail with a dot on the player's position. Overworld only:
+    /// elsewhere a one-line notice replaces it. Without the area's map image
+    /// the dot is drawn over a plain grid.
+    fn render_world_map(&self, ui: &hudhook::imgui::Ui) {
+        let located = self
+            .read_position()
+            .and_then(|pos| self.world_map.locate(pos.map_id, pos.x, pos.z));
+        let Some((calibration, uv)) = located else {
+            ui.text_disabled(self.locale.get("map.off_overworld"));
+            return;
+        };
+
+        let size = self.config.world_map.size * self.ui_scale;
+        let min = ui.cursor_screen_pos();
+        let max = [min[0] + size, min[1] + size];
+        let texture = self
+            .map_textures
+            .as_ref()
+            .and_then(|textures| textures.texture(calibration.area));
+        {
+            let draw_list = ui.get_window_draw_list();
+            match texture {
+                Some(texture_id) => draw_list.add_image(texture_id, min, max).build(),
+                None => {
+                    let grid = self.cached_colors.text_disabled;
+                    draw_list
+                        .add_rect(min, max, self.cached_colors.bg)
+                        .filled(true)
+                        .build();
+                    for i in 1..4 {
+                        let offset = size * i as f32 / 4.0;
+                        draw_list
+                            .add_line([min[0] + offset, min[1]], [min[0] + offset, max[1]], grid)
+                            .build();
+                        draw_list
+                            .add_line([min[0], min[1] + offset], [max[0], min[1] + offset], grid)
+                            .build();
+                    }
+                    draw_list.add_rect(min, max, grid).build();
+                }
+            }
+            let dot = [min[0] + uv[0] * size, min[1] + uv[1] * size];
+            draw_list
+                .add_circle(dot, 4.0 * self.ui_scale, self.cached_colors.theme.highlight)
+                .filled(true)
+                .build();
         }
-        ui.text_colored(self.cached_colors.text, &death_str);
+        ui.dummy([size, size]);
     }
 
-    /// Render exit list from zone_update:
-    /// ```text
-    /// → Ruin-Strewn Precipice          (green, discovered)
-    ///   Stranded Graveyard first door   (gray, word-wrapped)
-    /// → ???                             (white, undiscovered)
-    ///   Soldier of Godrick front        (gray, word-wrapped)
-    /// ```
-    fn render_exits(&self, ui: &hudhook::imgui::Ui, max_width: f32) {
+    /// Render the exit list of the current zone (layout in `core::overlay_layout`),
+    /// pinned exits first, then by usefulness (`core::exit_order`). In
+    /// interactive mode a click pins or unpins an exit.
+    fn render_exits(&mut self, ui: &hudhook::imgui::Ui, max_width: f32) {
         let zone = match self.current_zone_info() {
             Some(z) if !z.exits.is_empty() => z,
             _ => return,
         };
 
-        let green = [0.0, 1.0, 0.0, 1.0];
-        let white = self.cached_colors.text;
-        let indent = "  ";
+        let player = self.read_position().filter(|_| self.permissions.map());
+        let ranked = exit_order::rank(
+            &zone.exits,
+            |exit| self.zone_graph.tier_of(&exit.to_name),
+            self.progression.highest_tier(),
+            &self.config.exit_order,
+        );
+        let rows: Vec<ExitRow> = self
+            .exit_pins
+            .order(&zone.node_id, &zone.exits, ranked)
+            .into_iter()
+            .map(|index| {
+                let exit = &zone.exits[index];
+                // Distance + compass arrow only for undiscovered gates on this map
+                let distance = match (&player, &exit.map_id, exit.position) {
+                    (Some(p), Some(map), Some(gate)) if !exit.discovered => {
+                        exit_hint(p.map_id, p.pos(), map, gate)
+                    }
+                    _ => None,
+                };
+                ExitRow {
+                    exit,
+                    distance,
+                    annotation: self.exit_hint(index),
+                    pinned: self.exit_pins.is_pinned(&zone.node_id, &exit.text),
+                    visited: self.zone_graph.has_visited(exit),
+                }
+            })
+            .collect();
 
-        for exit in &zone.exits {
-            // Line 1: destination — green if discovered, white "???" if not
-            if exit.discovered {
-                let dest = format!("\u{2192} {}", exit.to_name);
-                let truncated = truncate_to_width(ui, &dest, max_width);
-                ui.text_colored(green, &truncated);
-            } else {
-                ui.text_colored(white, "\u{2192} ???");
-            }
+        let theme = &self.cached_colors.theme;
+        let colors = ExitColors {
+            discovered: theme.discovered,
+            undiscovered: theme.undiscovered,
+            pinned: theme.highlight,
+            visited: self.cached_colors.text_disabled,
+        };
+        let pin_hints = PinHints {
+            pin: self.locale.get("overlay.pin_exit"),
+            unpin: self.locale.get("overlay.unpin_exit"),
+        };
+        let clicked = overlay_layout::render_exits(
+            &mut ImguiRenderer::new(ui),
+            &rows,
+            colors,
+            max_width,
+            Some(pin_hints).filter(|_| self.interactive),
+        );
+
+        if let Some(row) = clicked {
+            let node_id = zone.node_id.clone();
+            let exit_text = rows[row].exit.text.clone();
+            let pinned = self.exit_pins.toggle(&node_id, &exit_text);
+            info!(node_id = %node_id, exit = %exit_text, pinned, "[UI] Exit pin toggled");
+        }
+    }
 
-            // Lines 2+: directions to reach the fog gate (gray, word-wrapped)
-            for line in wrap_text(ui, indent, &exit.text, max_width) {
-                ui.text_disabled(&line);
+    /// Render the race objectives (bingo / scavenger races):
+    /// ```text
+    /// Objectives                   1/3
+    /// ■ Kill Margit                     (discovered color, completed)
+    /// □ Reach Leyndell                  (text color, word-wrapped)
+    /// ```
+    fn render_objectives(&self, ui: &hudhook::imgui::Ui, max_width: f32) {
+        let progress = format!(
+            "{}/{}",
+            self.objectives.completed_count(),
+            self.objectives.total()
+        );
+        ui.text_disabled(self.locale.get("overlay.objectives"));
+        ui.same_line_with_pos(max_width - ui.calc_text_size(&progress)[0]);
+        ui.text(&progress);
+
+        for (objective, done) in self.objectives.objectives() {
+            let (bullet, color) = if done {
+                ("\u{25A0}", self.cached_colors.theme.discovered) // ■
+            } else {
+                ("\u{25A1}", self.cached_colors.text) // □
+            };
+            for (i, line) in wrap_text(ui, "  ", &objective.text, max_width)
+                .into_iter()
+                .enumerate()
+            {
+                // First line gets the checkbox in place of the indent
+                let line = if i == 0 {
+                    format!("{} {}", bullet, line.trim_start())
+                } else {
+                    line
+                };
+                ui.text_colored(color, &line);
             }
         }
     }
 
     /// Render a single leaderboard row with optional gap column:
-    /// `{rank}. {name}   [+/-gap]   {progress_or_time}`
-    /// Gap is color-coded: green (ahead), soft red (behind).
+    /// `{rank}. {name} · {zone}   [+/-gap]   {progress_or_time}`
+    /// The zone of the other playing participants follows the race's zone privacy.
+    /// Gap is color-coded with the theme: ahead (green) or behind (soft red).
     /// If `is_self` is true, the name color is brightened to stand out.
     fn render_participant_row(
         &self,
@@ -361,11 +1186,18 @@ impl RaceTracker {
             .as_deref()
             .unwrap_or(&p.twitch_username);
 
+        let theme = &self.cached_colors.theme;
         let base_color = match p.status.as_str() {
-            "finished" => [0.0, 1.0, 0.0, 1.0],
             "playing" => self.cached_colors.text,
-            "ready" => [1.0, 0.65, 0.0, 1.0],
-            _ => self.cached_colors.text_disabled,
+            status => theme
+                .status_color(status)
+                .unwrap_or(self.cached_colors.text_disabled),
+        };
+        // Idle and paused players are greyed out whatever their status
+        let base_color = if p.afk || p.pause.as_deref() == Some("paused") {
+            self.cached_colors.text_disabled
+        } else {
+            base_color
         };
         let color = if is_self {
             brighten(base_color, 0.35)
@@ -373,7 +1205,7 @@ impl RaceTracker {
             base_color
         };
 
-        let right_text = right_text_for(p, total_layers, is_setup);
+        let right_text = right_text_for(p, total_layers, is_setup, &self.locale);
         let gap_text = computed_gap_ms.map(crate::core::format_gap);
 
         // Layout: [name]  [gap right-aligned in gap_col]  [right right-aligned]
@@ -384,17 +1216,58 @@ impl RaceTracker {
             right_x
         };
 
-        // Left (name) — truncate to fit before gap column
-        let left_text = format!("{:2}. {}", rank, name);
+        // Left (name, badges, counters) — truncate to fit before gap column
+        let name_text = format!("{:2}. {}", rank, name);
+        let mut left_text = String::new();
+        if !p.key_items.is_empty() {
+            // ◆ = key items obtained
+            left_text.push_str(&format!(" \u{25C6}{}", p.key_items.len()));
+        }
+        if !p.objectives_completed.is_empty() {
+            // ■ = race objectives completed
+            left_text.push_str(&format!(" \u{25A0}{}", p.objectives_completed.len()));
+        }
+        if !is_self && !is_setup && p.status == "playing" {
+            if let Some(zone) = self.zone_label(p) {
+                left_text.push_str(&format!(" \u{00B7} {}", zone));
+            }
+        }
+        // Positions are window-relative, as for same_line_with_pos
         let left_max = gap_x - spacing;
-        let truncated = truncate_to_width(ui, &left_text, left_max);
-        ui.text_colored(color, &truncated);
+        let start_x = ui.cursor_pos()[0];
+        let name_truncated = truncate_to_width(ui, &name_text, left_max - start_x);
+        let mut left_x = start_x + ui.calc_text_size(&name_truncated)[0];
+        ui.text_colored(color, &name_truncated);
+        let mut hovered = ui.is_item_hovered();
+        // Badge tags in their own colors, as long as they fit whole
+        let space = ui.calc_text_size(" ")[0];
+        for badge in badges::badges(&p.badges) {
+            let tag_key = format!("{}_tag", badge.locale_key());
+            let tag = self.locale.get(&tag_key);
+            let tag_width = ui.calc_text_size(tag)[0];
+            if left_x + space + tag_width > left_max {
+                break;
+            }
+            ui.same_line_with_pos(left_x + space);
+            ui.text_colored(badge.color(theme), tag);
+            hovered |= ui.is_item_hovered();
+            left_x += space + tag_width;
+        }
+        if !left_text.is_empty() && left_x < left_max {
+            let truncated = truncate_to_width(ui, &left_text, left_max - left_x);
+            ui.same_line_with_pos(left_x);
+            ui.text_colored(color, &truncated);
+            hovered |= ui.is_item_hovered();
+        }
+        if self.interactive && hovered {
+            self.render_participant_tooltip(ui, p);
+        }
 
         // Gap (right-aligned within gap column, color-coded)
         if let Some(ref gt) = gap_text {
             let gap_color = match computed_gap_ms {
-                Some(ms) if ms < 0 => [0.3, 0.9, 0.3, 1.0], // green: ahead of pace
-                Some(ms) if ms > 0 => [0.9, 0.35, 0.35, 1.0], // soft red: behind
+                Some(ms) if ms < 0 => theme.ahead,
+                Some(ms) if ms > 0 => theme.behind,
                 _ => color,
             };
             let gt_width = ui.calc_text_size(gt)[0];
@@ -408,22 +1281,302 @@ impl RaceTracker {
         ui.text_colored(color, &right_text);
     }
 
+    /// Another participant's zone as the race's zone privacy allows
+    fn zone_label(&self, p: &crate::core::protocol::ParticipantInfo) -> Option<String> {
+        let label = self
+            .zone_privacy
+            .label(p.current_zone_name.as_deref(), p.current_layer_tier)?;
+        Some(match label {
+            ZoneLabel::Name(name) => name.to_string(),
+            ZoneLabel::Tier(tier) => self.locale.format("overlay.tier", &[("tier", &tier)]),
+            ZoneLabel::Hidden => self.locale.get("overlay.zone_hidden").to_string(),
+        })
+    }
+
+    /// Time in the current zone and the names behind a row's ◆ and ■
+    /// counters (interactive mode)
+    fn render_participant_tooltip(
+        &self,
+        ui: &hudhook::imgui::Ui,
+        p: &crate::core::protocol::ParticipantInfo,
+    ) {
+        let mut lines = Vec::new();
+        if let Some(in_zone) = self.time_in_zone(p) {
+            let time = format_time_u32(in_zone);
+            lines.push(self.locale.format("overlay.in_zone", &[("time", &time)]));
+        }
+        let seed_items = self
+            .seed_info()
+            .map(|s| s.key_items.as_slice())
+            .unwrap_or(&[]);
+        if !p.key_items.is_empty() {
+            lines.push(self.locale.get("overlay.key_items").to_string());
+            for id in &p.key_items {
+                match seed_items.iter().find(|item| item.id == *id) {
+                    Some(item) => lines.push(format!("\u{25C6} {}", item.name)),
+                    None => lines.push(format!("\u{25C6} #{}", id)),
+                }
+            }
+        }
+        if !p.objectives_completed.is_empty() {
+            lines.push(self.locale.get("overlay.objectives_done").to_string());
+            for (objective, _) in self
+                .objectives
+                .objectives()
+                .filter(|(o, _)| p.objectives_completed.contains(&o.id))
+            {
+                lines.push(format!("\u{25A0} {}", objective.text));
+            }
+        }
+        if !lines.is_empty() {
+            ui.tooltip_text(lines.join("\n"));
+        }
+    }
+
+    /// How long a playing participant has been in their current zone: the
+    /// local IGT for the player, the IGT interpolated since the last
+    /// leaderboard for the others (as for the gaps)
+    fn time_in_zone(&self, p: &crate::core::protocol::ParticipantInfo) -> Option<u32> {
+        if p.status != "playing" {
+            return None;
+        }
+        let entry = p.zone_entry_igt?;
+        let igt = if self.my_participant_id() == Some(&p.id) {
+            self.read_igt()? as i32
+        } else {
+            let elapsed_ms = self
+                .race_state
+                .leaderboard_received_at
+                .map(|t| t.elapsed().as_millis().min(10_000) as i32)
+                .unwrap_or(0);
+            p.igt_ms.saturating_add(elapsed_ms)
+        };
+        Some(igt.saturating_sub(entry).max(0) as u32)
+    }
+
     /// Leaderboard with color-coded status, gap timing, and right-aligned values.
     /// Gaps are computed client-side using leader_splits for real-time updates.
     /// Always shows the local player: if ranked beyond top 10, anchors them
     /// at the bottom with a `···` separator and their real rank.
-    fn render_leaderboard(&self, ui: &hudhook::imgui::Ui, max_width: f32) {
-        let participants = self.participants();
-        if participants.is_empty() {
-            ui.text_disabled("No participants");
+    fn render_leaderboard(&mut self, ui: &hudhook::imgui::Ui, max_width: f32) {
+        if self.participants().is_empty() {
+            ui.text_disabled(self.locale.get("overlay.no_participants"));
             return;
         }
 
+        // Over budget: skip the per-frame re-layout and refresh it a few times per second
+        let layout = match self.leaderboard_layout.take() {
+            Some(layout)
+                if self.frame_profiler.is_degraded()
+                    && layout.gaps.len() == self.participants().len()
+                    && layout.computed_at.elapsed() < DEGRADED_REFRESH =>
+            {
+                layout
+            }
+            _ => self.compute_leaderboard_layout(ui),
+        };
+        let participants = self.participants();
+        let gaps = &layout.gaps;
+        let max_gap_width = layout.max_gap_width;
+        let max_right_width = layout.max_right_width;
+
+        // Header: race clock from the server's start time, corrected for clock offset
+        if let Some(elapsed_ms) = self.race_elapsed_ms().filter(|_| self.is_race_running()) {
+            let clock = format_time_u32(elapsed_ms.clamp(0, u32::MAX as i64) as u32);
+            let clock_width = ui.calc_text_size(&clock)[0];
+            ui.text_disabled(self.locale.get("overlay.race"));
+            ui.same_line_with_pos(max_width - clock_width);
+            ui.text_disabled(&clock);
+        }
+
         let total_layers = self.seed_info().map(|s| s.total_layers).unwrap_or(0);
         let is_setup = self
             .race_info()
             .is_some_and(|r| r.status.as_str() == "setup");
         let spacing = ui.calc_text_size(" ")[0];
+        let my_id = self.my_participant_id();
+
+        // Find local player's index in the (pre-sorted) participants list
+        let my_index = my_id.and_then(|my_id| participants.iter().position(|p| &p.id == my_id));
+
+        // Determine how many top rows to show and whether to anchor self
+        let need_anchor = participants.len() > 10 && my_index.map_or(false, |idx| idx >= 10);
+        let top_count = if need_anchor {
+            9
+        } else {
+            10.min(participants.len())
+        };
+
+        // Render top rows
+        for (i, p) in participants.iter().take(top_count).enumerate() {
+            let is_self = my_index == Some(i);
+            self.render_participant_row(
+                ui,
+                p,
+                i + 1,
+                total_layers,
+                max_width,
+                spacing,
+                is_self,
+                max_gap_width,
+                max_right_width,
+                is_setup,
+                gaps[i],
+            );
+        }
+
+        // Anchor: separator + self row
+        if need_anchor {
+            if let Some(idx) = my_index {
+                ui.text_disabled("  \u{00B7}\u{00B7}\u{00B7}");
+                let p = &participants[idx];
+                self.render_participant_row(
+                    ui,
+                    p,
+                    idx + 1,
+                    total_layers,
+                    max_width,
+                    spacing,
+                    true,
+                    max_gap_width,
+                    max_right_width,
+                    is_setup,
+                    gaps[idx],
+                );
+            }
+        }
+
+        // "+ N more" footer
+        let displayed = if need_anchor {
+            top_count + if my_index.is_some() { 1 } else { 0 }
+        } else {
+            top_count
+        };
+        if participants.len() > displayed {
+            let more = self.locale.format(
+                "overlay.more",
+                &[("count", &(participants.len() - displayed))],
+            );
+            ui.text_disabled(format!("  {}", more));
+        }
+
+        if !self.race_state.checkpoints.is_empty() {
+            self.render_checkpoints(ui, max_width);
+        }
+
+        self.leaderboard_layout = Some(layout);
+    }
+
+    /// The other racers' last milestones, oldest first, with their IGT
+    fn render_activity_feed(&self, ui: &hudhook::imgui::Ui) {
+        let settings = &self.config.activity_feed;
+        let mut entries = self
+            .activity_feed
+            .visible(Instant::now(), settings)
+            .peekable();
+        if entries.peek().is_none() {
+            return;
+        }
+        ui.separator();
+        for activity in entries {
+            ui.text_disabled(format!(
+                "{}  {}",
+                format_time_u32(activity.igt_ms),
+                self.activity_text(activity)
+            ));
+        }
+    }
+
+    fn activity_text(&self, activity: &Activity) -> String {
+        let player = &activity.player_name;
+        let (key, zone) = match (activity.kind, activity.zone_name.as_deref()) {
+            (ActivityKind::Zone, Some(zone)) => ("activity.zone", zone),
+            (ActivityKind::Zone, None) => ("activity.zone_hidden", ""),
+            (ActivityKind::Boss, Some(zone)) => ("activity.boss", zone),
+            (ActivityKind::Boss, None) => ("activity.boss_hidden", ""),
+            // Unknown kinds are filtered out of the feed
+            (ActivityKind::Finish | ActivityKind::Unknown, _) => ("activity.finish", ""),
+        };
+        self.locale
+            .format(key, &[("player", player), ("zone", &zone)])
+    }
+
+    /// Standings at each checkpoint: the local player's rank and gap to the
+    /// fastest where they crossed it, the fastest player otherwise. Their
+    /// best and worst checkpoints follow once they differ.
+    fn render_checkpoints(&self, ui: &hudhook::imgui::Ui, max_width: f32) {
+        let standings = checkpoints::standings(
+            self.race_state.checkpoints.checkpoints(),
+            self.participants(),
+        );
+        let my_id = self.my_participant_id().map(String::as_str);
+        let theme = &self.cached_colors.theme;
+
+        ui.separator();
+        ui.text_disabled(self.locale.get("overlay.checkpoints"));
+        for standing in &standings {
+            let mine = my_id.and_then(|id| standing.placement(id));
+            let (right, color) = match (mine, standing.times.first()) {
+                (Some(placement), _) if placement.rank == 1 => (
+                    format!("#1 {}", format_time(standing.times[0].1)),
+                    theme.ahead,
+                ),
+                (Some(placement), _) => (
+                    format!(
+                        "#{} {}",
+                        placement.rank,
+                        crate::core::format_gap(placement.gap_ms)
+                    ),
+                    theme.behind,
+                ),
+                (None, Some((leader, igt_ms))) => {
+                    let name = leader
+                        .twitch_display_name
+                        .as_deref()
+                        .unwrap_or(&leader.twitch_username);
+                    (
+                        format!("{} {}", name, format_time(*igt_ms)),
+                        self.cached_colors.text_disabled,
+                    )
+                }
+                (None, None) => ("--".to_string(), self.cached_colors.text_disabled),
+            };
+            ui.text(format!("  {}", standing.checkpoint.name));
+            ui.same_line_with_pos(max_width - ui.calc_text_size(&right)[0]);
+            ui.text_colored(color, &right);
+        }
+
+        let Some((best, worst)) = my_id.and_then(|id| checkpoints::best_and_worst(&standings, id))
+        else {
+            return;
+        };
+        if best == worst {
+            return;
+        }
+        let name = |placement: checkpoints::Placement| {
+            standings
+                .iter()
+                .find(|s| s.checkpoint.id == placement.checkpoint_id)
+                .map_or("", |s| s.checkpoint.name.as_str())
+        };
+        ui.text_disabled(self.locale.format(
+            "overlay.checkpoint_best",
+            &[("checkpoint", &name(best)), ("rank", &best.rank)],
+        ));
+        ui.text_disabled(self.locale.format(
+            "overlay.checkpoint_worst",
+            &[("checkpoint", &name(worst)), ("rank", &worst.rank)],
+        ));
+    }
+
+    /// Gaps and column widths for every participant.
+    /// Gaps are computed client-side using leader_splits for real-time updates.
+    fn compute_leaderboard_layout(&self, ui: &hudhook::imgui::Ui) -> LeaderboardLayout {
+        let participants = self.participants();
+        let total_layers = self.seed_info().map(|s| s.total_layers).unwrap_or(0);
+        let is_setup = self
+            .race_info()
+            .is_some_and(|r| r.status.as_str() == "setup");
 
         // Get leader_splits and leader IGT for gap computation
         let empty_splits = std::collections::HashMap::new();
@@ -499,7 +1652,7 @@ impl RaceTracker {
         let mut max_gap_width: f32 = 0.0;
         let mut max_right_width: f32 = 0.0;
         for (i, p) in participants.iter().enumerate() {
-            let rw = ui.calc_text_size(&right_text_for(p, total_layers, is_setup))[0];
+            let rw = ui.calc_text_size(&right_text_for(p, total_layers, is_setup, &self.locale))[0];
             if rw > max_right_width {
                 max_right_width = rw;
             }
@@ -511,82 +1664,44 @@ impl RaceTracker {
             }
         }
 
-        // Find local player's index in the (pre-sorted) participants list
-        let my_index = my_id.and_then(|my_id| participants.iter().position(|p| &p.id == my_id));
-
-        // Determine how many top rows to show and whether to anchor self
-        let need_anchor = participants.len() > 10 && my_index.map_or(false, |idx| idx >= 10);
-        let top_count = if need_anchor {
-            9
-        } else {
-            10.min(participants.len())
-        };
-
-        // Render top rows
-        for (i, p) in participants.iter().take(top_count).enumerate() {
-            let is_self = my_index == Some(i);
-            self.render_participant_row(
-                ui,
-                p,
-                i + 1,
-                total_layers,
-                max_width,
-                spacing,
-                is_self,
-                max_gap_width,
-                max_right_width,
-                is_setup,
-                gaps[i],
-            );
-        }
-
-        // Anchor: separator + self row
-        if need_anchor {
-            if let Some(idx) = my_index {
-                ui.text_disabled("  \u{00B7}\u{00B7}\u{00B7}");
-                let p = &participants[idx];
-                self.render_participant_row(
-                    ui,
-                    p,
-                    idx + 1,
-                    total_layers,
-                    max_width,
-                    spacing,
-                    true,
-                    max_gap_width,
-                    max_right_width,
-                    is_setup,
-                    gaps[idx],
-                );
-            }
-        }
-
-        // "+ N more" footer
-        let displayed = if need_anchor {
-            top_count + if my_index.is_some() { 1 } else { 0 }
-        } else {
-            top_count
-        };
-        if participants.len() > displayed {
-            ui.text_disabled(format!("  + {} more", participants.len() - displayed));
+        LeaderboardLayout {
+            gaps,
+            max_gap_width,
+            max_right_width,
+            computed_at: Instant::now(),
         }
     }
 
-    /// Temporary status message (yellow text with separator, disappears after 3s).
-    fn render_status_message(&self, ui: &hudhook::imgui::Ui) {
-        if let Some(status) = self.get_status() {
-            ui.separator();
-            ui.text_colored([1.0, 1.0, 0.0, 1.0], status);
+    /// Toasts below the overlay content, oldest first, fading in and out.
+    fn render_toasts(&self, ui: &hudhook::imgui::Ui) {
+        let now = Instant::now();
+        let mut toasts = self.toasts.active(now).peekable();
+        if toasts.peek().is_none() {
+            return;
+        }
+        ui.separator();
+        let theme = &self.cached_colors.theme;
+        for toast in toasts {
+            let [r, g, b, _] = match toast.kind {
+                ToastKind::Zone => self.cached_colors.text,
+                ToastKind::Discovery => theme.discovered,
+                ToastKind::Warning => theme.warning,
+                ToastKind::Connection | ToastKind::Info => theme.notice,
+                ToastKind::Item => theme.item,
+                ToastKind::Position => theme.highlight,
+            };
+            ui.text_colored([r, g, b, toast.alpha(now)], &toast.text);
         }
     }
 
     fn render_debug(&self, ui: &hudhook::imgui::Ui) {
-        ui.text_colored([1.0, 0.85, 0.3, 1.0], "Debug");
+        let locale = &self.locale;
+        ui.text_colored([1.0, 0.85, 0.3, 1.0], locale.get("debug.title"));
 
         let debug = self.debug_info();
 
         // Zones: show each participant's current_zone
-        ui.text_disabled("Zones:");
+        ui.text_disabled(locale.get("debug.zones"));
         let participants = self.participants();
         if participants.is_empty() {
             ui.text("  \u{2013}");
@@ -601,8 +1716,69 @@ impl RaceTracker {
             }
         }
 
+        // Play vs load time (IGT pause detection)
+        let analyzer = &self.igt_analyzer;
+        ui.text_disabled(locale.get("debug.load_time"));
+        ui.same_line();
+        ui.text(locale.format(
+            if analyzer.is_paused() {
+                "debug.load_time_paused"
+            } else {
+                "debug.load_time_value"
+            },
+            &[
+                (
+                    "load",
+                    &format_time_u32(analyzer.load_time().as_millis() as u32),
+                ),
+                ("pauses", &analyzer.pause_count()),
+                (
+                    "play",
+                    &format_time_u32(analyzer.play_time().as_millis() as u32),
+                ),
+            ],
+        ));
+
+        // Overlay cost per frame (smoothed)
+        let profiler = &self.frame_profiler;
+        ui.text_disabled(locale.get("debug.frame"));
+        ui.same_line();
+        let frame_text = locale.format(
+            if profiler.is_degraded() {
+                "debug.frame_degraded"
+            } else {
+                "debug.frame_value"
+            },
+            &[
+                ("update", &format!("{:.2}", profiler.update_ms())),
+                ("ui", &format!("{:.2}", profiler.ui_ms())),
+            ],
+        );
+        if profiler.is_degraded() {
+            ui.text_colored([1.0, 0.5, 0.0, 1.0], frame_text);
+        } else {
+            ui.text(frame_text);
+        }
+
+        // Detection incidents (expired warps, dropped discoveries)
+        let health = &self.detection_health;
+        ui.text_disabled(locale.get("debug.detection"));
+        ui.same_line();
+        let health_text = locale.format(
+            "debug.detection_value",
+            &[
+                ("expired", &health.expired()),
+                ("filtered", &health.filtered()),
+            ],
+        );
+        if health.is_healthy() {
+            ui.text(health_text);
+        } else {
+            ui.text_colored([1.0, 0.5, 0.0, 1.0], health_text);
+        }
+
         // Flag reader diagnostics
-        ui.text_disabled("Flag reader:");
+        ui.text_disabled(locale.get("debug.flag_reader"));
         ui.same_line();
         let status_color = if matches!(debug.flag_reader_status, FlagReaderStatus::Ok { .. }) {
             [0.0, 1.0, 0.0, 1.0] // green
@@ -617,7 +1793,7 @@ impl RaceTracker {
             FlagReadResult::NotSet => (self.cached_colors.text, "false"),
             FlagReadResult::Unreadable => ([1.0, 0.3, 0.3, 1.0], "None"),
         };
-        ui.text("  vanilla 6:");
+        ui.text(format!("  {}", locale.get("debug.vanilla_flag")));
         ui.same_line();
         ui.text_colored(sanity_color, sanity_label);
 
@@ -634,15 +1810,197 @@ impl RaceTracker {
             }
         }
 
-        // Last sent message
-        ui.text_disabled("Sent:");
-        ui.same_line();
-        ui.text(debug.last_sent.unwrap_or("\u{2013}"));
+        // Background threads (supervisor state, restarts)
+        ui.text_disabled(locale.get("debug.threads"));
+        for health in &debug.threads {
+            let color = match health.state() {
+                WorkerState::Panicked(_) => [1.0, 0.3, 0.3, 1.0],
+                WorkerState::Running if health.is_stalled() => [1.0, 0.5, 0.0, 1.0],
+                _ => self.cached_colors.text,
+            };
+            ui.text(format!("  {}:", health.name()));
+            ui.same_line();
+            ui.text_colored(color, health.status_label());
+        }
 
-        // Last received message
-        ui.text_disabled("Recv:");
-        ui.same_line();
-        ui.text(debug.last_received.unwrap_or("\u{2013}"));
+        // Memory pointer chains with failed reads (backoff in progress)
+        ui.text_disabled(locale.get("debug.memory_reads"));
+        let now = Instant::now();
+        let mut failing = false;
+        for (chain, stats) in debug.memory_reads.iter().filter(|(_, s)| s.failures > 0) {
+            failing = true;
+            let value = locale.format(
+                "debug.memory_reads_value",
+                &[("failures", &stats.failures), ("reads", &stats.reads)],
+            );
+            ui.text(format!("  {}:", chain));
+            ui.same_line();
+            match stats.backoff_left(now) {
+                Some(left) => ui.text_colored(
+                    [1.0, 0.5, 0.0, 1.0],
+                    locale.format(
+                        "debug.memory_reads_backoff",
+                        &[
+                            ("value", &value),
+                            ("seconds", &format!("{:.1}", left.as_secs_f32())),
+                        ],
+                    ),
+                ),
+                None => ui.text(value),
+            }
+        }
+        if !failing {
+            ui.text(format!("  {}", locale.get("debug.memory_reads_ok")));
+        }
+
+        self.render_memory_inspector(ui, &debug);
+    }
+
+    /// WebSocket frames of the message trace, oldest first, in their own
+    /// window next to the debug section (top-right, moved off the overlay
+    /// unless dragged elsewhere). Scrolling and the export button need the
+    /// mouse (interactive mode).
+    fn render_message_trace(&mut self, ui: &hudhook::imgui::Ui, display_size: [f32; 2]) {
+        let size = [560.0 * self.ui_scale, 320.0 * self.ui_scale];
+        let default = Rect {
+            pos: [display_size[0] - 20.0 - size[0], 20.0],
+            size,
+        };
+        let saved = self.config.layout.get(display_size, WindowId::MessageTrace);
+        let placement = self
+            .window_layout
+            .placement(WindowId::MessageTrace, saved, default);
+
+        let locale = &self.locale;
+        let sent_color = self.cached_colors.theme.igt;
+        let received_color = self.cached_colors.text;
+        let mut export = false;
+        let mut export_flags = false;
+        let mut window = ui
+            .window(format!(
+                "{}###message_trace",
+                locale.get("debug.trace_title")
+            ))
+            .size(size, Condition::FirstUseEver)
+            .movable(self.interactive);
+        if let Some(pos) = placement {
+            window = window.position(pos, Condition::Always);
+        }
+        let mut trace_rect = None;
+        window.build(|| {
+            trace_rect = Some(Rect {
+                pos: ui.window_pos(),
+                size: ui.window_size(),
+            });
+            let Ok(trace) = self.ws_client.trace().lock() else {
+                return;
+            };
+            ui.text_disabled(locale.format("debug.trace_count", &[("count", &trace.len())]));
+            ui.same_line();
+            export = ui.small_button(locale.get("debug.trace_export"));
+            if self.flag_trace.is_some() {
+                ui.same_line();
+                export_flags = ui.small_button(locale.get("debug.flag_trace_export"));
+            }
+            ui.separator();
+            ui.child_window("##trace_lines")
+                .horizontal_scrollbar(true)
+                .build(|| {
+                    for msg in trace.messages() {
+                        let color = match msg.direction {
+                            Direction::Sent => sent_color,
+                            Direction::Received => received_color,
+                        };
+                        ui.text_disabled(msg.time_label());
+                        ui.same_line();
+                        ui.text_colored(
+                            color,
+                            format!("{} {}", msg.direction.arrow(), msg.payload),
+                        );
+                    }
+                    // Follow new messages unless scrolled up
+                    if ui.scroll_y() >= ui.scroll_max_y() {
+                        ui.set_scroll_here_y_with_ratio(1.0);
+                    }
+                });
+        });
+        if let Some(rect) = trace_rect {
+            self.window_layout
+                .record(WindowId::MessageTrace, rect, self.interactive);
+        }
+        if export {
+            self.export_message_trace();
+        }
+        if export_flags {
+            self.export_flag_trace();
+        }
+    }
+
+    /// Raw memory values read this frame, to check offsets on new game patches
+    fn render_memory_inspector(&self, ui: &hudhook::imgui::Ui, debug: &DebugInfo<'_>) {
+        let dash = "\u{2013}";
+        let locale = &self.locale;
+        ui.text_disabled(locale.get("debug.memory"));
+
+        let anim = debug
+            .animation
+            .map(|id| format!("{} ({})", id, animation_label(id)))
+            .unwrap_or_else(|| dash.to_string());
+        ui.text(format!(
+            "  {}",
+            locale.format("debug.animation", &[("value", &anim)])
+        ));
+
+        match &debug.position {
+            Some(p) => {
+                let map = format!("0x{:08X} ({})", p.map_id, p.map_id_str);
+                ui.text(format!(
+                    "  {}",
+                    locale.format("debug.map", &[("value", &map)])
+                ));
+                let region = p
+                    .play_region_id
+                    .map(|r| r.to_string())
+                    .unwrap_or_else(|| dash.to_string());
+                ui.text(format!(
+                    "  {}",
+                    locale.format("debug.play_region", &[("value", &region)])
+                ));
+            }
+            None => ui.text(format!(
+                "  {}",
+                locale.format("debug.map_loading", &[("value", &dash)])
+            )),
+        }
+
+        let warp_requested = debug.captured_grace != 0;
+        ui.text(format!(
+            "  {}",
+            locale.format("debug.warp_requested", &[("value", &warp_requested)])
+        ));
+        let grace = match debug.captured_grace {
+            0 => dash.to_string(),
+            id => id.to_string(),
+        };
+        ui.text(format!(
+            "  {}",
+            locale.format("debug.target_grace", &[("value", &grace)])
+        ));
+
+        ui.text_disabled(locale.get("debug.flag_reads"));
+        if debug.flag_reads.is_empty() {
+            ui.text(format!("  {}", dash));
+        }
+        for read in debug.flag_reads.iter().rev() {
+            let (color, label) = match read.result {
+                Some(true) => ([0.0, 1.0, 0.0, 1.0], "true"),
+                Some(false) => (self.cached_colors.text, "false"),
+                None => ([1.0, 0.3, 0.3, 1.0], "None"),
+            };
+            ui.text(format!("  {}:", read.flag_id));
+            ui.same_line();
+            ui.text_colored(color, label);
+        }
     }
 }
 
@@ -661,12 +2019,11 @@ fn right_text_for(
     p: &crate::core::protocol::ParticipantInfo,
     total_layers: i32,
     is_setup: bool,
+    locale: &Locale,
 ) -> String {
     match p.status.as_str() {
         "finished" => format_time(p.igt_ms),
-        "ready" if is_setup => "ready".to_string(),
-        "registered" if is_setup => "registered".to_string(),
-        _ if is_setup => p.status.clone(),
+        status if is_setup => locale.status(status).to_string(),
         _ => {
             let display = (p.current_layer + 1).min(total_layers);
             format!("{}/{}", display, total_layers)
@@ -689,71 +2046,16 @@ fn format_time(ms: i32) -> String {
     }
 }
 
-fn format_time_u32(ms: u32) -> String {
-    let secs = ms / 1000;
-    let mins = secs / 60;
-    let hours = mins / 60;
-    format!("{:02}:{:02}:{:02}", hours, mins % 60, secs % 60)
+pub(crate) fn format_time_u32(ms: u32) -> String {
+    race_timer::format_clock(ms)
 }
 
 /// Word-wrap `text` into lines that fit within `max_width`, prepending `indent` to each line.
 fn wrap_text(ui: &hudhook::imgui::Ui, indent: &str, text: &str, max_width: f32) -> Vec<String> {
-    let full = format!("{}{}", indent, text);
-    if ui.calc_text_size(&full)[0] <= max_width {
-        return vec![full];
-    }
-
-    let mut lines = Vec::new();
-    let mut current_line = indent.to_string();
-    for word in text.split_whitespace() {
-        let candidate = if current_line.len() == indent.len() {
-            format!("{}{}", current_line, word)
-        } else {
-            format!("{} {}", current_line, word)
-        };
-
-        if ui.calc_text_size(&candidate)[0] <= max_width {
-            current_line = candidate;
-        } else if current_line.len() == indent.len() {
-            // Single word exceeds max_width — truncate it
-            let truncated = truncate_to_width(ui, &candidate, max_width);
-            lines.push(truncated.into_owned());
-        } else {
-            lines.push(current_line);
-            current_line = format!("{}{}", indent, word);
-        }
-    }
-    if current_line.len() > indent.len() {
-        lines.push(current_line);
-    }
-
-    lines
+    overlay_renderer::wrap_text(&ImguiRenderer::new(ui), indent, text, max_width)
 }
 
 /// Truncate text to fit within `max_width` pixels, adding "\u{2026}" if needed.
-///
-/// Returns `Cow::Borrowed` when the text fits (zero allocations in the common case).
-/// When truncation is needed, does a linear forward scan and one allocation for the result.
 fn truncate_to_width<'a>(ui: &hudhook::imgui::Ui, text: &'a str, max_width: f32) -> Cow<'a, str> {
-    if ui.calc_text_size(text)[0] <= max_width {
-        return Cow::Borrowed(text);
-    }
-
-    let ellipsis = "\u{2026}"; // …
-    let ellipsis_width = ui.calc_text_size(ellipsis)[0];
-    let target_width = max_width - ellipsis_width;
-    if target_width <= 0.0 {
-        return Cow::Borrowed(ellipsis);
-    }
-
-    // Linear forward scan: find the longest byte prefix that fits
-    let mut last_fit = 0;
-    for (byte_pos, _) in text.char_indices().skip(1) {
-        if ui.calc_text_size(&text[..byte_pos])[0] > target_width {
-            break;
-        }
-        last_fit = byte_pos;
-    }
-
-    Cow::Owned(format!("{}{}", &text[..last_fit], ellipsis))
+    overlay_renderer::truncate_to_width(&ImguiRenderer::new(ui), text, max_width)
 }
@@ -4,24 +4,42 @@
 
 use crossbeam_channel::{bounded, Receiver, Sender, TryRecvError};
 use std::collections::HashMap;
-use std::net::TcpStream;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 use tracing::{error, info, warn};
-use tungstenite::stream::MaybeTlsStream;
-use tungstenite::{connect, Message, WebSocket};
+use tungstenite::Message;
 
 use super::config::ServerSettings;
+use super::precise_clock::unix_time_ms;
+use super::transport::{open_transport, ServerTransport};
+use crate::core::activity_feed::ActivityKind;
+use crate::core::drift::DriftAnomaly;
+use crate::core::event_delay::DelayQueue;
+use crate::core::exit_signal::ExitSignal;
+use crate::core::kindling::KindlingUse;
+use crate::core::message_trace::{Direction, MessageTrace};
+use crate::core::permissions::Permissions;
 use crate::core::protocol::{
-    ClientMessage, ExitInfo, ParticipantInfo, RaceInfo, SeedInfo, ServerMessage,
+    CharacterFingerprint, Checkpoint, ClientMessage, Decoded, Encoding, ExitHint, ExitInfo, Frame,
+    Objective, ParticipantInfo, RaceInfo, SeedInfo, ServerDecoder, ServerMessage, PROTOCOL_VERSION,
 };
+use crate::core::save_reload::SaveReload;
+use crate::core::supervisor::{run_supervised, RestartBackoff, WorkerHealth, WorkerState};
+use crate::core::transport::{Transport, TransportSelector};
+use crate::core::zone_privacy::ZonePrivacy;
 
 // =============================================================================
 // TYPES
 // =============================================================================
 
+/// Clock sync probes sent in quick succession after connecting
+const CLOCK_SYNC_BURST: u32 = 5;
+const CLOCK_SYNC_BURST_INTERVAL: Duration = Duration::from_secs(2);
+/// Refresh interval once the burst is done (tracks clock drift)
+const CLOCK_SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Connection status
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConnectionStatus {
@@ -43,6 +61,8 @@ pub enum OutgoingMessage {
     EventFlag {
         flag_id: u32,
         igt_ms: u32,
+        seq: Option<u64>,
+        idempotency_key: Option<String>,
     },
     ZoneQuery {
         grace_entity_id: Option<u32>,
@@ -50,6 +70,42 @@ pub enum OutgoingMessage {
         position: Option<[f32; 3]>,
         play_region_id: Option<u32>,
     },
+    ItemObtained {
+        item_id: u32,
+        igt_ms: u32,
+    },
+    ObjectiveComplete {
+        objective_id: u32,
+        igt_ms: u32,
+    },
+    CheckpointCrossed {
+        checkpoint_id: u32,
+        igt_ms: u32,
+    },
+    Character(CharacterFingerprint),
+    NgCycle {
+        clear_count: u32,
+        igt_ms: u32,
+    },
+    ExitHintsRequest(String),
+    Afk {
+        afk: bool,
+        igt_ms: u32,
+    },
+    SaveReloaded(SaveReload),
+    KindlingUsed(KindlingUse),
+    TimingDrift(DriftAnomaly),
+    GraceDiscovered {
+        flag_id: u32,
+        igt_ms: u32,
+    },
+    LeaderboardResync,
+    PauseRequest {
+        igt_ms: u32,
+    },
+    /// Game closing: send the last status (igt_ms, death_count) when racing,
+    /// then close the socket for good
+    Quit(Option<(u32, u32)>),
     Shutdown,
 }
 
@@ -62,12 +118,28 @@ pub enum IncomingMessage {
         race: RaceInfo,
         seed: SeedInfo,
         participants: Vec<ParticipantInfo>,
+        protocol_version: u32,
+        objectives: Vec<Objective>,
+        checkpoints: Vec<Checkpoint>,
+        permissions: Permissions,
+        event_delay_secs: u32,
+        zone_privacy: ZonePrivacy,
     },
     AuthError(String),
-    RaceStart,
+    RaceStart {
+        started_at: Option<String>,
+    },
     LeaderboardUpdate {
         participants: Vec<ParticipantInfo>,
         leader_splits: Option<HashMap<String, i32>>,
+        generation: Option<u64>,
+    },
+    /// Changed participants and new standings since the previous generation
+    LeaderboardDelta {
+        generation: u64,
+        participants: Vec<ParticipantInfo>,
+        order: Vec<String>,
+        leader_splits: Option<HashMap<String, i32>>,
     },
     RaceStatusChange(String),
     PlayerUpdate(ParticipantInfo),
@@ -78,6 +150,44 @@ pub enum IncomingMessage {
         original_tier: Option<i32>,
         exits: Vec<ExitInfo>,
     },
+    /// Clock sync round trip, local times measured on the WS thread
+    ClockSync {
+        client_sent_ms: i64,
+        server_ms: i64,
+        client_received_ms: i64,
+    },
+    /// Server processed the event flag with this correlation id
+    EventFlagAck(u64),
+    /// A participant (possibly us) picked up a key item
+    ItemObtained {
+        participant_id: String,
+        player_name: String,
+        item_name: String,
+    },
+    /// A participant (possibly us) reached a milestone
+    RaceActivity {
+        participant_id: String,
+        player_name: String,
+        kind: ActivityKind,
+        zone_name: Option<String>,
+        igt_ms: u32,
+    },
+    /// The organizer re-rolled the seed
+    SeedChanged(SeedInfo),
+    /// Community hints of a zone's exits
+    ExitHints {
+        node_id: String,
+        hints: Vec<ExitHint>,
+    },
+    /// The organizer granted our pause request
+    PauseGranted,
+    /// The organizer ended our pause, or declined the request
+    Resume,
+    /// The organizer sets an event flag in the game
+    SetFlag {
+        flag_id: u32,
+        value: bool,
+    },
     /// Event flag drained from outgoing channel on reconnect — must be re-buffered
     RequeueEventFlag {
         flag_id: u32,
@@ -98,6 +208,37 @@ pub struct RaceWebSocketClient {
     thread_handle: Option<JoinHandle<()>>,
     shutdown_flag: Arc<AtomicBool>,
     current_status: ConnectionStatus,
+    /// Last character read by the tracker, sent with each auth
+    character: Arc<Mutex<Option<CharacterFingerprint>>>,
+    /// Worker thread state and heartbeat, for restarts and the debug panel
+    health: Arc<WorkerHealth>,
+    /// Last frames sent and received by the worker, for the debug panel
+    trace: Arc<Mutex<MessageTrace>>,
+    restart_backoff: RestartBackoff,
+    stall_logged: bool,
+    /// Set when the worker thread returns
+    closed: Arc<ExitSignal>,
+    /// Progress events held back by the race's broadcast delay
+    delayed: DelayQueue<OutgoingMessage>,
+}
+
+/// Outgoing queue and exit signal of the worker, for the game exit hook to
+/// close the connection from the window thread
+#[derive(Clone)]
+pub struct ExitHandle {
+    tx: Sender<OutgoingMessage>,
+    closed: Arc<ExitSignal>,
+}
+
+impl ExitHandle {
+    /// Send what's queued, then `status` as the last status_update, and close
+    /// the socket. Returns false when the worker didn't finish within `timeout`.
+    pub fn quit(&self, status: Option<(u32, u32)>, timeout: Duration) -> bool {
+        if self.tx.try_send(OutgoingMessage::Quit(status)).is_err() {
+            return false;
+        }
+        self.closed.wait(timeout)
+    }
 }
 
 impl RaceWebSocketClient {
@@ -109,6 +250,13 @@ impl RaceWebSocketClient {
             thread_handle: None,
             shutdown_flag: Arc::new(AtomicBool::new(false)),
             current_status: ConnectionStatus::Disconnected,
+            character: Arc::new(Mutex::new(None)),
+            health: WorkerHealth::new("websocket"),
+            trace: Arc::new(Mutex::new(MessageTrace::default())),
+            restart_backoff: RestartBackoff::default(),
+            stall_logged: false,
+            closed: Arc::new(ExitSignal::new()),
+            delayed: DelayQueue::default(),
         }
     }
 
@@ -135,31 +283,93 @@ impl RaceWebSocketClient {
         self.tx = Some(outgoing_tx);
         self.rx = Some(incoming_rx);
         self.shutdown_flag.store(false, Ordering::SeqCst);
+        self.closed.reset();
 
         let shutdown_flag = Arc::clone(&self.shutdown_flag);
+        let closed = Arc::clone(&self.closed);
+        let character = Arc::clone(&self.character);
+        let health = Arc::clone(&self.health);
+        let trace = Arc::clone(&self.trace);
         let settings = self.settings.clone();
 
         let handle = thread::spawn(move || {
-            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                websocket_thread(settings, outgoing_rx, incoming_tx.clone(), shutdown_flag);
-            }));
+            let result = run_supervised(&health, || {
+                websocket_thread(
+                    settings,
+                    outgoing_rx,
+                    incoming_tx.clone(),
+                    shutdown_flag,
+                    character,
+                    &health,
+                    &trace,
+                );
+            });
 
-            if let Err(panic_info) = result {
-                let msg = if let Some(s) = panic_info.downcast_ref::<&str>() {
-                    format!("WS thread panic: {}", s)
-                } else {
-                    "WS thread panic".to_string()
-                };
+            if let Err(panic_msg) = result {
+                let msg = format!("WS thread panic: {}", panic_msg);
                 error!("{}", msg);
                 let _ = incoming_tx.send(IncomingMessage::Error(msg));
                 let _ = incoming_tx.send(IncomingMessage::StatusChanged(ConnectionStatus::Error));
             }
+            closed.set();
         });
 
         self.thread_handle = Some(handle);
         self.current_status = ConnectionStatus::Connecting;
     }
 
+    /// Watchdog, called every frame: logs a stalled worker thread, and
+    /// restarts it after a panic once its backoff delay has passed. Returns
+    /// the panic message when the thread was restarted.
+    pub fn supervise(&mut self) -> Option<String> {
+        // A stuck thread can't be killed safely: only report it
+        let stalled = self.health.is_stalled();
+        if stalled && !self.stall_logged {
+            warn!(
+                since_beat_secs = self.health.since_beat().as_secs(),
+                "[WS] Worker thread stalled"
+            );
+        }
+        self.stall_logged = stalled;
+
+        let WorkerState::Panicked(panic_msg) = self.health.state() else {
+            return None;
+        };
+        let now = Instant::now();
+        self.restart_backoff.died(now);
+        if !self.restart_backoff.ready(now) {
+            return None;
+        }
+
+        // The thread already returned: joining doesn't block
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+        }
+        self.health.record_restart();
+        self.restart_backoff.restarted(now);
+        warn!(
+            cause = %panic_msg,
+            restarts = self.health.restarts(),
+            next_delay_secs = self.restart_backoff.delay().as_secs(),
+            "[WS] Restarting worker thread after panic"
+        );
+        self.connect();
+        Some(panic_msg)
+    }
+
+    /// Handle for the game exit hook, None when not connecting
+    pub fn exit_handle(&self) -> Option<ExitHandle> {
+        Some(ExitHandle {
+            tx: self.tx.clone()?,
+            closed: Arc::clone(&self.closed),
+        })
+    }
+
+    /// Worker thread state, for the debug panel
+    pub fn health(&self) -> &WorkerHealth {
+        &self.health
+    }
+
     pub fn disconnect(&mut self) {
         self.shutdown_flag.store(true, Ordering::SeqCst);
         if let Some(tx) = &self.tx {
@@ -192,45 +402,210 @@ impl RaceWebSocketClient {
         }
     }
 
-    pub fn send_event_flag(&self, flag_id: u32, igt_ms: u32) {
+    pub fn send_event_flag(
+        &mut self,
+        flag_id: u32,
+        igt_ms: u32,
+        seq: Option<u64>,
+        idempotency_key: Option<String>,
+    ) {
+        self.send_event(OutgoingMessage::EventFlag {
+            flag_id,
+            igt_ms,
+            seq,
+            idempotency_key,
+        });
+    }
+
+    pub fn send_item_obtained(&mut self, item_id: u32, igt_ms: u32) {
+        self.send_event(OutgoingMessage::ItemObtained { item_id, igt_ms });
+    }
+
+    pub fn send_objective_complete(&mut self, objective_id: u32, igt_ms: u32) {
+        self.send_event(OutgoingMessage::ObjectiveComplete {
+            objective_id,
+            igt_ms,
+        });
+    }
+
+    pub fn send_checkpoint_crossed(&mut self, checkpoint_id: u32, igt_ms: u32) {
+        self.send_event(OutgoingMessage::CheckpointCrossed {
+            checkpoint_id,
+            igt_ms,
+        });
+    }
+
+    /// Character to send with the next auth (reconnections included)
+    pub fn set_character(&self, character: CharacterFingerprint) {
+        if let Ok(mut current) = self.character.lock() {
+            *current = Some(character);
+        }
+    }
+
+    pub fn send_character(&self, character: CharacterFingerprint) {
+        if let Some(tx) = &self.tx {
+            if let Err(e) = tx.try_send(OutgoingMessage::Character(character)) {
+                warn!("[WS] Failed to queue message: {}", e);
+            }
+        }
+    }
+
+    pub fn send_ng_cycle(&self, clear_count: u32, igt_ms: u32) {
+        if let Some(tx) = &self.tx {
+            if let Err(e) = tx.try_send(OutgoingMessage::NgCycle {
+                clear_count,
+                igt_ms,
+            }) {
+                warn!("[WS] Failed to queue message: {}", e);
+            }
+        }
+    }
+
+    pub fn send_exit_hints_request(&self, node_id: String) {
+        if let Some(tx) = &self.tx {
+            if let Err(e) = tx.try_send(OutgoingMessage::ExitHintsRequest(node_id)) {
+                warn!("[WS] Failed to queue message: {}", e);
+            }
+        }
+    }
+
+    pub fn send_afk(&self, afk: bool, igt_ms: u32) {
+        if let Some(tx) = &self.tx {
+            if let Err(e) = tx.try_send(OutgoingMessage::Afk { afk, igt_ms }) {
+                warn!("[WS] Failed to queue message: {}", e);
+            }
+        }
+    }
+
+    pub fn send_save_reloaded(&self, reload: SaveReload) {
+        if let Some(tx) = &self.tx {
+            if let Err(e) = tx.try_send(OutgoingMessage::SaveReloaded(reload)) {
+                warn!("[WS] Failed to queue message: {}", e);
+            }
+        }
+    }
+
+    pub fn send_kindling_used(&self, kindling: KindlingUse) {
+        if let Some(tx) = &self.tx {
+            if let Err(e) = tx.try_send(OutgoingMessage::KindlingUsed(kindling)) {
+                warn!("[WS] Failed to queue message: {}", e);
+            }
+        }
+    }
+
+    pub fn send_timing_drift(&self, anomaly: DriftAnomaly) {
+        if let Some(tx) = &self.tx {
+            if let Err(e) = tx.try_send(OutgoingMessage::TimingDrift(anomaly)) {
+                warn!("[WS] Failed to queue message: {}", e);
+            }
+        }
+    }
+
+    pub fn send_grace_discovered(&mut self, flag_id: u32, igt_ms: u32) {
+        self.send_event(OutgoingMessage::GraceDiscovered { flag_id, igt_ms });
+    }
+
+    pub fn send_leaderboard_resync(&self) {
+        if let Some(tx) = &self.tx {
+            if let Err(e) = tx.try_send(OutgoingMessage::LeaderboardResync) {
+                warn!("[WS] Failed to queue message: {}", e);
+            }
+        }
+    }
+
+    pub fn send_pause_request(&self, igt_ms: u32) {
         if let Some(tx) = &self.tx {
-            if let Err(e) = tx.try_send(OutgoingMessage::EventFlag { flag_id, igt_ms }) {
+            if let Err(e) = tx.try_send(OutgoingMessage::PauseRequest { igt_ms }) {
                 warn!("[WS] Failed to queue message: {}", e);
             }
         }
     }
 
     pub fn send_zone_query(
-        &self,
+        &mut self,
         grace_entity_id: Option<u32>,
         map_id: Option<String>,
         position: Option<[f32; 3]>,
         play_region_id: Option<u32>,
     ) {
+        self.send_event(OutgoingMessage::ZoneQuery {
+            grace_entity_id,
+            map_id,
+            position,
+            play_region_id,
+        });
+    }
+
+    /// Send a progress event, after the race's broadcast delay if any
+    fn send_event(&mut self, message: OutgoingMessage) {
+        if let Some(message) = self.delayed.push(message, Instant::now()) {
+            self.queue(message);
+        }
+    }
+
+    fn queue(&self, message: OutgoingMessage) {
         if let Some(tx) = &self.tx {
-            if let Err(e) = tx.try_send(OutgoingMessage::ZoneQuery {
-                grace_entity_id,
-                map_id,
-                position,
-                play_region_id,
-            }) {
-                warn!("[WS] Failed to queue zone_query: {}", e);
+            if let Err(e) = tx.try_send(message) {
+                warn!("[WS] Failed to queue message: {}", e);
             }
         }
     }
 
+    /// Broadcast delay of the race's progress events (`auth_ok` `event_delay_secs`)
+    pub fn set_event_delay(&mut self, delay: Duration) {
+        self.delayed.set_delay(delay);
+    }
+
+    pub fn event_delay(&self) -> Duration {
+        self.delayed.delay()
+    }
+
+    /// Progress events waiting for the broadcast delay
+    pub fn delayed_events(&self) -> usize {
+        self.delayed.len()
+    }
+
+    /// Send the progress events whose broadcast delay has passed, every frame
+    pub fn release_delayed(&mut self) {
+        for message in self.delayed.due(Instant::now()) {
+            self.queue(message);
+        }
+    }
+
+    /// Send every held back progress event at once (game closing)
+    pub fn flush_delayed(&mut self) {
+        for message in self.delayed.flush() {
+            self.queue(message);
+        }
+    }
+
     pub fn poll(&mut self) -> Option<IncomingMessage> {
         let rx = self.rx.as_ref()?;
-        match rx.try_recv() {
+        let received = rx.try_recv().map_err(|e| e.is_disconnected());
+        self.received(received)
+    }
+
+    /// Like `poll`, but waits up to `timeout` for the next message (for
+    /// callers without a frame loop, such as the integration tests)
+    pub fn poll_timeout(&mut self, timeout: Duration) -> Option<IncomingMessage> {
+        let rx = self.rx.as_ref()?;
+        let received = rx.recv_timeout(timeout).map_err(|e| e.is_disconnected());
+        self.received(received)
+    }
+
+    /// Track the connection status; `Err(true)` when the worker is gone
+    fn received(&mut self, received: Result<IncomingMessage, bool>) -> Option<IncomingMessage> {
+        match received {
             Ok(msg) => {
                 if let IncomingMessage::StatusChanged(status) = &msg {
                     self.current_status = *status;
                 }
                 Some(msg)
             }
-            Err(TryRecvError::Empty) => None,
-            Err(TryRecvError::Disconnected) => {
-                self.current_status = ConnectionStatus::Disconnected;
+            Err(disconnected) => {
+                if disconnected {
+                    self.current_status = ConnectionStatus::Disconnected;
+                }
                 None
             }
         }
@@ -243,6 +618,11 @@ impl RaceWebSocketClient {
     pub fn is_connected(&self) -> bool {
         self.current_status == ConnectionStatus::Connected
     }
+
+    /// Frames exchanged with the server, kept across reconnections
+    pub fn trace(&self) -> &Mutex<MessageTrace> {
+        &self.trace
+    }
 }
 
 impl Drop for RaceWebSocketClient {
@@ -260,32 +640,37 @@ fn websocket_thread(
     outgoing_rx: Receiver<OutgoingMessage>,
     incoming_tx: Sender<IncomingMessage>,
     shutdown_flag: Arc<AtomicBool>,
+    character: Arc<Mutex<Option<CharacterFingerprint>>>,
+    health: &WorkerHealth,
+    trace: &Mutex<MessageTrace>,
 ) {
     let mut reconnect_delay = Duration::from_secs(1);
     let max_delay = Duration::from_secs(30);
+    // Kept across reconnections so unsupported message types are only logged once
+    let mut decoder = ServerDecoder::new();
+    let mut selector = TransportSelector::new(settings.transport);
 
     loop {
+        health.beat();
         if shutdown_flag.load(Ordering::SeqCst) {
             break;
         }
 
-        // Build URL
-        let base = settings.url.trim_end_matches('/');
-        let ws_base = if base.starts_with("https://") {
-            base.replacen("https://", "wss://", 1)
-        } else if base.starts_with("http://") {
-            base.replacen("http://", "ws://", 1)
-        } else {
-            base.to_string()
-        };
-        let endpoint = if settings.training { "training" } else { "mod" };
-        let url = format!("{}/ws/{}/{}", ws_base, endpoint, settings.race_id);
-
-        info!(url = %url, "[WS] Connecting...");
+        let transport = selector.next(Instant::now());
+        info!(url = %settings.url, transport = ?transport, "[WS] Connecting...");
         let _ = incoming_tx.send(IncomingMessage::StatusChanged(ConnectionStatus::Connecting));
 
-        match connect_and_auth(&url, &settings.mod_token, &incoming_tx) {
-            Ok(mut socket) => {
+        let auth_character = character.lock().ok().and_then(|c| c.clone());
+        match connect_and_auth(
+            &mut selector,
+            transport,
+            &settings,
+            auth_character,
+            &incoming_tx,
+            &mut decoder,
+            trace,
+        ) {
+            Ok((mut socket, encoding)) => {
                 info!("[WS] Connected and authenticated");
 
                 // Drain stale outgoing messages before notifying Connected.
@@ -294,13 +679,15 @@ fn websocket_thread(
                 let mut drained = 0u32;
                 while let Ok(msg) = outgoing_rx.try_recv() {
                     match msg {
-                        OutgoingMessage::Shutdown => {
+                        OutgoingMessage::Shutdown | OutgoingMessage::Quit(_) => {
                             let _ = incoming_tx.send(IncomingMessage::StatusChanged(
                                 ConnectionStatus::Disconnected,
                             ));
                             return;
                         }
-                        OutgoingMessage::EventFlag { flag_id, igt_ms } => {
+                        OutgoingMessage::EventFlag {
+                            flag_id, igt_ms, ..
+                        } => {
                             // Re-queue event flags back to the tracker for re-buffering.
                             // These were queued but never transmitted before disconnect.
                             let _ = incoming_tx
@@ -318,11 +705,20 @@ fn websocket_thread(
                     incoming_tx.send(IncomingMessage::StatusChanged(ConnectionStatus::Connected));
                 reconnect_delay = Duration::from_secs(1);
 
-                let result = message_loop(&mut socket, &outgoing_rx, &incoming_tx, &shutdown_flag);
+                let result = message_loop(
+                    socket.as_mut(),
+                    &outgoing_rx,
+                    &incoming_tx,
+                    &shutdown_flag,
+                    &mut decoder,
+                    encoding,
+                    health,
+                    trace,
+                );
                 if let Err(e) = &result {
                     info!(error = %e, "[WS] Disconnected");
                 }
-                let _ = socket.close(None);
+                socket.close();
 
                 if result.is_err() && !shutdown_flag.load(Ordering::SeqCst) {
                     let _ = incoming_tx.send(IncomingMessage::StatusChanged(
@@ -351,76 +747,178 @@ fn websocket_thread(
     ));
 }
 
+/// Record a frame in the message trace (a poisoned lock only loses the entry)
+fn trace_frame(trace: &Mutex<MessageTrace>, direction: Direction, frame: &Message) {
+    let Ok(mut trace) = trace.lock() else {
+        return;
+    };
+    match frame {
+        Message::Text(text) => trace.record_text(direction, unix_time_ms(), text),
+        Message::Binary(bytes) => trace.record_binary(direction, unix_time_ms(), bytes),
+        _ => {}
+    }
+}
+
+/// Send a client message in the encoding negotiated for the connection
+fn send_message(
+    socket: &mut dyn ServerTransport,
+    encoding: Encoding,
+    msg: &ClientMessage,
+    trace: &Mutex<MessageTrace>,
+) -> Result<(), String> {
+    let frame = match encoding.encode(msg)? {
+        Frame::Text(text) => Message::Text(text),
+        Frame::Binary(bytes) => Message::Binary(bytes),
+    };
+    trace_frame(trace, Direction::Sent, &frame);
+    socket.send(frame)
+}
+
 fn connect_and_auth(
-    url: &str,
-    mod_token: &str,
+    selector: &mut TransportSelector,
+    transport: Transport,
+    settings: &ServerSettings,
+    character: Option<CharacterFingerprint>,
     incoming_tx: &Sender<IncomingMessage>,
-) -> Result<WebSocket<MaybeTlsStream<TcpStream>>, String> {
-    let (mut socket, _) = connect(url).map_err(|e| format!("Connect failed: {}", e))?;
+    decoder: &mut ServerDecoder,
+    trace: &Mutex<MessageTrace>,
+) -> Result<(Box<dyn ServerTransport>, Encoding), String> {
+    let opened = open_transport(
+        &settings.url,
+        transport,
+        settings.training,
+        &settings.race_id,
+    );
+    let mut socket = match opened {
+        Ok(socket) => {
+            selector.connected(transport);
+            socket
+        }
+        Err(e) => {
+            if selector.handshake_failed(transport, Instant::now()) {
+                warn!("[WS] WebSocket looks blocked, falling back to HTTP long-polling");
+            }
+            return Err(e);
+        }
+    };
+    // Long-polling only carries JSON
+    let binary_protocol = settings.binary_protocol && transport == Transport::WebSocket;
 
-    // Send auth
+    // Send auth (always JSON, the encoding is negotiated by this exchange)
     let auth = ClientMessage::Auth {
-        mod_token: mod_token.to_string(),
+        mod_token: settings.mod_token.clone(),
+        protocol_version: PROTOCOL_VERSION,
+        character,
+        encodings: if binary_protocol {
+            Encoding::SUPPORTED.to_vec()
+        } else {
+            Vec::new()
+        },
     };
-    let json = serde_json::to_string(&auth).map_err(|e| format!("JSON: {}", e))?;
-    socket
-        .send(Message::Text(json))
-        .map_err(|e| format!("Send: {}", e))?;
+    send_auth_json(socket.as_mut(), &auth, trace)?;
 
     // Wait for response
-    let resp = socket.read().map_err(|e| format!("Read: {}", e))?;
-    match resp {
-        Message::Text(text) => {
-            let msg: ServerMessage =
-                serde_json::from_str(&text).map_err(|e| format!("Parse: {}", e))?;
-
-            match msg {
-                ServerMessage::AuthOk {
+    loop {
+        let Some(resp) = socket.read()? else {
+            continue;
+        };
+        trace_frame(trace, Direction::Received, &resp);
+        let Message::Text(text) = resp else {
+            return Err("Unexpected message type".to_string());
+        };
+        let msg = match decoder.decode(&text) {
+            Decoded::Message(msg) => *msg,
+            Decoded::Unsupported { msg_type, .. } => {
+                return Err(format!("Unexpected response: {}", msg_type))
+            }
+            Decoded::Invalid(e) => return Err(e),
+        };
+
+        match msg {
+            ServerMessage::AuthOk {
+                participant_id,
+                race,
+                seed,
+                participants,
+                protocol_version,
+                objectives,
+                checkpoints,
+                encoding,
+                permissions,
+                event_delay_secs,
+                zone_privacy,
+            } => {
+                decoder.set_version(protocol_version);
+                // Only trust an encoding this mod offered
+                let encoding = if decoder.version() >= 11
+                    && binary_protocol
+                    && Encoding::SUPPORTED.contains(&encoding)
+                {
+                    encoding
+                } else {
+                    Encoding::Json
+                };
+                info!(
+                    protocol_version = decoder.version(),
+                    encoding = ?encoding,
+                    "[WS] Protocol version negotiated"
+                );
+                let _ = incoming_tx.send(IncomingMessage::AuthOk {
                     participant_id,
                     race,
                     seed,
                     participants,
-                } => {
-                    let _ = incoming_tx.send(IncomingMessage::AuthOk {
-                        participant_id,
-                        race,
-                        seed,
-                        participants,
-                    });
-                    Ok(socket)
-                }
-                ServerMessage::AuthError { message } => {
-                    let _ = incoming_tx.send(IncomingMessage::AuthError(message.clone()));
-                    Err(format!("Auth failed: {}", message))
-                }
-                _ => Err(format!("Unexpected response: {:?}", msg)),
+                    protocol_version: decoder.version(),
+                    objectives,
+                    checkpoints,
+                    permissions,
+                    event_delay_secs,
+                    zone_privacy,
+                });
+                return Ok((socket, encoding));
+            }
+            ServerMessage::AuthError { message } => {
+                let _ = incoming_tx.send(IncomingMessage::AuthError(message.clone()));
+                return Err(format!("Auth failed: {}", message));
             }
+            _ => return Err(format!("Unexpected response: {:?}", msg)),
         }
-        _ => Err("Unexpected message type".to_string()),
     }
 }
 
+/// Send a message of the auth handshake, always JSON
+fn send_auth_json(
+    socket: &mut dyn ServerTransport,
+    msg: &ClientMessage,
+    trace: &Mutex<MessageTrace>,
+) -> Result<(), String> {
+    let json = serde_json::to_string(msg).map_err(|e| format!("JSON: {}", e))?;
+    let frame = Message::Text(json);
+    trace_frame(trace, Direction::Sent, &frame);
+    socket.send(frame)
+}
+
 fn message_loop(
-    socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
+    socket: &mut dyn ServerTransport,
     outgoing_rx: &Receiver<OutgoingMessage>,
     incoming_tx: &Sender<IncomingMessage>,
     shutdown_flag: &Arc<AtomicBool>,
+    decoder: &mut ServerDecoder,
+    encoding: Encoding,
+    health: &WorkerHealth,
+    trace: &Mutex<MessageTrace>,
 ) -> Result<(), String> {
     let mut last_ping_received = Instant::now();
     let ping_timeout = Duration::from_secs(60);
 
-    // Set non-blocking
-    match socket.get_ref() {
-        MaybeTlsStream::Plain(tcp) => {
-            let _ = tcp.set_nonblocking(true);
-        }
-        MaybeTlsStream::NativeTls(tls) => {
-            let _ = tls.get_ref().set_nonblocking(true);
-        }
-        _ => {}
-    }
+    // Clock sync probes: a quick burst after connecting, then a slow refresh
+    let mut last_clock_probe: Option<Instant> = None;
+    let mut clock_probes_sent = 0u32;
+
+    socket.set_nonblocking();
 
     loop {
+        health.beat();
         if shutdown_flag.load(Ordering::SeqCst) {
             return Ok(());
         }
@@ -430,14 +928,27 @@ fn message_loop(
             return Err("Server ping timeout (60s)".to_string());
         }
 
+        // Clock sync probe (servers before protocol 2 don't answer them)
+        let clock_sync_supported = decoder.version() >= 2;
+        let probe_interval = if clock_probes_sent < CLOCK_SYNC_BURST {
+            CLOCK_SYNC_BURST_INTERVAL
+        } else {
+            CLOCK_SYNC_INTERVAL
+        };
+        if clock_sync_supported && last_clock_probe.is_none_or(|t| t.elapsed() >= probe_interval) {
+            let probe = ClientMessage::ClockSync {
+                client_time_ms: unix_time_ms(),
+            };
+            send_message(socket, encoding, &probe, trace)?;
+            last_clock_probe = Some(Instant::now());
+            clock_probes_sent += 1;
+        }
+
         // Handle outgoing
         match outgoing_rx.try_recv() {
             Ok(OutgoingMessage::Ready) => {
                 let msg = ClientMessage::Ready;
-                let json = serde_json::to_string(&msg).map_err(|e| e.to_string())?;
-                socket
-                    .send(Message::Text(json))
-                    .map_err(|e| e.to_string())?;
+                send_message(socket, encoding, &msg, trace)?;
             }
             Ok(OutgoingMessage::StatusUpdate {
                 igt_ms,
@@ -446,18 +957,23 @@ fn message_loop(
                 let msg = ClientMessage::StatusUpdate {
                     igt_ms,
                     death_count,
+                    disconnecting: false,
+                };
+                send_message(socket, encoding, &msg, trace)?;
+            }
+            Ok(OutgoingMessage::EventFlag {
+                flag_id,
+                igt_ms,
+                seq,
+                idempotency_key,
+            }) => {
+                let msg = ClientMessage::EventFlag {
+                    flag_id,
+                    igt_ms,
+                    seq,
+                    idempotency_key,
                 };
-                let json = serde_json::to_string(&msg).map_err(|e| e.to_string())?;
-                socket
-                    .send(Message::Text(json))
-                    .map_err(|e| e.to_string())?;
-            }
-            Ok(OutgoingMessage::EventFlag { flag_id, igt_ms }) => {
-                let msg = ClientMessage::EventFlag { flag_id, igt_ms };
-                let json = serde_json::to_string(&msg).map_err(|e| e.to_string())?;
-                socket
-                    .send(Message::Text(json))
-                    .map_err(|e| e.to_string())?;
+                send_message(socket, encoding, &msg, trace)?;
             }
             Ok(OutgoingMessage::ZoneQuery {
                 grace_entity_id,
@@ -471,10 +987,102 @@ fn message_loop(
                     position,
                     play_region_id,
                 };
-                let json = serde_json::to_string(&msg).map_err(|e| e.to_string())?;
-                socket
-                    .send(Message::Text(json))
-                    .map_err(|e| e.to_string())?;
+                send_message(socket, encoding, &msg, trace)?;
+            }
+            Ok(OutgoingMessage::ItemObtained { item_id, igt_ms }) => {
+                let msg = ClientMessage::ItemObtained { item_id, igt_ms };
+                send_message(socket, encoding, &msg, trace)?;
+            }
+            Ok(OutgoingMessage::ObjectiveComplete {
+                objective_id,
+                igt_ms,
+            }) => {
+                let msg = ClientMessage::ObjectiveComplete {
+                    objective_id,
+                    igt_ms,
+                };
+                send_message(socket, encoding, &msg, trace)?;
+            }
+            Ok(OutgoingMessage::CheckpointCrossed {
+                checkpoint_id,
+                igt_ms,
+            }) => {
+                let msg = ClientMessage::CheckpointCrossed {
+                    checkpoint_id,
+                    igt_ms,
+                };
+                send_message(socket, encoding, &msg, trace)?;
+            }
+            Ok(OutgoingMessage::Character(character)) => {
+                let msg = ClientMessage::Character { character };
+                send_message(socket, encoding, &msg, trace)?;
+            }
+            Ok(OutgoingMessage::NgCycle {
+                clear_count,
+                igt_ms,
+            }) => {
+                let msg = ClientMessage::NgCycle {
+                    clear_count,
+                    igt_ms,
+                };
+                send_message(socket, encoding, &msg, trace)?;
+            }
+            Ok(OutgoingMessage::ExitHintsRequest(node_id)) => {
+                let msg = ClientMessage::ExitHintsRequest { node_id };
+                send_message(socket, encoding, &msg, trace)?;
+            }
+            Ok(OutgoingMessage::Afk { afk, igt_ms }) => {
+                let msg = ClientMessage::Afk { afk, igt_ms };
+                send_message(socket, encoding, &msg, trace)?;
+            }
+            Ok(OutgoingMessage::SaveReloaded(reload)) => {
+                let msg = ClientMessage::SaveReloaded {
+                    igt_ms: reload.igt_ms,
+                    igt_delta_ms: reload.igt_delta_ms,
+                    death_count: reload.death_count,
+                    death_delta: reload.death_delta,
+                };
+                send_message(socket, encoding, &msg, trace)?;
+            }
+            Ok(OutgoingMessage::KindlingUsed(kindling)) => {
+                let msg = ClientMessage::KindlingUsed {
+                    igt_ms: kindling.igt_ms,
+                    used: kindling.used,
+                    remaining: kindling.remaining,
+                };
+                send_message(socket, encoding, &msg, trace)?;
+            }
+            Ok(OutgoingMessage::TimingDrift(anomaly)) => {
+                let msg = ClientMessage::TimingDrift {
+                    igt_ms: anomaly.igt_ms,
+                    igt_delta_ms: anomaly.igt_delta_ms,
+                    wall_delta_ms: anomaly.wall_delta_ms,
+                };
+                send_message(socket, encoding, &msg, trace)?;
+            }
+            Ok(OutgoingMessage::GraceDiscovered { flag_id, igt_ms }) => {
+                let msg = ClientMessage::GraceDiscovered { flag_id, igt_ms };
+                send_message(socket, encoding, &msg, trace)?;
+            }
+            Ok(OutgoingMessage::LeaderboardResync) => {
+                send_message(socket, encoding, &ClientMessage::LeaderboardResync, trace)?;
+            }
+            Ok(OutgoingMessage::PauseRequest { igt_ms }) => {
+                let msg = ClientMessage::PauseRequest { igt_ms };
+                send_message(socket, encoding, &msg, trace)?;
+            }
+            Ok(OutgoingMessage::Quit(status)) => {
+                if let Some((igt_ms, death_count)) = status {
+                    let msg = ClientMessage::StatusUpdate {
+                        igt_ms,
+                        death_count,
+                        disconnecting: decoder.version() >= 17,
+                    };
+                    send_message(socket, encoding, &msg, trace)?;
+                }
+                info!("[WS] Game closing, disconnecting");
+                shutdown_flag.store(true, Ordering::SeqCst);
+                return Ok(());
             }
             Ok(OutgoingMessage::Shutdown) => return Ok(()),
             Err(TryRecvError::Empty) => {}
@@ -483,27 +1091,68 @@ fn message_loop(
 
         // Handle incoming
         match socket.read() {
-            Ok(Message::Text(text)) => {
-                if let Ok(msg) = serde_json::from_str::<ServerMessage>(&text) {
+            // Servers may answer in either frame type once msgpack is negotiated
+            Ok(Some(frame)) => {
+                trace_frame(trace, Direction::Received, &frame);
+                let decoded = match frame {
+                    Message::Binary(bytes) => decoder.decode_binary(&bytes),
+                    frame => decoder.decode(frame.to_text().unwrap_or_default()),
+                };
+                let msg = match decoded {
+                    Decoded::Message(msg) => Some(*msg),
+                    Decoded::Unsupported { msg_type, first } => {
+                        if first {
+                            warn!(msg_type = %msg_type, "[WS] Ignoring unsupported message type");
+                        }
+                        None
+                    }
+                    Decoded::Invalid(e) => {
+                        warn!(error = %e, "[WS] Ignoring malformed message");
+                        None
+                    }
+                };
+                if let Some(msg) = msg {
                     match msg {
                         ServerMessage::Ping => {
                             last_ping_received = Instant::now();
                             let pong = ClientMessage::Pong;
-                            let json = serde_json::to_string(&pong).map_err(|e| e.to_string())?;
-                            socket
-                                .send(Message::Text(json))
-                                .map_err(|e| e.to_string())?;
+                            send_message(socket, encoding, &pong, trace)?;
                         }
-                        ServerMessage::RaceStart => {
-                            let _ = incoming_tx.send(IncomingMessage::RaceStart);
+                        ServerMessage::RaceStart { started_at } => {
+                            let _ = incoming_tx.send(IncomingMessage::RaceStart { started_at });
+                        }
+                        ServerMessage::ClockSync {
+                            client_time_ms,
+                            server_time_ms,
+                        } => {
+                            let _ = incoming_tx.send(IncomingMessage::ClockSync {
+                                client_sent_ms: client_time_ms,
+                                server_ms: server_time_ms,
+                                client_received_ms: unix_time_ms(),
+                            });
                         }
                         ServerMessage::LeaderboardUpdate {
                             participants,
                             leader_splits,
+                            generation,
                         } => {
                             let _ = incoming_tx.send(IncomingMessage::LeaderboardUpdate {
                                 participants,
                                 leader_splits,
+                                generation,
+                            });
+                        }
+                        ServerMessage::LeaderboardDelta {
+                            generation,
+                            participants,
+                            order,
+                            leader_splits,
+                        } => {
+                            let _ = incoming_tx.send(IncomingMessage::LeaderboardDelta {
+                                generation,
+                                participants,
+                                order,
+                                leader_splits,
                             });
                         }
                         ServerMessage::RaceStatusChange { status } => {
@@ -527,6 +1176,51 @@ fn message_loop(
                                 exits,
                             });
                         }
+                        ServerMessage::EventFlagAck { seq } => {
+                            let _ = incoming_tx.send(IncomingMessage::EventFlagAck(seq));
+                        }
+                        ServerMessage::ItemObtained {
+                            participant_id,
+                            player_name,
+                            item_name,
+                            ..
+                        } => {
+                            let _ = incoming_tx.send(IncomingMessage::ItemObtained {
+                                participant_id,
+                                player_name,
+                                item_name,
+                            });
+                        }
+                        ServerMessage::RaceActivity {
+                            participant_id,
+                            player_name,
+                            kind,
+                            zone_name,
+                            igt_ms,
+                        } => {
+                            let _ = incoming_tx.send(IncomingMessage::RaceActivity {
+                                participant_id,
+                                player_name,
+                                kind,
+                                zone_name,
+                                igt_ms,
+                            });
+                        }
+                        ServerMessage::SeedChanged { seed } => {
+                            let _ = incoming_tx.send(IncomingMessage::SeedChanged(seed));
+                        }
+                        ServerMessage::ExitHints { node_id, hints } => {
+                            let _ = incoming_tx.send(IncomingMessage::ExitHints { node_id, hints });
+                        }
+                        ServerMessage::PauseGranted => {
+                            let _ = incoming_tx.send(IncomingMessage::PauseGranted);
+                        }
+                        ServerMessage::Resume => {
+                            let _ = incoming_tx.send(IncomingMessage::Resume);
+                        }
+                        ServerMessage::SetFlag { flag_id, value } => {
+                            let _ = incoming_tx.send(IncomingMessage::SetFlag { flag_id, value });
+                        }
                         ServerMessage::Error { message } => {
                             let _ = incoming_tx.send(IncomingMessage::Error(message));
                         }
@@ -534,12 +1228,8 @@ fn message_loop(
                     }
                 }
             }
-            Ok(Message::Close(_)) => return Err("Server closed".to_string()),
-            Err(tungstenite::Error::Io(ref e))
-                if e.kind() == std::io::ErrorKind::WouldBlock
-                    || e.kind() == std::io::ErrorKind::Interrupted => {}
-            Err(e) => return Err(format!("Read error: {}", e)),
-            _ => {}
+            Ok(None) => {}
+            Err(e) => return Err(e),
         }
 
         thread::sleep(Duration::from_millis(10));
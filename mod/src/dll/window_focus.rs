@@ -0,0 +1,36 @@
+//! Game window focus, for the low-power mode (`core::focus`)
+
+use std::sync::atomic::{AtomicIsize, Ordering};
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, IsIconic};
+
+use super::exit_hook::find_game_window;
+use crate::core::focus::WindowFocus;
+
+/// Game window handle, looked up once it exists
+static GAME_WINDOW: AtomicIsize = AtomicIsize::new(0);
+
+/// Focus of the game window this frame. Focused until the window is found.
+pub fn current() -> WindowFocus {
+    let hwnd = match GAME_WINDOW.load(Ordering::Relaxed) {
+        0 => match find_game_window() {
+            Some(hwnd) => {
+                GAME_WINDOW.store(hwnd.0, Ordering::Relaxed);
+                hwnd
+            }
+            None => return WindowFocus::Focused,
+        },
+        raw => HWND(raw),
+    };
+    // SAFETY: plain window queries; a destroyed handle only reads as unfocused
+    unsafe {
+        if IsIconic(hwnd).as_bool() {
+            WindowFocus::Minimized
+        } else if GetForegroundWindow() == hwnd {
+            WindowFocus::Focused
+        } else {
+            WindowFocus::Background
+        }
+    }
+}
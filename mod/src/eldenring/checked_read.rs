@@ -0,0 +1,59 @@
+//! Checked memory reads
+//!
+//! Every read of game memory goes through `read` with the name of its
+//! pointer chain. libeldenring's `PointerChain` reads with
+//! ReadProcessMemory, so an address a game patch moved fails instead of
+//! faulting; raw pointers are never dereferenced. Outcomes feed the
+//! process-wide `ReadAudit` (`core::read_audit`): chains failing in a row
+//! back off, and the counters show in the debug panel.
+
+use std::sync::Mutex;
+use std::time::Instant;
+
+use libeldenring::memedit::PointerChain;
+use tracing::warn;
+
+use crate::core::read_audit::{ChainStats, ReadAudit, BACKOFF_THRESHOLD};
+
+static AUDIT: Mutex<ReadAudit> = Mutex::new(ReadAudit::new());
+
+/// Run `read` for `chain` unless the chain is backing off (None then), and
+/// record whether it succeeded
+pub fn read<T>(chain: &'static str, read: impl FnOnce() -> Option<T>) -> Option<T> {
+    let now = Instant::now();
+    // A poisoned lock only loses the counters: keep reading
+    if let Ok(mut audit) = AUDIT.lock() {
+        if !audit.allow(chain, now) {
+            return None;
+        }
+    }
+    let value = read();
+    if let Ok(mut audit) = AUDIT.lock() {
+        if audit.record(chain, value.is_some(), now) {
+            warn!(
+                chain,
+                failures = BACKOFF_THRESHOLD,
+                "[MEMORY] Reads failing in a row, backing off"
+            );
+        }
+    }
+    value
+}
+
+/// Read the value at `addr`, as part of `chain`
+pub fn read_at<T>(chain: &'static str, addr: usize) -> Option<T> {
+    read(chain, || PointerChain::<T>::new(&[addr]).read())
+}
+
+/// Counters of every chain read so far, by name
+pub fn stats() -> Vec<(&'static str, ChainStats)> {
+    AUDIT
+        .lock()
+        .map(|audit| {
+            audit
+                .chains()
+                .map(|(name, stats)| (name, stats.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,141 @@
+//! Environment check before hooking
+//!
+//! Gathers the game's parent process and loaded modules and runs
+//! `core::environment` on them. If the game runs under Easy Anti-Cheat, the
+//! player is told with a message box and the DLL unloads without installing
+//! any hook. The same module list feeds `core::module_conflicts`.
+
+use tracing::{info, warn};
+use windows::core::{w, HSTRING};
+use windows::Win32::Foundation::{CloseHandle, HINSTANCE, HWND};
+use windows::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, Module32FirstW, Module32NextW, Process32FirstW, Process32NextW,
+    MODULEENTRY32W, PROCESSENTRY32W, TH32CS_SNAPMODULE, TH32CS_SNAPPROCESS,
+};
+use windows::Win32::System::Threading::GetCurrentProcessId;
+use windows::Win32::UI::WindowsAndMessaging::{MessageBoxW, MB_ICONERROR, MB_OK};
+
+use crate::core::environment::{check_game, EnvironmentViolation};
+use crate::core::module_conflicts::{find_conflicts, Conflict, LoadedModule};
+
+struct ProcessInfo {
+    pid: u32,
+    parent_pid: u32,
+    name: String,
+}
+
+/// Snapshot of the running processes, empty if the snapshot fails
+fn list_processes() -> Vec<ProcessInfo> {
+    let mut processes = Vec::new();
+    // SAFETY: the snapshot handle is closed below; entry.dwSize is set as required.
+    unsafe {
+        let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else {
+            warn!("[ENV] Process snapshot failed");
+            return processes;
+        };
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut more = Process32FirstW(snapshot, &mut entry).is_ok();
+        while more {
+            let len = entry
+                .szExeFile
+                .iter()
+                .position(|&c| c == 0)
+                .unwrap_or(entry.szExeFile.len());
+            processes.push(ProcessInfo {
+                pid: entry.th32ProcessID,
+                parent_pid: entry.th32ParentProcessID,
+                name: String::from_utf16_lossy(&entry.szExeFile[..len]),
+            });
+            more = Process32NextW(snapshot, &mut entry).is_ok();
+        }
+        let _ = CloseHandle(snapshot);
+    }
+    processes
+}
+
+/// Check that the game isn't running under Easy Anti-Cheat
+pub fn check(own: HINSTANCE) -> Result<(), EnvironmentViolation> {
+    let processes = list_processes();
+    // SAFETY: no preconditions.
+    let pid = unsafe { GetCurrentProcessId() };
+    let parent = processes
+        .iter()
+        .find(|p| p.pid == pid)
+        .and_then(|me| processes.iter().find(|p| p.pid == me.parent_pid))
+        .map(|p| p.name.as_str());
+    let modules = list_modules(own);
+    let names: Vec<&str> = modules.iter().map(|m| m.name.as_str()).collect();
+
+    let result = check_game(parent, &names);
+    if result.is_ok() {
+        info!(parent = ?parent, "[ENV] Environment check passed");
+    }
+    result
+}
+
+/// Modules loaded in the game process, empty if the snapshot fails
+fn list_modules(own: HINSTANCE) -> Vec<LoadedModule> {
+    let mut modules = Vec::new();
+    // SAFETY: the snapshot handle is closed below; entry.dwSize is set as required.
+    unsafe {
+        let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPMODULE, GetCurrentProcessId())
+        else {
+            warn!("[ENV] Module snapshot failed");
+            return modules;
+        };
+        let mut entry = MODULEENTRY32W {
+            dwSize: std::mem::size_of::<MODULEENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut more = Module32FirstW(snapshot, &mut entry).is_ok();
+        while more {
+            let len = entry
+                .szModule
+                .iter()
+                .position(|&c| c == 0)
+                .unwrap_or(entry.szModule.len());
+            modules.push(LoadedModule {
+                name: String::from_utf16_lossy(&entry.szModule[..len]),
+                own: entry.hModule.0 == own.0,
+            });
+            more = Module32NextW(snapshot, &mut entry).is_ok();
+        }
+        let _ = CloseHandle(snapshot);
+    }
+    modules
+}
+
+/// Known conflicting mods loaded in the game, logged
+pub fn module_conflicts(own: HINSTANCE) -> Vec<Conflict> {
+    let modules = list_modules(own);
+    let conflicts = find_conflicts(&modules);
+    for conflict in &conflicts {
+        warn!(
+            module = %conflict.module,
+            "[ENV] Conflicting mod loaded: {}: {}",
+            conflict.name,
+            conflict.kind.description()
+        );
+    }
+    if conflicts.is_empty() {
+        info!(modules = modules.len(), "[ENV] No conflicting mod loaded");
+    }
+    conflicts
+}
+
+/// Blocking message box explaining why the mod won't load
+pub fn show_refusal(violation: &EnvironmentViolation) {
+    let text = HSTRING::from(violation.user_message());
+    // SAFETY: both strings are NUL-terminated and outlive the call.
+    unsafe {
+        MessageBoxW(
+            HWND::default(),
+            &text,
+            w!("SpeedFog Racing"),
+            MB_OK | MB_ICONERROR,
+        );
+    }
+}
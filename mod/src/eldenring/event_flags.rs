@@ -15,6 +15,15 @@ use std::fmt;
 use libeldenring::memedit::PointerChain;
 use tracing::{debug, info, warn};
 
+use super::checked_read::{self, read_at};
+use super::version::FlagManagerLayout;
+
+/// Pointer chains of the reads, as named in the read audit
+const MANAGER: &str = "event_flags.manager";
+const DIVISOR: &str = "event_flags.divisor";
+const TREE: &str = "event_flags.tree";
+const PAGE: &str = "event_flags.page";
+
 /// Diagnostic status of the event flag reader.
 pub enum FlagReaderStatus {
     /// base_ptr.read() returned None — memory not readable
@@ -47,11 +56,13 @@ impl fmt::Display for FlagReaderStatus {
 pub struct EventFlagReader {
     /// Pointer to the VirtualMemoryFlag manager
     base_ptr: PointerChain<usize>,
+    /// Manager field offsets for the running game version
+    layout: FlagManagerLayout,
 }
 
 impl EventFlagReader {
     /// Create a new EventFlagReader from the csfd4_virtual_memory_flag base address.
-    pub fn new(csfd4_virtual_memory_flag: usize) -> Self {
+    pub fn new(csfd4_virtual_memory_flag: usize, layout: FlagManagerLayout) -> Self {
         info!(
             base_addr = format_args!("0x{:x}", csfd4_virtual_memory_flag),
             "[EVENT_FLAGS] EventFlagReader created"
@@ -59,21 +70,19 @@ impl EventFlagReader {
         // csfd4_virtual_memory_flag is the address storing the CSFd4VirtualMemoryFlag*
         // Single dereference gives us the manager struct pointer
         let base_ptr = PointerChain::<usize>::new(&[csfd4_virtual_memory_flag]);
-        Self { base_ptr }
+        Self { base_ptr, layout }
     }
 
     /// Diagnose the current state of the flag reader without the ambiguity of Option<bool>.
     pub fn diagnose(&self) -> FlagReaderStatus {
-        let manager = match self.base_ptr.read() {
+        let manager = match checked_read::read(MANAGER, || self.base_ptr.read()) {
             Some(m) => m,
             None => return FlagReaderStatus::NoPtrRead,
         };
         if manager == 0 {
             return FlagReaderStatus::ManagerNull;
         }
-        let divisor: u32 = PointerChain::<u32>::new(&[manager + 0x1c])
-            .read()
-            .unwrap_or(0);
+        let divisor: u32 = read_at(DIVISOR, manager + self.layout.divisor).unwrap_or(0);
         FlagReaderStatus::Ok {
             manager_addr: manager,
             divisor,
@@ -86,12 +95,12 @@ impl EventFlagReader {
     /// then writes the bit. The game's save system serializes the tree,
     /// so the change persists in the save file.
     pub fn set_flag(&self, flag_id: u32, value: bool) -> bool {
-        let manager = match self.base_ptr.read() {
+        let manager = match checked_read::read(MANAGER, || self.base_ptr.read()) {
             Some(m) if m != 0 => m,
             _ => return false,
         };
 
-        let divisor: u32 = match PointerChain::<u32>::new(&[manager + 0x1c]).read() {
+        let divisor: u32 = match read_at(DIVISOR, manager + self.layout.divisor) {
             Some(d) if d != 0 => d,
             _ => return false,
         };
@@ -109,7 +118,7 @@ impl EventFlagReader {
         let mask = 1u8 << bit_index;
 
         let addr = data_ptr + byte_offset;
-        let current: u8 = match PointerChain::<u8>::new(&[addr]).read() {
+        let current: u8 = match read_at(PAGE, addr) {
             Some(v) => v,
             None => return false,
         };
@@ -121,9 +130,10 @@ impl EventFlagReader {
         };
 
         if new_val != current {
-            // SAFETY: Single-byte write is atomic on x86. Flag 1040292900 (category 1040292,
-            // offset 900) is exclusively ours — FogRando uses offsets 100-299, so no
-            // concurrent modification of this byte is possible.
+            // SAFETY: Single-byte write is atomic on x86, to a page found in the game's
+            // flag tree. Flag 1040292900 (category 1040292, offset 900) is exclusively
+            // ours — FogRando uses offsets 100-299. Other flags are only written on the
+            // organizer's request, restricted to the player's `[remote_flags]` whitelist.
             unsafe {
                 std::ptr::write(addr as *mut u8, new_val);
             }
@@ -136,13 +146,13 @@ impl EventFlagReader {
     ///
     /// Returns `None` if memory read fails (game loading, etc.)
     pub fn is_flag_set(&self, flag_id: u32) -> Option<bool> {
-        let manager = self.base_ptr.read()?;
+        let manager = checked_read::read(MANAGER, || self.base_ptr.read())?;
         if manager == 0 {
             return None;
         }
 
-        // Read divisor at manager + 0x1c (typically 1000)
-        let divisor: u32 = PointerChain::<u32>::new(&[manager + 0x1c]).read()?;
+        // Read divisor (typically 1000)
+        let divisor: u32 = read_at(DIVISOR, manager + self.layout.divisor)?;
         if divisor == 0 {
             warn!("[EVENT_FLAGS] Divisor is 0");
             return None;
@@ -151,25 +161,25 @@ impl EventFlagReader {
         let category = flag_id / divisor;
         let remainder = flag_id % divisor;
 
-        // Traverse red-black tree at manager + 0x38 to find category page
+        // Traverse the category red-black tree to find the page
         let data_ptr = self.find_category_page(manager, category)?;
 
         // Read the specific bit from the category page
         let byte_offset = (remainder >> 3) as usize;
         let bit_index = 7 - (remainder & 7);
 
-        let byte_val: u8 = PointerChain::<u8>::new(&[data_ptr + byte_offset]).read()?;
+        let byte_val: u8 = read_at(PAGE, data_ptr + byte_offset)?;
         Some((byte_val & (1 << bit_index)) != 0)
     }
 
     /// Walk the red-black tree and collect category keys (for diagnostics).
     /// Returns up to `limit` categories via in-order traversal.
     pub fn dump_categories(&self, limit: usize) -> Option<Vec<u32>> {
-        let manager = self.base_ptr.read()?;
+        let manager = checked_read::read(MANAGER, || self.base_ptr.read())?;
         if manager == 0 {
             return None;
         }
-        let root: usize = PointerChain::<usize>::new(&[manager + 0x38]).read()?;
+        let root: usize = read_at(TREE, manager + self.layout.tree_root)?;
         if root == 0 {
             return Some(Vec::new());
         }
@@ -177,7 +187,7 @@ impl EventFlagReader {
         let mut categories = Vec::new();
         let mut stack: Vec<usize> = Vec::new();
         // Start from root's +0x8 child (same as find_category_page)
-        let mut current: usize = PointerChain::<usize>::new(&[root + 0x8]).read()?;
+        let mut current: usize = read_at(TREE, root + 0x8)?;
 
         // In-order traversal of the red-black tree
         for _ in 0..10000 {
@@ -186,26 +196,26 @@ impl EventFlagReader {
             }
 
             if current != 0 {
-                let sentinel: u8 = PointerChain::<u8>::new(&[current + 0x19]).read()?;
+                let sentinel: u8 = read_at(TREE, current + 0x19)?;
                 if sentinel != 0 {
                     // Sentinel node — treat as null
                     if let Some(parent) = stack.pop() {
-                        let key: u32 = PointerChain::<u32>::new(&[parent + 0x20]).read()?;
+                        let key: u32 = read_at(TREE, parent + 0x20)?;
                         categories.push(key);
-                        current = PointerChain::<usize>::new(&[parent + 0x10]).read()?;
+                        current = read_at(TREE, parent + 0x10)?;
                     } else {
                         break;
                     }
                 } else {
                     stack.push(current);
                     // Go left: node + 0x0
-                    current = PointerChain::<usize>::new(&[current]).read()?;
+                    current = read_at(TREE, current)?;
                 }
             } else if let Some(parent) = stack.pop() {
-                let key: u32 = PointerChain::<u32>::new(&[parent + 0x20]).read()?;
+                let key: u32 = read_at(TREE, parent + 0x20)?;
                 categories.push(key);
                 // Go right: node + 0x10
-                current = PointerChain::<usize>::new(&[parent + 0x10]).read()?;
+                current = read_at(TREE, parent + 0x10)?;
             } else {
                 break;
             }
@@ -225,14 +235,14 @@ impl EventFlagReader {
     /// - `+0x28`: address calculation mode (1=formula, 2=absent, >2=direct ptr)
     /// - `+0x30`: data pointer or multiplier (depends on mode)
     fn find_category_page(&self, manager: usize, category: u32) -> Option<usize> {
-        // Root node at manager + 0x38
-        let root: usize = PointerChain::<usize>::new(&[manager + 0x38]).read()?;
+        // Root node of the category tree
+        let root: usize = read_at(TREE, manager + self.layout.tree_root)?;
         if root == 0 {
             return None;
         }
 
         // Start traversal from root's +0x8 child (per SoulMemory reference)
-        let mut node: usize = PointerChain::<usize>::new(&[root + 0x8]).read()?;
+        let mut node: usize = read_at(TREE, root + 0x8)?;
         // Track the best candidate (last node where we went left, i.e., category <= node_value)
         let mut candidate: usize = root;
 
@@ -243,20 +253,20 @@ impl EventFlagReader {
             }
 
             // Check sentinel byte at node + 0x19
-            let sentinel: u8 = PointerChain::<u8>::new(&[node + 0x19]).read()?;
+            let sentinel: u8 = read_at(TREE, node + 0x19)?;
             if sentinel != 0 {
                 break;
             }
 
-            let node_value: u32 = PointerChain::<u32>::new(&[node + 0x20]).read()?;
+            let node_value: u32 = read_at(TREE, node + 0x20)?;
 
             if node_value < category {
                 // Go right: node + 0x10
-                node = PointerChain::<usize>::new(&[node + 0x10]).read()?;
+                node = read_at(TREE, node + 0x10)?;
             } else {
                 // Go left (or match): node + 0x0, record candidate
                 candidate = node;
-                node = PointerChain::<usize>::new(&[node]).read()?;
+                node = read_at(TREE, node)?;
             }
         }
 
@@ -265,7 +275,7 @@ impl EventFlagReader {
             debug!(category, "[EVENT_FLAGS] Category not found in tree");
             return None;
         }
-        let candidate_value: u32 = PointerChain::<u32>::new(&[candidate + 0x20]).read()?;
+        let candidate_value: u32 = read_at(TREE, candidate + 0x20)?;
         if category < candidate_value {
             debug!(
                 category,
@@ -275,13 +285,13 @@ impl EventFlagReader {
         }
 
         // Read address calculation mode at candidate + 0x28
-        let addr_mode: i32 = PointerChain::<i32>::new(&[candidate + 0x28]).read()?;
+        let addr_mode: i32 = read_at(TREE, candidate + 0x28)?;
         match addr_mode - 1 {
             0 => {
-                // Mode 1: formula — (manager[0x20] * node[0x30]) + manager[0x28]
-                let multiplier: i32 = PointerChain::<i32>::new(&[candidate + 0x30]).read()?;
-                let factor: i32 = PointerChain::<i32>::new(&[manager + 0x20]).read()?;
-                let base_addr: usize = PointerChain::<usize>::new(&[manager + 0x28]).read()?;
+                // Mode 1: formula — (manager[page_multiplier] * node[0x30]) + manager[page_base]
+                let multiplier: i32 = read_at(TREE, candidate + 0x30)?;
+                let factor: i32 = read_at(TREE, manager + self.layout.page_multiplier)?;
+                let base_addr: usize = read_at(TREE, manager + self.layout.page_base)?;
                 let calculated =
                     base_addr.wrapping_add((factor as i64 * multiplier as i64) as usize);
                 if calculated == 0 {
@@ -295,7 +305,7 @@ impl EventFlagReader {
             }
             _ => {
                 // Mode > 2: direct pointer at node + 0x30
-                let data_ptr: usize = PointerChain::<usize>::new(&[candidate + 0x30]).read()?;
+                let data_ptr: usize = read_at(TREE, candidate + 0x30)?;
                 if data_ptr == 0 {
                     return None;
                 }
@@ -1,96 +1,335 @@
 //! Elden Ring GameStateReader implementation
 //!
-//! Reads player position and animation state from Elden Ring memory
-//! using libeldenring pointer chains.
+//! Reads player position, animation, HP and stats from Elden Ring memory
+//! using libeldenring pointer chains. Every read goes through
+//! `checked_read` under the name of its chain.
 
+use std::sync::OnceLock;
 use std::time::Duration;
 
 use libeldenring::memedit::PointerChain;
 use libeldenring::pointers::Pointers;
+use tracing::warn;
 
-use crate::core::constants::{
-    FIELD_AREA_PLAY_REGION_ID_OFFSET, GAMEDATAMAN_DEATH_COUNT_OFFSET, INVALID_MAP_ID,
-};
+use super::checked_read;
+use super::scan;
+use super::version::{BossBarLayout, InventoryLayout, VersionSupport};
+use crate::core::character::decode_name;
+use crate::core::constants::INVALID_MAP_ID;
 use crate::core::map_utils::format_map_id;
+use crate::core::player_stats::Flasks;
+use crate::core::protocol::CharacterFingerprint;
 use crate::core::traits::GameStateReader;
 use crate::core::types::PlayerPosition;
 
+/// Goods type flag in inventory item IDs (high nibble 0x4 = EquipParamGoods)
+const GOODS_TYPE_FLAG: u32 = 0x4000_0000;
+
+/// Sanity bound on an inventory's slot count: anything above is a bad read
+const MAX_INVENTORY_SLOTS: u32 = 0xC00;
+
+/// Values read from memory during the current frame.
+///
+/// Each one walks its pointer chain on first use and is reused for the rest of
+/// the frame, so the tracker, UI and debug panel share a single read.
+/// OnceLock rather than OnceCell: the tracker must stay Sync for hudhook.
+#[derive(Default)]
+struct FrameState {
+    igt: OnceLock<Option<u32>>,
+    deaths: OnceLock<Option<u32>>,
+    position: OnceLock<Option<PlayerPosition>>,
+    level: OnceLock<Option<u32>>,
+    runes: OnceLock<Option<u32>>,
+}
+
 /// Elden Ring game state reader
 ///
 /// Uses libeldenring to read from Elden Ring's memory.
 pub struct GameState {
-    pointers: Pointers,
-    play_region_id_ptr: PointerChain<u32>,
+    /// libeldenring's pointer chains; None on versions libeldenring doesn't know
+    pointers: Option<Pointers>,
+    /// Address of the CSFD4VirtualMemoryFlag pointer (AOB-resolved on unknown versions)
+    virtual_memory_flag: usize,
+    /// None when CS::FieldArea has no known address
+    play_region_id_ptr: Option<PointerChain<u32>>,
     death_count_ptr: PointerChain<u32>,
+    igt_ptr: PointerChain<u32>,
+    clear_count_ptr: PointerChain<u32>,
+    character_level_ptr: PointerChain<u32>,
+    character_name_ptr: PointerChain<[u16; 16]>,
+    character_class_ptr: PointerChain<u8>,
+    hp_ptr: PointerChain<u32>,
+    runes_ptr: PointerChain<u32>,
+    /// None when GameMan has no known address
+    save_slot_ptr: Option<PointerChain<u32>>,
+    /// Address of the CSFeManImp pointer; None when its signature wasn't found
+    fe_man: Option<usize>,
+    boss_bars: BossBarLayout,
+    key_item_entries_ptr: PointerChain<usize>,
+    key_item_capacity_ptr: PointerChain<u32>,
+    key_items: InventoryLayout,
+    normal_item_entries_ptr: PointerChain<usize>,
+    normal_item_capacity_ptr: PointerChain<u32>,
+    normal_items: InventoryLayout,
+    frame_state: FrameState,
 }
 
 impl GameState {
-    /// Create a new GameState reader
-    pub fn new() -> Self {
-        let pointers = Pointers::new();
-
-        // Create pointer chain for PlayRegionId (FieldArea + 0xE4)
-        let play_region_id_ptr = PointerChain::<u32>::new(&[
-            pointers.base_addresses.field_area,
-            FIELD_AREA_PLAY_REGION_ID_OFFSET,
+    /// Create a new GameState reader using the offsets for the detected game version
+    pub fn new(support: &VersionSupport) -> Self {
+        // libeldenring only builds its pointers for the versions it ships addresses for
+        let pointers = if libeldenring::version::check_version().is_ok() {
+            Some(Pointers::new())
+        } else {
+            warn!(
+                "[VERSION] Unknown to libeldenring, position, animation and fast travel disabled"
+            );
+            None
+        };
+        let base = pointers.as_ref().map(|p| &p.base_addresses);
+        let offsets = &support.offsets;
+
+        // Unknown version: don't trust the static addresses for the globals we own
+        let (game_data_man, virtual_memory_flag) = match base {
+            Some(base) if support.known => (base.game_data_man, base.csfd4_virtual_memory_flag),
+            _ => (
+                scan::find_game_data_man()
+                    .or(base.map(|b| b.game_data_man))
+                    .unwrap_or(0),
+                scan::find_virtual_memory_flag()
+                    .or(base.map(|b| b.csfd4_virtual_memory_flag))
+                    .unwrap_or(0),
+            ),
+        };
+
+        // Create pointer chain for PlayRegionId (FieldArea + offset)
+        let play_region_id_ptr = base
+            .map(|b| PointerChain::<u32>::new(&[b.field_area, offsets.field_area_play_region_id]));
+
+        // Create pointer chain for death count (GameDataMan + offset)
+        let death_count_ptr =
+            PointerChain::<u32>::new(&[game_data_man, offsets.game_data_man_death_count]);
+
+        // Create pointer chain for the IGT (GameDataMan + offset)
+        let igt_ptr = PointerChain::<u32>::new(&[game_data_man, offsets.game_data_man_igt]);
+
+        // Create pointer chain for clear count / NG+ cycle (GameDataMan + offset)
+        let clear_count_ptr =
+            PointerChain::<u32>::new(&[game_data_man, offsets.game_data_man_clear_count]);
+
+        // Character fields (GameDataMan -> PlayerGameData + offset)
+        let player_game_data = offsets.game_data_man_player_game_data;
+        let character_level_ptr =
+            PointerChain::<u32>::new(&[game_data_man, player_game_data, offsets.player_level]);
+        let character_name_ptr =
+            PointerChain::<[u16; 16]>::new(&[game_data_man, player_game_data, offsets.player_name]);
+        let character_class_ptr =
+            PointerChain::<u8>::new(&[game_data_man, player_game_data, offsets.player_class]);
+        let hp_ptr =
+            PointerChain::<u32>::new(&[game_data_man, player_game_data, offsets.player_hp]);
+        let runes_ptr =
+            PointerChain::<u32>::new(&[game_data_man, player_game_data, offsets.player_runes]);
+
+        // Key item inventory (GameDataMan -> PlayerGameData -> entries)
+        let key_item_entries_ptr = PointerChain::<usize>::new(&[
+            game_data_man,
+            player_game_data,
+            offsets.key_items.entries,
+        ]);
+        let key_item_capacity_ptr = PointerChain::<u32>::new(&[
+            game_data_man,
+            player_game_data,
+            offsets.key_items.capacity,
         ]);
 
-        // Create pointer chain for death count (GameDataMan + 0x94)
-        let death_count_ptr = PointerChain::<u32>::new(&[
-            pointers.base_addresses.game_data_man,
-            GAMEDATAMAN_DEATH_COUNT_OFFSET,
+        // Normal item inventory, for the flasks (same layout)
+        let normal_item_entries_ptr = PointerChain::<usize>::new(&[
+            game_data_man,
+            player_game_data,
+            offsets.normal_items.entries,
         ]);
+        let normal_item_capacity_ptr = PointerChain::<u32>::new(&[
+            game_data_man,
+            player_game_data,
+            offsets.normal_items.capacity,
+        ]);
+
+        // Active save slot (GameMan + offset)
+        let save_slot_ptr =
+            base.map(|b| PointerChain::<u32>::new(&[b.game_man, offsets.game_man_save_slot]));
 
         Self {
             pointers,
+            virtual_memory_flag,
             play_region_id_ptr,
             death_count_ptr,
+            igt_ptr,
+            clear_count_ptr,
+            character_level_ptr,
+            character_name_ptr,
+            character_class_ptr,
+            hp_ptr,
+            runes_ptr,
+            save_slot_ptr,
+            // Not in libeldenring's base addresses on any version
+            fe_man: scan::find_fe_man(),
+            boss_bars: offsets.boss_bars,
+            key_item_entries_ptr,
+            key_item_capacity_ptr,
+            key_items: offsets.key_items,
+            normal_item_entries_ptr,
+            normal_item_capacity_ptr,
+            normal_items: offsets.normal_items,
+            frame_state: FrameState::default(),
         }
     }
 
-    /// Get base addresses (for creating EventFlagReader)
-    pub fn base_addresses(&self) -> &libeldenring::prelude::base_addresses::BaseAddresses {
-        &self.pointers.base_addresses
+    /// Start a new frame: drop the values cached during the previous one
+    pub fn begin_frame(&mut self) {
+        self.frame_state = FrameState::default();
+    }
+
+    /// Get base addresses (for creating the warp hook); None on versions
+    /// libeldenring doesn't know
+    pub fn base_addresses(&self) -> Option<&libeldenring::prelude::base_addresses::BaseAddresses> {
+        self.pointers.as_ref().map(|p| &p.base_addresses)
     }
 
-    /// Read the death count from game memory
+    /// Address of the CSFD4VirtualMemoryFlag pointer (for creating EventFlagReader)
+    pub fn virtual_memory_flag(&self) -> usize {
+        self.virtual_memory_flag
+    }
+
+    /// Read the death count from game memory (cached for the frame)
     ///
     /// Returns the total number of deaths for the current character.
     pub fn read_deaths(&self) -> Option<u32> {
-        self.death_count_ptr.read()
+        *self
+            .frame_state
+            .deaths
+            .get_or_init(|| checked_read::read("deaths", || self.death_count_ptr.read()))
     }
 
-    /// Read the in-game time from game memory
+    /// Read the in-game time from game memory (cached for the frame)
     ///
     /// Returns the IGT in milliseconds.
     pub fn read_igt(&self) -> Option<u32> {
-        // libeldenring reads IGT as usize but it's actually a u32 in milliseconds
-        self.pointers.igt.read().map(|v| v as u32)
+        *self
+            .frame_state
+            .igt
+            .get_or_init(|| checked_read::read("igt", || self.igt_ptr.read()))
     }
-}
 
-impl Default for GameState {
-    fn default() -> Self {
-        Self::new()
+    /// Read the clear count from game memory (not cached, polled slowly)
+    ///
+    /// Returns the number of completed playthroughs (0 = NG, 1 = NG+, ...).
+    pub fn read_clear_count(&self) -> Option<u32> {
+        checked_read::read("clear_count", || self.clear_count_ptr.read())
     }
-}
 
-impl GameStateReader for GameState {
-    fn wait_for_game_loaded(&self) {
-        let poll_interval = Duration::from_millis(100);
-        loop {
-            if let Some(menu_timer) = self.pointers.menu_timer.read() {
-                if menu_timer > 0. {
-                    break;
+    /// Read the player's current HP (not cached, read by the death tracking only)
+    pub fn read_hp(&self) -> Option<u32> {
+        checked_read::read("hp", || self.hp_ptr.read())
+    }
+
+    /// Whether a boss health bar is on screen (not cached, read by the death
+    /// tracking and the boss fight overlay mode). None when CSFeManImp
+    /// couldn't be located or read.
+    pub fn read_boss_bar(&self) -> Option<bool> {
+        let fe_man: usize = checked_read::read_at("fe_man", self.fe_man?)?;
+        if fe_man == 0 {
+            return None;
+        }
+        let layout = &self.boss_bars;
+        let mut shown = false;
+        for slot in 0..layout.count {
+            let entry = fe_man + layout.first + slot * layout.stride;
+            shown |= checked_read::read_at::<u64>("fe_man.boss_bars", entry)? != u64::MAX;
+        }
+        Some(shown)
+    }
+
+    /// Quantity of a key item (goods ID) in the inventory, 0 when absent
+    /// (not cached, polled slowly). None on the title screen and during loads.
+    pub fn read_key_item_count(&self, goods_id: u32) -> Option<u32> {
+        let entries: usize = checked_read::read("key_items", || self.key_item_entries_ptr.read())?;
+        let capacity =
+            checked_read::read("key_items.capacity", || self.key_item_capacity_ptr.read())?;
+        let mut count = 0;
+        scan_goods(
+            "key_items.entry",
+            &self.key_items,
+            entries,
+            capacity,
+            |id, quantity| {
+                if id == goods_id {
+                    count += quantity;
                 }
-            }
-            std::thread::sleep(poll_interval);
+            },
+        )?;
+        Some(count)
+    }
+
+    /// Read the rune level (cached for the frame)
+    pub fn read_level(&self) -> Option<u32> {
+        *self.frame_state.level.get_or_init(|| {
+            checked_read::read("character.level", || self.character_level_ptr.read())
+        })
+    }
+
+    /// Read the runes held (cached for the frame)
+    pub fn read_runes(&self) -> Option<u32> {
+        *self
+            .frame_state
+            .runes
+            .get_or_init(|| checked_read::read("runes", || self.runes_ptr.read()))
+    }
+
+    /// Read the flask charges left (not cached: scans the inventory, polled
+    /// slowly). None on the title screen and during loads.
+    pub fn read_flasks(&self) -> Option<Flasks> {
+        let entries: usize =
+            checked_read::read("normal_items", || self.normal_item_entries_ptr.read())?;
+        let capacity = checked_read::read("normal_items.capacity", || {
+            self.normal_item_capacity_ptr.read()
+        })?;
+        let mut flasks = Flasks::default();
+        scan_goods(
+            "normal_items.entry",
+            &self.normal_items,
+            entries,
+            capacity,
+            |id, quantity| flasks.add(id, quantity),
+        )?;
+        Some(flasks)
+    }
+
+    /// Read the loaded character's fingerprint (not cached, polled slowly)
+    ///
+    /// Returns None on the title screen, when no character is loaded yet.
+    pub fn read_character(&self) -> Option<CharacterFingerprint> {
+        let name = decode_name(&checked_read::read("character.name", || {
+            self.character_name_ptr.read()
+        })?);
+        let level = self.read_level()?;
+        if name.is_empty() || level == 0 {
+            return None;
         }
+        Some(CharacterFingerprint {
+            slot: self
+                .save_slot_ptr
+                .as_ref()
+                .and_then(|ptr| checked_read::read("save_slot", || ptr.read())),
+            name,
+            level,
+            class_id: checked_read::read("character.class", || self.character_class_ptr.read())?,
+        })
     }
 
-    fn read_position(&self) -> Option<PlayerPosition> {
-        let [x, y, z, _, _] = self.pointers.global_position.read()?;
-        let map_id = self.pointers.global_position.read_map_id()?;
+    fn read_position_uncached(&self) -> Option<PlayerPosition> {
+        let position = &self.pointers.as_ref()?.global_position;
+        let [x, y, z, _, _] = checked_read::read("position", || position.read())?;
+        let map_id = checked_read::read("position.map_id", || position.read_map_id())?;
 
         // Check if position is valid (not during loading screen)
         if map_id == INVALID_MAP_ID || (x == 0.0 && y == 0.0 && z == 0.0) {
@@ -103,11 +342,65 @@ impl GameStateReader for GameState {
             x,
             y,
             z,
-            play_region_id: self.play_region_id_ptr.read(),
+            play_region_id: self
+                .play_region_id_ptr
+                .as_ref()
+                .and_then(|ptr| checked_read::read("play_region", || ptr.read())),
         })
     }
+}
+
+/// Visit the goods of an inventory with their quantity. None when the
+/// inventory isn't there (title screen, loads) or can't be read.
+fn scan_goods(
+    chain: &'static str,
+    layout: &InventoryLayout,
+    entries: usize,
+    capacity: u32,
+    mut visit: impl FnMut(u32, u32),
+) -> Option<()> {
+    if entries == 0 || capacity > MAX_INVENTORY_SLOTS {
+        return None;
+    }
+    for slot in 0..capacity as usize {
+        let entry = entries + slot * layout.stride;
+        let id: u32 = checked_read::read_at(chain, entry + layout.item_id)?;
+        if id & 0xF000_0000 == GOODS_TYPE_FLAG {
+            let quantity: u32 = checked_read::read_at(chain, entry + layout.quantity)?;
+            visit(id & !GOODS_TYPE_FLAG, quantity);
+        }
+    }
+    Some(())
+}
+
+impl GameStateReader for GameState {
+    fn wait_for_game_loaded(&self) {
+        let poll_interval = Duration::from_millis(100);
+        loop {
+            // Without libeldenring's menu timer, the IGT starts once a character is loaded
+            let loaded = match &self.pointers {
+                Some(pointers) => checked_read::read("menu_timer", || pointers.menu_timer.read())
+                    .is_some_and(|menu_timer| menu_timer > 0.),
+                None => {
+                    checked_read::read("igt", || self.igt_ptr.read()).is_some_and(|igt| igt > 0)
+                }
+            };
+            if loaded {
+                break;
+            }
+            std::thread::sleep(poll_interval);
+        }
+    }
+
+    fn read_position(&self) -> Option<PlayerPosition> {
+        self.frame_state
+            .position
+            .get_or_init(|| self.read_position_uncached())
+            .clone()
+    }
 
     fn read_animation(&self) -> Option<u32> {
-        self.pointers.cur_anim.read()
+        let pointers = self.pointers.as_ref()?;
+        checked_read::read("animation", || pointers.cur_anim.read())
     }
 }
@@ -0,0 +1,125 @@
+//! Fog gate scanner
+//!
+//! Lists the randomizer's fog gates among the map assets of the blocks the
+//! game currently has loaded (CSWorldGeomMan), for the proximity hint in
+//! `core::gate_proximity`. Only IDs and positions are read: nothing tells
+//! where a gate leads.
+//!
+//! Walks game-owned vectors through safe pointer reads, bounded so a stale
+//! pointer during a loading screen can't send it into a long loop.
+
+use libeldenring::memedit::PointerChain;
+use tracing::{info, warn};
+
+use super::checked_read::{self, read_at};
+use super::scan;
+use super::version::GeomLayout;
+use crate::core::gate_proximity::{is_fog_gate_entity, GateCandidate};
+
+/// Blocks loaded at once stay in the dozens (overworld tiles around the player)
+const MAX_BLOCKS: usize = 128;
+
+/// Asset instances read per block
+const MAX_GEOMS_PER_BLOCK: usize = 8192;
+
+/// Pointer chains of the reads, as named in the read audit
+const MANAGER: &str = "geom.manager";
+const BLOCKS: &str = "geom.blocks";
+const GEOMS: &str = "geom.assets";
+
+pub struct GateScanner {
+    /// Pointer to the CSWorldGeomMan instance
+    manager_ptr: PointerChain<usize>,
+    layout: GeomLayout,
+}
+
+impl GateScanner {
+    /// Locate CSWorldGeomMan by signature. None when it can't be found (the
+    /// hint is then unavailable for this session).
+    pub fn locate(layout: GeomLayout) -> Option<Self> {
+        let Some(world_geom_man) = scan::find_world_geom_man() else {
+            warn!("[SCAN] CSWorldGeomMan not found, fog gate proximity disabled");
+            return None;
+        };
+        info!(
+            addr = format_args!("0x{:x}", world_geom_man),
+            "[SCAN] Fog gate scanner ready"
+        );
+        Some(Self {
+            manager_ptr: PointerChain::<usize>::new(&[world_geom_man]),
+            layout,
+        })
+    }
+
+    /// Fog gates of every loaded block. Empty while the manager is not
+    /// readable (title screen, loading).
+    pub fn scan(&self) -> Vec<GateCandidate> {
+        let mut gates = Vec::new();
+        let Some(manager) =
+            checked_read::read(MANAGER, || self.manager_ptr.read()).filter(|&m| m != 0)
+        else {
+            return gates;
+        };
+        let blocks = read_pointer_vector(
+            BLOCKS,
+            manager + self.layout.blocks_begin,
+            manager + self.layout.blocks_end,
+            MAX_BLOCKS,
+        );
+        for block in blocks {
+            self.scan_block(block, &mut gates);
+        }
+        gates
+    }
+
+    fn scan_block(&self, block: usize, gates: &mut Vec<GateCandidate>) {
+        let layout = &self.layout;
+        let Some(map_id) = read_at(BLOCKS, block + layout.block_map_id) else {
+            return;
+        };
+        let geoms = read_pointer_vector(
+            GEOMS,
+            block + layout.geoms_begin,
+            block + layout.geoms_end,
+            MAX_GEOMS_PER_BLOCK,
+        );
+        for geom in geoms {
+            let Some(entity_id) = read_at::<u32>(GEOMS, geom + layout.geom_entity_id) else {
+                continue;
+            };
+            if !is_fog_gate_entity(entity_id) {
+                continue;
+            }
+            if let Some(position) = read_at(GEOMS, geom + layout.geom_position) {
+                gates.push(GateCandidate {
+                    entity_id,
+                    map_id,
+                    position,
+                });
+            }
+        }
+    }
+}
+
+/// Non-null elements of an MSVC `std::vector<T*>` given the addresses of its
+/// begin and end pointers, at most `limit`
+fn read_pointer_vector(
+    chain: &'static str,
+    begin_addr: usize,
+    end_addr: usize,
+    limit: usize,
+) -> Vec<usize> {
+    let read = |addr: usize| read_at::<usize>(chain, addr);
+    let (Some(begin), Some(end)) = (read(begin_addr), read(end_addr)) else {
+        return Vec::new();
+    };
+    if begin == 0 || end <= begin {
+        return Vec::new();
+    }
+    let stride = std::mem::size_of::<usize>();
+    let count = ((end - begin) / stride).min(limit);
+    (0..count)
+        .filter_map(|i| read(begin + i * stride))
+        .filter(|&p| p != 0)
+        .collect()
+}
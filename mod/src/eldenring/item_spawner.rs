@@ -18,7 +18,8 @@ use libeldenring::pointers::Pointers;
 use tracing::{error, info, warn};
 
 use crate::core::protocol::SpawnItem;
-use crate::eldenring::EventFlagReader;
+use crate::core::supervisor::WorkerHealth;
+use crate::eldenring::{checked_read, EventFlagReader};
 
 /// Gem type flag in item ID encoding (high nibble 0x8 = EquipParamGem)
 const GEM_TYPE_FLAG: u32 = 0x8000_0000;
@@ -40,6 +41,13 @@ struct SpawnRequest {
 /// func_item_inject signature: (MapItemMan*, SpawnRequest*, output*, flags)
 type SpawnItemFn = unsafe extern "system" fn(*const c_void, *mut SpawnRequest, *mut u32, u32);
 
+/// MapItemMan instance behind its static pointer, None while null or unreadable
+fn read_map_item_man(static_addr: usize) -> Option<*const c_void> {
+    checked_read::read_at::<usize>("map_item_man", static_addr)
+        .filter(|&p| p != 0)
+        .map(|p| p as *const c_void)
+}
+
 /// Spawn items received from auth_ok. **Blocks** until the game is fully loaded.
 ///
 /// Call this from a dedicated thread — it polls MapItemMan every 500ms until
@@ -47,14 +55,23 @@ type SpawnItemFn = unsafe extern "system" fn(*const c_void, *mut SpawnRequest, *
 /// for each item.
 ///
 /// Uses event flag `ITEMS_SPAWNED_FLAG` to prevent re-giving items on
-/// reconnect or game restart (flag persists in save file).
-pub fn spawn_items_blocking(items: Vec<SpawnItem>, flag_reader: &EventFlagReader) {
+/// reconnect or game restart (flag persists in save file). `health` is beaten
+/// while waiting so the supervisor doesn't report the thread as stalled.
+pub fn spawn_items_blocking(
+    items: Vec<SpawnItem>,
+    flag_reader: &EventFlagReader,
+    health: &WorkerHealth,
+) {
     if items.is_empty() {
         return;
     }
 
     info!(count = items.len(), "Waiting to spawn items...");
 
+    if libeldenring::version::check_version().is_err() {
+        error!("func_item_inject not available for this game version");
+        return;
+    }
     let pointers = Pointers::new();
     let base = &pointers.base_addresses;
 
@@ -68,12 +85,11 @@ pub fn spawn_items_blocking(items: Vec<SpawnItem>, flag_reader: &EventFlagReader
     // No timeout — the player may stay on the title screen or character creation
     // for an arbitrarily long time before loading in (e.g. race lobby).
     // The thread is lightweight (sleeps 500ms) and bounded by the game process.
-    let pp = base.map_item_man as *const *const c_void;
     let wait_start = std::time::Instant::now();
     let mut last_log = std::time::Instant::now();
     loop {
-        let p = unsafe { pp.read() };
-        if !p.is_null() {
+        health.beat();
+        if read_map_item_man(base.map_item_man).is_some() {
             break;
         }
         if last_log.elapsed() > Duration::from_secs(60) {
@@ -106,11 +122,10 @@ pub fn spawn_items_blocking(items: Vec<SpawnItem>, flag_reader: &EventFlagReader
         }
     }
 
-    let p_map_item_man = unsafe { pp.read() };
-    if p_map_item_man.is_null() {
+    let Some(p_map_item_man) = read_map_item_man(base.map_item_man) else {
         error!("MapItemMan became null after delay");
         return;
-    }
+    };
 
     let spawn_fn: SpawnItemFn = unsafe { std::mem::transmute(func_addr) };
 
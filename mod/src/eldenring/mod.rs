@@ -5,9 +5,14 @@
 //!
 //! The implementations here satisfy the traits defined in `core::traits`.
 
+pub mod checked_read;
+pub mod environment;
 mod event_flags;
 mod game_state;
+pub mod geom_scan;
 pub mod item_spawner;
+mod scan;
+pub mod version;
 pub mod warp_hook;
 
 pub use event_flags::{EventFlagReader, FlagReaderStatus};
@@ -0,0 +1,129 @@
+//! AOB scanning of the game executable
+//!
+//! Fallback for game versions missing from the offset table: locates the
+//! globals this mod reads directly by signature instead of fixed addresses.
+//! Patterns follow SoulSplitter's Elden Ring signatures.
+
+use std::ffi::c_void;
+
+use tracing::{debug, info, warn};
+use windows::core::PCWSTR;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Memory::{
+    VirtualQuery, MEMORY_BASIC_INFORMATION, MEM_COMMIT, PAGE_GUARD, PAGE_NOACCESS,
+};
+
+use crate::core::aob::{find_pattern, parse_pattern, rip_relative_target};
+
+/// `mov rdi, [CSFD4VirtualMemoryFlag]` followed by a null check
+const VIRTUAL_MEMORY_FLAG_PATTERN: &str = "48 8b 3d ?? ?? ?? ?? 48 85 ff ?? ?? 32 c0 e9";
+
+/// `mov rax, [GameDataMan]` in a small getter
+const GAME_DATA_MAN_PATTERN: &str = "48 8b 05 ?? ?? ?? ?? 48 85 c0 74 05 48 8b 40 58 c3 c3";
+
+/// `mov rcx, [CSWorldGeomMan]` followed by a null check
+const WORLD_GEOM_MAN_PATTERN: &str = "48 8b 0d ?? ?? ?? ?? 48 85 c9 74 ?? 48 8b 49 08 e8";
+
+/// `mov rcx, [CSFeManImp]` before a HUD update call
+const FE_MAN_PATTERN: &str = "48 8b 0d ?? ?? ?? ?? 8b da 48 85 c9 75 ?? 48 8d 0d";
+
+/// The global signatures are `mov r64, [rip+disp32]`: displacement at +3, 7-byte instruction
+const MOV_RIP_DISP_OFFSET: usize = 3;
+const MOV_RIP_LEN: usize = 7;
+
+/// Size of the PE image loaded at `base`, read from its optional header.
+///
+/// # Safety
+/// `base` must be the base address of a mapped PE image.
+unsafe fn image_size(base: usize) -> Option<usize> {
+    let e_lfanew = *((base + 0x3C) as *const u32) as usize;
+    let nt = base + e_lfanew;
+    if *(nt as *const u32) != 0x0000_4550 {
+        // "PE\0\0"
+        return None;
+    }
+    // IMAGE_NT_HEADERS64: Signature (4) + FileHeader (20) + OptionalHeader.SizeOfImage at +56
+    Some(*((nt + 4 + 20 + 56) as *const u32) as usize)
+}
+
+/// Scan the readable committed regions of the main module for `pattern`.
+/// Returns the absolute address of the first match.
+fn scan_main_module(pattern: &str) -> Option<usize> {
+    let pattern = parse_pattern(pattern)?;
+
+    // SAFETY: a null module name designates the host executable, which stays mapped.
+    let base = unsafe { GetModuleHandleW(PCWSTR::null()) }.ok()?.0 as usize;
+    let end = base + unsafe { image_size(base) }?;
+
+    let mut addr = base;
+    while addr < end {
+        let mut mbi = MEMORY_BASIC_INFORMATION::default();
+        // SAFETY: VirtualQuery only inspects the address space, it never dereferences.
+        let written = unsafe {
+            VirtualQuery(
+                Some(addr as *const c_void),
+                &mut mbi,
+                std::mem::size_of::<MEMORY_BASIC_INFORMATION>(),
+            )
+        };
+        if written == 0 || mbi.RegionSize == 0 {
+            break;
+        }
+        let region_start = mbi.BaseAddress as usize;
+        let region_end = (region_start + mbi.RegionSize).min(end);
+
+        let readable =
+            mbi.State == MEM_COMMIT && mbi.Protect.0 & (PAGE_NOACCESS.0 | PAGE_GUARD.0) == 0;
+        if readable && region_end > addr {
+            // SAFETY: the region is committed and not guarded/no-access.
+            let bytes = unsafe { std::slice::from_raw_parts(addr as *const u8, region_end - addr) };
+            if let Some(offset) = find_pattern(bytes, &pattern) {
+                return Some(addr + offset);
+            }
+        }
+        addr = region_end;
+    }
+    None
+}
+
+/// Find a RIP-relative global referenced by the instruction matched by `pattern`.
+fn scan_global(name: &str, pattern: &str) -> Option<usize> {
+    let Some(instr_addr) = scan_main_module(pattern) else {
+        warn!(name, "[SCAN] Signature not found");
+        return None;
+    };
+    // SAFETY: the match lies inside a readable region and is at least MOV_RIP_LEN bytes long.
+    let instr = unsafe { std::slice::from_raw_parts(instr_addr as *const u8, MOV_RIP_LEN) };
+    let target = rip_relative_target(instr_addr, instr, MOV_RIP_DISP_OFFSET, MOV_RIP_LEN)?;
+    debug!(
+        name,
+        instr = format_args!("0x{:x}", instr_addr),
+        "[SCAN] Signature matched"
+    );
+    info!(
+        name,
+        addr = format_args!("0x{:x}", target),
+        "[SCAN] Resolved global"
+    );
+    Some(target)
+}
+
+/// Address of the static CSFD4VirtualMemoryFlag pointer (event flag manager)
+pub fn find_virtual_memory_flag() -> Option<usize> {
+    scan_global("CSFD4VirtualMemoryFlag", VIRTUAL_MEMORY_FLAG_PATTERN)
+}
+
+/// Address of the static GameDataMan pointer
+pub fn find_game_data_man() -> Option<usize> {
+    scan_global("GameDataMan", GAME_DATA_MAN_PATTERN)
+}
+
+/// Address of the static CSWorldGeomMan pointer (map assets)
+pub fn find_world_geom_man() -> Option<usize> {
+    scan_global("CSWorldGeomMan", WORLD_GEOM_MAN_PATTERN)
+}
+
+/// Address of the static CSFeManImp pointer (HUD, boss health bars)
+pub fn find_fe_man() -> Option<usize> {
+    scan_global("CSFeManImp", FE_MAN_PATTERN)
+}
@@ -0,0 +1,347 @@
+//! Game version detection and per-version memory offsets
+//!
+//! libeldenring resolves the global base addresses. The struct field offsets we
+//! read on top of them (FieldArea, GameDataMan, GameMan, CSFD4VirtualMemoryFlag, CSFeMan,
+//! the inventories) live in `OFFSET_TABLE`, so supporting a new game patch
+//! is a matter of adding an entry.
+//!
+//! When the running version is not in the table, the latest layout is assumed and
+//! the globals this mod owns (flag manager, GameDataMan) are located by AOB
+//! scanning instead of trusting libeldenring's addresses. If libeldenring
+//! doesn't know the version either, its pointer chains (position, animation,
+//! menu timer) and the fast travel hook are unavailable and the mod runs on the
+//! scanned globals alone.
+
+use std::ffi::c_void;
+use std::fmt;
+
+use tracing::{info, warn};
+use windows::core::{w, PCWSTR};
+use windows::Win32::Foundation::HMODULE;
+use windows::Win32::Storage::FileSystem::{
+    GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW, VS_FIXEDFILEINFO,
+};
+use windows::Win32::System::LibraryLoader::GetModuleFileNameW;
+
+use crate::core::constants::{
+    FIELD_AREA_PLAY_REGION_ID_OFFSET, GAMEDATAMAN_CLEAR_COUNT_OFFSET,
+    GAMEDATAMAN_DEATH_COUNT_OFFSET, GAMEDATAMAN_IGT_OFFSET, GAMEDATAMAN_PLAYER_GAME_DATA_OFFSET,
+    GAMEMAN_SAVE_SLOT_OFFSET, PLAYER_GAME_DATA_CLASS_OFFSET, PLAYER_GAME_DATA_HP_OFFSET,
+    PLAYER_GAME_DATA_LEVEL_OFFSET, PLAYER_GAME_DATA_NAME_OFFSET, PLAYER_GAME_DATA_RUNES_OFFSET,
+};
+
+/// eldenring.exe product version (e.g. 2.6.0 for game patch 1.16)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GameVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl GameVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl fmt::Display for GameVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:02}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Offsets inside CSFD4VirtualMemoryFlag (the event flag manager)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlagManagerLayout {
+    /// u32 flags-per-category divisor (typically 1000)
+    pub divisor: usize,
+    /// i32 page size factor used by address mode 1
+    pub page_multiplier: usize,
+    /// Base pointer of the page pool used by address mode 1
+    pub page_base: usize,
+    /// Root of the category red-black tree
+    pub tree_root: usize,
+}
+
+/// Offsets inside CSWorldGeomMan (map assets of the loaded blocks)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeomLayout {
+    /// Begin/end pointers of the vector of loaded block data pointers
+    pub blocks_begin: usize,
+    pub blocks_end: usize,
+    /// Map id (u32, same encoding as the player's map id) within a block
+    pub block_map_id: usize,
+    /// Begin/end pointers of the block's asset instance pointers
+    pub geoms_begin: usize,
+    pub geoms_end: usize,
+    /// Entity ID (u32) within an asset instance
+    pub geom_entity_id: usize,
+    /// Position (3 x f32, block-local) within an asset instance
+    pub geom_position: usize,
+}
+
+/// Boss health bars inside CSFeManImp (HUD)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BossBarLayout {
+    /// First bar entry; each starts with the boss's character handle (u64),
+    /// -1 while the slot is empty
+    pub first: usize,
+    pub stride: usize,
+    pub count: usize,
+}
+
+/// An item inventory inside PlayerGameData (EquipInventoryData): key items,
+/// or the normal items holding the flasks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InventoryLayout {
+    /// Pointer to the key item entries
+    pub entries: usize,
+    /// Number of entries (u32), empty ones included
+    pub capacity: usize,
+    pub stride: usize,
+    /// Item ID (u32, category in the high nibble) within an entry
+    pub item_id: usize,
+    /// Quantity (u32) within an entry
+    pub quantity: usize,
+}
+
+/// Struct field offsets read on top of libeldenring's base addresses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameOffsets {
+    /// PlayRegionId within CS::FieldArea
+    pub field_area_play_region_id: usize,
+    /// Death count within GameDataMan
+    pub game_data_man_death_count: usize,
+    /// In-game time within GameDataMan
+    pub game_data_man_igt: usize,
+    /// Clear count (NG+ cycle) within GameDataMan
+    pub game_data_man_clear_count: usize,
+    /// PlayerGameData pointer within GameDataMan
+    pub game_data_man_player_game_data: usize,
+    /// Character fields within PlayerGameData
+    pub player_level: usize,
+    pub player_name: usize,
+    pub player_class: usize,
+    pub player_hp: usize,
+    pub player_runes: usize,
+    /// Active save slot within GameMan
+    pub game_man_save_slot: usize,
+    pub flag_manager: FlagManagerLayout,
+    pub world_geom: GeomLayout,
+    pub boss_bars: BossBarLayout,
+    pub key_items: InventoryLayout,
+    pub normal_items: InventoryLayout,
+}
+
+/// Layout shared by every patch supported by the pinned libeldenring.
+const LAYOUT_BASE: GameOffsets = GameOffsets {
+    field_area_play_region_id: FIELD_AREA_PLAY_REGION_ID_OFFSET,
+    game_data_man_death_count: GAMEDATAMAN_DEATH_COUNT_OFFSET,
+    game_data_man_igt: GAMEDATAMAN_IGT_OFFSET,
+    game_data_man_clear_count: GAMEDATAMAN_CLEAR_COUNT_OFFSET,
+    game_data_man_player_game_data: GAMEDATAMAN_PLAYER_GAME_DATA_OFFSET,
+    player_level: PLAYER_GAME_DATA_LEVEL_OFFSET,
+    player_name: PLAYER_GAME_DATA_NAME_OFFSET,
+    player_class: PLAYER_GAME_DATA_CLASS_OFFSET,
+    player_hp: PLAYER_GAME_DATA_HP_OFFSET,
+    player_runes: PLAYER_GAME_DATA_RUNES_OFFSET,
+    game_man_save_slot: GAMEMAN_SAVE_SLOT_OFFSET,
+    flag_manager: FlagManagerLayout {
+        divisor: 0x1c,
+        page_multiplier: 0x20,
+        page_base: 0x28,
+        tree_root: 0x38,
+    },
+    world_geom: GeomLayout {
+        blocks_begin: 0x18,
+        blocks_end: 0x20,
+        block_map_id: 0x8,
+        geoms_begin: 0x288,
+        geoms_end: 0x290,
+        geom_entity_id: 0x28,
+        geom_position: 0x70,
+    },
+    boss_bars: BossBarLayout {
+        first: 0x59F0,
+        stride: 0x20,
+        count: 3,
+    },
+    key_items: InventoryLayout {
+        entries: 0x5F0,
+        capacity: 0x5E8,
+        stride: 0x18,
+        item_id: 0x4,
+        quantity: 0x8,
+    },
+    // Not checked against a game build yet: assumed to sit just before the
+    // key items, with the same entries, on every exe up to 2.6.1. Wrong
+    // offsets only make `{flasks}` wrong or empty.
+    normal_items: InventoryLayout {
+        entries: 0x5C8,
+        capacity: 0x5C0,
+        stride: 0x18,
+        item_id: 0x4,
+        quantity: 0x8,
+    },
+};
+
+/// A range of exe versions (inclusive) sharing the same offsets
+struct VersionEntry {
+    min: GameVersion,
+    max: GameVersion,
+    offsets: GameOffsets,
+}
+
+/// Version → offset table, oldest first. Add an entry when a patch moves a field.
+const OFFSET_TABLE: &[VersionEntry] = &[VersionEntry {
+    min: GameVersion::new(1, 2, 0),
+    max: GameVersion::new(2, 6, 1),
+    offsets: LAYOUT_BASE,
+}];
+
+/// Result of version detection
+#[derive(Debug, Clone, Copy)]
+pub struct VersionSupport {
+    /// None if the exe version resource couldn't be read
+    pub version: Option<GameVersion>,
+    pub offsets: GameOffsets,
+    /// False when falling back to the latest layout + AOB scanning
+    pub known: bool,
+}
+
+/// Look up the offsets for a version; unknown versions get the latest layout.
+pub fn offsets_for(version: Option<GameVersion>) -> VersionSupport {
+    let entry = version.and_then(|v| OFFSET_TABLE.iter().find(|e| e.min <= v && v <= e.max));
+    match entry {
+        Some(e) => VersionSupport {
+            version,
+            offsets: e.offsets,
+            known: true,
+        },
+        None => VersionSupport {
+            version,
+            offsets: OFFSET_TABLE[OFFSET_TABLE.len() - 1].offsets,
+            known: false,
+        },
+    }
+}
+
+/// Detect the running game version and pick its offsets.
+pub fn detect() -> VersionSupport {
+    let version = read_exe_version();
+    let support = offsets_for(version);
+    match (support.version, support.known) {
+        (Some(v), true) => info!(version = %v, "[VERSION] Game version supported"),
+        (Some(v), false) => warn!(
+            version = %v,
+            "[VERSION] Unknown game version, assuming latest layout (AOB fallback)"
+        ),
+        (None, _) => warn!("[VERSION] Could not read game version, assuming latest layout"),
+    }
+    support
+}
+
+/// Read the product version of the host executable (eldenring.exe).
+fn read_exe_version() -> Option<GameVersion> {
+    let mut path = [0u16; 260];
+    // SAFETY: a null module handle designates the host executable.
+    let len = unsafe { GetModuleFileNameW(HMODULE::default(), &mut path) } as usize;
+    if len == 0 || len >= path.len() {
+        return None;
+    }
+    let path = &path[..=len]; // include the NUL terminator
+    let path_ptr = PCWSTR::from_raw(path.as_ptr());
+
+    // SAFETY: path_ptr is NUL-terminated; buffers are sized from the API's own report.
+    unsafe {
+        let size = GetFileVersionInfoSizeW(path_ptr, None);
+        if size == 0 {
+            return None;
+        }
+        let mut data = vec![0u8; size as usize];
+        GetFileVersionInfoW(path_ptr, 0, size, data.as_mut_ptr() as *mut c_void).ok()?;
+
+        let mut info_ptr: *mut c_void = std::ptr::null_mut();
+        let mut info_len = 0u32;
+        if !VerQueryValueW(
+            data.as_ptr() as *const c_void,
+            w!("\\"),
+            &mut info_ptr,
+            &mut info_len,
+        )
+        .as_bool()
+            || info_ptr.is_null()
+            || (info_len as usize) < std::mem::size_of::<VS_FIXEDFILEINFO>()
+        {
+            return None;
+        }
+        let info = &*(info_ptr as *const VS_FIXEDFILEINFO);
+        Some(GameVersion::new(
+            info.dwProductVersionMS >> 16,
+            info.dwProductVersionMS & 0xFFFF,
+            info.dwProductVersionLS >> 16,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offsets_for_known_version() {
+        let support = offsets_for(Some(GameVersion::new(2, 6, 0)));
+        assert!(support.known);
+        assert_eq!(support.version, Some(GameVersion::new(2, 6, 0)));
+        assert_eq!(support.offsets, LAYOUT_BASE);
+    }
+
+    #[test]
+    fn test_offsets_for_range_bounds_inclusive() {
+        assert!(offsets_for(Some(GameVersion::new(1, 2, 0))).known);
+        assert!(offsets_for(Some(GameVersion::new(2, 6, 1))).known);
+    }
+
+    #[test]
+    fn test_offsets_for_unknown_version_uses_latest_layout() {
+        for version in [GameVersion::new(1, 0, 0), GameVersion::new(2, 7, 0)] {
+            let support = offsets_for(Some(version));
+            assert!(!support.known);
+            assert_eq!(support.version, Some(version));
+            assert_eq!(
+                support.offsets,
+                OFFSET_TABLE[OFFSET_TABLE.len() - 1].offsets
+            );
+        }
+    }
+
+    #[test]
+    fn test_offsets_for_unreadable_version() {
+        let support = offsets_for(None);
+        assert!(!support.known);
+        assert_eq!(support.version, None);
+        assert_eq!(
+            support.offsets,
+            OFFSET_TABLE[OFFSET_TABLE.len() - 1].offsets
+        );
+    }
+
+    #[test]
+    fn test_offset_table_sorted_without_overlap() {
+        for entry in OFFSET_TABLE {
+            assert!(entry.min <= entry.max);
+        }
+        for pair in OFFSET_TABLE.windows(2) {
+            assert!(pair[0].max < pair[1].min);
+        }
+    }
+
+    #[test]
+    fn test_game_version_display() {
+        assert_eq!(GameVersion::new(2, 6, 0).to_string(), "2.06.0");
+    }
+}
@@ -9,6 +9,8 @@ use std::sync::OnceLock;
 use retour::GenericDetour;
 use tracing::{debug, error, info, warn};
 
+use crate::core::entity_utils;
+
 /// Captured grace entity ID from the last warp call
 static CAPTURED_GRACE_ENTITY_ID: AtomicU32 = AtomicU32::new(0);
 
@@ -81,13 +83,19 @@ unsafe extern "system" fn warp_hook(arg1: u64, arg2: u64, grace_id_param: u32) {
         // The game passes grace_entity_id - 0x3e8 (1000)
         let grace_entity_id = grace_id_param.wrapping_add(0x3e8);
 
-        // Store for later retrieval
-        CAPTURED_GRACE_ENTITY_ID.store(grace_entity_id, Ordering::SeqCst);
-
-        debug!(
-            "Warp hook triggered: param={}, grace_entity_id={}",
-            grace_id_param, grace_entity_id
-        );
+        // Store for later retrieval, unless the warp doesn't target a grace
+        if entity_utils::is_grace(grace_entity_id) {
+            CAPTURED_GRACE_ENTITY_ID.store(grace_entity_id, Ordering::SeqCst);
+            debug!(
+                "Warp hook triggered: param={}, grace_entity_id={}",
+                grace_id_param, grace_entity_id
+            );
+        } else {
+            debug!(
+                "Warp hook ignored: param={}, entity {} is not a grace",
+                grace_id_param, grace_entity_id
+            );
+        }
 
         // Call the original function
         if let Some(detour) = WARP_DETOUR.get() {
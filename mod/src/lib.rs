@@ -1,6 +1,7 @@
 // SpeedFog Racing Mod for Elden Ring
 
-pub mod core;
+/// Platform-independent types, from the `speedfog-race-core` crate
+pub use speedfog_race_core as core;
 
 #[cfg(target_os = "windows")]
 mod eldenring;
@@ -8,6 +9,10 @@ mod eldenring;
 #[cfg(target_os = "windows")]
 mod dll;
 
+/// Race server client, public for the integration tests (`tests/websocket.rs`)
+#[cfg(target_os = "windows")]
+pub use dll::{config::ServerSettings, websocket};
+
 #[cfg(target_os = "windows")]
 use std::ffi::c_void;
 #[cfg(target_os = "windows")]
@@ -31,6 +36,8 @@ use windows::Win32::System::SystemServices::DLL_PROCESS_ATTACH;
 #[cfg(target_os = "windows")]
 use crate::dll::config::RaceConfig;
 #[cfg(target_os = "windows")]
+use crate::dll::config_errors::ConfigErrorOverlay;
+#[cfg(target_os = "windows")]
 use crate::dll::RaceTracker;
 
 /// Keeps the log writer alive for the DLL's lifetime. Its Drop impl flushes
@@ -62,21 +69,27 @@ fn start_mod(hmodule: HINSTANCE) {
     init_logging(hmodule);
     info!("SpeedFog Racing mod starting...");
 
-    let tracker = match RaceTracker::new(hmodule) {
-        Some(t) => t,
-        None => {
-            error!("Failed to initialize RaceTracker");
-            eject();
-            return;
+    // Never hook a game protected by Easy Anti-Cheat
+    if let Err(violation) = crate::eldenring::environment::check(hmodule) {
+        error!(reason = %violation, "Unsafe environment, not loading");
+        crate::eldenring::environment::show_refusal(&violation);
+        eject();
+        return;
+    }
+
+    // A broken config still gets hooked: the overlay lists what to fix
+    let hooks = match RaceTracker::new(hmodule) {
+        Ok(tracker) => Hudhook::builder().with::<ImguiDx12Hooks>(tracker),
+        Err(errors) => {
+            for e in &errors {
+                error!(error = %e, "Config error");
+            }
+            error!("Failed to initialize RaceTracker, showing config errors");
+            Hudhook::builder().with::<ImguiDx12Hooks>(ConfigErrorOverlay::new(errors))
         }
     };
 
-    if let Err(e) = Hudhook::builder()
-        .with::<ImguiDx12Hooks>(tracker)
-        .with_hmodule(hmodule)
-        .build()
-        .apply()
-    {
+    if let Err(e) = hooks.with_hmodule(hmodule).build().apply() {
         error!("Couldn't apply hooks: {e:?}");
         eject();
     }
@@ -87,9 +100,8 @@ fn start_mod(hmodule: HINSTANCE) {
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "system" fn DllMain(hmodule: HINSTANCE, reason: u32, _: *mut c_void) -> bool {
     if reason == DLL_PROCESS_ATTACH {
-        if libeldenring::version::check_version().is_err() {
-            return false;
-        }
+        // Unknown game versions still load: `eldenring::version` picks the
+        // offsets and falls back to signature scanning
         std::thread::spawn(move || {
             start_mod(hmodule);
         });
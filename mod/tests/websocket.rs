@@ -0,0 +1,520 @@
+//! RaceWebSocketClient against an in-process race server
+//!
+//! The mock server speaks the race protocol over a local tungstenite socket,
+//! so the client state machine (auth, race events, reconnection) is checked
+//! without a live server. Each test drives one connection at a time from the
+//! test thread while the client's worker thread runs as in game. A second
+//! mock answers the HTTP long-poll fallback.
+
+#![cfg(target_os = "windows")]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use serde_json::{json, Value};
+use tungstenite::{Message, WebSocket};
+
+use speedfog_race_mod::core::protocol::PROTOCOL_VERSION;
+use speedfog_race_mod::core::transport::TransportMode;
+use speedfog_race_mod::websocket::{ConnectionStatus, IncomingMessage, RaceWebSocketClient};
+use speedfog_race_mod::ServerSettings;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// =============================================================================
+// MOCK SERVER
+// =============================================================================
+
+struct MockServer {
+    listener: TcpListener,
+}
+
+impl MockServer {
+    fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        Self { listener }
+    }
+
+    fn settings(&self, binary_protocol: bool) -> ServerSettings {
+        ServerSettings {
+            url: format!("ws://{}", self.listener.local_addr().unwrap()),
+            mod_token: "token-1".to_string(),
+            race_id: "race-1".to_string(),
+            binary_protocol,
+            ..ServerSettings::default()
+        }
+    }
+
+    /// Next connection from the client, WebSocket handshake done
+    fn accept(&self) -> ModConnection {
+        let deadline = Instant::now() + TIMEOUT;
+        let stream = loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => break stream,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    assert!(Instant::now() < deadline, "client didn't connect");
+                    thread::sleep(Duration::from_millis(10));
+                }
+                Err(e) => panic!("accept: {}", e),
+            }
+        };
+        stream.set_nonblocking(false).unwrap();
+        stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+        ModConnection {
+            socket: tungstenite::accept(stream).unwrap(),
+        }
+    }
+}
+
+/// Server side of one mod connection
+struct ModConnection {
+    socket: WebSocket<TcpStream>,
+}
+
+impl ModConnection {
+    /// Next client message, JSON or MessagePack, clock sync probes skipped
+    fn recv(&mut self) -> Value {
+        loop {
+            let msg: Value = match self.socket.read().unwrap() {
+                Message::Text(text) => serde_json::from_str(&text).unwrap(),
+                Message::Binary(bytes) => rmp_serde::from_slice(&bytes).unwrap(),
+                _ => continue,
+            };
+            if msg["type"] != "clock_sync" {
+                return msg;
+            }
+        }
+    }
+
+    fn send(&mut self, msg: Value) {
+        self.socket.send(Message::Text(msg.to_string())).unwrap();
+    }
+
+    /// Answer the client's auth, returning it
+    fn auth_ok(&mut self, encoding: &str) -> Value {
+        let auth = self.recv();
+        assert_eq!(auth["type"], "auth");
+        self.send(json!({
+            "type": "auth_ok",
+            "participant_id": "p-1",
+            "race": {"id": "race-1", "name": "Test Race", "status": "open"},
+            "seed": {"total_layers": 5},
+            "participants": [participant("p-1", "playing")],
+            "protocol_version": PROTOCOL_VERSION,
+            "encoding": encoding,
+        }));
+        auth
+    }
+
+    fn close(mut self) {
+        let _ = self.socket.close(None);
+        let _ = self.socket.flush();
+    }
+}
+
+fn participant(id: &str, status: &str) -> Value {
+    json!({
+        "id": id,
+        "twitch_username": format!("player-{}", id),
+        "twitch_display_name": null,
+        "status": status,
+        "current_zone": null,
+        "current_layer": 0,
+        "igt_ms": 0,
+        "death_count": 0,
+    })
+}
+
+// =============================================================================
+// MOCK LONG-POLL SERVER
+// =============================================================================
+
+/// HTTP long-poll endpoints of the race server, one thread per request.
+/// Messages posted by the client come out of `received`; messages pushed to
+/// `to_client` are handed to its next poll.
+struct MockLongPollServer {
+    url: String,
+    received: Receiver<Value>,
+    to_client: Sender<Value>,
+}
+
+impl MockLongPollServer {
+    fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (received_tx, received) = unbounded::<Value>();
+        let (to_client, outgoing) = unbounded::<Value>();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let received_tx = received_tx.clone();
+                let outgoing = outgoing.clone();
+                thread::spawn(move || serve_long_poll(stream, &received_tx, &outgoing));
+            }
+        });
+        Self {
+            url,
+            received,
+            to_client,
+        }
+    }
+
+    fn settings(&self) -> ServerSettings {
+        ServerSettings {
+            url: self.url.clone(),
+            mod_token: "token-1".to_string(),
+            race_id: "race-1".to_string(),
+            transport: TransportMode::LongPoll,
+            ..ServerSettings::default()
+        }
+    }
+
+    /// Next client message, clock sync probes skipped
+    fn recv(&self) -> Value {
+        loop {
+            let msg = self
+                .received
+                .recv_timeout(TIMEOUT)
+                .expect("no message from the client");
+            if msg["type"] != "clock_sync" {
+                return msg;
+            }
+        }
+    }
+
+    fn send(&self, msg: Value) {
+        self.to_client.send(msg).unwrap();
+    }
+}
+
+/// Answer one HTTP request of the long-poll client
+fn serve_long_poll(stream: TcpStream, received: &Sender<Value>, outgoing: &Receiver<Value>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).unwrap();
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap();
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap(), parts.next().unwrap());
+    let answer = match method {
+        "POST" if path == "/poll/mod/race-1" => json!({"session_id": "s-1"}),
+        "POST" if path == "/poll/session/s-1" => {
+            let body: Value = serde_json::from_slice(&body).unwrap();
+            for text in body["messages"].as_array().unwrap() {
+                received
+                    .send(serde_json::from_str(text.as_str().unwrap()).unwrap())
+                    .unwrap();
+            }
+            Value::Null
+        }
+        "GET" if path.starts_with("/poll/session/s-1?") => {
+            let messages: Vec<String> = outgoing
+                .recv_timeout(Duration::from_millis(200))
+                .into_iter()
+                .map(|msg| msg.to_string())
+                .collect();
+            json!({"messages": messages, "closed": false})
+        }
+        "DELETE" => Value::Null,
+        _ => panic!("unexpected request: {} {}", method, path),
+    };
+    let answer = answer.to_string();
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        answer.len(),
+        answer
+    )
+    .unwrap();
+}
+
+// =============================================================================
+// CLIENT HELPERS
+// =============================================================================
+
+fn next(client: &mut RaceWebSocketClient) -> IncomingMessage {
+    client
+        .poll_timeout(TIMEOUT)
+        .expect("no message from the WebSocket worker")
+}
+
+fn expect_status(client: &mut RaceWebSocketClient, expected: ConnectionStatus) {
+    match next(client) {
+        IncomingMessage::StatusChanged(status) => assert_eq!(status, expected),
+        other => panic!("expected StatusChanged({:?}), got {:?}", expected, other),
+    }
+    assert_eq!(client.status(), expected);
+}
+
+/// Connect and authenticate, up to the Connected status
+fn connect(server: &MockServer, client: &mut RaceWebSocketClient) -> ModConnection {
+    client.connect();
+    let mut conn = server.accept();
+    conn.auth_ok("json");
+    expect_status(client, ConnectionStatus::Connecting);
+    assert!(matches!(next(client), IncomingMessage::AuthOk { .. }));
+    expect_status(client, ConnectionStatus::Connected);
+    conn
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[test]
+fn test_auth_sends_token_and_version() {
+    let server = MockServer::start();
+    let mut client = RaceWebSocketClient::new(server.settings(false));
+    client.connect();
+    let mut conn = server.accept();
+
+    let auth = conn.auth_ok("json");
+    assert_eq!(auth["mod_token"], "token-1");
+    assert_eq!(auth["protocol_version"], PROTOCOL_VERSION);
+    assert_eq!(auth["encodings"], json!([]));
+
+    expect_status(&mut client, ConnectionStatus::Connecting);
+    match next(&mut client) {
+        IncomingMessage::AuthOk {
+            participant_id,
+            race,
+            seed,
+            participants,
+            protocol_version,
+            ..
+        } => {
+            assert_eq!(participant_id, "p-1");
+            assert_eq!(race.name, "Test Race");
+            assert_eq!(seed.total_layers, 5);
+            assert_eq!(participants.len(), 1);
+            assert_eq!(protocol_version, PROTOCOL_VERSION);
+        }
+        other => panic!("expected AuthOk, got {:?}", other),
+    }
+    expect_status(&mut client, ConnectionStatus::Connected);
+    assert!(client.is_connected());
+
+    client.disconnect();
+    assert_eq!(client.status(), ConnectionStatus::Disconnected);
+}
+
+#[test]
+fn test_auth_error() {
+    let server = MockServer::start();
+    let mut client = RaceWebSocketClient::new(server.settings(false));
+    client.connect();
+    let mut conn = server.accept();
+
+    assert_eq!(conn.recv()["type"], "auth");
+    conn.send(json!({"type": "auth_error", "message": "Invalid mod token"}));
+
+    expect_status(&mut client, ConnectionStatus::Connecting);
+    match next(&mut client) {
+        IncomingMessage::AuthError(message) => assert_eq!(message, "Invalid mod token"),
+        other => panic!("expected AuthError, got {:?}", other),
+    }
+    assert!(matches!(next(&mut client), IncomingMessage::Error(_)));
+    expect_status(&mut client, ConnectionStatus::Error);
+
+    // Stop before the retry: nobody would answer its handshake
+    client.disconnect();
+}
+
+#[test]
+fn test_race_start_and_leaderboard() {
+    let server = MockServer::start();
+    let mut client = RaceWebSocketClient::new(server.settings(false));
+    let mut conn = connect(&server, &mut client);
+
+    client.send_ready();
+    assert_eq!(conn.recv()["type"], "ready");
+
+    conn.send(json!({"type": "race_start", "started_at": "2026-01-01T20:00:00Z"}));
+    match next(&mut client) {
+        IncomingMessage::RaceStart { started_at } => {
+            assert_eq!(started_at.as_deref(), Some("2026-01-01T20:00:00Z"));
+        }
+        other => panic!("expected RaceStart, got {:?}", other),
+    }
+
+    conn.send(json!({
+        "type": "leaderboard_update",
+        "participants": [participant("p-2", "playing"), participant("p-1", "playing")],
+    }));
+    match next(&mut client) {
+        IncomingMessage::LeaderboardUpdate { participants, .. } => {
+            let ids: Vec<_> = participants.iter().map(|p| p.id.as_str()).collect();
+            assert_eq!(ids, ["p-2", "p-1"]);
+        }
+        other => panic!("expected LeaderboardUpdate, got {:?}", other),
+    }
+
+    client.send_status_update(61_000, 3);
+    let status = conn.recv();
+    assert_eq!(status["type"], "status_update");
+    assert_eq!(status["igt_ms"], 61_000);
+    assert_eq!(status["death_count"], 3);
+    assert!(status.get("disconnecting").is_none());
+
+    client.disconnect();
+}
+
+#[test]
+fn test_ping_pong() {
+    let server = MockServer::start();
+    let mut client = RaceWebSocketClient::new(server.settings(false));
+    let mut conn = connect(&server, &mut client);
+
+    conn.send(json!({"type": "ping"}));
+    assert_eq!(conn.recv()["type"], "pong");
+
+    client.disconnect();
+}
+
+#[test]
+fn test_msgpack_after_negotiation() {
+    let server = MockServer::start();
+    let mut client = RaceWebSocketClient::new(server.settings(true));
+    client.connect();
+    let mut conn = server.accept();
+
+    let auth = conn.auth_ok("msgpack");
+    assert_eq!(auth["encodings"], json!(["msgpack"]));
+    expect_status(&mut client, ConnectionStatus::Connecting);
+    assert!(matches!(next(&mut client), IncomingMessage::AuthOk { .. }));
+    expect_status(&mut client, ConnectionStatus::Connected);
+
+    client.send_event_flag(
+        1040292842,
+        4_532_100,
+        Some(7),
+        Some("race-1:ac3e35353be515b6:1040292842".to_string()),
+    );
+    let frame = loop {
+        match conn.socket.read().unwrap() {
+            Message::Binary(bytes) => {
+                let msg: Value = rmp_serde::from_slice(&bytes).unwrap();
+                if msg["type"] != "clock_sync" {
+                    break msg;
+                }
+            }
+            other => panic!("expected a binary frame, got {:?}", other),
+        }
+    };
+    assert_eq!(frame["type"], "event_flag");
+    assert_eq!(frame["flag_id"], 1040292842);
+    assert_eq!(frame["seq"], 7);
+    assert_eq!(
+        frame["idempotency_key"],
+        "race-1:ac3e35353be515b6:1040292842"
+    );
+
+    client.disconnect();
+}
+
+#[test]
+fn test_reconnect_and_reauth() {
+    let server = MockServer::start();
+    let mut client = RaceWebSocketClient::new(server.settings(false));
+    let conn = connect(&server, &mut client);
+
+    conn.close();
+    expect_status(&mut client, ConnectionStatus::Reconnecting);
+
+    // Queued while disconnected: the event flag goes back to the tracker,
+    // the stale status update is dropped
+    client.send_status_update(1_000, 0);
+    client.send_event_flag(1040292842, 2_000, Some(1), None);
+
+    let mut conn = server.accept();
+    let auth = conn.auth_ok("json");
+    assert_eq!(auth["mod_token"], "token-1");
+
+    expect_status(&mut client, ConnectionStatus::Connecting);
+    assert!(matches!(next(&mut client), IncomingMessage::AuthOk { .. }));
+    match next(&mut client) {
+        IncomingMessage::RequeueEventFlag { flag_id, igt_ms } => {
+            assert_eq!(flag_id, 1040292842);
+            assert_eq!(igt_ms, 2_000);
+        }
+        other => panic!("expected RequeueEventFlag, got {:?}", other),
+    }
+    expect_status(&mut client, ConnectionStatus::Connected);
+
+    client.send_ready();
+    assert_eq!(conn.recv()["type"], "ready");
+
+    client.disconnect();
+}
+
+#[test]
+fn test_quit_sends_last_status() {
+    let server = MockServer::start();
+    let mut client = RaceWebSocketClient::new(server.settings(false));
+    let mut conn = connect(&server, &mut client);
+
+    let exit = client.exit_handle().unwrap();
+    assert!(exit.quit(Some((90_000, 4)), TIMEOUT));
+
+    let status = conn.recv();
+    assert_eq!(status["type"], "status_update");
+    assert_eq!(status["igt_ms"], 90_000);
+    assert_eq!(status["disconnecting"], true);
+    expect_status(&mut client, ConnectionStatus::Disconnected);
+}
+
+#[test]
+fn test_long_poll_transport() {
+    let server = MockLongPollServer::start();
+    let mut settings = server.settings();
+    // Long-polling only carries JSON, whatever the setting
+    settings.binary_protocol = true;
+    let mut client = RaceWebSocketClient::new(settings);
+    client.connect();
+
+    let auth = server.recv();
+    assert_eq!(auth["type"], "auth");
+    assert_eq!(auth["mod_token"], "token-1");
+    assert_eq!(auth["encodings"], json!([]));
+    server.send(json!({
+        "type": "auth_ok",
+        "participant_id": "p-1",
+        "race": {"id": "race-1", "name": "Test Race", "status": "open"},
+        "seed": {"total_layers": 5},
+        "participants": [participant("p-1", "playing")],
+        "protocol_version": PROTOCOL_VERSION,
+        "encoding": "json",
+    }));
+    expect_status(&mut client, ConnectionStatus::Connecting);
+    assert!(matches!(next(&mut client), IncomingMessage::AuthOk { .. }));
+    expect_status(&mut client, ConnectionStatus::Connected);
+
+    client.send_ready();
+    assert_eq!(server.recv()["type"], "ready");
+
+    server.send(json!({"type": "ping"}));
+    assert_eq!(server.recv()["type"], "pong");
+
+    client.disconnect();
+}
@@ -0,0 +1,31 @@
+"""add checkpoints to participant
+
+Revision ID: 3e8b1d5c7a02
+Revises: 7c2f5e81a9d4
+Create Date: 2026-10-16 11:02:41.318275
+
+"""
+
+from collections.abc import Sequence
+
+import sqlalchemy as sa
+from sqlalchemy.dialects import postgresql
+
+from alembic import op
+
+# revision identifiers, used by Alembic.
+revision: str = "3e8b1d5c7a02"
+down_revision: str | None = "7c2f5e81a9d4"
+branch_labels: str | Sequence[str] | None = None
+depends_on: str | Sequence[str] | None = None
+
+
+def upgrade() -> None:
+    op.add_column(
+        "participants",
+        sa.Column("checkpoints", postgresql.JSON(astext_type=sa.Text()), nullable=True),
+    )
+
+
+def downgrade() -> None:
+    op.drop_column("participants", "checkpoints")
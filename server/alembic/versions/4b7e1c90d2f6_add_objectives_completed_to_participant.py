@@ -0,0 +1,31 @@
+"""add objectives_completed to participant
+
+Revision ID: 4b7e1c90d2f6
+Revises: 9d41c7e2b8a3
+Create Date: 2026-10-15 19:02:41.230518
+
+"""
+
+from collections.abc import Sequence
+
+import sqlalchemy as sa
+from sqlalchemy.dialects import postgresql
+
+from alembic import op
+
+# revision identifiers, used by Alembic.
+revision: str = "4b7e1c90d2f6"
+down_revision: str | None = "9d41c7e2b8a3"
+branch_labels: str | Sequence[str] | None = None
+depends_on: str | Sequence[str] | None = None
+
+
+def upgrade() -> None:
+    op.add_column(
+        "participants",
+        sa.Column("objectives_completed", postgresql.JSON(astext_type=sa.Text()), nullable=True),
+    )
+
+
+def downgrade() -> None:
+    op.drop_column("participants", "objectives_completed")
@@ -0,0 +1,31 @@
+"""add kindling_uses to participant
+
+Revision ID: 5a9c2e7f1b34
+Revises: 3e8b1d5c7a02
+Create Date: 2026-10-16 13:24:09.517342
+
+"""
+
+from collections.abc import Sequence
+
+import sqlalchemy as sa
+from sqlalchemy.dialects import postgresql
+
+from alembic import op
+
+# revision identifiers, used by Alembic.
+revision: str = "5a9c2e7f1b34"
+down_revision: str | None = "3e8b1d5c7a02"
+branch_labels: str | Sequence[str] | None = None
+depends_on: str | Sequence[str] | None = None
+
+
+def upgrade() -> None:
+    op.add_column(
+        "participants",
+        sa.Column("kindling_uses", postgresql.JSON(astext_type=sa.Text()), nullable=True),
+    )
+
+
+def downgrade() -> None:
+    op.drop_column("participants", "kindling_uses")
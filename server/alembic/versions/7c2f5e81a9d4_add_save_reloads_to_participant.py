@@ -0,0 +1,31 @@
+"""add save_reloads to participant
+
+Revision ID: 7c2f5e81a9d4
+Revises: 4b7e1c90d2f6
+Create Date: 2026-10-16 09:14:27.503816
+
+"""
+
+from collections.abc import Sequence
+
+import sqlalchemy as sa
+from sqlalchemy.dialects import postgresql
+
+from alembic import op
+
+# revision identifiers, used by Alembic.
+revision: str = "7c2f5e81a9d4"
+down_revision: str | None = "4b7e1c90d2f6"
+branch_labels: str | Sequence[str] | None = None
+depends_on: str | Sequence[str] | None = None
+
+
+def upgrade() -> None:
+    op.add_column(
+        "participants",
+        sa.Column("save_reloads", postgresql.JSON(astext_type=sa.Text()), nullable=True),
+    )
+
+
+def downgrade() -> None:
+    op.drop_column("participants", "save_reloads")
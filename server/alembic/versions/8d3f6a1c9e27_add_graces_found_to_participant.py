@@ -0,0 +1,31 @@
+"""add graces_found to participant
+
+Revision ID: 8d3f6a1c9e27
+Revises: 5a9c2e7f1b34
+Create Date: 2026-10-16 15:02:41.208519
+
+"""
+
+from collections.abc import Sequence
+
+import sqlalchemy as sa
+from sqlalchemy.dialects import postgresql
+
+from alembic import op
+
+# revision identifiers, used by Alembic.
+revision: str = "8d3f6a1c9e27"
+down_revision: str | None = "5a9c2e7f1b34"
+branch_labels: str | Sequence[str] | None = None
+depends_on: str | Sequence[str] | None = None
+
+
+def upgrade() -> None:
+    op.add_column(
+        "participants",
+        sa.Column("graces_found", postgresql.JSON(astext_type=sa.Text()), nullable=True),
+    )
+
+
+def downgrade() -> None:
+    op.drop_column("participants", "graces_found")
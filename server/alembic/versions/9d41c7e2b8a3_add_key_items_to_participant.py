@@ -0,0 +1,31 @@
+"""add key_items to participant
+
+Revision ID: 9d41c7e2b8a3
+Revises: afe8e80417ef
+Create Date: 2026-10-15 14:12:08.417093
+
+"""
+
+from collections.abc import Sequence
+
+import sqlalchemy as sa
+from sqlalchemy.dialects import postgresql
+
+from alembic import op
+
+# revision identifiers, used by Alembic.
+revision: str = "9d41c7e2b8a3"
+down_revision: str | None = "afe8e80417ef"
+branch_labels: str | Sequence[str] | None = None
+depends_on: str | Sequence[str] | None = None
+
+
+def upgrade() -> None:
+    op.add_column(
+        "participants",
+        sa.Column("key_items", postgresql.JSON(astext_type=sa.Text()), nullable=True),
+    )
+
+
+def downgrade() -> None:
+    op.drop_column("participants", "key_items")
@@ -0,0 +1,31 @@
+"""add timing_anomalies to participant
+
+Revision ID: b4e7d2a9f153
+Revises: 8d3f6a1c9e27
+Create Date: 2026-10-16 16:41:07.532914
+
+"""
+
+from collections.abc import Sequence
+
+import sqlalchemy as sa
+from sqlalchemy.dialects import postgresql
+
+from alembic import op
+
+# revision identifiers, used by Alembic.
+revision: str = "b4e7d2a9f153"
+down_revision: str | None = "8d3f6a1c9e27"
+branch_labels: str | Sequence[str] | None = None
+depends_on: str | Sequence[str] | None = None
+
+
+def upgrade() -> None:
+    op.add_column(
+        "participants",
+        sa.Column("timing_anomalies", postgresql.JSON(astext_type=sa.Text()), nullable=True),
+    )
+
+
+def downgrade() -> None:
+    op.drop_column("participants", "timing_anomalies")
@@ -0,0 +1,31 @@
+"""add badges to participant
+
+Revision ID: c5f8e3b1a264
+Revises: b4e7d2a9f153
+Create Date: 2026-10-16 18:12:44.208317
+
+"""
+
+from collections.abc import Sequence
+
+import sqlalchemy as sa
+from sqlalchemy.dialects import postgresql
+
+from alembic import op
+
+# revision identifiers, used by Alembic.
+revision: str = "c5f8e3b1a264"
+down_revision: str | None = "b4e7d2a9f153"
+branch_labels: str | Sequence[str] | None = None
+depends_on: str | Sequence[str] | None = None
+
+
+def upgrade() -> None:
+    op.add_column(
+        "participants",
+        sa.Column("badges", postgresql.JSON(astext_type=sa.Text()), nullable=True),
+    )
+
+
+def downgrade() -> None:
+    op.drop_column("participants", "badges")
@@ -13,6 +13,7 @@ from speedfog_racing.schemas import (
     AcceptInviteResponse,
     InviteInfoResponse,
 )
+from speedfog_racing.services.badge_service import compute_badges
 
 router = APIRouter()
 
@@ -111,6 +112,7 @@ async def accept_invite(
         user_id=user.id,
         user=user,
         color_index=next_color,
+        badges=await compute_badges(db, user.id, invite.race.organizer_id),
     )
     db.add(participant)
 
@@ -2,7 +2,7 @@
 
 import asyncio
 import logging
-from datetime import UTC, datetime
+from datetime import UTC, datetime, timedelta
 from pathlib import Path
 from uuid import UUID
 
@@ -24,6 +24,7 @@ from speedfog_racing.auth import (
     get_current_user_optional,
     get_user_by_twitch_username,
 )
+from speedfog_racing.config import settings
 from speedfog_racing.database import async_session_maker, get_db
 from speedfog_racing.discord import (
     create_scheduled_event,
@@ -59,6 +60,7 @@ from speedfog_racing.schemas import (
     RaceDetailResponse,
     RaceListResponse,
     RaceResponse,
+    SetFlagRequest,
     UpdateRaceRequest,
 )
 from speedfog_racing.services import (
@@ -67,13 +69,24 @@ from speedfog_racing.services import (
     get_pool_config,
     reroll_seed_for_race,
 )
+from speedfog_racing.services.badge_service import compute_badges
 from speedfog_racing.services.race_lifecycle import check_race_auto_finish
 from speedfog_racing.services.seed_pack_service import (
     sanitize_filename,
     stream_seed_pack_with_config,
 )
-from speedfog_racing.websocket import broadcast_race_start, broadcast_race_state_update
-from speedfog_racing.websocket.manager import manager
+from speedfog_racing.websocket import (
+    broadcast_race_start,
+    broadcast_race_state_update,
+    broadcast_seed_changed,
+)
+from speedfog_racing.websocket.manager import PAUSE_GRANTED, PAUSE_REQUESTED, manager
+from speedfog_racing.websocket.schemas import (
+    PauseGrantedMessage,
+    ResumeMessage,
+    SetFlagMessage,
+    extract_settable_flags,
+)
 
 logger = logging.getLogger(__name__)
 
@@ -282,6 +295,7 @@ async def create_race(
             user=user,
             race=race,
             color_index=0,
+            badges=await compute_badges(db, user.id, race.organizer_id),
         )
         db.add(participant)
 
@@ -568,6 +582,7 @@ async def add_participant(
             user=target_user,
             race=race,
             color_index=next_color,
+            badges=await compute_badges(db, target_user.id, race.organizer_id),
         )
         db.add(participant)
         try:
@@ -876,6 +891,7 @@ async def join_race(
         user=user,
         race=race,
         color_index=next_color,
+        badges=await compute_badges(db, user.id, race.organizer_id),
     )
     db.add(participant)
     try:
@@ -1021,7 +1037,7 @@ async def start_race(
     db: AsyncSession = Depends(get_db),
     user: User = Depends(get_current_user),
 ) -> RaceResponse:
-    """Start the race immediately."""
+    """Start the race, after the configured countdown."""
     race = await _get_race_or_404(db, race_id, load_participants=True, load_casters=True)
     _require_organizer(race, user)
 
@@ -1042,7 +1058,7 @@ async def start_race(
         race,
         [RaceStatus.SETUP],
         RaceStatus.RUNNING,
-        started_at=datetime.now(UTC),
+        started_at=datetime.now(UTC) + timedelta(seconds=settings.race_start_countdown_seconds),
     )
 
     await db.commit()
@@ -1128,6 +1144,11 @@ async def reroll_seed(
     race = await _get_race_or_404(
         db, race_id, load_participants=True, load_casters=True, load_invites=True
     )
+
+    # Connected mods still run the old seed pack: tell them to reset
+    if race.seed:
+        await broadcast_seed_changed(race_id, race.seed)
+
     return _race_detail_response(race, user=user)
 
 
@@ -1209,6 +1230,7 @@ async def reset_race(
     )
 
     for p in race.participants:
+        manager.pauses.pop(p.id, None)
         p.status = ParticipantStatus.REGISTERED
         p.current_zone = None
         p.current_layer = 0
@@ -1245,6 +1267,7 @@ async def finish_race(
 
     # Mark remaining playing participants as abandoned
     for p in race.participants:
+        manager.pauses.pop(p.id, None)
         if p.status == ParticipantStatus.PLAYING:
             p.status = ParticipantStatus.ABANDONED
 
@@ -1297,6 +1320,7 @@ async def abandon_race(
         )
 
     participant.status = ParticipantStatus.ABANDONED
+    manager.pauses.pop(participant.id, None)
     await db.commit()
 
     # Re-query with eager-loaded relationships
@@ -1318,6 +1342,123 @@ async def abandon_race(
     return race_response(race)
 
 
+async def _get_running_participant_target(
+    db: AsyncSession, race_id: UUID, participant_id: UUID, user: User
+) -> Race:
+    """Load a running race for an organizer action on one of its participants."""
+    race = await _get_race_or_404(db, race_id, load_participants=True)
+    _require_organizer(race, user)
+
+    if race.status != RaceStatus.RUNNING:
+        raise HTTPException(
+            status_code=status.HTTP_400_BAD_REQUEST,
+            detail="Can only act on participants of a running race",
+        )
+
+    if not any(p.id == participant_id for p in race.participants):
+        raise HTTPException(
+            status_code=status.HTTP_404_NOT_FOUND,
+            detail="Participant not found",
+        )
+
+    return race
+
+
+@router.post(
+    "/{race_id}/participants/{participant_id}/pause",
+    status_code=status.HTTP_204_NO_CONTENT,
+)
+async def grant_pause(
+    race_id: UUID,
+    participant_id: UUID,
+    db: AsyncSession = Depends(get_db),
+    user: User = Depends(get_current_user),
+) -> None:
+    """Grant a participant's pause request: their mod stops reporting progress."""
+    race = await _get_running_participant_target(db, race_id, participant_id, user)
+
+    if manager.pauses.get(participant_id) != PAUSE_REQUESTED:
+        raise HTTPException(
+            status_code=status.HTTP_400_BAD_REQUEST,
+            detail="Participant has not requested a pause",
+        )
+
+    manager.pauses[participant_id] = PAUSE_GRANTED
+    logger.info(f"Pause granted: race={race_id}, participant={participant_id}")
+    await manager.send_to_mod(race_id, participant_id, PauseGrantedMessage().model_dump_json())
+
+    graph_json = race.seed.graph_json if race.seed else None
+    await manager.broadcast_leaderboard(race_id, race.participants, graph_json=graph_json)
+
+
+@router.post(
+    "/{race_id}/participants/{participant_id}/resume",
+    status_code=status.HTTP_204_NO_CONTENT,
+)
+async def resume_pause(
+    race_id: UUID,
+    participant_id: UUID,
+    db: AsyncSession = Depends(get_db),
+    user: User = Depends(get_current_user),
+) -> None:
+    """Resume a paused participant, or decline their pending pause request."""
+    race = await _get_running_participant_target(db, race_id, participant_id, user)
+
+    if manager.pauses.pop(participant_id, None) is None:
+        raise HTTPException(
+            status_code=status.HTTP_400_BAD_REQUEST,
+            detail="Participant is not paused",
+        )
+
+    logger.info(f"Pause ended: race={race_id}, participant={participant_id}")
+    await manager.send_to_mod(race_id, participant_id, ResumeMessage().model_dump_json())
+
+    graph_json = race.seed.graph_json if race.seed else None
+    await manager.broadcast_leaderboard(race_id, race.participants, graph_json=graph_json)
+
+
+@router.post(
+    "/{race_id}/participants/{participant_id}/flags",
+    status_code=status.HTTP_204_NO_CONTENT,
+)
+async def set_participant_flag(
+    race_id: UUID,
+    participant_id: UUID,
+    request: SetFlagRequest,
+    db: AsyncSession = Depends(get_db),
+    user: User = Depends(get_current_user),
+) -> None:
+    """Set an event flag in a participant's game (catch-up mechanics).
+
+    Only flags listed in the race's ``settable_flags`` are sent; the mod
+    applies them only if the player allowed them in their config.
+    """
+    race = await _get_running_participant_target(db, race_id, participant_id, user)
+
+    if request.flag_id not in extract_settable_flags(race.config):
+        raise HTTPException(
+            status_code=status.HTTP_400_BAD_REQUEST,
+            detail="Flag is not settable in this race",
+        )
+
+    if manager.mod_protocol_version(race_id, participant_id) < 20:
+        raise HTTPException(
+            status_code=status.HTTP_409_CONFLICT,
+            detail="Participant's mod is not connected or does not support set_flag",
+        )
+
+    message = SetFlagMessage(flag_id=request.flag_id, value=request.value)
+    if not await manager.send_to_mod(race_id, participant_id, message.model_dump_json()):
+        raise HTTPException(
+            status_code=status.HTTP_409_CONFLICT,
+            detail="Failed to reach the participant's mod",
+        )
+    logger.info(
+        f"Flag set: race={race_id}, participant={participant_id}, "
+        f"flag={request.flag_id}, value={request.value}"
+    )
+
+
 @router.delete("/{race_id}", status_code=status.HTTP_204_NO_CONTENT)
 async def delete_race(
     race_id: UUID,
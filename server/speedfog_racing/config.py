@@ -40,6 +40,11 @@ class Settings(BaseSettings):
     discord_public_key: str | None = None
     discord_channel_id: str | None = None
 
+    # Races
+    # Delay between the organizer starting a race and its start timestamp, so mods
+    # can show a synchronized 3-2-1 countdown (0 = start immediately)
+    race_start_countdown_seconds: int = 0
+
     # Server
     log_level: str = "INFO"
     log_json: bool = False
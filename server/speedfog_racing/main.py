@@ -6,9 +6,10 @@ import logging
 import uuid
 from collections.abc import AsyncGenerator
 from contextlib import asynccontextmanager
+from functools import partial
 from pathlib import Path
 
-from fastapi import FastAPI, Request, WebSocket
+from fastapi import FastAPI, HTTPException, Request, WebSocket
 from fastapi.middleware.cors import CORSMiddleware
 from fastapi.responses import JSONResponse
 from slowapi.errors import RateLimitExceeded
@@ -18,7 +19,7 @@ from speedfog_racing import __version__
 from speedfog_racing.api import api_router
 from speedfog_racing.config import settings
 from speedfog_racing.database import async_session_maker, get_db_context, init_db
-from speedfog_racing.rate_limit import limiter
+from speedfog_racing.rate_limit import get_real_ip, limiter
 from speedfog_racing.services import scan_pool
 from speedfog_racing.services.i18n import load_translations
 from speedfog_racing.services.inactivity_monitor import inactivity_monitor_loop
@@ -29,6 +30,14 @@ from speedfog_racing.websocket import (
     handle_training_mod_websocket,
     handle_training_spectator_websocket,
 )
+from speedfog_racing.websocket.long_poll import (
+    POLL_WAIT_MAX,
+    LongPollBatch,
+    LongPollOpened,
+    LongPollSend,
+    LongPollSocket,
+    long_poll_sessions,
+)
 from speedfog_racing.websocket.manager import manager as ws_manager
 
 # Configure logging
@@ -165,6 +174,67 @@ async def websocket_training_spectator(websocket: WebSocket, session_id: uuid.UU
     await handle_training_spectator_websocket(websocket, session_id, async_session_maker)
 
 
+# Long-poll fallback of the mod WebSocket routes
+
+
+def _long_poll_session(session_id: uuid.UUID) -> LongPollSocket:
+    socket = long_poll_sessions.get(session_id)
+    if socket is None:
+        raise HTTPException(status_code=404, detail="Unknown long-poll session")
+    return socket
+
+
+@app.post("/poll/mod/{race_id}")
+@limiter.limit("10/minute")
+async def long_poll_mod_open(request: Request, race_id: uuid.UUID) -> LongPollOpened:
+    """Open a long-poll session standing in for /ws/mod/{race_id}."""
+    session_id = long_poll_sessions.open(
+        partial(handle_mod_websocket, race_id=race_id, session_maker=async_session_maker),
+        get_real_ip(request),
+    )
+    if session_id is None:
+        raise HTTPException(status_code=503, detail="Too many long-poll sessions")
+    return LongPollOpened(session_id=session_id)
+
+
+@app.post("/poll/training/{training_id}")
+@limiter.limit("10/minute")
+async def long_poll_training_open(request: Request, training_id: uuid.UUID) -> LongPollOpened:
+    """Open a long-poll session standing in for /ws/training/{session_id}."""
+    session_id = long_poll_sessions.open(
+        partial(
+            handle_training_mod_websocket,
+            session_id=training_id,
+            session_maker=async_session_maker,
+        ),
+        get_real_ip(request),
+    )
+    if session_id is None:
+        raise HTTPException(status_code=503, detail="Too many long-poll sessions")
+    return LongPollOpened(session_id=session_id)
+
+
+@app.post("/poll/session/{session_id}")
+async def long_poll_send(session_id: uuid.UUID, body: LongPollSend) -> None:
+    """Messages from the mod, as it would send them in text frames."""
+    _long_poll_session(session_id).push(body.messages)
+
+
+@app.get("/poll/session/{session_id}")
+async def long_poll_receive(session_id: uuid.UUID, wait: float = POLL_WAIT_MAX) -> LongPollBatch:
+    """Messages for the mod, held open until one is queued or `wait` passes."""
+    long_poll_sessions.expire_idle()
+    socket = _long_poll_session(session_id)
+    messages, closed = await socket.poll(min(max(wait, 0.0), POLL_WAIT_MAX))
+    return LongPollBatch(messages=messages, closed=closed, close_reason=socket.close_reason)
+
+
+@app.delete("/poll/session/{session_id}")
+async def long_poll_close(session_id: uuid.UUID) -> None:
+    """Close the session, like closing the WebSocket."""
+    await long_poll_sessions.close(session_id)
+
+
 def main() -> None:
     """Run the server."""
     import uvicorn
@@ -179,6 +179,15 @@ class Participant(Base):
     )
     color_index: Mapped[int] = mapped_column(Integer, default=0, server_default="0")
     zone_history: Mapped[list[dict[str, Any]] | None] = mapped_column(JSON, nullable=True)
+    key_items: Mapped[list[dict[str, Any]] | None] = mapped_column(JSON, nullable=True)
+    objectives_completed: Mapped[list[dict[str, Any]] | None] = mapped_column(JSON, nullable=True)
+    checkpoints: Mapped[list[dict[str, Any]] | None] = mapped_column(JSON, nullable=True)
+    save_reloads: Mapped[list[dict[str, Any]] | None] = mapped_column(JSON, nullable=True)
+    kindling_uses: Mapped[list[dict[str, Any]] | None] = mapped_column(JSON, nullable=True)
+    graces_found: Mapped[list[dict[str, Any]] | None] = mapped_column(JSON, nullable=True)
+    timing_anomalies: Mapped[list[dict[str, Any]] | None] = mapped_column(JSON, nullable=True)
+    # Leaderboard badges decided when joining (services.badge_service)
+    badges: Mapped[list[str] | None] = mapped_column(JSON, nullable=True)
 
     # Relationships
     race: Mapped["Race"] = relationship(back_populates="participants")
@@ -4,7 +4,7 @@ from slowapi import Limiter
 from starlette.requests import Request
 
 
-def _get_real_ip(request: Request) -> str:
+def get_real_ip(request: Request) -> str:
     """Extract client IP from X-Forwarded-For (set by nginx) or fall back to direct IP."""
     forwarded = request.headers.get("X-Forwarded-For")
     if forwarded:
@@ -15,4 +15,4 @@ def _get_real_ip(request: Request) -> str:
 
 
 # default_limits applies to all routes; auth endpoints override with stricter limits.
-limiter = Limiter(key_func=_get_real_ip, default_limits=["60/minute"])
+limiter = Limiter(key_func=get_real_ip, default_limits=["60/minute"])
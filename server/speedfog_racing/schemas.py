@@ -58,6 +58,13 @@ class AddCasterRequest(BaseModel):
     twitch_username: str
 
 
+class SetFlagRequest(BaseModel):
+    """Request to set an event flag in a participant's game. Organizer only."""
+
+    flag_id: int
+    value: bool = True
+
+
 # =============================================================================
 # Response Schemas
 # =============================================================================
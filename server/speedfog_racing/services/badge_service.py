@@ -0,0 +1,72 @@
+"""Participant badges shown next to names on the leaderboards.
+
+``organizer`` and ``previous_winner`` are decided once, when the participant
+joins the race, and stored on the row; ``streamer`` follows the Twitch live
+status and is added when the leaderboard is built.
+"""
+
+import uuid
+
+from sqlalchemy import and_, func, select
+from sqlalchemy.ext.asyncio import AsyncSession
+
+from speedfog_racing.models import Participant, ParticipantStatus, Race, RaceStatus
+
+BADGE_ORGANIZER = "organizer"
+BADGE_PREVIOUS_WINNER = "previous_winner"
+BADGE_STREAMER = "streamer"
+
+
+async def has_won_a_race(db: AsyncSession, user_id: uuid.UUID) -> bool:
+    """Whether the user had the best IGT of a finished race."""
+    winning_igt = (
+        select(
+            Participant.race_id.label("race_id"),
+            func.min(Participant.igt_ms).label("igt_ms"),
+        )
+        .join(Race, Participant.race_id == Race.id)
+        .where(
+            Race.status == RaceStatus.FINISHED,
+            Participant.status == ParticipantStatus.FINISHED,
+        )
+        .group_by(Participant.race_id)
+        .subquery()
+    )
+    result = await db.execute(
+        select(Participant.id)
+        .join(
+            winning_igt,
+            and_(
+                Participant.race_id == winning_igt.c.race_id,
+                Participant.igt_ms == winning_igt.c.igt_ms,
+            ),
+        )
+        .where(
+            Participant.user_id == user_id,
+            Participant.status == ParticipantStatus.FINISHED,
+        )
+        .limit(1)
+    )
+    return result.scalar_one_or_none() is not None
+
+
+async def compute_badges(
+    db: AsyncSession,
+    user_id: uuid.UUID,
+    organizer_id: uuid.UUID,
+) -> list[str]:
+    """Badges of a user joining a race organized by organizer_id."""
+    badges = []
+    if user_id == organizer_id:
+        badges.append(BADGE_ORGANIZER)
+    if await has_won_a_race(db, user_id):
+        badges.append(BADGE_PREVIOUS_WINNER)
+    return badges
+
+
+def participant_badges(stored: list[str] | None, *, is_live: bool) -> list[str]:
+    """Badges sent in ParticipantInfo: the stored ones, then streamer while live."""
+    badges = list(stored or [])
+    if is_live:
+        badges.append(BADGE_STREAMER)
+    return badges
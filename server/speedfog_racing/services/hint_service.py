@@ -0,0 +1,60 @@
+"""Community exit hints.
+
+Spoiler-free annotations for fog gates ("Leads to a legacy dungeon", "Dead
+end"), contributed by the community in data/exit_hints.json and keyed by the
+zone_id of the gate's destination. Hints describe what kind of area lies
+behind a gate without naming it, so they are safe to show on undiscovered
+exits.
+"""
+
+import json
+import logging
+from pathlib import Path
+from typing import Any
+
+logger = logging.getLogger(__name__)
+
+_HINTS_FILE = Path(__file__).parent.parent.parent / "data" / "exit_hints.json"
+
+
+def load_exit_hints() -> dict[str, str]:
+    """Load the destination zone_id → hint text mapping from exit_hints.json.
+
+    Returns an empty mapping when the file is missing, so hints stay optional.
+    """
+    if not _HINTS_FILE.exists():
+        logger.warning("exit_hints.json not found, community hints disabled")
+        return {}
+    data = json.loads(_HINTS_FILE.read_text())
+    hints: dict[str, str] = data["hints"]
+    return hints
+
+
+def compute_exit_hints(
+    node_id: str,
+    graph_json: dict[str, Any],
+    hints_db: dict[str, str],
+) -> list[dict[str, Any]]:
+    """Hints for the exits of a node, as [{"exit_index": i, "text": ...}].
+
+    ``exit_index`` is the position of the exit in zone_update's ``exits``, so
+    malformed exits are skipped exactly like compute_zone_update() does. The
+    first zone of the destination node with a hint wins.
+    """
+    nodes: dict[str, Any] = graph_json.get("nodes", {})
+    node_data = nodes.get(node_id)
+    if not isinstance(node_data, dict):
+        return []
+
+    hints: list[dict[str, Any]] = []
+    exit_datas = [e for e in node_data.get("exits", []) if isinstance(e, dict)]
+    for index, exit_data in enumerate(exit_datas):
+        to_node = nodes.get(exit_data.get("to"))
+        if not isinstance(to_node, dict):
+            continue
+        for zone_id in to_node.get("zones", []):
+            text = hints_db.get(zone_id)
+            if text:
+                hints.append({"exit_index": index, "text": text})
+                break
+    return hints
@@ -8,6 +8,17 @@ def _format_zone_name(zone_id: str) -> str:
     return zone_id.replace("_", " ").title()
 
 
+def _exit_location(exit_data: dict[str, Any]) -> tuple[str, list[float]] | None:
+    """Fog gate map_id and map-local position, if the seed provides them."""
+    map_id = exit_data.get("map_id")
+    position = exit_data.get("position")
+    if not isinstance(map_id, str) or not isinstance(position, list) or len(position) != 3:
+        return None
+    if not all(isinstance(v, int | float) for v in position):
+        return None
+    return map_id, [float(v) for v in position]
+
+
 def get_layer_for_node(node_id: str, graph_json: dict[str, Any]) -> int:
     """Get layer for a node_id from graph_json nodes.
 
@@ -100,8 +111,13 @@ def compute_zone_update(
             "to_name": to_name,
             "discovered": isinstance(to_id, str) and to_id in discovered_ids,
         }
+        if isinstance(to_id, str):
+            ex["to_node_id"] = to_id
         if from_zone_label:
             ex["from_zone"] = from_zone_label
+        location = _exit_location(exit_data)
+        if location:
+            ex["map_id"], ex["position"] = location
         exits.append(ex)
 
     return {
@@ -126,3 +142,24 @@ def get_tier_for_node(node_id: str, graph_json: dict[str, Any]) -> int | None:
         if isinstance(tier, int | float):
             return int(tier)
     return None
+
+
+def get_display_name_for_node(node_id: str, graph_json: dict[str, Any]) -> str | None:
+    """Get the display name of a node_id from graph_json nodes.
+
+    Returns None if node not found or if display_name is missing.
+    """
+    nodes: dict[str, Any] = graph_json.get("nodes", {})
+    node_data = nodes.get(node_id, {})
+    if isinstance(node_data, dict):
+        display_name = node_data.get("display_name")
+        if isinstance(display_name, str):
+            return display_name
+    return None
+
+
+def is_boss_node(node_id: str, graph_json: dict[str, Any]) -> bool:
+    """Whether node_id is a boss arena: leaving it means its boss is down."""
+    nodes: dict[str, Any] = graph_json.get("nodes", {})
+    node_data = nodes.get(node_id, {})
+    return isinstance(node_data, dict) and node_data.get("type") == "boss_arena"
@@ -1,7 +1,11 @@
 """WebSocket handlers for SpeedFog Racing."""
 
 from speedfog_racing.websocket.manager import ConnectionManager, manager
-from speedfog_racing.websocket.mod import broadcast_race_start, handle_mod_websocket
+from speedfog_racing.websocket.mod import (
+    broadcast_race_start,
+    broadcast_seed_changed,
+    handle_mod_websocket,
+)
 from speedfog_racing.websocket.spectator import (
     broadcast_race_state_update,
     handle_spectator_websocket,
@@ -17,6 +21,7 @@ __all__ = [
     "handle_spectator_websocket",
     "broadcast_race_start",
     "broadcast_race_state_update",
+    "broadcast_seed_changed",
     "training_manager",
     "handle_training_mod_websocket",
     "handle_training_spectator_websocket",
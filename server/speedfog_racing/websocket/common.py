@@ -1,17 +1,29 @@
 """Shared WebSocket utilities used by both race and training handlers."""
 
 import asyncio
+import importlib
 import json
 import logging
+import time
+import uuid
+from collections import deque
+from collections.abc import Awaitable, Callable
 from dataclasses import dataclass
 from typing import Any
 
-from fastapi import WebSocket
+from fastapi import WebSocket, WebSocketDisconnect
 
 from speedfog_racing.services.grace_service import load_graces_mapping
+from speedfog_racing.services.hint_service import load_exit_hints
 from speedfog_racing.services.i18n import translate_zone_update
 from speedfog_racing.services.layer_service import compute_zone_update
-from speedfog_racing.websocket.schemas import AuthErrorMessage, ErrorMessage, PingMessage
+from speedfog_racing.websocket.schemas import (
+    AuthErrorMessage,
+    ClockSyncMessage,
+    ErrorMessage,
+    EventFlagAckMessage,
+    PingMessage,
+)
 
 logger = logging.getLogger(__name__)
 
@@ -19,6 +31,48 @@ HEARTBEAT_INTERVAL = 30.0  # seconds between pings
 SEND_TIMEOUT = 5.0  # seconds before a send is considered failed
 MOD_AUTH_TIMEOUT = 5.0  # seconds to wait for auth message
 
+# Highest mod protocol version this server speaks.
+# 1: original protocol, 2: version negotiation + clock_sync,
+# 3: event_flag correlation ids acknowledged by event_flag_ack,
+# 4: key item pickups (seed key_items, item_obtained),
+# 5: race objectives (auth_ok objectives, objective_complete),
+# 6: seed_changed pushed after a seed re-roll,
+# 7: character fingerprint (auth character, character, seed expected_character),
+# 8: ng_cycle when the character enters NG+,
+# 9: community exit hints (exit_hints_request, exit_hints),
+# 10: afk status (participant afk in the leaderboard),
+# 11: binary encoding negotiated at auth (auth encodings, auth_ok encoding),
+# 12: save_reloaded when IGT or the death count goes backwards,
+# 13: auth_ok permissions (features a race turns off),
+# 14: pause_request, pause_granted and resume (participant pause in the leaderboard),
+# 15: race checkpoints (auth_ok checkpoints, checkpoint_crossed, participant checkpoints),
+# 16: kindling_used when a Messmer's Kindling is burnt,
+# 17: status_update disconnecting when the game closes,
+# 18: grace_discovered when a Site of Grace is touched,
+# 19: leaderboard generation, leaderboard_delta and leaderboard_resync,
+# 20: set_flag (organizer-triggered event flags, opt-in on the mod),
+# 21: auth_ok event_delay_secs (broadcast delay of the progress events),
+# 22: auth_ok zone_privacy, participant current_zone_name,
+# 23: event_flag idempotency_key (dedupe across game restarts),
+# 24: race_activity (participant milestones for the mod activity feed),
+# 25: timing_drift (IGT running faster or slower than the wall clock),
+# 26: participant badges (organizer, previous winner, streamer),
+# 27: participant zone_entry_igt (time spent in the current zone)
+MOD_PROTOCOL_VERSION = 27
+
+# Wire encodings of mod messages after auth (auth and auth_ok are always JSON)
+JSON_ENCODING = "json"
+MSGPACK_ENCODING = "msgpack"
+
+# MessagePack is optional: without the msgpack package, mods stay on JSON
+try:
+    _msgpack: Any = importlib.import_module("msgpack")
+except ImportError:
+    _msgpack = None
+
+# Processed event_flag ids remembered per participant for deduplication
+EVENT_FLAG_SEQ_HISTORY = 256
+
 
 async def heartbeat_loop(
     websocket: WebSocket,
@@ -39,6 +93,144 @@ async def heartbeat_loop(
             pass
 
 
+def negotiate_protocol_version(auth_msg: dict[str, Any]) -> int:
+    """Protocol version for a mod connection: the lower of the mod's and ours.
+
+    Mods predating negotiation send no protocol_version and speak version 1.
+    """
+    client_version = auth_msg.get("protocol_version")
+    if not isinstance(client_version, int) or isinstance(client_version, bool):
+        return 1
+    return max(1, min(client_version, MOD_PROTOCOL_VERSION))
+
+
+def negotiate_encoding(auth_msg: dict[str, Any], protocol_version: int) -> str:
+    """Encoding for a mod connection: MessagePack when the mod offers it (protocol 11+)."""
+    encodings = auth_msg.get("encodings")
+    if protocol_version < 11 or _msgpack is None or not isinstance(encodings, list):
+        return JSON_ENCODING
+    return MSGPACK_ENCODING if MSGPACK_ENCODING in encodings else JSON_ENCODING
+
+
+def pack_message(message: str) -> bytes:
+    """Re-encode a serialized JSON message as MessagePack, keeping its shape."""
+    packed: bytes = _msgpack.packb(json.loads(message))
+    return packed
+
+
+async def receive_mod_message(websocket: WebSocket) -> dict[str, Any] | None:
+    """Next mod message, from a JSON text frame or a MessagePack binary frame.
+
+    Returns None (after logging) for frames that don't decode to a message.
+    """
+    frame = await websocket.receive()
+    if frame["type"] == "websocket.disconnect":
+        raise WebSocketDisconnect(frame.get("code", 1000))
+    try:
+        if frame.get("bytes") is not None:
+            if _msgpack is None:
+                raise ValueError("MessagePack is not supported by this server")
+            msg = _msgpack.unpackb(frame["bytes"])
+        else:
+            msg = json.loads(frame.get("text") or "")
+    except Exception as e:
+        logger.warning(f"Invalid message from mod (ignored): {e}")
+        return None
+    if not isinstance(msg, dict):
+        logger.warning("Invalid message from mod (ignored): not an object")
+        return None
+    return msg
+
+
+class EventFlagDedup:
+    """Remembers processed event_flag correlation ids per participant.
+
+    Mods resend unacknowledged event flags with the same ``seq``, possibly on a
+    new connection, so ids are kept across reconnects (bounded per participant).
+    A restarted game starts a new ``seq`` range: from protocol 23 on, flags also
+    carry an ``idempotency_key`` that stays the same, remembered the same way.
+    """
+
+    def __init__(self, max_per_participant: int = EVENT_FLAG_SEQ_HISTORY) -> None:
+        self._max = max_per_participant
+        self._seen: dict[uuid.UUID, deque[int]] = {}
+        self._seen_keys: dict[uuid.UUID, deque[str]] = {}
+
+    def seen(self, participant_id: uuid.UUID, seq: int) -> bool:
+        return seq in self._seen.get(participant_id, ())
+
+    def remember(self, participant_id: uuid.UUID, seq: int) -> None:
+        self._seen.setdefault(participant_id, deque(maxlen=self._max)).append(seq)
+
+    def seen_key(self, participant_id: uuid.UUID, key: str) -> bool:
+        return key in self._seen_keys.get(participant_id, ())
+
+    def remember_key(self, participant_id: uuid.UUID, key: str) -> None:
+        self._seen_keys.setdefault(participant_id, deque(maxlen=self._max)).append(key)
+
+
+event_flag_dedup = EventFlagDedup()
+
+
+def parse_event_flag_seq(msg: dict[str, Any]) -> int | None:
+    """Correlation id of an event_flag, None for mods before protocol 3."""
+    seq = msg.get("seq")
+    if not isinstance(seq, int) or isinstance(seq, bool):
+        return None
+    return seq
+
+
+def parse_event_flag_key(msg: dict[str, Any]) -> str | None:
+    """Idempotency key of an event_flag, None for mods before protocol 23."""
+    key = msg.get("idempotency_key")
+    if not isinstance(key, str) or not key:
+        return None
+    return key
+
+
+async def handle_event_flag_once(
+    websocket: WebSocket,
+    participant_id: uuid.UUID,
+    msg: dict[str, Any],
+    handler: Callable[[], Awaitable[None]],
+    *,
+    dedup: EventFlagDedup = event_flag_dedup,
+    send_timeout: float = SEND_TIMEOUT,
+) -> None:
+    """Run ``handler`` for an event_flag unless its ``seq`` or its
+    ``idempotency_key`` was already processed.
+
+    Flags carrying a ``seq`` are acknowledged once handled (or when recognized
+    as a resend). If the handler raises, no ack is sent and the mod resends.
+    """
+    seq = parse_event_flag_seq(msg)
+    key = parse_event_flag_key(msg)
+    if seq is None and key is None:
+        await handler()
+        return
+
+    if seq is not None and dedup.seen(participant_id, seq):
+        logger.info("Duplicate event_flag seq=%s from %s (acked again)", seq, participant_id)
+    elif key is not None and dedup.seen_key(participant_id, key):
+        logger.info("Duplicate event_flag key=%s from %s (acked again)", key, participant_id)
+        if seq is not None:
+            dedup.remember(participant_id, seq)
+    else:
+        await handler()
+        if seq is not None:
+            dedup.remember(participant_id, seq)
+        if key is not None:
+            dedup.remember_key(participant_id, key)
+
+    if seq is None:
+        return
+    ack = EventFlagAckMessage(seq=seq)
+    try:
+        await asyncio.wait_for(websocket.send_text(ack.model_dump_json()), timeout=send_timeout)
+    except Exception:
+        pass
+
+
 async def send_auth_error(websocket: WebSocket, message: str) -> None:
     """Send auth error and close connection."""
     logger.warning("Auth error: %s", message)
@@ -63,6 +255,25 @@ async def send_error(
         pass
 
 
+async def send_clock_sync(
+    websocket: WebSocket, msg: dict[str, Any], *, send_timeout: float = SEND_TIMEOUT
+) -> None:
+    """Answer a clock_sync probe with the server's wall clock (ms since epoch).
+
+    The mod combines both timestamps with the round-trip time to estimate its
+    clock offset and display the authoritative race clock.
+    """
+    client_time_ms = msg.get("client_time_ms")
+    if not isinstance(client_time_ms, int) or isinstance(client_time_ms, bool):
+        logger.warning("Invalid clock_sync from mod (ignored): %r", client_time_ms)
+        return
+    reply = ClockSyncMessage(client_time_ms=client_time_ms, server_time_ms=int(time.time() * 1000))
+    try:
+        await asyncio.wait_for(websocket.send_text(reply.model_dump_json()), timeout=send_timeout)
+    except Exception:
+        pass
+
+
 async def send_zone_update(
     websocket: WebSocket,
     node_id: str,
@@ -93,6 +304,17 @@ def get_graces_mapping() -> dict[str, dict[str, Any]]:
     return _graces_mapping
 
 
+_exit_hints: dict[str, str] | None = None
+
+
+def get_exit_hints() -> dict[str, str]:
+    """Lazily load and cache the community exit hints."""
+    global _exit_hints
+    if _exit_hints is None:
+        _exit_hints = load_exit_hints()
+    return _exit_hints
+
+
 def extract_event_ids(graph_json: dict[str, Any]) -> tuple[list[int], int | None]:
     """Extract sorted event_ids and finish_event from graph_json."""
     finish_event_id: int | None = None
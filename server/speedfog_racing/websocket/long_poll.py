@@ -0,0 +1,193 @@
+"""HTTP long-poll fallback for mods that can't open a WebSocket.
+
+Some networks (corporate proxies, strict firewalls) block the WebSocket
+upgrade. The mod then falls back to plain HTTP: it opens a session, posts the
+messages it would have sent as text frames, and long-polls for the server's.
+A `LongPollSocket` stands in for the WebSocket so the regular mod handlers
+run unchanged, in a background task per session. Long-poll sessions always
+use JSON: binary frames can't be carried.
+"""
+
+import asyncio
+import logging
+import time
+import uuid
+from collections.abc import Awaitable, Callable
+from typing import Any
+
+from fastapi import WebSocketDisconnect
+from pydantic import BaseModel
+
+logger = logging.getLogger(__name__)
+
+POLL_WAIT_MAX = 25.0  # seconds a poll is held open when nothing is queued
+SESSION_IDLE_TIMEOUT = 60.0  # seconds without a poll before a session is dropped
+MAX_SESSIONS = 1000
+MAX_SESSIONS_PER_CLIENT = 16  # per address, so one client can't take all the sessions
+
+
+class LongPollOpened(BaseModel):
+    session_id: uuid.UUID
+
+
+class LongPollSend(BaseModel):
+    messages: list[str]
+
+
+class LongPollBatch(BaseModel):
+    messages: list[str]
+    closed: bool
+    close_reason: str | None = None
+
+
+class LongPollSocket:
+    """The part of the Starlette WebSocket API the mod handlers use, over HTTP."""
+
+    def __init__(self, *, idle_timeout: float = SESSION_IDLE_TIMEOUT) -> None:
+        self._inbox: asyncio.Queue[dict[str, Any]] = asyncio.Queue()
+        self._outbox: list[str] = []
+        self._outbox_ready = asyncio.Event()
+        self._idle_timeout = idle_timeout
+        self.last_polled = time.monotonic()
+        self.closed = False
+        self.close_code: int | None = None
+        self.close_reason: str | None = None
+
+    # --- WebSocket side (mod handlers) ---
+
+    async def accept(self) -> None:
+        pass
+
+    async def receive(self) -> dict[str, Any]:
+        if self.closed and self._inbox.empty():
+            return {"type": "websocket.disconnect", "code": self.close_code or 1000}
+        return await self._inbox.get()
+
+    async def receive_text(self) -> str:
+        frame = await self.receive()
+        if frame["type"] == "websocket.disconnect":
+            raise WebSocketDisconnect(frame.get("code", 1000))
+        text: str = frame["text"]
+        return text
+
+    async def send_text(self, data: str) -> None:
+        if self.closed:
+            raise RuntimeError("Long-poll session is closed")
+        if self.is_idle():
+            # The mod stopped polling: fail the send so the heartbeat closes us
+            raise RuntimeError("Long-poll session is idle")
+        self._outbox.append(data)
+        self._outbox_ready.set()
+
+    async def send_bytes(self, data: bytes) -> None:
+        raise RuntimeError("Long-poll sessions only carry JSON")
+
+    async def close(self, code: int = 1000, reason: str | None = None) -> None:
+        self.shutdown(code, reason)
+
+    # --- HTTP side (mod requests) ---
+
+    def push(self, messages: list[str]) -> None:
+        """Messages posted by the mod, handed to the handler in order."""
+        for text in messages:
+            self._inbox.put_nowait({"type": "websocket.receive", "text": text})
+
+    async def poll(self, wait: float) -> tuple[list[str], bool]:
+        """Messages queued for the mod, waiting up to `wait` seconds for one.
+
+        Returns the messages and whether the session is closed.
+        """
+        self.last_polled = time.monotonic()
+        if not self._outbox and not self.closed:
+            try:
+                await asyncio.wait_for(self._outbox_ready.wait(), timeout=wait)
+            except TimeoutError:
+                pass
+        self.last_polled = time.monotonic()
+        messages, self._outbox = self._outbox, []
+        self._outbox_ready.clear()
+        return messages, self.closed and not messages
+
+    def shutdown(self, code: int = 1000, reason: str | None = None) -> None:
+        """Close the session: the handler sees a disconnect, the mod a closed poll."""
+        if self.closed:
+            return
+        self.closed = True
+        self.close_code = code
+        self.close_reason = reason
+        self._inbox.put_nowait({"type": "websocket.disconnect", "code": code})
+        self._outbox_ready.set()
+
+    def is_idle(self, now: float | None = None) -> bool:
+        now = time.monotonic() if now is None else now
+        return now - self.last_polled > self._idle_timeout
+
+
+SessionHandler = Callable[[LongPollSocket], Awaitable[None]]
+
+
+class LongPollSessions:
+    """Open long-poll sessions, each running a mod handler in the background."""
+
+    def __init__(self, *, idle_timeout: float = SESSION_IDLE_TIMEOUT) -> None:
+        self._sessions: dict[uuid.UUID, tuple[LongPollSocket, asyncio.Task[None], str]] = {}
+        self._idle_timeout = idle_timeout
+
+    def open(self, handler: SessionHandler, client: str = "") -> uuid.UUID | None:
+        """Start a session for `handler`, None when too many are open, overall
+        or from `client` (the requester's address)."""
+        self.expire_idle()
+        if len(self._sessions) >= MAX_SESSIONS:
+            return None
+        if self.count(client) >= MAX_SESSIONS_PER_CLIENT:
+            logger.warning(f"Long-poll session refused: too many from {client}")
+            return None
+        session_id = uuid.uuid4()
+        socket = LongPollSocket(idle_timeout=self._idle_timeout)
+        task = asyncio.create_task(self._run(session_id, socket, handler))
+        self._sessions[session_id] = (socket, task, client)
+        return session_id
+
+    def get(self, session_id: uuid.UUID) -> LongPollSocket | None:
+        entry = self._sessions.get(session_id)
+        return entry[0] if entry else None
+
+    def count(self, client: str) -> int:
+        """Sessions held by `client`, closed ones included until forgotten."""
+        return sum(1 for _, _, owner in self._sessions.values() if owner == client)
+
+    async def close(self, session_id: uuid.UUID) -> None:
+        socket = self.get(session_id)
+        if socket:
+            await socket.close()
+
+    def expire_idle(self, now: float | None = None) -> None:
+        """Close sessions the mod stopped polling, so their handlers clean up,
+        and forget the closed ones whose handler is done."""
+        for session_id, (socket, task, _) in list(self._sessions.items()):
+            if not socket.is_idle(now):
+                continue
+            if not socket.closed:
+                logger.info(f"Long-poll session expired: {session_id}")
+                socket.shutdown(1001, "Session expired")
+            elif task.done():
+                del self._sessions[session_id]
+
+    def __len__(self) -> int:
+        return len(self._sessions)
+
+    async def _run(
+        self, session_id: uuid.UUID, socket: LongPollSocket, handler: SessionHandler
+    ) -> None:
+        try:
+            await handler(socket)
+        except WebSocketDisconnect:
+            pass
+        except Exception:
+            logger.exception(f"Error in long-poll session: {session_id}")
+        finally:
+            # Kept until the mod stops polling, so it sees the close
+            socket.shutdown()
+
+
+long_poll_sessions = LongPollSessions()
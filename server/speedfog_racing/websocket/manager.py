@@ -9,9 +9,20 @@ from typing import Any
 from fastapi import WebSocket
 
 from speedfog_racing.models import Participant
-from speedfog_racing.services.layer_service import get_layer_for_node, get_tier_for_node
+from speedfog_racing.services.badge_service import participant_badges
+from speedfog_racing.services.layer_service import (
+    get_display_name_for_node,
+    get_layer_for_node,
+    get_tier_for_node,
+)
 from speedfog_racing.services.twitch_live import twitch_live_service
+from speedfog_racing.websocket.common import JSON_ENCODING, MSGPACK_ENCODING, pack_message
 from speedfog_racing.websocket.schemas import (
+    ZONE_PRIVACY_NAME,
+    ZONE_PRIVACY_TIER,
+    CharacterFingerprint,
+    CheckpointTime,
+    LeaderboardDeltaMessage,
     LeaderboardUpdateMessage,
     ParticipantInfo,
     PlayerUpdateMessage,
@@ -23,6 +34,10 @@ logger = logging.getLogger(__name__)
 
 SEND_TIMEOUT = 5.0  # seconds before a send is considered failed
 
+# Pause states of a participant (ParticipantInfo.pause)
+PAUSE_REQUESTED = "requested"
+PAUSE_GRANTED = "paused"
+
 
 @dataclass
 class ModConnection:
@@ -32,6 +47,16 @@ class ModConnection:
     participant_id: uuid.UUID
     user_id: uuid.UUID
     locale: str = "en"
+    protocol_version: int = 1
+    character: CharacterFingerprint | None = None
+    # Game completions of the loaded character (0 = first playthrough)
+    clear_count: int = 0
+    # The player reported being idle (cleared with the connection)
+    afk: bool = False
+    # Encoding negotiated at auth for the messages the server broadcasts
+    encoding: str = JSON_ENCODING
+    # Holds the room's latest leaderboard: gets deltas from then on (protocol 19+)
+    leaderboard_synced: bool = False
 
 
 @dataclass
@@ -51,18 +76,55 @@ class RaceRoom:
     # participant_id -> connection
     mods: dict[uuid.UUID, ModConnection] = field(default_factory=dict)
     spectators: list[SpectatorConnection] = field(default_factory=list)
+    # Number of the last leaderboard broadcast, and its content as mods got it for resyncs
+    leaderboard_generation: int = 0
+    leaderboard: list[ParticipantInfo] = field(default_factory=list)
+    leader_splits: dict[int, int] | None = None
+    # Race's zone_privacy: what the mods are sent of the participants' zones
+    zone_privacy: str = ZONE_PRIVACY_NAME
+
+    def afk_ids(self) -> set[uuid.UUID]:
+        """Participants whose mod reports them idle."""
+        return {pid for pid, conn in self.mods.items() if conn.afk}
+
+    async def broadcast_to_mods(
+        self,
+        message: str,
+        *,
+        min_protocol_version: int = 1,
+        participant_ids: set[uuid.UUID] | None = None,
+    ) -> None:
+        """Send message to all connected mods concurrently with timeout.
 
-    async def broadcast_to_mods(self, message: str) -> None:
-        """Send message to all connected mods concurrently with timeout."""
+        Mods that negotiated a protocol older than min_protocol_version are skipped,
+        as are those not in participant_ids when given.
+        """
         if not self.mods:
             return
 
         # Snapshot to avoid issues with concurrent dict modification
         snapshot = dict(self.mods)
+        if participant_ids is not None:
+            snapshot = {pid: conn for pid, conn in snapshot.items() if pid in participant_ids}
+        if min_protocol_version > 1:
+            snapshot = {
+                pid: conn
+                for pid, conn in snapshot.items()
+                if conn.protocol_version >= min_protocol_version
+            }
+
+        # Encoded once for all the MessagePack connections
+        packed: bytes | None = None
+        if any(conn.encoding == MSGPACK_ENCODING for conn in snapshot.values()):
+            packed = pack_message(message)
 
         async def _send(participant_id: uuid.UUID, conn: ModConnection) -> uuid.UUID | None:
             try:
-                await asyncio.wait_for(conn.websocket.send_text(message), timeout=SEND_TIMEOUT)
+                if packed is not None and conn.encoding == MSGPACK_ENCODING:
+                    send = conn.websocket.send_bytes(packed)
+                else:
+                    send = conn.websocket.send_text(message)
+                await asyncio.wait_for(send, timeout=SEND_TIMEOUT)
             except Exception:
                 return participant_id
             return None
@@ -111,6 +173,8 @@ class ConnectionManager:
 
     def __init__(self) -> None:
         self.rooms: dict[uuid.UUID, RaceRoom] = {}
+        # participant_id -> pause state, kept across mod reconnections
+        self.pauses: dict[uuid.UUID, str] = {}
 
     def get_or_create_room(self, race_id: uuid.UUID) -> RaceRoom:
         """Get or create a room for a race."""
@@ -129,14 +193,22 @@ class ConnectionManager:
         user_id: uuid.UUID,
         websocket: WebSocket,
         locale: str = "en",
+        protocol_version: int = 1,
+        character: CharacterFingerprint | None = None,
+        encoding: str = JSON_ENCODING,
+        zone_privacy: str = ZONE_PRIVACY_NAME,
     ) -> None:
         """Register a mod connection."""
         room = self.get_or_create_room(race_id)
+        room.zone_privacy = zone_privacy
         room.mods[participant_id] = ModConnection(
             websocket=websocket,
             participant_id=participant_id,
             user_id=user_id,
             locale=locale,
+            protocol_version=protocol_version,
+            character=character,
+            encoding=encoding,
         )
         logger.info(f"Mod connected: race={race_id}, participant={participant_id}")
 
@@ -189,6 +261,33 @@ class ConnectionManager:
 
         logger.info(f"Closed room: race={race_id}")
 
+    async def send_to_mod(
+        self, race_id: uuid.UUID, participant_id: uuid.UUID, message: str
+    ) -> bool:
+        """Send a message to one connected mod in its negotiated encoding.
+
+        Returns False when the mod is not connected or the send failed.
+        """
+        room = self.get_room(race_id)
+        conn = room.mods.get(participant_id) if room else None
+        if conn is None:
+            return False
+        try:
+            if conn.encoding == MSGPACK_ENCODING:
+                send = conn.websocket.send_bytes(pack_message(message))
+            else:
+                send = conn.websocket.send_text(message)
+            await asyncio.wait_for(send, timeout=SEND_TIMEOUT)
+        except Exception:
+            return False
+        return True
+
+    def mod_protocol_version(self, race_id: uuid.UUID, participant_id: uuid.UUID) -> int:
+        """Protocol version negotiated by a connected mod, 0 when not connected."""
+        room = self.get_room(race_id)
+        conn = room.mods.get(participant_id) if room else None
+        return conn.protocol_version if conn else 0
+
     def is_mod_connected(self, race_id: uuid.UUID, participant_id: uuid.UUID) -> bool:
         """Check if a mod is connected."""
         room = self.get_room(race_id)
@@ -208,6 +307,7 @@ class ConnectionManager:
 
         sorted_participants = sort_leaderboard(participants, graph_json=graph_json)
         connected_ids = set(room.mods.keys())
+        afk_ids = room.afk_ids()
 
         # Compute leader splits for gap timing
         leader_splits: dict[int, int] = {}
@@ -224,6 +324,8 @@ class ConnectionManager:
             participant_to_info(
                 p,
                 connected_ids=connected_ids,
+                afk_ids=afk_ids,
+                pauses=self.pauses,
                 graph_json=graph_json,
                 gap_ms=compute_gap_ms(
                     p.status.value,
@@ -246,11 +348,71 @@ class ConnectionManager:
             for i, p in enumerate(sorted_participants)
         ]
 
-        message = LeaderboardUpdateMessage(
+        await self._send_leaderboard(room, participant_infos, leader_splits or None)
+
+    async def _send_leaderboard(
+        self,
+        room: RaceRoom,
+        participant_infos: list[ParticipantInfo],
+        leader_splits: dict[int, int] | None,
+    ) -> None:
+        """Send a new leaderboard generation.
+
+        Mods holding the previous generation (protocol 19+) only get the rows
+        that changed and the new order. Spectators, older mods and mods that
+        just connected get the full leaderboard. Mods only get the zones the
+        race's zone privacy shows.
+        """
+        mod_infos = [redact_zone(info, room.zone_privacy) for info in participant_infos]
+        previous = {info.id: info for info in room.leaderboard}
+        room.leaderboard_generation += 1
+        room.leaderboard = mod_infos
+        room.leader_splits = leader_splits
+
+        full = LeaderboardUpdateMessage(
+            participants=mod_infos,
+            leader_splits=leader_splits,
+            generation=room.leaderboard_generation,
+        )
+        delta = LeaderboardDeltaMessage(
+            generation=room.leaderboard_generation,
+            participants=[info for info in mod_infos if previous.get(info.id) != info],
+            order=[info.id for info in mod_infos],
+            leader_splits=leader_splits,
+        )
+        spectators = LeaderboardUpdateMessage(
             participants=participant_infos,
-            leader_splits=leader_splits if leader_splits else None,
+            leader_splits=leader_splits,
+            generation=room.leaderboard_generation,
         )
-        await room.broadcast_to_all(message.model_dump_json())
+        synced = {pid for pid, conn in room.mods.items() if conn.leaderboard_synced}
+        unsynced = set(room.mods) - synced
+        for pid in unsynced:
+            conn = room.mods[pid]
+            conn.leaderboard_synced = conn.protocol_version >= 19
+        await asyncio.gather(
+            room.broadcast_to_mods(full.model_dump_json(), participant_ids=unsynced),
+            room.broadcast_to_mods(delta.model_dump_json(), participant_ids=synced),
+            room.broadcast_to_spectators(spectators.model_dump_json()),
+        )
+
+    async def resync_leaderboard(self, race_id: uuid.UUID, participant_id: uuid.UUID) -> None:
+        """Send the latest leaderboard to a mod that missed a delta."""
+        room = self.get_room(race_id)
+        conn = room.mods.get(participant_id) if room else None
+        if room is None or conn is None:
+            return
+        conn.leaderboard_synced = False
+        if not room.leaderboard_generation:
+            return  # Nothing broadcast yet: the next one is sent in full
+        message = LeaderboardUpdateMessage(
+            participants=room.leaderboard,
+            leader_splits=room.leader_splits,
+            generation=room.leaderboard_generation,
+        )
+        conn.leaderboard_synced = True
+        if not await self.send_to_mod(race_id, participant_id, message.model_dump_json()):
+            conn.leaderboard_synced = False
 
     async def broadcast_player_update(
         self,
@@ -270,19 +432,23 @@ class ConnectionManager:
             return
 
         connected_ids = set(room.mods.keys())
-        message = PlayerUpdateMessage(
-            player=participant_to_info(
-                participant,
-                connected_ids=connected_ids,
-                graph_json=graph_json,
-                layer_entry_igt=get_layer_entry_igt(
-                    participant.zone_history, participant.current_layer, graph_json
-                )
-                if graph_json
-                else None,
+        info = participant_to_info(
+            participant,
+            connected_ids=connected_ids,
+            afk_ids=room.afk_ids(),
+            pauses=self.pauses,
+            graph_json=graph_json,
+            layer_entry_igt=get_layer_entry_igt(
+                participant.zone_history, participant.current_layer, graph_json
             )
+            if graph_json
+            else None,
+        )
+        mod_message = PlayerUpdateMessage(player=redact_zone(info, room.zone_privacy))
+        await asyncio.gather(
+            room.broadcast_to_mods(mod_message.model_dump_json()),
+            room.broadcast_to_spectators(PlayerUpdateMessage(player=info).model_dump_json()),
         )
-        await room.broadcast_to_all(message.model_dump_json())
 
     async def _broadcast_spectator_count(self, room: RaceRoom) -> None:
         """Broadcast spectator count to all spectators in a room."""
@@ -350,6 +516,20 @@ def get_layer_entry_igt(
     return None
 
 
+def get_zone_entry_igt(
+    zone_history: list[dict[str, Any]] | None,
+    current_zone: str | None,
+) -> int | None:
+    """Get the player's IGT when they entered their current zone (latest visit)."""
+    if not zone_history or current_zone is None:
+        return None
+    last = zone_history[-1]
+    igt = last.get("igt_ms")
+    if last.get("node_id") != current_zone or igt is None:
+        return None
+    return int(igt)
+
+
 def compute_gap_ms(
     status: str,
     *,
@@ -392,15 +572,21 @@ def participant_to_info(
     participant: Participant,
     *,
     connected_ids: set[uuid.UUID] | None = None,
+    afk_ids: set[uuid.UUID] | None = None,
+    pauses: dict[uuid.UUID, str] | None = None,
     graph_json: dict[str, Any] | None = None,
     gap_ms: int | None = None,
     layer_entry_igt: int | None = None,
 ) -> ParticipantInfo:
     """Convert a Participant model to ParticipantInfo schema."""
-    # Compute tier on the fly from current_zone + graph_json
+    # Compute tier and zone name on the fly from current_zone + graph_json
     tier: int | None = None
+    zone_name: str | None = None
     if graph_json and participant.current_zone:
         tier = get_tier_for_node(participant.current_zone, graph_json)
+        zone_name = get_display_name_for_node(participant.current_zone, graph_json)
+
+    is_live = twitch_live_service.is_live(participant.user.twitch_username)
 
     return ParticipantInfo(
         id=str(participant.id),
@@ -408,20 +594,53 @@ def participant_to_info(
         twitch_display_name=participant.user.twitch_display_name,
         status=participant.status.value,
         current_zone=participant.current_zone,
+        current_zone_name=zone_name,
         current_layer=participant.current_layer,
         current_layer_tier=tier,
         igt_ms=participant.igt_ms,
         death_count=participant.death_count,
         color_index=participant.color_index,
         mod_connected=participant.id in connected_ids if connected_ids else False,
+        afk=participant.id in afk_ids if afk_ids else False,
         zone_history=participant.zone_history,
         gap_ms=gap_ms,
         layer_entry_igt=layer_entry_igt,
-        is_live=twitch_live_service.is_live(participant.user.twitch_username),
+        zone_entry_igt=get_zone_entry_igt(participant.zone_history, participant.current_zone),
+        is_live=is_live,
         stream_url=twitch_live_service.stream_url(participant.user.twitch_username),
+        key_items=[entry["item_id"] for entry in participant.key_items or []],
+        objectives_completed=[
+            entry["objective_id"] for entry in participant.objectives_completed or []
+        ],
+        checkpoints=[CheckpointTime(**entry) for entry in participant.checkpoints or []],
+        save_reloads=len(participant.save_reloads or []),
+        kindling_used=sum(entry["used"] for entry in participant.kindling_uses or []),
+        graces_found=len(participant.graces_found or []),
+        timing_anomalies=len(participant.timing_anomalies or []),
+        badges=participant_badges(participant.badges, is_live=is_live),
+        pause=pauses.get(participant.id) if pauses else None,
     )
 
 
+def redact_zone(info: ParticipantInfo, zone_privacy: str) -> ParticipantInfo:
+    """Participant info as mods receive it under the race's zone privacy.
+
+    With ``tier`` the zone and the zones visited are left out, with ``hidden``
+    the tier too, so mods (older ones included) and their message traces never
+    hold more than the race shows. Spectators get the full info.
+    """
+    if zone_privacy == ZONE_PRIVACY_NAME:
+        return info
+    update: dict[str, Any] = {
+        "current_zone": None,
+        "current_zone_name": None,
+        "zone_history": None,
+    }
+    if zone_privacy != ZONE_PRIVACY_TIER:
+        update["current_layer_tier"] = None
+    return info.model_copy(update=update)
+
+
 def sort_leaderboard(
     participants: list[Participant],
     *,
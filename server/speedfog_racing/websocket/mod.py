@@ -5,49 +5,105 @@ import json
 import logging
 import uuid
 from datetime import UTC, datetime
+from functools import partial
 from typing import Any
 
 from fastapi import WebSocket, WebSocketDisconnect
+from pydantic import ValidationError
 from sqlalchemy import select
 from sqlalchemy.ext.asyncio import AsyncSession, async_sessionmaker
 from sqlalchemy.orm import selectinload
 
 from speedfog_racing.discord import fire_race_finished_notifications
-from speedfog_racing.models import Caster, Participant, ParticipantStatus, Race, RaceStatus
+from speedfog_racing.models import (
+    Caster,
+    Participant,
+    ParticipantStatus,
+    Race,
+    RaceStatus,
+    Seed,
+)
 from speedfog_racing.services.grace_service import resolve_zone_query
+from speedfog_racing.services.hint_service import compute_exit_hints
 from speedfog_racing.services.layer_service import (
+    get_display_name_for_node,
     get_layer_for_node,
     get_start_node,
+    is_boss_node,
 )
 from speedfog_racing.services.race_lifecycle import check_race_auto_finish
 from speedfog_racing.websocket.common import (
+    JSON_ENCODING,
     MOD_AUTH_TIMEOUT,
     extract_event_ids,
+    get_exit_hints,
     get_graces_mapping,
+    handle_event_flag_once,
     heartbeat_loop,
+    negotiate_encoding,
+    negotiate_protocol_version,
     parse_zone_query_input,
+    receive_mod_message,
     send_auth_error,
+    send_clock_sync,
     send_error,
     send_zone_update,
 )
 from speedfog_racing.websocket.manager import (
+    PAUSE_REQUESTED,
     manager,
     participant_to_info,
+    redact_zone,
     sort_leaderboard,
 )
 from speedfog_racing.websocket.schemas import (
+    ACTIVITY_BOSS,
+    ACTIVITY_FINISH,
+    ACTIVITY_ZONE,
+    ZONE_PRIVACY_NAME,
     AuthOkMessage,
+    CharacterFingerprint,
+    Checkpoint,
+    ExitHint,
+    ExitHintsMessage,
+    ItemObtainedMessage,
+    KeyItem,
+    Objective,
     ParticipantInfo,
+    RaceActivityMessage,
     RaceInfo,
     RaceStartMessage,
+    SeedChangedMessage,
     SeedInfo,
+    extract_checkpoints,
+    extract_event_delay,
+    extract_expected_character,
+    extract_key_items,
+    extract_objectives,
+    extract_permissions,
+    extract_segments,
     extract_spawn_items,
+    extract_start_location,
+    extract_zone_privacy,
 )
 from speedfog_racing.websocket.spectator import broadcast_race_state_update
 
 logger = logging.getLogger(__name__)
 
 
+# Longest each participant event list may grow: a misbehaving mod can't
+# bloat the participant row
+PARTICIPANT_EVENT_CAPS = {
+    "save_reloads": 200,
+    "kindling_uses": 50,
+    "timing_anomalies": 200,
+    "graces_found": 500,
+}
+
+# Event flag blocks of the graces' unlock flags (the mod's core::graces)
+GRACE_FLAG_RANGES = (range(71000, 74000), range(76000, 77000))
+
+
 def _get_graph_json(participant: Participant) -> dict[str, Any] | None:
     """Get graph_json from participant's race seed."""
     seed = participant.race.seed
@@ -108,6 +164,9 @@ async def handle_mod_websocket(
             return
 
         mod_token = auth_msg["mod_token"]
+        protocol_version = negotiate_protocol_version(auth_msg)
+        encoding = negotiate_encoding(auth_msg, protocol_version)
+        character = parse_character(auth_msg.get("character"))
 
         # Auth phase: open session, authenticate, send auth_ok, close session
         async with session_maker() as db:
@@ -135,12 +194,13 @@ async def handle_mod_websocket(
             # Keep IDs for use after session closes
             participant_id = participant.id
             user_id = participant.user_id
+            zone_privacy = extract_zone_privacy(race.config)
 
             # Resolve locale from user preference
             if participant.user.locale:
                 mod_locale = participant.user.locale
 
-            await send_auth_ok(websocket, participant)
+            await send_auth_ok(websocket, participant, protocol_version, encoding)
 
             # Send zone_update on reconnect (race already running)
             seed = participant.race.seed
@@ -153,7 +213,22 @@ async def handle_mod_websocket(
         # Session closed — released back to pool
 
         # Register connection (includes locale)
-        await manager.connect_mod(race_id, participant_id, user_id, websocket, mod_locale)
+        await manager.connect_mod(
+            race_id,
+            participant_id,
+            user_id,
+            websocket,
+            mod_locale,
+            protocol_version,
+            character,
+            encoding,
+            zone_privacy,
+        )
+        if character:
+            logger.info(
+                f"Mod character: race={race_id}, participant={participant_id}, "
+                f"slot={character.slot}, class={character.class_id}, level={character.level}"
+            )
 
         # Broadcast updated connection status to all clients
         try:
@@ -172,24 +247,41 @@ async def handle_mod_websocket(
         try:
             # Main message loop
             while True:
-                data = await websocket.receive_text()
-                try:
-                    msg = json.loads(data)
-                except json.JSONDecodeError as e:
-                    logger.warning(f"Invalid JSON from mod (ignored): {e}")
+                msg = await receive_mod_message(websocket)
+                if msg is None:
                     continue
 
                 msg_type = msg.get("type")
 
                 if msg_type == "pong":
                     pass  # Heartbeat response, no action needed
+                elif msg_type == "clock_sync":
+                    await send_clock_sync(websocket, msg)
                 elif msg_type == "ready":
                     await handle_ready(session_maker, participant_id)
                 elif msg_type == "status_update":
                     await handle_status_update(websocket, session_maker, participant_id, msg)
+                    if msg.get("disconnecting") is True:
+                        # Game closing: drop the connection now rather than
+                        # waiting for the socket to time out
+                        logger.info(
+                            f"Mod quitting: race={race_id}, participant={participant_id}, "
+                            f"igt={msg.get('igt_ms')}"
+                        )
+                        break
                 elif msg_type == "event_flag":
-                    await handle_event_flag(
-                        websocket, session_maker, participant_id, msg, mod_locale
+                    await handle_event_flag_once(
+                        websocket,
+                        participant_id,
+                        msg,
+                        partial(
+                            handle_event_flag,
+                            websocket,
+                            session_maker,
+                            participant_id,
+                            msg,
+                            mod_locale,
+                        ),
                     )
                 elif msg_type == "finished":
                     await handle_finished(websocket, session_maker, participant_id, msg)
@@ -197,6 +289,32 @@ async def handle_mod_websocket(
                     await handle_zone_query(
                         websocket, session_maker, participant_id, msg, mod_locale
                     )
+                elif msg_type == "item_obtained":
+                    await handle_item_obtained(session_maker, participant_id, msg)
+                elif msg_type == "objective_complete":
+                    await handle_objective_complete(session_maker, participant_id, msg)
+                elif msg_type == "checkpoint_crossed":
+                    await handle_checkpoint_crossed(session_maker, participant_id, msg)
+                elif msg_type == "character":
+                    handle_character(race_id, participant_id, msg)
+                elif msg_type == "ng_cycle":
+                    handle_ng_cycle(race_id, participant_id, msg)
+                elif msg_type == "afk":
+                    await handle_afk(session_maker, race_id, participant_id, msg)
+                elif msg_type == "save_reloaded":
+                    await handle_save_reloaded(session_maker, participant_id, msg)
+                elif msg_type == "kindling_used":
+                    await handle_kindling_used(session_maker, participant_id, msg)
+                elif msg_type == "timing_drift":
+                    await handle_timing_drift(session_maker, participant_id, msg)
+                elif msg_type == "grace_discovered":
+                    await handle_grace_discovered(session_maker, participant_id, msg)
+                elif msg_type == "leaderboard_resync":
+                    await manager.resync_leaderboard(race_id, participant_id)
+                elif msg_type == "pause_request":
+                    await handle_pause_request(session_maker, race_id, participant_id, msg)
+                elif msg_type == "exit_hints_request":
+                    await handle_exit_hints_request(session_maker, race_id, participant_id, msg)
                 else:
                     logger.warning(f"Unknown message type: {msg_type}")
         finally:
@@ -225,6 +343,272 @@ async def handle_mod_websocket(
                 logger.warning(f"Failed to broadcast disconnect: race={race_id}")
 
 
+def parse_character(data: Any) -> CharacterFingerprint | None:
+    """Parse a character fingerprint sent by the mod, None if absent or malformed."""
+    if data is None:
+        return None
+    try:
+        return CharacterFingerprint.model_validate(data)
+    except ValidationError:
+        logger.warning("Invalid character fingerprint from mod (ignored)")
+        return None
+
+
+def is_other_character(previous: CharacterFingerprint, current: CharacterFingerprint) -> bool:
+    """Whether two fingerprints are different characters (leveling up doesn't count)."""
+    return (
+        previous.slot != current.slot
+        or previous.name != current.name
+        or previous.class_id != current.class_id
+    )
+
+
+def handle_character(race_id: uuid.UUID, participant_id: uuid.UUID, msg: dict[str, Any]) -> None:
+    """Handle a character (re)load: keep the fingerprint and log character switches."""
+    character = parse_character(msg.get("character"))
+    room = manager.get_room(race_id)
+    conn = room.mods.get(participant_id) if room else None
+    if character is None or conn is None:
+        return
+
+    previous = conn.character
+    conn.character = character
+    if previous is not None and is_other_character(previous, character):
+        logger.warning(
+            f"Mod character changed: race={race_id}, participant={participant_id}, "
+            f"slot {previous.slot} -> {character.slot}, "
+            f"class {previous.class_id} -> {character.class_id}"
+        )
+
+
+def handle_ng_cycle(race_id: uuid.UUID, participant_id: uuid.UUID, msg: dict[str, Any]) -> None:
+    """Handle an NG+ transition: seeds are built for a first playthrough, so log it."""
+    clear_count = msg.get("clear_count")
+    if not isinstance(clear_count, int) or isinstance(clear_count, bool) or clear_count < 0:
+        return
+    room = manager.get_room(race_id)
+    conn = room.mods.get(participant_id) if room else None
+    if conn is None:
+        return
+
+    conn.clear_count = clear_count
+    if clear_count > 0:
+        logger.warning(
+            f"Mod character in NG+{clear_count}: race={race_id}, participant={participant_id}, "
+            f"igt={msg.get('igt_ms')}"
+        )
+
+
+async def handle_afk(
+    session_maker: async_sessionmaker[AsyncSession],
+    race_id: uuid.UUID,
+    participant_id: uuid.UUID,
+    msg: dict[str, Any],
+) -> None:
+    """Handle the player going idle or coming back: grey them out on the leaderboard."""
+    afk = msg.get("afk")
+    if not isinstance(afk, bool):
+        return
+    room = manager.get_room(race_id)
+    conn = room.mods.get(participant_id) if room else None
+    if conn is None or conn.afk == afk:
+        return
+
+    conn.afk = afk
+    logger.info(
+        f"Mod {'AFK' if afk else 'back from AFK'}: race={race_id}, "
+        f"participant={participant_id}, igt={msg.get('igt_ms')}"
+    )
+    async with session_maker() as db:
+        participant = await _load_participant(db, participant_id)
+        if participant:
+            await manager.broadcast_leaderboard(
+                race_id, participant.race.participants, graph_json=_get_graph_json(participant)
+            )
+
+
+async def handle_pause_request(
+    session_maker: async_sessionmaker[AsyncSession],
+    race_id: uuid.UUID,
+    participant_id: uuid.UUID,
+    msg: dict[str, Any],
+) -> None:
+    """Handle a pause request: flag it on the leaderboard for the organizer to grant."""
+    if participant_id in manager.pauses:
+        return
+
+    async with session_maker() as db:
+        participant = await _load_participant(db, participant_id)
+        if not participant:
+            return
+
+        if participant.race.status != RaceStatus.RUNNING:
+            return
+
+        if participant.status != ParticipantStatus.PLAYING:
+            return
+
+    manager.pauses[participant_id] = PAUSE_REQUESTED
+    logger.info(
+        f"Mod pause requested: race={race_id}, participant={participant_id}, "
+        f"igt={msg.get('igt_ms')}"
+    )
+    # Session closed — safe to broadcast
+    await manager.broadcast_leaderboard(
+        race_id, participant.race.participants, graph_json=_get_graph_json(participant)
+    )
+
+
+def _is_count(value: Any) -> bool:
+    return isinstance(value, int) and not isinstance(value, bool) and value >= 0
+
+
+async def _record_participant_event(
+    session_maker: async_sessionmaker[AsyncSession],
+    participant_id: uuid.UUID,
+    column: str,
+    entry: dict[str, Any],
+    unique_key: str | None = None,
+) -> Participant | None:
+    """Append an entry to one of the participant's event lists and broadcast
+    the leaderboard.
+
+    Only recorded while the participant races, below the column's cap and,
+    with ``unique_key``, when no entry has the same value for it yet.
+    Returns the participant when recorded.
+    """
+    async with session_maker() as db:
+        participant = await _load_participant(db, participant_id)
+        if not participant:
+            return None
+
+        if participant.race.status != RaceStatus.RUNNING:
+            return None
+
+        if participant.status in (ParticipantStatus.FINISHED, ParticipantStatus.ABANDONED):
+            return None
+
+        entries = getattr(participant, column) or []
+        # Resent after a reconnection
+        if unique_key and any(e[unique_key] == entry[unique_key] for e in entries):
+            return None
+        if len(entries) >= PARTICIPANT_EVENT_CAPS[column]:
+            logger.warning(
+                f"Mod {column} full, entry dropped: race={participant.race_id}, "
+                f"participant={participant_id}"
+            )
+            return None
+
+        setattr(participant, column, [*entries, entry])
+        await db.commit()
+
+    # Session closed — safe to broadcast
+    await manager.broadcast_leaderboard(
+        participant.race_id,
+        participant.race.participants,
+        graph_json=_get_graph_json(participant),
+    )
+    return participant
+
+
+async def handle_save_reloaded(
+    session_maker: async_sessionmaker[AsyncSession],
+    participant_id: uuid.UUID,
+    msg: dict[str, Any],
+) -> None:
+    """Handle a save reload: record it so organizers can enforce no-savescum rules."""
+    fields = ("igt_ms", "igt_delta_ms", "death_count", "death_delta")
+    values = [msg.get(field) for field in fields]
+    if not all(isinstance(v, int) and not isinstance(v, bool) for v in values):
+        return
+    reload = dict(zip(fields, values, strict=True))
+
+    participant = await _record_participant_event(
+        session_maker, participant_id, "save_reloads", reload
+    )
+    if participant:
+        logger.warning(
+            f"Mod save reload: race={participant.race_id}, participant={participant_id}, "
+            f"igt={reload['igt_ms']} ({reload['igt_delta_ms']:+}ms), "
+            f"deaths={reload['death_count']} ({reload['death_delta']:+})"
+        )
+
+
+async def handle_kindling_used(
+    session_maker: async_sessionmaker[AsyncSession],
+    participant_id: uuid.UUID,
+    msg: dict[str, Any],
+) -> None:
+    """Handle a Messmer's Kindling use: record it for races that restrict burning."""
+    fields = ("igt_ms", "used", "remaining")
+    values = [msg.get(field) for field in fields]
+    if not all(_is_count(v) for v in values):
+        return
+    use = dict(zip(fields, values, strict=True))
+    if use["used"] == 0:
+        return
+
+    participant = await _record_participant_event(
+        session_maker, participant_id, "kindling_uses", use
+    )
+    if participant:
+        logger.info(
+            f"Mod kindling used: race={participant.race_id}, participant={participant_id}, "
+            f"igt={use['igt_ms']}, used={use['used']}, remaining={use['remaining']}"
+        )
+
+
+async def handle_timing_drift(
+    session_maker: async_sessionmaker[AsyncSession],
+    participant_id: uuid.UUID,
+    msg: dict[str, Any],
+) -> None:
+    """Handle IGT drifting from the wall clock: an integrity signal for organizers."""
+    fields = ("igt_ms", "igt_delta_ms", "wall_delta_ms")
+    values = [msg.get(field) for field in fields]
+    if not all(_is_count(v) for v in values):
+        return
+    drift = dict(zip(fields, values, strict=True))
+    if drift["wall_delta_ms"] == 0:
+        return
+
+    participant = await _record_participant_event(
+        session_maker, participant_id, "timing_anomalies", drift
+    )
+    if participant:
+        ratio = drift["igt_delta_ms"] / drift["wall_delta_ms"]
+        logger.warning(
+            f"Mod timing drift: race={participant.race_id}, participant={participant_id}, "
+            f"igt={drift['igt_ms']}, igt_delta={drift['igt_delta_ms']}, "
+            f"wall_delta={drift['wall_delta_ms']}, ratio={ratio:.3f}"
+        )
+
+
+async def handle_grace_discovered(
+    session_maker: async_sessionmaker[AsyncSession],
+    participant_id: uuid.UUID,
+    msg: dict[str, Any],
+) -> None:
+    """Handle a Site of Grace found: spectator context, counted on the leaderboard."""
+    fields = ("flag_id", "igt_ms")
+    values = [msg.get(field) for field in fields]
+    if not all(_is_count(v) for v in values):
+        return
+    grace = dict(zip(fields, values, strict=True))
+    # Only grace unlock flags: anything else isn't a grace
+    if not any(grace["flag_id"] in flags for flags in GRACE_FLAG_RANGES):
+        return
+
+    participant = await _record_participant_event(
+        session_maker, participant_id, "graces_found", grace, unique_key="flag_id"
+    )
+    if participant:
+        logger.info(
+            f"Mod grace discovered: race={participant.race_id}, participant={participant_id}, "
+            f"flag={grace['flag_id']}, igt={grace['igt_ms']}"
+        )
+
+
 async def authenticate_mod(
     db: AsyncSession, race_id: uuid.UUID, mod_token: str
 ) -> Participant | None:
@@ -237,11 +621,8 @@ async def authenticate_mod(
     return result.scalar_one_or_none()
 
 
-async def send_auth_ok(websocket: WebSocket, participant: Participant) -> None:
-    """Send successful auth response with race state."""
-    race = participant.race
-    seed = race.seed
-
+def build_mod_seed_info(seed: Seed | None, protocol_version: int) -> SeedInfo:
+    """Seed info sent to mods: flags to watch, items to spawn, no graph."""
     # Extract event_ids and finish_event from graph_json
     event_ids: list[int] = []
     finish_event_id: int | None = None
@@ -251,13 +632,80 @@ async def send_auth_ok(websocket: WebSocket, participant: Participant) -> None:
     # Extract gem items from care_package for runtime spawning by the mod
     spawn_items = extract_spawn_items(seed.graph_json) if seed and seed.graph_json else []
 
+    # Key items the mod watches for (item_obtained needs protocol 4)
+    key_items: list[KeyItem] = []
+    if protocol_version >= 4 and seed and seed.graph_json:
+        key_items = extract_key_items(seed.graph_json)
+
+    expected_character = None
+    if protocol_version >= 7 and seed and seed.graph_json:
+        expected_character = extract_expected_character(seed.graph_json)
+
+    # Segments are timed by the mod alone: older mods ignore the field
+    segments = extract_segments(seed.graph_json) if seed and seed.graph_json else []
+    start = extract_start_location(seed.graph_json) if seed and seed.graph_json else None
+
+    return SeedInfo(
+        seed_id=str(seed.id) if seed else None,
+        total_layers=seed.total_layers if seed else 0,
+        graph_json=None,  # Mods don't need the graph
+        event_ids=event_ids,
+        finish_event=finish_event_id,
+        spawn_items=spawn_items,
+        key_items=key_items,
+        expected_character=expected_character,
+        segments=segments,
+        start=start,
+    )
+
+
+async def send_auth_ok(
+    websocket: WebSocket,
+    participant: Participant,
+    protocol_version: int = 1,
+    encoding: str = JSON_ENCODING,
+) -> None:
+    """Send successful auth response with race state."""
+    race = participant.race
+    seed = race.seed
+
+    # Race objectives (bingo / scavenger races), objective_complete needs protocol 5
+    objectives: list[Objective] = []
+    if protocol_version >= 5:
+        objectives = extract_objectives(race.config)
+
+    # Intermediate checkpoints, checkpoint_crossed needs protocol 15
+    checkpoints: list[Checkpoint] = []
+    if protocol_version >= 15:
+        checkpoints = extract_checkpoints(race.config)
+
+    # Broadcast delay of the progress events, applied by the mod from protocol 21
+    event_delay_secs = 0
+    if protocol_version >= 21:
+        event_delay_secs = extract_event_delay(race.config)
+
+    # Zone privacy of the leaderboard; older mods always show names, but
+    # like newer ones are only sent the zones the race shows
+    race_zone_privacy = extract_zone_privacy(race.config)
+    zone_privacy = race_zone_privacy if protocol_version >= 22 else ZONE_PRIVACY_NAME
+
     # Build participant list
     room = manager.get_room(race.id)
     connected_ids = set(room.mods.keys()) if room else set()
+    afk_ids = room.afk_ids() if room else set()
     graph = seed.graph_json if seed else None
     sorted_participants = sort_leaderboard(race.participants)
     participant_infos: list[ParticipantInfo] = [
-        participant_to_info(p, connected_ids=connected_ids, graph_json=graph)
+        redact_zone(
+            participant_to_info(
+                p,
+                connected_ids=connected_ids,
+                afk_ids=afk_ids,
+                pauses=manager.pauses,
+                graph_json=graph,
+            ),
+            race_zone_privacy,
+        )
         for p in sorted_participants
     ]
 
@@ -272,15 +720,15 @@ async def send_auth_ok(websocket: WebSocket, participant: Participant) -> None:
                 race.seeds_released_at.isoformat() if race.seeds_released_at else None
             ),
         ),
-        seed=SeedInfo(
-            seed_id=str(seed.id) if seed else None,
-            total_layers=seed.total_layers if seed else 0,
-            graph_json=None,  # Mods don't need the graph
-            event_ids=event_ids,
-            finish_event=finish_event_id,
-            spawn_items=spawn_items,
-        ),
+        seed=build_mod_seed_info(seed, protocol_version),
         participants=participant_infos,
+        protocol_version=protocol_version,
+        objectives=objectives,
+        checkpoints=checkpoints,
+        encoding=encoding,
+        permissions=extract_permissions(race.config),
+        event_delay_secs=event_delay_secs,
+        zone_privacy=zone_privacy,
     )
     await websocket.send_text(message.model_dump_json())
 
@@ -411,6 +859,7 @@ async def handle_event_flag(
     is_first_visit = False
     igt = 0
     node_id: str | None = None
+    previous_zone: str | None = None
     seed_graph: dict[str, Any] | None = None
 
     async with session_maker() as db:
@@ -464,6 +913,7 @@ async def handle_event_flag(
             is_first_visit = not any(entry.get("node_id") == node_id for entry in old_history)
 
             # Always append to zone_history (including revisits/backtracks)
+            previous_zone = participant.current_zone
             participant.last_igt_change_at = datetime.now(UTC)
             participant.igt_ms = igt
             participant.current_zone = node_id
@@ -489,6 +939,13 @@ async def handle_event_flag(
             participant.race.participants,
             graph_json=seed_graph,
         )
+        if node_id and seed_graph:
+            if previous_zone and is_boss_node(previous_zone, seed_graph):
+                kind, zone = ACTIVITY_BOSS, previous_zone
+            else:
+                kind, zone = ACTIVITY_ZONE, node_id
+            zone_name = get_display_name_for_node(zone, seed_graph)
+            await broadcast_race_activity(participant, kind, igt, zone_name)
     else:
         # Revisit: broadcast player position update only
         await manager.broadcast_player_update(
@@ -556,6 +1013,197 @@ async def handle_zone_query(
     await manager.broadcast_player_update(participant.race_id, participant, graph_json=graph_json)
 
 
+async def handle_exit_hints_request(
+    session_maker: async_sessionmaker[AsyncSession],
+    race_id: uuid.UUID,
+    participant_id: uuid.UUID,
+    msg: dict[str, Any],
+) -> None:
+    """Handle exit_hints_request: send the community hints of a zone's exits.
+
+    Only zones the player has visited are answered, so the request can't be
+    used to probe the layout of the seed.
+    """
+    node_id = msg.get("node_id")
+    if not isinstance(node_id, str):
+        return
+
+    async with session_maker() as db:
+        participant = await _load_participant(db, participant_id)
+        if not participant:
+            return
+
+        seed = participant.race.seed
+        if not seed or not seed.graph_json:
+            return
+
+        visited = {e.get("node_id") for e in participant.zone_history or []}
+        if node_id != participant.current_zone and node_id not in visited:
+            logger.debug(
+                "exit_hints_request: zone %s not visited by participant %s",
+                node_id,
+                participant_id,
+            )
+            return
+
+        hints = compute_exit_hints(node_id, seed.graph_json, get_exit_hints())
+
+    message = ExitHintsMessage(
+        node_id=node_id,
+        hints=[ExitHint.model_validate(h) for h in hints],
+    )
+    await manager.send_to_mod(race_id, participant_id, message.model_dump_json())
+
+
+async def handle_item_obtained(
+    session_maker: async_sessionmaker[AsyncSession],
+    participant_id: uuid.UUID,
+    msg: dict[str, Any],
+) -> None:
+    """Handle a key item pickup: record it once and announce it to the other mods."""
+    item_id = msg.get("item_id")
+    if not isinstance(item_id, int) or isinstance(item_id, bool):
+        return
+    igt = msg.get("igt_ms", 0) if isinstance(msg.get("igt_ms"), int) else 0
+
+    async with session_maker() as db:
+        participant = await _load_participant(db, participant_id)
+        if not participant:
+            return
+
+        if participant.race.status != RaceStatus.RUNNING:
+            return
+
+        if participant.status in (ParticipantStatus.FINISHED, ParticipantStatus.ABANDONED):
+            return  # Silently drop — player finished or abandoned
+
+        seed = participant.race.seed
+        if not seed or not seed.graph_json:
+            return
+
+        seed_graph = seed.graph_json
+        item = next((k for k in extract_key_items(seed_graph) if k.id == item_id), None)
+        if item is None:
+            logger.warning(f"Unknown key item {item_id} from participant {participant_id}")
+            return
+
+        obtained = participant.key_items or []
+        if any(entry.get("item_id") == item_id for entry in obtained):
+            return  # Already recorded (resent after a reconnect)
+
+        participant.key_items = [*obtained, {"item_id": item_id, "igt_ms": igt}]
+        await db.commit()
+
+    # Session closed — safe to broadcast
+    user = participant.user
+    message = ItemObtainedMessage(
+        participant_id=str(participant.id),
+        player_name=user.twitch_display_name or user.twitch_username,
+        item_id=item.id,
+        item_name=item.name,
+        igt_ms=igt,
+    )
+    room = manager.get_room(participant.race_id)
+    if room:
+        await room.broadcast_to_mods(message.model_dump_json(), min_protocol_version=4)
+
+    await manager.broadcast_leaderboard(
+        participant.race_id,
+        participant.race.participants,
+        graph_json=seed_graph,
+    )
+
+
+async def handle_objective_complete(
+    session_maker: async_sessionmaker[AsyncSession],
+    participant_id: uuid.UUID,
+    msg: dict[str, Any],
+) -> None:
+    """Handle a race objective completion: record it once and update the leaderboard."""
+    objective_id = msg.get("objective_id")
+    if not isinstance(objective_id, int) or isinstance(objective_id, bool):
+        return
+    igt = msg.get("igt_ms", 0) if isinstance(msg.get("igt_ms"), int) else 0
+
+    async with session_maker() as db:
+        participant = await _load_participant(db, participant_id)
+        if not participant:
+            return
+
+        if participant.race.status != RaceStatus.RUNNING:
+            return
+
+        if participant.status in (ParticipantStatus.FINISHED, ParticipantStatus.ABANDONED):
+            return  # Silently drop — player finished or abandoned
+
+        objectives = extract_objectives(participant.race.config)
+        if not any(o.id == objective_id for o in objectives):
+            logger.warning(f"Unknown objective {objective_id} from participant {participant_id}")
+            return
+
+        completed = participant.objectives_completed or []
+        if any(entry.get("objective_id") == objective_id for entry in completed):
+            return  # Already recorded (resent after a reconnect)
+
+        participant.objectives_completed = [
+            *completed,
+            {"objective_id": objective_id, "igt_ms": igt},
+        ]
+        await db.commit()
+
+    # Session closed — safe to broadcast
+    await manager.broadcast_leaderboard(
+        participant.race_id,
+        participant.race.participants,
+        graph_json=_get_graph_json(participant),
+    )
+
+
+async def handle_checkpoint_crossed(
+    session_maker: async_sessionmaker[AsyncSession],
+    participant_id: uuid.UUID,
+    msg: dict[str, Any],
+) -> None:
+    """Handle a race checkpoint crossing: record it once and update the leaderboard."""
+    checkpoint_id = msg.get("checkpoint_id")
+    if not isinstance(checkpoint_id, int) or isinstance(checkpoint_id, bool):
+        return
+    igt = msg.get("igt_ms", 0) if isinstance(msg.get("igt_ms"), int) else 0
+
+    async with session_maker() as db:
+        participant = await _load_participant(db, participant_id)
+        if not participant:
+            return
+
+        if participant.race.status != RaceStatus.RUNNING:
+            return
+
+        if participant.status in (ParticipantStatus.FINISHED, ParticipantStatus.ABANDONED):
+            return  # Silently drop — player finished or abandoned
+
+        checkpoints = extract_checkpoints(participant.race.config)
+        if not any(c.id == checkpoint_id for c in checkpoints):
+            logger.warning(f"Unknown checkpoint {checkpoint_id} from participant {participant_id}")
+            return
+
+        crossed = participant.checkpoints or []
+        if any(entry.get("checkpoint_id") == checkpoint_id for entry in crossed):
+            return  # Already recorded (resent after a reconnect)
+
+        participant.checkpoints = [
+            *crossed,
+            {"checkpoint_id": checkpoint_id, "igt_ms": igt},
+        ]
+        await db.commit()
+
+    # Session closed — safe to broadcast
+    await manager.broadcast_leaderboard(
+        participant.race_id,
+        participant.race.participants,
+        graph_json=_get_graph_json(participant),
+    )
+
+
 async def handle_finished(
     websocket: WebSocket,
     session_maker: async_sessionmaker[AsyncSession],
@@ -586,6 +1234,7 @@ async def handle_finished(
         if isinstance(msg.get("igt_ms"), int):
             participant.igt_ms = msg["igt_ms"]
         participant.finished_at = datetime.now(UTC)
+        manager.pauses.pop(participant_id, None)
 
         # Bump current_layer to total_layers so progress displays N/N
         seed = participant.race.seed
@@ -618,6 +1267,43 @@ async def handle_finished(
         participant.race.participants,
         graph_json=_get_graph_json(participant),
     )
+    await broadcast_race_activity(participant, ACTIVITY_FINISH, participant.igt_ms)
+
+
+async def broadcast_race_activity(
+    participant: Participant, kind: str, igt: int, zone_name: str | None = None
+) -> None:
+    """Announce a participant's milestone to the mods' activity feeds (protocol 24+).
+
+    The zone name is left out unless the race shows zone names (``zone_privacy``).
+    """
+    room = manager.get_room(participant.race_id)
+    if not room:
+        return
+    if extract_zone_privacy(participant.race.config) != ZONE_PRIVACY_NAME:
+        zone_name = None
+    user = participant.user
+    message = RaceActivityMessage(
+        participant_id=str(participant.id),
+        player_name=user.twitch_display_name or user.twitch_username,
+        kind=kind,
+        zone_name=zone_name,
+        igt_ms=igt,
+    )
+    await room.broadcast_to_mods(message.model_dump_json(), min_protocol_version=24)
+
+
+async def broadcast_seed_changed(race_id: uuid.UUID, seed: Seed) -> None:
+    """Push the re-rolled seed to connected mods, so they reset without reconnecting.
+
+    Mods older than protocol 6 only notice the new seed on their next auth_ok.
+    """
+    room = manager.get_room(race_id)
+    if not room:
+        return
+    message = SeedChangedMessage(seed=build_mod_seed_info(seed, MOD_PROTOCOL_VERSION))
+    await room.broadcast_to_mods(message.model_dump_json(), min_protocol_version=6)
+    logger.info(f"Seed change broadcast: race={race_id}, seed={seed.id}")
 
 
 async def broadcast_race_start(
@@ -629,7 +1315,7 @@ async def broadcast_race_start(
     room = manager.get_room(race_id)
     if room:
         # Send race_start to mods
-        message = RaceStartMessage()
+        message = RaceStartMessage(started_at=started_at)
         await room.broadcast_to_mods(message.model_dump_json())
 
         # Send zone_update for start node to each connected mod
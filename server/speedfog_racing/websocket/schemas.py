@@ -12,6 +12,7 @@ class AuthMessage(BaseModel):
 
     type: Literal["auth"] = "auth"
     mod_token: str
+    protocol_version: int = 1
 
 
 class ReadyMessage(BaseModel):
@@ -26,6 +27,8 @@ class StatusUpdateMessage(BaseModel):
     type: Literal["status_update"] = "status_update"
     igt_ms: int
     death_count: int
+    # Last update before the game closes (protocol 17+)
+    disconnecting: bool = False
 
 
 class EventFlagMessage(BaseModel):
@@ -34,6 +37,120 @@ class EventFlagMessage(BaseModel):
     type: Literal["event_flag"] = "event_flag"
     flag_id: int
     igt_ms: int
+    seq: int | None = None
+    idempotency_key: str | None = None  # protocol 23+
+
+
+class ItemObtainedRequestMessage(BaseModel):
+    """Key item picked up by the player (protocol 4+)."""
+
+    type: Literal["item_obtained"] = "item_obtained"
+    item_id: int
+    igt_ms: int
+
+
+class ObjectiveCompleteRequestMessage(BaseModel):
+    """Race objective completed by the player (protocol 5+)."""
+
+    type: Literal["objective_complete"] = "objective_complete"
+    objective_id: int
+    igt_ms: int
+
+
+class CheckpointCrossedRequestMessage(BaseModel):
+    """Race checkpoint crossed by the player (protocol 15+)."""
+
+    type: Literal["checkpoint_crossed"] = "checkpoint_crossed"
+    checkpoint_id: int
+    igt_ms: int
+
+
+class CharacterFingerprint(BaseModel):
+    """Character loaded in the game, sent in auth and on change (protocol 7+)."""
+
+    slot: int | None = None
+    name: str
+    level: int
+    class_id: int
+
+
+class CharacterRequestMessage(BaseModel):
+    """The player loaded a character, or switched to another one (protocol 7+)."""
+
+    type: Literal["character"] = "character"
+    character: CharacterFingerprint
+
+
+class NgCycleRequestMessage(BaseModel):
+    """The character's NG+ cycle changed, or was already NG+ at load (protocol 8+)."""
+
+    type: Literal["ng_cycle"] = "ng_cycle"
+    clear_count: int
+    igt_ms: int
+
+
+class ExitHintsRequestMessage(BaseModel):
+    """Ask for the community hints of a visited zone's exits (protocol 9+)."""
+
+    type: Literal["exit_hints_request"] = "exit_hints_request"
+    node_id: str
+
+
+class AfkRequestMessage(BaseModel):
+    """The player went idle, or came back (protocol 10+)."""
+
+    type: Literal["afk"] = "afk"
+    afk: bool
+    igt_ms: int
+
+
+class SaveReloadedRequestMessage(BaseModel):
+    """IGT or the death count went backwards: a save was reloaded (protocol 12+)."""
+
+    type: Literal["save_reloaded"] = "save_reloaded"
+    igt_ms: int
+    igt_delta_ms: int
+    death_count: int
+    death_delta: int
+
+
+class KindlingUsedRequestMessage(BaseModel):
+    """The Messmer's Kindling count went down: one was burnt (protocol 16+)."""
+
+    type: Literal["kindling_used"] = "kindling_used"
+    igt_ms: int
+    used: int
+    remaining: int
+
+
+class TimingDriftRequestMessage(BaseModel):
+    """IGT ran faster or slower than the wall clock over a window (protocol 25+)."""
+
+    type: Literal["timing_drift"] = "timing_drift"
+    igt_ms: int
+    igt_delta_ms: int
+    wall_delta_ms: int
+
+
+class GraceDiscoveredRequestMessage(BaseModel):
+    """A Site of Grace was touched: its unlock flag got set (protocol 18+)."""
+
+    type: Literal["grace_discovered"] = "grace_discovered"
+    flag_id: int
+    igt_ms: int
+
+
+class LeaderboardResyncRequestMessage(BaseModel):
+    """A leaderboard delta didn't follow the mod's generation (protocol 19+)."""
+
+    type: Literal["leaderboard_resync"] = "leaderboard_resync"
+
+
+class PauseRequestMessage(BaseModel):
+    """The player asks the organizer to pause their race (protocol 14+)."""
+
+    type: Literal["pause_request"] = "pause_request"
+    igt_ms: int
 
 
 class PongMessage(BaseModel):
@@ -42,6 +159,13 @@ class PongMessage(BaseModel):
     type: Literal["pong"] = "pong"
 
 
+class ClockSyncRequestMessage(BaseModel):
+    """Clock sync probe from mod (client wall clock, ms since epoch)."""
+
+    type: Literal["clock_sync"] = "clock_sync"
+    client_time_ms: int
+
+
 class SpawnItem(BaseModel):
     """Item to be spawned at runtime by the mod (e.g., Gem/Ash of War)."""
 
@@ -59,9 +183,249 @@ def extract_spawn_items(graph_json: dict[str, Any]) -> list[SpawnItem]:
     ]
 
 
+class KeyItem(BaseModel):
+    """Seed-relevant item the mod watches for, detected by its pickup event flag."""
+
+    id: int
+    flag_id: int
+    name: str
+
+
+def extract_key_items(graph_json: dict[str, Any]) -> list[KeyItem]:
+    """Extract the key items (progression gates) listed in the seed graph."""
+    items: list[KeyItem] = []
+    for item in graph_json.get("key_items", []):
+        if not isinstance(item, dict):
+            continue
+        item_id, flag_id, name = item.get("id"), item.get("flag_id"), item.get("name")
+        if isinstance(item_id, int) and isinstance(flag_id, int) and isinstance(name, str):
+            items.append(KeyItem(id=item_id, flag_id=flag_id, name=name))
+    return items
+
+
+class Segment(BaseModel):
+    """Run segment timed by the mod, from the first arrival in one node to another."""
+
+    name: str
+    from_node: str
+    to_node: str
+
+
+def extract_segments(graph_json: dict[str, Any]) -> list[Segment]:
+    """Extract the run segments listed in the seed graph."""
+    segments: list[Segment] = []
+    for entry in graph_json.get("segments", []):
+        if not isinstance(entry, dict):
+            continue
+        name, from_node, to_node = entry.get("name"), entry.get("from_node"), entry.get("to_node")
+        if isinstance(name, str) and isinstance(from_node, str) and isinstance(to_node, str):
+            segments.append(Segment(name=name, from_node=from_node, to_node=to_node))
+    return segments
+
+
+class StartLocation(BaseModel):
+    """Where the race starts: the graph's start node and, when the seed provides
+    it, the starting grace's map and map-local position."""
+
+    node_id: str
+    map_id: str | None = None
+    position: list[float] | None = None
+
+
+def extract_start_location(graph_json: dict[str, Any]) -> StartLocation | None:
+    """Extract the start node (type "start", else the top-level start_node key)."""
+    nodes: dict[str, Any] = graph_json.get("nodes", {})
+    node_id = None
+    for nid, data in nodes.items():
+        if isinstance(data, dict) and data.get("type") == "start":
+            node_id = nid
+            break
+    if node_id is None:
+        fallback = graph_json.get("start_node")
+        if not isinstance(fallback, str) or fallback not in nodes:
+            return None
+        node_id = fallback
+    start = StartLocation(node_id=node_id)
+    node = nodes.get(node_id)
+    map_id = node.get("map_id") if isinstance(node, dict) else None
+    position = node.get("position") if isinstance(node, dict) else None
+    if (
+        isinstance(map_id, str)
+        and isinstance(position, list)
+        and len(position) == 3
+        and all(isinstance(v, int | float) for v in position)
+    ):
+        start.map_id = map_id
+        start.position = [float(v) for v in position]
+    return start
+
+
+class ExpectedCharacter(BaseModel):
+    """Starting class and level the seed was generated for."""
+
+    class_id: int | None = None
+    level: int | None = None
+
+
+def extract_expected_character(graph_json: dict[str, Any]) -> ExpectedCharacter | None:
+    """Extract the seed's starting character, if the graph declares one."""
+    character = graph_json.get("starting_character")
+    if not isinstance(character, dict):
+        return None
+    class_id, level = character.get("class_id"), character.get("level")
+    expected = ExpectedCharacter(
+        class_id=class_id if isinstance(class_id, int) else None,
+        level=level if isinstance(level, int) else None,
+    )
+    if expected.class_id is None and expected.level is None:
+        return None
+    return expected
+
+
+class Objective(BaseModel):
+    """Custom race objective (bingo / scavenger races), completed by an event flag."""
+
+    id: int
+    text: str
+    flag_id: int
+
+
+def extract_objectives(race_config: dict[str, Any] | None) -> list[Objective]:
+    """Extract the objectives from a race config, numbered by their position.
+
+    Entries are ``{"text": str, "flag_id": int}``; invalid entries are skipped
+    but keep their number, so ids stay stable if the list is fixed later.
+    """
+    objectives: list[Objective] = []
+    entries = (race_config or {}).get("objectives", [])
+    if not isinstance(entries, list):
+        return objectives
+    for index, entry in enumerate(entries):
+        if not isinstance(entry, dict):
+            continue
+        text, flag_id = entry.get("text"), entry.get("flag_id")
+        if isinstance(text, str) and isinstance(flag_id, int) and not isinstance(flag_id, bool):
+            objectives.append(Objective(id=index, text=text, flag_id=flag_id))
+    return objectives
+
+
+class Checkpoint(BaseModel):
+    """Intermediate race checkpoint, crossed on an event flag or on entering a zone."""
+
+    id: int
+    name: str
+    flag_id: int | None = None
+    node_id: str | None = None
+
+
+def extract_checkpoints(race_config: dict[str, Any] | None) -> list[Checkpoint]:
+    """Extract the checkpoints from a race config, numbered by their position.
+
+    Entries are ``{"name": str, "flag_id": int}`` or ``{"name": str,
+    "node_id": str}``; invalid entries are skipped but keep their number.
+    """
+    checkpoints: list[Checkpoint] = []
+    entries = (race_config or {}).get("checkpoints", [])
+    if not isinstance(entries, list):
+        return checkpoints
+    for index, entry in enumerate(entries):
+        if not isinstance(entry, dict):
+            continue
+        name, flag_id, node_id = entry.get("name"), entry.get("flag_id"), entry.get("node_id")
+        if not isinstance(name, str):
+            continue
+        if isinstance(flag_id, int) and not isinstance(flag_id, bool):
+            checkpoints.append(Checkpoint(id=index, name=name, flag_id=flag_id))
+        elif isinstance(node_id, str) and node_id:
+            checkpoints.append(Checkpoint(id=index, name=name, node_id=node_id))
+    return checkpoints
+
+
+def extract_settable_flags(race_config: dict[str, Any] | None) -> set[int]:
+    """Event flags the organizer may set in the participants' games.
+
+    Taken from ``settable_flags`` (a list of ints); other entries are ignored.
+    """
+    entries = (race_config or {}).get("settable_flags", [])
+    if not isinstance(entries, list):
+        return set()
+    return {f for f in entries if isinstance(f, int) and not isinstance(f, bool)}
+
+
+# Longest broadcast delay a race can enforce (the mod applies the same cap)
+MAX_EVENT_DELAY_SECS = 600
+
+
+def extract_event_delay(race_config: dict[str, Any] | None) -> int:
+    """Seconds the mods hold their progress events back (broadcast races).
+
+    Taken from ``event_delay_secs`` (an int), clamped to 0..MAX_EVENT_DELAY_SECS.
+    """
+    delay = (race_config or {}).get("event_delay_secs", 0)
+    if not isinstance(delay, int) or isinstance(delay, bool):
+        return 0
+    return max(0, min(delay, MAX_EVENT_DELAY_SECS))
+
+
+# How much of the other participants' zones the mod leaderboard shows
+ZONE_PRIVACY_NAME = "name"
+ZONE_PRIVACY_TIER = "tier"
+ZONE_PRIVACY_HIDDEN = "hidden"
+ZONE_PRIVACY_MODES = (ZONE_PRIVACY_NAME, ZONE_PRIVACY_TIER, ZONE_PRIVACY_HIDDEN)
+
+
+def extract_zone_privacy(race_config: dict[str, Any] | None) -> str:
+    """Zone privacy of the race's mod leaderboards, from ``zone_privacy``.
+
+    Unknown values hide the zones rather than reveal more than intended.
+    """
+    mode = (race_config or {}).get("zone_privacy", ZONE_PRIVACY_NAME)
+    return mode if mode in ZONE_PRIVACY_MODES else ZONE_PRIVACY_HIDDEN
+
+
+# Mod features a race can turn off (auth_ok permissions bitfield, protocol 13+)
+PERMISSION_EXITS_PANEL = 1 << 0
+PERMISSION_LEADERBOARD = 1 << 1
+PERMISSION_SCALING_INFO = 1 << 2
+PERMISSION_MAP = 1 << 3
+PERMISSIONS_ALL = (
+    PERMISSION_EXITS_PANEL | PERMISSION_LEADERBOARD | PERMISSION_SCALING_INFO | PERMISSION_MAP
+)
+
+_PERMISSION_KEYS = {
+    "exits_panel": PERMISSION_EXITS_PANEL,
+    "leaderboard": PERMISSION_LEADERBOARD,
+    "scaling_info": PERMISSION_SCALING_INFO,
+    "map": PERMISSION_MAP,
+}
+
+
+def extract_permissions(race_config: dict[str, Any] | None) -> int:
+    """Build the permissions bitfield from a race config.
+
+    ``permissions`` maps feature names to booleans; features left out (or not
+    set to ``false``) stay allowed, so regular races need no entry.
+    """
+    permissions = PERMISSIONS_ALL
+    entries = (race_config or {}).get("permissions", {})
+    if not isinstance(entries, dict):
+        return permissions
+    for key, bit in _PERMISSION_KEYS.items():
+        if entries.get(key) is False:
+            permissions &= ~bit
+    return permissions
+
+
 # --- Server -> Client Messages ---
 
 
+class CheckpointTime(BaseModel):
+    """IGT at which a participant crossed a checkpoint."""
+
+    checkpoint_id: int
+    igt_ms: int
+
+
 class ParticipantInfo(BaseModel):
     """Participant info for leaderboard."""
 
@@ -70,17 +434,35 @@ class ParticipantInfo(BaseModel):
     twitch_display_name: str | None
     status: str
     current_zone: str | None
+    # Display name of current_zone (protocol 22+)
+    current_zone_name: str | None = None
     current_layer: int
     current_layer_tier: int | None = None
     igt_ms: int
     death_count: int
     color_index: int = 0
     mod_connected: bool = False
+    afk: bool = False
     zone_history: list[dict[str, object]] | None = None
     gap_ms: int | None = None
     layer_entry_igt: int | None = None
+    # IGT when entering current_zone, its latest visit (protocol 27+)
+    zone_entry_igt: int | None = None
     is_live: bool = False
     stream_url: str | None = None
+    key_items: list[int] = Field(default_factory=list)
+    objectives_completed: list[int] = Field(default_factory=list)
+    # Checkpoints crossed, in crossing order (protocol 15+)
+    checkpoints: list[CheckpointTime] = Field(default_factory=list)
+    save_reloads: int = 0
+    kindling_used: int = 0
+    graces_found: int = 0
+    # IGT/wall clock drift windows reported by the mod (protocol 25+)
+    timing_anomalies: int = 0
+    # organizer, previous_winner, streamer (protocol 26+)
+    badges: list[str] = Field(default_factory=list)
+    # "requested" while waiting for the organizer, "paused" once granted
+    pause: str | None = None
 
 
 class RaceInfo(BaseModel):
@@ -104,6 +486,11 @@ class SeedInfo(BaseModel):
     event_ids: list[int] = Field(default_factory=list)
     finish_event: int | None = None
     spawn_items: list[SpawnItem] = Field(default_factory=list)
+    key_items: list[KeyItem] = Field(default_factory=list)
+    expected_character: ExpectedCharacter | None = None
+    segments: list[Segment] = Field(default_factory=list)
+    # Start of the race, checked by the mod's warmup lobby (older mods ignore it)
+    start: StartLocation | None = None
 
 
 class AuthOkMessage(BaseModel):
@@ -114,6 +501,18 @@ class AuthOkMessage(BaseModel):
     race: RaceInfo
     seed: SeedInfo
     participants: list[ParticipantInfo]
+    protocol_version: int = 1
+    objectives: list[Objective] = Field(default_factory=list)
+    # Race checkpoints (protocol 15+)
+    checkpoints: list[Checkpoint] = Field(default_factory=list)
+    # Encoding of the following messages: "json" or "msgpack" (protocol 11+)
+    encoding: str = "json"
+    # Mod features allowed by the race (PERMISSION_* bits, protocol 13+)
+    permissions: int = PERMISSIONS_ALL
+    # Seconds the mod holds its progress events back (protocol 21+)
+    event_delay_secs: int = 0
+    # How much of the other participants' zones the mod shows (protocol 22+)
+    zone_privacy: str = ZONE_PRIVACY_NAME
 
 
 class AuthErrorMessage(BaseModel):
@@ -134,6 +533,7 @@ class RaceStartMessage(BaseModel):
     """Race start broadcast."""
 
     type: Literal["race_start"] = "race_start"
+    started_at: str | None = None
 
 
 class LeaderboardUpdateMessage(BaseModel):
@@ -142,6 +542,20 @@ class LeaderboardUpdateMessage(BaseModel):
     type: Literal["leaderboard_update"] = "leaderboard_update"
     participants: list[ParticipantInfo]
     leader_splits: dict[int, int] | None = None
+    # Broadcast number in the room, for the deltas that follow (None: standalone)
+    generation: int | None = None
+
+
+class LeaderboardDeltaMessage(BaseModel):
+    """Leaderboard changes since the previous generation (mods, protocol 19+)."""
+
+    type: Literal["leaderboard_delta"] = "leaderboard_delta"
+    generation: int
+    # Only the rows that changed
+    participants: list[ParticipantInfo]
+    # Participant ids in leaderboard order (rows not listed are gone)
+    order: list[str]
+    leader_splits: dict[int, int] | None = None
 
 
 class RaceStateMessage(BaseModel):
@@ -181,6 +595,9 @@ class ExitInfo(BaseModel):
     text: str
     to_name: str
     discovered: bool
+    to_node_id: str | None = None
+    map_id: str | None = None
+    position: list[float] | None = None
 
 
 class ZoneUpdateMessage(BaseModel):
@@ -198,3 +615,89 @@ class PingMessage(BaseModel):
     """Heartbeat ping from server."""
 
     type: Literal["ping"] = "ping"
+
+
+class EventFlagAckMessage(BaseModel):
+    """Acknowledges an event_flag by its correlation id (protocol 3+)."""
+
+    type: Literal["event_flag_ack"] = "event_flag_ack"
+    seq: int
+
+
+class ItemObtainedMessage(BaseModel):
+    """A participant picked up a key item, broadcast to mods (protocol 4+)."""
+
+    type: Literal["item_obtained"] = "item_obtained"
+    participant_id: str
+    player_name: str
+    item_id: int
+    item_name: str
+    igt_ms: int
+
+
+# Milestones of race_activity (protocol 24+)
+ACTIVITY_ZONE = "zone"
+ACTIVITY_BOSS = "boss"
+ACTIVITY_FINISH = "finish"
+
+
+class RaceActivityMessage(BaseModel):
+    """A participant's milestone for the mods' activity feed (protocol 24+)."""
+
+    type: Literal["race_activity"] = "race_activity"
+    participant_id: str
+    player_name: str
+    kind: str
+    # Zone entered, or boss arena left; None when the race hides zones
+    zone_name: str | None = None
+    igt_ms: int
+
+
+class SeedChangedMessage(BaseModel):
+    """The organizer re-rolled the race seed, broadcast to mods (protocol 6+)."""
+
+    type: Literal["seed_changed"] = "seed_changed"
+    seed: SeedInfo
+
+
+class ExitHint(BaseModel):
+    """Community hint for one exit, indexed like zone_update's exits."""
+
+    exit_index: int
+    text: str
+
+
+class ExitHintsMessage(BaseModel):
+    """Community hints of a zone's exits, unicast to the requesting mod (protocol 9+)."""
+
+    type: Literal["exit_hints"] = "exit_hints"
+    node_id: str
+    hints: list[ExitHint]
+
+
+class PauseGrantedMessage(BaseModel):
+    """The organizer granted the mod's pause request, unicast (protocol 14+)."""
+
+    type: Literal["pause_granted"] = "pause_granted"
+
+
+class ResumeMessage(BaseModel):
+    """The organizer resumed the paused race or declined the request, unicast (protocol 14+)."""
+
+    type: Literal["resume"] = "resume"
+
+
+class SetFlagMessage(BaseModel):
+    """The organizer sets an event flag in the mod's game, unicast (protocol 20+)."""
+
+    type: Literal["set_flag"] = "set_flag"
+    flag_id: int
+    value: bool = True
+
+
+class ClockSyncMessage(BaseModel):
+    """Clock sync reply: echoes the mod's timestamp alongside the server's."""
+
+    type: Literal["clock_sync"] = "clock_sync"
+    client_time_ms: int
+    server_time_ms: int
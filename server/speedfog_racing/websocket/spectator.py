@@ -179,10 +179,17 @@ async def send_race_state(
     """Send current race state to a spectator."""
     room = manager.get_room(race.id)
     connected_ids = set(room.mods.keys()) if room else set()
+    afk_ids = room.afk_ids() if room else set()
     graph = race.seed.graph_json if race.seed else None
     sorted_participants = sort_leaderboard(race.participants)
     participant_infos: list[ParticipantInfo] = [
-        participant_to_info(p, connected_ids=connected_ids, graph_json=graph)
+        participant_to_info(
+            p,
+            connected_ids=connected_ids,
+            afk_ids=afk_ids,
+            pauses=manager.pauses,
+            graph_json=graph,
+        )
         for p in sorted_participants
     ]
 
@@ -5,6 +5,7 @@ import json
 import logging
 import uuid
 from datetime import UTC, datetime
+from functools import partial
 from typing import Any
 
 from fastapi import WebSocket, WebSocketDisconnect
@@ -24,9 +25,12 @@ from speedfog_racing.websocket.common import (
     MOD_AUTH_TIMEOUT,
     extract_event_ids,
     get_graces_mapping,
+    handle_event_flag_once,
     heartbeat_loop,
+    negotiate_protocol_version,
     parse_zone_query_input,
     send_auth_error,
+    send_clock_sync,
     send_error,
     send_zone_update,
 )
@@ -89,6 +93,7 @@ async def handle_training_mod_websocket(
             return
 
         mod_token = auth_msg["mod_token"]
+        protocol_version = negotiate_protocol_version(auth_msg)
 
         async with session_maker() as db:
             # Find session by mod_token
@@ -118,10 +123,11 @@ async def handle_training_mod_websocket(
             mod_locale = session.user.locale or "en"
 
             # Send auth_ok
-            await _send_auth_ok(websocket, session)
+            await _send_auth_ok(websocket, session, protocol_version)
 
             # Send race_start immediately (training starts right away)
-            await websocket.send_text(RaceStartMessage().model_dump_json())
+            started_at = session.created_at.isoformat() if session.created_at else None
+            await websocket.send_text(RaceStartMessage(started_at=started_at).model_dump_json())
 
             # Send initial zone_update if session has progress
             seed = session.seed
@@ -161,11 +167,23 @@ async def handle_training_mod_websocket(
 
                 if msg_type == "pong":
                     pass
+                elif msg_type == "clock_sync":
+                    await send_clock_sync(websocket, msg)
                 elif msg_type == "status_update":
                     await _handle_status_update(websocket, session_maker, session_id, msg)
                 elif msg_type == "event_flag":
-                    await _handle_event_flag(
-                        websocket, session_maker, session_id, msg, locale=mod_locale
+                    await handle_event_flag_once(
+                        websocket,
+                        session_id,
+                        msg,
+                        partial(
+                            _handle_event_flag,
+                            websocket,
+                            session_maker,
+                            session_id,
+                            msg,
+                            locale=mod_locale,
+                        ),
                     )
                 elif msg_type == "zone_query":
                     await _handle_zone_query(
@@ -242,7 +260,9 @@ def build_training_participant_info(
     )
 
 
-async def _send_auth_ok(websocket: WebSocket, session: TrainingSession) -> None:
+async def _send_auth_ok(
+    websocket: WebSocket, session: TrainingSession, protocol_version: int = 1
+) -> None:
     """Send auth_ok with training session info."""
     seed = session.seed
 
@@ -271,6 +291,7 @@ async def _send_auth_ok(websocket: WebSocket, session: TrainingSession) -> None:
             spawn_items=spawn_items,
         ),
         participants=[build_training_participant_info(session)],
+        protocol_version=protocol_version,
     )
     await websocket.send_text(message.model_dump_json())
 
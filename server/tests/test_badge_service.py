@@ -0,0 +1,114 @@
+"""Tests for participant badges."""
+
+import pytest
+from sqlalchemy.ext.asyncio import AsyncSession, async_sessionmaker, create_async_engine
+
+from speedfog_racing.database import Base
+from speedfog_racing.models import (
+    Participant,
+    ParticipantStatus,
+    Race,
+    RaceStatus,
+    User,
+    UserRole,
+)
+from speedfog_racing.services.badge_service import (
+    BADGE_ORGANIZER,
+    BADGE_PREVIOUS_WINNER,
+    BADGE_STREAMER,
+    compute_badges,
+    participant_badges,
+)
+
+
+@pytest.fixture
+async def async_engine():
+    engine = create_async_engine("sqlite+aiosqlite:///:memory:")
+    async with engine.begin() as conn:
+        await conn.run_sync(Base.metadata.create_all)
+    yield engine
+    await engine.dispose()
+
+
+@pytest.fixture
+def async_session(async_engine):
+    return async_sessionmaker(async_engine, class_=AsyncSession, expire_on_commit=False)
+
+
+@pytest.fixture
+async def users(async_session):
+    """Organizer, a winner and a runner-up of a finished race, and a newcomer."""
+    async with async_session() as db:
+        organizer, winner, runner_up, newcomer = (
+            User(
+                twitch_id=name,
+                twitch_username=name,
+                api_token=f"tok_{name}",
+                role=UserRole.ORGANIZER if name == "organizer" else UserRole.USER,
+            )
+            for name in ("organizer", "winner", "runner_up", "newcomer")
+        )
+        db.add_all([organizer, winner, runner_up, newcomer])
+        await db.flush()
+
+        finished = Race(name="Past Race", organizer_id=organizer.id, status=RaceStatus.FINISHED)
+        # Running race: the leader hasn't won it yet
+        running = Race(name="Live Race", organizer_id=organizer.id, status=RaceStatus.RUNNING)
+        db.add_all([finished, running])
+        await db.flush()
+
+        db.add_all(
+            [
+                Participant(
+                    race_id=finished.id,
+                    user_id=winner.id,
+                    status=ParticipantStatus.FINISHED,
+                    igt_ms=300000,
+                ),
+                Participant(
+                    race_id=finished.id,
+                    user_id=runner_up.id,
+                    status=ParticipantStatus.FINISHED,
+                    igt_ms=400000,
+                ),
+                # Abandoned with a lower IGT: not a win
+                Participant(
+                    race_id=finished.id,
+                    user_id=newcomer.id,
+                    status=ParticipantStatus.ABANDONED,
+                    igt_ms=100000,
+                ),
+                Participant(
+                    race_id=running.id,
+                    user_id=runner_up.id,
+                    status=ParticipantStatus.FINISHED,
+                    igt_ms=200000,
+                ),
+            ]
+        )
+        await db.commit()
+        return organizer.id, winner.id, runner_up.id, newcomer.id
+
+
+@pytest.mark.asyncio
+async def test_compute_badges(async_session, users):
+    organizer_id, winner_id, runner_up_id, newcomer_id = users
+    async with async_session() as db:
+        assert await compute_badges(db, organizer_id, organizer_id) == [BADGE_ORGANIZER]
+        assert await compute_badges(db, winner_id, organizer_id) == [BADGE_PREVIOUS_WINNER]
+        assert await compute_badges(db, runner_up_id, organizer_id) == []
+        assert await compute_badges(db, newcomer_id, organizer_id) == []
+        # A past winner organizing their own race gets both
+        assert await compute_badges(db, winner_id, winner_id) == [
+            BADGE_ORGANIZER,
+            BADGE_PREVIOUS_WINNER,
+        ]
+
+
+def test_streamer_badge_follows_live_status():
+    assert participant_badges(None, is_live=False) == []
+    assert participant_badges(None, is_live=True) == [BADGE_STREAMER]
+    assert participant_badges([BADGE_ORGANIZER], is_live=True) == [
+        BADGE_ORGANIZER,
+        BADGE_STREAMER,
+    ]
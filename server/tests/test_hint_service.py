@@ -0,0 +1,46 @@
+"""Unit tests for the community exit hints service."""
+
+from speedfog_racing.services.hint_service import compute_exit_hints, load_exit_hints
+
+GRAPH = {
+    "nodes": {
+        "chapel_start_4f96": {
+            "zones": ["chapel_start"],
+            "exits": [
+                {"to": "stormveil_db4a", "text": "Gate A"},
+                "bogus",
+                {"to": "limgrave_cave_a1b2", "text": "Gate B"},
+                {"to": "boss_c3d4", "text": "Gate C"},
+            ],
+        },
+        "stormveil_db4a": {"zones": ["stormveil_start", "stormveil"]},
+        "limgrave_cave_a1b2": {"zones": ["limgrave_coastalcave"]},
+        "boss_c3d4": {"zones": ["limgrave_coastalcave_boss"]},
+    }
+}
+
+HINTS = {
+    "stormveil": "Leads to a legacy dungeon",
+    "limgrave_coastalcave_boss": "Boss arena, dead end",
+}
+
+
+def test_load_exit_hints():
+    """Loads the real exit_hints.json, keyed by zone_id."""
+    hints = load_exit_hints()
+    assert hints["stormveil"] == "Leads to a legacy dungeon"
+    assert all(isinstance(text, str) and text for text in hints.values())
+
+
+def test_compute_exit_hints_indexes_match_zone_update():
+    """Malformed exits are skipped, so indexes follow zone_update's exits list."""
+    hints = compute_exit_hints("chapel_start_4f96", GRAPH, HINTS)
+    assert hints == [
+        {"exit_index": 0, "text": "Leads to a legacy dungeon"},
+        {"exit_index": 2, "text": "Boss arena, dead end"},
+    ]
+
+
+def test_compute_exit_hints_unknown_node():
+    assert compute_exit_hints("nowhere", GRAPH, HINTS) == []
+    assert compute_exit_hints("stormveil_db4a", GRAPH, HINTS) == []
@@ -759,6 +759,55 @@ def test_status_update_transitions_to_playing_with_start_zone(
     assert history[0]["igt_ms"] == 0
 
 
+def test_status_update_disconnecting_closes_connection(
+    integration_client, race_with_participants, integration_db
+):
+    """A status_update with disconnecting=True is applied, then the server closes."""
+    import asyncio
+
+    from starlette.websockets import WebSocketDisconnect
+
+    race_id = race_with_participants["race_id"]
+    organizer = race_with_participants["organizer"]
+    players = race_with_participants["players"]
+
+    response = integration_client.post(
+        f"/api/races/{race_id}/start",
+        headers={"Authorization": f"Bearer {organizer.api_token}"},
+    )
+    assert response.status_code == 200
+
+    with integration_client.websocket_connect(f"/ws/mod/{race_id}") as ws0:
+        mod0 = ModTestClient(ws0, players[0]["mod_token"])
+        assert mod0.auth()["type"] == "auth_ok"
+        ws0.send_json(
+            {
+                "type": "status_update",
+                "igt_ms": 5000,
+                "death_count": 2,
+                "disconnecting": True,
+            }
+        )
+        with pytest.raises(WebSocketDisconnect):
+            for _ in range(10):
+                mod0.receive()
+
+    async def check_db():
+        async with integration_db() as db:
+            result = await db.execute(
+                select(Participant).where(
+                    Participant.race_id == uuid.UUID(race_id),
+                    Participant.user_id == players[0]["user"].id,
+                )
+            )
+            p = result.scalar_one()
+            return p.id, p.igt_ms, p.death_count
+
+    participant_id, igt_ms, death_count = asyncio.run(check_db())
+    assert (igt_ms, death_count) == (5000, 2)
+    assert not manager.is_mod_connected(uuid.UUID(race_id), participant_id)
+
+
 # =============================================================================
 # Scenario 4: Zone History Accumulation
 # =============================================================================
@@ -3,9 +3,11 @@
 from speedfog_racing.services.layer_service import (
     _format_zone_name,
     compute_zone_update,
+    get_display_name_for_node,
     get_layer_for_node,
     get_start_node,
     get_tier_for_node,
+    is_boss_node,
 )
 
 
@@ -59,6 +61,25 @@ def test_get_tier_for_node_no_nodes():
     assert get_tier_for_node("any", {}) is None
 
 
+def test_get_display_name_for_node():
+    graph = {
+        "nodes": {
+            "stormveil_a1b2": {"display_name": "Stormveil Castle"},
+            "node_a": {"layer": 1},
+        }
+    }
+    assert get_display_name_for_node("stormveil_a1b2", graph) == "Stormveil Castle"
+    assert get_display_name_for_node("node_a", graph) is None
+    assert get_display_name_for_node("unknown_node", graph) is None
+
+
+def test_is_boss_node():
+    graph = {"nodes": {"arena": {"type": "boss_arena"}, "field": {"type": "legacy_dungeon"}}}
+    assert is_boss_node("arena", graph)
+    assert not is_boss_node("field", graph)
+    assert not is_boss_node("unknown_node", graph)
+
+
 def test_get_start_node_found():
     graph = {
         "nodes": {
@@ -153,6 +174,7 @@ def test_compute_zone_update_basic():
     assert result["exits"][0]["text"] == "Soldier of Godrick front"
     assert result["exits"][0]["to_name"] == "Road's End Catacombs"
     assert result["exits"][0]["discovered"] is False
+    assert result["exits"][0]["to_node_id"] == "catacombs_a123"
     assert result["exits"][1]["to_name"] == "Ruin-Strewn Precipice"
     assert result["exits"][1]["discovered"] is False
 
@@ -169,6 +191,35 @@ def test_compute_zone_update_discovered():
     assert result["exits"][1]["discovered"] is False  # precipice_b456 not in history
 
 
+def test_compute_zone_update_exit_location():
+    """Fog gate map_id + position are forwarded when the seed has them."""
+    graph = {
+        "nodes": {
+            "cave_e235": {
+                "display_name": "Cave of Knowledge",
+                "exits": [
+                    {
+                        "text": "Front door",
+                        "to": "a",
+                        "map_id": "m31_17_00_00",
+                        "position": [1, -2.5, 30],
+                    },
+                    {"text": "Back door", "to": "b", "map_id": "m31_17_00_00", "position": [1, 2]},
+                    {"text": "Side door", "to": "c"},
+                ],
+            }
+        }
+    }
+    result = compute_zone_update("cave_e235", graph, zone_history=None)
+    assert result is not None
+    exits = result["exits"]
+    assert exits[0]["map_id"] == "m31_17_00_00"
+    assert exits[0]["position"] == [1.0, -2.5, 30.0]
+    # Malformed or missing locations are dropped
+    assert "position" not in exits[1]
+    assert "map_id" not in exits[2]
+
+
 def test_compute_zone_update_node_not_found():
     """Returns None for unknown node."""
     result = compute_zone_update("nonexistent", GRAPH_WITH_EXITS, zone_history=None)
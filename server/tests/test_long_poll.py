@@ -0,0 +1,119 @@
+"""Tests for the HTTP long-poll fallback of the mod WebSocket."""
+
+import asyncio
+import json
+import uuid
+
+import pytest
+from fastapi import WebSocketDisconnect
+
+from speedfog_racing.websocket import long_poll
+from speedfog_racing.websocket.common import receive_mod_message
+from speedfog_racing.websocket.long_poll import LongPollSessions, LongPollSocket
+
+
+async def echo_handler(socket: LongPollSocket) -> None:
+    """Answer each message with its type, like a tiny mod handler."""
+    await socket.accept()
+    while True:
+        msg = await receive_mod_message(socket)  # type: ignore[arg-type]
+        if msg is None:
+            continue
+        await socket.send_text(json.dumps({"type": "echo", "of": msg.get("type")}))
+
+
+class TestLongPollSocket:
+    async def test_messages_flow_both_ways(self):
+        socket = LongPollSocket()
+        socket.push(['{"type": "auth"}'])
+        assert await socket.receive_text() == '{"type": "auth"}'
+
+        await socket.send_text('{"type": "auth_ok"}')
+        await socket.send_text('{"type": "ping"}')
+        messages, closed = await socket.poll(wait=0.0)
+        assert messages == ['{"type": "auth_ok"}', '{"type": "ping"}']
+        assert not closed
+
+    async def test_poll_waits_for_a_message(self):
+        socket = LongPollSocket()
+        poll = asyncio.create_task(socket.poll(wait=5.0))
+        await asyncio.sleep(0)
+        await socket.send_text('{"type": "ping"}')
+        messages, closed = await asyncio.wait_for(poll, timeout=1.0)
+        assert messages == ['{"type": "ping"}']
+        assert not closed
+
+    async def test_poll_times_out_empty(self):
+        socket = LongPollSocket()
+        assert await socket.poll(wait=0.01) == ([], False)
+
+    async def test_close_reaches_both_sides(self):
+        socket = LongPollSocket()
+        await socket.send_text('{"type": "error"}')
+        await socket.close(code=4003, reason="Invalid mod token or race")
+
+        with pytest.raises(WebSocketDisconnect):
+            await socket.receive_text()
+        # Queued messages are delivered before the close is reported
+        assert await socket.poll(wait=0.0) == (['{"type": "error"}'], False)
+        assert await socket.poll(wait=0.0) == ([], True)
+        assert socket.close_reason == "Invalid mod token or race"
+
+    async def test_binary_frames_refused(self):
+        socket = LongPollSocket()
+        with pytest.raises(RuntimeError):
+            await socket.send_bytes(b"\x80")
+
+    async def test_idle_session_fails_sends(self):
+        socket = LongPollSocket(idle_timeout=0.0)
+        await asyncio.sleep(0.01)
+        with pytest.raises(RuntimeError):
+            await socket.send_text('{"type": "ping"}')
+
+
+class TestLongPollSessions:
+    async def test_handler_runs_in_background(self):
+        sessions = LongPollSessions()
+        session_id = sessions.open(echo_handler)
+        socket = sessions.get(session_id)
+        assert socket is not None
+
+        socket.push(['{"type": "pong"}'])
+        messages, _ = await socket.poll(wait=1.0)
+        assert [json.loads(m) for m in messages] == [{"type": "echo", "of": "pong"}]
+
+        await sessions.close(session_id)
+        assert await socket.poll(wait=1.0) == ([], True)
+
+    async def test_idle_sessions_expire(self):
+        sessions = LongPollSessions(idle_timeout=0.0)
+        session_id = sessions.open(echo_handler)
+        socket = sessions.get(session_id)
+        await asyncio.sleep(0.01)
+
+        # First pass closes it, and the handler returns on the disconnect
+        sessions.expire_idle()
+        assert socket.closed
+        await asyncio.sleep(0.01)
+        # Second pass forgets it
+        sessions.expire_idle()
+        assert sessions.get(session_id) is None
+        assert len(sessions) == 0
+
+    async def test_unknown_session(self):
+        sessions = LongPollSessions()
+        assert sessions.get(uuid.uuid4()) is None
+
+    async def test_sessions_capped_per_client(self, monkeypatch):
+        monkeypatch.setattr(long_poll, "MAX_SESSIONS_PER_CLIENT", 2)
+        sessions = LongPollSessions()
+        first = sessions.open(echo_handler, "203.0.113.7")
+        assert sessions.open(echo_handler, "203.0.113.7") is not None
+        assert sessions.open(echo_handler, "203.0.113.7") is None
+        # Other addresses aren't affected
+        assert sessions.open(echo_handler, "198.51.100.2") is not None
+        assert sessions.count("203.0.113.7") == 2
+
+        # A closed session still counts until the mod stops polling it
+        await sessions.close(first)
+        assert sessions.open(echo_handler, "203.0.113.7") is None
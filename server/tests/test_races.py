@@ -3,7 +3,7 @@
 import json
 import tempfile
 import zipfile
-from datetime import UTC, datetime
+from datetime import UTC, datetime, timedelta
 from pathlib import Path
 
 import pytest
@@ -21,6 +21,7 @@ from speedfog_racing.models import (
     User,
     UserRole,
 )
+from speedfog_racing.websocket.manager import PAUSE_GRANTED, PAUSE_REQUESTED, manager
 
 
 @pytest.fixture
@@ -681,6 +682,35 @@ async def test_start_race(test_client, organizer, seed):
         assert data["started_at"] is not None
 
 
+@pytest.mark.asyncio
+async def test_start_race_with_countdown(test_client, organizer, seed, monkeypatch):
+    """A configured countdown puts the start timestamp in the future."""
+    monkeypatch.setattr("speedfog_racing.api.races.settings.race_start_countdown_seconds", 10)
+    async with test_client as client:
+        create_response = await client.post(
+            "/api/races",
+            json={"name": "Test Race"},
+            headers={"Authorization": f"Bearer {organizer.api_token}"},
+        )
+        race_id = create_response.json()["id"]
+        await client.post(
+            f"/api/races/{race_id}/release-seeds",
+            headers={"Authorization": f"Bearer {organizer.api_token}"},
+        )
+
+        response = await client.post(
+            f"/api/races/{race_id}/start",
+            headers={"Authorization": f"Bearer {organizer.api_token}"},
+        )
+        assert response.status_code == 200
+        data = response.json()
+        assert data["status"] == "running"
+        started_at = datetime.fromisoformat(data["started_at"])
+        if started_at.tzinfo is None:
+            started_at = started_at.replace(tzinfo=UTC)
+        assert started_at > datetime.now(UTC) + timedelta(seconds=5)
+
+
 @pytest.mark.asyncio
 async def test_cannot_start_already_started_race(test_client, organizer, seed):
     """Cannot start a race that's already started."""
@@ -1860,6 +1890,131 @@ async def test_abandon_race_registered_participant(test_client, organizer, playe
         assert response.status_code == 200
 
 
+# --- participant pause ---
+
+
+async def _create_running_race(async_session, organizer, player, seed_number):
+    """A running race with one playing participant, returns (race_id, participant_id)."""
+    async with async_session() as db:
+        seed = Seed(
+            seed_number=seed_number,
+            pool_name="standard",
+            graph_json={"total_layers": 10, "nodes": []},
+            total_layers=10,
+            folder_path=f"/test/{seed_number}",
+            status=SeedStatus.CONSUMED,
+        )
+        db.add(seed)
+        await db.flush()
+
+        race = Race(
+            name="Running Race",
+            organizer_id=organizer.id,
+            seed_id=seed.id,
+            status=RaceStatus.RUNNING,
+            started_at=datetime.now(UTC),
+        )
+        db.add(race)
+        await db.flush()
+
+        participant = Participant(
+            race_id=race.id,
+            user_id=player.id,
+            status=ParticipantStatus.PLAYING,
+        )
+        db.add(participant)
+        await db.commit()
+        return race.id, participant.id
+
+
+@pytest.mark.asyncio
+async def test_grant_and_resume_pause(test_client, organizer, player, async_session):
+    """The organizer grants a requested pause, then resumes the participant."""
+    race_id, participant_id = await _create_running_race(async_session, organizer, player, "s980")
+    headers = {"Authorization": f"Bearer {organizer.api_token}"}
+    base = f"/api/races/{race_id}/participants/{participant_id}"
+
+    async with test_client as client:
+        # Nothing to grant before the mod asks
+        response = await client.post(f"{base}/pause", headers=headers)
+        assert response.status_code == 400
+
+        manager.pauses[participant_id] = PAUSE_REQUESTED
+        try:
+            response = await client.post(f"{base}/pause", headers=headers)
+            assert response.status_code == 204
+            assert manager.pauses[participant_id] == PAUSE_GRANTED
+
+            response = await client.post(f"{base}/resume", headers=headers)
+            assert response.status_code == 204
+            assert participant_id not in manager.pauses
+
+            response = await client.post(f"{base}/resume", headers=headers)
+            assert response.status_code == 400
+        finally:
+            manager.pauses.pop(participant_id, None)
+
+
+@pytest.mark.asyncio
+async def test_pause_non_organizer(test_client, organizer, player, async_session):
+    """Only the organizer can grant or end a pause."""
+    race_id, participant_id = await _create_running_race(async_session, organizer, player, "s981")
+    manager.pauses[participant_id] = PAUSE_REQUESTED
+    try:
+        async with test_client as client:
+            for action in ("pause", "resume"):
+                response = await client.post(
+                    f"/api/races/{race_id}/participants/{participant_id}/{action}",
+                    headers={"Authorization": f"Bearer {player.api_token}"},
+                )
+                assert response.status_code == 403
+        assert manager.pauses[participant_id] == PAUSE_REQUESTED
+    finally:
+        manager.pauses.pop(participant_id, None)
+
+
+# --- organizer-set event flags ---
+
+
+@pytest.mark.asyncio
+async def test_set_participant_flag(test_client, organizer, player, async_session, monkeypatch):
+    """Only whitelisted flags are sent, to mods that speak protocol 20+."""
+    race_id, participant_id = await _create_running_race(async_session, organizer, player, "s982")
+    async with async_session() as db:
+        race = await db.get(Race, race_id)
+        race.config = {"settable_flags": [10010500]}
+        await db.commit()
+
+    sent: list[str] = []
+
+    async def fake_send(race_id, participant_id, message):
+        sent.append(message)
+        return True
+
+    monkeypatch.setattr(manager, "send_to_mod", fake_send)
+    headers = {"Authorization": f"Bearer {organizer.api_token}"}
+    url = f"/api/races/{race_id}/participants/{participant_id}/flags"
+
+    async with test_client as client:
+        response = await client.post(url, json={"flag_id": 123}, headers=headers)
+        assert response.status_code == 400
+
+        # Mod not connected
+        response = await client.post(url, json={"flag_id": 10010500}, headers=headers)
+        assert response.status_code == 409
+
+        monkeypatch.setattr(manager, "mod_protocol_version", lambda race_id, pid: 20)
+        response = await client.post(url, json={"flag_id": 10010500}, headers=headers)
+        assert response.status_code == 204
+
+        player_headers = {"Authorization": f"Bearer {player.api_token}"}
+        response = await client.post(url, json={"flag_id": 10010500}, headers=player_headers)
+        assert response.status_code == 403
+
+    expected = {"type": "set_flag", "flag_id": 10010500, "value": True}
+    assert [json.loads(m) for m in sent] == [expected]
+
+
 # --- cast-join / cast-leave ---
 
 
@@ -6,29 +6,64 @@ from datetime import datetime
 from unittest.mock import AsyncMock, MagicMock
 
 import pytest
+from fastapi import WebSocketDisconnect
 
 from speedfog_racing.models import ParticipantStatus, RaceStatus
+from speedfog_racing.services.twitch_live import twitch_live_service
+from speedfog_racing.websocket import common as common_ws
+from speedfog_racing.websocket import mod as mod_ws
+from speedfog_racing.websocket.common import (
+    MOD_PROTOCOL_VERSION,
+    EventFlagDedup,
+    handle_event_flag_once,
+    negotiate_encoding,
+    negotiate_protocol_version,
+    pack_message,
+    parse_event_flag_key,
+    parse_event_flag_seq,
+    receive_mod_message,
+    send_clock_sync,
+)
 from speedfog_racing.websocket.manager import (
+    PAUSE_GRANTED,
+    PAUSE_REQUESTED,
     ConnectionManager,
+    ModConnection,
     RaceRoom,
     SpectatorConnection,
     participant_to_info,
+    redact_zone,
     sort_leaderboard,
 )
 from speedfog_racing.websocket.schemas import (
+    PERMISSION_LEADERBOARD,
+    PERMISSION_MAP,
+    PERMISSIONS_ALL,
     AuthErrorMessage,
     AuthOkMessage,
+    ClockSyncMessage,
     EventFlagMessage,
     ExitInfo,
     LeaderboardUpdateMessage,
     ParticipantInfo,
+    PauseGrantedMessage,
     PingMessage,
     PongMessage,
     RaceInfo,
+    RaceStartMessage,
     RaceStateMessage,
     RaceStatusChangeMessage,
     SeedInfo,
     ZoneUpdateMessage,
+    extract_checkpoints,
+    extract_event_delay,
+    extract_expected_character,
+    extract_key_items,
+    extract_objectives,
+    extract_permissions,
+    extract_settable_flags,
+    extract_start_location,
+    extract_zone_privacy,
 )
 
 # --- Mock Models ---
@@ -74,6 +109,14 @@ class MockParticipant:
         finished_at: datetime | None = None,
         color_index: int = 0,
         zone_history: list[dict] | None = None,
+        key_items: list[dict] | None = None,
+        objectives_completed: list[dict] | None = None,
+        checkpoints: list[dict] | None = None,
+        save_reloads: list[dict] | None = None,
+        kindling_uses: list[dict] | None = None,
+        graces_found: list[dict] | None = None,
+        timing_anomalies: list[dict] | None = None,
+        badges: list[str] | None = None,
     ):
         self.id = id or uuid.uuid4()
         self.race_id = race_id or uuid.uuid4()
@@ -88,6 +131,14 @@ class MockParticipant:
         self.finished_at = finished_at
         self.color_index = color_index
         self.zone_history = zone_history
+        self.key_items = key_items
+        self.objectives_completed = objectives_completed
+        self.checkpoints = checkpoints
+        self.save_reloads = save_reloads
+        self.kindling_uses = kindling_uses
+        self.graces_found = graces_found
+        self.timing_anomalies = timing_anomalies
+        self.badges = badges
 
 
 class MockRace:
@@ -98,12 +149,34 @@ class MockRace:
         status: RaceStatus = RaceStatus.SETUP,
         seed: MockSeed | None = None,
         participants: list | None = None,
+        config: dict | None = None,
     ):
         self.id = id or uuid.uuid4()
         self.name = name
         self.status = status
         self.seed = seed or MockSeed()
         self.participants = participants or []
+        self.config = config or {}
+
+
+# --- Shared Fixtures ---
+
+
+@pytest.fixture
+def running_participant() -> MockParticipant:
+    """A playing participant in a running race."""
+    participant = MockParticipant(status=ParticipantStatus.PLAYING)
+    participant.race = MockRace(status=RaceStatus.RUNNING, participants=[participant])
+    return participant
+
+
+@pytest.fixture
+def patched_mod_ws(monkeypatch, running_participant):
+    """Point the mod handlers at `running_participant` and a fake manager (returned)."""
+    monkeypatch.setattr(mod_ws, "_load_participant", AsyncMock(return_value=running_participant))
+    fake_manager = MagicMock(broadcast_leaderboard=AsyncMock())
+    monkeypatch.setattr(mod_ws, "manager", fake_manager)
+    return fake_manager
 
 
 # --- Schema Tests ---
@@ -184,6 +257,19 @@ class TestSchemas:
         assert data["type"] == "auth_ok"
         assert data["participant_id"] == "abc-123"
         assert data["race"]["name"] == "Race"
+        assert data["protocol_version"] == 1
+        assert data["permissions"] == PERMISSIONS_ALL
+
+    def test_auth_ok_message_with_protocol_version(self):
+        """Negotiated protocol version is echoed to the mod."""
+        msg = AuthOkMessage(
+            participant_id="abc-123",
+            race=RaceInfo(id="1", name="Race", status="setup"),
+            seed=SeedInfo(total_layers=10),
+            participants=[],
+            protocol_version=2,
+        )
+        assert json.loads(msg.model_dump_json())["protocol_version"] == 2
 
     def test_auth_error_message(self):
         """Test AuthErrorMessage serialization."""
@@ -265,6 +351,22 @@ class TestSchemas:
         data = json.loads(msg.model_dump_json())
         assert data == {"type": "pong"}
 
+    def test_race_start_message_started_at(self):
+        """RaceStartMessage carries the authoritative start time."""
+        assert json.loads(RaceStartMessage().model_dump_json()) == {
+            "type": "race_start",
+            "started_at": None,
+        }
+        msg = RaceStartMessage(started_at="2026-02-28T20:00:00+00:00")
+        data = json.loads(msg.model_dump_json())
+        assert data["started_at"] == "2026-02-28T20:00:00+00:00"
+
+    def test_clock_sync_message(self):
+        """Test ClockSyncMessage schema."""
+        msg = ClockSyncMessage(client_time_ms=1000, server_time_ms=1250)
+        data = json.loads(msg.model_dump_json())
+        assert data == {"type": "clock_sync", "client_time_ms": 1000, "server_time_ms": 1250}
+
     def test_zone_update_message(self):
         """Test ZoneUpdateMessage serialization."""
         msg = ZoneUpdateMessage(
@@ -414,6 +516,1175 @@ class TestConnectionManager:
 # --- Leaderboard Tests ---
 
 
+class TestClockSync:
+    @pytest.mark.asyncio
+    async def test_reply_echoes_client_time(self):
+        """clock_sync reply echoes the probe and adds the server time."""
+        websocket = AsyncMock()
+        before = int(datetime.now().timestamp() * 1000)
+        await send_clock_sync(websocket, {"type": "clock_sync", "client_time_ms": 42})
+        data = json.loads(websocket.send_text.call_args[0][0])
+        assert data["type"] == "clock_sync"
+        assert data["client_time_ms"] == 42
+        assert data["server_time_ms"] >= before
+
+    @pytest.mark.asyncio
+    async def test_invalid_probe_ignored(self):
+        """Malformed probes get no reply."""
+        websocket = AsyncMock()
+        await send_clock_sync(websocket, {"type": "clock_sync", "client_time_ms": "soon"})
+        await send_clock_sync(websocket, {"type": "clock_sync"})
+        websocket.send_text.assert_not_called()
+
+
+class TestProtocolVersion:
+    def test_legacy_mod_gets_version_1(self):
+        """Mods predating negotiation send no protocol_version."""
+        assert negotiate_protocol_version({"type": "auth", "mod_token": "t"}) == 1
+
+    def test_negotiates_lower_version(self):
+        """The connection uses the lower of the mod's and the server's version."""
+        assert negotiate_protocol_version({"protocol_version": 2}) == 2
+        assert negotiate_protocol_version({"protocol_version": 99}) == MOD_PROTOCOL_VERSION
+        assert negotiate_protocol_version({"protocol_version": 0}) == 1
+
+    def test_invalid_version_is_legacy(self):
+        assert negotiate_protocol_version({"protocol_version": "2"}) == 1
+        assert negotiate_protocol_version({"protocol_version": True}) == 1
+
+
+class TestEncoding:
+    """MessagePack negotiated at auth (protocol 11+)."""
+
+    @pytest.fixture
+    def msgpack(self, monkeypatch):
+        msgpack = pytest.importorskip("msgpack")
+        monkeypatch.setattr(common_ws, "_msgpack", msgpack)
+        return msgpack
+
+    def test_negotiate_encoding(self, monkeypatch):
+        monkeypatch.setattr(common_ws, "_msgpack", MagicMock())
+        offer = {"encodings": ["msgpack"]}
+        assert negotiate_encoding(offer, 11) == "msgpack"
+        assert negotiate_encoding(offer, 10) == "json"
+        assert negotiate_encoding({}, 11) == "json"
+        assert negotiate_encoding({"encodings": ["cbor"]}, 11) == "json"
+        assert negotiate_encoding({"encodings": "msgpack"}, 11) == "json"
+
+        # Server installed without the msgpack package
+        monkeypatch.setattr(common_ws, "_msgpack", None)
+        assert negotiate_encoding(offer, 11) == "json"
+
+    @pytest.mark.asyncio
+    async def test_receive_text_and_binary_frames(self, msgpack):
+        ws = AsyncMock()
+        ws.receive.side_effect = [
+            {"type": "websocket.receive", "text": '{"type": "ready"}'},
+            {"type": "websocket.receive", "bytes": msgpack.packb({"type": "pong"})},
+            {"type": "websocket.receive", "text": "not json {{{"},
+            {"type": "websocket.receive", "bytes": msgpack.packb([1, 2])},
+            {"type": "websocket.disconnect", "code": 1000},
+        ]
+        assert await receive_mod_message(ws) == {"type": "ready"}
+        assert await receive_mod_message(ws) == {"type": "pong"}
+        assert await receive_mod_message(ws) is None
+        assert await receive_mod_message(ws) is None
+        with pytest.raises(WebSocketDisconnect):
+            await receive_mod_message(ws)
+
+    @pytest.mark.asyncio
+    async def test_broadcast_per_connection_encoding(self, msgpack):
+        room = RaceRoom(race_id=uuid.uuid4())
+        for encoding in ("json", "msgpack"):
+            pid = uuid.uuid4()
+            room.mods[pid] = ModConnection(
+                websocket=AsyncMock(), participant_id=pid, user_id=uuid.uuid4(), encoding=encoding
+            )
+        message = json.dumps({"type": "race_status_change", "status": "running"})
+
+        await room.broadcast_to_mods(message)
+        json_conn, msgpack_conn = room.mods.values()
+        json_conn.websocket.send_text.assert_awaited_once_with(message)
+        msgpack_conn.websocket.send_text.assert_not_called()
+        packed = msgpack_conn.websocket.send_bytes.await_args[0][0]
+        assert msgpack.unpackb(packed) == json.loads(message)
+        assert packed == pack_message(message)
+
+
+class TestEventFlagAck:
+    """Event flags with a correlation id are processed once and acknowledged."""
+
+    def test_parse_seq(self):
+        assert parse_event_flag_seq({"flag_id": 1, "seq": 42}) == 42
+        assert parse_event_flag_seq({"flag_id": 1}) is None
+        assert parse_event_flag_seq({"flag_id": 1, "seq": "42"}) is None
+        assert parse_event_flag_seq({"flag_id": 1, "seq": True}) is None
+
+    def test_parse_key(self):
+        assert parse_event_flag_key({"idempotency_key": "r:00ff:1"}) == "r:00ff:1"
+        assert parse_event_flag_key({"flag_id": 1}) is None
+        assert parse_event_flag_key({"idempotency_key": ""}) is None
+        assert parse_event_flag_key({"idempotency_key": 42}) is None
+
+    @pytest.mark.asyncio
+    async def test_processes_and_acks(self):
+        websocket = AsyncMock()
+        handler = AsyncMock()
+        pid = uuid.uuid4()
+        msg = {"type": "event_flag", "flag_id": 100, "igt_ms": 5000, "seq": 7}
+        await handle_event_flag_once(websocket, pid, msg, handler, dedup=EventFlagDedup())
+        handler.assert_awaited_once()
+        data = json.loads(websocket.send_text.call_args[0][0])
+        assert data == {"type": "event_flag_ack", "seq": 7}
+
+    @pytest.mark.asyncio
+    async def test_resend_is_deduplicated_across_connections(self):
+        """A resend after a dropped ack is acked again but not reprocessed."""
+        dedup = EventFlagDedup()
+        handler = AsyncMock()
+        pid = uuid.uuid4()
+        msg = {"type": "event_flag", "flag_id": 100, "igt_ms": 5000, "seq": 7}
+        await handle_event_flag_once(AsyncMock(), pid, msg, handler, dedup=dedup)
+        reconnected = AsyncMock()
+        await handle_event_flag_once(reconnected, pid, msg, handler, dedup=dedup)
+        handler.assert_awaited_once()
+        data = json.loads(reconnected.send_text.call_args[0][0])
+        assert data["seq"] == 7
+
+    @pytest.mark.asyncio
+    async def test_restarted_game_is_deduplicated_by_key(self):
+        """A restarted game resends the flag with a new seq but the same key."""
+        dedup = EventFlagDedup()
+        handler = AsyncMock()
+        pid = uuid.uuid4()
+        key = "race-1:ac3e35353be515b6:100"
+        first = {"type": "event_flag", "flag_id": 100, "igt_ms": 5000, "seq": 7}
+        await handle_event_flag_once(
+            AsyncMock(), pid, {**first, "idempotency_key": key}, handler, dedup=dedup
+        )
+        restarted = AsyncMock()
+        resend = {**first, "seq": 9_000_001, "idempotency_key": key}
+        await handle_event_flag_once(restarted, pid, resend, handler, dedup=dedup)
+        handler.assert_awaited_once()
+        data = json.loads(restarted.send_text.call_args[0][0])
+        assert data == {"type": "event_flag_ack", "seq": 9_000_001}
+        assert dedup.seen(pid, 9_000_001)
+
+    @pytest.mark.asyncio
+    async def test_same_seq_other_participant_is_processed(self):
+        dedup = EventFlagDedup()
+        handler = AsyncMock()
+        msg = {"type": "event_flag", "flag_id": 100, "igt_ms": 5000, "seq": 7}
+        await handle_event_flag_once(AsyncMock(), uuid.uuid4(), msg, handler, dedup=dedup)
+        await handle_event_flag_once(AsyncMock(), uuid.uuid4(), msg, handler, dedup=dedup)
+        assert handler.await_count == 2
+
+    @pytest.mark.asyncio
+    async def test_legacy_flag_without_seq_is_not_acked(self):
+        websocket = AsyncMock()
+        handler = AsyncMock()
+        msg = {"type": "event_flag", "flag_id": 100, "igt_ms": 5000}
+        await handle_event_flag_once(websocket, uuid.uuid4(), msg, handler, dedup=EventFlagDedup())
+        await handle_event_flag_once(websocket, uuid.uuid4(), msg, handler, dedup=EventFlagDedup())
+        assert handler.await_count == 2
+        websocket.send_text.assert_not_called()
+
+    @pytest.mark.asyncio
+    async def test_failed_handler_is_not_acked(self):
+        """No ack on failure, so the mod resends and the server retries."""
+        dedup = EventFlagDedup()
+        websocket = AsyncMock()
+        pid = uuid.uuid4()
+        msg = {"type": "event_flag", "flag_id": 100, "igt_ms": 5000, "seq": 7}
+        with pytest.raises(RuntimeError):
+            await handle_event_flag_once(
+                websocket, pid, msg, AsyncMock(side_effect=RuntimeError), dedup=dedup
+            )
+        websocket.send_text.assert_not_called()
+        assert not dedup.seen(pid, 7)
+
+    def test_dedup_history_is_bounded(self):
+        dedup = EventFlagDedup(max_per_participant=2)
+        pid = uuid.uuid4()
+        for seq in (1, 2, 3):
+            dedup.remember(pid, seq)
+        assert not dedup.seen(pid, 1)
+        assert dedup.seen(pid, 2) and dedup.seen(pid, 3)
+
+
+class TestKeyItems:
+    """Key item pickups: seed list, recording and broadcast."""
+
+    GRAPH = {
+        "layers": [],
+        "key_items": [
+            {"id": 8107, "flag_id": 400001, "name": "Rold Medallion"},
+            {"id": 8105, "flag_id": "400002", "name": "Dectus Medallion"},  # invalid flag
+            "bogus",
+        ],
+    }
+
+    def _participant(self, **kwargs) -> MockParticipant:
+        user = MockUser(twitch_username="p1", twitch_display_name="Player One")
+        participant = MockParticipant(user=user, status=ParticipantStatus.PLAYING, **kwargs)
+        participant.race = MockRace(
+            status=RaceStatus.RUNNING,
+            seed=MockSeed(graph_json=self.GRAPH),
+            participants=[participant],
+        )
+        return participant
+
+    def _patch(self, monkeypatch, participant):
+        monkeypatch.setattr(mod_ws, "_load_participant", AsyncMock(return_value=participant))
+        room = MagicMock(broadcast_to_mods=AsyncMock())
+        fake_manager = MagicMock(
+            get_room=MagicMock(return_value=room), broadcast_leaderboard=AsyncMock()
+        )
+        monkeypatch.setattr(mod_ws, "manager", fake_manager)
+        return room, fake_manager
+
+    def test_extract_key_items_skips_invalid_entries(self):
+        items = extract_key_items(self.GRAPH)
+        assert [(i.id, i.flag_id, i.name) for i in items] == [(8107, 400001, "Rold Medallion")]
+        assert extract_key_items({"layers": []}) == []
+
+    def test_participant_info_lists_obtained_items(self):
+        participant = MockParticipant(key_items=[{"item_id": 8107, "igt_ms": 5000}])
+        assert participant_to_info(participant).key_items == [8107]
+        assert participant_to_info(MockParticipant()).key_items == []
+
+    @pytest.mark.asyncio
+    async def test_item_obtained_recorded_once_and_broadcast(self, monkeypatch):
+        participant = self._participant()
+        room, fake_manager = self._patch(monkeypatch, participant)
+        msg = {"type": "item_obtained", "item_id": 8107, "igt_ms": 5000}
+
+        await mod_ws.handle_item_obtained(MagicMock(), participant.id, msg)
+        assert participant.key_items == [{"item_id": 8107, "igt_ms": 5000}]
+        payload, kwargs = room.broadcast_to_mods.call_args
+        data = json.loads(payload[0])
+        assert data["type"] == "item_obtained"
+        assert data["player_name"] == "Player One"
+        assert data["item_name"] == "Rold Medallion"
+        assert kwargs == {"min_protocol_version": 4}
+        fake_manager.broadcast_leaderboard.assert_awaited_once()
+
+        # Resent after a reconnect: not recorded or announced again
+        await mod_ws.handle_item_obtained(MagicMock(), participant.id, msg)
+        assert len(participant.key_items) == 1
+        room.broadcast_to_mods.assert_awaited_once()
+
+    @pytest.mark.asyncio
+    async def test_unknown_item_ignored(self, monkeypatch):
+        participant = self._participant()
+        room, _ = self._patch(monkeypatch, participant)
+        await mod_ws.handle_item_obtained(
+            MagicMock(), participant.id, {"type": "item_obtained", "item_id": 1, "igt_ms": 0}
+        )
+        assert participant.key_items is None
+        room.broadcast_to_mods.assert_not_called()
+
+    @pytest.mark.asyncio
+    async def test_broadcast_skips_older_protocols(self):
+        room = RaceRoom(race_id=uuid.uuid4())
+        old_ws, new_ws = AsyncMock(), AsyncMock()
+        for ws, version in ((old_ws, 3), (new_ws, 4)):
+            pid = uuid.uuid4()
+            room.mods[pid] = ModConnection(
+                websocket=ws, participant_id=pid, user_id=uuid.uuid4(), protocol_version=version
+            )
+        await room.broadcast_to_mods('{"type": "test"}', min_protocol_version=4)
+        old_ws.send_text.assert_not_called()
+        new_ws.send_text.assert_called_once_with('{"type": "test"}')
+
+
+class TestObjectives:
+    """Race objectives (bingo / scavenger races): config list and completions."""
+
+    CONFIG = {
+        "objectives": [
+            {"text": "Kill Margit", "flag_id": 10000800},
+            {"text": "Get the Rold Medallion", "flag_id": "400001"},  # invalid flag
+            {"text": "Reach Leyndell", "flag_id": 11000000},
+        ]
+    }
+
+    def _participant(self, **kwargs) -> MockParticipant:
+        participant = MockParticipant(status=ParticipantStatus.PLAYING, **kwargs)
+        participant.race = MockRace(
+            status=RaceStatus.RUNNING, participants=[participant], config=self.CONFIG
+        )
+        return participant
+
+    def _patch(self, monkeypatch, participant):
+        monkeypatch.setattr(mod_ws, "_load_participant", AsyncMock(return_value=participant))
+        fake_manager = MagicMock(broadcast_leaderboard=AsyncMock())
+        monkeypatch.setattr(mod_ws, "manager", fake_manager)
+        return fake_manager
+
+    def test_extract_objectives_keeps_positions(self):
+        objectives = extract_objectives(self.CONFIG)
+        assert [(o.id, o.text, o.flag_id) for o in objectives] == [
+            (0, "Kill Margit", 10000800),
+            (2, "Reach Leyndell", 11000000),
+        ]
+        assert extract_objectives({}) == []
+        assert extract_objectives(None) == []
+        assert extract_objectives({"objectives": "bogus"}) == []
+
+    def test_participant_info_lists_completed_objectives(self):
+        participant = MockParticipant(objectives_completed=[{"objective_id": 2, "igt_ms": 5000}])
+        assert participant_to_info(participant).objectives_completed == [2]
+        assert participant_to_info(MockParticipant()).objectives_completed == []
+
+    @pytest.mark.asyncio
+    async def test_objective_complete_recorded_once(self, monkeypatch):
+        participant = self._participant()
+        fake_manager = self._patch(monkeypatch, participant)
+        msg = {"type": "objective_complete", "objective_id": 2, "igt_ms": 5000}
+
+        await mod_ws.handle_objective_complete(MagicMock(), participant.id, msg)
+        assert participant.objectives_completed == [{"objective_id": 2, "igt_ms": 5000}]
+        fake_manager.broadcast_leaderboard.assert_awaited_once()
+
+        # Resent after a reconnect: not recorded again
+        await mod_ws.handle_objective_complete(MagicMock(), participant.id, msg)
+        assert len(participant.objectives_completed) == 1
+        fake_manager.broadcast_leaderboard.assert_awaited_once()
+
+    @pytest.mark.asyncio
+    async def test_unknown_objective_ignored(self, monkeypatch):
+        participant = self._participant()
+        fake_manager = self._patch(monkeypatch, participant)
+        for objective_id in (1, 7, "2"):
+            await mod_ws.handle_objective_complete(
+                MagicMock(),
+                participant.id,
+                {"type": "objective_complete", "objective_id": objective_id, "igt_ms": 0},
+            )
+        assert participant.objectives_completed is None
+        fake_manager.broadcast_leaderboard.assert_not_called()
+
+
+class TestCheckpoints:
+    """Intermediate race checkpoints: config list and crossings."""
+
+    CONFIG = {
+        "checkpoints": [
+            {"name": "Margit", "flag_id": 10000800},
+            {"name": "Liurnia", "node_id": "liurnia_main"},
+            {"name": "Nowhere"},  # neither flag nor zone
+            {"name": "Leyndell", "flag_id": True},  # invalid flag
+        ]
+    }
+
+    def _participant(self, **kwargs) -> MockParticipant:
+        participant = MockParticipant(status=ParticipantStatus.PLAYING, **kwargs)
+        participant.race = MockRace(
+            status=RaceStatus.RUNNING, participants=[participant], config=self.CONFIG
+        )
+        return participant
+
+    def _patch(self, monkeypatch, participant):
+        monkeypatch.setattr(mod_ws, "_load_participant", AsyncMock(return_value=participant))
+        fake_manager = MagicMock(broadcast_leaderboard=AsyncMock())
+        monkeypatch.setattr(mod_ws, "manager", fake_manager)
+        return fake_manager
+
+    def test_extract_checkpoints(self):
+        checkpoints = extract_checkpoints(self.CONFIG)
+        assert [(c.id, c.name, c.flag_id, c.node_id) for c in checkpoints] == [
+            (0, "Margit", 10000800, None),
+            (1, "Liurnia", None, "liurnia_main"),
+        ]
+        assert extract_checkpoints(None) == []
+        assert extract_checkpoints({"checkpoints": {}}) == []
+
+    def test_participant_info_lists_checkpoint_times(self):
+        participant = MockParticipant(checkpoints=[{"checkpoint_id": 1, "igt_ms": 90000}])
+        times = participant_to_info(participant).checkpoints
+        assert [(t.checkpoint_id, t.igt_ms) for t in times] == [(1, 90000)]
+        assert participant_to_info(MockParticipant()).checkpoints == []
+
+    @pytest.mark.asyncio
+    async def test_checkpoint_crossed_recorded_once(self, monkeypatch):
+        participant = self._participant()
+        fake_manager = self._patch(monkeypatch, participant)
+        msg = {"type": "checkpoint_crossed", "checkpoint_id": 1, "igt_ms": 90000}
+
+        await mod_ws.handle_checkpoint_crossed(MagicMock(), participant.id, msg)
+        assert participant.checkpoints == [{"checkpoint_id": 1, "igt_ms": 90000}]
+        fake_manager.broadcast_leaderboard.assert_awaited_once()
+
+        await mod_ws.handle_checkpoint_crossed(MagicMock(), participant.id, msg)
+        assert len(participant.checkpoints) == 1
+        fake_manager.broadcast_leaderboard.assert_awaited_once()
+
+    @pytest.mark.asyncio
+    async def test_unknown_checkpoint_ignored(self, monkeypatch):
+        participant = self._participant()
+        fake_manager = self._patch(monkeypatch, participant)
+        for checkpoint_id in (2, 3, "0"):
+            await mod_ws.handle_checkpoint_crossed(
+                MagicMock(),
+                participant.id,
+                {"type": "checkpoint_crossed", "checkpoint_id": checkpoint_id, "igt_ms": 0},
+            )
+        assert participant.checkpoints is None
+        fake_manager.broadcast_leaderboard.assert_not_called()
+
+
+class TestPermissions:
+    """Mod features turned off by the race config."""
+
+    def test_everything_allowed_by_default(self):
+        assert extract_permissions(None) == PERMISSIONS_ALL
+        assert extract_permissions({}) == PERMISSIONS_ALL
+        assert extract_permissions({"permissions": "bogus"}) == PERMISSIONS_ALL
+
+    def test_blind_race(self):
+        config = {"permissions": {"leaderboard": False, "map": False, "exits_panel": True}}
+        hidden = PERMISSION_LEADERBOARD | PERMISSION_MAP
+        assert extract_permissions(config) == PERMISSIONS_ALL & ~hidden
+
+    def test_only_false_disables(self):
+        config = {"permissions": {"leaderboard": 0, "map": None, "unknown": False}}
+        assert extract_permissions(config) == PERMISSIONS_ALL
+
+
+class TestSettableFlags:
+    """Event flags the organizer may set in the participants' games."""
+
+    def test_extract(self):
+        assert extract_settable_flags(None) == set()
+        assert extract_settable_flags({"settable_flags": "bogus"}) == set()
+        config = {"settable_flags": [10010500, "11000000", True, 10010500]}
+        assert extract_settable_flags(config) == {10010500}
+
+
+class TestEventDelay:
+    """Broadcast delay the mods apply to their progress events."""
+
+    def test_extract(self):
+        assert extract_event_delay(None) == 0
+        assert extract_event_delay({"event_delay_secs": "30"}) == 0
+        assert extract_event_delay({"event_delay_secs": True}) == 0
+        assert extract_event_delay({"event_delay_secs": 45}) == 45
+        assert extract_event_delay({"event_delay_secs": -5}) == 0
+        assert extract_event_delay({"event_delay_secs": 86400}) == 600
+
+
+class TestZonePrivacy:
+    """How much of the other participants' zones the mod leaderboard shows."""
+
+    def test_extract(self):
+        assert extract_zone_privacy(None) == "name"
+        assert extract_zone_privacy({"zone_privacy": "tier"}) == "tier"
+        assert extract_zone_privacy({"zone_privacy": "hidden"}) == "hidden"
+        # Unknown modes never reveal more than intended
+        assert extract_zone_privacy({"zone_privacy": "blurred"}) == "hidden"
+
+    def test_participant_zone_name(self):
+        graph = {"nodes": {"node_a": {"display_name": "Stormveil Castle", "tier": 3}}}
+        participant = MockParticipant(user=MockUser(), current_zone="node_a")
+        info = participant_to_info(participant, graph_json=graph)
+        assert info.current_zone_name == "Stormveil Castle"
+        assert participant_to_info(participant).current_zone_name is None
+
+    def test_redact_zone(self):
+        graph = {"nodes": {"node_a": {"display_name": "Stormveil Castle", "tier": 3}}}
+        participant = MockParticipant(
+            user=MockUser(),
+            current_zone="node_a",
+            zone_history=[{"node_id": "node_a", "igt_ms": 1000}],
+        )
+        info = participant_to_info(participant, graph_json=graph)
+        assert redact_zone(info, "name") == info
+
+        tier = redact_zone(info, "tier")
+        assert (tier.current_zone, tier.current_zone_name, tier.zone_history) == (None, None, None)
+        assert tier.current_layer_tier == 3
+
+        hidden = redact_zone(info, "hidden")
+        assert hidden.current_zone is None
+        assert hidden.current_layer_tier is None
+        assert hidden.igt_ms == info.igt_ms
+
+    @pytest.mark.asyncio
+    async def test_mods_only_get_shown_zones(self):
+        """Every mod, older ones included, gets the redacted rows; spectators the full ones."""
+        manager = ConnectionManager()
+        race_id = uuid.uuid4()
+        room = manager.get_or_create_room(race_id)
+        room.zone_privacy = "hidden"
+        old_ws, new_ws, spectator_ws = AsyncMock(), AsyncMock(), AsyncMock()
+        for ws, version in ((old_ws, 18), (new_ws, 22)):
+            pid = uuid.uuid4()
+            room.mods[pid] = ModConnection(
+                websocket=ws, participant_id=pid, user_id=uuid.uuid4(), protocol_version=version
+            )
+        room.spectators.append(SpectatorConnection(websocket=spectator_ws))
+        participant = MockParticipant(
+            status=ParticipantStatus.PLAYING,
+            current_zone="node_a",
+            zone_history=[{"node_id": "node_a", "igt_ms": 1000}],
+        )
+
+        await manager.broadcast_leaderboard(race_id, [participant])
+        for ws in (old_ws, new_ws):
+            (row,) = json.loads(ws.send_text.call_args.args[0])["participants"]
+            assert (row["current_zone"], row["zone_history"]) == (None, None)
+        (row,) = json.loads(spectator_ws.send_text.call_args.args[0])["participants"]
+        assert row["current_zone"] == "node_a"
+
+        await manager.broadcast_player_update(race_id, participant)
+        assert json.loads(new_ws.send_text.call_args.args[0])["player"]["current_zone"] is None
+        player = json.loads(spectator_ws.send_text.call_args.args[0])["player"]
+        assert player["current_zone"] == "node_a"
+
+
+class TestRaceActivity:
+    """Participant milestones broadcast to the mods' activity feeds."""
+
+    GRAPH = {
+        "layers": [],
+        "event_map": {"1001": "castle", "1002": "liurnia"},
+        "finish_event": 9999,
+        "nodes": {
+            "start": {"type": "start", "layer": 0},
+            "castle": {"type": "boss_arena", "layer": 1, "display_name": "Stormveil Castle"},
+            "liurnia": {"layer": 2, "display_name": "Liurnia"},
+        },
+    }
+
+    def _participant(self, config: dict | None = None) -> MockParticipant:
+        user = MockUser(twitch_username="p1", twitch_display_name="Player One")
+        participant = MockParticipant(
+            user=user,
+            status=ParticipantStatus.PLAYING,
+            current_zone="start",
+            zone_history=[{"node_id": "start", "igt_ms": 0}],
+        )
+        participant.race = MockRace(
+            status=RaceStatus.RUNNING,
+            seed=MockSeed(graph_json=self.GRAPH),
+            participants=[participant],
+            config=config,
+        )
+        return participant
+
+    def _patch(self, monkeypatch, participant):
+        monkeypatch.setattr(mod_ws, "_load_participant", AsyncMock(return_value=participant))
+        monkeypatch.setattr(mod_ws, "send_zone_update", AsyncMock())
+        room = MagicMock(broadcast_to_mods=AsyncMock())
+        fake_manager = MagicMock(
+            get_room=MagicMock(return_value=room),
+            broadcast_leaderboard=AsyncMock(),
+            broadcast_player_update=AsyncMock(),
+        )
+        monkeypatch.setattr(mod_ws, "manager", fake_manager)
+        return room
+
+    async def _event_flag(self, participant, flag_id: int, igt_ms: int) -> None:
+        msg = {"type": "event_flag", "flag_id": flag_id, "igt_ms": igt_ms}
+        await mod_ws.handle_event_flag(AsyncMock(), MagicMock(), participant.id, msg)
+
+    @pytest.mark.asyncio
+    async def test_new_zone_then_boss_down(self, monkeypatch):
+        participant = self._participant()
+        room = self._patch(monkeypatch, participant)
+
+        await self._event_flag(participant, 1001, 5000)
+        payload, kwargs = room.broadcast_to_mods.call_args
+        assert json.loads(payload[0]) == {
+            "type": "race_activity",
+            "participant_id": str(participant.id),
+            "player_name": "Player One",
+            "kind": "zone",
+            "zone_name": "Stormveil Castle",
+            "igt_ms": 5000,
+        }
+        assert kwargs == {"min_protocol_version": 24}
+
+        # Leaving the boss arena: its boss is down
+        await self._event_flag(participant, 1002, 60000)
+        data = json.loads(room.broadcast_to_mods.call_args[0][0])
+        assert (data["kind"], data["zone_name"]) == ("boss", "Stormveil Castle")
+
+        # Revisits aren't milestones
+        await self._event_flag(participant, 1001, 90000)
+        assert room.broadcast_to_mods.await_count == 2
+
+    @pytest.mark.asyncio
+    async def test_zone_names_follow_privacy(self, monkeypatch):
+        participant = self._participant(config={"zone_privacy": "tier"})
+        room = self._patch(monkeypatch, participant)
+        await self._event_flag(participant, 1001, 5000)
+        data = json.loads(room.broadcast_to_mods.call_args[0][0])
+        assert data["kind"] == "zone"
+        assert data["zone_name"] is None
+
+    @pytest.mark.asyncio
+    async def test_finish(self, monkeypatch):
+        participant = self._participant()
+        room = self._patch(monkeypatch, participant)
+        await mod_ws.broadcast_race_activity(participant, "finish", 754000)
+        data = json.loads(room.broadcast_to_mods.call_args[0][0])
+        assert (data["kind"], data["igt_ms"], data["zone_name"]) == ("finish", 754000, None)
+
+
+class TestSegments:
+    """Run segments declared in the seed graph, timed by the mod."""
+
+    def test_mod_seed_info_segments(self):
+        graph = {
+            "segments": [
+                {"name": "Castle", "from_node": "stormveil", "to_node": "liurnia"},
+                {"name": "Broken", "from_node": "caelid"},
+                "bogus",
+            ]
+        }
+        info = mod_ws.build_mod_seed_info(MockSeed(graph_json=graph), MOD_PROTOCOL_VERSION)
+        assert [(s.name, s.from_node, s.to_node) for s in info.segments] == [
+            ("Castle", "stormveil", "liurnia")
+        ]
+        assert mod_ws.build_mod_seed_info(None, MOD_PROTOCOL_VERSION).segments == []
+
+
+class TestSeedChanged:
+    """Seed re-roll pushed to connected mods."""
+
+    GRAPH = {
+        "layers": [],
+        "key_items": [{"id": 8107, "flag_id": 400001, "name": "Rold Medallion"}],
+    }
+
+    def test_mod_seed_info_omits_graph(self):
+        seed = MockSeed(graph_json=self.GRAPH)
+        info = mod_ws.build_mod_seed_info(seed, MOD_PROTOCOL_VERSION)
+        assert info.seed_id == str(seed.id)
+        assert info.graph_json is None
+        assert [i.id for i in info.key_items] == [8107]
+        # key_items needs protocol 4
+        assert mod_ws.build_mod_seed_info(seed, 3).key_items == []
+        assert mod_ws.build_mod_seed_info(None, MOD_PROTOCOL_VERSION).seed_id is None
+
+    @pytest.mark.asyncio
+    async def test_broadcast_seed_changed(self, monkeypatch):
+        room = MagicMock(broadcast_to_mods=AsyncMock())
+        monkeypatch.setattr(mod_ws, "manager", MagicMock(get_room=MagicMock(return_value=room)))
+        seed = MockSeed(graph_json=self.GRAPH)
+
+        await mod_ws.broadcast_seed_changed(uuid.uuid4(), seed)
+        payload, kwargs = room.broadcast_to_mods.call_args
+        data = json.loads(payload[0])
+        assert data["type"] == "seed_changed"
+        assert data["seed"]["seed_id"] == str(seed.id)
+        assert kwargs == {"min_protocol_version": 6}
+
+    @pytest.mark.asyncio
+    async def test_broadcast_seed_changed_without_room(self, monkeypatch):
+        monkeypatch.setattr(mod_ws, "manager", MagicMock(get_room=MagicMock(return_value=None)))
+        await mod_ws.broadcast_seed_changed(uuid.uuid4(), MockSeed())
+
+
+class TestCharacterFingerprint:
+    """Character checks: fingerprint, NG+ cycle and seed expectation."""
+
+    CHARACTER = {"slot": 2, "name": "Tarnished", "level": 9, "class_id": 8}
+
+    def _room(self, monkeypatch, character=None):
+        pid = uuid.uuid4()
+        room = RaceRoom(race_id=uuid.uuid4())
+        room.mods[pid] = ModConnection(
+            websocket=AsyncMock(),
+            participant_id=pid,
+            user_id=uuid.uuid4(),
+            protocol_version=7,
+            character=character,
+        )
+        monkeypatch.setattr(mod_ws, "manager", MagicMock(get_room=MagicMock(return_value=room)))
+        return room, pid
+
+    def test_parse_character(self):
+        character = mod_ws.parse_character(self.CHARACTER)
+        assert character is not None
+        assert (character.slot, character.name, character.level) == (2, "Tarnished", 9)
+        assert mod_ws.parse_character(None) is None
+        assert mod_ws.parse_character({"name": "x"}) is None
+        # Slot is optional (unreadable on some game versions)
+        assert mod_ws.parse_character({"name": "x", "level": 1, "class_id": 0}) is not None
+
+    def test_leveling_up_is_same_character(self):
+        before = mod_ws.parse_character(self.CHARACTER)
+        after = mod_ws.parse_character({**self.CHARACTER, "level": 40})
+        other = mod_ws.parse_character({**self.CHARACTER, "slot": 3})
+        assert not mod_ws.is_other_character(before, after)
+        assert mod_ws.is_other_character(before, other)
+
+    def test_character_message_updates_connection(self, monkeypatch):
+        room, pid = self._room(monkeypatch, mod_ws.parse_character(self.CHARACTER))
+        mod_ws.handle_character(
+            room.race_id, pid, {"type": "character", "character": {**self.CHARACTER, "slot": 3}}
+        )
+        assert room.mods[pid].character.slot == 3
+
+        # Malformed fingerprints keep the previous one
+        mod_ws.handle_character(room.race_id, pid, {"type": "character", "character": "bogus"})
+        assert room.mods[pid].character.slot == 3
+
+    def test_ng_cycle_recorded(self, monkeypatch):
+        room, pid = self._room(monkeypatch)
+        mod_ws.handle_ng_cycle(room.race_id, pid, {"type": "ng_cycle", "clear_count": 1})
+        assert room.mods[pid].clear_count == 1
+
+        # Malformed counts are ignored
+        for bogus in (-1, "2", True, None):
+            mod_ws.handle_ng_cycle(room.race_id, pid, {"type": "ng_cycle", "clear_count": bogus})
+        assert room.mods[pid].clear_count == 1
+
+    def test_extract_expected_character(self):
+        expected = extract_expected_character({"starting_character": {"class_id": 8, "level": 9}})
+        assert expected is not None
+        assert (expected.class_id, expected.level) == (8, 9)
+        assert extract_expected_character({"layers": []}) is None
+        assert extract_expected_character({"starting_character": {"class_id": "x"}}) is None
+
+    def test_extract_start_location(self):
+        graph = {
+            "nodes": {
+                "chapel_start_4f96": {
+                    "type": "start",
+                    "map_id": "m10_01_00_00",
+                    "position": [1, 2.5, -3],
+                },
+                "stormveil_godrick_48fd": {"layer": 3},
+            }
+        }
+        start = extract_start_location(graph)
+        assert start is not None
+        assert start.node_id == "chapel_start_4f96"
+        assert (start.map_id, start.position) == ("m10_01_00_00", [1.0, 2.5, -3.0])
+
+        # Top-level start_node fallback, no grace location
+        start = extract_start_location({"nodes": {"a": {}}, "start_node": "a"})
+        assert start is not None
+        assert (start.node_id, start.map_id, start.position) == ("a", None, None)
+        assert extract_start_location({"nodes": {"a": {}}, "start_node": "b"}) is None
+
+    def test_expected_character_needs_protocol_7(self):
+        seed = MockSeed(graph_json={"layers": [], "starting_character": {"class_id": 8}})
+        assert mod_ws.build_mod_seed_info(seed, 6).expected_character is None
+        assert mod_ws.build_mod_seed_info(seed, 7).expected_character is not None
+
+
+class TestExitHints:
+    """Community exit hints for visited zones."""
+
+    GRAPH = {
+        "nodes": {
+            "start": {"zones": ["chapel_start"], "exits": [{"to": "castle", "text": "Gate"}]},
+            "castle": {"zones": ["stormveil"], "exits": [{"to": "start", "text": "Back"}]},
+        }
+    }
+
+    def _participant(self, **kwargs) -> MockParticipant:
+        participant = MockParticipant(status=ParticipantStatus.PLAYING, **kwargs)
+        participant.race = MockRace(
+            status=RaceStatus.RUNNING,
+            seed=MockSeed(graph_json=self.GRAPH),
+            participants=[participant],
+        )
+        return participant
+
+    def _patch(self, monkeypatch, participant):
+        monkeypatch.setattr(mod_ws, "_load_participant", AsyncMock(return_value=participant))
+        fake_manager = MagicMock(send_to_mod=AsyncMock(return_value=True))
+        monkeypatch.setattr(mod_ws, "manager", fake_manager)
+        return fake_manager
+
+    @pytest.fixture(autouse=True)
+    def _hints(self, monkeypatch):
+        monkeypatch.setattr(
+            mod_ws, "get_exit_hints", lambda: {"stormveil": "Leads to a legacy dungeon"}
+        )
+
+    @pytest.mark.asyncio
+    async def test_hints_sent_for_visited_zone(self, monkeypatch):
+        participant = self._participant(zone_history=[{"node_id": "start", "igt_ms": 0}])
+        fake_manager = self._patch(monkeypatch, participant)
+
+        await mod_ws.handle_exit_hints_request(
+            MagicMock(),
+            participant.race.id,
+            participant.id,
+            {"type": "exit_hints_request", "node_id": "start"},
+        )
+        race_id, participant_id, payload = fake_manager.send_to_mod.call_args[0]
+        assert (race_id, participant_id) == (participant.race.id, participant.id)
+        data = json.loads(payload)
+        assert data == {
+            "type": "exit_hints",
+            "node_id": "start",
+            "hints": [{"exit_index": 0, "text": "Leads to a legacy dungeon"}],
+        }
+
+    @pytest.mark.asyncio
+    async def test_unvisited_zone_not_answered(self, monkeypatch):
+        participant = self._participant(
+            current_zone="start", zone_history=[{"node_id": "start", "igt_ms": 0}]
+        )
+        fake_manager = self._patch(monkeypatch, participant)
+
+        for node_id in ("castle", 42):
+            await mod_ws.handle_exit_hints_request(
+                MagicMock(),
+                participant.race.id,
+                participant.id,
+                {"type": "exit_hints_request", "node_id": node_id},
+            )
+        fake_manager.send_to_mod.assert_not_called()
+
+
+class TestAfk:
+    """Idle players reported by their mod."""
+
+    def _room(self, monkeypatch):
+        participant = MockParticipant(status=ParticipantStatus.PLAYING)
+        participant.race = MockRace(status=RaceStatus.RUNNING, participants=[participant])
+        room = RaceRoom(race_id=participant.race.id)
+        room.mods[participant.id] = ModConnection(
+            websocket=AsyncMock(),
+            participant_id=participant.id,
+            user_id=uuid.uuid4(),
+            protocol_version=10,
+        )
+        manager = MagicMock(
+            get_room=MagicMock(return_value=room), broadcast_leaderboard=AsyncMock()
+        )
+        monkeypatch.setattr(mod_ws, "manager", manager)
+        monkeypatch.setattr(mod_ws, "_load_participant", AsyncMock(return_value=participant))
+        return room, participant.id, manager
+
+    @pytest.mark.asyncio
+    async def test_afk_broadcast_on_change(self, monkeypatch):
+        room, pid, manager = self._room(monkeypatch)
+        msg = {"type": "afk", "afk": True, "igt_ms": 5000}
+        await mod_ws.handle_afk(MagicMock(), room.race_id, pid, msg)
+        assert room.afk_ids() == {pid}
+        assert manager.broadcast_leaderboard.await_count == 1
+
+        # Repeats don't rebroadcast
+        await mod_ws.handle_afk(MagicMock(), room.race_id, pid, msg)
+        assert manager.broadcast_leaderboard.await_count == 1
+
+        await mod_ws.handle_afk(
+            MagicMock(), room.race_id, pid, {"type": "afk", "afk": False, "igt_ms": 9000}
+        )
+        assert room.afk_ids() == set()
+        assert manager.broadcast_leaderboard.await_count == 2
+
+    @pytest.mark.asyncio
+    async def test_malformed_afk_ignored(self, monkeypatch):
+        room, pid, manager = self._room(monkeypatch)
+        for bogus in (1, "true", None):
+            await mod_ws.handle_afk(
+                MagicMock(), room.race_id, pid, {"type": "afk", "afk": bogus, "igt_ms": 0}
+            )
+        assert not room.mods[pid].afk
+        manager.broadcast_leaderboard.assert_not_called()
+
+    def test_participant_info_afk(self):
+        participant = MockParticipant()
+        assert not participant_to_info(participant).afk
+        assert participant_to_info(participant, afk_ids={participant.id}).afk
+
+
+class TestPause:
+    """Pause requests sent by the mod, granted by the organizer."""
+
+    def _patch(self, monkeypatch, participant):
+        participant.race = MockRace(status=RaceStatus.RUNNING, participants=[participant])
+        monkeypatch.setattr(mod_ws, "_load_participant", AsyncMock(return_value=participant))
+        fake_manager = MagicMock(pauses={}, broadcast_leaderboard=AsyncMock())
+        monkeypatch.setattr(mod_ws, "manager", fake_manager)
+        return fake_manager
+
+    @pytest.mark.asyncio
+    async def test_pause_requested(self, monkeypatch):
+        participant = MockParticipant(status=ParticipantStatus.PLAYING)
+        fake_manager = self._patch(monkeypatch, participant)
+        msg = {"type": "pause_request", "igt_ms": 5000}
+        await mod_ws.handle_pause_request(MagicMock(), participant.race.id, participant.id, msg)
+        assert fake_manager.pauses == {participant.id: PAUSE_REQUESTED}
+        assert fake_manager.broadcast_leaderboard.await_count == 1
+
+        # Already requested (or granted): no new broadcast
+        await mod_ws.handle_pause_request(MagicMock(), participant.race.id, participant.id, msg)
+        assert fake_manager.broadcast_leaderboard.await_count == 1
+
+    @pytest.mark.asyncio
+    async def test_pause_request_when_not_playing_ignored(self, monkeypatch):
+        participant = MockParticipant(status=ParticipantStatus.FINISHED)
+        fake_manager = self._patch(monkeypatch, participant)
+        await mod_ws.handle_pause_request(
+            MagicMock(), participant.race.id, participant.id, {"type": "pause_request"}
+        )
+        assert fake_manager.pauses == {}
+        fake_manager.broadcast_leaderboard.assert_not_called()
+
+    @pytest.mark.asyncio
+    async def test_send_to_mod(self):
+        manager = ConnectionManager()
+        race_id, pid = uuid.uuid4(), uuid.uuid4()
+        room = manager.get_or_create_room(race_id)
+        room.mods[pid] = ModConnection(
+            websocket=AsyncMock(), participant_id=pid, user_id=uuid.uuid4()
+        )
+
+        message = PauseGrantedMessage().model_dump_json()
+        assert await manager.send_to_mod(race_id, pid, message)
+        room.mods[pid].websocket.send_text.assert_awaited_once_with(message)
+        # Not connected
+        assert not await manager.send_to_mod(race_id, uuid.uuid4(), message)
+
+    def test_participant_info_pause(self):
+        participant = MockParticipant()
+        assert participant_to_info(participant).pause is None
+        info = participant_to_info(participant, pauses={participant.id: PAUSE_GRANTED})
+        assert info.pause == "paused"
+
+
+class TestSaveReloaded:
+    """Save reloads (save-scumming) reported by the mod."""
+
+    MSG = {
+        "type": "save_reloaded",
+        "igt_ms": 540000,
+        "igt_delta_ms": -60000,
+        "death_count": 3,
+        "death_delta": -2,
+    }
+
+    @pytest.mark.asyncio
+    async def test_reload_recorded(self, patched_mod_ws, running_participant):
+        participant, fake_manager = running_participant, patched_mod_ws
+        await mod_ws.handle_save_reloaded(MagicMock(), participant.id, self.MSG)
+        await mod_ws.handle_save_reloaded(MagicMock(), participant.id, self.MSG)
+        expected = {k: v for k, v in self.MSG.items() if k != "type"}
+        assert participant.save_reloads == [expected, expected]
+        assert fake_manager.broadcast_leaderboard.await_count == 2
+        assert participant_to_info(participant).save_reloads == 2
+
+    @pytest.mark.asyncio
+    async def test_malformed_reload_ignored(self, patched_mod_ws, running_participant):
+        participant, fake_manager = running_participant, patched_mod_ws
+        for bogus in ({"igt_delta_ms": "-60000"}, {"death_delta": None}, {"igt_ms": True}):
+            await mod_ws.handle_save_reloaded(MagicMock(), participant.id, {**self.MSG, **bogus})
+        assert participant.save_reloads is None
+        fake_manager.broadcast_leaderboard.assert_not_called()
+
+    @pytest.mark.asyncio
+    async def test_reload_after_finish_ignored(self, patched_mod_ws, running_participant):
+        participant, fake_manager = running_participant, patched_mod_ws
+        participant.status = ParticipantStatus.FINISHED
+        await mod_ws.handle_save_reloaded(MagicMock(), participant.id, self.MSG)
+        assert participant.save_reloads is None
+        fake_manager.broadcast_leaderboard.assert_not_called()
+
+    @pytest.mark.asyncio
+    async def test_reloads_capped(self, patched_mod_ws, running_participant):
+        participant, fake_manager = running_participant, patched_mod_ws
+        cap = mod_ws.PARTICIPANT_EVENT_CAPS["save_reloads"]
+        participant.save_reloads = [{"igt_ms": 0}] * cap
+        await mod_ws.handle_save_reloaded(MagicMock(), participant.id, self.MSG)
+        assert len(participant.save_reloads) == cap
+        fake_manager.broadcast_leaderboard.assert_not_called()
+
+
+class TestKindlingUsed:
+    """Messmer's Kindling uses reported by the mod."""
+
+    MSG = {"type": "kindling_used", "igt_ms": 3600000, "used": 1, "remaining": 1}
+
+    @pytest.mark.asyncio
+    async def test_use_recorded(self, patched_mod_ws, running_participant):
+        participant, fake_manager = running_participant, patched_mod_ws
+        await mod_ws.handle_kindling_used(MagicMock(), participant.id, self.MSG)
+        await mod_ws.handle_kindling_used(
+            MagicMock(), participant.id, {**self.MSG, "used": 1, "remaining": 0}
+        )
+        assert participant.kindling_uses == [
+            {"igt_ms": 3600000, "used": 1, "remaining": 1},
+            {"igt_ms": 3600000, "used": 1, "remaining": 0},
+        ]
+        assert fake_manager.broadcast_leaderboard.await_count == 2
+        assert participant_to_info(participant).kindling_used == 2
+
+    @pytest.mark.asyncio
+    async def test_malformed_use_ignored(self, patched_mod_ws, running_participant):
+        participant, fake_manager = running_participant, patched_mod_ws
+        for bogus in ({"used": 0}, {"used": -1}, {"remaining": None}, {"igt_ms": True}):
+            await mod_ws.handle_kindling_used(MagicMock(), participant.id, {**self.MSG, **bogus})
+        assert participant.kindling_uses is None
+        fake_manager.broadcast_leaderboard.assert_not_called()
+
+    @pytest.mark.asyncio
+    async def test_use_after_finish_ignored(self, patched_mod_ws, running_participant):
+        participant, fake_manager = running_participant, patched_mod_ws
+        participant.status = ParticipantStatus.FINISHED
+        await mod_ws.handle_kindling_used(MagicMock(), participant.id, self.MSG)
+        assert participant.kindling_uses is None
+        fake_manager.broadcast_leaderboard.assert_not_called()
+
+
+class TestTimingDrift:
+    """IGT/wall clock drift reported by the mod."""
+
+    MSG = {"type": "timing_drift", "igt_ms": 600000, "igt_delta_ms": 45000, "wall_delta_ms": 30000}
+
+    @pytest.mark.asyncio
+    async def test_drift_recorded(self, patched_mod_ws, running_participant):
+        participant, fake_manager = running_participant, patched_mod_ws
+        await mod_ws.handle_timing_drift(MagicMock(), participant.id, self.MSG)
+        assert participant.timing_anomalies == [
+            {"igt_ms": 600000, "igt_delta_ms": 45000, "wall_delta_ms": 30000}
+        ]
+        fake_manager.broadcast_leaderboard.assert_awaited_once()
+        assert participant_to_info(participant).timing_anomalies == 1
+
+    @pytest.mark.asyncio
+    async def test_malformed_drift_ignored(self, patched_mod_ws, running_participant):
+        participant, fake_manager = running_participant, patched_mod_ws
+        for bogus in ({"wall_delta_ms": 0}, {"igt_delta_ms": -1}, {"igt_ms": None}):
+            await mod_ws.handle_timing_drift(MagicMock(), participant.id, {**self.MSG, **bogus})
+        assert participant.timing_anomalies is None
+        fake_manager.broadcast_leaderboard.assert_not_called()
+
+    @pytest.mark.asyncio
+    async def test_drift_after_finish_ignored(self, patched_mod_ws, running_participant):
+        participant, fake_manager = running_participant, patched_mod_ws
+        participant.status = ParticipantStatus.FINISHED
+        await mod_ws.handle_timing_drift(MagicMock(), participant.id, self.MSG)
+        assert participant.timing_anomalies is None
+        fake_manager.broadcast_leaderboard.assert_not_called()
+
+
+class TestGraceDiscovered:
+    """Sites of Grace found, reported by the mod."""
+
+    MSG = {"type": "grace_discovered", "flag_id": 76101, "igt_ms": 120000}
+
+    @pytest.mark.asyncio
+    async def test_grace_recorded_once(self, patched_mod_ws, running_participant):
+        participant, fake_manager = running_participant, patched_mod_ws
+        await mod_ws.handle_grace_discovered(MagicMock(), participant.id, self.MSG)
+        # Resent after a reconnection
+        await mod_ws.handle_grace_discovered(MagicMock(), participant.id, self.MSG)
+        await mod_ws.handle_grace_discovered(
+            MagicMock(), participant.id, {**self.MSG, "flag_id": 71000, "igt_ms": 300000}
+        )
+        assert participant.graces_found == [
+            {"flag_id": 76101, "igt_ms": 120000},
+            {"flag_id": 71000, "igt_ms": 300000},
+        ]
+        assert fake_manager.broadcast_leaderboard.await_count == 2
+        assert participant_to_info(participant).graces_found == 2
+
+    @pytest.mark.asyncio
+    async def test_malformed_grace_ignored(self, patched_mod_ws, running_participant):
+        participant, fake_manager = running_participant, patched_mod_ws
+        for bogus in ({"flag_id": -1}, {"flag_id": "76101"}, {"igt_ms": None}, {"igt_ms": True}):
+            await mod_ws.handle_grace_discovered(MagicMock(), participant.id, {**self.MSG, **bogus})
+        assert participant.graces_found is None
+        fake_manager.broadcast_leaderboard.assert_not_called()
+
+    @pytest.mark.asyncio
+    async def test_non_grace_flag_ignored(self, patched_mod_ws, running_participant):
+        participant, fake_manager = running_participant, patched_mod_ws
+        for flag_id in (70999, 74000, 1040292801):
+            await mod_ws.handle_grace_discovered(
+                MagicMock(), participant.id, {**self.MSG, "flag_id": flag_id}
+            )
+        assert participant.graces_found is None
+        fake_manager.broadcast_leaderboard.assert_not_called()
+
+    @pytest.mark.asyncio
+    async def test_grace_after_finish_ignored(self, patched_mod_ws, running_participant):
+        participant, fake_manager = running_participant, patched_mod_ws
+        participant.status = ParticipantStatus.FINISHED
+        await mod_ws.handle_grace_discovered(MagicMock(), participant.id, self.MSG)
+        assert participant.graces_found is None
+        fake_manager.broadcast_leaderboard.assert_not_called()
+
+
+class TestLeaderboardDelta:
+    """Differential leaderboard broadcasts (protocol 19+)."""
+
+    def _room(self, manager, *versions):
+        race_id = uuid.uuid4()
+        room = manager.get_or_create_room(race_id)
+        sockets = []
+        for version in versions:
+            pid, ws = uuid.uuid4(), AsyncMock()
+            room.mods[pid] = ModConnection(
+                websocket=ws, participant_id=pid, user_id=uuid.uuid4(), protocol_version=version
+            )
+            sockets.append(ws)
+        return race_id, room, sockets
+
+    @staticmethod
+    def _sent(ws) -> dict:
+        return json.loads(ws.send_text.call_args.args[0])
+
+    @pytest.mark.asyncio
+    async def test_full_then_delta(self):
+        manager = ConnectionManager()
+        race_id, room, (old_ws, new_ws) = self._room(manager, 18, 19)
+        p1 = MockParticipant(status=ParticipantStatus.PLAYING, current_layer=2, igt_ms=1000)
+        p2 = MockParticipant(status=ParticipantStatus.PLAYING, current_layer=1, igt_ms=1000)
+
+        await manager.broadcast_leaderboard(race_id, [p1, p2])
+        first = self._sent(new_ws)
+        assert first["type"] == "leaderboard_update"
+        assert first["generation"] == 1
+        assert len(first["participants"]) == 2
+
+        # p2 overtakes p1: only their row changed
+        p2.current_layer = 3
+        await manager.broadcast_leaderboard(race_id, [p1, p2])
+        delta = self._sent(new_ws)
+        assert delta["type"] == "leaderboard_delta"
+        assert delta["generation"] == 2
+        assert [p["id"] for p in delta["participants"]] == [str(p2.id)]
+        assert delta["order"] == [str(p2.id), str(p1.id)]
+
+        # Older mods keep getting the full leaderboard
+        assert self._sent(old_ws)["type"] == "leaderboard_update"
+        assert room.leaderboard_generation == 2
+
+    @pytest.mark.asyncio
+    async def test_resync_sends_latest(self):
+        manager = ConnectionManager()
+        race_id, room, (ws,) = self._room(manager, 19)
+        participant = MockParticipant(status=ParticipantStatus.PLAYING)
+        (pid,) = room.mods
+
+        # Nothing broadcast yet: the next broadcast is full
+        await manager.resync_leaderboard(race_id, pid)
+        ws.send_text.assert_not_called()
+
+        await manager.broadcast_leaderboard(race_id, [participant])
+        await manager.broadcast_leaderboard(race_id, [participant])
+        assert self._sent(ws)["type"] == "leaderboard_delta"
+
+        await manager.resync_leaderboard(race_id, pid)
+        resync = self._sent(ws)
+        assert resync["type"] == "leaderboard_update"
+        assert resync["generation"] == 2
+        assert room.mods[pid].leaderboard_synced
+
+
 class TestLeaderboard:
     """Test leaderboard sorting."""
 
@@ -564,6 +1835,15 @@ class TestParticipantToInfo:
         info = participant_to_info(participant)
         assert info.zone_history == history
 
+    def test_participant_info_badges(self, monkeypatch):
+        """Stored badges are sent, streamer is added while live."""
+        participant = MockParticipant(badges=["organizer"])
+        assert participant_to_info(participant).badges == ["organizer"]
+        assert participant_to_info(MockParticipant()).badges == []
+
+        monkeypatch.setattr(twitch_live_service, "is_live", lambda _: True)
+        assert participant_to_info(participant).badges == ["organizer", "streamer"]
+
 
 class TestGapComputation:
     """Test gap timing computation."""
@@ -808,6 +2088,33 @@ class TestGapComputation:
         assert get_layer_entry_igt([], 0, self._graph()) is None
         assert get_layer_entry_igt(None, 0, self._graph()) is None
 
+    def test_get_zone_entry_igt(self):
+        """Returns the IGT of the latest visit to the current zone."""
+        from speedfog_racing.websocket.manager import get_zone_entry_igt
+
+        history = [
+            {"node_id": "start", "igt_ms": 0},
+            {"node_id": "zone_a", "igt_ms": 30000},
+            {"node_id": "start", "igt_ms": 50000},
+            {"node_id": "zone_a", "igt_ms": 75000},
+        ]
+        assert get_zone_entry_igt(history, "zone_a") == 75000
+        # History not caught up with the current zone
+        assert get_zone_entry_igt(history, "zone_b") is None
+        assert get_zone_entry_igt(None, "zone_a") is None
+        assert get_zone_entry_igt(history, None) is None
+
+    def test_participant_to_info_zone_entry_igt(self):
+        """participant_to_info includes the current zone's entry IGT."""
+        participant = MockParticipant(
+            current_zone="zone_a",
+            zone_history=[
+                {"node_id": "start", "igt_ms": 0},
+                {"node_id": "zone_a", "igt_ms": 30000},
+            ],
+        )
+        assert participant_to_info(participant).zone_entry_igt == 30000
+
     def test_participant_to_info_with_gap(self):
         """participant_to_info passes gap_ms through."""
         user = MockUser(twitch_username="p1")
@@ -386,6 +386,50 @@ export async function removeParticipant(
   }
 }
 
+/**
+ * Grant a participant's pause request (organizer, running race).
+ */
+export async function grantPause(
+  raceId: string,
+  participantId: string,
+): Promise<void> {
+  const response = await fetch(
+    `${API_BASE}/races/${raceId}/participants/${participantId}/pause`,
+    {
+      method: "POST",
+      headers: getAuthHeaders(),
+    },
+  );
+  if (!response.ok) {
+    const error: ApiError = await response
+      .json()
+      .catch(() => ({ detail: "Unknown error" }));
+    throw new Error(error.detail);
+  }
+}
+
+/**
+ * Resume a paused participant, or decline their pending pause request.
+ */
+export async function resumePause(
+  raceId: string,
+  participantId: string,
+): Promise<void> {
+  const response = await fetch(
+    `${API_BASE}/races/${raceId}/participants/${participantId}/resume`,
+    {
+      method: "POST",
+      headers: getAuthHeaders(),
+    },
+  );
+  if (!response.ok) {
+    const error: ApiError = await response
+      .json()
+      .catch(() => ({ detail: "Unknown error" }));
+    throw new Error(error.detail);
+  }
+}
+
 /**
  * Self-register as a participant in an open-registration race.
  */
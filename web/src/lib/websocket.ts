@@ -19,6 +19,13 @@ export interface WsParticipant {
   death_count: number;
   color_index: number;
   mod_connected: boolean;
+  afk?: boolean;
+  save_reloads?: number;
+  kindling_used?: number;
+  graces_found?: number;
+  timing_anomalies?: number;
+  zone_entry_igt?: number | null;
+  pause?: "requested" | "paused" | null;
   zone_history: { node_id: string; igt_ms: number; deaths?: number }[] | null;
   is_live?: boolean;
   stream_url?: string | null;